@@ -0,0 +1,436 @@
+use std::f64::consts::SQRT_2;
+
+use levenberg_marquardt::{LeastSquaresProblem, LevenbergMarquardt};
+use nalgebra::{
+    matrix, DMatrix, DVector, Dyn, Matrix3, Owned, Point2, Point3, Rotation3, SymmetricEigen,
+    Vector2, Vector3,
+};
+
+pub const MINIMUM_NUMBER_OF_VIEWS: usize = 3;
+
+const NUMBER_OF_INTRINSIC_PARAMETERS: usize = 6;
+const NUMBER_OF_POSE_PARAMETERS: usize = 6;
+
+/// Pinhole camera with two coefficients of radial distortion, all in pixels
+///
+/// Computations are done in f64 because the squared pixel coordinates of the homographies exceed
+/// the precision of f32.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Intrinsics {
+    pub focal_lengths: Vector2<f64>,
+    pub optical_center: Point2<f64>,
+    pub radial_distortion: Vector2<f64>,
+}
+
+impl Intrinsics {
+    pub fn project(&self, point_in_camera: Point3<f64>) -> Point2<f64> {
+        let normalized = point_in_camera.xy().coords / point_in_camera.z;
+        let radius_squared = normalized.norm_squared();
+        let distortion = 1.0
+            + self.radial_distortion.x * radius_squared
+            + self.radial_distortion.y * radius_squared.powi(2);
+        self.optical_center + (normalized * distortion).component_mul(&self.focal_lengths)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Solution {
+    pub intrinsics: Intrinsics,
+    /// Root mean square distance between observed and reprojected points in pixels
+    pub reprojection_error: f64,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IntrinsicsError {
+    #[error("at least {MINIMUM_NUMBER_OF_VIEWS} views are required, got {0}")]
+    NotEnoughViews(usize),
+    #[error("view does not contain a point for every object point")]
+    IncompleteView,
+    #[error("views do not constrain the intrinsics, e.g. because they are parallel")]
+    Degenerate,
+    #[error("refinement did not converge")]
+    NotConverged,
+}
+
+/// Estimates intrinsics from several views of the same planar object
+///
+/// The object points lie in the plane z = 0. Every view contains the image points of all object
+/// points in the same order. Initial intrinsics and poses are computed in closed form following
+/// Zhang ("A Flexible New Technique for Camera Calibration", 2000), assuming zero skew and no
+/// distortion. All parameters including the distortion are then refined by minimizing the
+/// reprojection error.
+pub fn solve(
+    object_points: &[Point2<f64>],
+    views: &[Vec<Point2<f64>>],
+) -> Result<Solution, IntrinsicsError> {
+    if views.len() < MINIMUM_NUMBER_OF_VIEWS {
+        return Err(IntrinsicsError::NotEnoughViews(views.len()));
+    }
+    if views.iter().any(|view| view.len() != object_points.len()) {
+        return Err(IntrinsicsError::IncompleteView);
+    }
+    let homographies = views
+        .iter()
+        .map(|view| homography(object_points, view))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(IntrinsicsError::Degenerate)?;
+    let intrinsics = closed_form_intrinsics(&homographies).ok_or(IntrinsicsError::Degenerate)?;
+    let intrinsic_matrix = matrix![
+        intrinsics.focal_lengths.x, 0.0, intrinsics.optical_center.x;
+        0.0, intrinsics.focal_lengths.y, intrinsics.optical_center.y;
+        0.0, 0.0, 1.0
+    ];
+    let inverse_intrinsic_matrix = intrinsic_matrix
+        .try_inverse()
+        .ok_or(IntrinsicsError::Degenerate)?;
+
+    let mut parameters = vec![
+        intrinsics.focal_lengths.x,
+        intrinsics.focal_lengths.y,
+        intrinsics.optical_center.x,
+        intrinsics.optical_center.y,
+        0.0,
+        0.0,
+    ];
+    for homography in &homographies {
+        let (rotation, translation) = pose_from_homography(homography, &inverse_intrinsic_matrix);
+        parameters.extend(rotation.scaled_axis().iter());
+        parameters.extend(translation.iter());
+    }
+
+    let problem = IntrinsicsProblem {
+        parameters: DVector::from_vec(parameters),
+        object_points: object_points.to_vec(),
+        views: views.to_vec(),
+    };
+    let (problem, report) = LevenbergMarquardt::new().minimize(problem);
+    if !report.termination.was_successful() {
+        return Err(IntrinsicsError::NotConverged);
+    }
+    let residuals = problem.residuals().ok_or(IntrinsicsError::NotConverged)?;
+    Ok(Solution {
+        intrinsics: intrinsics_from_parameters(&problem.parameters),
+        reprojection_error: (residuals.norm_squared() / (residuals.len() / 2) as f64).sqrt(),
+    })
+}
+
+/// Projective transformation mapping the points `from` onto the points `to`
+///
+/// Uses the normalized direct linear transformation, at least four points are required.
+pub fn homography(from: &[Point2<f64>], to: &[Point2<f64>]) -> Option<Matrix3<f64>> {
+    if from.len() < 4 || from.len() != to.len() {
+        return None;
+    }
+    let normalize_from = normalization(from)?;
+    let normalize_to = normalization(to)?;
+    let rows = from.iter().zip(to).flat_map(|(from, to)| {
+        let from = normalize_from.transform_point(from);
+        let to = normalize_to.transform_point(to);
+        [
+            [
+                -from.x,
+                -from.y,
+                -1.0,
+                0.0,
+                0.0,
+                0.0,
+                to.x * from.x,
+                to.x * from.y,
+                to.x,
+            ],
+            [
+                0.0,
+                0.0,
+                0.0,
+                -from.x,
+                -from.y,
+                -1.0,
+                to.y * from.x,
+                to.y * from.y,
+                to.y,
+            ],
+        ]
+    });
+    let equations = DMatrix::from_row_iterator(2 * from.len(), 9, rows.flatten());
+    let normalized_homography = Matrix3::from_row_slice(null_vector(&equations).as_slice());
+    Some(normalize_to.try_inverse()? * normalized_homography * normalize_from)
+}
+
+/// Translates the centroid into the origin and scales the mean distance to it to sqrt(2)
+fn normalization(points: &[Point2<f64>]) -> Option<Matrix3<f64>> {
+    let centroid = points
+        .iter()
+        .map(|point| point.coords)
+        .sum::<Vector2<f64>>()
+        / points.len() as f64;
+    let mean_distance = points
+        .iter()
+        .map(|point| (point.coords - centroid).norm())
+        .sum::<f64>()
+        / points.len() as f64;
+    if mean_distance <= f64::EPSILON {
+        return None;
+    }
+    let scale = SQRT_2 / mean_distance;
+    Some(matrix![
+        scale, 0.0, -scale * centroid.x;
+        0.0, scale, -scale * centroid.y;
+        0.0, 0.0, 1.0
+    ])
+}
+
+/// Unit vector minimizing the norm of the product with the matrix
+fn null_vector(matrix: &DMatrix<f64>) -> DVector<f64> {
+    let eigen = SymmetricEigen::new(matrix.transpose() * matrix);
+    eigen
+        .eigenvectors
+        .column(eigen.eigenvalues.imin())
+        .into_owned()
+}
+
+fn closed_form_intrinsics(homographies: &[Matrix3<f64>]) -> Option<Intrinsics> {
+    // b = (B11, B12, B22, B13, B23, B33) of the image of the absolute conic B = K^-T K^-1
+    let constraint = |homography: &Matrix3<f64>, i: usize, j: usize| {
+        let hi = homography.column(i);
+        let hj = homography.column(j);
+        [
+            hi[0] * hj[0],
+            hi[0] * hj[1] + hi[1] * hj[0],
+            hi[1] * hj[1],
+            hi[2] * hj[0] + hi[0] * hj[2],
+            hi[2] * hj[1] + hi[1] * hj[2],
+            hi[2] * hj[2],
+        ]
+    };
+    let rows = homographies
+        .iter()
+        .flat_map(|homography| {
+            let orthogonal = constraint(homography, 0, 1);
+            let first_norm = constraint(homography, 0, 0);
+            let second_norm = constraint(homography, 1, 1);
+            let equal_norms: [f64; 6] =
+                std::array::from_fn(|index| first_norm[index] - second_norm[index]);
+            [orthogonal, equal_norms]
+        })
+        .chain([[0.0, 1.0, 0.0, 0.0, 0.0, 0.0]]);
+    let equations = DMatrix::from_row_iterator(2 * homographies.len() + 1, 6, rows.flatten());
+    let b = null_vector(&equations);
+    let (b11, b12, b22, b13, b23, b33) = (b[0], b[1], b[2], b[3], b[4], b[5]);
+
+    let denominator = b11 * b22 - b12 * b12;
+    let optical_center_y = (b12 * b13 - b11 * b23) / denominator;
+    let lambda = b33 - (b13 * b13 + optical_center_y * (b12 * b13 - b11 * b23)) / b11;
+    let focal_length_x = (lambda / b11).sqrt();
+    let focal_length_y = (lambda * b11 / denominator).sqrt();
+    let optical_center_x = -b13 * focal_length_x.powi(2) / lambda;
+    let intrinsics = Intrinsics {
+        focal_lengths: Vector2::new(focal_length_x, focal_length_y),
+        optical_center: Point2::new(optical_center_x, optical_center_y),
+        radial_distortion: Vector2::zeros(),
+    };
+    let is_valid = intrinsics
+        .focal_lengths
+        .iter()
+        .all(|length| length.is_finite())
+        && intrinsics
+            .optical_center
+            .iter()
+            .all(|center| center.is_finite());
+    is_valid.then_some(intrinsics)
+}
+
+fn pose_from_homography(
+    homography: &Matrix3<f64>,
+    inverse_intrinsic_matrix: &Matrix3<f64>,
+) -> (Rotation3<f64>, Vector3<f64>) {
+    let first_column = inverse_intrinsic_matrix * homography.column(0);
+    let second_column = inverse_intrinsic_matrix * homography.column(1);
+    let third_column = inverse_intrinsic_matrix * homography.column(2);
+    let mut scale = 1.0 / first_column.norm();
+    // the object lies in front of the camera
+    if third_column.z * scale < 0.0 {
+        scale = -scale;
+    }
+    let first_axis = first_column * scale;
+    let second_axis = second_column * scale;
+    let rotation = Rotation3::from_matrix(&Matrix3::from_columns(&[
+        first_axis,
+        second_axis,
+        first_axis.cross(&second_axis),
+    ]));
+    (rotation, third_column * scale)
+}
+
+fn intrinsics_from_parameters(parameters: &DVector<f64>) -> Intrinsics {
+    Intrinsics {
+        focal_lengths: Vector2::new(parameters[0], parameters[1]),
+        optical_center: Point2::new(parameters[2], parameters[3]),
+        radial_distortion: Vector2::new(parameters[4], parameters[5]),
+    }
+}
+
+fn reprojection_residuals(
+    parameters: &DVector<f64>,
+    object_points: &[Point2<f64>],
+    views: &[Vec<Point2<f64>>],
+) -> DVector<f64> {
+    let intrinsics = intrinsics_from_parameters(parameters);
+    let residuals = views.iter().enumerate().flat_map(|(index, view)| {
+        let offset = NUMBER_OF_INTRINSIC_PARAMETERS + index * NUMBER_OF_POSE_PARAMETERS;
+        let rotation = Rotation3::new(parameters.fixed_rows::<3>(offset).into_owned());
+        let translation = parameters.fixed_rows::<3>(offset + 3).into_owned();
+        object_points
+            .iter()
+            .zip(view)
+            .flat_map(move |(object_point, image_point)| {
+                let point_in_camera =
+                    rotation * Point3::new(object_point.x, object_point.y, 0.0) + translation;
+                let residual = intrinsics.project(point_in_camera) - image_point;
+                [residual.x, residual.y]
+            })
+    });
+    DVector::from_iterator(2 * views.len() * object_points.len(), residuals)
+}
+
+struct IntrinsicsProblem {
+    parameters: DVector<f64>,
+    object_points: Vec<Point2<f64>>,
+    views: Vec<Vec<Point2<f64>>>,
+}
+
+impl LeastSquaresProblem<f64, Dyn, Dyn> for IntrinsicsProblem {
+    type ResidualStorage = Owned<f64, Dyn>;
+    type JacobianStorage = Owned<f64, Dyn, Dyn>;
+    type ParameterStorage = Owned<f64, Dyn>;
+
+    fn set_params(&mut self, parameters: &DVector<f64>) {
+        self.parameters.copy_from(parameters);
+    }
+
+    fn params(&self) -> DVector<f64> {
+        self.parameters.clone()
+    }
+
+    fn residuals(&self) -> Option<DVector<f64>> {
+        Some(reprojection_residuals(
+            &self.parameters,
+            &self.object_points,
+            &self.views,
+        ))
+    }
+
+    /// Central differences, the projection with distortion is tedious to differentiate by hand
+    fn jacobian(&self) -> Option<DMatrix<f64>> {
+        let number_of_residuals = 2 * self.views.len() * self.object_points.len();
+        let mut jacobian = DMatrix::zeros(number_of_residuals, self.parameters.len());
+        for index in 0..self.parameters.len() {
+            let step = 1e-6 * self.parameters[index].abs().max(1.0);
+            let mut parameters = self.parameters.clone();
+            parameters[index] += step;
+            let forward = reprojection_residuals(&parameters, &self.object_points, &self.views);
+            parameters[index] -= 2.0 * step;
+            let backward = reprojection_residuals(&parameters, &self.object_points, &self.views);
+            jacobian.set_column(index, &((forward - backward) / (2.0 * step)));
+        }
+        Some(jacobian)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::{point, vector};
+
+    use super::*;
+
+    fn checkerboard(columns: usize, rows: usize, square_size: f64) -> Vec<Point2<f64>> {
+        (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| {
+                    point![column as f64 * square_size, row as f64 * square_size]
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn homography_maps_points_onto_each_other() {
+        let from = [
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 1.0],
+            point![0.0, 1.0],
+        ];
+        let to = [
+            point![10.0, 20.0],
+            point![50.0, 22.0],
+            point![48.0, 70.0],
+            point![12.0, 60.0],
+        ];
+
+        let homography = homography(&from, &to).unwrap();
+
+        for (from, to) in from.iter().zip(to) {
+            let mapped = homography.transform_point(from);
+            assert_relative_eq!(mapped, to, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn intrinsics_are_recovered_from_views_of_a_checkerboard() {
+        let intrinsics = Intrinsics {
+            focal_lengths: vector![486.4, 484.8],
+            optical_center: point![326.4, 235.2],
+            radial_distortion: vector![-0.08, 0.02],
+        };
+        let object_points = checkerboard(7, 5, 0.04);
+        let poses = [
+            (vector![0.2, -0.1, 0.05], vector![-0.12, -0.08, 0.45]),
+            (vector![-0.3, 0.2, -0.1], vector![-0.15, -0.06, 0.5]),
+            (vector![0.1, 0.35, 0.1], vector![-0.1, -0.1, 0.4]),
+            (vector![-0.1, -0.3, 0.02], vector![-0.08, -0.1, 0.55]),
+            (vector![0.35, 0.1, -0.05], vector![-0.14, -0.05, 0.5]),
+        ];
+        let views: Vec<_> = poses
+            .iter()
+            .map(|(rotation, translation)| {
+                let rotation = Rotation3::new(*rotation);
+                object_points
+                    .iter()
+                    .map(|point| {
+                        intrinsics.project(rotation * point![point.x, point.y, 0.0] + translation)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let solution = solve(&object_points, &views).unwrap();
+
+        assert_relative_eq!(
+            solution.intrinsics.focal_lengths,
+            intrinsics.focal_lengths,
+            epsilon = 1e-6
+        );
+        assert_relative_eq!(
+            solution.intrinsics.optical_center,
+            intrinsics.optical_center,
+            epsilon = 1e-6
+        );
+        assert_relative_eq!(
+            solution.intrinsics.radial_distortion,
+            intrinsics.radial_distortion,
+            epsilon = 1e-6
+        );
+        assert!(solution.reprojection_error < 1e-6);
+    }
+
+    #[test]
+    fn too_few_views_are_rejected() {
+        let object_points = checkerboard(3, 3, 1.0);
+
+        assert!(matches!(
+            solve(&object_points, &[object_points.clone()]),
+            Err(IntrinsicsError::NotEnoughViews(1))
+        ));
+    }
+}
@@ -5,6 +5,7 @@ use problem::CalibrationProblem;
 use types::FieldDimensions;
 
 pub mod corrections;
+pub mod intrinsics;
 pub mod jacobian;
 pub mod lines;
 pub mod measurement;
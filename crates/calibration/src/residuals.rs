@@ -70,11 +70,12 @@ impl Residuals {
             border_to_connecting_angle,
             connecting_to_goal_box_angle,
             distance_between_parallel_line_start_points: distance_between_parallel_line_start_points
-                - field_dimensions.goal_box_area_length,
+                - field_dimensions.goal_box_area_length.0,
             distance_between_parallel_line_center_points:
-                distance_between_parallel_line_center_points - field_dimensions.goal_box_area_length,
+                distance_between_parallel_line_center_points
+                    - field_dimensions.goal_box_area_length.0,
             distance_between_parallel_line_end_points: distance_between_parallel_line_end_points
-                - field_dimensions.goal_box_area_length,
+                - field_dimensions.goal_box_area_length.0,
         })
     }
 }
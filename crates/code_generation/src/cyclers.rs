@@ -64,6 +64,9 @@ fn generate_database_struct() -> TokenStream {
         pub(crate) struct Database {
             pub main_outputs: MainOutputs,
             pub additional_outputs: AdditionalOutputs,
+            pub timings: types::Timings,
+            pub node_failures: types::NodeFailures,
+            pub overruns: types::CycleOverruns,
         }
     }
 }
@@ -90,6 +93,12 @@ fn generate_struct(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
             own_changed: std::sync::Arc<tokio::sync::Notify>,
             own_subscribed_outputs_reader: framework::Reader<std::collections::HashSet<String>>,
             parameters_reader: framework::Reader<crate::structs::Parameters>,
+            recorder: Option<framework::Recorder>,
+            snapshot_file: Option<framework::SnapshotFile>,
+            timing_window: framework::TimingWindow,
+            failure_containment: framework::FailureContainment,
+            cycle_budget: framework::CycleBudgetMonitor,
+            cycle_count: usize,
             persistent_state: crate::structs::#module_name::PersistentState,
             #realtime_inputs
             #input_output_fields
@@ -186,6 +195,7 @@ fn generate_new_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
         .iter_nodes()
         .map(|node| format_ident!("{}", node.name.to_case(Case::Snake)));
     let input_output_identifiers = generate_input_output_identifiers(cycler, cyclers);
+    let node_names: Vec<_> = cycler.iter_nodes().map(|node| &node.name).collect();
 
     quote! {
         pub(crate) fn new(
@@ -195,10 +205,19 @@ fn generate_new_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
             own_changed: std::sync::Arc<tokio::sync::Notify>,
             own_subscribed_outputs_reader: framework::Reader<std::collections::HashSet<String>>,
             parameters_reader: framework::Reader<crate::structs::Parameters>,
+            recorder: Option<framework::Recorder>,
+            snapshot_file: Option<framework::SnapshotFile>,
+            failure_tolerance: framework::FailureTolerance,
+            cycle_budget: framework::CycleBudget,
             #input_output_fields
         ) -> color_eyre::Result<Self> {
             let parameters = parameters_reader.next().clone();
             let mut persistent_state = crate::structs::#cycler_module_name::PersistentState::default();
+            if let Some(snapshot_file) = &snapshot_file {
+                snapshot_file
+                    .restore(&mut persistent_state)
+                    .wrap_err("failed to restore snapshot")?;
+            }
             #node_initializers
             Ok(Self {
                 instance,
@@ -207,6 +226,12 @@ fn generate_new_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
                 own_changed,
                 own_subscribed_outputs_reader,
                 parameters_reader,
+                recorder,
+                snapshot_file,
+                timing_window: framework::TimingWindow::new(&[#(#node_names,)*]),
+                failure_containment: framework::FailureContainment::new(&[#(#node_names,)*], failure_tolerance),
+                cycle_budget: framework::CycleBudgetMonitor::new(format!("{instance:?}"), &[#(#node_names,)*], cycle_budget),
+                cycle_count: 0,
                 persistent_state,
                 #input_output_identifiers
                 #(#node_identifiers,)*
@@ -244,6 +269,12 @@ fn generate_node_field_initializers(node: &Node, cycler: &Cycler) -> TokenStream
             Field::AdditionalOutput { name, .. } => {
                 panic!("unexpected additional output field `{name}` in CreationContext")
             }
+            Field::AdditionalOutputsSubscribed { name } => {
+                panic!("unexpected additional outputs subscription field `{name}` in new context")
+            }
+            Field::DecimatedInput { name, .. } => {
+                panic!("unexpected decimated input field `{name}` in new context")
+            }
             Field::HardwareInterface { name } => quote! {
                 #name: &hardware_interface,
             },
@@ -327,19 +358,33 @@ fn generate_start_method() -> TokenStream {
         pub(crate) fn start(
             mut self,
             keep_running: tokio_util::sync::CancellationToken,
+            thread_scheduling: framework::ThreadScheduling,
         ) -> color_eyre::Result<std::thread::JoinHandle<color_eyre::Result<()>>> {
             let instance_name = format!("{:?}", self.instance);
             std::thread::Builder::new()
                 .name(instance_name.clone())
                 .spawn(move || {
+                    thread_scheduling.apply_to_current_thread();
+                    let mut cycle_index = 0;
                     while !keep_running.is_cancelled() {
-                        if let Err(error) = self.cycle() {
+                        logging::set_cycle_index(cycle_index);
+                        cycle_index += 1;
+                        // panics outside of nodes terminate all cyclers like errors instead of
+                        // only this thread, such that the runtime exits and the crash is handled
+                        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| self.cycle()))
+                            .unwrap_or_else(|_| Err(color_eyre::eyre::eyre!("cycler panicked")));
+                        if let Err(error) = result {
                             keep_running.cancel();
                             return Err(error).wrap_err_with(|| {
                                 format!("failed to execute cycle of cycler `{:?}`", self.instance)
                             });
                         }
                     }
+                    if let Some(snapshot_file) = &mut self.snapshot_file {
+                        snapshot_file
+                            .write(&self.persistent_state)
+                            .wrap_err("failed to write snapshot")?;
+                    }
                     Ok(())
                 })
                 .wrap_err_with(|| {
@@ -353,11 +398,11 @@ fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
     let setup_node_executions = cycler
         .setup_nodes
         .iter()
-        .map(|node| generate_node_execution(node, cycler));
+        .map(|node| generate_node_execution(node, cycler, false));
     let cycle_node_executions = cycler
         .cycle_nodes
         .iter()
-        .map(|node| generate_node_execution(node, cycler));
+        .map(|node| generate_node_execution(node, cycler, true));
 
     let post_setup = match cycler.kind {
         CyclerKind::Perception => quote! {
@@ -408,6 +453,7 @@ fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
                 let instance = self.instance;
                 let instance_name = format!("{instance:?}");
                 let itt_domain = ittapi::Domain::new(&instance_name);
+                let cycle_start = std::time::Instant::now();
 
                 let mut own_database = self.own_writer.next();
                 let own_database_reference = {
@@ -423,6 +469,8 @@ fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
 
                 #post_setup
 
+                // setup nodes wait for new data, the budget only covers the remaining nodes
+                let budget_start = std::time::Instant::now();
                 {
                     let own_subscribed_outputs = self.own_subscribed_outputs_reader.next();
                     let parameters = self.parameters_reader.next();
@@ -431,7 +479,47 @@ fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
                 }
 
                 #after_remaining_nodes
+
+                self.timing_window.record_cycle(cycle_start.elapsed());
+                self.cycle_budget.record_cycle(budget_start.elapsed());
+                let is_timings_subscribed = self
+                    .own_subscribed_outputs_reader
+                    .next()
+                    .iter()
+                    .any(|subscribed_output| framework::should_be_filled(subscribed_output, "timings"));
+                if is_timings_subscribed {
+                    own_database_reference.timings = self.timing_window.timings();
+                }
+                let is_node_failures_subscribed = self
+                    .own_subscribed_outputs_reader
+                    .next()
+                    .iter()
+                    .any(|subscribed_output| framework::should_be_filled(subscribed_output, "node_failures"));
+                if is_node_failures_subscribed {
+                    own_database_reference.node_failures = self.failure_containment.failures();
+                }
+                let is_overruns_subscribed = self
+                    .own_subscribed_outputs_reader
+                    .next()
+                    .iter()
+                    .any(|subscribed_output| framework::should_be_filled(subscribed_output, "overruns"));
+                if is_overruns_subscribed {
+                    own_database_reference.overruns = self.cycle_budget.overruns();
+                }
+
+                if let Some(recorder) = &self.recorder {
+                    let now = <HardwareInterface as hardware::TimeInterface>::get_now(&*self.hardware_interface);
+                    recorder
+                        .record(now, &own_database_reference.main_outputs)
+                        .wrap_err("failed to record main outputs")?;
+                }
             }
+            if let Some(snapshot_file) = &mut self.snapshot_file {
+                snapshot_file
+                    .write_if_due(&self.persistent_state)
+                    .wrap_err("failed to write snapshot")?;
+            }
+            self.cycle_count = self.cycle_count.wrapping_add(1);
             self.own_changed.notify_one();
             Ok(())
         }
@@ -451,28 +539,50 @@ fn generate_perception_cycler_updates(cyclers: &Cyclers) -> TokenStream {
         .collect()
 }
 
-fn generate_node_execution(node: &Node, cycler: &Cycler) -> TokenStream {
+fn generate_node_execution(node: &Node, cycler: &Cycler, can_be_skipped: bool) -> TokenStream {
     let are_required_inputs_some = generate_required_input_condition(node, cycler);
     let node_name = &node.name;
     let node_module = &node.module;
     let node_member = format_ident!("{}", node.name.to_case(Case::Snake));
+    let node_index = cycler
+        .iter_nodes()
+        .position(|other_node| other_node.name == node.name)
+        .expect("node should be part of its cycler");
     let context_initializers = generate_context_initializers(node, cycler);
     let error_message = format!("failed to execute cycle of `{}`", node.name);
     let database_updates = generate_database_updates(node);
     let database_updates_from_defaults = generate_database_updates_from_defaults(node);
+    let is_not_skipped = if can_be_skipped {
+        quote! { !self.cycle_budget.should_skip(#node_index, budget_start.elapsed()) }
+    } else {
+        quote! { true }
+    };
     quote! {
         {
-            if #are_required_inputs_some {
+            if #is_not_skipped && #are_required_inputs_some {
                 let main_outputs = {
                     let _task = ittapi::Task::begin(&itt_domain, #node_name);
-                    self.#node_member.cycle(
-                        #node_module::CycleContext {
-                            #context_initializers
-                        },
-                    )
-                    .wrap_err(#error_message)?
+                    let node_start = std::time::Instant::now();
+                    let main_outputs = self.failure_containment.execute(#node_index, || {
+                        self.#node_member.cycle(
+                            #node_module::CycleContext {
+                                #context_initializers
+                            },
+                        )
+                    })
+                    .wrap_err(#error_message)?;
+                    self.timing_window.record_node(#node_index, node_start.elapsed());
+                    main_outputs
                 };
-                #database_updates
+                // contained failures reset the outputs to their defaults like missing required inputs
+                match main_outputs {
+                    Some(main_outputs) => {
+                        #database_updates
+                    }
+                    None => {
+                        #database_updates_from_defaults
+                    }
+                }
             }
             else {
                 #database_updates_from_defaults
@@ -545,6 +655,43 @@ fn generate_context_initializers(node: &Node, cycler: &Cycler) -> TokenStream {
                         )
                     }
                 }
+                Field::AdditionalOutputsSubscribed { name } => {
+                    let path_strings: Vec<_> = node
+                        .contexts
+                        .cycle_context
+                        .iter()
+                        .filter_map(|field| match field {
+                            Field::AdditionalOutput { path, .. } => Some(
+                                once("additional_outputs")
+                                    .chain(path.segments.iter().map(|segment| segment.name.as_str()))
+                                    .join("."),
+                            ),
+                            _ => None,
+                        })
+                        .collect();
+                    quote! {
+                        #name: false #(|| own_subscribed_outputs
+                            .iter()
+                            .any(|subscribed_output| framework::should_be_filled(subscribed_output, #path_strings)))*
+                    }
+                }
+                Field::DecimatedInput {
+                    decimation,
+                    name,
+                    path,
+                    ..
+                } => {
+                    let accessor = path_to_accessor_token_stream(
+                        quote! { own_database_reference.main_outputs },
+                        path,
+                        ReferenceKind::Immutable,
+                        cycler,
+                    );
+                    let flatten = path.contains_optional().then(|| quote! { .flatten() });
+                    quote! {
+                        #name: (self.cycle_count % #decimation == 0).then(|| #accessor) #flatten
+                    }
+                }
                 Field::HardwareInterface { name } => quote! {
                     #name: &self.hardware_interface
                 },
@@ -738,3 +885,64 @@ fn generate_database_updates_from_defaults(node: &Node) -> TokenStream {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use source_analyzer::contexts::Contexts;
+
+    use super::*;
+
+    fn node_with_cycle_context(cycle_context: &str) -> Node {
+        let source = format!(
+            "#[context] pub struct CreationContext {{}}
+            #[context] pub struct CycleContext {{ {cycle_context} }}
+            #[context] pub struct MainOutputs {{}}"
+        );
+        Node {
+            name: "BallFilter".to_string(),
+            module: syn::parse_str("control::ball_filter").unwrap(),
+            file_path: "control/src/ball_filter.rs".into(),
+            contexts: Contexts::try_from_file(&syn::parse_file(&source).unwrap()).unwrap(),
+        }
+    }
+
+    #[test]
+    fn additional_outputs_subscribed_checks_all_additional_outputs_of_the_node() {
+        let cycler = Cycler {
+            name: "Control".to_string(),
+            kind: CyclerKind::RealTime,
+            instances: vec!["Control".to_string()],
+            setup_nodes: Vec::new(),
+            cycle_nodes: Vec::new(),
+        };
+        let node = node_with_cycle_context(
+            r#"
+            hypotheses: AdditionalOutput<Vec<f32>, "ball_filter.hypotheses">,
+            ratio: Parameter<f32, "ball_filter.ratio">,
+            field_balls: AdditionalOutput<Vec<f32>, "field_balls">,
+            any_subscribed: AdditionalOutputsSubscribed,
+            "#,
+        );
+
+        assert!(generate_context_initializers(&node, &cycler)
+            .to_string()
+            .contains(
+                &quote! {
+                    any_subscribed: false
+                        || own_subscribed_outputs
+                            .iter()
+                            .any(|subscribed_output| framework::should_be_filled(subscribed_output, "additional_outputs.ball_filter.hypotheses"))
+                        || own_subscribed_outputs
+                            .iter()
+                            .any(|subscribed_output| framework::should_be_filled(subscribed_output, "additional_outputs.field_balls")),
+                }
+                .to_string()
+            ));
+
+        let node = node_with_cycle_context("any_subscribed: AdditionalOutputsSubscribed");
+        assert_eq!(
+            generate_context_initializers(&node, &cycler).to_string(),
+            quote! { any_subscribed: false, }.to_string()
+        );
+    }
+}
@@ -13,12 +13,29 @@ pub fn generate_run_function(cyclers: &Cyclers) -> TokenStream {
     let construct_cyclers = generate_cycler_constructors(cyclers);
     let start_cyclers = generate_cycler_starts(cyclers);
     let join_cyclers = generate_cycler_joins(cyclers);
+    let has_snapshots = cyclers
+        .cyclers
+        .iter()
+        .any(|cycler| !cycler.snapshot_paths().is_empty());
+    let construct_snapshot_directory = if has_snapshots {
+        quote! {
+            let snapshot_directory = hardware::PathsInterface::get_paths(&*hardware_interface)
+                .snapshots
+                .map(|directory| directory.join(&body_id));
+        }
+    } else {
+        quote! {}
+    };
 
     quote! {
-        #[allow(clippy::redundant_clone)]
+        #[allow(clippy::redundant_clone, clippy::too_many_arguments)]
         pub fn run(
             hardware_interface: std::sync::Arc<impl crate::HardwareInterface + Send + Sync + 'static>,
             addresses: Option<impl tokio::net::ToSocketAddrs + std::marker::Send + std::marker::Sync + 'static>,
+            privileged_token: Option<String>,
+            thread_scheduling: std::collections::HashMap<String, framework::ThreadScheduling>,
+            failure_tolerance: framework::FailureTolerance,
+            cycle_budgets: std::collections::HashMap<String, framework::CycleBudget>,
             parameters_directory: impl std::convert::AsRef<std::path::Path> + std::marker::Send + std::marker::Sync + 'static,
             body_id: String,
             head_id: String,
@@ -30,10 +47,23 @@ pub fn generate_run_function(cyclers: &Cyclers) -> TokenStream {
             #construct_multiple_buffers
             #construct_future_queues
 
+            #construct_snapshot_directory
+
             let communication_server = communication::server::Runtime::start(
-                addresses, parameters_directory, body_id, head_id, #number_of_parameter_slots, keep_running.clone())
+                addresses,
+                privileged_token,
+                thread_scheduling.get("Communication").cloned().unwrap_or_default(),
+                parameters_directory,
+                crate::structs::parameters_metadata(),
+                body_id,
+                head_id,
+                #number_of_parameter_slots,
+                keep_running.clone(),
+            )
                 .wrap_err("failed to start communication server")?;
 
+            let recording_directory = hardware::PathsInterface::get_paths(&*hardware_interface).recordings;
+
             #construct_cyclers
 
             #start_cyclers
@@ -130,8 +160,33 @@ fn generate_cycler_constructors(cyclers: &Cyclers) -> TokenStream {
                     quote! { #identifier.clone() }
                 },
             });
+        let recorder_identifier = format_ident!("{instance_name_snake_case}_recorder");
+        let recording_file_name = format!("{instance}.bincode.gz");
+        let recorder_error_message = format!("failed to start recorder for cycler `{instance}`");
+        let snapshot_error_message = format!("failed to start snapshots for cycler `{instance}`");
+        let snapshot_file_identifier = format_ident!("{instance_name_snake_case}_snapshot_file");
+        let snapshot_paths = cycler.snapshot_paths();
+        let snapshot_file = if snapshot_paths.is_empty() {
+            quote! { None }
+        } else {
+            let snapshot_file_name = format!("{instance}.json");
+            let snapshot_paths = snapshot_paths.iter();
+            quote! {
+                snapshot_directory
+                    .as_ref()
+                    .map(|directory| framework::SnapshotFile::start(directory.join(#snapshot_file_name), &[#(#snapshot_paths,)*]))
+                    .transpose()
+                    .wrap_err(#snapshot_error_message)?
+            }
+        };
         let error_message = format!("failed to create cycler `{}`", instance);
         quote! {
+            let #recorder_identifier = recording_directory
+                .as_ref()
+                .map(|directory| framework::Recorder::start(directory.join(#recording_file_name)))
+                .transpose()
+                .wrap_err(#recorder_error_message)?;
+            let #snapshot_file_identifier = #snapshot_file;
             let #cycler_database_changed_identifier = std::sync::Arc::new(tokio::sync::Notify::new());
             let (#own_subscribed_outputs_writer_identifier, #own_subscribed_outputs_reader_identifier) = framework::multiple_buffer_with_slots([
                 Default::default(),
@@ -145,12 +200,17 @@ fn generate_cycler_constructors(cyclers: &Cyclers) -> TokenStream {
                 #cycler_database_changed_identifier.clone(),
                 #own_subscribed_outputs_reader_identifier,
                 communication_server.get_parameters_reader(),
+                #recorder_identifier,
+                #snapshot_file_identifier,
+                failure_tolerance,
+                cycle_budgets.get(#cycler_instance_name).cloned().unwrap_or_default(),
                 #own_producer_identifier
                 #(#other_cycler_inputs,)*
             )
             .wrap_err(#error_message)?;
             communication_server.register_cycler_instance(
                 #cycler_instance_name,
+                crate::structs::#cycler_module_name::outputs_metadata(),
                 #cycler_database_changed_identifier,
                 #own_reader_identifier.clone(),
                 #own_subscribed_outputs_writer_identifier,
@@ -171,7 +231,10 @@ fn generate_cycler_starts(cyclers: &Cyclers) -> TokenStream {
             let error_message = format!("failed to start cycler `{}`", instance);
             quote! {
                 let #cycler_handle_identifier = #cycler_variable_identifier
-                    .start(keep_running.clone())
+                    .start(
+                        keep_running.clone(),
+                        thread_scheduling.get(#instance).cloned().unwrap_or_default(),
+                    )
                     .wrap_err(#error_message)?;
             }
         })
@@ -1,7 +1,13 @@
+use std::collections::BTreeMap;
+
 use convert_case::{Case, Casing};
 use proc_macro2::{Ident, TokenStream};
-use quote::{format_ident, quote};
-use source_analyzer::{struct_hierarchy::StructHierarchy, structs::Structs};
+use quote::{format_ident, quote, ToTokens};
+use source_analyzer::{
+    contexts::{FieldMetadata, ParameterConstraints},
+    struct_hierarchy::StructHierarchy,
+    structs::Structs,
+};
 
 pub fn generate_structs(structs: &Structs) -> TokenStream {
     let derives = quote! {
@@ -14,8 +20,14 @@ pub fn generate_structs(structs: &Structs) -> TokenStream {
             serialize_hierarchy::SerializeHierarchy,
          )]
     };
-    let parameters =
-        hierarchy_to_token_stream(&structs.parameters, format_ident!("Parameters"), &derives);
+    let parameters = hierarchy_to_token_stream(
+        &structs.parameters,
+        format_ident!("Parameters"),
+        &derives,
+        "",
+        &structs.parameter_constraints,
+    );
+    let parameters_metadata = metadata_to_token_stream(&structs.parameter_metadata);
     let cyclers = structs
         .cyclers
         .iter()
@@ -25,23 +37,34 @@ pub fn generate_structs(structs: &Structs) -> TokenStream {
                 &cycler_structs.main_outputs,
                 format_ident!("MainOutputs"),
                 &derives,
+                "",
+                &BTreeMap::new(),
             );
             let additional_outputs = hierarchy_to_token_stream(
                 &cycler_structs.additional_outputs,
                 format_ident!("AdditionalOutputs"),
                 &derives,
+                "",
+                &BTreeMap::new(),
             );
             let persistent_state = hierarchy_to_token_stream(
                 &cycler_structs.persistent_state,
                 format_ident!("PersistentState"),
                 &derives,
+                "",
+                &BTreeMap::new(),
             );
+            let outputs_metadata = metadata_to_token_stream(&cycler_structs.output_metadata);
 
             quote! {
                 pub mod #cycler_module_identifier {
                     #main_outputs
                     #additional_outputs
                     #persistent_state
+
+                    pub fn outputs_metadata() -> communication::messages::Metadata {
+                        #outputs_metadata
+                    }
                 }
             }
         });
@@ -49,6 +72,33 @@ pub fn generate_structs(structs: &Structs) -> TokenStream {
     quote! {
         #parameters
         #(#cyclers)*
+
+        pub fn parameters_metadata() -> communication::messages::Metadata {
+            #parameters_metadata
+        }
+    }
+}
+
+fn metadata_to_token_stream(metadata: &BTreeMap<String, FieldMetadata>) -> TokenStream {
+    let entries = metadata.iter().map(|(path, FieldMetadata { doc, unit })| {
+        let doc = optional_string_to_token_stream(doc);
+        let unit = optional_string_to_token_stream(unit);
+        quote! {
+            (
+                String::from(#path),
+                communication::messages::FieldMetadata { doc: #doc, unit: #unit },
+            )
+        }
+    });
+    quote! {
+        std::collections::BTreeMap::from([#(#entries,)*])
+    }
+}
+
+fn optional_string_to_token_stream(value: &Option<String>) -> TokenStream {
+    match value {
+        Some(value) => quote! { Some(String::from(#value)) },
+        None => quote! { None },
     }
 }
 
@@ -56,14 +106,21 @@ fn hierarchy_to_token_stream(
     hierarchy: &StructHierarchy,
     struct_name: Ident,
     derives: &TokenStream,
+    path: &str,
+    constraints: &BTreeMap<String, ParameterConstraints>,
 ) -> TokenStream {
     let fields = match hierarchy {
         StructHierarchy::Struct { fields } => fields,
         StructHierarchy::Optional { .. } => panic!("option instead of struct"),
         StructHierarchy::Field { .. } => panic!("field instead of struct"),
     };
+    let field_path = |name: &str| match path {
+        "" => name.to_string(),
+        path => format!("{path}.{name}"),
+    };
     let struct_fields = fields.iter().map(|(name, struct_hierarchy)| {
         let name_identifier = format_ident!("{}", name);
+        let field_path = field_path(name);
         match struct_hierarchy {
             StructHierarchy::Struct { .. } => {
                 let struct_name_identifier =
@@ -80,23 +137,60 @@ fn hierarchy_to_token_stream(
                     panic!("unexpected optional in an optional struct")
                 }
                 StructHierarchy::Field { data_type } => {
-                    quote! { pub #name_identifier: Option<#data_type> }
+                    let attributes = constraints.get(&field_path).map(|constraints| {
+                        generate_constraint_attributes(&struct_name, name, constraints, true)
+                    });
+                    quote! { #attributes pub #name_identifier: Option<#data_type> }
                 }
             },
             StructHierarchy::Field { data_type } => {
-                quote! { pub #name_identifier: #data_type }
+                let attributes = constraints.get(&field_path).map(|constraints| {
+                    generate_constraint_attributes(&struct_name, name, constraints, false)
+                });
+                quote! { #attributes pub #name_identifier: #data_type }
             }
         }
     });
+    let constraint_functions = fields.iter().filter_map(|(name, struct_hierarchy)| {
+        let field_path = field_path(name);
+        let constraints = constraints.get(&field_path)?;
+        match struct_hierarchy {
+            StructHierarchy::Field { data_type } => Some(generate_constraint_functions(
+                &struct_name,
+                name,
+                &field_path,
+                data_type.to_token_stream(),
+                constraints,
+                false,
+            )),
+            StructHierarchy::Optional { child } => match &**child {
+                StructHierarchy::Field { data_type } => Some(generate_constraint_functions(
+                    &struct_name,
+                    name,
+                    &field_path,
+                    quote! { Option<#data_type> },
+                    constraints,
+                    true,
+                )),
+                _ => None,
+            },
+            StructHierarchy::Struct { .. } => None,
+        }
+    });
     let child_structs = fields.iter().map(|(name, struct_hierarchy)| {
         let struct_name = format_ident!("{}{}", struct_name, name.to_case(Case::Pascal));
+        let field_path = field_path(name);
         match struct_hierarchy {
-            StructHierarchy::Struct { .. } => {
-                hierarchy_to_token_stream(struct_hierarchy, struct_name, derives)
-            }
+            StructHierarchy::Struct { .. } => hierarchy_to_token_stream(
+                struct_hierarchy,
+                struct_name,
+                derives,
+                &field_path,
+                constraints,
+            ),
             StructHierarchy::Optional { child } => match &**child {
                 StructHierarchy::Struct { .. } => {
-                    hierarchy_to_token_stream(child, struct_name, derives)
+                    hierarchy_to_token_stream(child, struct_name, derives, &field_path, constraints)
                 }
                 StructHierarchy::Optional { .. } => {
                     panic!("unexpected optional in an optional struct")
@@ -111,6 +205,163 @@ fn hierarchy_to_token_stream(
         pub struct #struct_name {
             #(#struct_fields,)*
         }
+        #(#constraint_functions)*
         #(#child_structs)*
     }
 }
+
+fn constraint_function_name(prefix: &str, struct_name: &Ident, field_name: &str) -> Ident {
+    format_ident!(
+        "{}_{}_{}",
+        prefix,
+        struct_name.to_string().to_case(Case::Snake),
+        field_name
+    )
+}
+
+fn has_validation(constraints: &ParameterConstraints) -> bool {
+    constraints.range.is_some() || constraints.non_empty
+}
+
+fn generate_constraint_attributes(
+    struct_name: &Ident,
+    field_name: &str,
+    constraints: &ParameterConstraints,
+    is_optional: bool,
+) -> TokenStream {
+    let default = match &constraints.default {
+        Some(_) => {
+            let function_name =
+                constraint_function_name("default", struct_name, field_name).to_string();
+            quote! { #[serde(default = #function_name)] }
+        }
+        // a custom deserializer loses serde's implicit `None` for missing optional fields
+        None if is_optional && has_validation(constraints) => quote! { #[serde(default)] },
+        None => quote! {},
+    };
+    let validation = if has_validation(constraints) {
+        let function_name =
+            constraint_function_name("deserialize_validated", struct_name, field_name).to_string();
+        quote! { #[serde(deserialize_with = #function_name)] }
+    } else {
+        quote! {}
+    };
+    quote! {
+        #default
+        #validation
+    }
+}
+
+fn generate_constraint_functions(
+    struct_name: &Ident,
+    field_name: &str,
+    path: &str,
+    data_type: TokenStream,
+    constraints: &ParameterConstraints,
+    is_optional: bool,
+) -> TokenStream {
+    let default = constraints.default.as_ref().map(|default| {
+        let function_name = constraint_function_name("default", struct_name, field_name);
+        quote! {
+            fn #function_name() -> #data_type {
+                #default
+            }
+        }
+    });
+    let validation = has_validation(constraints).then(|| {
+        let function_name =
+            constraint_function_name("deserialize_validated", struct_name, field_name);
+        let range_check = constraints.range.as_ref().map(|range| {
+            let range_string = range
+                .to_token_stream()
+                .to_string()
+                .replace('{', "{{")
+                .replace('}', "}}");
+            let message = format!("parameter `{path}` must be in range {range_string}, got {{:?}}");
+            quote! {
+                if !(#range).contains(value) {
+                    return Err(serde::de::Error::custom(format!(#message, value)));
+                }
+            }
+        });
+        let non_empty_check = constraints.non_empty.then(|| {
+            let message = format!("parameter `{path}` must not be empty");
+            quote! {
+                if value.is_empty() {
+                    return Err(serde::de::Error::custom(#message));
+                }
+            }
+        });
+        let checks = quote! {
+            #range_check
+            #non_empty_check
+        };
+        let checked_value = if is_optional {
+            quote! {
+                if let Some(value) = &deserialized_value {
+                    #checks
+                }
+            }
+        } else {
+            quote! {
+                let value = &deserialized_value;
+                #checks
+            }
+        };
+        quote! {
+            fn #function_name<'de, D>(deserializer: D) -> Result<#data_type, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let deserialized_value = <#data_type as serde::Deserialize>::deserialize(deserializer)?;
+                #checked_value
+                Ok(deserialized_value)
+            }
+        }
+    });
+    quote! {
+        #default
+        #validation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validated_parameters_are_deserialized_with_generated_function() {
+        let struct_name = format_ident!("ParametersA");
+        let constraints = ParameterConstraints {
+            non_empty: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            generate_constraint_attributes(&struct_name, "names", &constraints, false).to_string(),
+            quote! {
+                #[serde(deserialize_with = "deserialize_validated_parameters_a_names")]
+            }
+            .to_string()
+        );
+        // missing optional parameters stay `None` despite the custom deserializer
+        assert_eq!(
+            generate_constraint_attributes(&struct_name, "names", &constraints, true).to_string(),
+            quote! {
+                #[serde(default)]
+                #[serde(deserialize_with = "deserialize_validated_parameters_a_names")]
+            }
+            .to_string()
+        );
+        assert!(generate_constraint_functions(
+            &struct_name,
+            "names",
+            "a.names",
+            quote! { Vec<String> },
+            &constraints,
+            false,
+        )
+        .to_string()
+        .contains("\"parameter `a.names` must not be empty\""));
+    }
+}
@@ -1,5 +1,9 @@
-use std::collections::BTreeSet;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
 
+use parameters::provenance::Source;
 use serde_json::Value;
 use tokio::{
     spawn,
@@ -16,12 +20,14 @@ use crate::{
         parameter_subscription_manager::{self, parameter_subscription_manager},
         SubscriberMessage,
     },
-    messages::{Fields, Format, Path},
+    messages::{
+        CyclerInstance, Encoding, Fields, Format, Metadata, Path, Reason, SubscriptionOptions,
+    },
 };
 
 use super::{
     id_tracker::id_tracker,
-    output_subscription_manager::{self, output_subscription_manager},
+    output_subscription_manager::{self, output_subscription_manager, FieldsChange, ManagerError},
     responder::responder,
     CyclerOutput,
 };
@@ -35,6 +41,10 @@ pub struct Communication {
 
 impl Communication {
     pub fn new(address: Option<String>, connect: bool) -> Self {
+        Self::new_with_encoding(address, connect, Encoding::Json)
+    }
+
+    pub fn new_with_encoding(address: Option<String>, connect: bool, encoding: Encoding) -> Self {
         let (connector_sender, connector_receiver) = mpsc::channel(10);
         let (output_subscription_manager_sender, output_subscription_manager_receiver) =
             mpsc::channel(10);
@@ -51,6 +61,7 @@ impl Communication {
             responder_sender.clone(),
             address,
             connect,
+            encoding,
         ));
         spawn(output_subscription_manager(
             output_subscription_manager_receiver,
@@ -97,10 +108,45 @@ impl Communication {
         subscriber_receiver
     }
 
+    pub async fn subscribe_output_manager_errors(&self) -> Receiver<ManagerError> {
+        let (subscriber_sender, subscriber_receiver) = mpsc::channel(10);
+        self.output_subscription_manager
+            .send(output_subscription_manager::Message::SubscribeToErrors {
+                subscriber: subscriber_sender,
+            })
+            .await
+            .unwrap();
+        subscriber_receiver
+    }
+
+    /// Notifies about outputs which appeared or disappeared after reconnecting to a robot
+    pub async fn subscribe_output_fields_changes(&self) -> Receiver<FieldsChange> {
+        let (subscriber_sender, subscriber_receiver) = mpsc::channel(10);
+        self.output_subscription_manager
+            .send(
+                output_subscription_manager::Message::SubscribeToFieldsChanges {
+                    subscriber: subscriber_sender,
+                },
+            )
+            .await
+            .unwrap();
+        subscriber_receiver
+    }
+
     pub async fn subscribe_output(
         &self,
         output: CyclerOutput,
         format: Format,
+    ) -> (Uuid, Receiver<SubscriberMessage>) {
+        self.subscribe_output_with_options(output, format, Default::default())
+            .await
+    }
+
+    pub async fn subscribe_output_with_options(
+        &self,
+        output: CyclerOutput,
+        format: Format,
+        options: SubscriptionOptions,
     ) -> (Uuid, Receiver<SubscriberMessage>) {
         let (subscriber_sender, subscriber_receiver) = mpsc::channel(10);
         let (response_sender, response_receiver) = oneshot::channel();
@@ -108,6 +154,7 @@ impl Communication {
             .send(output_subscription_manager::Message::Subscribe {
                 output,
                 format,
+                options,
                 subscriber: subscriber_sender,
                 response_sender,
             })
@@ -155,6 +202,16 @@ impl Communication {
         response_receiver.await.unwrap()
     }
 
+    /// Queries the descriptions and units of all documented outputs
+    pub async fn get_output_metadata(&self) -> Result<BTreeMap<CyclerInstance, Metadata>, Reason> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.output_subscription_manager
+            .send(output_subscription_manager::Message::GetMetadata { response_sender })
+            .await
+            .unwrap();
+        response_receiver.await.unwrap()
+    }
+
     pub async fn get_parameter_fields(&self) -> Option<BTreeSet<Path>> {
         let (response_sender, response_receiver) = oneshot::channel();
         self.parameter_subscription_manager
@@ -164,6 +221,45 @@ impl Communication {
         response_receiver.await.unwrap()
     }
 
+    /// Queries the descriptions and units of all documented parameters
+    pub async fn get_parameter_metadata(&self) -> Result<Metadata, Reason> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.parameter_subscription_manager
+            .send(parameter_subscription_manager::Message::GetMetadata { response_sender })
+            .await
+            .unwrap();
+        response_receiver.await.unwrap()
+    }
+
+    /// Queries the origin of every parameter value at or below `path`
+    pub async fn get_parameter_provenance(
+        &self,
+        path: &str,
+    ) -> Result<BTreeMap<Path, Source>, Reason> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.parameter_subscription_manager
+            .send(parameter_subscription_manager::Message::GetProvenance {
+                path: path.to_owned(),
+                response_sender,
+            })
+            .await
+            .unwrap();
+        response_receiver.await.unwrap()
+    }
+
+    /// Writes the current values of the parameters at `paths` into the files they originate from
+    pub async fn store_parameters(&self, paths: Vec<Path>) -> Result<(), Reason> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.parameter_subscription_manager
+            .send(parameter_subscription_manager::Message::StoreParameters {
+                paths,
+                response_sender,
+            })
+            .await
+            .unwrap();
+        response_receiver.await.unwrap()
+    }
+
     pub async fn update_parameter_value(&self, path: &str, value: Value) {
         self.parameter_subscription_manager
             .send(
@@ -175,4 +271,35 @@ impl Communication {
             .await
             .unwrap();
     }
+
+    /// Overrides the parameter at `path` until the time to live expires
+    pub async fn inject_command(&self, path: &str, value: Value, time_to_live: Duration) {
+        self.parameter_subscription_manager
+            .send(parameter_subscription_manager::Message::InjectCommand {
+                path: path.to_owned(),
+                value,
+                time_to_live,
+            })
+            .await
+            .unwrap();
+    }
+
+    /// Repeats one of the predefined test motions on the robot, standing before and after
+    pub async fn run_motion_test(&self, motion: Value, repetitions: usize) {
+        self.parameter_subscription_manager
+            .send(parameter_subscription_manager::Message::RunMotionTest {
+                motion,
+                repetitions,
+            })
+            .await
+            .unwrap();
+    }
+
+    /// Sends the joystick state, it has to be repeated while the robot should keep moving
+    pub async fn teleoperate(&self, command: Value) {
+        self.parameter_subscription_manager
+            .send(parameter_subscription_manager::Message::Teleoperate { command })
+            .await
+            .unwrap();
+    }
 }
@@ -12,11 +12,17 @@ use tokio::{
 };
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 
-use crate::client::{
-    output_subscription_manager, parameter_subscription_manager,
-    receiver::receiver as receiver_task, requester::requester, responder,
+use crate::{
+    client::{
+        output_subscription_manager, parameter_subscription_manager,
+        receiver::receiver as receiver_task, requester::requester, responder,
+    },
+    messages::{Encoding, Request},
 };
 
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+const MAXIMUM_RECONNECT_DELAY: Duration = Duration::from_secs(16);
+
 #[derive(Debug)]
 pub enum Message {
     SubscribeToUpdates(Sender<ConnectionStatus>),
@@ -56,6 +62,7 @@ pub enum ConnectionStatus {
     },
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn connector(
     mut receiver: Receiver<Message>,
     sender: Sender<Message>,
@@ -64,6 +71,7 @@ pub async fn connector(
     responder: Sender<responder::Message>,
     initial_address: Option<String>,
     initial_connect: bool,
+    encoding: Encoding,
 ) {
     let mut status = match (initial_address, initial_connect) {
         (Some(address), true) => {
@@ -77,6 +85,7 @@ pub async fn connector(
     };
 
     let mut subscribers = Vec::new();
+    let mut failed_connection_attempts = 0;
 
     while let Some(message) = receiver.recv().await {
         status = match status {
@@ -235,6 +244,13 @@ pub async fn connector(
                 Message::Connected(ws_stream) => {
                     let (writer, reader) = (*ws_stream).split();
                     let (requester_sender, requester_receiver) = channel(10);
+                    if encoding != Encoding::Json {
+                        // requested before any subscription to have all updates in the new encoding
+                        requester_sender
+                            .send(Request::SetEncoding(encoding))
+                            .await
+                            .unwrap();
+                    }
                     output_subscription_manager
                         .send(output_subscription_manager::Message::Connect {
                             requester: requester_sender.clone(),
@@ -256,11 +272,16 @@ pub async fn connector(
                         sender.clone(),
                     ));
                     info!("Connected to {}", address);
+                    failed_connection_attempts = 0;
                     ConnectionState::Connected { address }
                 }
                 Message::ConnectionFailed { info } => {
                     error!("Connection failed: {}", info);
-                    spawn_reconnect_timer(sender.clone());
+                    spawn_reconnect_timer(
+                        sender.clone(),
+                        reconnect_delay(failed_connection_attempts),
+                    );
+                    failed_connection_attempts += 1;
                     ConnectionState::Disconnected {
                         connect: true,
                         address: Some(address),
@@ -313,7 +334,11 @@ pub async fn connector(
                 Message::Connected(_) => panic!("This should never happen"),
                 Message::ConnectionFailed { info } => {
                     error!("Connection failed: {}", info);
-                    spawn_reconnect_timer(sender.clone());
+                    spawn_reconnect_timer(
+                        sender.clone(),
+                        reconnect_delay(failed_connection_attempts),
+                    );
+                    failed_connection_attempts += 1;
                     output_subscription_manager
                         .send(output_subscription_manager::Message::Disconnect)
                         .await
@@ -349,9 +374,18 @@ pub async fn connector(
     }
 }
 
-fn spawn_reconnect_timer(sender: Sender<Message>) {
+fn reconnect_delay(failed_connection_attempts: u32) -> Duration {
+    INITIAL_RECONNECT_DELAY
+        .checked_mul(2_u32.saturating_pow(failed_connection_attempts))
+        .map_or(MAXIMUM_RECONNECT_DELAY, |delay| {
+            delay.min(MAXIMUM_RECONNECT_DELAY)
+        })
+}
+
+fn spawn_reconnect_timer(sender: Sender<Message>, delay: Duration) {
     spawn(async move {
-        sleep(Duration::from_secs(1)).await;
+        info!("Reconnecting in {delay:?}");
+        sleep(delay).await;
         sender.send(Message::ReconnectTimerElapsed).await.unwrap();
     });
 }
@@ -399,3 +433,17 @@ async fn replace_ongoing_connection(
         _ => panic!("Connection attempt was not cancelled. I don't know how to recover"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconnect_delay_grows_exponentially_until_maximum() {
+        assert_eq!(reconnect_delay(0), INITIAL_RECONNECT_DELAY);
+        assert_eq!(reconnect_delay(1), INITIAL_RECONNECT_DELAY * 2);
+        assert_eq!(reconnect_delay(2), INITIAL_RECONNECT_DELAY * 4);
+        assert_eq!(reconnect_delay(10), MAXIMUM_RECONNECT_DELAY);
+        assert_eq!(reconnect_delay(u32::MAX), MAXIMUM_RECONNECT_DELAY);
+    }
+}
@@ -10,4 +10,5 @@ mod types;
 
 pub use crate::client::communication::Communication;
 pub use connector::ConnectionStatus;
+pub use output_subscription_manager::{FieldsChange, ManagerError};
 pub use types::{Cycler, CyclerOutput, HierarchyType, Output, OutputHierarchy, SubscriberMessage};
@@ -1,7 +1,8 @@
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap};
 
 use color_eyre::Result;
 use log::{error, info, warn};
+use thiserror::Error;
 use tokio::{
     spawn,
     sync::{mpsc, oneshot},
@@ -11,10 +12,12 @@ use uuid::Uuid;
 use crate::{
     client::{
         id_tracker::{self, get_message_id},
-        responder, Output, SubscriberMessage,
+        responder::{self, forward_response},
+        Output, SubscriberMessage,
     },
     messages::{
-        Fields, Format, OutputsRequest, Request,
+        CyclerInstance, Fields, Format, Metadata, OutputsRequest, Reason, Request,
+        SubscriptionOptions,
         TextualDataOrBinaryReference::{self, BinaryReference, TextualData},
     },
 };
@@ -30,6 +33,7 @@ pub enum Message {
     Subscribe {
         output: CyclerOutput,
         format: Format,
+        options: SubscriptionOptions,
         subscriber: mpsc::Sender<SubscriberMessage>,
         response_sender: oneshot::Sender<Uuid>,
     },
@@ -48,13 +52,81 @@ pub enum Message {
     GetOutputFields {
         response_sender: oneshot::Sender<Option<Fields>>,
     },
+    SubscribeToErrors {
+        subscriber: mpsc::Sender<ManagerError>,
+    },
+    SubscribeToFieldsChanges {
+        subscriber: mpsc::Sender<FieldsChange>,
+    },
+    GetMetadata {
+        response_sender: oneshot::Sender<Result<BTreeMap<CyclerInstance, Metadata>, Reason>>,
+    },
 }
 
+/// Output fields which appeared or disappeared since the previously known fields, e.g. because a
+/// binary with different outputs was deployed while being connected
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FieldsChange {
+    pub added: Fields,
+    pub removed: Fields,
+}
+
+impl FieldsChange {
+    fn between(old_fields: &Fields, new_fields: &Fields) -> Self {
+        let difference = |minuend: &Fields, subtrahend: &Fields| -> Fields {
+            minuend
+                .iter()
+                .filter_map(|(cycler_instance, paths)| {
+                    let missing_paths: BTreeSet<_> = match subtrahend.get(cycler_instance) {
+                        Some(other_paths) => paths.difference(other_paths).cloned().collect(),
+                        None => paths.clone(),
+                    };
+                    (!missing_paths.is_empty()).then(|| (cycler_instance.clone(), missing_paths))
+                })
+                .collect()
+        };
+        Self {
+            added: difference(new_fields, old_fields),
+            removed: difference(old_fields, new_fields),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    fn removes(&self, output: &CyclerOutput) -> bool {
+        let path = match &output.output {
+            Output::Main { path } => format!("main_outputs.{path}"),
+            Output::Additional { path } => format!("additional_outputs.{path}"),
+            // timings are not part of the fields and exist in every cycler
+            Output::Timings { .. } => return false,
+        };
+        self.removed
+            .get(&output.cycler.to_string())
+            .map_or(false, |paths| paths.contains(&path))
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+pub enum ManagerError {
+    #[error("subscriber {uuid} of {output:?} hung up and was unsubscribed")]
+    SubscriberHungUp { uuid: Uuid, output: CyclerOutput },
+    #[error("failed to send request for {output:?}, the connection is gone")]
+    RequestNotSent { output: CyclerOutput },
+    #[error("failed to await response for {output:?}, the responder is gone")]
+    ResponderNotAvailable { output: CyclerOutput },
+    #[error("got update for unknown subscription {subscription_id}")]
+    UnknownSubscription { subscription_id: usize },
+}
+
+type SubscriptionKey = (CyclerOutput, Format, SubscriptionOptions);
+
 #[derive(Default)]
 struct SubscriptionManager {
-    ids_to_outputs: HashMap<usize, (CyclerOutput, Format)>,
+    ids_to_outputs: HashMap<usize, SubscriptionKey>,
     outputs_to_subscribers:
-        HashMap<(CyclerOutput, Format), HashMap<Uuid, mpsc::Sender<SubscriberMessage>>>,
+        HashMap<SubscriptionKey, HashMap<Uuid, mpsc::Sender<SubscriberMessage>>>,
 }
 
 pub async fn output_subscription_manager(
@@ -67,7 +139,9 @@ pub async fn output_subscription_manager(
     let mut requester = None;
     let mut fields = None;
     let mut binary_data_waiting_for_references: HashMap<usize, Vec<u8>> = HashMap::new();
-    let mut binary_references_waiting_for_data: HashMap<usize, CyclerOutput> = HashMap::new();
+    let mut binary_references_waiting_for_data: HashMap<usize, SubscriptionKey> = HashMap::new();
+    let mut error_subscribers = Vec::new();
+    let mut fields_change_subscribers: Vec<mpsc::Sender<FieldsChange>> = Vec::new();
 
     while let Some(message) = receiver.recv().await {
         match message {
@@ -75,11 +149,12 @@ pub async fn output_subscription_manager(
                 requester: new_requester,
             } => {
                 assert!(manager.ids_to_outputs.is_empty());
-                for ((output, format), subscribers) in &manager.outputs_to_subscribers {
+                for ((output, format, options), subscribers) in &manager.outputs_to_subscribers {
                     let subscribers = subscribers.values().cloned().collect();
-                    if let Some(subscription_id) = subscribe(
+                    match subscribe(
                         output.clone(),
                         *format,
+                        *options,
                         subscribers,
                         &id_tracker,
                         &responder,
@@ -87,9 +162,12 @@ pub async fn output_subscription_manager(
                     )
                     .await
                     {
-                        manager
-                            .ids_to_outputs
-                            .insert(subscription_id, (output.clone(), *format));
+                        Ok(subscription_id) => {
+                            manager
+                                .ids_to_outputs
+                                .insert(subscription_id, (output.clone(), *format, *options));
+                        }
+                        Err(error) => report(&mut error_subscribers, error),
                     }
                 }
                 match query_output_fields(sender.clone(), &id_tracker, &responder, &new_requester)
@@ -108,101 +186,89 @@ pub async fn output_subscription_manager(
             Message::Subscribe {
                 output,
                 format,
+                options,
                 subscriber: output_sender,
                 response_sender,
             } => {
                 let uuid = Uuid::new_v4();
                 match response_sender.send(uuid) {
                     Ok(()) => {
-                        add_subscription(
+                        if let Err(error) = add_subscription(
                             &mut manager,
                             uuid,
                             output,
                             format,
+                            options,
                             output_sender,
                             &id_tracker,
                             &responder,
                             &requester,
                         )
                         .await
+                        {
+                            report(&mut error_subscribers, error);
+                        }
                     }
                     Err(error) => error!("{error}"),
                 };
             }
             Message::Unsubscribe { uuid } => {
-                let mut subscriptions_to_remove = Vec::new();
-                manager
-                    .outputs_to_subscribers
-                    .retain(|output_format, clients| {
-                        if clients.remove(&uuid).is_none() {
-                            return true;
-                        }
-
-                        if clients.is_empty() {
-                            let maybe_subscription_id =
-                                manager
-                                    .ids_to_outputs
-                                    .iter()
-                                    .find_map(|(id, other_output)| {
-                                        (output_format == other_output).then_some(*id)
-                                    });
-                            if let Some(id) = maybe_subscription_id {
-                                subscriptions_to_remove.push(id);
-                            }
-                        }
-                        !clients.is_empty()
-                    });
-                for subscription_id in subscriptions_to_remove {
-                    if let Some(requester) = &requester {
-                        manager.ids_to_outputs.remove(&subscription_id);
-                        unsubscribe(subscription_id, &id_tracker, &responder, requester).await;
-                    }
-                }
+                remove_subscriber(&mut manager, uuid, &id_tracker, &responder, &requester).await;
             }
             Message::Update { items } => {
+                let mut hung_up_subscribers = Vec::new();
                 for (subscription_id, value_or_reference) in items {
                     let Some(output) = manager.ids_to_outputs.get(&subscription_id) else {
-                        warn!("unknown subscription_id: {subscription_id}");
+                        report(
+                            &mut error_subscribers,
+                            ManagerError::UnknownSubscription { subscription_id },
+                        );
                         continue;
                     };
                     if let Some(senders) = manager.outputs_to_subscribers.get(output) {
                         match value_or_reference {
                             TextualData { data } => {
-                                for sender in senders.values() {
-                                    if let Err(error) = sender
-                                        .send(SubscriberMessage::Update {
-                                            value: data.clone(),
-                                        })
-                                        .await
-                                    {
-                                        error!("{error}");
-                                    }
-                                }
+                                let message = SubscriberMessage::Update { value: data };
+                                hung_up_subscribers
+                                    .extend(send_to_subscribers(senders, message, &output.0).await);
                             }
                             BinaryReference { reference_id } => {
-                                if let Some(image) =
+                                if let Some(data) =
                                     binary_data_waiting_for_references.remove(&reference_id)
                                 {
-                                    for sender in senders.values() {
-                                        if let Err(error) = sender
-                                            .send(SubscriberMessage::UpdateBinary {
-                                                data: image.clone(),
-                                            })
-                                            .await
-                                        {
-                                            error!("{error}");
-                                        }
-                                    }
+                                    let message = SubscriberMessage::UpdateBinary { data };
+                                    hung_up_subscribers.extend(
+                                        send_to_subscribers(senders, message, &output.0).await,
+                                    );
                                 } else {
                                     binary_references_waiting_for_data
-                                        .insert(reference_id, output.0.clone());
+                                        .insert(reference_id, output.clone());
                                 }
                             }
                         }
                     }
                 }
+                for error in hung_up_subscribers {
+                    if let ManagerError::SubscriberHungUp { uuid, .. } = &error {
+                        remove_subscriber(&mut manager, *uuid, &id_tracker, &responder, &requester)
+                            .await;
+                    }
+                    report(&mut error_subscribers, error);
+                }
             }
             Message::UpdateFields { fields: new_fields } => {
+                if let Some(old_fields) = &fields {
+                    let change = FieldsChange::between(old_fields, &new_fields);
+                    if !change.is_empty() {
+                        invalidate_removed_outputs(&manager, &change).await;
+                        fields_change_subscribers.retain(|subscriber| !subscriber.is_closed());
+                        for subscriber in &fields_change_subscribers {
+                            if let Err(error) = subscriber.send(change.clone()).await {
+                                error!("{error}");
+                            }
+                        }
+                    }
+                }
                 fields = Some(new_fields);
             }
             Message::GetOutputFields { response_sender } => {
@@ -211,31 +277,127 @@ pub async fn output_subscription_manager(
                 }
             }
             Message::UpdateBinary { referenced_items } => {
+                let mut hung_up_subscribers = Vec::new();
                 for (reference_id, data) in referenced_items {
                     if let Some(output) = binary_references_waiting_for_data.get(&reference_id) {
-                        let subscribers = manager
-                            .outputs_to_subscribers
-                            .get(&(output.clone(), Format::Binary));
+                        let subscribers = manager.outputs_to_subscribers.get(output);
                         if let Some(senders) = subscribers {
-                            for sender in senders.values() {
-                                if let Err(error) = sender
-                                    .send(SubscriberMessage::UpdateBinary { data: data.clone() })
-                                    .await
-                                {
-                                    error!("{error}");
-                                }
-                            }
+                            let message = SubscriberMessage::UpdateBinary { data };
+                            hung_up_subscribers
+                                .extend(send_to_subscribers(senders, message, &output.0).await);
                         }
                     } else {
                         binary_data_waiting_for_references.insert(reference_id, data);
                     }
                 }
+                for error in hung_up_subscribers {
+                    if let ManagerError::SubscriberHungUp { uuid, .. } = &error {
+                        remove_subscriber(&mut manager, *uuid, &id_tracker, &responder, &requester)
+                            .await;
+                    }
+                    report(&mut error_subscribers, error);
+                }
+            }
+            Message::SubscribeToErrors { subscriber } => {
+                error_subscribers.push(subscriber);
+            }
+            Message::SubscribeToFieldsChanges { subscriber } => {
+                fields_change_subscribers.push(subscriber);
+            }
+            Message::GetMetadata { response_sender } => {
+                forward_response(
+                    |id| Request::Outputs(OutputsRequest::GetMetadata { id }),
+                    |response| match response {
+                        Response::OutputMetadata(metadata) => Ok(Ok(metadata)),
+                        response => Err(response),
+                    },
+                    response_sender,
+                    &id_tracker,
+                    &responder,
+                    &mut requester,
+                )
+                .await
             }
         }
     }
     info!("Finished manager");
 }
 
+fn report(error_subscribers: &mut Vec<mpsc::Sender<ManagerError>>, error: ManagerError) {
+    warn!("{error}");
+    error_subscribers.retain(|subscriber| !subscriber.is_closed());
+    for subscriber in error_subscribers.iter() {
+        if let Err(error) = subscriber.try_send(error.clone()) {
+            error!("{error}");
+        }
+    }
+}
+
+/// Notifies subscribers of outputs that do not exist anymore instead of leaving them without updates
+async fn invalidate_removed_outputs(manager: &SubscriptionManager, change: &FieldsChange) {
+    for ((output, _format, _options), senders) in &manager.outputs_to_subscribers {
+        if change.removes(output) {
+            let message = SubscriberMessage::SubscriptionFailure {
+                info: format!("{output:?} was removed from the outputs"),
+            };
+            // hung up subscribers are garbage-collected on the next update
+            let _ = send_to_subscribers(senders, message, output).await;
+        }
+    }
+}
+
+/// Returns a `SubscriberHungUp` error for every subscriber whose channel is closed
+async fn send_to_subscribers(
+    senders: &HashMap<Uuid, mpsc::Sender<SubscriberMessage>>,
+    message: SubscriberMessage,
+    output: &CyclerOutput,
+) -> Vec<ManagerError> {
+    let mut hung_up_subscribers = Vec::new();
+    for (uuid, sender) in senders {
+        if sender.send(message.clone()).await.is_err() {
+            hung_up_subscribers.push(ManagerError::SubscriberHungUp {
+                uuid: *uuid,
+                output: output.clone(),
+            });
+        }
+    }
+    hung_up_subscribers
+}
+
+async fn remove_subscriber(
+    manager: &mut SubscriptionManager,
+    uuid: Uuid,
+    id_tracker: &mpsc::Sender<id_tracker::Message>,
+    responder: &mpsc::Sender<responder::Message>,
+    requester: &Option<mpsc::Sender<Request>>,
+) {
+    let mut subscriptions_to_remove = Vec::new();
+    manager
+        .outputs_to_subscribers
+        .retain(|output_format, clients| {
+            if clients.remove(&uuid).is_none() {
+                return true;
+            }
+
+            if clients.is_empty() {
+                let maybe_subscription_id = manager
+                    .ids_to_outputs
+                    .iter()
+                    .find_map(|(id, other_output)| (output_format == other_output).then_some(*id));
+                if let Some(id) = maybe_subscription_id {
+                    subscriptions_to_remove.push(id);
+                }
+            }
+            !clients.is_empty()
+        });
+    for subscription_id in subscriptions_to_remove {
+        if let Some(requester) = requester {
+            manager.ids_to_outputs.remove(&subscription_id);
+            unsubscribe(subscription_id, id_tracker, responder, requester).await;
+        }
+    }
+}
+
 async fn query_output_fields(
     manager: mpsc::Sender<Message>,
     id_tracker: &mpsc::Sender<id_tracker::Message>,
@@ -253,7 +415,9 @@ async fn query_output_fields(
     let request = Request::Outputs(OutputsRequest::GetFields { id: message_id });
     requester.send(request).await?;
     spawn(async move {
-        let response = response_receiver.await.unwrap();
+        let Ok(response) = response_receiver.await else {
+            return error!("responder dropped the output fields request");
+        };
         match response {
             Response::Fields(fields) => {
                 if let Err(error) = manager.send(Message::UpdateFields { fields }).await {
@@ -272,76 +436,83 @@ async fn add_subscription(
     uuid: Uuid,
     output: CyclerOutput,
     format: Format,
+    options: SubscriptionOptions,
     output_sender: mpsc::Sender<SubscriberMessage>,
     id_tracker: &mpsc::Sender<id_tracker::Message>,
     responder: &mpsc::Sender<responder::Message>,
     requester: &Option<mpsc::Sender<Request>>,
-) {
+) -> Result<(), ManagerError> {
     match manager
         .outputs_to_subscribers
-        .entry((output.clone(), format))
+        .entry((output.clone(), format, options))
     {
         Entry::Occupied(mut entry) => {
             entry.get_mut().insert(uuid, output_sender);
         }
         Entry::Vacant(entry) => {
+            entry
+                .insert(HashMap::new())
+                .insert(uuid, output_sender.clone());
             if let Some(requester) = requester {
-                if let Some(subscription_id) = subscribe(
+                let subscription_id = subscribe(
                     output.clone(),
                     format,
-                    vec![output_sender.clone()],
+                    options,
+                    vec![output_sender],
                     id_tracker,
                     responder,
                     requester,
                 )
-                .await
-                {
-                    manager
-                        .ids_to_outputs
-                        .insert(subscription_id, (output, format));
-                }
+                .await?;
+                manager
+                    .ids_to_outputs
+                    .insert(subscription_id, (output, format, options));
             };
-            entry.insert(HashMap::new()).insert(uuid, output_sender);
         }
     };
+    Ok(())
 }
 
 async fn subscribe(
     output: CyclerOutput,
     format: Format,
+    options: SubscriptionOptions,
     subscribers: Vec<mpsc::Sender<SubscriberMessage>>,
     id_tracker: &mpsc::Sender<id_tracker::Message>,
     responder: &mpsc::Sender<responder::Message>,
     requester: &mpsc::Sender<Request>,
-) -> Option<usize> {
+) -> Result<usize, ManagerError> {
     let message_id = get_message_id(id_tracker).await;
     let (response_sender, response_receiver) = oneshot::channel();
-    if let Err(error) = responder
+    if responder
         .send(responder::Message::Await {
             id: message_id,
             response_sender,
         })
         .await
+        .is_err()
     {
-        error!("{error}");
-        return None;
+        return Err(ManagerError::ResponderNotAvailable { output });
     }
-    let path = match output.output {
+    let path = match &output.output {
         Output::Main { path } => format!("main_outputs.{path}"),
         Output::Additional { path } => format!("additional_outputs.{path}"),
+        Output::Timings { path } => format!("timings.{path}"),
     };
     let request = Request::Outputs(OutputsRequest::Subscribe {
         id: message_id,
         cycler_instance: output.cycler.to_string(),
         path,
         format,
+        options,
     });
-    if let Err(error) = requester.send(request).await {
-        error!("{error}");
-        return None;
+    if requester.send(request).await.is_err() {
+        return Err(ManagerError::RequestNotSent { output });
     }
     spawn(async move {
-        let response = response_receiver.await.unwrap();
+        let Ok(response) = response_receiver.await else {
+            return error!("responder dropped the subscribe request");
+        };
         let result = match response {
             Response::Subscribe(result) => result,
             response => return error!("unexpected response: {response:?}"),
@@ -351,13 +522,12 @@ async fn subscribe(
             Err(error) => SubscriberMessage::SubscriptionFailure { info: error },
         };
         for sender in subscribers {
-            if let Err(error) = sender.send(message.clone()).await {
-                error!("{error}");
-            }
+            // hung up subscribers are garbage-collected on the next update
+            let _ = sender.send(message.clone()).await;
         }
     });
 
-    Some(message_id)
+    Ok(message_id)
 }
 
 async fn unsubscribe(
@@ -385,7 +555,9 @@ async fn unsubscribe(
         error!("{error}")
     }
     spawn(async move {
-        let response = response_receiver.await.unwrap();
+        let Ok(response) = response_receiver.await else {
+            return error!("responder dropped the unsubscribe request");
+        };
         let result = match response {
             Response::Unsubscribe(result) => result,
             response => return error!("unexpected response: {response:?}"),
@@ -395,3 +567,162 @@ async fn unsubscribe(
         };
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::client::{id_tracker::id_tracker, responder::responder};
+
+    use super::*;
+
+    async fn subscribe_to(
+        manager: &mpsc::Sender<Message>,
+        output: &CyclerOutput,
+    ) -> (Uuid, mpsc::Receiver<SubscriberMessage>) {
+        let (subscriber, subscriber_receiver) = mpsc::channel(10);
+        let (response_sender, response_receiver) = oneshot::channel();
+        manager
+            .send(Message::Subscribe {
+                output: output.clone(),
+                format: Format::Textual,
+                options: SubscriptionOptions::default(),
+                subscriber,
+                response_sender,
+            })
+            .await
+            .unwrap();
+        (response_receiver.await.unwrap(), subscriber_receiver)
+    }
+
+    async fn send_update(manager: &mpsc::Sender<Message>, subscription_id: usize, value: Value) {
+        manager
+            .send(Message::Update {
+                items: [(subscription_id, TextualData { data: value })].into(),
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn hung_up_subscriber_is_dropped_while_others_keep_receiving_updates() {
+        let (id_tracker_sender, id_tracker_receiver) = mpsc::channel(10);
+        let (responder_sender, responder_receiver) = mpsc::channel(10);
+        let (manager_sender, manager_receiver) = mpsc::channel(10);
+        let (requester, mut requests) = mpsc::channel(10);
+        let (error_subscriber, mut errors) = mpsc::channel(10);
+        spawn(id_tracker(id_tracker_receiver));
+        spawn(responder(responder_receiver));
+        spawn(output_subscription_manager(
+            manager_receiver,
+            manager_sender.clone(),
+            id_tracker_sender,
+            responder_sender,
+        ));
+        let output: CyclerOutput = "Control.main.a".parse().unwrap();
+
+        manager_sender
+            .send(Message::SubscribeToErrors {
+                subscriber: error_subscriber,
+            })
+            .await
+            .unwrap();
+        manager_sender
+            .send(Message::Connect { requester })
+            .await
+            .unwrap();
+        assert!(matches!(
+            requests.recv().await,
+            Some(Request::Outputs(OutputsRequest::GetFields { .. }))
+        ));
+        let (_alive_uuid, mut alive) = subscribe_to(&manager_sender, &output).await;
+        let Some(Request::Outputs(OutputsRequest::Subscribe {
+            id: subscription_id,
+            ..
+        })) = requests.recv().await
+        else {
+            panic!("expected a subscribe request");
+        };
+        let (dead_uuid, dead) = subscribe_to(&manager_sender, &output).await;
+        drop(dead);
+
+        send_update(&manager_sender, subscription_id, Value::from(1)).await;
+        assert!(matches!(
+            alive.recv().await,
+            Some(SubscriberMessage::Update { value }) if value == 1
+        ));
+        assert!(matches!(
+            errors.recv().await,
+            Some(ManagerError::SubscriberHungUp { uuid, .. }) if uuid == dead_uuid
+        ));
+
+        send_update(&manager_sender, subscription_id, Value::from(2)).await;
+        assert!(matches!(
+            alive.recv().await,
+            Some(SubscriberMessage::Update { value }) if value == 2
+        ));
+        // round trip through the manager to make sure the update has been handled completely
+        let (response_sender, response_receiver) = oneshot::channel();
+        manager_sender
+            .send(Message::GetOutputFields { response_sender })
+            .await
+            .unwrap();
+        response_receiver.await.unwrap();
+        assert!(errors.try_recv().is_err());
+        // the remaining subscriber keeps the subscription alive
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[test]
+    fn fields_change_contains_added_and_removed_paths() {
+        let old_fields: Fields = [
+            (
+                "Control".to_string(),
+                ["main_outputs.a".to_string(), "main_outputs.b".to_string()].into(),
+            ),
+            (
+                "VisionTop".to_string(),
+                ["main_outputs.c".to_string()].into(),
+            ),
+        ]
+        .into();
+        let new_fields: Fields = [
+            (
+                "Control".to_string(),
+                ["main_outputs.a".to_string(), "main_outputs.d".to_string()].into(),
+            ),
+            (
+                "VisionBottom".to_string(),
+                ["main_outputs.c".to_string()].into(),
+            ),
+        ]
+        .into();
+
+        let change = FieldsChange::between(&old_fields, &new_fields);
+
+        assert_eq!(
+            change,
+            FieldsChange {
+                added: [
+                    ("Control".to_string(), ["main_outputs.d".to_string()].into()),
+                    (
+                        "VisionBottom".to_string(),
+                        ["main_outputs.c".to_string()].into()
+                    ),
+                ]
+                .into(),
+                removed: [
+                    ("Control".to_string(), ["main_outputs.b".to_string()].into()),
+                    (
+                        "VisionTop".to_string(),
+                        ["main_outputs.c".to_string()].into()
+                    ),
+                ]
+                .into(),
+            }
+        );
+        assert!(change.removes(&"Control.main.b".parse().unwrap()));
+        assert!(!change.removes(&"Control.main.a".parse().unwrap()));
+        assert!(FieldsChange::between(&new_fields, &new_fields).is_empty());
+    }
+}
@@ -1,7 +1,11 @@
-use std::collections::{hash_map::Entry, BTreeSet, HashMap};
+use std::{
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap},
+    time::Duration,
+};
 
 use color_eyre::eyre::Result;
 use log::{error, info, warn};
+use parameters::provenance::Source;
 use serde_json::Value;
 use tokio::{
     spawn,
@@ -12,9 +16,10 @@ use uuid::Uuid;
 use crate::{
     client::{
         id_tracker::{self, get_message_id},
-        responder, SubscriberMessage,
+        responder::{self, forward_response},
+        SubscriberMessage,
     },
-    messages::{ParametersRequest, Path, Request},
+    messages::{InjectCommandRequest, Metadata, ParametersRequest, Path, Reason, Request},
 };
 
 use super::responder::Response;
@@ -47,6 +52,29 @@ pub enum Message {
         path: String,
         value: Value,
     },
+    InjectCommand {
+        path: String,
+        value: Value,
+        time_to_live: Duration,
+    },
+    RunMotionTest {
+        motion: Value,
+        repetitions: usize,
+    },
+    Teleoperate {
+        command: Value,
+    },
+    GetProvenance {
+        path: Path,
+        response_sender: oneshot::Sender<Result<BTreeMap<Path, Source>, Reason>>,
+    },
+    StoreParameters {
+        paths: Vec<Path>,
+        response_sender: oneshot::Sender<Result<(), Reason>>,
+    },
+    GetMetadata {
+        response_sender: oneshot::Sender<Result<Metadata, Reason>>,
+    },
 }
 
 #[derive(Default)]
@@ -187,6 +215,124 @@ pub async fn parameter_subscription_manager(
                     }
                 }
             }
+            Message::InjectCommand {
+                path,
+                value,
+                time_to_live,
+            } => {
+                if let Some(some_requester) = requester {
+                    match send_command(
+                        |id| InjectCommandRequest::Inject {
+                            id,
+                            path,
+                            data: value,
+                            time_to_live,
+                        },
+                        &id_tracker,
+                        &responder,
+                        &some_requester,
+                    )
+                    .await
+                    {
+                        Ok(_) => requester = Some(some_requester),
+                        Err(error) => {
+                            error!("{error}");
+                            requester = None
+                        }
+                    }
+                }
+            }
+            Message::RunMotionTest {
+                motion,
+                repetitions,
+            } => {
+                if let Some(some_requester) = requester {
+                    match send_command(
+                        |id| InjectCommandRequest::RunMotionTest {
+                            id,
+                            motion,
+                            repetitions,
+                        },
+                        &id_tracker,
+                        &responder,
+                        &some_requester,
+                    )
+                    .await
+                    {
+                        Ok(_) => requester = Some(some_requester),
+                        Err(error) => {
+                            error!("{error}");
+                            requester = None
+                        }
+                    }
+                }
+            }
+            Message::Teleoperate { command } => {
+                if let Some(some_requester) = requester {
+                    match send_command(
+                        |id| InjectCommandRequest::Teleoperate { id, command },
+                        &id_tracker,
+                        &responder,
+                        &some_requester,
+                    )
+                    .await
+                    {
+                        Ok(_) => requester = Some(some_requester),
+                        Err(error) => {
+                            error!("{error}");
+                            requester = None
+                        }
+                    }
+                }
+            }
+            Message::GetProvenance {
+                path,
+                response_sender,
+            } => {
+                forward_response(
+                    |id| Request::Parameters(ParametersRequest::GetProvenance { id, path }),
+                    |response| match response {
+                        Response::Provenance(result) => Ok(result),
+                        response => Err(response),
+                    },
+                    response_sender,
+                    &id_tracker,
+                    &responder,
+                    &mut requester,
+                )
+                .await
+            }
+            Message::StoreParameters {
+                paths,
+                response_sender,
+            } => {
+                forward_response(
+                    |id| Request::Parameters(ParametersRequest::StoreParameters { id, paths }),
+                    |response| match response {
+                        Response::StoreParameters(result) => Ok(result),
+                        response => Err(response),
+                    },
+                    response_sender,
+                    &id_tracker,
+                    &responder,
+                    &mut requester,
+                )
+                .await
+            }
+            Message::GetMetadata { response_sender } => {
+                forward_response(
+                    |id| Request::Parameters(ParametersRequest::GetMetadata { id }),
+                    |response| match response {
+                        Response::ParameterMetadata(metadata) => Ok(Ok(metadata)),
+                        response => Err(response),
+                    },
+                    response_sender,
+                    &id_tracker,
+                    &responder,
+                    &mut requester,
+                )
+                .await
+            }
         }
     }
     info!("Finished manager");
@@ -261,6 +407,37 @@ async fn update_parameter_value(
     Ok(())
 }
 
+async fn send_command(
+    command: impl FnOnce(usize) -> InjectCommandRequest,
+    id_tracker: &mpsc::Sender<id_tracker::Message>,
+    responder: &mpsc::Sender<responder::Message>,
+    requester: &mpsc::Sender<Request>,
+) -> Result<()> {
+    let message_id = get_message_id(id_tracker).await;
+    let (response_sender, response_receiver) = oneshot::channel();
+    responder
+        .send(responder::Message::Await {
+            id: message_id,
+            response_sender,
+        })
+        .await?;
+    requester
+        .send(Request::InjectCommand(command(message_id)))
+        .await?;
+    spawn(async move {
+        let response = response_receiver.await.unwrap();
+        match response {
+            Response::InjectCommand(Ok(_)) => {}
+            Response::InjectCommand(Err(error)) => {
+                error!("Failed to send command: {}", error)
+            }
+            response => error!("unexpected response: {response:?}"),
+        };
+    });
+
+    Ok(())
+}
+
 async fn add_subscription(
     manager: &mut SubscriptionManager,
     uuid: Uuid,
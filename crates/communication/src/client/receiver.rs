@@ -1,6 +1,7 @@
 use bincode::deserialize;
 use futures_util::{stream::SplitStream, StreamExt};
 use log::{debug, error, info};
+use rmp_serde::from_slice;
 use serde_json::from_str;
 use tokio::{net::TcpStream, sync::mpsc::Sender};
 use tokio_tungstenite::{tungstenite, MaybeTlsStream, WebSocketStream};
@@ -11,8 +12,8 @@ use crate::{
         responder::{Message, Response},
     },
     messages::{
-        BinaryOutputsResponse, BinaryResponse, ParametersResponse, TextualOutputsResponse,
-        TextualResponse,
+        BinaryOutputsResponse, BinaryResponse, EncodedResponse, Encoding, InjectCommandResponse,
+        ParametersResponse, TextualOutputsResponse, TextualResponse,
     },
 };
 
@@ -25,6 +26,7 @@ pub async fn receiver(
     parameter_subscription_manager: Sender<parameter_subscription_manager::Message>,
     connector: Sender<connector::Message>,
 ) {
+    let mut encoding = Encoding::Json;
     while let Some(message) = reader.next().await {
         debug!("Receiver got message: {message:?}");
         match message {
@@ -37,91 +39,67 @@ pub async fn receiver(
                             continue;
                         }
                     };
-                    match message {
-                        TextualResponse::Outputs(outputs_message) => match outputs_message {
-                            TextualOutputsResponse::GetFields { id, fields } => {
-                                respond(&responder, id, Response::Fields(fields)).await
-                            }
-                            TextualOutputsResponse::GetNext { id: _, result: _ } => todo!(),
-                            TextualOutputsResponse::Subscribe { id, result } => {
-                                respond(&responder, id, Response::Subscribe(result)).await
-                            }
-                            TextualOutputsResponse::Unsubscribe { id, result } => {
-                                respond(&responder, id, Response::Unsubscribe(result)).await
-                            }
-                            TextualOutputsResponse::SubscribedData { items } => {
-                                if let Err(error) = output_subscription_manager
-                                    .send(output_subscription_manager::Message::Update { items })
-                                    .await
-                                {
-                                    error!("{error}");
-                                }
-                            }
-                        },
-                        TextualResponse::Parameters(parameters_message) => match parameters_message
-                        {
-                            ParametersResponse::GetFields { id, fields } => {
-                                respond(&responder, id, Response::ParameterFields(fields)).await
-                            }
-                            ParametersResponse::Subscribe { id, result } => {
-                                respond(&responder, id, Response::Subscribe(result)).await
-                            }
-                            ParametersResponse::Unsubscribe { id, result } => {
-                                respond(&responder, id, Response::Unsubscribe(result)).await
-                            }
-                            ParametersResponse::SubscribedData {
-                                subscription_id,
-                                data,
-                            } => {
-                                if let Err(error) = parameter_subscription_manager
-                                    .send(parameter_subscription_manager::Message::Update {
-                                        subscription_id,
-                                        data,
-                                    })
-                                    .await
-                                {
-                                    error!("{error}");
-                                }
-                            }
-                            ParametersResponse::Update { id, result } => {
-                                respond(&responder, id, Response::Update(result)).await
-                            }
-                            ParametersResponse::GetCurrent { id: _, result: _ } => todo!(),
-                            ParametersResponse::LoadFromDisk { id: _, result: _ } => todo!(),
-                            ParametersResponse::StoreToDisk { id: _, result: _ } => todo!(),
-                        },
-                        message => todo!("unimplemented message {message:?}"),
-                    }
+                    handle_textual_response(
+                        message,
+                        &mut encoding,
+                        &responder,
+                        &output_subscription_manager,
+                        &parameter_subscription_manager,
+                    )
+                    .await;
                 }
                 tungstenite::Message::Close(close_frame) => {
                     info!("closed: {close_frame:?}");
+                    output_subscription_manager
+                        .send(output_subscription_manager::Message::Disconnect)
+                        .await
+                        .unwrap();
+                    connector
+                        .send(connector::Message::ConnectionFailed {
+                            info: "Peer closed connection".to_string(),
+                        })
+                        .await
+                        .unwrap();
                     break;
                 }
-                tungstenite::Message::Binary(data) => {
-                    let response = match deserialize::<BinaryResponse>(&data) {
-                        Ok(payload) => payload,
-                        Err(error) => {
-                            error!("Failed to deserialize binary message content: {error:?}");
-                            continue;
-                        }
-                    };
-                    let message = match response {
-                        BinaryResponse::Outputs(binary_output_response) => {
-                            match binary_output_response {
-                                BinaryOutputsResponse::GetNext {
-                                    reference_id: _,
-                                    data: _,
-                                } => todo!(),
-                                BinaryOutputsResponse::SubscribedData { referenced_items } => {
-                                    output_subscription_manager::Message::UpdateBinary {
-                                        referenced_items,
-                                    }
-                                }
+                tungstenite::Message::Binary(data) => match encoding {
+                    Encoding::Json => {
+                        let response = match deserialize::<BinaryResponse>(&data) {
+                            Ok(payload) => payload,
+                            Err(error) => {
+                                error!("Failed to deserialize binary message content: {error:?}");
+                                continue;
+                            }
+                        };
+                        handle_binary_response(response, &output_subscription_manager).await;
+                    }
+                    Encoding::MessagePack => {
+                        let response = match from_slice::<EncodedResponse>(&data) {
+                            Ok(payload) => payload,
+                            Err(error) => {
+                                error!(
+                                    "Failed to deserialize MessagePack message content: {error:?}"
+                                );
+                                continue;
+                            }
+                        };
+                        match response {
+                            EncodedResponse::Textual(message) => {
+                                handle_textual_response(
+                                    message,
+                                    &mut encoding,
+                                    &responder,
+                                    &output_subscription_manager,
+                                    &parameter_subscription_manager,
+                                )
+                                .await
+                            }
+                            EncodedResponse::Binary(response) => {
+                                handle_binary_response(response, &output_subscription_manager).await
                             }
                         }
-                    };
-                    output_subscription_manager.send(message).await.unwrap();
-                }
+                    }
+                },
                 _ => {
                     error!("Got unsupported message type from socket");
                     break;
@@ -144,6 +122,109 @@ pub async fn receiver(
     }
 }
 
+async fn handle_textual_response(
+    message: TextualResponse,
+    encoding: &mut Encoding,
+    responder: &Sender<Message>,
+    output_subscription_manager: &Sender<output_subscription_manager::Message>,
+    parameter_subscription_manager: &Sender<parameter_subscription_manager::Message>,
+) {
+    match message {
+        TextualResponse::Outputs(outputs_message) => match outputs_message {
+            TextualOutputsResponse::GetFields { id, fields } => {
+                respond(responder, id, Response::Fields(fields)).await
+            }
+            TextualOutputsResponse::GetMetadata { id, metadata } => {
+                respond(responder, id, Response::OutputMetadata(metadata)).await
+            }
+            TextualOutputsResponse::GetNext { id: _, result: _ } => todo!(),
+            TextualOutputsResponse::Subscribe { id, result } => {
+                respond(responder, id, Response::Subscribe(result)).await
+            }
+            TextualOutputsResponse::Unsubscribe { id, result } => {
+                respond(responder, id, Response::Unsubscribe(result)).await
+            }
+            TextualOutputsResponse::SubscribedData { items } => {
+                if let Err(error) = output_subscription_manager
+                    .send(output_subscription_manager::Message::Update { items })
+                    .await
+                {
+                    error!("{error}");
+                }
+            }
+        },
+        TextualResponse::Parameters(parameters_message) => match parameters_message {
+            ParametersResponse::GetFields { id, fields } => {
+                respond(responder, id, Response::ParameterFields(fields)).await
+            }
+            ParametersResponse::Subscribe { id, result } => {
+                respond(responder, id, Response::Subscribe(result)).await
+            }
+            ParametersResponse::Unsubscribe { id, result } => {
+                respond(responder, id, Response::Unsubscribe(result)).await
+            }
+            ParametersResponse::SubscribedData {
+                subscription_id,
+                data,
+            } => {
+                if let Err(error) = parameter_subscription_manager
+                    .send(parameter_subscription_manager::Message::Update {
+                        subscription_id,
+                        data,
+                    })
+                    .await
+                {
+                    error!("{error}");
+                }
+            }
+            ParametersResponse::Update { id, result } => {
+                respond(responder, id, Response::Update(result)).await
+            }
+            ParametersResponse::GetCurrent { id: _, result: _ } => todo!(),
+            ParametersResponse::LoadFromDisk { id: _, result: _ } => todo!(),
+            ParametersResponse::StoreToDisk { id: _, result: _ } => todo!(),
+            ParametersResponse::GetProvenance { id, result } => {
+                respond(responder, id, Response::Provenance(result)).await
+            }
+            ParametersResponse::StoreParameters { id, result } => {
+                respond(responder, id, Response::StoreParameters(result)).await
+            }
+            ParametersResponse::GetMetadata { id, metadata } => {
+                respond(responder, id, Response::ParameterMetadata(metadata)).await
+            }
+        },
+        TextualResponse::InjectCommand(
+            InjectCommandResponse::Inject { id, result }
+            | InjectCommandResponse::Clear { id, result }
+            | InjectCommandResponse::RunMotionTest { id, result }
+            | InjectCommandResponse::Teleoperate { id, result },
+        ) => respond(responder, id, Response::InjectCommand(result)).await,
+        TextualResponse::EncodingChanged(new_encoding) => {
+            info!("Switched encoding to {new_encoding:?}");
+            *encoding = new_encoding;
+        }
+        message => todo!("unimplemented message {message:?}"),
+    }
+}
+
+async fn handle_binary_response(
+    response: BinaryResponse,
+    output_subscription_manager: &Sender<output_subscription_manager::Message>,
+) {
+    let message = match response {
+        BinaryResponse::Outputs(binary_output_response) => match binary_output_response {
+            BinaryOutputsResponse::GetNext {
+                reference_id: _,
+                data: _,
+            } => todo!(),
+            BinaryOutputsResponse::SubscribedData { referenced_items } => {
+                output_subscription_manager::Message::UpdateBinary { referenced_items }
+            }
+        },
+    };
+    output_subscription_manager.send(message).await.unwrap();
+}
+
 async fn respond(responder: &Sender<responder::Message>, id: usize, response: responder::Response) {
     if let Err(error) = responder.send(Message::Respond { id, response }).await {
         error!("{error}");
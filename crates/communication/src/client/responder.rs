@@ -1,9 +1,17 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
+use color_eyre::Result;
 use log::{debug, error};
-use tokio::sync::{mpsc, oneshot};
+use parameters::provenance::Source;
+use tokio::{
+    spawn,
+    sync::{mpsc, oneshot},
+};
 
-use crate::messages::{Fields, Path, Reason};
+use crate::{
+    client::id_tracker::{self, get_message_id},
+    messages::{CyclerInstance, Fields, Metadata, Path, Reason, Request},
+};
 
 #[derive(Debug)]
 pub enum Message {
@@ -20,7 +28,12 @@ pub enum Message {
 #[derive(Debug)]
 pub enum Response {
     Fields(Fields),
+    InjectCommand(Result<(), Reason>),
+    OutputMetadata(BTreeMap<CyclerInstance, Metadata>),
+    ParameterMetadata(Metadata),
     ParameterFields(BTreeSet<Path>),
+    Provenance(Result<BTreeMap<Path, Source>, Reason>),
+    StoreParameters(Result<(), Reason>),
     Subscribe(Result<(), Reason>),
     Unsubscribe(Result<(), Reason>),
     Update(Result<(), Reason>),
@@ -48,3 +61,57 @@ pub async fn responder(mut receiver: mpsc::Receiver<Message>) {
         }
     }
 }
+
+/// Sends the request and forwards its result to the `response_sender` once the response arrives
+///
+/// `extract` takes the result out of the expected response and hands back any other response. If
+/// the request cannot be sent, the connection is considered gone and the `requester` is reset.
+pub async fn forward_response<T: Send + 'static>(
+    request: impl FnOnce(usize) -> Request,
+    extract: impl FnOnce(Response) -> Result<Result<T, Reason>, Response> + Send + 'static,
+    response_sender: oneshot::Sender<Result<T, Reason>>,
+    id_tracker: &mpsc::Sender<id_tracker::Message>,
+    responder: &mpsc::Sender<Message>,
+    requester: &mut Option<mpsc::Sender<Request>>,
+) {
+    let Some(connected_requester) = requester.as_ref() else {
+        send_result(response_sender, Err("not connected".to_string()));
+        return;
+    };
+    let message_id = get_message_id(id_tracker).await;
+    let (awaited_response_sender, awaited_response_receiver) = oneshot::channel();
+    let sent: Result<()> = async {
+        responder
+            .send(Message::Await {
+                id: message_id,
+                response_sender: awaited_response_sender,
+            })
+            .await?;
+        connected_requester.send(request(message_id)).await?;
+        Ok(())
+    }
+    .await;
+    if let Err(error) = sent {
+        error!("{error}");
+        *requester = None;
+        send_result(
+            response_sender,
+            Err(format!("failed to send request: {error}")),
+        );
+        return;
+    }
+    spawn(async move {
+        let result = match awaited_response_receiver.await {
+            Ok(response) => extract(response)
+                .unwrap_or_else(|response| Err(format!("unexpected response: {response:?}"))),
+            Err(error) => Err(format!("no response: {error}")),
+        };
+        send_result(response_sender, result);
+    });
+}
+
+fn send_result<T>(response_sender: oneshot::Sender<Result<T, Reason>>, result: Result<T, Reason>) {
+    if response_sender.send(result).is_err() {
+        error!("Failed to send to response channel");
+    }
+}
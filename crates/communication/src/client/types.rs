@@ -35,6 +35,9 @@ impl FromStr for CyclerOutput {
             "additional" | "additional_outputs" => Output::Additional {
                 path: path.to_string(),
             },
+            "timings" => Output::Timings {
+                path: path.to_string(),
+            },
             _ => bail!("unknown output '{output_str}'"),
         };
         Ok(CyclerOutput { cycler, output })
@@ -79,6 +82,7 @@ impl FromStr for Cycler {
 pub enum Output {
     Main { path: String },
     Additional { path: String },
+    Timings { path: String },
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,47 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{from_slice, Value};
+use tokio::{
+    net::UdpSocket,
+    time::{timeout_at, Instant},
+};
+
+pub const DISCOVERY_PORT: u16 = 4243;
+
+/// Periodically broadcast by the communication server of every robot
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Announcement {
+    pub hostname: String,
+    pub body_id: String,
+    pub head_id: String,
+    /// Latest values of announced outputs, e.g. `Control.main_outputs.primary_state`
+    pub outputs: BTreeMap<String, Value>,
+}
+
+/// Collects the announcements of all robots received within the listen duration
+pub async fn discover_robots(
+    listen_duration: Duration,
+) -> io::Result<BTreeMap<IpAddr, Announcement>> {
+    let socket = UdpSocket::bind(SocketAddr::new(
+        Ipv4Addr::UNSPECIFIED.into(),
+        DISCOVERY_PORT,
+    ))
+    .await?;
+    let deadline = Instant::now() + listen_duration;
+    let mut robots = BTreeMap::new();
+    let mut buffer = vec![0; u16::MAX as usize];
+    while let Ok(result) = timeout_at(deadline, socket.recv_from(&mut buffer)).await {
+        let (length, address) = result?;
+        // datagrams of other applications on the same port are ignored
+        if let Ok(announcement) = from_slice(&buffer[..length]) {
+            robots.insert(address.ip(), announcement);
+        }
+    }
+    Ok(robots)
+}
@@ -1,4 +1,5 @@
 pub mod client;
+pub mod discovery;
 pub mod messages;
 #[cfg(feature = "server")]
 pub mod server;
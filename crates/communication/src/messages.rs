@@ -1,6 +1,10 @@
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    time::Duration,
+};
 
-use parameters::directory::Scope;
+use logging::LogEntry;
+use parameters::{directory::Scope, provenance::Source};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
@@ -10,26 +14,41 @@ pub type Path = String;
 pub type Reason = String;
 pub type Type = String;
 pub type Fields = BTreeMap<CyclerInstance, BTreeSet<Path>>;
+pub type Metadata = BTreeMap<Path, FieldMetadata>;
+
+/// Human-readable description and unit of a field as declared in the node contexts
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FieldMetadata {
+    pub doc: Option<String>,
+    pub unit: Option<String>,
+}
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Request {
+    InjectCommand(InjectCommandRequest),
     Injections(InjectionsRequest),
+    Logs(LogsRequest),
     Outputs(OutputsRequest),
     Parameters(ParametersRequest),
+    SetEncoding(Encoding),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Response {
     Textual(TextualResponse),
     Binary(BinaryResponse),
+    SetEncoding(Encoding),
     Close { code: CloseCode, reason: Reason },
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum TextualResponse {
+    InjectCommand(InjectCommandResponse),
     Injections(InjectionsResponse),
+    Logs(LogsResponse),
     Outputs(TextualOutputsResponse),
     Parameters(ParametersResponse),
+    EncodingChanged(Encoding),
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -37,6 +56,76 @@ pub enum BinaryResponse {
     Outputs(BinaryOutputsResponse),
 }
 
+/// Wire encoding of server responses, negotiated per connection via `Request::SetEncoding`
+///
+/// With `Json`, textual responses are sent as JSON text frames and binary responses as Bincode
+/// binary frames. With `MessagePack`, all responses are sent as MessagePack binary frames
+/// containing an `EncodedResponse`. Clients not negotiating an encoding stay at `Json`.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Encoding {
+    #[default]
+    Json,
+    MessagePack,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum EncodedResponse {
+    Textual(TextualResponse),
+    Binary(BinaryResponse),
+}
+
+/// Temporarily overrides parameters, e.g. to remote-control the robot from a debug tool
+///
+/// The injected data is never stored to disk. The previous value is restored after the time to
+/// live expired, on `Clear`, or when the parameter is updated regularly.
+///
+/// `RunMotionTest` injects a request for the motion test harness, `motion` is one of the
+/// predefined test motions, e.g. `"StandUp"`. Clearing `motion_test.request` aborts the test.
+/// `Teleoperate` injects the joystick state into `behavior.teleoperation.command`. It has to be
+/// repeated continuously, the robot stops shortly after the last command.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum InjectCommandRequest {
+    Inject {
+        id: usize,
+        path: Path,
+        data: Value,
+        time_to_live: Duration,
+    },
+    Clear {
+        id: usize,
+        path: Path,
+    },
+    RunMotionTest {
+        id: usize,
+        motion: Value,
+        repetitions: usize,
+    },
+    Teleoperate {
+        id: usize,
+        command: Value,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum InjectCommandResponse {
+    Inject {
+        id: usize,
+        result: Result<(), Reason>,
+    },
+    Clear {
+        id: usize,
+        result: Result<(), Reason>,
+    },
+    RunMotionTest {
+        id: usize,
+        result: Result<(), Reason>,
+    },
+    Teleoperate {
+        id: usize,
+        result: Result<(), Reason>,
+    },
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum InjectionsRequest {
     Set {
@@ -65,11 +154,37 @@ pub enum InjectionsResponse {
     },
 }
 
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum LogsRequest {
+    Subscribe { id: usize },
+    Unsubscribe { id: usize, subscription_id: usize },
+    UnsubscribeEverything,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum LogsResponse {
+    Subscribe {
+        id: usize,
+        result: Result<(), Reason>,
+    },
+    Unsubscribe {
+        id: usize,
+        result: Result<(), Reason>,
+    },
+    SubscribedEntry {
+        subscription_id: usize,
+        entry: LogEntry,
+    },
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum OutputsRequest {
     GetFields {
         id: usize,
     },
+    GetMetadata {
+        id: usize,
+    },
     GetNext {
         id: usize,
         cycler_instance: CyclerInstance,
@@ -81,6 +196,13 @@ pub enum OutputsRequest {
         cycler_instance: CyclerInstance,
         path: Path,
         format: Format,
+        #[serde(default)]
+        options: SubscriptionOptions,
+    },
+    /// Responds with the execution durations of the nodes as `GetNext` response
+    GetTimings {
+        id: usize,
+        cycler_instance: CyclerInstance,
     },
     Unsubscribe {
         id: usize,
@@ -95,6 +217,11 @@ pub enum TextualOutputsResponse {
         id: usize,
         fields: Fields,
     },
+    /// Descriptions and units of all documented outputs
+    GetMetadata {
+        id: usize,
+        metadata: BTreeMap<CyclerInstance, Metadata>,
+    },
     GetNext {
         id: usize,
         result: Result<TextualDataOrBinaryReference, Reason>,
@@ -122,9 +249,11 @@ pub enum TextualDataOrBinaryReference {
 pub enum BinaryOutputsResponse {
     GetNext {
         reference_id: usize,
+        #[serde(with = "serde_bytes")]
         data: Vec<u8>,
     },
     SubscribedData {
+        #[serde(with = "referenced_bytes")]
         referenced_items: HashMap<usize, Vec<u8>>,
     },
 }
@@ -139,6 +268,9 @@ pub enum ParametersRequest {
     Update { id: usize, path: Path, data: Value },
     LoadFromDisk { id: usize },
     StoreToDisk { id: usize, scope: Scope, path: Path },
+    GetProvenance { id: usize, path: Path },
+    StoreParameters { id: usize, paths: Vec<Path> },
+    GetMetadata { id: usize },
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -175,6 +307,20 @@ pub enum ParametersResponse {
         id: usize,
         result: Result<(), Reason>,
     },
+    /// Origin of every parameter value at or below the requested path
+    GetProvenance {
+        id: usize,
+        result: Result<BTreeMap<Path, Source>, Reason>,
+    },
+    StoreParameters {
+        id: usize,
+        result: Result<(), Reason>,
+    },
+    /// Descriptions and units of all documented parameters
+    GetMetadata {
+        id: usize,
+        metadata: Metadata,
+    },
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
@@ -182,3 +328,82 @@ pub enum Format {
     Textual,
     Binary,
 }
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct SubscriptionOptions {
+    /// Updates arriving earlier than this interval after the last sent update are dropped
+    pub minimum_interval: Option<Duration>,
+    /// Updates are only sent if the serialized data differs from the last sent update
+    pub only_on_change: bool,
+}
+
+mod referenced_bytes {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde_bytes::{ByteBuf, Bytes};
+
+    pub fn serialize<S>(items: &HashMap<usize, Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(
+            items
+                .iter()
+                .map(|(reference_id, data)| (reference_id, Bytes::new(data))),
+        )
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<HashMap<usize, Vec<u8>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let items = HashMap::<usize, ByteBuf>::deserialize(deserializer)?;
+        Ok(items
+            .into_iter()
+            .map(|(reference_id, data)| (reference_id, data.into_vec()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bincode::{deserialize, serialize};
+    use rmp_serde::{from_slice, to_vec_named};
+
+    use super::*;
+
+    #[test]
+    fn binary_responses_round_trip_in_all_encodings() {
+        let response = BinaryResponse::Outputs(BinaryOutputsResponse::SubscribedData {
+            referenced_items: HashMap::from([(42, vec![0, 1, 128, 255])]),
+        });
+
+        let bincode = serialize(&response).unwrap();
+        assert_eq!(deserialize::<BinaryResponse>(&bincode).unwrap(), response);
+
+        let message_pack = to_vec_named(&EncodedResponse::Binary(response.clone())).unwrap();
+        assert_eq!(
+            from_slice::<EncodedResponse>(&message_pack).unwrap(),
+            EncodedResponse::Binary(response)
+        );
+    }
+
+    #[test]
+    fn textual_responses_round_trip_in_message_pack() {
+        let response = TextualResponse::Outputs(TextualOutputsResponse::SubscribedData {
+            items: HashMap::from([(
+                1,
+                TextualDataOrBinaryReference::TextualData {
+                    data: Value::from(vec![1.5, -2.0]),
+                },
+            )]),
+        });
+
+        let message_pack = to_vec_named(&EncodedResponse::Textual(response.clone())).unwrap();
+        assert_eq!(
+            from_slice::<EncodedResponse>(&message_pack).unwrap(),
+            EncodedResponse::Textual(response)
+        );
+    }
+}
@@ -12,12 +12,13 @@ use tokio::{
 };
 use tokio_util::sync::CancellationToken;
 
-use crate::messages::ParametersRequest;
+use crate::messages::{LogsRequest, ParametersRequest};
 
 use super::{
     client_request::ClientRequest,
     connection::{connection, ConnectionError},
     outputs,
+    parameters::StorageRequest,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -34,7 +35,10 @@ pub fn acceptor(
     addresses: impl ToSocketAddrs + Send + Sync + 'static,
     keep_running: CancellationToken,
     outputs_sender: Sender<outputs::Request>,
+    logs_sender: Sender<ClientRequest<LogsRequest>>,
     parameters_sender: Sender<ClientRequest<ParametersRequest>>,
+    parameters_storage_sender: Sender<StorageRequest>,
+    privileged_token: Option<String>,
 ) -> JoinHandle<Result<(), AcceptError>> {
     let next_client_id = AtomicUsize::default();
     spawn(async move {
@@ -56,7 +60,10 @@ pub fn acceptor(
                 keep_running.clone(),
                 error_sender.clone(),
                 outputs_sender.clone(),
+                logs_sender.clone(),
                 parameters_sender.clone(),
+                parameters_storage_sender.clone(),
+                privileged_token.clone(),
                 client_id,
             );
         }
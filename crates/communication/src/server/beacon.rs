@@ -0,0 +1,122 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    net::{Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+use log::debug;
+use serde_json::to_vec;
+use tokio::{
+    net::UdpSocket,
+    select, spawn,
+    sync::mpsc::{channel, Sender},
+    task::JoinHandle,
+    time::interval,
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    discovery::{Announcement, DISCOVERY_PORT},
+    messages::{
+        Format, OutputsRequest, Response, TextualDataOrBinaryReference, TextualOutputsResponse,
+        TextualResponse,
+    },
+};
+
+use super::{client::Client, client_request::ClientRequest, outputs};
+
+const BEACON_INTERVAL: Duration = Duration::from_secs(1);
+/// Client ID of the beacon, chosen to not collide with IDs of connected clients
+const BEACON_CLIENT_ID: usize = usize::MAX;
+const ANNOUNCED_OUTPUTS: &[(&str, &str)] = &[
+    ("Control", "main_outputs.primary_state"),
+    ("Control", "main_outputs.sensor_data.battery"),
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum BeaconError {
+    #[error("failed to bind beacon socket")]
+    SocketNotBound(#[source] io::Error),
+    #[error("failed to enable broadcast on beacon socket")]
+    BroadcastNotEnabled(#[source] io::Error),
+}
+
+/// Periodically broadcasts an `Announcement` of this robot for discovery by clients
+pub fn beacon(
+    outputs_sender: Sender<outputs::Request>,
+    body_id: String,
+    head_id: String,
+    keep_running: CancellationToken,
+) -> JoinHandle<Result<(), BeaconError>> {
+    spawn(async move {
+        let socket = UdpSocket::bind(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0))
+            .await
+            .map_err(BeaconError::SocketNotBound)?;
+        socket
+            .set_broadcast(true)
+            .map_err(BeaconError::BroadcastNotEnabled)?;
+        let broadcast_address = SocketAddr::new(Ipv4Addr::BROADCAST.into(), DISCOVERY_PORT);
+
+        let hostname = hostname::get()
+            .ok()
+            .and_then(|hostname| hostname.into_string().ok())
+            .unwrap_or_else(|| body_id.clone());
+        let mut announcement = Announcement {
+            hostname,
+            body_id,
+            head_id,
+            outputs: BTreeMap::new(),
+        };
+
+        let (response_sender, mut response_receiver) = channel(16);
+        let client = Client {
+            id: BEACON_CLIENT_ID,
+            response_sender,
+        };
+        let mut ticks = interval(BEACON_INTERVAL);
+        loop {
+            select! {
+                _ = ticks.tick() => {
+                    for (id, (cycler_instance, path)) in ANNOUNCED_OUTPUTS.iter().enumerate() {
+                        let request = outputs::Request::ClientRequest(ClientRequest {
+                            request: OutputsRequest::GetNext {
+                                id,
+                                cycler_instance: cycler_instance.to_string(),
+                                path: path.to_string(),
+                                format: Format::Textual,
+                            },
+                            client: client.clone(),
+                        });
+                        if outputs_sender.send(request).await.is_err() {
+                            debug!("outputs router terminated before beacon");
+                        }
+                    }
+                    let message = to_vec(&announcement).expect("announcement should be serializable");
+                    if let Err(error) = socket.send_to(&message, broadcast_address).await {
+                        debug!("failed to broadcast announcement: {error:?}");
+                    }
+                }
+                response = response_receiver.recv() => {
+                    let Some(Response::Textual(TextualResponse::Outputs(
+                        TextualOutputsResponse::GetNext {
+                            id,
+                            result: Ok(TextualDataOrBinaryReference::TextualData { data }),
+                        },
+                    ))) = response else {
+                        continue;
+                    };
+                    if let Some((cycler_instance, path)) = ANNOUNCED_OUTPUTS.get(id) {
+                        announcement
+                            .outputs
+                            .insert(format!("{cycler_instance}.{path}"), data);
+                    }
+                }
+                _ = keep_running.cancelled() => {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    })
+}
@@ -1,18 +1,32 @@
-use std::{io, net::SocketAddr};
+use std::{collections::VecDeque, io, net::SocketAddr};
 
 use futures_util::StreamExt;
 use log::error;
 use tokio::{
     net::TcpStream,
     select, spawn,
-    sync::mpsc::{channel, Sender, UnboundedSender},
+    sync::mpsc::{channel, Receiver, Sender, UnboundedSender},
+};
+use tokio_tungstenite::{
+    accept_hdr_async,
+    tungstenite::{
+        handshake::server::{
+            ErrorResponse, Request as HandshakeRequest, Response as HandshakeResponse,
+        },
+        protocol::frame::coding::CloseCode,
+    },
 };
-use tokio_tungstenite::accept_async;
 use tokio_util::sync::CancellationToken;
 
-use crate::messages::ParametersRequest;
+use crate::messages::{LogsRequest, ParametersRequest, Response};
+
+use super::{
+    client_request::ClientRequest, outputs, parameters::StorageRequest, permission::Permission,
+    receiver::receiver, sender::sender,
+};
 
-use super::{client_request::ClientRequest, outputs, receiver::receiver, sender::sender};
+/// Responses which are not yet written to a slow client before it gets disconnected
+const MAXIMUM_QUEUED_RESPONSES: usize = 128;
 
 #[derive(Debug, thiserror::Error)]
 pub enum ConnectionError {
@@ -36,22 +50,30 @@ pub enum ReceiverOrSenderError {
     BincodeNotSerialized(bincode::Error),
     #[error("got unexpected binary message")]
     GotUnexpectedBinaryMessage,
+    #[error("client did not keep up with the responses and was disconnected")]
+    ClientTooSlow,
     #[error("failed to deserialize JSON")]
     JsonNotDeserialized(serde_json::Error),
     #[error("failed to serialize JSON")]
     JsonNotSerialized(serde_json::Error),
+    #[error("failed to serialize MessagePack")]
+    MessagePackNotSerialized(rmp_serde::encode::Error),
     #[error("failed to read WebSocket message")]
     WebSocketMessageNotRead(tokio_tungstenite::tungstenite::Error),
     #[error("failed to write WebSocket message")]
     WebSocketMessageNotWritten(tokio_tungstenite::tungstenite::Error),
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn connection(
     stream: TcpStream,
     keep_running: CancellationToken,
     connection_error_sender: UnboundedSender<ConnectionError>,
     outputs_sender: Sender<outputs::Request>,
+    logs_sender: Sender<ClientRequest<LogsRequest>>,
     parameters_sender: Sender<ClientRequest<ParametersRequest>>,
+    parameters_storage_sender: Sender<StorageRequest>,
+    privileged_token: Option<String>,
     client_id: usize,
 ) {
     spawn(async move {
@@ -65,8 +87,15 @@ pub fn connection(
             }
         };
 
+        let mut permission = Permission::ReadOnly;
+        let remember_permission = |request: &HandshakeRequest,
+                                   response: HandshakeResponse|
+         -> Result<HandshakeResponse, ErrorResponse> {
+            permission = Permission::of_handshake(request, privileged_token.as_deref());
+            Ok(response)
+        };
         let websocket_stream = select! {
-            result = accept_async(stream) => match result {
+            result = accept_hdr_async(stream, remember_permission) => match result {
                 Ok(websocket_stream) => websocket_stream,
                 Err(source) => {
                     connection_error_sender
@@ -83,6 +112,7 @@ pub fn connection(
         let (receiver_or_sender_error_sender, mut receiver_or_sender_error_receiver) = channel(1);
         let keep_only_self_running = CancellationToken::new();
         let (response_sender, response_receiver) = channel(1);
+        let (queued_response_sender, queued_response_receiver) = channel(1);
 
         spawn(receiver(
             reader,
@@ -90,16 +120,26 @@ pub fn connection(
             keep_running,
             keep_only_self_running.clone(),
             client_id,
+            permission,
             response_sender,
             outputs_sender,
+            logs_sender,
             parameters_sender,
+            parameters_storage_sender,
+        ));
+
+        spawn(response_queue(
+            response_receiver,
+            queued_response_sender,
+            receiver_or_sender_error_sender.clone(),
+            keep_only_self_running.clone(),
         ));
 
         spawn(sender(
             writer,
             receiver_or_sender_error_sender,
             keep_only_self_running,
-            response_receiver,
+            queued_response_receiver,
         ));
 
         while let Some(error) = receiver_or_sender_error_receiver.recv().await {
@@ -113,3 +153,57 @@ pub fn connection(
         }
     });
 }
+
+/// Buffers responses between the server tasks and the sender of a client
+///
+/// The server tasks share their responses with all clients, so a client not reading fast enough
+/// must not block them. Instead, the client is disconnected once too many responses are queued.
+async fn response_queue(
+    mut response_receiver: Receiver<Response>,
+    queued_response_sender: Sender<Response>,
+    error_sender: Sender<ReceiverOrSenderError>,
+    keep_only_self_running: CancellationToken,
+) {
+    let mut queue = VecDeque::new();
+    let mut is_too_slow = false;
+    loop {
+        select! {
+            response = response_receiver.recv() => {
+                let Some(response) = response else {
+                    break;
+                };
+                // responses of disconnected clients are dropped until all server tasks hung up
+                if is_too_slow {
+                    continue;
+                }
+                if queue.len() < MAXIMUM_QUEUED_RESPONSES {
+                    queue.push_back(response);
+                    continue;
+                }
+                is_too_slow = true;
+                let error = ReceiverOrSenderError::ClientTooSlow;
+                queue.clear();
+                queue.push_back(Response::Close {
+                    code: CloseCode::Again,
+                    reason: error.to_string(),
+                });
+                error_sender
+                    .send(error)
+                    .await
+                    .expect("receiver should always wait for all senders");
+                keep_only_self_running.cancel();
+            },
+            permit = queued_response_sender.reserve(), if !queue.is_empty() => {
+                let Ok(permit) = permit else {
+                    break;
+                };
+                permit.send(queue.pop_front().expect("queue should not be empty"));
+            },
+        }
+    }
+    for response in queue {
+        if queued_response_sender.send(response).await.is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+
+use futures_util::{stream::FuturesUnordered, StreamExt};
+use logging::LogEntry;
+use tokio::{
+    select, spawn,
+    sync::{broadcast::error::RecvError, mpsc::Receiver},
+    task::JoinHandle,
+};
+
+use crate::{
+    messages::{LogsRequest, LogsResponse, Response, TextualResponse},
+    server::{client::Client, client_request::ClientRequest},
+};
+
+/// Entries of these targets are not forwarded because sending them would log again
+const EXCLUDED_TARGETS: &[&str] = &["communication", "tokio_tungstenite", "tungstenite"];
+
+pub fn logs(mut request_receiver: Receiver<ClientRequest<LogsRequest>>) -> JoinHandle<()> {
+    spawn(async move {
+        let mut entries = logging::subscribe();
+        let mut subscriptions = HashSet::new();
+        loop {
+            select! {
+                request = request_receiver.recv() => {
+                    let Some(request) = request else {
+                        break;
+                    };
+                    handle_request(request, &mut subscriptions).await;
+                },
+                entry = entries.recv() => {
+                    match entry {
+                        Ok(entry) => forward_entry(entry, &subscriptions).await,
+                        // lagging subscribers only miss entries, the log file still contains them
+                        Err(RecvError::Lagged(_)) => {}
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            }
+        }
+    })
+}
+
+async fn handle_request(
+    request: ClientRequest<LogsRequest>,
+    subscriptions: &mut HashSet<(Client, usize)>,
+) {
+    match request.request {
+        LogsRequest::Subscribe { id } => {
+            let result = if subscriptions.insert((request.client.clone(), id)) {
+                Ok(())
+            } else {
+                Err(format!("already subscribed with id {id}"))
+            };
+            respond(request, LogsResponse::Subscribe { id, result }).await;
+        }
+        LogsRequest::Unsubscribe {
+            id,
+            subscription_id,
+        } => {
+            let result = if subscriptions.remove(&(request.client.clone(), subscription_id)) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "never subscribed with subscription id {subscription_id}"
+                ))
+            };
+            respond(request, LogsResponse::Unsubscribe { id, result }).await;
+        }
+        LogsRequest::UnsubscribeEverything => {
+            subscriptions.retain(|(client, _subscription_id)| &request.client != client);
+        }
+    }
+}
+
+async fn respond(request: ClientRequest<LogsRequest>, response: LogsResponse) {
+    request
+        .client
+        .response_sender
+        .send(Response::Textual(TextualResponse::Logs(response)))
+        .await
+        .expect("receiver should always wait for all senders");
+}
+
+async fn forward_entry(entry: LogEntry, subscriptions: &HashSet<(Client, usize)>) {
+    if EXCLUDED_TARGETS
+        .iter()
+        .any(|target| entry.target.starts_with(target))
+    {
+        return;
+    }
+    // failed sends are not logged because each log would emit another entry
+    let _send_results: Vec<_> =
+        FuturesUnordered::from_iter(subscriptions.iter().map(|(client, subscription_id)| {
+            let response =
+                Response::Textual(TextualResponse::Logs(LogsResponse::SubscribedEntry {
+                    subscription_id: *subscription_id,
+                    entry: entry.clone(),
+                }));
+            async move { client.response_sender.send(response).await }
+        }))
+        .collect()
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc::channel;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn terminates_on_request_sender_drop() {
+        let (request_sender, request_receiver) = channel(1);
+        let logs_task = logs(request_receiver);
+
+        drop(request_sender);
+        logs_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn subscribing_twice_with_same_id_fails() {
+        let (request_sender, request_receiver) = channel(1);
+        let logs_task = logs(request_receiver);
+
+        let (response_sender, mut response_receiver) = channel(1);
+        let client = Client {
+            id: 1337,
+            response_sender,
+        };
+        for expected_result in [Ok(()), Err("already subscribed with id 42".to_string())] {
+            request_sender
+                .send(ClientRequest {
+                    request: LogsRequest::Subscribe { id: 42 },
+                    client: client.clone(),
+                })
+                .await
+                .unwrap();
+            assert_eq!(
+                response_receiver.recv().await.unwrap(),
+                Response::Textual(TextualResponse::Logs(LogsResponse::Subscribe {
+                    id: 42,
+                    result: expected_result,
+                })),
+            );
+        }
+
+        drop(request_sender);
+        logs_task.await.unwrap();
+    }
+}
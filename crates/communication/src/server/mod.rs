@@ -1,9 +1,12 @@
 mod acceptor;
+mod beacon;
 mod client;
 mod client_request;
 mod connection;
+mod logs;
 mod outputs;
 pub mod parameters; // TODO: revert to private visibility after behavior simulator is refactored to not access private functionality anymore
+mod permission;
 mod receiver;
 mod runtime;
 mod sender;
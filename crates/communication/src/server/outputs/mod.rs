@@ -1,8 +1,9 @@
 use std::collections::BTreeSet;
 
-use tokio::sync::mpsc::Sender;
+use serde_json::Value;
+use tokio::{sync::mpsc::Sender, time::Instant};
 
-use crate::messages::{Format, OutputsRequest, Path};
+use crate::messages::{Format, Metadata, OutputsRequest, Path, SubscriptionOptions};
 
 use super::client_request::ClientRequest;
 
@@ -15,6 +16,7 @@ pub enum Request {
     RegisterCycler {
         cycler_instance: String,
         fields: BTreeSet<Path>,
+        metadata: Metadata,
         request_sender: Sender<ClientRequest<OutputsRequest>>,
     },
 }
@@ -24,4 +26,36 @@ struct Subscription {
     pub path: Path,
     pub format: Format,
     pub once: bool,
+    pub options: SubscriptionOptions,
+    pub last_update: Option<Instant>,
+    pub last_data: Option<SentData>,
+}
+
+#[derive(Debug, PartialEq)]
+enum SentData {
+    Textual(Value),
+    Binary(Vec<u8>),
+}
+
+impl Subscription {
+    fn is_update_due(&self, now: Instant) -> bool {
+        match (self.options.minimum_interval, self.last_update) {
+            (Some(minimum_interval), Some(last_update)) => {
+                now.duration_since(last_update) >= minimum_interval
+            }
+            _ => true,
+        }
+    }
+
+    /// Remembers the data if the subscription only wants changes and returns whether it changed
+    fn update_data(&mut self, data: SentData) -> bool {
+        if !self.options.only_on_change {
+            return true;
+        }
+        if self.last_data.as_ref() == Some(&data) {
+            return false;
+        }
+        self.last_data = Some(data);
+        true
+    }
 }
@@ -16,21 +16,23 @@ use tokio::{
         Notify,
     },
     task::JoinHandle,
+    time::Instant,
 };
 
 use crate::{
     messages::{
-        BinaryOutputsResponse, BinaryResponse, Format, OutputsRequest, Response,
+        BinaryOutputsResponse, BinaryResponse, Format, Metadata, OutputsRequest, Response,
         TextualDataOrBinaryReference, TextualOutputsResponse, TextualResponse,
     },
     server::{client::Client, client_request::ClientRequest},
 };
 
-use super::{Request, Subscription};
+use super::{Request, SentData, Subscription};
 
 pub fn provider<Outputs>(
     outputs_sender: Sender<Request>,
     cycler_instance: &'static str,
+    metadata: Metadata,
     outputs_changed: Arc<Notify>,
     outputs_reader: Reader<Outputs>,
     subscribed_outputs_writer: Writer<HashSet<String>>,
@@ -45,6 +47,7 @@ where
             .send(Request::RegisterCycler {
                 cycler_instance: cycler_instance.to_string(),
                 fields: Outputs::get_fields(),
+                metadata,
                 request_sender,
             })
             .await
@@ -96,10 +99,20 @@ where
     Outputs: SerializeHierarchy,
 {
     let is_get_next = matches!(request.request, OutputsRequest::GetNext { .. });
+    let options = match request.request {
+        OutputsRequest::Subscribe { options, .. } => options,
+        _ => Default::default(),
+    };
     match request.request {
         OutputsRequest::GetFields { .. } => {
             panic!("GetFields should be answered by output router");
         }
+        OutputsRequest::GetMetadata { .. } => {
+            panic!("GetMetadata should be answered by output router");
+        }
+        OutputsRequest::GetTimings { .. } => {
+            panic!("GetTimings should be converted to GetNext by output router");
+        }
         OutputsRequest::GetNext {
             id,
             cycler_instance: received_cycler_instance,
@@ -111,6 +124,7 @@ where
             cycler_instance: received_cycler_instance,
             path,
             format,
+            ..
         } => {
             assert_eq!(cycler_instance, received_cycler_instance);
             if Outputs::exists(&path) {
@@ -142,6 +156,9 @@ where
                             path,
                             format,
                             once: is_get_next,
+                            options,
+                            last_update: None,
+                            last_data: None,
                         });
                         if !is_get_next {
                             request
@@ -243,9 +260,13 @@ async fn handle_notified_output(
     let mut binary_get_next_items = HashMap::new();
     let mut binary_subscribed_items: HashMap<Client, HashMap<usize, Vec<u8>>> = HashMap::new();
     let mut subscriptions_state = SubscriptionsState::Unchanged;
+    let now = Instant::now();
     {
         let output = outputs_reader.next();
         subscriptions.retain(|(client, subscription_id), subscription| {
+            if !subscription.once && !subscription.is_update_due(now) {
+                return true;
+            }
             let data = match subscription.format {
                 Format::Textual => {
                     let data = match output
@@ -257,6 +278,9 @@ async fn handle_notified_output(
                             return true;
                         }
                     };
+                    if !subscription.update_data(SentData::Textual(data.clone())) {
+                        return true;
+                    }
                     TextualDataOrBinaryReference::TextualData { data }
                 }
                 Format::Binary => {
@@ -269,6 +293,9 @@ async fn handle_notified_output(
                         error!("failed to serialize {:?}: {error:?}", subscription.path);
                         return true;
                     }
+                    if !subscription.update_data(SentData::Binary(data.clone())) {
+                        return true;
+                    }
                     let reference_id = next_binary_reference_id.0;
                     *next_binary_reference_id += 1;
                     if subscription.once {
@@ -285,6 +312,7 @@ async fn handle_notified_output(
                     TextualDataOrBinaryReference::BinaryReference { reference_id }
                 }
             };
+            subscription.last_update = Some(now);
             if subscription.once {
                 textual_get_next_items.insert((client.clone(), *subscription_id), data);
                 subscriptions_state = SubscriptionsState::Changed;
@@ -364,7 +392,7 @@ mod tests {
     use serialize_hierarchy::Error;
     use tokio::{sync::mpsc::error::TryRecvError, task::yield_now, time::timeout};
 
-    use crate::messages::Format;
+    use crate::messages::{Format, SubscriptionOptions};
 
     use super::*;
 
@@ -432,6 +460,7 @@ mod tests {
         let join_handle = provider(
             outputs_sender,
             cycler_instance,
+            Default::default(),
             outputs_changed,
             output,
             subscribed_outputs_writer,
@@ -440,7 +469,7 @@ mod tests {
             let Some(request) = outputs_receiver.recv().await else {
                 panic!("expected request");
             };
-            let Request::RegisterCycler { cycler_instance: cycler_instance_to_register, fields, request_sender } = request else {
+            let Request::RegisterCycler { cycler_instance: cycler_instance_to_register, fields, request_sender, .. } = request else {
                 panic!("expected Request::RegisterCycler");
             };
             assert_eq!(cycler_instance, cycler_instance_to_register);
@@ -534,6 +563,7 @@ mod tests {
                     cycler_instance: cycler_instance.clone(),
                     path: path.clone(),
                     format,
+                    options: Default::default(),
                 },
                 client: Client {
                     id: client_id,
@@ -571,6 +601,7 @@ mod tests {
                     cycler_instance,
                     path: path.clone(),
                     format,
+                    options: Default::default(),
                 },
                 client: Client {
                     id: client_id,
@@ -635,6 +666,7 @@ mod tests {
                     cycler_instance: cycler_instance.clone(),
                     path: path.clone(),
                     format,
+                    options: Default::default(),
                 },
                 client: Client {
                     id: 1337,
@@ -672,6 +704,7 @@ mod tests {
                     cycler_instance,
                     path: path.clone(),
                     format,
+                    options: Default::default(),
                 },
                 client: Client {
                     id: 7331,
@@ -736,6 +769,7 @@ mod tests {
                     cycler_instance: cycler_instance.clone(),
                     path: path.clone(),
                     format,
+                    options: Default::default(),
                 },
                 client: Client {
                     id: client_id,
@@ -773,6 +807,7 @@ mod tests {
                     cycler_instance,
                     path: path.clone(),
                     format,
+                    options: Default::default(),
                 },
                 client: Client {
                     id: client_id,
@@ -890,6 +925,7 @@ mod tests {
                     cycler_instance: cycler_instance.to_string(),
                     path: path.clone(),
                     format: Format::Textual,
+                    options: Default::default(),
                 },
                 client: Client {
                     id: client_id,
@@ -1014,6 +1050,7 @@ mod tests {
                     cycler_instance: cycler_instance.to_string(),
                     path: path.clone(),
                     format: Format::Textual,
+                    options: Default::default(),
                 },
                 client: Client {
                     id: 1337,
@@ -1130,6 +1167,7 @@ mod tests {
                     cycler_instance: cycler_instance.to_string(),
                     path: path.clone(),
                     format: Format::Textual,
+                    options: Default::default(),
                 },
                 client: Client {
                     id: client_id,
@@ -1257,6 +1295,7 @@ mod tests {
                     cycler_instance: cycler_instance.to_string(),
                     path: path.clone(),
                     format: Format::Binary,
+                    options: Default::default(),
                 },
                 client: Client {
                     id: client_id,
@@ -1389,6 +1428,7 @@ mod tests {
                     cycler_instance: cycler_instance.to_string(),
                     path: path.clone(),
                     format: Format::Textual,
+                    options: Default::default(),
                 },
                 client: Client {
                     id: client_id,
@@ -1427,6 +1467,7 @@ mod tests {
                     cycler_instance: cycler_instance.to_string(),
                     path: path.clone(),
                     format: Format::Textual,
+                    options: Default::default(),
                 },
                 client: Client {
                     id: client_id,
@@ -1743,4 +1784,83 @@ mod tests {
         drop(request_sender);
         provider_task.await.unwrap();
     }
+
+    #[tokio::test]
+    async fn only_on_change_subscription_skips_unchanged_data() {
+        let cycler_instance = "CyclerInstance";
+        let path = "a.b.c".to_string();
+        let value = Value::from(42);
+        let outputs_changed = Arc::new(Notify::new());
+        let (_output_writer, outputs_reader) = multiple_buffer_with_slots([OutputsFake {
+            existing_fields: [(path.clone(), value.clone())].into(),
+        }]);
+
+        let (provider_task, _fields, request_sender, _subscribed_outputs_reader) =
+            get_registered_request_sender_from_provider(
+                cycler_instance,
+                outputs_changed.clone(),
+                outputs_reader,
+            )
+            .await;
+
+        const SUBSCRIPTION_ID: usize = 42;
+        let (response_sender, mut response_receiver) = channel(1);
+        request_sender
+            .send(ClientRequest {
+                request: OutputsRequest::Subscribe {
+                    id: SUBSCRIPTION_ID,
+                    cycler_instance: cycler_instance.to_string(),
+                    path: path.clone(),
+                    format: Format::Textual,
+                    options: SubscriptionOptions {
+                        minimum_interval: None,
+                        only_on_change: true,
+                    },
+                },
+                client: Client {
+                    id: 1337,
+                    response_sender: response_sender.clone(),
+                },
+            })
+            .await
+            .unwrap();
+        let response = response_receiver.recv().await.unwrap();
+        assert!(
+            matches!(
+                response,
+                Response::Textual(TextualResponse::Outputs(
+                    TextualOutputsResponse::Subscribe {
+                        id: SUBSCRIPTION_ID,
+                        result: Ok(()),
+                    }
+                ))
+            ),
+            "unexpected {response:?}",
+        );
+
+        outputs_changed.notify_one();
+        let subscribed_data = response_receiver.recv().await.unwrap();
+        assert_eq!(
+            subscribed_data,
+            Response::Textual(TextualResponse::Outputs(
+                TextualOutputsResponse::SubscribedData {
+                    items: [(
+                        SUBSCRIPTION_ID,
+                        TextualDataOrBinaryReference::TextualData { data: value }
+                    )]
+                    .into()
+                }
+            )),
+        );
+
+        outputs_changed.notify_one();
+        yield_now().await;
+        match response_receiver.try_recv() {
+            Err(TryRecvError::Empty) => {}
+            response => panic!("unexpected result from try_recv(): {response:?}"),
+        }
+
+        drop(request_sender);
+        provider_task.await.unwrap();
+    }
 }
@@ -7,7 +7,9 @@ use tokio::{
 };
 
 use crate::{
-    messages::{OutputsRequest, Path, Response, TextualOutputsResponse, TextualResponse},
+    messages::{
+        Format, Metadata, OutputsRequest, Path, Response, TextualOutputsResponse, TextualResponse,
+    },
     server::{client::Client, client_request::ClientRequest},
 };
 
@@ -31,9 +33,11 @@ pub fn router(mut request_receiver: Receiver<Request>) -> JoinHandle<()> {
                 Request::RegisterCycler {
                     cycler_instance,
                     fields,
+                    metadata,
                     request_sender,
                 } => {
-                    request_channels_of_cyclers.insert(cycler_instance, (fields, request_sender));
+                    request_channels_of_cyclers
+                        .insert(cycler_instance, (fields, metadata, request_sender));
                 }
             }
         }
@@ -44,10 +48,30 @@ async fn handle_request(
     request: ClientRequest<OutputsRequest>,
     request_channels_of_cyclers: &HashMap<
         String,
-        (BTreeSet<Path>, Sender<ClientRequest<OutputsRequest>>),
+        (
+            BTreeSet<Path>,
+            Metadata,
+            Sender<ClientRequest<OutputsRequest>>,
+        ),
     >,
     cached_cycler_instances: &mut HashMap<(Client, usize), String>,
 ) {
+    // timings are part of every cycler database and thus requested like any other output
+    let request = match request.request {
+        OutputsRequest::GetTimings {
+            id,
+            cycler_instance,
+        } => ClientRequest {
+            request: OutputsRequest::GetNext {
+                id,
+                cycler_instance,
+                path: "timings".to_string(),
+                format: Format::Textual,
+            },
+            client: request.client,
+        },
+        _ => request,
+    };
     match &request.request {
         OutputsRequest::GetFields { id } => {
             request
@@ -58,7 +82,7 @@ async fn handle_request(
                         id: *id,
                         fields: request_channels_of_cyclers
                             .iter()
-                            .map(|(cycler_instance, (fields, _request_sender))| {
+                            .map(|(cycler_instance, (fields, _metadata, _request_sender))| {
                                 (cycler_instance.clone(), fields.clone())
                             })
                             .collect(),
@@ -67,6 +91,27 @@ async fn handle_request(
                 .await
                 .expect("receiver should always wait for all senders");
         }
+        OutputsRequest::GetMetadata { id } => {
+            request
+                .client
+                .response_sender
+                .send(Response::Textual(TextualResponse::Outputs(
+                    TextualOutputsResponse::GetMetadata {
+                        id: *id,
+                        metadata: request_channels_of_cyclers
+                            .iter()
+                            .filter(|(_cycler_instance, (_fields, metadata, _request_sender))| {
+                                !metadata.is_empty()
+                            })
+                            .map(|(cycler_instance, (_fields, metadata, _request_sender))| {
+                                (cycler_instance.clone(), metadata.clone())
+                            })
+                            .collect(),
+                    },
+                )))
+                .await
+                .expect("receiver should always wait for all senders");
+        }
         OutputsRequest::GetNext {
             id,
             cycler_instance,
@@ -83,7 +128,7 @@ async fn handle_request(
             }
 
             match request_channels_of_cyclers.get(cycler_instance) {
-                Some((_fields, request_channel)) => {
+                Some((_fields, _metadata, request_channel)) => {
                     request_channel
                         .send(request)
                         .await
@@ -112,6 +157,7 @@ async fn handle_request(
                 }
             }
         }
+        OutputsRequest::GetTimings { .. } => unreachable!("GetTimings is converted to GetNext"),
         OutputsRequest::Unsubscribe {
             id,
             subscription_id,
@@ -137,7 +183,7 @@ async fn handle_request(
             };
 
             match request_channels_of_cyclers.get(&cycler_instance) {
-                Some((_fields, request_channel)) => {
+                Some((_fields, _metadata, request_channel)) => {
                     request_channel
                         .send(request)
                         .await
@@ -161,7 +207,7 @@ async fn handle_request(
         OutputsRequest::UnsubscribeEverything => {
             cached_cycler_instances
                 .retain(|(client, _subscription_id), _cycler_instance| client != &request.client);
-            for (_fields, request_channel) in request_channels_of_cyclers.values() {
+            for (_fields, _metadata, request_channel) in request_channels_of_cyclers.values() {
                 request_channel
                     .send(request.clone())
                     .await
@@ -175,7 +221,7 @@ async fn handle_request(
 mod tests {
     use tokio::sync::mpsc::{channel, error::TryRecvError};
 
-    use crate::messages::Format;
+    use crate::messages::FieldMetadata;
 
     use super::*;
 
@@ -200,6 +246,7 @@ mod tests {
             .send(Request::RegisterCycler {
                 cycler_instance: cycler_instance.to_string(),
                 fields: fields.clone(),
+                metadata: Default::default(),
                 request_sender: provider_request_sender,
             })
             .await
@@ -235,6 +282,61 @@ mod tests {
         router_task.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn metadata_of_documented_cycler_instances_is_returned() {
+        let (request_sender, request_receiver) = channel(1);
+        let router_task = router(request_receiver);
+
+        let metadata: Metadata = [(
+            "main_outputs.ball".to_string(),
+            FieldMetadata {
+                doc: Some("Ball position".to_string()),
+                unit: Some("m".to_string()),
+            },
+        )]
+        .into();
+        let (provider_request_sender, _provider_request_receiver) = channel(1);
+        for (cycler_instance, metadata) in [
+            ("Documented", metadata.clone()),
+            ("Undocumented", Default::default()),
+        ] {
+            request_sender
+                .send(Request::RegisterCycler {
+                    cycler_instance: cycler_instance.to_string(),
+                    fields: Default::default(),
+                    metadata,
+                    request_sender: provider_request_sender.clone(),
+                })
+                .await
+                .unwrap();
+        }
+
+        let (response_sender, mut response_receiver) = channel(1);
+        request_sender
+            .send(Request::ClientRequest(ClientRequest {
+                request: OutputsRequest::GetMetadata { id: 42 },
+                client: Client {
+                    id: 1337,
+                    response_sender,
+                },
+            }))
+            .await
+            .unwrap();
+        let response = response_receiver.recv().await.unwrap();
+        assert_eq!(
+            response,
+            Response::Textual(TextualResponse::Outputs(
+                TextualOutputsResponse::GetMetadata {
+                    id: 42,
+                    metadata: [("Documented".to_string(), metadata)].into()
+                }
+            )),
+        );
+
+        drop(request_sender);
+        router_task.await.unwrap();
+    }
+
     #[tokio::test]
     async fn unknown_cycler_instance_results_in_error() {
         let (request_sender, request_receiver) = channel(1);
@@ -287,6 +389,7 @@ mod tests {
             .send(Request::RegisterCycler {
                 cycler_instance: cycler_instance.to_string(),
                 fields: Default::default(),
+                metadata: Default::default(),
                 request_sender: provider_request_sender,
             })
             .await
@@ -316,6 +419,56 @@ mod tests {
         router_task.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn timings_request_is_forwarded_as_get_next() {
+        let (request_sender, request_receiver) = channel(1);
+        let router_task = router(request_receiver);
+
+        let cycler_instance = "CyclerInstance";
+        let (provider_request_sender, mut provider_request_receiver) = channel(1);
+        request_sender
+            .send(Request::RegisterCycler {
+                cycler_instance: cycler_instance.to_string(),
+                fields: Default::default(),
+                metadata: Default::default(),
+                request_sender: provider_request_sender,
+            })
+            .await
+            .unwrap();
+
+        let (response_sender, _response_receiver) = channel(1);
+        let client = Client {
+            id: 1337,
+            response_sender,
+        };
+        request_sender
+            .send(Request::ClientRequest(ClientRequest {
+                request: OutputsRequest::GetTimings {
+                    id: 42,
+                    cycler_instance: cycler_instance.to_string(),
+                },
+                client: client.clone(),
+            }))
+            .await
+            .unwrap();
+        let forwarded_client_request = provider_request_receiver.recv().await.unwrap();
+        assert_eq!(
+            forwarded_client_request,
+            ClientRequest {
+                request: OutputsRequest::GetNext {
+                    id: 42,
+                    cycler_instance: cycler_instance.to_string(),
+                    path: "timings".to_string(),
+                    format: Format::Textual,
+                },
+                client,
+            }
+        );
+
+        drop(request_sender);
+        router_task.await.unwrap();
+    }
+
     #[tokio::test]
     async fn unsubscribe_request_is_forwarded_to_subscribe_request_cycler_instance() {
         let (request_sender, request_receiver) = channel(1);
@@ -327,6 +480,7 @@ mod tests {
             .send(Request::RegisterCycler {
                 cycler_instance: cycler_instance.to_string(),
                 fields: Default::default(),
+                metadata: Default::default(),
                 request_sender: provider_request_sender,
             })
             .await
@@ -343,6 +497,7 @@ mod tests {
                 cycler_instance: "CyclerInstance".to_string(),
                 path: "a.b.c".to_string(),
                 format: Format::Textual,
+                options: Default::default(),
             },
             client: client.clone(),
         };
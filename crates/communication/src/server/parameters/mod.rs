@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use parameters::directory::Scope;
 use serde_json::Value;
 
@@ -26,4 +28,38 @@ pub enum StorageRequest {
         scope: Scope,
         path: Path,
     },
+    GetProvenance {
+        client: Client,
+        id: usize,
+        path: Path,
+    },
+    /// Writes the current values at the paths into the parameter files providing them
+    StoreParameters {
+        client: Client,
+        id: usize,
+        paths: Vec<Path>,
+    },
+    InjectCommand {
+        client: Client,
+        id: usize,
+        path: Path,
+        data: Value,
+        time_to_live: Duration,
+    },
+    ClearCommand {
+        client: Client,
+        id: usize,
+        path: Path,
+    },
+    RunMotionTest {
+        client: Client,
+        id: usize,
+        motion: Value,
+        repetitions: usize,
+    },
+    Teleoperate {
+        client: Client,
+        id: usize,
+        command: Value,
+    },
 }
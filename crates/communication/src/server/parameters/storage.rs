@@ -1,25 +1,51 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    collections::HashMap,
+    future::pending,
+    mem::take,
+    path::Path,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use framework::Writer;
-use parameters::directory::{deserialize, serialize};
+use log::error;
+use parameters::{
+    directory::{deserialize_with_provenance, serialize, store},
+    provenance::{Provenance, Source},
+};
 use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
 use serialize_hierarchy::SerializeHierarchy;
 use tokio::{
-    spawn,
+    select, spawn,
     sync::{mpsc::Receiver, Notify},
     task::JoinHandle,
+    time::{sleep_until, Instant},
 };
 
 use crate::{
-    messages::{ParametersResponse, Response, TextualResponse},
+    messages::{InjectCommandResponse, ParametersResponse, Response, TextualResponse},
     server::client::Client,
 };
 
 use super::StorageRequest;
 
+const MOTION_TEST_PATH: &str = "motion_test.request";
+/// Long enough for many repetitions, but a forgotten test does not outlive the bench session
+const MOTION_TEST_TIME_TO_LIVE: Duration = Duration::from_secs(600);
+const TELEOPERATION_PATH: &str = "behavior.teleoperation.command";
+/// The robot stops if the joystick stops sending, e.g. because the connection dropped
+const TELEOPERATION_TIME_TO_LIVE: Duration = Duration::from_millis(500);
+
+struct Injection {
+    original_data: Value,
+    expiration: Instant,
+}
+
 pub fn storage<Parameters>(
     parameters_writer: Writer<Parameters>,
     parameters_changed: Arc<Notify>,
+    mut provenance: Provenance,
     mut request_receiver: Receiver<StorageRequest>,
     parameters_directory: impl AsRef<Path> + Send + Sync + 'static,
     body_id: String,
@@ -30,24 +56,49 @@ where
 {
     spawn(async move {
         let mut parameters = (*parameters_writer.next()).clone();
-        while let Some(request) = request_receiver.recv().await {
-            handle_request(
-                request,
-                &mut parameters,
-                &parameters_writer,
-                &parameters_changed,
-                &parameters_directory,
-                &body_id,
-                &head_id,
-            )
-            .await;
+        let mut injections = HashMap::new();
+        loop {
+            let next_expiration = injections
+                .values()
+                .map(|injection: &Injection| injection.expiration)
+                .min();
+            select! {
+                request = request_receiver.recv() => {
+                    let Some(request) = request else {
+                        break;
+                    };
+                    handle_request(
+                        request,
+                        &mut parameters,
+                        &mut provenance,
+                        &mut injections,
+                        &parameters_writer,
+                        &parameters_changed,
+                        &parameters_directory,
+                        &body_id,
+                        &head_id,
+                    )
+                    .await;
+                },
+                _ = sleep_until_expiration(next_expiration) => {
+                    expire_injections(
+                        &mut parameters,
+                        &mut injections,
+                        &parameters_writer,
+                        &parameters_changed,
+                    );
+                },
+            }
         }
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_request<Parameters>(
     request: StorageRequest,
     parameters: &mut Parameters,
+    provenance: &mut Provenance,
+    injections: &mut HashMap<String, Injection>,
     parameters_writer: &Writer<Parameters>,
     parameters_changed: &Arc<Notify>,
     parameters_directory: impl AsRef<Path>,
@@ -63,59 +114,36 @@ async fn handle_request<Parameters>(
             path,
             data,
         } => {
-            if !Parameters::exists(&path) {
-                respond(
-                    client,
-                    ParametersResponse::Update {
-                        id,
-                        result: Err(format!("path {path:?} does not exist")),
-                    },
-                )
-                .await;
-                return;
+            let result = update(parameters, &path, data.clone());
+            if result.is_ok() {
+                provenance.replace(&path, &data, Source::Runtime);
+                // a regular update replaces an injected command instead of being reverted later
+                injections.remove(&path);
+                publish(parameters, parameters_writer, parameters_changed);
             }
-
-            if let Err(error) = parameters.deserialize_path(&path, data) {
-                respond(
-                    client,
-                    ParametersResponse::Update {
-                        id,
-                        result: Err(format!("failed to deserialize: {error:?}")),
-                    },
-                )
-                .await;
-                return;
-            }
-
-            {
-                let mut slot = parameters_writer.next();
-                *slot = parameters.clone();
-            }
-            parameters_changed.notify_one();
-
-            respond(client, ParametersResponse::Update { id, result: Ok(()) }).await;
+            respond(client, ParametersResponse::Update { id, result }).await;
         }
         StorageRequest::LoadFromDisk { client, id } => {
-            let parameters = match deserialize(parameters_directory, body_id, head_id).await {
-                Ok(parameters) => parameters,
-                Err(error) => {
-                    respond(
-                        client,
-                        ParametersResponse::LoadFromDisk {
-                            id,
-                            result: Err(format!("failed to deserialize parameters: {error:?}")),
-                        },
-                    )
-                    .await;
-                    return;
-                }
-            };
+            let (loaded_parameters, loaded_provenance) =
+                match deserialize_with_provenance(parameters_directory, body_id, head_id).await {
+                    Ok(loaded) => loaded,
+                    Err(error) => {
+                        respond(
+                            client,
+                            ParametersResponse::LoadFromDisk {
+                                id,
+                                result: Err(format!("failed to deserialize parameters: {error:?}")),
+                            },
+                        )
+                        .await;
+                        return;
+                    }
+                };
 
-            {
-                let mut slot = parameters_writer.next();
-                *slot = parameters;
-            }
-            parameters_changed.notify_one();
+            *parameters = loaded_parameters;
+            *provenance = loaded_provenance;
+            injections.clear();
+            publish(parameters, parameters_writer, parameters_changed);
 
             respond(
                 client,
@@ -129,11 +157,25 @@ async fn handle_request<Parameters>(
             scope,
             path,
         } => {
+            let stored_parameters = match without_injections(parameters, injections) {
+                Ok(stored_parameters) => stored_parameters,
+                Err(error) => {
+                    respond(
+                        client,
+                        ParametersResponse::StoreToDisk {
+                            id,
+                            result: Err(error),
+                        },
+                    )
+                    .await;
+                    return;
+                }
+            };
             if let Err(error) = serialize(
-                parameters,
+                &stored_parameters,
                 scope,
                 &path,
-                parameters_directory,
+                &parameters_directory,
                 body_id,
                 head_id,
             )
@@ -149,6 +191,15 @@ async fn handle_request<Parameters>(
                 .await;
                 return;
             }
+            match deserialize_with_provenance::<Parameters>(parameters_directory, body_id, head_id)
+                .await
+            {
+                Ok((_stored_parameters, stored_provenance)) => {
+                    *provenance =
+                        keep_runtime_sources_outside(provenance, stored_provenance, &[path]);
+                }
+                Err(error) => error!("failed to update provenance after storing: {error:?}"),
+            }
 
             respond(
                 client,
@@ -156,7 +207,253 @@ async fn handle_request<Parameters>(
             )
             .await;
         }
+        StorageRequest::InjectCommand {
+            client,
+            id,
+            path,
+            data,
+            time_to_live,
+        } => {
+            let result = inject(parameters, injections, path, data, time_to_live);
+            if result.is_ok() {
+                publish(parameters, parameters_writer, parameters_changed);
+            }
+            respond_to_command(client, InjectCommandResponse::Inject { id, result }).await;
+        }
+        StorageRequest::StoreParameters { client, id, paths } => {
+            let result = match without_injections(parameters, injections) {
+                Ok(stored_parameters) => store(
+                    &stored_parameters,
+                    &paths,
+                    &parameters_directory,
+                    body_id,
+                    head_id,
+                )
+                .await
+                .map_err(|error| format!("failed to store parameters: {error:?}")),
+                Err(error) => Err(error),
+            };
+            if result.is_ok() {
+                match deserialize_with_provenance::<Parameters>(
+                    parameters_directory,
+                    body_id,
+                    head_id,
+                )
+                .await
+                {
+                    Ok((_stored_parameters, stored_provenance)) => {
+                        *provenance =
+                            keep_runtime_sources_outside(provenance, stored_provenance, &paths);
+                    }
+                    Err(error) => error!("failed to update provenance after storing: {error:?}"),
+                }
+            }
+            respond(client, ParametersResponse::StoreParameters { id, result }).await;
+        }
+        StorageRequest::GetProvenance { client, id, path } => {
+            let result = if path.is_empty() || Parameters::exists(&path) {
+                Ok(provenance.sources(&path))
+            } else {
+                Err(format!("path {path:?} does not exist"))
+            };
+            respond(client, ParametersResponse::GetProvenance { id, result }).await;
+        }
+        StorageRequest::ClearCommand { client, id, path } => {
+            let result = match injections.remove(&path) {
+                Some(injection) => update(parameters, &path, injection.original_data),
+                None => Err(format!("no command injected at {path:?}")),
+            };
+            if result.is_ok() {
+                publish(parameters, parameters_writer, parameters_changed);
+            }
+            respond_to_command(client, InjectCommandResponse::Clear { id, result }).await;
+        }
+        StorageRequest::RunMotionTest {
+            client,
+            id,
+            motion,
+            repetitions,
+        } => {
+            // the request time distinguishes repeated requests of the same test
+            let data = json!({
+                "motion": motion,
+                "repetitions": repetitions,
+                "requested_at": SystemTime::now(),
+            });
+            let result = inject(
+                parameters,
+                injections,
+                MOTION_TEST_PATH.to_string(),
+                data,
+                MOTION_TEST_TIME_TO_LIVE,
+            );
+            if result.is_ok() {
+                publish(parameters, parameters_writer, parameters_changed);
+            }
+            respond_to_command(client, InjectCommandResponse::RunMotionTest { id, result }).await;
+        }
+        StorageRequest::Teleoperate {
+            client,
+            id,
+            command,
+        } => {
+            let result = inject(
+                parameters,
+                injections,
+                TELEOPERATION_PATH.to_string(),
+                command,
+                TELEOPERATION_TIME_TO_LIVE,
+            );
+            if result.is_ok() {
+                publish(parameters, parameters_writer, parameters_changed);
+            }
+            respond_to_command(client, InjectCommandResponse::Teleoperate { id, result }).await;
+        }
+    }
+}
+
+/// Returns the stored provenance with the sources of runtime updates which were not stored, i.e.
+/// are not located at or below any of the stored paths
+fn keep_runtime_sources_outside(
+    current: &Provenance,
+    mut stored: Provenance,
+    stored_paths: &[String],
+) -> Provenance {
+    let unstored_runtime_paths: Vec<_> = current
+        .iter()
+        .filter(|(path, source)| {
+            **source == Source::Runtime
+                && !stored_paths.iter().any(|stored_path| {
+                    stored_path.is_empty()
+                        || *path == stored_path
+                        || path.starts_with(&format!("{stored_path}."))
+                })
+        })
+        .map(|(path, _source)| path.clone())
+        .collect();
+    for path in unstored_runtime_paths {
+        // only the source of the leaf is of interest, not its value
+        stored.replace(&path, &Value::Null, Source::Runtime);
+    }
+    stored
+}
+
+/// Applies the update to a copy to keep the current parameters untouched if it fails
+fn update<Parameters>(parameters: &mut Parameters, path: &str, data: Value) -> Result<(), String>
+where
+    Parameters: Clone + DeserializeOwned + Serialize + SerializeHierarchy,
+{
+    if !Parameters::exists(path) {
+        return Err(format!("path {path:?} does not exist"));
+    }
+    let mut updated_parameters = parameters.clone();
+    updated_parameters
+        .deserialize_path(path, data)
+        .map_err(|error| format!("failed to deserialize: {error:?}"))?;
+    validate(&updated_parameters).map_err(|error| format!("failed to validate: {error:?}"))?;
+    *parameters = updated_parameters;
+    Ok(())
+}
+
+fn inject<Parameters>(
+    parameters: &mut Parameters,
+    injections: &mut HashMap<String, Injection>,
+    path: String,
+    data: Value,
+    time_to_live: Duration,
+) -> Result<(), String>
+where
+    Parameters: Clone + DeserializeOwned + Serialize + SerializeHierarchy,
+{
+    if !Parameters::exists(&path) {
+        return Err(format!("path {path:?} does not exist"));
+    }
+    // a repeated injection only extends the time to live, the first original data is kept
+    let original_data = match injections.get(&path) {
+        Some(injection) => injection.original_data.clone(),
+        None => parameters
+            .serialize_path(&path, serde_json::value::Serializer)
+            .map_err(|error| format!("failed to serialize: {error:?}"))?,
+    };
+    update(parameters, &path, data)?;
+    injections.insert(
+        path,
+        Injection {
+            original_data,
+            expiration: Instant::now() + time_to_live,
+        },
+    );
+    Ok(())
+}
+
+fn expire_injections<Parameters>(
+    parameters: &mut Parameters,
+    injections: &mut HashMap<String, Injection>,
+    parameters_writer: &Writer<Parameters>,
+    parameters_changed: &Arc<Notify>,
+) where
+    Parameters: Clone + DeserializeOwned + Serialize + SerializeHierarchy,
+{
+    let now = Instant::now();
+    let (expired_injections, active_injections): (HashMap<_, _>, HashMap<_, _>) = take(injections)
+        .into_iter()
+        .partition(|(_path, injection)| injection.expiration <= now);
+    *injections = active_injections;
+    for (path, injection) in expired_injections {
+        if let Err(error) = update(parameters, &path, injection.original_data) {
+            error!("failed to restore {path:?} after injected command expired: {error}");
+        }
+    }
+    publish(parameters, parameters_writer, parameters_changed);
+}
+
+async fn sleep_until_expiration(expiration: Option<Instant>) {
+    match expiration {
+        Some(expiration) => sleep_until(expiration).await,
+        None => pending().await,
+    }
+}
+
+/// Returns a copy of the parameters with all injected commands reverted
+fn without_injections<Parameters>(
+    parameters: &Parameters,
+    injections: &HashMap<String, Injection>,
+) -> Result<Parameters, String>
+where
+    Parameters: Clone + SerializeHierarchy,
+{
+    let mut parameters = parameters.clone();
+    for (path, injection) in injections {
+        parameters
+            .deserialize_path(path, injection.original_data.clone())
+            .map_err(|error| format!("failed to revert injected command at {path:?}: {error:?}"))?;
+    }
+    Ok(parameters)
+}
+
+fn publish<Parameters>(
+    parameters: &Parameters,
+    parameters_writer: &Writer<Parameters>,
+    parameters_changed: &Arc<Notify>,
+) where
+    Parameters: Clone,
+{
+    {
+        let mut slot = parameters_writer.next();
+        *slot = parameters.clone();
     }
+    parameters_changed.notify_one();
+}
+
+/// Checks that the parameters survive a serialization round trip, i.e. every cycler is able to
+/// deserialize them after partial updates
+fn validate<Parameters>(parameters: &Parameters) -> serde_json::Result<()>
+where
+    Parameters: DeserializeOwned + Serialize,
+{
+    let value = serde_json::to_value(parameters)?;
+    serde_json::from_value::<Parameters>(value)?;
+    Ok(())
 }
 
 async fn respond(client: Client, response: ParametersResponse) {
@@ -167,6 +464,14 @@ async fn respond(client: Client, response: ParametersResponse) {
         .expect("receiver should always wait for all senders");
 }
 
+async fn respond_to_command(client: Client, response: InjectCommandResponse) {
+    client
+        .response_sender
+        .send(Response::Textual(TextualResponse::InjectCommand(response)))
+        .await
+        .expect("receiver should always wait for all senders");
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::{BTreeSet, HashMap};
@@ -175,7 +480,10 @@ mod tests {
     use serde::{Deserialize, Deserializer, Serializer};
     use serde_json::Value;
     use serialize_hierarchy::Error;
-    use tokio::sync::mpsc::{channel, error::TryRecvError};
+    use tokio::{
+        sync::mpsc::{channel, error::TryRecvError},
+        time::{sleep, timeout},
+    };
 
     use crate::server::client::Client;
 
@@ -189,6 +497,7 @@ mod tests {
         let subscriptions_task = storage(
             parameters_writer,
             parameters_changed,
+            Default::default(),
             request_receiver,
             ".",
             Default::default(),
@@ -256,6 +565,7 @@ mod tests {
         let subscriptions_task = storage(
             parameters_writer,
             parameters_changed.clone(),
+            Default::default(),
             request_receiver,
             ".",
             Default::default(),
@@ -295,4 +605,187 @@ mod tests {
         drop(request_sender);
         subscriptions_task.await.unwrap();
     }
+
+    #[tokio::test]
+    async fn updated_parameters_have_runtime_provenance() {
+        let path = "a.b.c".to_string();
+        let (parameters_writer, _parameters_reader) =
+            multiple_buffer_with_slots([ParametersFake {
+                existing_fields: [(path.clone(), 42)].into(),
+            }]);
+        let mut provenance = Provenance::default();
+        provenance.merge(
+            &serde_json::json!({ "a": { "b": { "c": 42 } } }),
+            Source::File("default.json".into()),
+        );
+        let (request_sender, request_receiver) = channel(1);
+        let storage_task = storage(
+            parameters_writer,
+            Arc::new(Notify::new()),
+            provenance,
+            request_receiver,
+            ".",
+            Default::default(),
+            Default::default(),
+        );
+
+        let (response_sender, mut response_receiver) = channel(1);
+        let client = Client {
+            id: 1337,
+            response_sender,
+        };
+        request_sender
+            .send(StorageRequest::GetProvenance {
+                client: client.clone(),
+                id: 42,
+                path: path.clone(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response_receiver.recv().await.unwrap(),
+            Response::Textual(TextualResponse::Parameters(
+                ParametersResponse::GetProvenance {
+                    id: 42,
+                    result: Ok([(path.clone(), Source::File("default.json".into()))].into()),
+                }
+            )),
+        );
+
+        request_sender
+            .send(StorageRequest::UpdateParameter {
+                client: client.clone(),
+                id: 43,
+                path: path.clone(),
+                data: Value::from(1337),
+            })
+            .await
+            .unwrap();
+        response_receiver.recv().await.unwrap();
+        request_sender
+            .send(StorageRequest::GetProvenance {
+                client,
+                id: 44,
+                path: path.clone(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response_receiver.recv().await.unwrap(),
+            Response::Textual(TextualResponse::Parameters(
+                ParametersResponse::GetProvenance {
+                    id: 44,
+                    result: Ok([(path, Source::Runtime)].into()),
+                }
+            )),
+        );
+
+        drop(request_sender);
+        storage_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn failing_update_request_keeps_parameters() {
+        let path = "a.b.c".to_string();
+        let (parameters_writer, parameters_reader) = multiple_buffer_with_slots([ParametersFake {
+            existing_fields: [(path.clone(), 42)].into(),
+        }]);
+        let parameters_changed = Arc::new(Notify::new());
+        let (request_sender, request_receiver) = channel(1);
+        let subscriptions_task = storage(
+            parameters_writer,
+            parameters_changed.clone(),
+            Default::default(),
+            request_receiver,
+            ".",
+            Default::default(),
+            Default::default(),
+        );
+
+        let (response_sender, mut response_receiver) = channel(1);
+        request_sender
+            .send(StorageRequest::UpdateParameter {
+                client: Client {
+                    id: 1337,
+                    response_sender: response_sender.clone(),
+                },
+                id: 42,
+                path: path.clone(),
+                data: Value::from("not a number"),
+            })
+            .await
+            .unwrap();
+        let response = response_receiver.recv().await.unwrap();
+        match response {
+            Response::Textual(TextualResponse::Parameters(ParametersResponse::Update {
+                id: 42,
+                result: Err(_),
+            })) => {}
+            response => panic!("unexpected response: {response:?}"),
+        }
+        let parameters = parameters_reader.next();
+        assert_eq!(parameters.existing_fields.get(&path), Some(42).as_ref());
+
+        drop(request_sender);
+        subscriptions_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn injected_command_is_reverted_after_time_to_live() {
+        let path = "a.b.c".to_string();
+        let (parameters_writer, parameters_reader) = multiple_buffer_with_slots([ParametersFake {
+            existing_fields: [(path.clone(), 42)].into(),
+        }]);
+        let parameters_changed = Arc::new(Notify::new());
+        let (request_sender, request_receiver) = channel(1);
+        let storage_task = storage(
+            parameters_writer,
+            parameters_changed,
+            Default::default(),
+            request_receiver,
+            ".",
+            Default::default(),
+            Default::default(),
+        );
+
+        let (response_sender, mut response_receiver) = channel(1);
+        request_sender
+            .send(StorageRequest::InjectCommand {
+                client: Client {
+                    id: 1337,
+                    response_sender,
+                },
+                id: 42,
+                path: path.clone(),
+                data: Value::from(1337),
+                time_to_live: Duration::from_millis(100),
+            })
+            .await
+            .unwrap();
+        let response = response_receiver.recv().await.unwrap();
+        assert_eq!(
+            response,
+            Response::Textual(TextualResponse::InjectCommand(
+                InjectCommandResponse::Inject {
+                    id: 42,
+                    result: Ok(()),
+                }
+            )),
+        );
+        assert_eq!(
+            parameters_reader.next().existing_fields.get(&path),
+            Some(1337).as_ref()
+        );
+
+        timeout(Duration::from_secs(1), async {
+            while parameters_reader.next().existing_fields.get(&path) != Some(&42) {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("injected command should be reverted");
+
+        drop(request_sender);
+        storage_task.await.unwrap();
+    }
 }
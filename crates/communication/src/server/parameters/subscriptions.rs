@@ -17,7 +17,7 @@ use tokio::{
 };
 
 use crate::{
-    messages::{ParametersRequest, ParametersResponse, Path, Response, TextualResponse},
+    messages::{Metadata, ParametersRequest, ParametersResponse, Path, Response, TextualResponse},
     server::{client::Client, client_request::ClientRequest},
 };
 
@@ -28,6 +28,7 @@ pub fn subscriptions<Parameters>(
     parameters_reader: Reader<Parameters>,
     parameters_changed: Arc<Notify>,
     storage_request_sender: Sender<StorageRequest>,
+    metadata: Metadata,
 ) -> JoinHandle<()>
 where
     Parameters: Send + SerializeHierarchy + Sync + 'static,
@@ -48,6 +49,7 @@ where
                         &storage_request_sender,
                         &mut subscriptions,
                         &fields,
+                        &metadata,
                     ).await;
                 },
                 _ = parameters_changed.notified() => {
@@ -64,6 +66,7 @@ async fn handle_request<Parameters>(
     storage_request_sender: &Sender<StorageRequest>,
     subscriptions: &mut HashMap<(Client, usize), Path>,
     fields: &BTreeSet<String>,
+    metadata: &Metadata,
 ) where
     Parameters: SerializeHierarchy,
 {
@@ -78,6 +81,16 @@ async fn handle_request<Parameters>(
             )
             .await;
         }
+        ParametersRequest::GetMetadata { id } => {
+            respond(
+                request,
+                ParametersResponse::GetMetadata {
+                    id,
+                    metadata: metadata.clone(),
+                },
+            )
+            .await;
+        }
         ParametersRequest::GetCurrent { id, ref path } => {
             let data = {
                 let parameters = parameters_reader.next();
@@ -220,6 +233,26 @@ async fn handle_request<Parameters>(
                 .await
                 .expect("receiver should always wait for all senders");
         }
+        ParametersRequest::GetProvenance { id, path } => {
+            storage_request_sender
+                .send(StorageRequest::GetProvenance {
+                    client: request.client,
+                    id,
+                    path,
+                })
+                .await
+                .expect("receiver should always wait for all senders");
+        }
+        ParametersRequest::StoreParameters { id, paths } => {
+            storage_request_sender
+                .send(StorageRequest::StoreParameters {
+                    client: request.client,
+                    id,
+                    paths,
+                })
+                .await
+                .expect("receiver should always wait for all senders");
+        }
     }
 }
 
@@ -291,6 +324,8 @@ mod tests {
         task::yield_now,
     };
 
+    use crate::messages::FieldMetadata;
+
     use super::*;
 
     #[tokio::test]
@@ -304,6 +339,52 @@ mod tests {
             parameters_reader,
             parameters_changed,
             storage_request_sender,
+            Default::default(),
+        );
+
+        drop(request_sender);
+        subscriptions_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn metadata_is_returned() {
+        let (request_sender, request_receiver) = channel(1);
+        let (_parameters_writer, parameters_reader) = multiple_buffer_with_slots([42usize]);
+        let parameters_changed = Arc::new(Notify::new());
+        let (storage_request_sender, _storage_request_receiver) = channel(1);
+        let metadata: Metadata = [(
+            "a.b".to_string(),
+            FieldMetadata {
+                doc: Some("Distance".to_string()),
+                unit: Some("m".to_string()),
+            },
+        )]
+        .into();
+        let subscriptions_task = subscriptions(
+            request_receiver,
+            parameters_reader,
+            parameters_changed,
+            storage_request_sender,
+            metadata.clone(),
+        );
+
+        let (response_sender, mut response_receiver) = channel(1);
+        request_sender
+            .send(ClientRequest {
+                request: ParametersRequest::GetMetadata { id: 42 },
+                client: Client {
+                    id: 1337,
+                    response_sender,
+                },
+            })
+            .await
+            .unwrap();
+        let response = response_receiver.recv().await.unwrap();
+        assert_eq!(
+            response,
+            Response::Textual(TextualResponse::Parameters(
+                ParametersResponse::GetMetadata { id: 42, metadata }
+            )),
         );
 
         drop(request_sender);
@@ -321,6 +402,7 @@ mod tests {
             parameters_reader,
             parameters_changed,
             storage_request_sender,
+            Default::default(),
         );
 
         let (response_sender, mut response_receiver) = channel(1);
@@ -412,6 +494,7 @@ mod tests {
             parameters_reader,
             parameters_changed,
             storage_request_sender,
+            Default::default(),
         );
 
         let (response_sender, mut response_receiver) = channel(1);
@@ -460,6 +543,7 @@ mod tests {
             parameters_reader,
             parameters_changed,
             storage_request_sender,
+            Default::default(),
         );
 
         const ID: usize = 42;
@@ -555,6 +639,7 @@ mod tests {
             parameters_reader,
             parameters_changed,
             storage_request_sender,
+            Default::default(),
         );
 
         const ID: usize = 42;
@@ -648,6 +733,7 @@ mod tests {
             parameters_reader,
             parameters_changed,
             storage_request_sender,
+            Default::default(),
         );
 
         let client_id = 1337;
@@ -740,6 +826,7 @@ mod tests {
             parameters_reader,
             parameters_changed,
             storage_request_sender,
+            Default::default(),
         );
 
         let (response_sender, mut response_receiver) = channel(1);
@@ -794,6 +881,7 @@ mod tests {
             parameters_reader,
             parameters_changed,
             storage_request_sender,
+            Default::default(),
         );
 
         const SUBSCRIPTION_ID: usize = 42;
@@ -921,6 +1009,7 @@ mod tests {
             parameters_reader,
             parameters_changed,
             storage_request_sender,
+            Default::default(),
         );
 
         const SUBSCRIPTION_ID: usize = 42;
@@ -1037,6 +1126,7 @@ mod tests {
             parameters_reader,
             parameters_changed,
             storage_request_sender,
+            Default::default(),
         );
 
         let path = "a.b.c".to_string();
@@ -1095,6 +1185,7 @@ mod tests {
             parameters_reader,
             parameters_changed,
             storage_request_sender,
+            Default::default(),
         );
 
         let client_id = 1337;
@@ -1146,6 +1237,7 @@ mod tests {
             parameters_reader,
             parameters_changed,
             storage_request_sender,
+            Default::default(),
         );
 
         let client_id = 1337;
@@ -1213,6 +1305,7 @@ mod tests {
             parameters_reader,
             parameters_changed.clone(),
             storage_request_sender,
+            Default::default(),
         );
 
         const SUBSCRIPTION_ID: usize = 42;
@@ -0,0 +1,141 @@
+use tokio_tungstenite::tungstenite::handshake::server::Request as HandshakeRequest;
+
+use crate::messages::{
+    InjectCommandRequest, InjectCommandResponse, InjectionsRequest, InjectionsResponse,
+    ParametersRequest, ParametersResponse, Request, TextualResponse,
+};
+
+const PERMISSION_DENIED: &str = "permission denied, connect with the privileged token to modify";
+
+/// What a connected client is allowed to do
+///
+/// Read-only clients can only query and subscribe. Modifying the robot, i.e. updating, loading and
+/// storing parameters as well as injecting commands and outputs, requires privileges.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Permission {
+    ReadOnly,
+    Privileged,
+}
+
+impl Permission {
+    /// Clients are privileged if no token is configured or if they pass the configured token as
+    /// `token` query parameter when connecting, e.g. `ws://10.1.24.42:1337/?token=secret`
+    pub fn of_handshake(request: &HandshakeRequest, privileged_token: Option<&str>) -> Self {
+        let Some(privileged_token) = privileged_token else {
+            return Self::Privileged;
+        };
+        let token = request.uri().query().and_then(|query| {
+            query
+                .split('&')
+                .find_map(|parameter| parameter.strip_prefix("token="))
+        });
+        if token == Some(privileged_token) {
+            Self::Privileged
+        } else {
+            Self::ReadOnly
+        }
+    }
+
+    /// Returns the response rejecting the request if this permission does not allow it
+    pub fn reject(self, request: &Request) -> Option<TextualResponse> {
+        if self == Self::Privileged {
+            return None;
+        }
+        let result = Err(PERMISSION_DENIED.to_string());
+        let response = match request {
+            Request::InjectCommand(InjectCommandRequest::Inject { id, .. }) => {
+                TextualResponse::InjectCommand(InjectCommandResponse::Inject { id: *id, result })
+            }
+            Request::InjectCommand(InjectCommandRequest::Clear { id, .. }) => {
+                TextualResponse::InjectCommand(InjectCommandResponse::Clear { id: *id, result })
+            }
+            Request::InjectCommand(InjectCommandRequest::RunMotionTest { id, .. }) => {
+                TextualResponse::InjectCommand(InjectCommandResponse::RunMotionTest {
+                    id: *id,
+                    result,
+                })
+            }
+            Request::InjectCommand(InjectCommandRequest::Teleoperate { id, .. }) => {
+                TextualResponse::InjectCommand(InjectCommandResponse::Teleoperate {
+                    id: *id,
+                    result,
+                })
+            }
+            Request::Injections(InjectionsRequest::Set { id, .. }) => {
+                TextualResponse::Injections(InjectionsResponse::Set { id: *id, result })
+            }
+            Request::Injections(InjectionsRequest::Unset { id, .. }) => {
+                TextualResponse::Injections(InjectionsResponse::Unset { id: *id, result })
+            }
+            Request::Parameters(ParametersRequest::Update { id, .. }) => {
+                TextualResponse::Parameters(ParametersResponse::Update { id: *id, result })
+            }
+            Request::Parameters(ParametersRequest::LoadFromDisk { id }) => {
+                TextualResponse::Parameters(ParametersResponse::LoadFromDisk { id: *id, result })
+            }
+            Request::Parameters(ParametersRequest::StoreToDisk { id, .. }) => {
+                TextualResponse::Parameters(ParametersResponse::StoreToDisk { id: *id, result })
+            }
+            Request::Parameters(ParametersRequest::StoreParameters { id, .. }) => {
+                TextualResponse::Parameters(ParametersResponse::StoreParameters { id: *id, result })
+            }
+            _ => return None,
+        };
+        Some(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    fn handshake(uri: &str) -> HandshakeRequest {
+        HandshakeRequest::builder().uri(uri).body(()).unwrap()
+    }
+
+    #[test]
+    fn only_matching_token_grants_privileges() {
+        let token = Some("secret");
+        assert_eq!(
+            Permission::of_handshake(&handshake("ws://localhost:1337/?token=secret"), token),
+            Permission::Privileged
+        );
+        assert_eq!(
+            Permission::of_handshake(&handshake("ws://localhost:1337/?token=guess"), token),
+            Permission::ReadOnly
+        );
+        assert_eq!(
+            Permission::of_handshake(&handshake("ws://localhost:1337/"), token),
+            Permission::ReadOnly
+        );
+        assert_eq!(
+            Permission::of_handshake(&handshake("ws://localhost:1337/"), None),
+            Permission::Privileged
+        );
+    }
+
+    #[test]
+    fn read_only_clients_can_subscribe_but_not_update() {
+        let subscribe = Request::Parameters(ParametersRequest::Subscribe {
+            id: 42,
+            path: "a.b".to_string(),
+        });
+        let update = Request::Parameters(ParametersRequest::Update {
+            id: 42,
+            path: "a.b".to_string(),
+            data: Value::Null,
+        });
+
+        assert_eq!(Permission::ReadOnly.reject(&subscribe), None);
+        assert_eq!(
+            Permission::ReadOnly.reject(&update),
+            Some(TextualResponse::Parameters(ParametersResponse::Update {
+                id: 42,
+                result: Err(PERMISSION_DENIED.to_string()),
+            }))
+        );
+        assert_eq!(Permission::Privileged.reject(&update), None);
+    }
+}
@@ -8,11 +8,16 @@ use tokio_tungstenite::{
 use tokio_util::sync::CancellationToken;
 
 use crate::{
-    messages::{OutputsRequest, ParametersRequest, Request, Response},
+    messages::{
+        InjectCommandRequest, LogsRequest, OutputsRequest, ParametersRequest, Request, Response,
+    },
     server::client_request::ClientRequest,
 };
 
-use super::{client::Client, connection::ReceiverOrSenderError, outputs};
+use super::{
+    client::Client, connection::ReceiverOrSenderError, outputs, parameters::StorageRequest,
+    permission::Permission,
+};
 
 #[allow(clippy::too_many_arguments)]
 pub async fn receiver(
@@ -21,9 +26,12 @@ pub async fn receiver(
     keep_running: CancellationToken,
     keep_only_self_running: CancellationToken,
     client_id: usize,
+    permission: Permission,
     response_sender: Sender<Response>,
     outputs_sender: Sender<outputs::Request>,
+    logs_sender: Sender<ClientRequest<LogsRequest>>,
     parameters_sender: Sender<ClientRequest<ParametersRequest>>,
+    parameters_storage_sender: Sender<StorageRequest>,
 ) {
     select! {
         _ = async {
@@ -33,9 +41,12 @@ pub async fn receiver(
                     &error_sender,
                     &keep_only_self_running,
                     client_id,
+                    permission,
                     &response_sender,
                     &outputs_sender,
+                    &logs_sender,
                     &parameters_sender,
+                    &parameters_storage_sender,
                 ).await;
             }
         } => {},
@@ -53,6 +64,16 @@ pub async fn receiver(
         }))
         .await
         .expect("receiver should always wait for all senders");
+    logs_sender
+        .send(ClientRequest {
+            request: LogsRequest::UnsubscribeEverything,
+            client: Client {
+                id: client_id,
+                response_sender: response_sender.clone(),
+            },
+        })
+        .await
+        .expect("receiver should always wait for all senders");
     parameters_sender
         .send(ClientRequest {
             request: ParametersRequest::UnsubscribeEverything,
@@ -65,14 +86,18 @@ pub async fn receiver(
         .expect("receiver should always wait for all senders");
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_message(
     message: Result<Message, tokio_tungstenite::tungstenite::Error>,
     error_sender: &Sender<ReceiverOrSenderError>,
     keep_only_self_running: &CancellationToken,
     client_id: usize,
+    permission: Permission,
     response_sender: &Sender<Response>,
     outputs_sender: &Sender<outputs::Request>,
+    logs_sender: &Sender<ClientRequest<LogsRequest>>,
     parameters_sender: &Sender<ClientRequest<ParametersRequest>>,
+    parameters_storage_sender: &Sender<StorageRequest>,
 ) {
     let message = match message {
         Ok(message) => message,
@@ -104,6 +129,14 @@ async fn handle_message(
                 }
             };
 
+            if let Some(rejection) = permission.reject(&request) {
+                response_sender
+                    .send(Response::Textual(rejection))
+                    .await
+                    .expect("receiver should always wait for all senders");
+                return;
+            }
+
             let client = Client {
                 id: client_id,
                 response_sender: response_sender.clone(),
@@ -118,13 +151,65 @@ async fn handle_message(
                         .await
                         .expect("receiver should always wait for all senders");
                 }
+                Request::InjectCommand(request) => {
+                    let request = match request {
+                        InjectCommandRequest::Inject {
+                            id,
+                            path,
+                            data,
+                            time_to_live,
+                        } => StorageRequest::InjectCommand {
+                            client,
+                            id,
+                            path,
+                            data,
+                            time_to_live,
+                        },
+                        InjectCommandRequest::Clear { id, path } => {
+                            StorageRequest::ClearCommand { client, id, path }
+                        }
+                        InjectCommandRequest::RunMotionTest {
+                            id,
+                            motion,
+                            repetitions,
+                        } => StorageRequest::RunMotionTest {
+                            client,
+                            id,
+                            motion,
+                            repetitions,
+                        },
+                        InjectCommandRequest::Teleoperate { id, command } => {
+                            StorageRequest::Teleoperate {
+                                client,
+                                id,
+                                command,
+                            }
+                        }
+                    };
+                    parameters_storage_sender
+                        .send(request)
+                        .await
+                        .expect("receiver should always wait for all senders");
+                }
                 Request::Injections(_) => todo!(),
+                Request::Logs(request) => {
+                    logs_sender
+                        .send(ClientRequest { request, client })
+                        .await
+                        .expect("receiver should always wait for all senders");
+                }
                 Request::Parameters(request) => {
                     parameters_sender
                         .send(ClientRequest { request, client })
                         .await
                         .expect("receiver should always wait for all senders");
                 }
+                Request::SetEncoding(encoding) => {
+                    response_sender
+                        .send(Response::SetEncoding(encoding))
+                        .await
+                        .expect("receiver should always wait for all senders");
+                }
             }
         }
         Message::Binary(_) => {
@@ -8,8 +8,8 @@ use std::{
     thread::{self, JoinHandle},
 };
 
-use framework::{multiple_buffer_with_slots, Reader, Writer};
-use parameters::directory::{deserialize, DirectoryError};
+use framework::{multiple_buffer_with_slots, Reader, ThreadScheduling, Writer};
+use parameters::directory::{deserialize_with_provenance, DirectoryError};
 use serde::{de::DeserializeOwned, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 use tokio::{
@@ -22,10 +22,12 @@ use tokio::{
 };
 use tokio_util::sync::CancellationToken;
 
-use crate::server::outputs::router::router;
+use crate::{messages::Metadata, server::outputs::router::router};
 
 use super::{
     acceptor::{acceptor, AcceptError},
+    beacon::{beacon, BeaconError},
+    logs::logs,
     outputs::{provider::provider, Request},
     parameters::{storage::storage, subscriptions::subscriptions},
 };
@@ -34,6 +36,8 @@ use super::{
 pub enum StartError {
     #[error("error while accepting connections")]
     AcceptError(#[source] AcceptError),
+    #[error("error while broadcasting announcements")]
+    BeaconError(#[source] BeaconError),
     #[error("one or more tasks encountered an error: {0:?}")]
     TasksErrored(Vec<StartError>),
     #[error("thread not started")]
@@ -56,9 +60,13 @@ impl<Parameters> Runtime<Parameters>
 where
     Parameters: Clone + DeserializeOwned + Send + Serialize + SerializeHierarchy + Sync + 'static,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn start(
         addresses: Option<impl ToSocketAddrs + Send + Sync + 'static>,
+        privileged_token: Option<String>,
+        thread_scheduling: ThreadScheduling,
         parameters_directory: impl AsRef<Path> + Send + Sync + 'static,
+        parameters_metadata: Metadata,
         body_id: String,
         head_id: String,
         amount_of_parameters_slots: usize,
@@ -69,6 +77,7 @@ where
         let join_handle = thread::Builder::new()
             .name("communication".to_string())
             .spawn(move || {
+                thread_scheduling.apply_to_current_thread();
                 let runtime = match runtime::Builder::new_current_thread().enable_all().build() {
                     Ok(runtime) => Arc::new(runtime),
                     Err(error) => {
@@ -81,9 +90,11 @@ where
 
                 let inner_runtime = runtime.clone();
                 runtime.block_on(async move {
-                    let initial_parameters: Parameters =
-                        match deserialize(&parameters_directory, &body_id, &head_id).await {
-                            Ok(initial_parameters) => initial_parameters,
+                    let (initial_parameters, initial_provenance): (Parameters, _) =
+                        match deserialize_with_provenance(&parameters_directory, &body_id, &head_id)
+                            .await
+                        {
+                            Ok(initial) => initial,
                             Err(source) => {
                                 runtime_sender.send(None).ok().expect(
                                 "successful thread creation should always wait for runtime_sender",
@@ -100,6 +111,7 @@ where
                             .take(amount_of_parameters_slots + 1),
                     );
 
+                    let (logs_sender, logs_receiver) = channel(1);
                     let (parameters_sender, parameters_receiver) = channel(1);
                     let (parameters_storage_sender, parameters_storage_receiver) = channel(1);
 
@@ -113,25 +125,40 @@ where
                         .ok()
                         .expect("successful thread creation should always wait for runtime_sender");
 
+                    // only announce the robot if it is reachable, i.e. addresses is Some
+                    let beacon_task = addresses.is_some().then(|| {
+                        beacon(
+                            outputs_sender.clone(),
+                            body_id.clone(),
+                            head_id.clone(),
+                            keep_running.clone(),
+                        )
+                    });
                     // only start acceptor if addresses is Some
                     let acceptor_task = addresses.map(|addresses| {
                         acceptor(
                             addresses,
                             keep_running.clone(),
                             outputs_sender,
+                            logs_sender,
                             parameters_sender,
+                            parameters_storage_sender.clone(),
+                            privileged_token,
                         )
                     });
                     let outputs_task = router(outputs_receiver);
+                    let logs_task = logs(logs_receiver);
                     let parameters_subscriptions_task = subscriptions(
                         parameters_receiver,
                         parameters_reader,
                         parameters_changed.clone(),
                         parameters_storage_sender,
+                        parameters_metadata,
                     );
                     let parameters_storage_task = storage(
                         parameters_writer,
                         parameters_changed.clone(),
+                        initial_provenance,
                         parameters_storage_receiver,
                         parameters_directory,
                         body_id,
@@ -144,7 +171,12 @@ where
                         Some(acceptor_task) => Some(acceptor_task.await),
                         None => None,
                     };
+                    let beacon_task_result = match beacon_task {
+                        Some(beacon_task) => Some(beacon_task.await),
+                        None => None,
+                    };
                     let outputs_task_result = outputs_task.await;
+                    let logs_task_result = logs_task.await;
                     let parameters_subscriptions_task_result = parameters_subscriptions_task.await;
                     let parameters_storage_task_result = parameters_storage_task.await;
 
@@ -156,7 +188,14 @@ where
                             task_errors.push(StartError::AcceptError(error));
                         }
                     }
+                    if let Some(beacon_task_result) = beacon_task_result {
+                        if let Err(error) = beacon_task_result.expect("failed to join beacon task")
+                        {
+                            task_errors.push(StartError::BeaconError(error));
+                        }
+                    }
                     outputs_task_result.expect("failed to join outputs task");
+                    logs_task_result.expect("failed to join logs task");
                     parameters_subscriptions_task_result.expect("failed to join outputs task");
                     parameters_storage_task_result.expect("failed to join outputs task");
 
@@ -200,6 +239,7 @@ where
     pub fn register_cycler_instance<Outputs>(
         &self,
         cycler_instance: &'static str,
+        metadata: Metadata,
         outputs_changed: Arc<Notify>,
         outputs_reader: Reader<Outputs>,
         subscribed_outputs_writer: Writer<HashSet<String>>,
@@ -210,6 +250,7 @@ where
         provider(
             self.outputs_sender.clone(),
             cycler_instance,
+            metadata,
             outputs_changed,
             outputs_reader,
             subscribed_outputs_writer,
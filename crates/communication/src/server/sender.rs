@@ -1,5 +1,6 @@
 use bincode::serialize;
 use futures_util::{stream::SplitSink, SinkExt};
+use rmp_serde::to_vec_named;
 use serde_json::to_string;
 use tokio::{
     net::TcpStream,
@@ -11,7 +12,7 @@ use tokio_tungstenite::{
 };
 use tokio_util::sync::CancellationToken;
 
-use crate::messages::Response;
+use crate::messages::{EncodedResponse, Encoding, Response, TextualResponse};
 
 use super::connection::ReceiverOrSenderError;
 
@@ -21,42 +22,35 @@ pub async fn sender(
     keep_only_self_running: CancellationToken,
     mut response_receiver: Receiver<Response>,
 ) {
+    let mut encoding = Encoding::Json;
     while let Some(response) = response_receiver.recv().await {
         let message = match response {
-            Response::Textual(textual) => {
-                let message_string = match to_string(&textual) {
-                    Ok(message_string) => message_string,
-                    Err(error) => {
-                        error_sender
-                            .send(ReceiverOrSenderError::JsonNotSerialized(error))
-                            .await
-                            .expect("receiver should always wait for all senders");
-                        keep_only_self_running.cancel();
-                        continue;
-                    }
-                };
-
-                Message::Text(message_string)
-            }
-            Response::Binary(binary) => {
-                let message_bytes = match serialize(&binary) {
-                    Ok(message_bytes) => message_bytes,
-                    Err(error) => {
-                        error_sender
-                            .send(ReceiverOrSenderError::BincodeNotSerialized(error))
-                            .await
-                            .expect("receiver should always wait for all senders");
-                        keep_only_self_running.cancel();
-                        continue;
-                    }
-                };
-
-                Message::Binary(message_bytes)
+            Response::SetEncoding(new_encoding) => {
+                // acknowledge in the previous encoding, everything afterwards uses the new one
+                let message = encode(
+                    EncodedResponse::Textual(TextualResponse::EncodingChanged(new_encoding)),
+                    encoding,
+                );
+                encoding = new_encoding;
+                message
             }
-            Response::Close { code, reason } => Message::Close(Some(CloseFrame {
+            Response::Textual(textual) => encode(EncodedResponse::Textual(textual), encoding),
+            Response::Binary(binary) => encode(EncodedResponse::Binary(binary), encoding),
+            Response::Close { code, reason } => Ok(Message::Close(Some(CloseFrame {
                 code,
                 reason: reason.into(),
-            })),
+            }))),
+        };
+        let message = match message {
+            Ok(message) => message,
+            Err(error) => {
+                error_sender
+                    .send(error)
+                    .await
+                    .expect("receiver should always wait for all senders");
+                keep_only_self_running.cancel();
+                continue;
+            }
         };
 
         match writer.send(message).await {
@@ -71,3 +65,17 @@ pub async fn sender(
         }
     }
 }
+
+fn encode(response: EncodedResponse, encoding: Encoding) -> Result<Message, ReceiverOrSenderError> {
+    match (encoding, response) {
+        (Encoding::Json, EncodedResponse::Textual(textual)) => to_string(&textual)
+            .map(Message::Text)
+            .map_err(ReceiverOrSenderError::JsonNotSerialized),
+        (Encoding::Json, EncodedResponse::Binary(binary)) => serialize(&binary)
+            .map(Message::Binary)
+            .map_err(ReceiverOrSenderError::BincodeNotSerialized),
+        (Encoding::MessagePack, response) => to_vec_named(&response)
+            .map(Message::Binary)
+            .map_err(ReceiverOrSenderError::MessagePackNotSerialized),
+    }
+}
@@ -22,6 +22,8 @@ pub fn context(_attributes: TokenStream, input: TokenStream) -> TokenStream {
         "CreationContext" => ["HardwareInterface", "Parameter", "PersistentState"].as_slice(),
         "CycleContext" => [
             "AdditionalOutput",
+            "AdditionalOutputsSubscribed",
+            "DecimatedInput",
             "HardwareInterface",
             "HistoricInput",
             "Input",
@@ -55,6 +57,50 @@ pub fn context(_attributes: TokenStream, input: TokenStream) -> TokenStream {
                         format!("{struct_name} may not contain members of type {field_type}")
                     );
                 };
+                // defaults and validations of parameters are evaluated by the code generation
+                if let Some(attribute) = field
+                    .attrs
+                    .iter()
+                    .find(|attribute| attribute.path.is_ident("parameter"))
+                {
+                    if field_type != "Parameter" {
+                        abort!(
+                            attribute,
+                            format!("unexpected `parameter` attribute on {field_type}")
+                        );
+                    }
+                }
+                // snapshots of persistent state are written and restored by the cyclers
+                if let Some(attribute) = field
+                    .attrs
+                    .iter()
+                    .find(|attribute| attribute.path.is_ident("snapshot"))
+                {
+                    if field_type != "PersistentState" {
+                        abort!(
+                            attribute,
+                            format!("unexpected `snapshot` attribute on {field_type}")
+                        );
+                    }
+                }
+                // descriptions and units are exposed to debugging tools via communication
+                if let Some(attribute) = field
+                    .attrs
+                    .iter()
+                    .find(|attribute| attribute.path.is_ident("metadata"))
+                {
+                    if !matches!(field_type.as_str(), "Input" | "MainOutput" | "Parameter") {
+                        abort!(
+                            attribute,
+                            format!("unexpected `metadata` attribute on {field_type}")
+                        );
+                    }
+                }
+                field.attrs.retain(|attribute| {
+                    !attribute.path.is_ident("parameter")
+                        && !attribute.path.is_ident("snapshot")
+                        && !attribute.path.is_ident("metadata")
+                });
 
                 match field_type.as_str() {
                     "PerceptionInput" => match &mut first_segment.arguments {
@@ -68,6 +114,21 @@ pub fn context(_attributes: TokenStream, input: TokenStream) -> TokenStream {
                         }
                         _ => abort!(first_segment, "expected exactly three generic parameters"),
                     },
+                    "DecimatedInput" => match &mut first_segment.arguments {
+                        PathArguments::AngleBracketed(arguments) if arguments.args.len() == 3 => {
+                            pop_integer_argument(arguments);
+                            pop_string_argument(arguments);
+                            let data_type = get_data_type(arguments);
+                            let is_optional = is_option(data_type);
+                            into_reference_with_lifetime(data_type, None);
+                            if !is_optional {
+                                embed_into_option(data_type);
+                            }
+                            requires_lifetime_parameter = true;
+                            field.ty = data_type.clone();
+                        }
+                        _ => abort!(first_segment, "expected exactly three generic parameters"),
+                    },
                     "Input" | "RequiredInput" => match &mut first_segment.arguments {
                         PathArguments::AngleBracketed(arguments)
                             if arguments.args.len() == 2 || arguments.args.len() == 3 =>
@@ -91,14 +152,28 @@ pub fn context(_attributes: TokenStream, input: TokenStream) -> TokenStream {
                             "expected exactly two or three generic parameters"
                         ),
                     },
-                    "Parameter" | "PersistentState" => match &mut first_segment.arguments {
+                    "Parameter" => match &mut first_segment.arguments {
+                        PathArguments::AngleBracketed(arguments) if arguments.args.len() == 2 => {
+                            pop_string_argument(arguments);
+                            let data_type = get_data_type(arguments);
+                            into_reference_with_lifetime(data_type, None);
+                            requires_lifetime_parameter = true;
+                            field.ty = data_type.clone();
+                        }
+                        _ => abort!(first_segment, "expected exactly two generic parameters"),
+                    },
+                    // persistent state is always present, optional state is borrowed as a whole
+                    // to allow nodes to set and reset it
+                    "PersistentState" => match &mut first_segment.arguments {
                         PathArguments::AngleBracketed(arguments) if arguments.args.len() == 2 => {
                             pop_string_argument(arguments);
                             let data_type = get_data_type(arguments);
-                            into_reference_with_lifetime(
-                                data_type,
-                                (first_segment.ident == "PersistentState").then(Mut::default),
-                            );
+                            *data_type = Type::Reference(TypeReference {
+                                and_token: Default::default(),
+                                lifetime: Some(Lifetime::new("'context", Span::call_site())),
+                                mutability: Some(Mut::default()),
+                                elem: Box::new(data_type.clone()),
+                            });
                             requires_lifetime_parameter = true;
                             field.ty = data_type.clone();
                         }
@@ -121,6 +196,15 @@ pub fn context(_attributes: TokenStream, input: TokenStream) -> TokenStream {
                             _ => abort!(first_segment, "expected exactly two generic parameters"),
                         }
                     }
+                    "AdditionalOutputsSubscribed" => match &first_segment.arguments {
+                        PathArguments::None => {
+                            field.ty = Type::Path(TypePath {
+                                qself: None,
+                                path: Path::from(format_ident!("bool")),
+                            });
+                        }
+                        _ => abort!(first_segment, "expected no generic parameters"),
+                    },
                     "MainOutput" => {}
                     "HardwareInterface" => {
                         requires_lifetime_parameter = true;
@@ -233,6 +317,28 @@ fn pop_string_argument(arguments: &mut AngleBracketedGenericArguments) {
     }
 }
 
+fn pop_integer_argument(arguments: &mut AngleBracketedGenericArguments) {
+    match arguments.args.pop() {
+        Some(
+            Pair::End(GenericArgument::Const(Expr::Lit(ExprLit {
+                lit: Lit::Int(_), ..
+            })))
+            | Pair::Punctuated(
+                GenericArgument::Const(Expr::Lit(ExprLit {
+                    lit: Lit::Int(_), ..
+                })),
+                _,
+            ),
+        ) => {}
+        Some(argument) => {
+            abort!(argument, "expected integer literal");
+        }
+        _ => {
+            abort!(arguments, "expected exactly at least one generic parameter");
+        }
+    }
+}
+
 fn prepend_lifetime_argument(arguments: &mut AngleBracketedGenericArguments) {
     arguments.args.insert(
         0,
@@ -248,6 +354,16 @@ fn get_data_type(arguments: &mut AngleBracketedGenericArguments) -> &mut Type {
     }
 }
 
+fn is_option(data_type: &Type) -> bool {
+    matches!(
+        data_type,
+        Type::Path(TypePath {
+            path: Path { segments, .. },
+            ..
+        }) if !segments.is_empty() && segments.last().unwrap().ident == "Option"
+    )
+}
+
 fn into_reference_with_lifetime(data_type: &mut Type, mutability: Option<Mut>) {
     let data_type = match data_type {
         Type::Path(TypePath {
@@ -292,6 +408,24 @@ fn embed_into_vec(data_type: &mut Type) {
     });
 }
 
+fn embed_into_option(data_type: &mut Type) {
+    *data_type = Type::Path(TypePath {
+        qself: None,
+        path: Path {
+            leading_colon: None,
+            segments: Punctuated::from_iter([PathSegment {
+                ident: format_ident!("Option"),
+                arguments: PathArguments::AngleBracketed(AngleBracketedGenericArguments {
+                    colon2_token: None,
+                    lt_token: Default::default(),
+                    args: Punctuated::from_iter([GenericArgument::Type(data_type.clone())]),
+                    gt_token: Default::default(),
+                }),
+            }]),
+        },
+    });
+}
+
 fn unwrap_option(data_type: &mut Type) {
     *data_type =
         match data_type {
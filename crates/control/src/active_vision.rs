@@ -6,11 +6,14 @@ use framework::MainOutput;
 use nalgebra::{point, Isometry2, Point2, UnitComplex, Vector2};
 use ordered_float::NotNan;
 use types::{
-    parameters::LookAction as LookActionParameters, BallState, CycleTime, FieldDimensions,
-    Obstacle, ObstacleKind, PointOfInterest,
+    coordinate_systems::{Field, Ground, Transform},
+    parameters::LookAction as LookActionParameters,
+    BallState, CycleTime, FieldDimensions, Obstacle, ObstacleKind, PointOfInterest,
 };
 
 pub struct ActiveVision {
+    /// The field dimensions the field mark positions were generated from
+    field_dimensions: FieldDimensions,
     field_mark_positions: Vec<Point2<f32>>,
     last_point_of_interest_switch: Option<SystemTime>,
     current_point_of_interest: PointOfInterest,
@@ -27,8 +30,9 @@ pub struct CycleContext {
     pub rule_ball: Input<Option<BallState>, "rule_ball_state?">,
     pub cycle_time: Input<CycleTime, "cycle_time">,
     pub obstacles: Input<Vec<Obstacle>, "obstacles">,
+    pub field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
     pub parameters: Parameter<LookActionParameters, "behavior.look_action">,
-    pub robot_to_field: Input<Option<Isometry2<f32>>, "robot_to_field?">,
+    pub robot_to_field: Input<Option<Transform<Ground, Field>>, "robot_to_field?">,
 }
 
 #[context]
@@ -40,6 +44,7 @@ pub struct MainOutputs {
 impl ActiveVision {
     pub fn new(context: CreationContext) -> Result<Self> {
         Ok(Self {
+            field_dimensions: context.field_dimensions.clone(),
             field_mark_positions: generate_field_mark_positions(context.field_dimensions),
             last_point_of_interest_switch: None,
             current_point_of_interest: PointOfInterest::default(),
@@ -47,9 +52,16 @@ impl ActiveVision {
     }
 
     pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        if *context.field_dimensions != self.field_dimensions {
+            self.field_dimensions = context.field_dimensions.clone();
+            self.field_mark_positions = generate_field_mark_positions(context.field_dimensions);
+        }
         let cycle_start_time = context.cycle_time.start_time;
 
-        if let Some(robot_to_field) = context.robot_to_field {
+        if let Some(robot_to_field) = context
+            .robot_to_field
+            .map(|robot_to_field| &robot_to_field.inner)
+        {
             if self.last_point_of_interest_switch.is_none()
                 || cycle_start_time.duration_since(self.last_point_of_interest_switch.unwrap())?
                     > context.parameters.position_of_interest_switch_interval
@@ -73,7 +85,7 @@ impl ActiveVision {
                 }
                 PointOfInterest::Ball => {
                     if let Some(ball_state) = context.ball {
-                        ball_state.ball_in_ground
+                        ball_state.ball_in_ground.inner
                     } else {
                         context.parameters.look_forward_position
                     }
@@ -98,8 +110,8 @@ fn is_position_visible(position: Point2<f32>, parameters: &LookActionParameters)
     UnitComplex::rotation_between(&Vector2::x(), &position.coords)
         .angle()
         .abs()
-        < parameters.angle_threshold
-        && position.coords.norm() < parameters.distance_threshold
+        < parameters.angle_threshold.0
+        && position.coords.norm() < parameters.distance_threshold.0
 }
 
 fn closest_field_mark_visible(
@@ -120,32 +132,35 @@ fn closest_interesting_obstacle_visible(
 ) -> Option<Point2<f32>> {
     obstacles
         .iter()
-        .filter(|obstacle| matches!(obstacle.kind, ObstacleKind::Robot | ObstacleKind::Unknown))
+        .filter(|obstacle| {
+            obstacle.kind.is_robot() || matches!(obstacle.kind, ObstacleKind::Unknown)
+        })
         .map(|obstacle| obstacle.position)
         .filter(|obstacle_position| is_position_visible(*obstacle_position, parameters))
         .min_by_key(|position| NotNan::new(position.coords.norm()).unwrap())
 }
 
 fn generate_field_mark_positions(field_dimensions: &FieldDimensions) -> Vec<Point2<f32>> {
-    let left_center_circle_junction = point![0.0, field_dimensions.center_circle_diameter / 2.0];
-    let right_center_circle_junction = point![0.0, -field_dimensions.center_circle_diameter / 2.0];
-    let left_center_t_junction = point![0.0, field_dimensions.width / 2.0];
-    let right_center_t_junction = point![0.0, -field_dimensions.width / 2.0];
+    let left_center_circle_junction = point![0.0, field_dimensions.center_circle_diameter.0 / 2.0];
+    let right_center_circle_junction =
+        point![0.0, -field_dimensions.center_circle_diameter.0 / 2.0];
+    let left_center_t_junction = point![0.0, field_dimensions.width.0 / 2.0];
+    let right_center_t_junction = point![0.0, -field_dimensions.width.0 / 2.0];
     let left_opponent_penalty_box_corner = point![
-        field_dimensions.length / 2.0 - field_dimensions.penalty_area_length,
-        field_dimensions.penalty_area_width / 2.0
+        field_dimensions.length.0 / 2.0 - field_dimensions.penalty_area_length.0,
+        field_dimensions.penalty_area_width.0 / 2.0
     ];
     let right_opponent_penalty_box_corner = point![
-        field_dimensions.length / 2.0 - field_dimensions.penalty_area_length,
-        -field_dimensions.penalty_area_width / 2.0
+        field_dimensions.length.0 / 2.0 - field_dimensions.penalty_area_length.0,
+        -field_dimensions.penalty_area_width.0 / 2.0
     ];
     let left_own_penalty_box_corner = point![
-        -field_dimensions.length / 2.0 + field_dimensions.penalty_area_length,
-        field_dimensions.penalty_area_width / 2.0
+        -field_dimensions.length.0 / 2.0 + field_dimensions.penalty_area_length.0,
+        field_dimensions.penalty_area_width.0 / 2.0
     ];
     let right_own_penalty_box_corner = point![
-        -field_dimensions.length / 2.0 + field_dimensions.penalty_area_length,
-        -field_dimensions.penalty_area_width / 2.0
+        -field_dimensions.length.0 / 2.0 + field_dimensions.penalty_area_length.0,
+        -field_dimensions.penalty_area_width.0 / 2.0
     ];
     vec![
         left_center_circle_junction,
@@ -4,7 +4,9 @@ use color_eyre::Result;
 use context_attribute::context;
 use filtering::kalman_filter::KalmanFilter;
 use framework::{AdditionalOutput, HistoricInput, MainOutput, PerceptionInput};
-use nalgebra::{matrix, vector, Isometry2, Matrix2, Matrix2x4, Matrix4, Matrix4x2, Point2};
+use nalgebra::{
+    matrix, vector, Isometry2, Matrix2, Matrix2x4, Matrix4, Matrix4x2, Point2, Vector2,
+};
 use projection::Projection;
 use types::{
     ball_filter::Hypothesis, is_above_limbs,
@@ -13,6 +15,8 @@ use types::{
     CameraMatrix, Circle, CycleTime, FieldDimensions, Limb, ProjectedLimbs, SensorData,
 };
 
+const IMAGE_SIZE: Vector2<f32> = Vector2::new(640.0, 480.0);
+
 pub struct BallFilter {
     hypotheses: Vec<Hypothesis>,
 }
@@ -41,6 +45,7 @@ pub struct CycleContext {
 
     pub field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
     pub ball_filter_configuration: Parameter<BallFilterConfiguration, "ball_filter">,
+    pub bottom_half_resolution: Parameter<bool, "image_receiver.vision_bottom.half_resolution">,
 
     pub balls_bottom: PerceptionInput<Option<Vec<Ball>>, "VisionBottom", "balls?">,
     pub balls_top: PerceptionInput<Option<Vec<Ball>>, "VisionTop", "balls?">,
@@ -106,14 +111,16 @@ impl BallFilter {
                 .and_then(|limbs| *limbs);
             self.decay_hypotheses(
                 camera_matrices,
+                bottom_image_scale(context),
                 projected_limbs_bottom,
-                context.field_dimensions.ball_radius,
+                context.field_dimensions.ball_radius.0,
                 context.ball_filter_configuration,
             );
 
             for ball in balls {
                 self.update_hypotheses_with_measurement(
                     ball.position,
+                    ball.covariance,
                     *detection_time,
                     context.ball_filter_configuration,
                 );
@@ -134,7 +141,7 @@ impl BallFilter {
         context
             .ball_filter_hypotheses
             .fill_if_subscribed(|| self.hypotheses.clone());
-        let ball_radius = context.field_dimensions.ball_radius;
+        let ball_radius = context.field_dimensions.ball_radius.0;
 
         let ball_positions = self
             .hypotheses
@@ -142,15 +149,22 @@ impl BallFilter {
             .map(|hypothesis| hypothesis.selected_ball_position(context.ball_filter_configuration))
             .collect::<Vec<_>>();
         context.filtered_balls_in_image_top.fill_if_subscribed(|| {
-            project_to_image(&ball_positions, &context.camera_matrices.top, ball_radius)
+            project_to_image(
+                &ball_positions,
+                &context.camera_matrices.top,
+                ball_radius,
+                IMAGE_SIZE,
+            )
         });
+        let bottom_image_scale = bottom_image_scale(&context);
         context
             .filtered_balls_in_image_bottom
             .fill_if_subscribed(|| {
                 project_to_image(
                     &ball_positions,
-                    &context.camera_matrices.bottom,
+                    &context.camera_matrices.bottom.to_scaled(bottom_image_scale),
                     ball_radius,
+                    IMAGE_SIZE * bottom_image_scale,
                 )
             });
 
@@ -178,22 +192,31 @@ impl BallFilter {
     fn decay_hypotheses(
         &mut self,
         camera_matrices: Option<&CameraMatrices>,
+        bottom_image_scale: f32,
         projected_limbs: Option<&ProjectedLimbs>,
         ball_radius: f32,
         configuration: &BallFilterConfiguration,
     ) {
+        let bottom_camera_matrix = camera_matrices
+            .map(|camera_matrices| camera_matrices.bottom.to_scaled(bottom_image_scale));
         for hypothesis in self.hypotheses.iter_mut() {
-            let ball_in_view = match (camera_matrices.as_ref(), projected_limbs.as_ref()) {
-                (Some(camera_matrices), Some(projected_limbs)) => {
+            let ball_in_view = match (
+                camera_matrices,
+                bottom_camera_matrix.as_ref(),
+                projected_limbs,
+            ) {
+                (Some(camera_matrices), Some(bottom_camera_matrix), Some(projected_limbs)) => {
                     is_visible_to_camera(
                         hypothesis,
-                        &camera_matrices.bottom,
+                        bottom_camera_matrix,
+                        IMAGE_SIZE * bottom_image_scale,
                         ball_radius,
                         &projected_limbs.limbs,
                         configuration,
                     ) || is_visible_to_camera(
                         hypothesis,
                         &camera_matrices.top,
+                        IMAGE_SIZE,
                         ball_radius,
                         &[],
                         configuration,
@@ -255,20 +278,19 @@ impl BallFilter {
     fn update_hypothesis_with_measurement(
         hypothesis: &mut Hypothesis,
         detected_position: Point2<f32>,
+        detected_covariance: Matrix2<f32>,
         detection_time: SystemTime,
         configuration: &BallFilterConfiguration,
     ) {
         hypothesis.moving_state.update(
             Matrix2x4::identity(),
             detected_position.coords,
-            Matrix2::from_diagonal(&configuration.measurement_noise_moving)
-                * detected_position.coords.norm_squared(),
+            detected_covariance * configuration.measurement_noise_factor_moving,
         );
         hypothesis.resting_state.update(
             Matrix2x4::identity(),
             detected_position.coords,
-            Matrix2::from_diagonal(&configuration.measurement_noise_resting)
-                * detected_position.coords.norm_squared(),
+            detected_covariance * configuration.measurement_noise_factor_resting,
         );
 
         if !hypothesis.is_resting(configuration) {
@@ -281,6 +303,7 @@ impl BallFilter {
     fn update_hypotheses_with_measurement(
         &mut self,
         detected_position: Point2<f32>,
+        detected_covariance: Matrix2<f32>,
         detection_time: SystemTime,
         configuration: &BallFilterConfiguration,
     ) {
@@ -303,6 +326,7 @@ impl BallFilter {
             Self::update_hypothesis_with_measurement(
                 hypothesis,
                 detected_position,
+                detected_covariance,
                 detection_time,
                 configuration,
             )
@@ -352,9 +376,9 @@ impl BallFilter {
             let selected_position = hypothesis.selected_ball_position(configuration).position;
             let is_inside_field = {
                 selected_position.coords.x.abs()
-                    < field_dimensions.length / 2.0 + field_dimensions.border_strip_width
+                    < field_dimensions.length.0 / 2.0 + field_dimensions.border_strip_width.0
                     && selected_position.y.abs()
-                        < field_dimensions.width / 2.0 + field_dimensions.border_strip_width
+                        < field_dimensions.width.0 / 2.0 + field_dimensions.border_strip_width.0
             };
             now.duration_since(hypothesis.last_update)
                 .expect("Time has run backwards")
@@ -404,6 +428,7 @@ fn project_to_image(
     ball_position: &[BallPosition],
     camera_matrix: &CameraMatrix,
     ball_radius: f32,
+    image_size: Vector2<f32>,
 ) -> Vec<Circle> {
     ball_position
         .iter()
@@ -412,7 +437,11 @@ fn project_to_image(
                 .ground_with_z_to_pixel(ball_position.position, ball_radius)
                 .ok()?;
             let radius = camera_matrix
-                .get_pixel_radius(ball_radius, position_in_image, vector![640, 480])
+                .get_pixel_radius(
+                    ball_radius,
+                    position_in_image,
+                    image_size.map(|dimension| dimension as u32),
+                )
                 .ok()?;
             Some(Circle {
                 center: position_in_image,
@@ -422,9 +451,20 @@ fn project_to_image(
         .collect()
 }
 
+/// Bottom camera images are processed in half resolution if configured, image coordinates have to
+/// be scaled accordingly
+fn bottom_image_scale(context: &CycleContext) -> f32 {
+    if *context.bottom_half_resolution {
+        0.5
+    } else {
+        1.0
+    }
+}
+
 fn is_visible_to_camera(
     hypothesis: &Hypothesis,
     camera_matrix: &CameraMatrix,
+    image_size: Vector2<f32>,
     ball_radius: f32,
     projected_limbs: &[Limb],
     configuration: &BallFilterConfiguration,
@@ -435,7 +475,7 @@ fn is_visible_to_camera(
             Ok(position_in_image) => position_in_image,
             Err(_) => return false,
         };
-    (0.0..640.0).contains(&position_in_image.x)
-        && (0.0..480.0).contains(&position_in_image.y)
+    (0.0..image_size.x).contains(&position_in_image.x)
+        && (0.0..image_size.y).contains(&position_in_image.y)
         && is_above_limbs(position_in_image, projected_limbs)
 }
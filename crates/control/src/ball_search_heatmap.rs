@@ -0,0 +1,271 @@
+use std::time::SystemTime;
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::{AdditionalOutput, MainOutput, PerceptionInput};
+use nalgebra::{distance, Isometry2, Point2, UnitComplex};
+use spl_network_messages::PlayerNumber;
+use types::{
+    coordinate_systems::{Field, Ground, Transform},
+    messages::IncomingMessage,
+    parameters::BallSearchHeatmap as BallSearchHeatmapParameters,
+    BallSearchHeatmap as Heatmap, CycleTime, FieldDimensions, Obstacle, Players, SensorData,
+};
+
+/// Tracks where on the field the ball was not seen recently by the own and the teammates' cameras
+pub struct BallSearchHeatmap {
+    heatmap: Heatmap,
+    teammate_poses: Players<Option<(SystemTime, Isometry2<f32>)>>,
+}
+
+#[context]
+pub struct CreationContext {
+    pub field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    pub parameters: Parameter<BallSearchHeatmapParameters, "ball_search_heatmap">,
+}
+
+#[context]
+pub struct CycleContext {
+    pub ball_search_heatmap: AdditionalOutput<Heatmap, "ball_search_heatmap">,
+
+    pub cycle_time: Input<CycleTime, "cycle_time">,
+    pub obstacles: Input<Vec<Obstacle>, "obstacles">,
+    pub robot_to_field: Input<Option<Transform<Ground, Field>>, "robot_to_field?">,
+    pub sensor_data: Input<SensorData, "sensor_data">,
+    pub network_message: PerceptionInput<IncomingMessage, "SplNetwork", "message">,
+
+    pub parameters: Parameter<BallSearchHeatmapParameters, "ball_search_heatmap">,
+    pub player_number: Parameter<PlayerNumber, "player_number">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    /// Center of the most promising cell to search the ball in, in field coordinates
+    pub ball_search_position: MainOutput<Option<Point2<f32>>>,
+}
+
+impl BallSearchHeatmap {
+    pub fn new(context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            heatmap: Heatmap::new(context.field_dimensions, context.parameters.cell_size),
+            teammate_poses: Default::default(),
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let parameters = context.parameters;
+
+        let recovery = context.cycle_time.last_cycle_duration.as_secs_f32()
+            / parameters.information_recovery_duration.as_secs_f32();
+        for information in &mut self.heatmap.information {
+            *information = (*information + recovery).min(1.0);
+        }
+
+        for (received_at, messages) in &context.network_message.persistent {
+            for message in messages {
+                let IncomingMessage::Spl(message) = message else {
+                    continue;
+                };
+                if message.player_number == *context.player_number {
+                    continue;
+                }
+                self.teammate_poses[message.player_number] =
+                    (!message.fallen).then_some((*received_at, message.robot_to_field));
+            }
+        }
+
+        let own_view = context.robot_to_field.map(|robot_to_field| {
+            robot_to_field.inner * UnitComplex::new(context.sensor_data.positions.head.yaw)
+        });
+        let teammates: Vec<_> = self
+            .teammate_poses
+            .iter()
+            .filter_map(|(player_number, teammate_pose)| {
+                teammate_pose.map(|pose| (player_number, pose))
+            })
+            .filter(|(_, (received_at, _))| {
+                now.duration_since(*received_at)
+                    .map_or(true, |age| age < parameters.teammate_pose_timeout)
+            })
+            .map(|(player_number, (_, robot_to_field))| (player_number, robot_to_field))
+            .collect();
+        let teammate_views = teammates.iter().map(|(_, robot_to_field)| *robot_to_field);
+        let views: Vec<_> = own_view.into_iter().chain(teammate_views).collect();
+        for index in 0..self.heatmap.information.len() {
+            let cell_center = self.heatmap.cell_center(index);
+            if views
+                .iter()
+                .any(|view| is_in_view(*view, cell_center, parameters))
+            {
+                self.heatmap.information[index] = 0.0;
+            }
+        }
+
+        // teammates with lower player numbers choose first, such that searchers spread out
+        let mut claimed_cells = Vec::new();
+        for (_, teammate_to_field) in teammates
+            .iter()
+            .filter(|(player_number, _)| player_number < context.player_number)
+        {
+            claimed_cells.extend(most_promising_cell(
+                &self.heatmap,
+                *teammate_to_field,
+                &[],
+                &claimed_cells,
+                parameters,
+            ));
+        }
+        let ball_search_position = context.robot_to_field.and_then(|robot_to_field| {
+            most_promising_cell(
+                &self.heatmap,
+                robot_to_field.inner,
+                context.obstacles,
+                &claimed_cells,
+                parameters,
+            )
+        });
+
+        context
+            .ball_search_heatmap
+            .fill_if_subscribed(|| self.heatmap.clone());
+
+        Ok(MainOutputs {
+            ball_search_position: ball_search_position.into(),
+        })
+    }
+}
+
+fn is_in_view(
+    view_to_field: Isometry2<f32>,
+    point_in_field: Point2<f32>,
+    parameters: &BallSearchHeatmapParameters,
+) -> bool {
+    let point_in_view = view_to_field.inverse() * point_in_field;
+    let angle = point_in_view.y.atan2(point_in_view.x);
+    point_in_view.coords.norm() <= parameters.maximum_view_distance
+        && angle.abs() <= parameters.field_of_view_angle / 2.0
+}
+
+/// Chooses the cell with the most information, preferring close cells and skipping obstacles and
+/// cells near the ones already claimed by other searchers
+fn most_promising_cell(
+    heatmap: &Heatmap,
+    robot_to_field: Isometry2<f32>,
+    obstacles: &[Obstacle],
+    claimed_cells: &[Point2<f32>],
+    parameters: &BallSearchHeatmapParameters,
+) -> Option<Point2<f32>> {
+    let robot_position = robot_to_field * Point2::origin();
+    let obstacle_positions: Vec<_> = obstacles
+        .iter()
+        .map(|obstacle| {
+            (
+                robot_to_field * obstacle.position,
+                obstacle.radius_at_foot_height,
+            )
+        })
+        .collect();
+    heatmap
+        .cells()
+        .filter(|(_, cell_center, _)| {
+            obstacle_positions
+                .iter()
+                .all(|(position, radius)| distance(cell_center, position) > *radius)
+        })
+        .filter(|(_, cell_center, _)| {
+            claimed_cells.iter().all(|claimed_cell| {
+                distance(cell_center, claimed_cell) >= parameters.minimum_distance_between_searchers
+            })
+        })
+        .map(|(_, cell_center, information)| {
+            let distance_to_robot = distance(&robot_position, &cell_center);
+            (
+                cell_center,
+                information - parameters.distance_penalty * distance_to_robot,
+            )
+        })
+        .max_by(|(_, score), (_, other_score)| score.total_cmp(other_score))
+        .map(|(cell_center, _)| cell_center)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use nalgebra::{point, vector};
+    use types::units::Meters;
+
+    use super::*;
+
+    fn parameters() -> BallSearchHeatmapParameters {
+        BallSearchHeatmapParameters {
+            field_of_view_angle: FRAC_PI_2,
+            maximum_view_distance: 3.0,
+            distance_penalty: 0.1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_points_within_the_view_cone_are_seen() {
+        let view_to_field = Isometry2::new(vector![1.0, 0.0], FRAC_PI_2);
+
+        assert!(is_in_view(view_to_field, point![1.0, 2.0], &parameters()));
+        assert!(!is_in_view(view_to_field, point![1.0, -2.0], &parameters()));
+        assert!(!is_in_view(view_to_field, point![3.5, 1.0], &parameters()));
+        assert!(!is_in_view(view_to_field, point![1.0, 5.0], &parameters()));
+    }
+
+    #[test]
+    fn close_unseen_cells_are_preferred() {
+        let field_dimensions = FieldDimensions {
+            length: Meters(4.0),
+            width: Meters(2.0),
+            ..Default::default()
+        };
+        let mut heatmap = Heatmap::new(&field_dimensions, 1.0);
+        heatmap.information.fill(0.0);
+        heatmap.information[0] = 1.0;
+        heatmap.information[7] = 1.0;
+
+        let position = most_promising_cell(
+            &heatmap,
+            Isometry2::new(vector![1.5, 0.5], 0.0),
+            &[],
+            &[],
+            &parameters(),
+        );
+
+        assert_eq!(position, Some(heatmap.cell_center(7)));
+    }
+
+    #[test]
+    fn cells_claimed_by_other_searchers_are_avoided() {
+        let field_dimensions = FieldDimensions {
+            length: Meters(4.0),
+            width: Meters(2.0),
+            ..Default::default()
+        };
+        let mut heatmap = Heatmap::new(&field_dimensions, 1.0);
+        heatmap.information.fill(0.0);
+        heatmap.information[0] = 1.0;
+        heatmap.information[6] = 1.0;
+        heatmap.information[7] = 1.0;
+        let parameters = BallSearchHeatmapParameters {
+            minimum_distance_between_searchers: 2.0,
+            ..parameters()
+        };
+
+        let position = most_promising_cell(
+            &heatmap,
+            Isometry2::new(vector![1.5, 0.5], 0.0),
+            &[],
+            &[heatmap.cell_center(7)],
+            &parameters,
+        );
+
+        assert_eq!(position, Some(heatmap.cell_center(0)));
+    }
+}
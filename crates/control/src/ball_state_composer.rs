@@ -1,14 +1,15 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use color_eyre::Result;
 use context_attribute::context;
 use filtering::hysteresis::greater_than_with_hysteresis;
 use framework::MainOutput;
-use nalgebra::{point, Isometry2, Point2, Vector2};
+use nalgebra::{point, Vector2};
 use spl_network_messages::{SubState, Team};
 use types::{
-    BallPosition, BallState, CycleTime, FieldDimensions, GameControllerState, PenaltyShotDirection,
-    PrimaryState, Side,
+    coordinate_systems::{Field, Ground, Point, Transform},
+    BallPosition, BallSource, BallState, CycleTime, FieldDimensions, GameControllerState,
+    PenaltyShotDirection, PrimaryState, Side,
 };
 
 pub struct BallStateComposer {
@@ -23,11 +24,12 @@ pub struct CycleContext {
     pub cycle_time: Input<CycleTime, "cycle_time">,
     pub ball_position: Input<Option<BallPosition>, "ball_position?">,
     pub penalty_shot_direction: Input<Option<PenaltyShotDirection>, "penalty_shot_direction?">,
-    pub robot_to_field: Input<Option<Isometry2<f32>>, "robot_to_field?">,
+    pub robot_to_field: Input<Option<Transform<Ground, Field>>, "robot_to_field?">,
     pub team_ball: Input<Option<BallPosition>, "team_ball?">,
     pub primary_state: Input<PrimaryState, "primary_state">,
     pub game_controller_state: Input<Option<GameControllerState>, "game_controller_state?">,
     pub field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    pub team_ball_timeout: Parameter<Duration, "ball_state_composer.team_ball_timeout">,
 }
 
 #[context]
@@ -50,22 +52,36 @@ impl BallStateComposer {
             context.team_ball,
             context.robot_to_field,
         ) {
-            (Some(ball_position), _, Some(robot_to_field)) => Some(create_ball_state(
-                ball_position.position,
-                robot_to_field * ball_position.position,
-                ball_position.velocity,
-                ball_position.last_seen,
-                &mut self.last_ball_field_side,
-                context.penalty_shot_direction.copied(),
-            )),
-            (None, Some(ball_position), Some(robot_to_field)) => Some(create_ball_state(
-                robot_to_field.inverse() * ball_position.position,
-                ball_position.position,
-                ball_position.velocity,
-                ball_position.last_seen,
-                &mut self.last_ball_field_side,
-                context.penalty_shot_direction.copied(),
-            )),
+            (Some(ball_position), _, Some(robot_to_field)) => {
+                let ball_in_ground = Point::wrap(ball_position.position);
+                Some(create_ball_state(
+                    ball_in_ground,
+                    *robot_to_field * ball_in_ground,
+                    ball_position.velocity,
+                    ball_position.last_seen,
+                    ball_position.confidence,
+                    BallSource::Own,
+                    &mut self.last_ball_field_side,
+                    context.penalty_shot_direction.copied(),
+                ))
+            }
+            (None, Some(ball_position), Some(robot_to_field)) => {
+                let ball_in_field = Point::wrap(ball_position.position);
+                Some(create_ball_state(
+                    robot_to_field.inverse() * ball_in_field,
+                    ball_in_field,
+                    ball_position.velocity,
+                    ball_position.last_seen,
+                    team_ball_confidence(
+                        ball_position,
+                        context.cycle_time.start_time,
+                        *context.team_ball_timeout,
+                    ),
+                    BallSource::Team,
+                    &mut self.last_ball_field_side,
+                    context.penalty_shot_direction.copied(),
+                ))
+            }
             _ => None,
         };
 
@@ -89,23 +105,28 @@ impl BallStateComposer {
                     // If uncertain get ready to defend own goal
                     Team::Uncertain => -1.0,
                 };
-                let penalty_spot_x = context.field_dimensions.length / 2.0
-                    - context.field_dimensions.penalty_marker_distance;
-                let penalty_spot_location = point![side_factor * penalty_spot_x, 0.0];
+                let penalty_spot_location = Point::wrap(point![
+                    side_factor * context.field_dimensions.penalty_spot_x().0,
+                    0.0
+                ]);
                 Some(create_ball_state(
                     robot_to_field.inverse() * penalty_spot_location,
                     penalty_spot_location,
                     Vector2::zeros(),
                     context.cycle_time.start_time,
+                    1.0,
+                    BallSource::Rules,
                     &mut self.last_ball_field_side,
                     context.penalty_shot_direction.copied(),
                 ))
             }
             (PrimaryState::Ready, Some(robot_to_field), ..) => Some(create_ball_state(
-                robot_to_field.inverse() * Point2::origin(),
-                Point2::origin(),
+                robot_to_field.inverse() * Point::origin(),
+                Point::origin(),
                 Vector2::zeros(),
                 context.cycle_time.start_time,
+                1.0,
+                BallSource::Rules,
                 &mut self.last_ball_field_side,
                 context.penalty_shot_direction.copied(),
             )),
@@ -119,16 +140,30 @@ impl BallStateComposer {
     }
 }
 
+/// Fades out the confidence of the team ball until it is not trusted anymore after the timeout
+fn team_ball_confidence(team_ball: &BallPosition, now: SystemTime, timeout: Duration) -> f32 {
+    if timeout.is_zero() {
+        return 0.0;
+    }
+    let age = now.duration_since(team_ball.last_seen).unwrap_or_default();
+    let remaining_trust = 1.0 - age.as_secs_f32() / timeout.as_secs_f32();
+    team_ball.confidence * remaining_trust.clamp(0.0, 1.0)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn create_ball_state(
-    ball_in_ground: Point2<f32>,
-    ball_in_field: Point2<f32>,
+    ball_in_ground: Point<Ground>,
+    ball_in_field: Point<Field>,
     ball_in_ground_velocity: Vector2<f32>,
-    last_seen_ball: SystemTime,
+    last_seen: SystemTime,
+    confidence: f32,
+    source: BallSource,
     last_ball_field_side: &mut Side,
     penalty_shot_direction: Option<PenaltyShotDirection>,
 ) -> BallState {
     let was_in_left_half = *last_ball_field_side == Side::Left;
-    let is_in_left_half = greater_than_with_hysteresis(was_in_left_half, ball_in_field.y, 0.0, 0.1);
+    let is_in_left_half =
+        greater_than_with_hysteresis(was_in_left_half, ball_in_field.inner.y, 0.0, 0.1);
     let side = if is_in_left_half {
         Side::Left
     } else {
@@ -140,7 +175,9 @@ fn create_ball_state(
         ball_in_ground,
         ball_in_field,
         ball_in_ground_velocity,
-        last_seen_ball,
+        last_seen,
+        confidence,
+        source,
         field_side,
         penalty_shot_direction,
     }
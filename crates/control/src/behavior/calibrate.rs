@@ -9,3 +9,29 @@ pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::test_support::WorldStateBuilder;
+
+    use super::*;
+
+    #[test]
+    fn stands_with_unstiff_head_in_calibration() {
+        let calibration = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Calibration)
+            .build();
+        let initial = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Initial)
+            .build();
+
+        assert_eq!(
+            execute(&calibration),
+            Some(MotionCommand::Stand {
+                head: types::HeadMotion::Unstiff,
+                is_energy_saving: false,
+            })
+        );
+        assert_eq!(execute(&initial), None);
+    }
+}
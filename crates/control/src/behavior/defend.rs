@@ -1,11 +1,13 @@
 use std::ops::Range;
 
 use framework::AdditionalOutput;
-use nalgebra::{distance, point, vector, Isometry2, Point2};
+use nalgebra::{distance, point, vector, Isometry2};
 use spl_network_messages::{GamePhase, SubState, Team};
 use types::{
-    parameters::RolePositions, rotate_towards, BallState, FieldDimensions, GameControllerState,
-    Line, MotionCommand, PathObstacle, Side, WorldState,
+    coordinate_systems::{Field, Ground, Point, Pose},
+    parameters::RolePositions,
+    rotate_towards, BallState, FieldDimensions, GameControllerState, Line, MotionCommand,
+    OrientationMode, PathObstacle, Side, WorldState,
 };
 
 use super::{head::LookAction, walk_to_pose::WalkAndStand};
@@ -37,11 +39,23 @@ impl<'cycle> Defend<'cycle> {
 
     fn with_pose(
         &self,
-        pose: Isometry2<f32>,
+        pose: Pose<Ground>,
         path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
     ) -> Option<MotionCommand> {
-        self.walk_and_stand
-            .execute(pose, self.look_action.execute(), path_obstacles_output)
+        let mut motion_command =
+            self.walk_and_stand
+                .execute(pose, self.look_action.execute(), path_obstacles_output)?;
+        if let MotionCommand::Walk {
+            orientation_mode, ..
+        } = &mut motion_command
+        {
+            if let (OrientationMode::Override(_), Some(ball)) =
+                (*orientation_mode, self.world_state.ball)
+            {
+                *orientation_mode = OrientationMode::FaceTowards(ball.ball_in_ground.inner);
+            }
+        }
+        Some(motion_command)
     }
 
     pub fn left(
@@ -91,7 +105,7 @@ fn defend_left_pose(
     world_state: &WorldState,
     field_dimensions: &FieldDimensions,
     role_positions: &RolePositions,
-) -> Option<Isometry2<f32>> {
+) -> Option<Pose<Ground>> {
     let robot_to_field = world_state.robot.robot_to_field?;
     let ball = world_state
         .rule_ball
@@ -99,7 +113,7 @@ fn defend_left_pose(
         .unwrap_or_else(|| BallState::new_at_center(robot_to_field));
 
     let position_to_defend = point![
-        -field_dimensions.length / 2.0,
+        -field_dimensions.length.0 / 2.0,
         role_positions.defender_y_offset
     ];
     let mut distance_to_target = if ball.field_side == Side::Left {
@@ -112,7 +126,11 @@ fn defend_left_pose(
         world_state.game_controller_state,
         field_dimensions,
     );
-    let defend_pose = block_on_circle(ball.ball_in_field, position_to_defend, distance_to_target);
+    let defend_pose = block_on_circle(
+        ball.ball_in_field,
+        Point::wrap(position_to_defend),
+        distance_to_target,
+    );
     Some(robot_to_field.inverse() * defend_pose)
 }
 
@@ -120,7 +138,7 @@ fn defend_right_pose(
     world_state: &WorldState,
     field_dimensions: &FieldDimensions,
     role_positions: &RolePositions,
-) -> Option<Isometry2<f32>> {
+) -> Option<Pose<Ground>> {
     let robot_to_field = world_state.robot.robot_to_field?;
     let ball = world_state
         .rule_ball
@@ -128,7 +146,7 @@ fn defend_right_pose(
         .unwrap_or_else(|| BallState::new_at_center(robot_to_field));
 
     let position_to_defend = point![
-        -field_dimensions.length / 2.0,
+        -field_dimensions.length.0 / 2.0,
         -role_positions.defender_y_offset
     ];
     let mut distance_to_target = if ball.field_side == Side::Right {
@@ -141,7 +159,11 @@ fn defend_right_pose(
         world_state.game_controller_state,
         field_dimensions,
     );
-    let defend_pose = block_on_circle(ball.ball_in_field, position_to_defend, distance_to_target);
+    let defend_pose = block_on_circle(
+        ball.ball_in_field,
+        Point::wrap(position_to_defend),
+        distance_to_target,
+    );
     Some(robot_to_field.inverse() * defend_pose)
 }
 
@@ -149,7 +171,7 @@ fn defend_penalty_kick(
     world_state: &WorldState,
     field_dimensions: &FieldDimensions,
     role_positions: &RolePositions,
-) -> Option<Isometry2<f32>> {
+) -> Option<Pose<Ground>> {
     let robot_to_field = world_state.robot.robot_to_field?;
     let ball = world_state
         .rule_ball
@@ -157,7 +179,7 @@ fn defend_penalty_kick(
         .unwrap_or_else(|| BallState::new_at_center(robot_to_field));
 
     let position_to_defend = point![
-        (-field_dimensions.length + field_dimensions.penalty_area_length) / 2.0,
+        (-field_dimensions.length.0 + field_dimensions.penalty_area_length.0) / 2.0,
         0.0
     ];
     let mut distance_to_target = if ball.field_side == Side::Left {
@@ -171,7 +193,11 @@ fn defend_penalty_kick(
         field_dimensions,
     );
 
-    let defend_pose = block_on_circle(ball.ball_in_field, position_to_defend, distance_to_target);
+    let defend_pose = block_on_circle(
+        ball.ball_in_field,
+        Point::wrap(position_to_defend),
+        distance_to_target,
+    );
     Some(robot_to_field.inverse() * defend_pose)
 }
 
@@ -179,7 +205,7 @@ fn defend_goal_pose(
     world_state: &WorldState,
     field_dimensions: &FieldDimensions,
     role_positions: &RolePositions,
-) -> Option<Isometry2<f32>> {
+) -> Option<Pose<Ground>> {
     let robot_to_field = world_state.robot.robot_to_field?;
     let ball = world_state
         .rule_ball
@@ -204,11 +230,11 @@ fn defend_goal_pose(
         _ => role_positions.keeper_x_offset,
     };
 
-    let position_to_defend = point![-field_dimensions.length / 2.0 - 1.0, 0.0];
+    let position_to_defend = point![-field_dimensions.length.0 / 2.0 - 1.0, 0.0];
     let defend_pose = block_on_line(
         ball.ball_in_field,
-        position_to_defend,
-        -field_dimensions.length / 2.0 + keeper_x_offset,
+        Point::wrap(position_to_defend),
+        -field_dimensions.length.0 / 2.0 + keeper_x_offset,
         -0.7..0.7,
     );
     Some(robot_to_field.inverse() * defend_pose)
@@ -218,44 +244,46 @@ fn defend_kick_off_pose(
     world_state: &WorldState,
     field_dimensions: &FieldDimensions,
     role_positions: &RolePositions,
-) -> Option<Isometry2<f32>> {
+) -> Option<Pose<Ground>> {
     let robot_to_field = world_state.robot.robot_to_field?;
     let absolute_ball_position = match world_state.ball {
         Some(ball) => ball.ball_in_field,
-        None => Point2::origin(),
+        None => Point::origin(),
     };
-    let position_to_defend = point![-field_dimensions.length / 2.0, 0.0];
-    let center_circle_radius = field_dimensions.center_circle_diameter / 2.0;
-    let distance_to_target = distance(&position_to_defend, &absolute_ball_position)
+    let position_to_defend = point![-field_dimensions.length.0 / 2.0, 0.0];
+    let center_circle_radius = field_dimensions.center_circle_diameter.0 / 2.0;
+    let distance_to_target = distance(&position_to_defend, &absolute_ball_position.inner)
         - center_circle_radius
         - role_positions.striker_distance_to_non_free_center_circle;
     let defend_pose = block_on_circle(
         absolute_ball_position,
-        position_to_defend,
+        Point::wrap(position_to_defend),
         distance_to_target,
     );
     Some(robot_to_field.inverse() * defend_pose)
 }
 
 pub fn block_on_circle(
-    ball_position: Point2<f32>,
-    target: Point2<f32>,
+    ball_position: Point<Field>,
+    target: Point<Field>,
     distance_to_target: f32,
-) -> Isometry2<f32> {
+) -> Pose<Field> {
+    let (ball_position, target) = (ball_position.inner, target.inner);
     let target_to_ball = ball_position - target;
     let block_position = target + target_to_ball.normalize() * distance_to_target;
-    Isometry2::new(
+    Pose::wrap(Isometry2::new(
         block_position.coords,
         rotate_towards(block_position, ball_position).angle(),
-    )
+    ))
 }
 
 fn block_on_line(
-    ball_position: Point2<f32>,
-    target: Point2<f32>,
+    ball_position: Point<Field>,
+    target: Point<Field>,
     defense_line_x: f32,
     defense_line_y_range: Range<f32>,
-) -> Isometry2<f32> {
+) -> Pose<Field> {
+    let (ball_position, target) = (ball_position.inner, target.inner);
     let is_ball_in_front_of_defense_line = defense_line_x < ball_position.x;
     if is_ball_in_front_of_defense_line {
         let defense_line = Line(
@@ -270,19 +298,19 @@ fn block_on_line(
                 .y
                 .clamp(defense_line_y_range.start, defense_line_y_range.end)
         ];
-        Isometry2::new(
+        Pose::wrap(Isometry2::new(
             defense_position.coords,
             rotate_towards(defense_position, ball_position).angle(),
-        )
+        ))
     } else {
         let defense_position = point![
             defense_line_x,
             (defense_line_y_range.start + defense_line_y_range.end) / 2.0
         ];
-        Isometry2::new(
+        Pose::wrap(Isometry2::new(
             defense_position.coords,
             rotate_towards(defense_position, ball_position).angle(),
-        )
+        ))
     }
 }
 
@@ -297,11 +325,176 @@ fn penalty_kick_defender_radius(
         ..
     }) = game_controller_state
     {
-        let half_penalty_width = field_dimensions.penalty_area_width / 2.0;
+        let half_penalty_width = field_dimensions.penalty_area_width.0 / 2.0;
         let minimum_penalty_defender_radius =
-            vector![field_dimensions.penalty_area_length, half_penalty_width].norm();
+            vector![field_dimensions.penalty_area_length.0, half_penalty_width].norm();
         distance_to_target.max(minimum_penalty_defender_radius)
     } else {
         distance_to_target
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::Point2;
+    use types::{
+        coordinate_systems::Transform,
+        parameters::{PathPlanning, WalkAndStand as WalkAndStandParameters},
+        test_support::{BallStateBuilder, WorldStateBuilder},
+        units::Meters,
+        HeadMotion,
+    };
+
+    use crate::behavior::walk_to_pose::WalkPathPlanner;
+
+    use super::*;
+
+    fn field_dimensions() -> FieldDimensions {
+        FieldDimensions {
+            length: Meters(9.0),
+            width: Meters(6.0),
+            ..Default::default()
+        }
+    }
+
+    fn role_positions() -> RolePositions {
+        RolePositions {
+            defender_aggressive_ring_radius: 1.5,
+            defender_passive_ring_radius: 2.0,
+            defender_y_offset: 1.0,
+            keeper_x_offset: 0.1,
+            ..Default::default()
+        }
+    }
+
+    fn world_state(
+        robot_to_field: Isometry2<f32>,
+        ball_in_field: Point2<f32>,
+        ball_side: Side,
+    ) -> WorldState {
+        let robot_to_field = Transform::wrap(robot_to_field);
+        WorldStateBuilder::default()
+            .with_robot_to_field(robot_to_field)
+            .with_ball(
+                BallStateBuilder::new(robot_to_field)
+                    .in_field(ball_in_field)
+                    .with_field_side(ball_side)
+                    .build(),
+            )
+            .with_position_of_interest(point![1.0, 0.0])
+            .build()
+    }
+
+    fn defend(
+        world_state: &WorldState,
+        role: impl Fn(&Defend, &mut AdditionalOutput<Vec<PathObstacle>>) -> Option<MotionCommand>,
+    ) -> Option<MotionCommand> {
+        let field_dimensions = field_dimensions();
+        let role_positions = role_positions();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let parameters = WalkAndStandParameters {
+            target_reached_thresholds: vector![0.1, 0.1],
+            hybrid_align_distance: Meters(1.0),
+            distance_to_be_aligned: Meters(0.5),
+            ..Default::default()
+        };
+        let walk_and_stand = WalkAndStand::new(
+            world_state,
+            &parameters,
+            &walk_path_planner,
+            &MotionCommand::Unstiff,
+        );
+        let look_action = LookAction::new(world_state);
+        let defend = Defend::new(
+            world_state,
+            &field_dimensions,
+            &role_positions,
+            &walk_and_stand,
+            &look_action,
+        );
+        let mut path_obstacles = None;
+        role(
+            &defend,
+            &mut AdditionalOutput::new(false, &mut path_obstacles),
+        )
+    }
+
+    #[test]
+    fn defenders_keep_the_ring_radius_of_their_side() {
+        let defend_left = |ball_side| {
+            defend_left_pose(
+                &world_state(Isometry2::identity(), point![-0.5, 1.0], ball_side),
+                &field_dimensions(),
+                &role_positions(),
+            )
+            .unwrap()
+            .inner
+        };
+
+        let aggressive_pose = defend_left(Side::Left);
+        assert_relative_eq!(aggressive_pose.translation.vector, vector![-3.0, 1.0]);
+        assert_relative_eq!(aggressive_pose.rotation.angle(), 0.0);
+        let passive_pose = defend_left(Side::Right);
+        assert_relative_eq!(passive_pose.translation.vector, vector![-2.5, 1.0]);
+    }
+
+    #[test]
+    fn defenders_at_their_pose_stand() {
+        let world_state = world_state(
+            Isometry2::translation(-3.0, 1.0),
+            point![-0.5, 1.0],
+            Side::Left,
+        );
+
+        assert_eq!(
+            defend(&world_state, |defend, path_obstacles| defend
+                .left(path_obstacles)),
+            Some(MotionCommand::Stand {
+                head: HeadMotion::LookAt {
+                    target: point![1.0, 0.0],
+                    camera: None,
+                },
+                is_energy_saving: true,
+            })
+        );
+    }
+
+    #[test]
+    fn keepers_close_to_their_pose_face_the_ball() {
+        let world_state = world_state(
+            Isometry2::translation(-4.4, 0.5),
+            point![0.0, 0.0],
+            Side::Left,
+        );
+
+        let Some(MotionCommand::Walk {
+            head,
+            path,
+            orientation_mode,
+            ..
+        }) = defend(&world_state, |defend, path_obstacles| {
+            defend.goal(path_obstacles)
+        })
+        else {
+            panic!("expected walk command");
+        };
+        assert_eq!(
+            head,
+            HeadMotion::LookAt {
+                target: point![1.0, 0.0],
+                camera: None,
+            }
+        );
+        assert_relative_eq!(
+            path.last().unwrap().end(),
+            point![0.0, -0.5],
+            epsilon = 1e-5
+        );
+        let OrientationMode::FaceTowards(ball) = orientation_mode else {
+            panic!("expected keeper to face towards the ball");
+        };
+        assert_relative_eq!(ball, point![4.4, -0.5], epsilon = 1e-5);
+    }
+}
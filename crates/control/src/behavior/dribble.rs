@@ -1,10 +1,12 @@
-use nalgebra::{Isometry2, Point2};
+use std::time::SystemTime;
+
+use nalgebra::Isometry2;
 
 use types::{
     parameters::{Dribbling, InWalkKickInfo, InWalkKicks},
-    rotate_towards, HeadMotion, MotionCommand,
+    BallSource, HeadMotion, MotionCommand,
     OrientationMode::{self, AlignWithPath},
-    PathSegment, WorldState,
+    PathSegment, ScanProfile, WorldState,
 };
 
 use super::walk_to_pose::{hybrid_alignment, WalkPathPlanner};
@@ -16,13 +18,31 @@ pub fn execute(
     in_walk_kicks: &InWalkKicks,
     parameters: &Dribbling,
     dribble_path: Option<Vec<PathSegment>>,
+    now: SystemTime,
 ) -> Option<MotionCommand> {
-    let ball_position = world_state.ball?.ball_in_ground;
+    let ball = world_state.ball?;
+    let is_own_ball_recent =
+        ball.source == BallSource::Own && ball.age(now) <= parameters.maximum_own_ball_age;
+    let is_ball_at_feet = world_state.blind_ball_contact.is_some();
+    if !is_own_ball_recent && !is_ball_at_feet {
+        return None;
+    }
+    let ball_position = ball.ball_in_ground.inner;
     let head = HeadMotion::LookLeftAndRightOf {
         target: ball_position,
     };
     let kick_decisions = world_state.kick_decisions.as_ref()?;
     let instant_kick_decisions = world_state.instant_kick_decisions.as_ref()?;
+    // instant kicks are long shots at the goal, which might be the own one when dead reckoning
+    let is_localization_reliable = world_state
+        .robot
+        .localization_quality
+        .map_or(true, |quality| quality.is_reliable);
+    let instant_kick_decisions = if is_localization_reliable {
+        instant_kick_decisions.as_slice()
+    } else {
+        &[]
+    };
 
     let available_kick = kick_decisions
         .iter()
@@ -33,7 +53,10 @@ pub fn execute(
         });
     if let Some(kick) = available_kick {
         let command = MotionCommand::InWalkKick {
-            head,
+            // the ball is right in front of the feet, check the ground the kick will go over
+            head: HeadMotion::LookAround {
+                profile: ScanProfile::Down,
+            },
             kick: kick.variant,
             kicking_side: kick.kicking_side,
             strength: kick.strength,
@@ -60,12 +83,16 @@ pub fn execute(
     );
     let orientation_mode = match hybrid_orientation_mode {
         AlignWithPath if ball_position.coords.norm() > 0.0 => {
-            OrientationMode::Override(rotate_towards(Point2::origin(), ball_position))
+            OrientationMode::FaceTowards(ball_position)
         }
         orientation_mode => orientation_mode,
     };
     match dribble_path {
         Some(path) => {
+            // the ball is pushed along, keep the surroundings of the path in view instead
+            let head = HeadMotion::LookAround {
+                profile: ScanProfile::Narrow,
+            };
             Some(walk_path_planner.walk_with_obstacle_avoiding_arms(head, orientation_mode, path))
         }
         None => Some(MotionCommand::Stand {
@@ -75,10 +102,273 @@ pub fn execute(
     }
 }
 
-fn is_kick_pose_reached(kick_pose_to_robot: Isometry2<f32>, kick_info: &InWalkKickInfo) -> bool {
+pub fn is_kick_pose_reached(
+    kick_pose_to_robot: Isometry2<f32>,
+    kick_info: &InWalkKickInfo,
+) -> bool {
     let is_x_reached = kick_pose_to_robot.translation.x.abs() < kick_info.reached_thresholds.x;
     let is_y_reached = kick_pose_to_robot.translation.y.abs() < kick_info.reached_thresholds.y;
     let is_orientation_reached =
         kick_pose_to_robot.rotation.angle().abs() < kick_info.reached_thresholds.z;
     is_x_reached && is_y_reached && is_orientation_reached
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use nalgebra::{point, vector, Vector3};
+    use types::{
+        direct_path,
+        localization::LocalizationQuality,
+        parameters::PathPlanning,
+        test_support::{BallStateBuilder, WorldStateBuilder},
+        units::Meters,
+        ArmMotion, FieldDimensions, KickDecision, KickVariant, Side,
+    };
+
+    use super::*;
+
+    #[test]
+    fn does_nothing_without_ball() {
+        let world_state = WorldStateBuilder::default().build();
+        let field_dimensions = FieldDimensions::default();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+
+        assert_eq!(
+            execute(
+                &world_state,
+                &walk_path_planner,
+                &InWalkKicks::default(),
+                &Dribbling::default(),
+                None,
+                UNIX_EPOCH,
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn team_balls_and_old_own_balls_are_not_dribbled() {
+        let field_dimensions = FieldDimensions::default();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let parameters = Dribbling {
+            maximum_own_ball_age: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let now = UNIX_EPOCH + Duration::from_secs(10);
+        let dribble = |source, last_seen| {
+            let world_state = WorldStateBuilder::default()
+                .with_ball(
+                    BallStateBuilder::default()
+                        .with_source(source)
+                        .with_last_seen(last_seen)
+                        .build(),
+                )
+                .build();
+            execute(
+                &world_state,
+                &walk_path_planner,
+                &InWalkKicks::default(),
+                &parameters,
+                None,
+                now,
+            )
+        };
+
+        assert_eq!(dribble(BallSource::Team, now), None);
+        assert_eq!(dribble(BallSource::Own, UNIX_EPOCH), None);
+    }
+
+    #[test]
+    fn no_instant_kicks_without_reliable_localization() {
+        let field_dimensions = FieldDimensions::default();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let in_walk_kicks = InWalkKicks {
+            forward: InWalkKickInfo {
+                reached_thresholds: vector![0.1, 0.1, 0.1],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let parameters = Dribbling {
+            maximum_own_ball_age: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let instant_kick = KickDecision {
+            variant: KickVariant::Forward,
+            kicking_side: Side::Left,
+            kick_pose: Isometry2::identity(),
+            target: point![4.5, 0.0],
+            strength: 1.0,
+            visible: true,
+        };
+        let dribble = |is_reliable| {
+            let mut world_state = WorldStateBuilder::default()
+                .with_ball(BallStateBuilder::default().build())
+                .with_localization_quality(LocalizationQuality {
+                    pose_covariance_eigenvalues: Vector3::zeros(),
+                    time_since_last_measurement: None,
+                    is_reliable,
+                })
+                .build();
+            world_state.kick_decisions = Some(vec![]);
+            world_state.instant_kick_decisions = Some(vec![instant_kick]);
+            execute(
+                &world_state,
+                &walk_path_planner,
+                &in_walk_kicks,
+                &parameters,
+                None,
+                UNIX_EPOCH,
+            )
+        };
+
+        assert!(matches!(
+            dribble(true),
+            Some(MotionCommand::InWalkKick { .. })
+        ));
+        assert!(matches!(dribble(false), Some(MotionCommand::Stand { .. })));
+    }
+
+    #[test]
+    fn reached_kick_poses_are_kicked_from() {
+        let field_dimensions = FieldDimensions::default();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let in_walk_kicks = InWalkKicks {
+            forward: InWalkKickInfo {
+                reached_thresholds: vector![0.1, 0.1, 0.1],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let parameters = Dribbling {
+            maximum_own_ball_age: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let mut world_state = WorldStateBuilder::default()
+            .with_ball(BallStateBuilder::default().build())
+            .build();
+        world_state.kick_decisions = Some(vec![KickDecision {
+            variant: KickVariant::Forward,
+            kicking_side: Side::Right,
+            kick_pose: Isometry2::translation(0.05, 0.0),
+            target: point![2.0, 0.0],
+            strength: 0.5,
+            visible: true,
+        }]);
+        world_state.instant_kick_decisions = Some(vec![]);
+
+        assert_eq!(
+            execute(
+                &world_state,
+                &walk_path_planner,
+                &in_walk_kicks,
+                &parameters,
+                None,
+                UNIX_EPOCH,
+            ),
+            Some(MotionCommand::InWalkKick {
+                head: HeadMotion::LookAround {
+                    profile: ScanProfile::Down,
+                },
+                kick: KickVariant::Forward,
+                kicking_side: Side::Right,
+                strength: 0.5,
+            })
+        );
+    }
+
+    #[test]
+    fn robots_stand_looking_at_the_ball_without_kick_decisions() {
+        let field_dimensions = FieldDimensions::default();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let parameters = Dribbling {
+            maximum_own_ball_age: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let mut world_state = WorldStateBuilder::default()
+            .with_ball(
+                BallStateBuilder::default()
+                    .in_ground(point![1.0, 0.5])
+                    .build(),
+            )
+            .build();
+        world_state.kick_decisions = Some(vec![]);
+        world_state.instant_kick_decisions = Some(vec![]);
+
+        assert_eq!(
+            execute(
+                &world_state,
+                &walk_path_planner,
+                &InWalkKicks::default(),
+                &parameters,
+                Some(vec![]),
+                UNIX_EPOCH,
+            ),
+            Some(MotionCommand::Stand {
+                head: HeadMotion::LookLeftAndRightOf {
+                    target: point![1.0, 0.5],
+                },
+                is_energy_saving: false,
+            })
+        );
+    }
+
+    #[test]
+    fn dribble_paths_are_followed_facing_the_ball() {
+        let field_dimensions = FieldDimensions::default();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let parameters = Dribbling {
+            maximum_own_ball_age: Duration::from_secs(1),
+            hybrid_align_distance: Meters(1.0),
+            distance_to_be_aligned: Meters(0.5),
+            ..Default::default()
+        };
+        let mut world_state = WorldStateBuilder::default()
+            .with_ball(
+                BallStateBuilder::default()
+                    .in_ground(point![1.0, 0.0])
+                    .build(),
+            )
+            .build();
+        world_state.kick_decisions = Some(vec![KickDecision {
+            variant: KickVariant::Forward,
+            kicking_side: Side::Left,
+            kick_pose: Isometry2::translation(2.0, 0.0),
+            target: point![4.5, 0.0],
+            strength: 1.0,
+            visible: true,
+        }]);
+        world_state.instant_kick_decisions = Some(vec![]);
+        let dribble_path = direct_path(point![0.0, 0.0], point![2.0, 0.0]);
+
+        assert_eq!(
+            execute(
+                &world_state,
+                &walk_path_planner,
+                &InWalkKicks::default(),
+                &parameters,
+                Some(dribble_path.clone()),
+                UNIX_EPOCH,
+            ),
+            Some(MotionCommand::Walk {
+                head: HeadMotion::LookAround {
+                    profile: ScanProfile::Narrow,
+                },
+                path: dribble_path,
+                left_arm: ArmMotion::Swing,
+                right_arm: ArmMotion::Swing,
+                orientation_mode: OrientationMode::FaceTowards(point![1.0, 0.0]),
+                speed_profile: None,
+                arrival_tolerance: None,
+            })
+        );
+    }
+}
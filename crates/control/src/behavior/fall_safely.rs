@@ -8,3 +8,29 @@ pub fn execute(world_state: &WorldState, has_ground_contact: bool) -> Option<Mot
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::{test_support::WorldStateBuilder, FallDirection};
+
+    use super::*;
+
+    #[test]
+    fn protects_only_while_falling_with_ground_contact() {
+        let falling = WorldStateBuilder::default()
+            .with_fall_state(FallState::Falling {
+                direction: FallDirection::Backward,
+            })
+            .build();
+        let upright = WorldStateBuilder::default().build();
+
+        assert_eq!(
+            execute(&falling, true),
+            Some(MotionCommand::FallProtection {
+                direction: FallDirection::Backward
+            })
+        );
+        assert_eq!(execute(&falling, false), None);
+        assert_eq!(execute(&upright, true), None);
+    }
+}
@@ -9,3 +9,29 @@ pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::test_support::WorldStateBuilder;
+
+    use super::*;
+
+    #[test]
+    fn stands_energy_saving_with_zero_head_angles_in_initial() {
+        let initial = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Initial)
+            .build();
+        let ready = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Ready)
+            .build();
+
+        assert_eq!(
+            execute(&initial),
+            Some(MotionCommand::Stand {
+                head: HeadMotion::ZeroAngles,
+                is_energy_saving: true,
+            })
+        );
+        assert_eq!(execute(&ready), None);
+    }
+}
@@ -1,8 +1,11 @@
-use nalgebra::{Isometry2, Point2, UnitComplex};
+use nalgebra::{Point2, UnitComplex};
 use spl_network_messages::{GamePhase, SubState};
 use types::{
-    parameters::InterceptBall, BallState, FilteredGameState, GameControllerState, HeadMotion, Line,
-    LineSegment, MotionCommand, OrientationMode, PathSegment, Step, WorldState,
+    coordinate_systems::{Field, Ground, Transform},
+    parameters::InterceptBall,
+    units::Meters,
+    BallState, FilteredGameState, GameControllerState, HeadMotion, Line, LineSegment,
+    MotionCommand, OrientationMode, PathSegment, Step, WorldState,
 };
 
 pub fn execute(
@@ -43,17 +46,17 @@ pub fn execute(
                 turn: _,
             } = maximum_step_size;
 
-            if forward == 0.0 || left == 0.0 {
+            if forward == Meters(0.0) || left == Meters(0.0) {
                 return None;
             }
 
             let ball_line = Line(
-                ball.ball_in_ground,
-                ball.ball_in_ground + ball.ball_in_ground_velocity,
+                ball.ball_in_ground.inner,
+                ball.ball_in_ground.inner + ball.ball_in_ground_velocity,
             );
             let interception_point = ball_line.project_point(Point2::origin());
 
-            if interception_point.coords.norm() > parameters.maximum_intercept_distance {
+            if interception_point.coords.norm() > parameters.maximum_intercept_distance.0 {
                 return None;
             }
 
@@ -64,13 +67,15 @@ pub fn execute(
 
             Some(MotionCommand::Walk {
                 head: HeadMotion::LookAt {
-                    target: ball.ball_in_ground,
+                    target: ball.ball_in_ground.inner,
                     camera: None,
                 },
                 path,
                 left_arm: types::ArmMotion::Swing,
                 right_arm: types::ArmMotion::Swing,
                 orientation_mode: OrientationMode::Override(UnitComplex::default()),
+                speed_profile: None,
+                arrival_tolerance: None,
             })
         }
         _ => None,
@@ -79,22 +84,107 @@ pub fn execute(
 
 fn ball_is_interception_candidate(
     ball: BallState,
-    robot_to_field: Isometry2<f32>,
+    robot_to_field: Transform<Ground, Field>,
     parameters: &InterceptBall,
 ) -> bool {
-    let ball_is_in_front_of_robot = ball.ball_in_ground.coords.norm()
-        < parameters.maximum_ball_distance
-        && ball.ball_in_ground.x > 0.0;
+    let ball_is_in_front_of_robot = ball.ball_in_ground.inner.coords.norm()
+        < parameters.maximum_ball_distance.0
+        && ball.ball_in_ground.inner.x > 0.0;
     let ball_is_moving_towards_robot =
-        ball.ball_in_ground_velocity.x < -parameters.minimum_ball_velocity_towards_robot;
+        ball.ball_in_ground_velocity.x < -parameters.minimum_ball_velocity_towards_robot.0;
 
-    let ball_in_field_velocity = robot_to_field * ball.ball_in_ground_velocity;
-    let ball_is_moving = ball_in_field_velocity.norm() > parameters.minimum_ball_velocity;
+    let ball_in_field_velocity = robot_to_field.inner * ball.ball_in_ground_velocity;
+    let ball_is_moving = ball_in_field_velocity.norm() > parameters.minimum_ball_velocity.0;
     let ball_is_moving_towards_own_half =
-        ball_in_field_velocity.x < -parameters.minimum_ball_velocity_towards_own_half;
+        ball_in_field_velocity.x < -parameters.minimum_ball_velocity_towards_own_half.0;
 
     ball_is_in_front_of_robot
         && ball_is_moving
         && ball_is_moving_towards_robot
         && ball_is_moving_towards_own_half
 }
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{point, vector};
+    use types::{
+        test_support::{BallStateBuilder, GameControllerStateBuilder, WorldStateBuilder},
+        units::{MetersPerSecond, Radians},
+    };
+
+    use super::*;
+
+    const PARAMETERS: InterceptBall = InterceptBall {
+        maximum_ball_distance: Meters(2.0),
+        minimum_ball_velocity: MetersPerSecond(0.1),
+        minimum_ball_velocity_towards_robot: MetersPerSecond(0.1),
+        minimum_ball_velocity_towards_own_half: MetersPerSecond(0.1),
+        maximum_intercept_distance: Meters(1.0),
+    };
+
+    const MAXIMUM_STEP_SIZE: Step = Step {
+        forward: Meters(0.1),
+        left: Meters(0.1),
+        turn: Radians(0.0),
+    };
+
+    fn approaching_ball() -> BallState {
+        BallStateBuilder::default()
+            .in_ground(point![1.0, 0.5])
+            .with_velocity_in_ground(vector![-1.0, 0.0])
+            .build()
+    }
+
+    #[test]
+    fn walks_onto_line_of_approaching_ball() {
+        let world_state = WorldStateBuilder::default()
+            .with_ball(approaching_ball())
+            .build();
+
+        assert_eq!(
+            execute(&world_state, PARAMETERS, MAXIMUM_STEP_SIZE),
+            Some(MotionCommand::Walk {
+                head: HeadMotion::LookAt {
+                    target: point![1.0, 0.5],
+                    camera: None,
+                },
+                path: vec![PathSegment::LineSegment(LineSegment(
+                    Point2::origin(),
+                    point![0.0, 0.5]
+                ))],
+                left_arm: types::ArmMotion::Swing,
+                right_arm: types::ArmMotion::Swing,
+                orientation_mode: OrientationMode::Override(UnitComplex::default()),
+                speed_profile: None,
+                arrival_tolerance: None,
+            })
+        );
+    }
+
+    #[test]
+    fn does_not_intercept_resting_ball() {
+        let world_state = WorldStateBuilder::default()
+            .with_ball(
+                BallStateBuilder::default()
+                    .in_ground(point![1.0, 0.5])
+                    .build(),
+            )
+            .build();
+
+        assert_eq!(execute(&world_state, PARAMETERS, MAXIMUM_STEP_SIZE), None);
+    }
+
+    #[test]
+    fn does_not_intercept_in_penalty_kick() {
+        let world_state = WorldStateBuilder::default()
+            .with_ball(approaching_ball())
+            .with_game_controller_state(
+                GameControllerStateBuilder::default()
+                    .with_sub_state(SubState::PenaltyKick)
+                    .build(),
+            )
+            .build();
+
+        assert_eq!(execute(&world_state, PARAMETERS, MAXIMUM_STEP_SIZE), None);
+    }
+}
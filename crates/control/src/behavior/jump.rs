@@ -13,3 +13,45 @@ pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
             Some(PenaltyShotDirection::NotMoving) | None => None,
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use types::test_support::{BallStateBuilder, WorldStateBuilder};
+
+    use super::*;
+
+    fn world_state_with_shot(direction: PenaltyShotDirection) -> WorldState {
+        WorldStateBuilder::default()
+            .with_ball(
+                BallStateBuilder::default()
+                    .with_penalty_shot_direction(direction)
+                    .build(),
+            )
+            .build()
+    }
+
+    #[test]
+    fn jumps_towards_shot_direction() {
+        assert_eq!(
+            execute(&world_state_with_shot(PenaltyShotDirection::Left)),
+            Some(MotionCommand::Jump {
+                direction: JumpDirection::Left
+            })
+        );
+        assert_eq!(
+            execute(&world_state_with_shot(PenaltyShotDirection::Right)),
+            Some(MotionCommand::Jump {
+                direction: JumpDirection::Right
+            })
+        );
+    }
+
+    #[test]
+    fn does_not_jump_without_moving_ball() {
+        assert_eq!(
+            execute(&world_state_with_shot(PenaltyShotDirection::NotMoving)),
+            None
+        );
+        assert_eq!(execute(&WorldStateBuilder::default().build()), None);
+    }
+}
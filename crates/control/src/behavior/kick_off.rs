@@ -0,0 +1,239 @@
+use std::time::SystemTime;
+
+use framework::AdditionalOutput;
+use nalgebra::{point, Isometry2};
+use spl_network_messages::{GamePhase, GameState, Team};
+use types::{
+    coordinate_systems::{Field, Point, Pose},
+    parameters::{Dribbling, InWalkKicks, KickOff as KickOffParameters, KickOffStrategy},
+    rotate_towards, FieldDimensions, GameControllerState, HeadMotion, KickVariant, MotionCommand,
+    ObstacleKind, PathObstacle, Side, WorldState,
+};
+
+use crate::kick_selector::compute_kick_pose;
+
+use super::{
+    dribble::is_kick_pose_reached,
+    walk_to_pose::{hybrid_alignment, WalkAndStand, WalkPathPlanner},
+};
+
+/// Target of the indirect kick off in field coordinates, `None` if the kick off is played
+/// directly or there is no own kick off
+pub fn indirect_target(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    strategy: KickOffStrategy,
+    parameters: &KickOffParameters,
+    now: SystemTime,
+) -> Option<Point<Field>> {
+    let robot_to_field = world_state.robot.robot_to_field?;
+    let center_circle_radius = field_dimensions.center_circle_diameter.0 / 2.0;
+    let is_ball_in_center_circle = world_state.ball.map_or(false, |ball| {
+        ball.ball_in_field.inner.coords.norm() < center_circle_radius
+    });
+    if !is_kick_off_running(world_state, parameters, now) || !is_ball_in_center_circle {
+        return None;
+    }
+
+    let blocking_opponents: Vec<_> = world_state
+        .obstacles
+        .iter()
+        .filter(|obstacle| {
+            obstacle.kind.is_robot() && !matches!(obstacle.kind, ObstacleKind::Teammate)
+        })
+        .map(|obstacle| (robot_to_field * Point::wrap(obstacle.position)).inner)
+        .filter(|position| {
+            position.x > 0.0
+                && position.coords.norm()
+                    < center_circle_radius + parameters.opponent_detection_distance
+        })
+        .collect();
+    let is_indirect = match strategy {
+        KickOffStrategy::Direct => false,
+        KickOffStrategy::Indirect => true,
+        KickOffStrategy::Adaptive => !blocking_opponents.is_empty(),
+    };
+    if !is_indirect {
+        return None;
+    }
+
+    let target = parameters.indirect_target;
+    let opponent_side: f32 = blocking_opponents
+        .iter()
+        .map(|position| position.y.signum())
+        .sum();
+    let is_target_on_opponent_side = opponent_side * target.y > 0.0;
+    Some(Point::wrap(if is_target_on_opponent_side {
+        point![target.x, -target.y]
+    } else {
+        target
+    }))
+}
+
+/// Taps the ball to the indirect kick off target, a direct kick off is left to dribbling
+pub fn execute(
+    world_state: &WorldState,
+    walk_path_planner: &WalkPathPlanner,
+    in_walk_kicks: &InWalkKicks,
+    dribbling: &Dribbling,
+    parameters: &KickOffParameters,
+    indirect_target: Option<Point<Field>>,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    let target_in_field = indirect_target?;
+    let robot_to_field = world_state.robot.robot_to_field?;
+    let ball_position = world_state.ball?.ball_in_ground;
+    let head = HeadMotion::LookLeftAndRightOf {
+        target: ball_position.inner,
+    };
+    let kick_info = &in_walk_kicks[KickVariant::Forward];
+    let kick_pose = compute_kick_pose(
+        ball_position.inner,
+        (robot_to_field.inverse() * target_in_field).inner,
+        kick_info,
+        Side::Left,
+    );
+    if is_kick_pose_reached(kick_pose, kick_info) {
+        return Some(MotionCommand::InWalkKick {
+            head,
+            kick: KickVariant::Forward,
+            kicking_side: Side::Left,
+            strength: parameters.indirect_strength,
+        });
+    }
+
+    let path = walk_path_planner.plan(
+        Pose::wrap(kick_pose).position(),
+        robot_to_field,
+        Some(ball_position),
+        1.0,
+        &world_state.obstacles,
+        &world_state.rule_obstacles,
+        path_obstacles_output,
+    );
+    let orientation_mode = hybrid_alignment(
+        kick_pose,
+        dribbling.hybrid_align_distance,
+        dribbling.distance_to_be_aligned,
+    );
+    Some(walk_path_planner.walk_with_obstacle_avoiding_arms(head, orientation_mode, path))
+}
+
+/// Walks to the target a teammate announced for its indirect kick off to receive the ball
+pub fn receive(
+    world_state: &WorldState,
+    walk_and_stand: &WalkAndStand,
+    field_dimensions: &FieldDimensions,
+    parameters: &KickOffParameters,
+    now: SystemTime,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    if !is_kick_off_running(world_state, parameters, now) {
+        return None;
+    }
+    let robot_to_field = world_state.robot.robot_to_field?;
+    let maximum_target_distance =
+        field_dimensions.center_circle_diameter.0 / 2.0 + parameters.opponent_detection_distance;
+    let target_in_field = world_state
+        .teammate_intentions
+        .iter()
+        .filter_map(|(_, intention)| intention.and_then(|intention| intention.kick_target_in_field))
+        .find(|target| target.coords.norm() < maximum_target_distance)?;
+
+    let target = robot_to_field.inverse() * Point::<Field>::wrap(target_in_field);
+    let ball_position = world_state.ball.map_or_else(
+        || robot_to_field.inverse() * Point::origin(),
+        |ball| ball.ball_in_ground,
+    );
+    let target_pose = Pose::wrap(Isometry2::from_parts(
+        target.inner.coords.into(),
+        rotate_towards(target.inner, ball_position.inner),
+    ));
+    walk_and_stand.execute(
+        target_pose,
+        HeadMotion::LookAt {
+            target: ball_position.inner,
+            camera: None,
+        },
+        path_obstacles_output,
+    )
+}
+
+fn is_kick_off_running(
+    world_state: &WorldState,
+    parameters: &KickOffParameters,
+    now: SystemTime,
+) -> bool {
+    let Some(GameControllerState {
+        game_state: GameState::Playing,
+        game_phase: GamePhase::Normal,
+        kicking_team: Team::Hulks,
+        sub_state: None,
+        last_game_state_change,
+        ..
+    }) = world_state.game_controller_state
+    else {
+        return false;
+    };
+    now.duration_since(last_game_state_change)
+        .map_or(true, |time_since_kick_off| {
+            time_since_kick_off < parameters.duration
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use types::{
+        test_support::{BallStateBuilder, GameControllerStateBuilder, WorldStateBuilder},
+        units::Meters,
+        Obstacle,
+    };
+
+    use super::*;
+
+    fn parameters() -> KickOffParameters {
+        KickOffParameters {
+            duration: Duration::from_secs(10),
+            indirect_target: point![-0.3, 1.2],
+            indirect_strength: 0.3,
+            opponent_detection_distance: 0.75,
+        }
+    }
+
+    #[test]
+    fn adaptive_kick_off_taps_away_from_waiting_opponents() {
+        let field_dimensions = FieldDimensions {
+            center_circle_diameter: Meters(1.5),
+            ..Default::default()
+        };
+        let world_state_with = |obstacles| {
+            WorldStateBuilder::default()
+                .with_game_controller_state(GameControllerStateBuilder::default().build())
+                .with_ball(BallStateBuilder::default().build())
+                .with_obstacles(obstacles)
+                .build()
+        };
+        let target = |world_state: &WorldState| {
+            indirect_target(
+                world_state,
+                &field_dimensions,
+                KickOffStrategy::Adaptive,
+                &parameters(),
+                UNIX_EPOCH + Duration::from_secs(1),
+            )
+        };
+        let mut opponent = Obstacle::robot(point![1.0, 0.5], 0.2, 0.3);
+        opponent.kind = ObstacleKind::Opponent;
+        let mut teammate = Obstacle::robot(point![1.0, -0.5], 0.2, 0.3);
+        teammate.kind = ObstacleKind::Teammate;
+
+        assert_eq!(target(&world_state_with(vec![])), None);
+        assert_eq!(target(&world_state_with(vec![teammate])), None);
+        assert_eq!(
+            target(&world_state_with(vec![opponent, teammate])),
+            Some(Point::wrap(point![-0.3, -1.2]))
+        );
+    }
+}
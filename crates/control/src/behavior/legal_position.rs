@@ -0,0 +1,205 @@
+use nalgebra::{point, Point2, Vector2};
+use spl_network_messages::Team;
+use types::{
+    coordinate_systems::{Field, Point},
+    parameters::IllegalPositionAvoidance,
+    FieldDimensions, Role, WorldState,
+};
+
+/// Moves a position in ready or set to the closest position the robot is not penalized for in set
+///
+/// Robots have to be in their own half, outside the center circle if the opponent kicks off, and
+/// only a limited number of robots may defend in the own penalty area. Teammates with a lower
+/// player number take precedence for the penalty area. They are counted by their announced target
+/// or, if they do not walk anywhere, by their pose from their last message.
+pub fn nearest_legal_position(
+    position: Point<Field>,
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &IllegalPositionAvoidance,
+) -> Point<Field> {
+    let position = position.inner;
+    let mut position = point![position.x.min(-parameters.margin), position.y];
+
+    let is_opponent_kick_off = world_state
+        .game_controller_state
+        .map_or(false, |game_controller_state| {
+            game_controller_state.kicking_team == Team::Opponent
+        });
+    let center_circle_radius = field_dimensions.center_circle_diameter.0 / 2.0 + parameters.margin;
+    if is_opponent_kick_off && position.coords.norm() < center_circle_radius {
+        let direction = position
+            .coords
+            .try_normalize(f32::EPSILON)
+            .unwrap_or(-Vector2::x());
+        position = Point2::from(direction * center_circle_radius);
+    }
+
+    let is_keeper = matches!(
+        world_state.robot.role,
+        Role::Keeper | Role::ReplacementKeeper
+    );
+    let number_of_prioritized_defenders = world_state
+        .teammate_intentions
+        .iter()
+        .filter(|(player_number, _)| *player_number < world_state.robot.player_number)
+        .filter_map(|(player_number, intention)| match intention {
+            Some(intention) => Some(intention.target_pose_in_field * Point2::origin()),
+            None => world_state.teammate_poses[player_number].map(|pose| pose.position().inner),
+        })
+        .filter(|position| is_inside_own_penalty_area(*position, field_dimensions, 0.0))
+        .count();
+    let is_defender_allowed = is_keeper
+        || number_of_prioritized_defenders < parameters.maximum_number_of_defenders_in_penalty_area;
+    if !is_defender_allowed
+        && is_inside_own_penalty_area(position, field_dimensions, parameters.margin)
+    {
+        let front_line = -field_dimensions.length.0 / 2.0
+            + field_dimensions.penalty_area_length.0
+            + parameters.margin;
+        let side_line = field_dimensions.penalty_area_width.0 / 2.0 + parameters.margin;
+        let to_front = front_line - position.x;
+        let to_side = side_line - position.y.abs();
+        position = if to_front < to_side {
+            point![front_line, position.y]
+        } else {
+            point![position.x, side_line.copysign(position.y)]
+        };
+    }
+
+    Point::wrap(position)
+}
+
+fn is_inside_own_penalty_area(
+    position: Point2<f32>,
+    field_dimensions: &FieldDimensions,
+    margin: f32,
+) -> bool {
+    position.x < -field_dimensions.length.0 / 2.0 + field_dimensions.penalty_area_length.0 + margin
+        && position.y.abs() < field_dimensions.penalty_area_width.0 / 2.0 + margin
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::Isometry2;
+    use spl_network_messages::{Intention, PlayerNumber};
+    use types::{
+        coordinate_systems::Pose,
+        test_support::{GameControllerStateBuilder, WorldStateBuilder},
+        units::Meters,
+        Players,
+    };
+
+    use super::*;
+
+    fn field_dimensions() -> FieldDimensions {
+        FieldDimensions {
+            length: Meters(9.0),
+            width: Meters(6.0),
+            penalty_area_length: Meters(1.65),
+            penalty_area_width: Meters(4.0),
+            center_circle_diameter: Meters(1.5),
+            ..Default::default()
+        }
+    }
+
+    fn parameters() -> IllegalPositionAvoidance {
+        IllegalPositionAvoidance {
+            margin: 0.1,
+            maximum_number_of_defenders_in_penalty_area: 2,
+        }
+    }
+
+    #[test]
+    fn opponent_half_and_center_circle_are_left_on_opponent_kick_off() {
+        let world_state = WorldStateBuilder::default()
+            .with_game_controller_state(
+                GameControllerStateBuilder::default()
+                    .with_kicking_team(Team::Opponent)
+                    .build(),
+            )
+            .build();
+        let legal_position = |position| {
+            nearest_legal_position(
+                Point::wrap(position),
+                &world_state,
+                &field_dimensions(),
+                &parameters(),
+            )
+            .inner
+        };
+
+        assert_relative_eq!(legal_position(point![1.0, 2.0]), point![-0.1, 2.0]);
+        assert_relative_eq!(legal_position(point![-0.5, 0.0]), point![-0.85, 0.0]);
+        assert_relative_eq!(legal_position(point![-2.0, 1.0]), point![-2.0, 1.0]);
+    }
+
+    #[test]
+    fn surplus_defenders_leave_the_penalty_area() {
+        let intention = Some(Intention {
+            target_pose_in_field: Isometry2::translation(-4.0, 0.0),
+            kick_target_in_field: None,
+        });
+        let mut world_state = WorldStateBuilder::default()
+            .with_player_number(PlayerNumber::Three)
+            .build();
+        world_state.teammate_intentions = Players {
+            one: intention,
+            two: intention,
+            ..Default::default()
+        };
+
+        assert_relative_eq!(
+            nearest_legal_position(
+                Point::wrap(point![-3.0, 0.5]),
+                &world_state,
+                &field_dimensions(),
+                &parameters()
+            )
+            .inner,
+            point![-2.75, 0.5]
+        );
+        assert_relative_eq!(
+            nearest_legal_position(
+                Point::wrap(point![-4.0, 1.8]),
+                &world_state,
+                &field_dimensions(),
+                &parameters()
+            )
+            .inner,
+            point![-4.0, 2.1]
+        );
+    }
+
+    #[test]
+    fn teammates_standing_in_the_penalty_area_count_as_defenders() {
+        let intention = Some(Intention {
+            target_pose_in_field: Isometry2::translation(-4.0, 0.0),
+            kick_target_in_field: None,
+        });
+        let mut world_state = WorldStateBuilder::default()
+            .with_player_number(PlayerNumber::Three)
+            .build();
+        world_state.teammate_intentions = Players {
+            one: intention,
+            ..Default::default()
+        };
+        world_state.teammate_poses = Players {
+            one: Some(Pose::wrap(Isometry2::translation(3.0, 0.0))),
+            two: Some(Pose::wrap(Isometry2::translation(-4.2, 1.0))),
+            ..Default::default()
+        };
+
+        assert_relative_eq!(
+            nearest_legal_position(
+                Point::wrap(point![-3.0, 0.5]),
+                &world_state,
+                &field_dimensions(),
+                &parameters()
+            )
+            .inner,
+            point![-2.75, 0.5]
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use spl_network_messages::GamePhase;
-use types::{GameControllerState, MotionCommand, PrimaryState, WorldState};
+use types::{GameControllerState, MotionCommand, PrimaryState, ScanProfile, WorldState};
 
 pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
     match (
@@ -14,9 +14,56 @@ pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
             _,
         ) => None,
         (_, PrimaryState::Ready | PrimaryState::Playing) => Some(MotionCommand::Stand {
-            head: types::HeadMotion::LookAround,
+            head: types::HeadMotion::LookAround {
+                profile: ScanProfile::Wide,
+            },
             is_energy_saving: false,
         }),
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use spl_network_messages::Team;
+    use types::test_support::{GameControllerStateBuilder, WorldStateBuilder};
+
+    use super::*;
+
+    #[test]
+    fn looks_around_in_ready_and_playing() {
+        let look_around = Some(MotionCommand::Stand {
+            head: types::HeadMotion::LookAround {
+                profile: ScanProfile::Wide,
+            },
+            is_energy_saving: false,
+        });
+        for primary_state in [PrimaryState::Ready, PrimaryState::Playing] {
+            let world_state = WorldStateBuilder::default()
+                .with_primary_state(primary_state)
+                .build();
+
+            assert_eq!(execute(&world_state), look_around);
+        }
+        let set = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Set)
+            .build();
+        assert_eq!(execute(&set), None);
+    }
+
+    #[test]
+    fn does_not_look_around_in_penalty_shootout() {
+        let world_state = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Playing)
+            .with_game_controller_state(
+                GameControllerStateBuilder::default()
+                    .with_game_phase(GamePhase::PenaltyShootout {
+                        kicking_team: Team::Hulks,
+                    })
+                    .build(),
+            )
+            .build();
+
+        assert_eq!(execute(&world_state), None);
+    }
+}
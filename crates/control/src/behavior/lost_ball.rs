@@ -1,25 +1,29 @@
 use framework::AdditionalOutput;
 use nalgebra::Point2;
 use types::{
-    parameters::LostBall as LostBallParameters, rotate_towards, HeadMotion, MotionCommand,
-    OrientationMode, PathObstacle, WorldState,
+    coordinate_systems::{Field, Point},
+    parameters::LostBall as LostBallParameters,
+    rotate_towards, HeadMotion, MotionCommand, OrientationMode, PathObstacle, WorldState,
 };
 
 use super::walk_to_pose::WalkPathPlanner;
 
 pub fn execute(
     world_state: &WorldState,
-    absolute_last_known_ball_position: Point2<f32>,
+    absolute_last_known_ball_position: Point<Field>,
     walk_path_planner: &WalkPathPlanner,
     lost_ball_parameters: &LostBallParameters,
     path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
 ) -> Option<MotionCommand> {
     let robot_to_field = world_state.robot.robot_to_field?;
     let walk_target = robot_to_field.inverse()
-        * (absolute_last_known_ball_position - lost_ball_parameters.offset_to_last_ball_location);
+        * Point::wrap(
+            absolute_last_known_ball_position.inner
+                - lost_ball_parameters.offset_to_last_ball_location,
+        );
     let relative_last_known_ball_position =
         robot_to_field.inverse() * absolute_last_known_ball_position;
-    let orientation = rotate_towards(Point2::origin(), relative_last_known_ball_position);
+    let orientation = rotate_towards(Point2::origin(), relative_last_known_ball_position.inner);
     let path = walk_path_planner.plan(
         walk_target,
         robot_to_field,
@@ -35,3 +39,33 @@ pub fn execute(
         path,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::point;
+    use types::{parameters::PathPlanning, test_support::WorldStateBuilder, FieldDimensions};
+
+    use super::*;
+
+    #[test]
+    fn does_nothing_without_robot_pose() {
+        let world_state = WorldStateBuilder::default()
+            .without_robot_to_field()
+            .build();
+        let field_dimensions = FieldDimensions::default();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let mut path_obstacles = None;
+
+        assert_eq!(
+            execute(
+                &world_state,
+                Point::wrap(point![1.0, 0.0]),
+                &walk_path_planner,
+                &LostBallParameters::default(),
+                &mut AdditionalOutput::new(false, &mut path_obstacles),
+            ),
+            None
+        );
+    }
+}
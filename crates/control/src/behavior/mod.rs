@@ -6,17 +6,22 @@ mod head;
 mod initial;
 mod intercept_ball;
 mod jump;
+mod kick_off;
+mod legal_position;
 mod look_around;
 mod lost_ball;
 pub mod node;
 mod penalize;
+mod penalty_keeper;
 mod prepare_jump;
 mod search;
 mod sit_down;
 mod stand;
 mod stand_up;
 mod support;
+mod teleoperate;
 mod unstiff;
+mod walk_to_ball;
 mod walk_to_kick_off;
 mod walk_to_penalty_kick;
 pub mod walk_to_pose;
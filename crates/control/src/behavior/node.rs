@@ -3,12 +3,14 @@ use std::time::SystemTime;
 use color_eyre::Result;
 use context_attribute::context;
 use framework::{AdditionalOutput, MainOutput};
-use nalgebra::{point, Point2, Vector2};
-use spl_network_messages::{GamePhase, GameState, SubState, Team};
+use nalgebra::{Isometry2, Point2};
+use spl_network_messages::{GamePhase, GameState, Intention, SubState, Team};
 use types::{
+    coordinate_systems::{Field, Ground, Point, Pose},
     parameters::{Behavior as BehaviorParameters, InWalkKicks, InterceptBall, LostBall},
-    Action, CycleTime, FieldDimensions, FilteredGameState, GameControllerState, MotionCommand,
-    PathObstacle, PathSegment, PrimaryState, Role, Side, Step, WorldState,
+    rotate_towards, Action, CycleTime, FieldDimensions, FilteredGameState, GameControllerState,
+    MotionCommand, MotionSafeExits, MotionType, OrientationMode, PathObstacle, PathSegment,
+    PrimaryState, Role, SensorData, Side, Step, WorldState,
 };
 
 use super::{
@@ -16,15 +18,17 @@ use super::{
     defend::Defend,
     dribble, fall_safely,
     head::LookAction,
-    initial, intercept_ball, jump, look_around, lost_ball, penalize, prepare_jump, search,
-    sit_down, stand, stand_up, support, unstiff, walk_to_kick_off, walk_to_penalty_kick,
+    initial, intercept_ball, jump, kick_off, look_around, lost_ball, penalize, penalty_keeper,
+    prepare_jump, search, sit_down, stand, stand_up, support, teleoperate, unstiff, walk_to_ball,
+    walk_to_kick_off, walk_to_penalty_kick,
     walk_to_pose::{WalkAndStand, WalkPathPlanner},
 };
 
 pub struct Behavior {
     last_motion_command: MotionCommand,
-    absolute_last_known_ball_position: Point2<f32>,
+    absolute_last_known_ball_position: Point<Field>,
     active_since: Option<SystemTime>,
+    penalize_phase: penalize::Phase,
 }
 
 #[context]
@@ -43,6 +47,10 @@ pub struct CycleContext {
     pub world_state: Input<WorldState, "world_state">,
     pub cycle_time: Input<CycleTime, "cycle_time">,
     pub dribble_path: Input<Option<Vec<PathSegment>>, "dribble_path?">,
+    pub ball_search_position: Input<Option<Point2<f32>>, "ball_search_position?">,
+    pub kick_off_pose: Input<Option<Isometry2<f32>>, "kick_off_pose?">,
+    pub motion_test_command: Input<Option<MotionCommand>, "motion_test_command?">,
+    pub sensor_data: Input<SensorData, "sensor_data">,
 
     pub parameters: Parameter<BehaviorParameters, "behavior">,
     pub in_walk_kicks: Parameter<InWalkKicks, "in_walk_kicks">,
@@ -50,8 +58,9 @@ pub struct CycleContext {
     pub lost_ball_parameters: Parameter<LostBall, "behavior.lost_ball">,
     pub intercept_ball_parameters: Parameter<InterceptBall, "behavior.intercept_ball">,
     pub maximum_step_size: Parameter<Step, "step_planner.max_step_size">,
-    pub striker_set_position:
-        Parameter<Vector2<f32>, "behavior.role_positions.striker_set_position">,
+
+    pub intention: PersistentState<Option<Intention>, "intention">,
+    pub motion_safe_exits: PersistentState<MotionSafeExits, "motion_safe_exits">,
 }
 
 #[context]
@@ -64,8 +73,9 @@ impl Behavior {
     pub fn new(_context: CreationContext) -> Result<Self> {
         Ok(Self {
             last_motion_command: MotionCommand::Unstiff,
-            absolute_last_known_ball_position: point![0.0, 0.0],
+            absolute_last_known_ball_position: Point::origin(),
             active_since: None,
+            penalize_phase: Default::default(),
         })
     }
 
@@ -76,6 +86,11 @@ impl Behavior {
                 motion_command: command.clone().into(),
             });
         }
+        if let Some(command) = context.motion_test_command {
+            return Ok(MainOutputs {
+                motion_command: command.clone().into(),
+            });
+        }
 
         if let Some(ball_state) = &world_state.ball {
             self.absolute_last_known_ball_position = ball_state.ball_in_field;
@@ -113,6 +128,23 @@ impl Behavior {
             }
         }
 
+        let is_walking_to_kick_off = matches!(
+            world_state.filtered_game_state,
+            Some(FilteredGameState::Ready { .. })
+        ) && !matches!(
+            world_state.game_controller_state,
+            Some(GameControllerState {
+                sub_state: Some(SubState::PenaltyKick),
+                ..
+            })
+        ) && !matches!(
+            world_state.robot.role,
+            Role::Keeper | Role::ReplacementKeeper
+        );
+        if is_walking_to_kick_off {
+            actions.push(Action::WalkToKickOff);
+        }
+
         match world_state.robot.role {
             Role::DefenderLeft => actions.push(Action::DefendLeft),
             Role::DefenderRight => actions.push(Action::DefendRight),
@@ -120,10 +152,7 @@ impl Behavior {
                 Some(GameControllerState {
                     game_phase: GamePhase::PenaltyShootout { .. },
                     ..
-                }) => {
-                    actions.push(Action::Jump);
-                    actions.push(Action::PrepareJump);
-                }
+                }) => actions.push(Action::PenaltyKeeper),
                 _ => actions.push(Action::DefendGoal),
             },
             Role::Loser => actions.push(Action::SearchForLostBall),
@@ -133,7 +162,10 @@ impl Behavior {
             Role::Searcher => actions.push(Action::Search),
             Role::Striker => match world_state.filtered_game_state {
                 None | Some(FilteredGameState::Playing { ball_is_free: true }) => {
+                    actions.push(Action::KickOff);
                     actions.push(Action::Dribble);
+                    actions.push(Action::WalkToBall);
+                    actions.push(Action::Search);
                 }
                 Some(FilteredGameState::Ready {
                     kicking_team: Team::Hulks,
@@ -142,7 +174,7 @@ impl Behavior {
                         sub_state: Some(SubState::PenaltyKick),
                         ..
                     }) => actions.push(Action::WalkToPenaltyKick),
-                    _ => actions.push(Action::WalkToKickOff),
+                    _ => actions.push(Action::DefendKickOff),
                 },
                 _ => match world_state.game_controller_state {
                     Some(GameControllerState {
@@ -154,9 +186,29 @@ impl Behavior {
                     _ => actions.push(Action::DefendKickOff),
                 },
             },
-            Role::StrikerSupporter => actions.push(Action::SupportStriker),
+            Role::StrikerSupporter => {
+                actions.push(Action::ReceiveKickOff);
+                actions.push(Action::SupportStriker);
+            }
         };
 
+        if context.parameters.teleoperation.enable {
+            actions = vec![
+                Action::Unstiff,
+                Action::SitDown,
+                Action::FallSafely,
+                Action::StandUp,
+                Action::Teleoperate,
+            ];
+        }
+
+        let kick_off_target = kick_off::indirect_target(
+            world_state,
+            context.field_dimensions,
+            context.parameters.kick_off_strategy,
+            &context.parameters.kick_off,
+            now,
+        );
         let walk_path_planner = WalkPathPlanner::new(
             context.field_dimensions,
             &world_state.obstacles,
@@ -183,7 +235,15 @@ impl Behavior {
                 let motion_command = match action {
                     Action::Unstiff => unstiff::execute(world_state),
                     Action::SitDown => sit_down::execute(world_state),
-                    Action::Penalize => penalize::execute(world_state),
+                    Action::Penalize => penalize::execute(
+                        world_state,
+                        &mut self.penalize_phase,
+                        &self.last_motion_command,
+                        context.motion_safe_exits[MotionType::Walk],
+                        context.sensor_data.positions.head,
+                        &context.parameters.penalize,
+                        now,
+                    ),
                     Action::Initial => initial::execute(world_state),
                     Action::FallSafely => {
                         fall_safely::execute(world_state, *context.has_ground_contact)
@@ -208,14 +268,39 @@ impl Behavior {
                         context.in_walk_kicks,
                         &context.parameters.dribbling,
                         context.dribble_path.cloned(),
+                        now,
+                    ),
+                    Action::KickOff => kick_off::execute(
+                        world_state,
+                        &walk_path_planner,
+                        context.in_walk_kicks,
+                        &context.parameters.dribbling,
+                        &context.parameters.kick_off,
+                        kick_off_target,
+                        &mut context.path_obstacles,
                     ),
                     Action::Jump => jump::execute(world_state),
                     Action::PrepareJump => prepare_jump::execute(world_state),
-                    Action::Search => search::execute(
+                    Action::PenaltyKeeper => penalty_keeper::execute(
                         world_state,
-                        &walk_path_planner,
                         &walk_and_stand,
                         context.field_dimensions,
+                        &context.parameters.penalty_keeper,
+                        &mut context.path_obstacles,
+                    ),
+                    Action::ReceiveKickOff => kick_off::receive(
+                        world_state,
+                        &walk_and_stand,
+                        context.field_dimensions,
+                        &context.parameters.kick_off,
+                        now,
+                        &mut context.path_obstacles,
+                    ),
+                    Action::Teleoperate => teleoperate::execute(&context.parameters.teleoperation),
+                    Action::Search => search::execute(
+                        world_state,
+                        &walk_path_planner,
+                        context.ball_search_position.copied(),
                         &context.parameters.search,
                         &mut context.path_obstacles,
                     ),
@@ -280,12 +365,18 @@ impl Behavior {
                         &look_action,
                         &mut context.path_obstacles,
                     ),
+                    Action::WalkToBall => walk_to_ball::execute(
+                        world_state,
+                        &walk_and_stand,
+                        &context.parameters.walk_to_ball,
+                        &mut context.path_obstacles,
+                    ),
                     Action::WalkToKickOff => walk_to_kick_off::execute(
                         world_state,
                         &walk_and_stand,
                         &look_action,
                         &mut context.path_obstacles,
-                        *context.striker_set_position,
+                        context.kick_off_pose.copied(),
                     ),
                     Action::WalkToPenaltyKick => walk_to_penalty_kick::execute(
                         world_state,
@@ -305,9 +396,56 @@ impl Behavior {
         context.active_action.fill_if_subscribed(|| *action);
 
         self.last_motion_command = motion_command.clone();
+        *context.intention = intention(*action, &motion_command, world_state, kick_off_target);
 
         Ok(MainOutputs {
             motion_command: motion_command.into(),
         })
     }
 }
+
+/// Where this robot is heading and kicking to in field coordinates, announced to teammates
+fn intention(
+    action: Action,
+    motion_command: &MotionCommand,
+    world_state: &WorldState,
+    kick_off_target: Option<Point<Field>>,
+) -> Option<Intention> {
+    let robot_to_field = world_state.robot.robot_to_field?;
+    let MotionCommand::Walk {
+        path,
+        orientation_mode,
+        ..
+    } = motion_command
+    else {
+        return None;
+    };
+    let target_pose = path_end_pose(path, *orientation_mode)?;
+    let kick_target_in_field = match action {
+        Action::Dribble => world_state
+            .kick_decisions
+            .as_ref()
+            .and_then(|kick_decisions| kick_decisions.first())
+            .map(|kick_decision| (robot_to_field * Point::wrap(kick_decision.target)).inner),
+        Action::KickOff => kick_off_target.map(|target| target.inner),
+        _ => None,
+    };
+    Some(Intention {
+        target_pose_in_field: (robot_to_field * target_pose).inner,
+        kick_target_in_field,
+    })
+}
+
+fn path_end_pose(path: &[PathSegment], orientation_mode: OrientationMode) -> Option<Pose<Ground>> {
+    let last_segment = path.last()?;
+    let (approach, end) = (last_segment.tangent_at(1.0), last_segment.end());
+    let rotation = match orientation_mode {
+        OrientationMode::AlignWithPath => rotate_towards(end - approach, end),
+        OrientationMode::Override(orientation) => orientation,
+        OrientationMode::FaceTowards(target) => rotate_towards(end, target),
+    };
+    Some(Pose::wrap(Isometry2::from_parts(
+        end.coords.into(),
+        rotation,
+    )))
+}
@@ -1,8 +1,256 @@
-use types::{MotionCommand, PrimaryState, WorldState};
+use std::time::SystemTime;
 
-pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
-    match world_state.robot.primary_state {
-        PrimaryState::Penalized => Some(MotionCommand::Penalized),
-        _ => None,
+use types::{
+    parameters::Penalize, HeadJoints, HeadMotion, MotionCommand, PrimaryState, WorldState,
+};
+
+/// Progress of assuming the penalized pose
+///
+/// Cutting to the penalized pose while walking or kicking may destabilize the robot. A robot that
+/// walks or stands when being penalized therefore first finishes its step or kick, stands still for
+/// a moment and zeroes its head before it locks in the penalized pose. Robots not making progress
+/// are locked after a timeout.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Phase {
+    #[default]
+    NotPenalized,
+    Stopping {
+        penalized_since: SystemTime,
+    },
+    Standing {
+        penalized_since: SystemTime,
+        standing_since: SystemTime,
+    },
+    ZeroingHead {
+        penalized_since: SystemTime,
+    },
+    Locked,
+}
+
+pub fn execute(
+    world_state: &WorldState,
+    phase: &mut Phase,
+    last_motion_command: &MotionCommand,
+    walking_engine_is_standing: bool,
+    head_positions: HeadJoints<f32>,
+    parameters: &Penalize,
+    now: SystemTime,
+) -> Option<MotionCommand> {
+    if world_state.robot.primary_state != PrimaryState::Penalized {
+        *phase = Phase::NotPenalized;
+        return None;
+    }
+    *phase = next_phase(
+        *phase,
+        last_motion_command,
+        walking_engine_is_standing,
+        head_positions,
+        parameters,
+        now,
+    );
+    Some(match phase {
+        Phase::Stopping { .. } | Phase::Standing { .. } => MotionCommand::Stand {
+            head: last_motion_command
+                .head_motion()
+                .unwrap_or(HeadMotion::ZeroAngles),
+            is_energy_saving: false,
+        },
+        Phase::ZeroingHead { .. } => MotionCommand::Stand {
+            head: HeadMotion::ZeroAngles,
+            is_energy_saving: false,
+        },
+        Phase::NotPenalized | Phase::Locked => MotionCommand::Penalized,
+    })
+}
+
+fn next_phase(
+    phase: Phase,
+    last_motion_command: &MotionCommand,
+    walking_engine_is_standing: bool,
+    head_positions: HeadJoints<f32>,
+    parameters: &Penalize,
+    now: SystemTime,
+) -> Phase {
+    let penalized_since = match phase {
+        Phase::NotPenalized => {
+            return match last_motion_command {
+                MotionCommand::Stand { .. }
+                | MotionCommand::Walk { .. }
+                | MotionCommand::InWalkKick { .. } => Phase::Stopping {
+                    penalized_since: now,
+                },
+                _ => Phase::Locked,
+            }
+        }
+        Phase::Locked => return Phase::Locked,
+        Phase::Stopping { penalized_since }
+        | Phase::Standing {
+            penalized_since, ..
+        }
+        | Phase::ZeroingHead { penalized_since } => penalized_since,
+    };
+    if now.duration_since(penalized_since).unwrap_or_default() >= parameters.timeout {
+        return Phase::Locked;
+    }
+
+    match phase {
+        Phase::Stopping { .. } if walking_engine_is_standing => Phase::Standing {
+            penalized_since,
+            standing_since: now,
+        },
+        Phase::Standing { .. } if !walking_engine_is_standing => {
+            Phase::Stopping { penalized_since }
+        }
+        Phase::Standing { standing_since, .. }
+            if now.duration_since(standing_since).unwrap_or_default()
+                >= parameters.minimum_stand_duration =>
+        {
+            Phase::ZeroingHead { penalized_since }
+        }
+        Phase::ZeroingHead { .. }
+            if head_positions.yaw.abs() <= parameters.head_zero_tolerance
+                && head_positions.pitch.abs() <= parameters.head_zero_tolerance =>
+        {
+            Phase::Locked
+        }
+        phase => phase,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use types::{test_support::WorldStateBuilder, ArmMotion, OrientationMode};
+
+    use super::*;
+
+    fn parameters() -> Penalize {
+        Penalize {
+            minimum_stand_duration: Duration::from_millis(300),
+            head_zero_tolerance: 0.05,
+            timeout: Duration::from_secs(4),
+        }
+    }
+
+    fn walk() -> MotionCommand {
+        MotionCommand::Walk {
+            head: HeadMotion::Center,
+            path: vec![],
+            left_arm: ArmMotion::Swing,
+            right_arm: ArmMotion::Swing,
+            orientation_mode: OrientationMode::AlignWithPath,
+            speed_profile: None,
+            arrival_tolerance: None,
+        }
+    }
+
+    #[test]
+    fn penalized_only_when_primary_state_is_penalized() {
+        let penalized = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Penalized)
+            .build();
+        let playing = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Playing)
+            .build();
+        let head = HeadJoints::fill(0.0);
+        let now = SystemTime::UNIX_EPOCH;
+        let mut phase = Phase::default();
+
+        assert_eq!(
+            execute(
+                &penalized,
+                &mut phase,
+                &MotionCommand::Unstiff,
+                false,
+                head,
+                &parameters(),
+                now
+            ),
+            Some(MotionCommand::Penalized)
+        );
+        assert_eq!(
+            execute(
+                &playing,
+                &mut phase,
+                &MotionCommand::Penalized,
+                false,
+                head,
+                &parameters(),
+                now
+            ),
+            None
+        );
+        assert_eq!(phase, Phase::NotPenalized);
+    }
+
+    #[test]
+    fn walking_robot_stops_and_zeroes_head_before_locking() {
+        let parameters = parameters();
+        let start = SystemTime::UNIX_EPOCH;
+        let turned_head = HeadJoints {
+            yaw: 0.5,
+            pitch: 0.2,
+        };
+        let zeroed_head = HeadJoints::fill(0.01);
+
+        let phase = next_phase(
+            Phase::NotPenalized,
+            &walk(),
+            false,
+            turned_head,
+            &parameters,
+            start,
+        );
+        assert_eq!(
+            phase,
+            Phase::Stopping {
+                penalized_since: start
+            }
+        );
+
+        let still_kicking = start + Duration::from_millis(200);
+        let phase = next_phase(
+            phase,
+            &walk(),
+            false,
+            turned_head,
+            &parameters,
+            still_kicking,
+        );
+        assert!(matches!(phase, Phase::Stopping { .. }));
+
+        let standing = start + Duration::from_millis(500);
+        let phase = next_phase(phase, &walk(), true, turned_head, &parameters, standing);
+        assert!(matches!(phase, Phase::Standing { .. }));
+
+        let settled = standing + parameters.minimum_stand_duration;
+        let phase = next_phase(phase, &walk(), true, turned_head, &parameters, settled);
+        assert!(matches!(phase, Phase::ZeroingHead { .. }));
+
+        let phase = next_phase(phase, &walk(), true, turned_head, &parameters, settled);
+        assert!(matches!(phase, Phase::ZeroingHead { .. }));
+        let phase = next_phase(phase, &walk(), true, zeroed_head, &parameters, settled);
+        assert_eq!(phase, Phase::Locked);
+    }
+
+    #[test]
+    fn robot_is_locked_after_timeout() {
+        let parameters = parameters();
+        let start = SystemTime::UNIX_EPOCH;
+        let phase = Phase::Stopping {
+            penalized_since: start,
+        };
+
+        let phase = next_phase(
+            phase,
+            &walk(),
+            false,
+            HeadJoints::fill(0.0),
+            &parameters,
+            start + parameters.timeout,
+        );
+
+        assert_eq!(phase, Phase::Locked);
     }
 }
@@ -0,0 +1,140 @@
+use framework::AdditionalOutput;
+use nalgebra::{Isometry2, Point2, Vector2};
+use types::{
+    coordinate_systems::{Field, Pose},
+    parameters::PenaltyKeeper,
+    FieldDimensions, HeadMotion, JumpDirection, MotionCommand, PathObstacle, WorldState,
+};
+
+use super::{prepare_jump, walk_to_pose::WalkAndStand};
+
+/// Waits on the center of the goal line and dives towards a shot once it is recognized
+pub fn execute(
+    world_state: &WorldState,
+    walk_and_stand: &WalkAndStand,
+    field_dimensions: &FieldDimensions,
+    parameters: &PenaltyKeeper,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    let robot_to_field = world_state.robot.robot_to_field?;
+
+    if let Some(ball) = world_state.ball {
+        if let Some(direction) = dive_direction(
+            ball.ball_in_ground.inner,
+            ball.ball_in_ground_velocity,
+            field_dimensions,
+            parameters,
+        ) {
+            return Some(MotionCommand::Jump { direction });
+        }
+    }
+
+    let goal_line_center = Pose::<Field>::wrap(Isometry2::translation(
+        -field_dimensions.length.0 / 2.0,
+        0.0,
+    ));
+    let goal_line_center_in_robot = robot_to_field.inverse() * goal_line_center;
+    if goal_line_center_in_robot.inner.translation.vector.norm() > parameters.position_tolerance {
+        let head = match world_state.ball {
+            Some(ball) => HeadMotion::LookAt {
+                target: ball.ball_in_ground.inner,
+                camera: None,
+            },
+            None => HeadMotion::Center,
+        };
+        return walk_and_stand.execute(goal_line_center_in_robot, head, path_obstacles_output);
+    }
+
+    prepare_jump::execute(world_state)
+}
+
+/// Predicts where the ball crosses the line of the keeper, both in robot coordinates
+///
+/// Perceiving the shot and starting the dive takes the reaction time, shots arriving earlier can
+/// not be reached anymore. Shots close to the keeper are blocked by the squat, shots missing the
+/// goal are ignored.
+fn dive_direction(
+    ball_position: Point2<f32>,
+    ball_velocity: Vector2<f32>,
+    field_dimensions: &FieldDimensions,
+    parameters: &PenaltyKeeper,
+) -> Option<JumpDirection> {
+    let is_shot =
+        ball_velocity.norm() > parameters.minimum_ball_velocity.0 && ball_velocity.x < 0.0;
+    if !is_shot {
+        return None;
+    }
+    let time_to_keeper_line = -ball_position.x / ball_velocity.x;
+    if time_to_keeper_line < parameters.reaction_time.as_secs_f32() {
+        return None;
+    }
+    let crossing = ball_position + ball_velocity * time_to_keeper_line;
+    let is_on_goal =
+        crossing.y.abs() < field_dimensions.goal_inner_width.0 / 2.0 + parameters.goal_post_margin;
+    if !is_on_goal || crossing.y.abs() < parameters.squat_reach {
+        return None;
+    }
+    Some(if crossing.y > 0.0 {
+        JumpDirection::Left
+    } else {
+        JumpDirection::Right
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use nalgebra::{point, vector};
+    use types::units::{Meters, MetersPerSecond};
+
+    use super::*;
+
+    fn parameters() -> PenaltyKeeper {
+        PenaltyKeeper {
+            minimum_ball_velocity: MetersPerSecond(0.5),
+            reaction_time: Duration::from_millis(300),
+            squat_reach: 0.2,
+            goal_post_margin: 0.1,
+            position_tolerance: 0.3,
+        }
+    }
+
+    fn field_dimensions() -> FieldDimensions {
+        FieldDimensions {
+            goal_inner_width: Meters(1.5),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn dives_towards_shots_on_goal() {
+        let dive = |ball_velocity| {
+            dive_direction(
+                point![3.0, 0.0],
+                ball_velocity,
+                &field_dimensions(),
+                &parameters(),
+            )
+        };
+
+        assert_eq!(dive(vector![-2.0, 0.4]), Some(JumpDirection::Left));
+        assert_eq!(dive(vector![-2.0, -0.4]), Some(JumpDirection::Right));
+        assert_eq!(dive(vector![-2.0, 0.1]), None);
+        assert_eq!(dive(vector![-2.0, 2.0]), None);
+        assert_eq!(dive(vector![-0.3, 0.05]), None);
+    }
+
+    #[test]
+    fn does_not_dive_for_shots_faster_than_the_reaction_time() {
+        assert_eq!(
+            dive_direction(
+                point![0.5, 0.0],
+                vector![-2.0, 1.0],
+                &field_dimensions(),
+                &parameters()
+            ),
+            None
+        );
+    }
+}
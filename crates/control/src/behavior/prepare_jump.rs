@@ -3,3 +3,17 @@ use types::{MotionCommand, WorldState};
 pub fn execute(_world_state: &WorldState) -> Option<MotionCommand> {
     Some(MotionCommand::ArmsUpSquat)
 }
+
+#[cfg(test)]
+mod tests {
+    use types::test_support::WorldStateBuilder;
+
+    use super::*;
+
+    #[test]
+    fn always_squats_with_arms_up() {
+        let world_state = WorldStateBuilder::default().build();
+
+        assert_eq!(execute(&world_state), Some(MotionCommand::ArmsUpSquat));
+    }
+}
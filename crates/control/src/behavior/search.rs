@@ -1,113 +1,74 @@
 use framework::AdditionalOutput;
-use nalgebra::{point, Isometry2, Point2, UnitComplex};
+use nalgebra::{Point2, UnitComplex};
 use types::{
-    parameters::Search as SearchParameters, FieldDimensions, HeadMotion, MotionCommand,
-    OrientationMode, PathObstacle, Side, WorldState,
+    coordinate_systems::Point, parameters::Search as SearchParameters, HeadMotion, MotionCommand,
+    OrientationMode, PathObstacle, WorldState,
 };
 
-use super::walk_to_pose::{WalkAndStand, WalkPathPlanner};
-
-#[derive(Clone, Copy)]
-enum SearchRole {
-    Goal,
-    Defend { side: Side },
-    Center,
-    Aggressive,
-}
-
-impl SearchRole {
-    fn to_position(
-        self,
-        robot_to_field: Isometry2<f32>,
-        field_dimensions: &FieldDimensions,
-    ) -> Point2<f32> {
-        let goal = point![-field_dimensions.length / 2.0, 0.0];
-        let defending_left = point![
-            -field_dimensions.length / 2.0 + field_dimensions.goal_box_area_length + 0.2,
-            field_dimensions.goal_box_area_width / 2.0
-        ];
-        let defending_right = point![
-            -field_dimensions.length / 2.0 + field_dimensions.penalty_area_length + 0.2,
-            -field_dimensions.penalty_area_width / 2.0
-        ];
-        let center = point![0.0, 0.0];
-        let aggressive = point![
-            field_dimensions.length / 2.0 - field_dimensions.penalty_area_length,
-            0.0
-        ];
-
-        robot_to_field.inverse()
-            * match self {
-                SearchRole::Goal => goal,
-                SearchRole::Defend { side: Side::Left } => defending_left,
-                SearchRole::Defend { side: Side::Right } => defending_right,
-                SearchRole::Center => center,
-                SearchRole::Aggressive => aggressive,
-            }
-    }
-}
+use super::walk_to_pose::WalkPathPlanner;
 
+/// Walks towards the most promising position of the ball search heatmap, given in field
+/// coordinates, and turns on the spot once it is reached
 pub fn execute(
     world_state: &WorldState,
     walk_path_planner: &WalkPathPlanner,
-    walk_and_stand: &WalkAndStand,
-    field_dimensions: &FieldDimensions,
+    ball_search_position: Option<Point2<f32>>,
     parameters: &SearchParameters,
     path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
 ) -> Option<MotionCommand> {
     let robot_to_field = world_state.robot.robot_to_field?;
-    let search_role = assign_search_role(world_state);
-    let search_position = search_role
-        .map(|role| role.to_position(robot_to_field, field_dimensions))
-        .unwrap_or(point![0.0, 0.0]);
-    let head = HeadMotion::SearchForLostBall;
-    if let Some(SearchRole::Goal) = search_role {
-        let goal_pose = robot_to_field.inverse() * Isometry2::from(search_position.coords);
-        walk_and_stand.execute(goal_pose, head, path_obstacles_output)
+    let search_position = robot_to_field.inverse()
+        * ball_search_position
+            .map(Point::wrap)
+            .unwrap_or(Point::origin());
+    let path = walk_path_planner.plan(
+        search_position,
+        robot_to_field,
+        None,
+        1.0,
+        &world_state.obstacles,
+        &world_state.rule_obstacles,
+        path_obstacles_output,
+    );
+    let path_length: f32 = path.iter().map(|segment| segment.length()).sum();
+    let is_reached = path_length < parameters.position_reached_distance.0;
+    let orientation_mode = if is_reached {
+        OrientationMode::Override(UnitComplex::new(parameters.rotation_per_step))
     } else {
-        let path = walk_path_planner.plan(
-            search_position,
-            robot_to_field,
-            None,
-            1.0,
-            &world_state.obstacles,
-            &world_state.rule_obstacles,
-            path_obstacles_output,
-        );
-        let path_length: f32 = path.iter().map(|segment| segment.length()).sum();
-        let is_reached = path_length < parameters.position_reached_distance;
-        let orientation_mode = if is_reached {
-            OrientationMode::Override(UnitComplex::new(parameters.rotation_per_step))
-        } else {
-            OrientationMode::AlignWithPath
-        };
-        Some(walk_path_planner.walk_with_obstacle_avoiding_arms(head, orientation_mode, path))
-    }
+        OrientationMode::AlignWithPath
+    };
+    Some(walk_path_planner.walk_with_obstacle_avoiding_arms(
+        HeadMotion::SearchForLostBall,
+        orientation_mode,
+        path,
+    ))
 }
 
-fn assign_search_role(world_state: &WorldState) -> Option<SearchRole> {
-    let search_roles = [
-        SearchRole::Goal,
-        SearchRole::Defend { side: Side::Left },
-        SearchRole::Defend { side: Side::Right },
-        SearchRole::Center,
-        SearchRole::Aggressive,
-    ]
-    .into_iter();
-    let penalties = world_state
-        .game_controller_state
-        .map(|state| state.penalties)?;
-    let available_players = penalties
-        .iter()
-        .filter_map(|(number, penalty)| match penalty {
-            Some(_) => None,
-            None => Some(number),
-        });
+#[cfg(test)]
+mod tests {
+    use types::{parameters::PathPlanning, test_support::WorldStateBuilder, FieldDimensions};
+
+    use super::*;
 
-    available_players
-        .zip(search_roles)
-        .find_map(|(number, position)| {
-            let is_my_player_number = number == world_state.robot.player_number;
-            is_my_player_number.then_some(position)
-        })
+    #[test]
+    fn does_nothing_without_robot_pose() {
+        let world_state = WorldStateBuilder::default()
+            .without_robot_to_field()
+            .build();
+        let field_dimensions = FieldDimensions::default();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let mut path_obstacles = None;
+
+        assert_eq!(
+            execute(
+                &world_state,
+                &walk_path_planner,
+                None,
+                &SearchParameters::default(),
+                &mut AdditionalOutput::new(false, &mut path_obstacles),
+            ),
+            None
+        );
+    }
 }
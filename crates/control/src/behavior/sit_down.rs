@@ -1,10 +1,48 @@
-use types::{HeadMotion, MotionCommand, PrimaryState, WorldState};
+use types::{HeadMotion, MotionCommand, PrimaryState, SystemWarning, WorldState};
 
 pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
-    match world_state.robot.primary_state {
-        PrimaryState::Finished => Some(MotionCommand::SitDown {
+    let is_battery_critical = world_state
+        .robot
+        .system_warnings
+        .contains(&SystemWarning::CriticalBattery);
+    match (world_state.robot.primary_state, is_battery_critical) {
+        (PrimaryState::Finished, _) | (_, true) => Some(MotionCommand::SitDown {
             head: HeadMotion::Unstiff,
         }),
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::test_support::WorldStateBuilder;
+
+    use super::*;
+
+    const SIT_DOWN: MotionCommand = MotionCommand::SitDown {
+        head: HeadMotion::Unstiff,
+    };
+
+    #[test]
+    fn sits_down_when_finished() {
+        let world_state = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Finished)
+            .build();
+
+        assert_eq!(execute(&world_state), Some(SIT_DOWN));
+    }
+
+    #[test]
+    fn sits_down_on_critical_battery_in_any_state() {
+        let playing = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Playing)
+            .build();
+        let critical_battery = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Playing)
+            .with_system_warning(SystemWarning::CriticalBattery)
+            .build();
+
+        assert_eq!(execute(&playing), None);
+        assert_eq!(execute(&critical_battery), Some(SIT_DOWN));
+    }
+}
@@ -1,7 +1,8 @@
-use nalgebra::{point, Point2};
+use nalgebra::point;
 use spl_network_messages::{GamePhase, SubState, Team};
 use types::{
-    FieldDimensions, GameControllerState, HeadMotion, MotionCommand, PrimaryState, Role, WorldState,
+    coordinate_systems::Point, FieldDimensions, GameControllerState, HeadMotion, MotionCommand,
+    PrimaryState, Role, WorldState,
 };
 
 pub fn execute(
@@ -25,12 +26,11 @@ pub fn execute(
                         Team::Opponent => -1.0,
                         _ => 1.0,
                     };
-                    let penalty_spot_x =
-                        field_dimensions.length / 2.0 - field_dimensions.penalty_marker_distance;
-                    let penalty_spot_location = point![side_factor * penalty_spot_x, 0.0];
-                    robot_to_field.inverse() * penalty_spot_location
+                    let penalty_spot_location =
+                        point![side_factor * field_dimensions.penalty_spot_x().0, 0.0];
+                    robot_to_field.inverse() * Point::wrap(penalty_spot_location)
                 }
-                _ => robot_to_field.inverse() * Point2::origin(),
+                _ => robot_to_field.inverse() * Point::origin(),
             };
             let target = world_state
                 .ball
@@ -38,7 +38,7 @@ pub fn execute(
                 .unwrap_or(fallback_target);
             Some(MotionCommand::Stand {
                 head: HeadMotion::LookAt {
-                    target,
+                    target: target.inner,
                     camera: None,
                 },
                 is_energy_saving: true,
@@ -67,3 +67,99 @@ pub fn execute(
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::{
+        test_support::{BallStateBuilder, GameControllerStateBuilder, WorldStateBuilder},
+        units::Meters,
+    };
+
+    use super::*;
+
+    #[test]
+    fn looks_at_ball_in_set() {
+        let world_state = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Set)
+            .with_ball(
+                BallStateBuilder::default()
+                    .in_ground(point![1.0, 0.5])
+                    .build(),
+            )
+            .build();
+
+        assert_eq!(
+            execute(&world_state, &FieldDimensions::default()),
+            Some(MotionCommand::Stand {
+                head: HeadMotion::LookAt {
+                    target: point![1.0, 0.5],
+                    camera: None,
+                },
+                is_energy_saving: true,
+            })
+        );
+    }
+
+    #[test]
+    fn looks_at_penalty_spot_in_set_of_penalty_kick_without_ball() {
+        let field_dimensions = FieldDimensions {
+            length: Meters(9.0),
+            penalty_marker_distance: Meters(1.5),
+            ..Default::default()
+        };
+        let world_state = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Set)
+            .with_game_controller_state(
+                GameControllerStateBuilder::default()
+                    .with_kicking_team(Team::Opponent)
+                    .with_sub_state(SubState::PenaltyKick)
+                    .build(),
+            )
+            .build();
+
+        assert_eq!(
+            execute(&world_state, &field_dimensions),
+            Some(MotionCommand::Stand {
+                head: HeadMotion::LookAt {
+                    target: point![-3.0, 0.0],
+                    camera: None,
+                },
+                is_energy_saving: true,
+            })
+        );
+    }
+
+    #[test]
+    fn striker_centers_head_in_penalty_shootout_without_ball() {
+        let world_state = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Playing)
+            .with_role(Role::Striker)
+            .with_game_controller_state(
+                GameControllerStateBuilder::default()
+                    .with_game_phase(GamePhase::PenaltyShootout {
+                        kicking_team: Team::Hulks,
+                    })
+                    .build(),
+            )
+            .build();
+
+        assert_eq!(
+            execute(&world_state, &FieldDimensions::default()),
+            Some(MotionCommand::Stand {
+                head: HeadMotion::Center,
+                is_energy_saving: true,
+            })
+        );
+    }
+
+    #[test]
+    fn does_not_stand_while_playing_normally() {
+        let world_state = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Playing)
+            .with_role(Role::Striker)
+            .with_game_controller_state(GameControllerStateBuilder::default().build())
+            .build();
+
+        assert_eq!(execute(&world_state, &FieldDimensions::default()), None);
+    }
+}
@@ -6,3 +6,32 @@ pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::{test_support::WorldStateBuilder, Facing, FallDirection};
+
+    use super::*;
+
+    #[test]
+    fn stands_up_only_when_fallen() {
+        let fallen = WorldStateBuilder::default()
+            .with_fall_state(FallState::Fallen {
+                facing: Facing::Down,
+            })
+            .build();
+        let falling = WorldStateBuilder::default()
+            .with_fall_state(FallState::Falling {
+                direction: FallDirection::Forward,
+            })
+            .build();
+
+        assert_eq!(
+            execute(&fallen),
+            Some(MotionCommand::StandUp {
+                facing: Facing::Down
+            })
+        );
+        assert_eq!(execute(&falling), None);
+    }
+}
@@ -3,6 +3,7 @@ use std::f32::consts::FRAC_PI_4;
 use framework::AdditionalOutput;
 use nalgebra::{point, Isometry2, UnitComplex, Vector2};
 use types::{
+    coordinate_systems::{Field, Ground, Pose},
     rotate_towards, BallState, FieldDimensions, FilteredGameState, MotionCommand, PathObstacle,
     Side, WorldState,
 };
@@ -39,7 +40,7 @@ fn support_pose(
     distance_to_ball: f32,
     maximum_x_in_ready_and_when_ball_is_not_free: f32,
     minimum_x: f32,
-) -> Option<Isometry2<f32>> {
+) -> Option<Pose<Ground>> {
     let robot_to_field = world_state.robot.robot_to_field?;
     let ball = world_state
         .rule_ball
@@ -50,7 +51,7 @@ fn support_pose(
         Side::Left => -FRAC_PI_4,
         Side::Right => FRAC_PI_4,
     }) * -(Vector2::x() * distance_to_ball);
-    let supporting_position = ball.ball_in_field + offset_vector;
+    let supporting_position = ball.ball_in_field.inner + offset_vector;
     let clamped_x = match world_state.filtered_game_state {
         Some(FilteredGameState::Ready { .. })
         | Some(FilteredGameState::Playing {
@@ -61,15 +62,134 @@ fn support_pose(
         ),
         _ => supporting_position
             .x
-            .clamp(minimum_x, field_dimensions.length / 2.0),
+            .clamp(minimum_x, field_dimensions.length.0 / 2.0),
     };
-    let clamped_y = supporting_position
-        .y
-        .clamp(-field_dimensions.width / 2.0, field_dimensions.width / 2.0);
+    let clamped_y = supporting_position.y.clamp(
+        -field_dimensions.width.0 / 2.0,
+        field_dimensions.width.0 / 2.0,
+    );
     let clamped_position = point![clamped_x, clamped_y];
-    let support_pose = Isometry2::new(
+    let support_pose = Pose::<Field>::wrap(Isometry2::new(
         clamped_position.coords,
-        rotate_towards(clamped_position, ball.ball_in_field).angle(),
-    );
+        rotate_towards(clamped_position, ball.ball_in_field.inner).angle(),
+    ));
     Some(robot_to_field.inverse() * support_pose)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    use nalgebra::{point, vector};
+    use types::{
+        coordinate_systems::Transform,
+        parameters::{PathPlanning, WalkAndStand as WalkAndStandParameters},
+        test_support::WorldStateBuilder,
+        units::Meters,
+        HeadMotion,
+    };
+
+    use crate::behavior::walk_to_pose::WalkPathPlanner;
+
+    use super::*;
+
+    fn walk_and_stand_parameters() -> WalkAndStandParameters {
+        WalkAndStandParameters {
+            target_reached_thresholds: vector![0.1, 0.1],
+            hybrid_align_distance: Meters(1.0),
+            distance_to_be_aligned: Meters(0.5),
+            ..Default::default()
+        }
+    }
+
+    fn field_dimensions() -> FieldDimensions {
+        FieldDimensions {
+            length: Meters(9.0),
+            width: Meters(6.0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn supports_behind_the_ball_on_given_side() {
+        let world_state = WorldStateBuilder::default().build();
+
+        let pose = support_pose(
+            &world_state,
+            &field_dimensions(),
+            Some(Side::Left),
+            1.0,
+            0.0,
+            -4.5,
+        )
+        .unwrap();
+
+        assert!(
+            (pose.inner.translation.vector - vector![-FRAC_1_SQRT_2, FRAC_1_SQRT_2]).norm() < 1e-5
+        );
+        assert!((pose.inner.rotation.angle() + FRAC_PI_4).abs() < 1e-5);
+    }
+
+    #[test]
+    fn stands_looking_at_position_of_interest_at_support_pose() {
+        let world_state = WorldStateBuilder::default()
+            .with_robot_to_field(Transform::wrap(Isometry2::new(
+                vector![-FRAC_1_SQRT_2, FRAC_1_SQRT_2],
+                -FRAC_PI_4,
+            )))
+            .with_position_of_interest(point![0.5, 0.5])
+            .build();
+        let field_dimensions = field_dimensions();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let parameters = walk_and_stand_parameters();
+        let walk_and_stand = WalkAndStand::new(
+            &world_state,
+            &parameters,
+            &walk_path_planner,
+            &MotionCommand::Unstiff,
+        );
+        let look_action = LookAction::new(&world_state);
+        let mut path_obstacles = None;
+
+        assert_eq!(
+            execute(
+                &world_state,
+                &field_dimensions,
+                Some(Side::Left),
+                1.0,
+                0.0,
+                -4.5,
+                &walk_and_stand,
+                &look_action,
+                &mut AdditionalOutput::new(false, &mut path_obstacles),
+            ),
+            Some(MotionCommand::Stand {
+                head: HeadMotion::LookAt {
+                    target: point![0.5, 0.5],
+                    camera: None,
+                },
+                is_energy_saving: true,
+            })
+        );
+    }
+
+    #[test]
+    fn does_not_support_without_robot_pose() {
+        let world_state = WorldStateBuilder::default()
+            .without_robot_to_field()
+            .build();
+
+        assert_eq!(
+            support_pose(
+                &world_state,
+                &field_dimensions(),
+                Some(Side::Left),
+                1.0,
+                0.0,
+                -4.5,
+            ),
+            None
+        );
+    }
+}
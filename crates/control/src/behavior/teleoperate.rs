@@ -0,0 +1,123 @@
+use std::f32::consts::FRAC_PI_2;
+
+use nalgebra::{vector, Point2, UnitComplex, Vector2};
+use types::{
+    direct_path, parameters::Teleoperation, ArmMotion, HeadMotion, MotionCommand, OrientationMode,
+    SpeedProfile,
+};
+
+/// Drives the robot with the commands of a joystick, the robot stands if the commands stop
+pub fn execute(parameters: &Teleoperation) -> Option<MotionCommand> {
+    if !parameters.enable {
+        return None;
+    }
+    let Some(command) = parameters.command else {
+        return Some(MotionCommand::Stand {
+            head: HeadMotion::Center,
+            is_energy_saving: false,
+        });
+    };
+    let head = HeadMotion::Angles {
+        yaw: command.head.yaw,
+        pitch: command.head.pitch,
+    };
+    if let Some(kick) = command.kick {
+        return Some(MotionCommand::InWalkKick {
+            head,
+            kick: kick.kick,
+            kicking_side: kick.kicking_side,
+            strength: parameters.kick_strength,
+        });
+    }
+
+    let velocity = vector![command.walk_velocity.forward, command.walk_velocity.left];
+    let turn = command.walk_velocity.turn.clamp(-1.0, 1.0);
+    let speed = velocity.norm().max(turn.abs()).min(1.0);
+    if speed < f32::EPSILON {
+        return Some(MotionCommand::Stand {
+            head,
+            is_energy_saving: false,
+        });
+    }
+    let direction = velocity
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_else(Vector2::zeros);
+    let target = Point2::from(direction * parameters.walk_target_distance);
+    Some(MotionCommand::Walk {
+        head,
+        path: direct_path(Point2::origin(), target),
+        left_arm: ArmMotion::Swing,
+        right_arm: ArmMotion::Swing,
+        orientation_mode: OrientationMode::Override(UnitComplex::new(turn * FRAC_PI_2)),
+        speed_profile: Some(SpeedProfile {
+            cruise_fraction: speed,
+            creep_fraction: speed,
+            slow_down_radius: 0.0,
+        }),
+        arrival_tolerance: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{
+        parameters::{TeleoperationCommand, TeleoperationKick, WalkVelocity},
+        KickVariant, Side,
+    };
+
+    use super::*;
+
+    #[test]
+    fn robot_stands_without_joystick_commands() {
+        let mut parameters = Teleoperation {
+            enable: false,
+            command: None,
+            walk_target_distance: 0.5,
+            kick_strength: 1.0,
+        };
+        assert_eq!(execute(&parameters), None);
+
+        parameters.enable = true;
+        assert_eq!(
+            execute(&parameters),
+            Some(MotionCommand::Stand {
+                head: HeadMotion::Center,
+                is_energy_saving: false,
+            })
+        );
+
+        parameters.command = Some(TeleoperationCommand {
+            walk_velocity: WalkVelocity {
+                forward: 0.5,
+                left: 0.0,
+                turn: 0.0,
+            },
+            ..Default::default()
+        });
+        assert!(matches!(
+            execute(&parameters),
+            Some(MotionCommand::Walk {
+                speed_profile: Some(SpeedProfile {
+                    cruise_fraction,
+                    ..
+                }),
+                ..
+            }) if cruise_fraction == 0.5
+        ));
+
+        parameters.command = Some(TeleoperationCommand {
+            kick: Some(TeleoperationKick {
+                kick: KickVariant::Forward,
+                kicking_side: Side::Right,
+            }),
+            ..Default::default()
+        });
+        assert!(matches!(
+            execute(&parameters),
+            Some(MotionCommand::InWalkKick {
+                kicking_side: Side::Right,
+                ..
+            })
+        ));
+    }
+}
@@ -6,3 +6,23 @@ pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::test_support::WorldStateBuilder;
+
+    use super::*;
+
+    #[test]
+    fn unstiff_only_when_primary_state_is_unstiff() {
+        let unstiff = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Unstiff)
+            .build();
+        let playing = WorldStateBuilder::default()
+            .with_primary_state(PrimaryState::Playing)
+            .build();
+
+        assert_eq!(execute(&unstiff), Some(MotionCommand::Unstiff));
+        assert_eq!(execute(&playing), None);
+    }
+}
@@ -0,0 +1,108 @@
+use framework::AdditionalOutput;
+use nalgebra::{Isometry2, Point2, Vector2};
+use types::{
+    coordinate_systems::Pose, parameters::WalkToBall as WalkToBallParameters, rotate_towards,
+    HeadMotion, MotionCommand, PathObstacle, WorldState,
+};
+
+use super::walk_to_pose::WalkAndStand;
+
+/// Approaches a ball which is not trusted enough for dribbling, e.g. a ball only known from
+/// teammates, until it is perceived by the robot itself
+pub fn execute(
+    world_state: &WorldState,
+    walk_and_stand: &WalkAndStand,
+    parameters: &WalkToBallParameters,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    let ball = world_state.ball?;
+    if ball.confidence < parameters.minimum_team_ball_confidence {
+        return None;
+    }
+    let ball_position = ball.ball_in_ground.inner;
+    let direction_to_ball = ball_position
+        .coords
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_else(Vector2::x);
+    let target_position = ball_position - direction_to_ball * parameters.distance_to_ball.0;
+    let target_pose = Pose::wrap(Isometry2::from_parts(
+        target_position.coords.into(),
+        rotate_towards(Point2::origin(), ball_position),
+    ));
+    walk_and_stand.execute(
+        target_pose,
+        HeadMotion::LookAt {
+            target: ball_position,
+            camera: None,
+        },
+        path_obstacles_output,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{point, vector};
+    use types::{
+        parameters::{PathPlanning, WalkAndStand as WalkAndStandParameters},
+        test_support::{BallStateBuilder, WorldStateBuilder},
+        units::Meters,
+        BallSource, FieldDimensions,
+    };
+
+    use crate::behavior::walk_to_pose::WalkPathPlanner;
+
+    use super::*;
+
+    #[test]
+    fn only_confident_team_balls_are_approached() {
+        let field_dimensions = FieldDimensions::default();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let walk_and_stand_parameters = WalkAndStandParameters {
+            target_reached_thresholds: vector![0.1, 0.1],
+            hybrid_align_distance: Meters(1.0),
+            distance_to_be_aligned: Meters(0.5),
+            ..Default::default()
+        };
+        let parameters = WalkToBallParameters {
+            minimum_team_ball_confidence: 0.5,
+            distance_to_ball: Meters(0.3),
+        };
+        let approach = |confidence| {
+            let world_state = WorldStateBuilder::default()
+                .with_ball(
+                    BallStateBuilder::default()
+                        .in_ground(point![0.3, 0.0])
+                        .with_source(BallSource::Team)
+                        .with_confidence(confidence)
+                        .build(),
+                )
+                .build();
+            let walk_and_stand = WalkAndStand::new(
+                &world_state,
+                &walk_and_stand_parameters,
+                &walk_path_planner,
+                &MotionCommand::Unstiff,
+            );
+            let mut path_obstacles = None;
+            execute(
+                &world_state,
+                &walk_and_stand,
+                &parameters,
+                &mut AdditionalOutput::new(false, &mut path_obstacles),
+            )
+        };
+
+        assert_eq!(approach(0.2), None);
+        assert_eq!(
+            approach(0.8),
+            Some(MotionCommand::Stand {
+                head: HeadMotion::LookAt {
+                    target: point![0.3, 0.0],
+                    camera: None,
+                },
+                is_energy_saving: true,
+            })
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use framework::AdditionalOutput;
-use nalgebra::{Translation2, Vector2};
-use types::{MotionCommand, PathObstacle, WorldState};
+use nalgebra::Isometry2;
+use types::{coordinate_systems::Pose, MotionCommand, PathObstacle, WorldState};
 
 use super::{head::LookAction, walk_to_pose::WalkAndStand};
 
@@ -9,12 +9,105 @@ pub fn execute(
     walk_and_stand: &WalkAndStand,
     look_action: &LookAction,
     path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
-    striker_set_position: Vector2<f32>,
+    kick_off_pose: Option<Isometry2<f32>>,
 ) -> Option<MotionCommand> {
     let robot_to_field = world_state.robot.robot_to_field?;
     walk_and_stand.execute(
-        robot_to_field.inverse() * Translation2::from(striker_set_position),
+        robot_to_field.inverse() * Pose::wrap(kick_off_pose?),
         look_action.execute(),
         path_obstacles_output,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{point, vector};
+    use types::{
+        coordinate_systems::Transform,
+        parameters::{PathPlanning, WalkAndStand as WalkAndStandParameters},
+        test_support::WorldStateBuilder,
+        units::Meters,
+        FieldDimensions, HeadMotion,
+    };
+
+    use crate::behavior::walk_to_pose::WalkPathPlanner;
+
+    use super::*;
+
+    fn walk_and_stand_parameters() -> WalkAndStandParameters {
+        WalkAndStandParameters {
+            target_reached_thresholds: vector![0.1, 0.1],
+            hybrid_align_distance: Meters(1.0),
+            distance_to_be_aligned: Meters(0.5),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn stands_looking_at_position_of_interest_at_set_position() {
+        let kick_off_pose = Isometry2::new(vector![-0.5, 0.0], 0.0);
+        let world_state = WorldStateBuilder::default()
+            .with_robot_to_field(Transform::wrap(kick_off_pose))
+            .with_position_of_interest(point![1.0, 0.0])
+            .build();
+        let field_dimensions = FieldDimensions::default();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let parameters = walk_and_stand_parameters();
+        let walk_and_stand = WalkAndStand::new(
+            &world_state,
+            &parameters,
+            &walk_path_planner,
+            &MotionCommand::Unstiff,
+        );
+        let look_action = LookAction::new(&world_state);
+        let mut path_obstacles = None;
+
+        assert_eq!(
+            execute(
+                &world_state,
+                &walk_and_stand,
+                &look_action,
+                &mut AdditionalOutput::new(false, &mut path_obstacles),
+                Some(kick_off_pose),
+            ),
+            Some(MotionCommand::Stand {
+                head: HeadMotion::LookAt {
+                    target: point![1.0, 0.0],
+                    camera: None,
+                },
+                is_energy_saving: true,
+            })
+        );
+    }
+
+    #[test]
+    fn does_nothing_without_robot_pose() {
+        let world_state = WorldStateBuilder::default()
+            .without_robot_to_field()
+            .build();
+        let field_dimensions = FieldDimensions::default();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let parameters = walk_and_stand_parameters();
+        let walk_and_stand = WalkAndStand::new(
+            &world_state,
+            &parameters,
+            &walk_path_planner,
+            &MotionCommand::Unstiff,
+        );
+        let look_action = LookAction::new(&world_state);
+        let mut path_obstacles = None;
+
+        assert_eq!(
+            execute(
+                &world_state,
+                &walk_and_stand,
+                &look_action,
+                &mut AdditionalOutput::new(false, &mut path_obstacles),
+                Some(Isometry2::new(vector![-0.5, 0.0], 0.0)),
+            ),
+            None
+        );
+    }
+}
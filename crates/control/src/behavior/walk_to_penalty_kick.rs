@@ -1,6 +1,6 @@
 use framework::AdditionalOutput;
 use nalgebra::Isometry2;
-use types::{FieldDimensions, MotionCommand, PathObstacle, WorldState};
+use types::{coordinate_systems::Pose, FieldDimensions, MotionCommand, PathObstacle, WorldState};
 
 use super::{head::LookAction, walk_to_pose::WalkAndStand};
 
@@ -12,15 +12,80 @@ pub fn execute(
     field_dimensions: &FieldDimensions,
 ) -> Option<MotionCommand> {
     let robot_to_field = world_state.robot.robot_to_field?;
-    let kick_off_pose = Isometry2::translation(
-        field_dimensions.length / 2.0
-            - field_dimensions.penalty_marker_distance
-            - field_dimensions.penalty_marker_size * 2.0,
+    let kick_off_pose = Pose::wrap(Isometry2::translation(
+        field_dimensions.penalty_spot_x().0 - field_dimensions.penalty_marker_size.0 * 2.0,
         0.0,
-    );
+    ));
     walk_and_stand.execute(
         robot_to_field.inverse() * kick_off_pose,
         look_action.execute(),
         path_obstacles_output,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{point, vector};
+    use types::{
+        coordinate_systems::Transform,
+        parameters::{PathPlanning, WalkAndStand as WalkAndStandParameters},
+        test_support::WorldStateBuilder,
+        units::Meters,
+        HeadMotion,
+    };
+
+    use crate::behavior::walk_to_pose::WalkPathPlanner;
+
+    use super::*;
+
+    fn walk_and_stand_parameters() -> WalkAndStandParameters {
+        WalkAndStandParameters {
+            target_reached_thresholds: vector![0.1, 0.1],
+            hybrid_align_distance: Meters(1.0),
+            distance_to_be_aligned: Meters(0.5),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn stands_looking_at_position_of_interest_in_front_of_penalty_marker() {
+        let field_dimensions = FieldDimensions {
+            length: Meters(9.0),
+            penalty_marker_distance: Meters(1.3),
+            penalty_marker_size: Meters(0.1),
+            ..Default::default()
+        };
+        let world_state = WorldStateBuilder::default()
+            .with_robot_to_field(Transform::wrap(Isometry2::translation(3.0, 0.0)))
+            .with_position_of_interest(point![1.5, 0.0])
+            .build();
+        let path_planning = PathPlanning::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let parameters = walk_and_stand_parameters();
+        let walk_and_stand = WalkAndStand::new(
+            &world_state,
+            &parameters,
+            &walk_path_planner,
+            &MotionCommand::Unstiff,
+        );
+        let look_action = LookAction::new(&world_state);
+        let mut path_obstacles = None;
+
+        assert_eq!(
+            execute(
+                &world_state,
+                &walk_and_stand,
+                &look_action,
+                &mut AdditionalOutput::new(false, &mut path_obstacles),
+                &field_dimensions,
+            ),
+            Some(MotionCommand::Stand {
+                head: HeadMotion::LookAt {
+                    target: point![1.5, 0.0],
+                    camera: None,
+                },
+                is_energy_saving: true,
+            })
+        );
+    }
+}
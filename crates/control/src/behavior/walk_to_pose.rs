@@ -1,15 +1,20 @@
 use filtering::hysteresis::less_than_with_hysteresis;
 use framework::AdditionalOutput;
 use nalgebra::{point, Isometry2, Point2, UnitComplex};
+use spl_network_messages::SubState;
 use types::{
+    coordinate_systems::{Field, Ground, Point, Pose, Transform},
     direct_path,
     parameters::{PathPlanning as PathPlanningParameters, WalkAndStand as WalkAndStandParameters},
-    ArmMotion, FieldDimensions, HeadMotion, MotionCommand, Obstacle, OrientationMode, PathObstacle,
-    PathSegment, RuleObstacle, Side, WorldState,
+    units::Meters,
+    ArmMotion, FieldDimensions, FilteredGameState, GameControllerState, HeadMotion, MotionCommand,
+    Obstacle, OrientationMode, PathObstacle, PathSegment, RuleObstacle, Side, WorldState,
 };
 
 use crate::path_planner::PathPlanner;
 
+use super::legal_position::nearest_legal_position;
+
 pub struct WalkPathPlanner<'cycle> {
     field_dimensions: &'cycle FieldDimensions,
     obstacles: &'cycle [Obstacle],
@@ -31,29 +36,46 @@ impl<'cycle> WalkPathPlanner<'cycle> {
     #[allow(clippy::too_many_arguments)]
     pub fn plan(
         &self,
-        target_in_robot: Point2<f32>,
-        robot_to_field: Isometry2<f32>,
-        ball_obstacle: Option<Point2<f32>>,
+        target_in_robot: Point<Ground>,
+        robot_to_field: Transform<Ground, Field>,
+        ball_obstacle: Option<Point<Ground>>,
         ball_obstacle_radius_factor: f32,
         obstacles: &[Obstacle],
         rule_obstacles: &[RuleObstacle],
         path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
     ) -> Vec<PathSegment> {
+        let field_to_robot = robot_to_field.inverse();
         let mut planner = PathPlanner::default();
+        planner.with_rotation_penalty(self.parameters.rotation_penalty_factor);
+        planner.with_inflation(
+            self.parameters.inflation_radius,
+            self.parameters.inflation_weight,
+        );
+        planner.with_obstacle_prediction(
+            self.parameters.line_walking_speed,
+            self.parameters.maximum_obstacle_prediction_time,
+        );
+        planner.with_teammate_radius_reduction(self.parameters.teammate_radius_reduction);
         planner.with_obstacles(obstacles, self.parameters.robot_radius_at_hip_height);
         planner.with_rule_obstacles(
-            robot_to_field.inverse(),
+            field_to_robot.inner,
             rule_obstacles,
             self.parameters.robot_radius_at_hip_height,
         );
         planner.with_field_borders(
-            robot_to_field,
-            self.field_dimensions.length,
-            self.field_dimensions.width,
-            self.field_dimensions.border_strip_width,
+            robot_to_field.inner,
+            self.field_dimensions.length.0,
+            self.field_dimensions.width.0,
+            self.field_dimensions.border_strip_width.0,
             self.parameters.field_border_weight,
         );
-        planner.with_goal_support_structures(robot_to_field.inverse(), self.field_dimensions);
+        planner.with_goal_support_structures(field_to_robot.inner, self.field_dimensions);
+        planner.with_carpet_boundary(robot_to_field.inner, self.field_dimensions);
+        planner.with_goal_posts(
+            field_to_robot.inner,
+            self.field_dimensions,
+            self.parameters.robot_radius_at_hip_height,
+        );
         if let Some(ball_position) = ball_obstacle {
             let foot_proportion = self.parameters.minimum_robot_radius_at_foot_height
                 / self.parameters.robot_radius_at_foot_height;
@@ -61,23 +83,27 @@ impl<'cycle> WalkPathPlanner<'cycle> {
                 self.parameters.robot_radius_at_foot_height
                     * ((ball_obstacle_radius_factor * (1.0 - foot_proportion)) + foot_proportion);
             planner.with_ball(
-                ball_position,
+                ball_position.inner,
                 self.parameters.ball_obstacle_radius,
                 calculated_robot_radius_at_foot_height,
             );
         }
 
         let target_in_field = robot_to_field * target_in_robot;
-        let x_max = self.field_dimensions.length / 2.0 + self.field_dimensions.border_strip_width;
-        let y_max = self.field_dimensions.width / 2.0 + self.field_dimensions.border_strip_width;
-        let clamped_target_in_robot = robot_to_field.inverse()
-            * point![
-                target_in_field.x.clamp(-x_max, x_max),
-                target_in_field.y.clamp(-y_max, y_max)
-            ];
+        let x_max = self.field_dimensions.length.0 / 2.0
+            + self.field_dimensions.border_strip_width.0
+            - self.parameters.robot_radius_at_hip_height;
+        let y_max = self.field_dimensions.width.0 / 2.0
+            + self.field_dimensions.border_strip_width.0
+            - self.parameters.robot_radius_at_hip_height;
+        let clamped_target_in_robot = field_to_robot
+            * Point::wrap(point![
+                target_in_field.inner.x.clamp(-x_max, x_max),
+                target_in_field.inner.y.clamp(-y_max, y_max)
+            ]);
 
         let path = planner
-            .plan(Point2::origin(), clamped_target_in_robot)
+            .plan(Point2::origin(), clamped_target_in_robot.inner)
             .unwrap();
         path_obstacles_output.fill_if_subscribed(|| planner.obstacles.clone());
         path.unwrap_or_else(|| direct_path(Point2::origin(), Point2::origin()))
@@ -89,15 +115,38 @@ impl<'cycle> WalkPathPlanner<'cycle> {
         orientation_mode: OrientationMode,
         path: Vec<PathSegment>,
     ) -> MotionCommand {
+        let is_crowded = self.is_robot_close_on(Side::Left) && self.is_robot_close_on(Side::Right);
+        let (left_arm, right_arm) = if is_crowded {
+            (ArmMotion::BehindBack, ArmMotion::BehindBack)
+        } else {
+            (
+                self.arm_motion_with_obstacles(Side::Left),
+                self.arm_motion_with_obstacles(Side::Right),
+            )
+        };
         MotionCommand::Walk {
             head,
             orientation_mode,
             path,
-            left_arm: self.arm_motion_with_obstacles(Side::Left),
-            right_arm: self.arm_motion_with_obstacles(Side::Right),
+            left_arm,
+            right_arm,
+            speed_profile: None,
+            arrival_tolerance: None,
         }
     }
 
+    fn is_robot_close_on(&self, side: Side) -> bool {
+        self.obstacles.iter().any(|obstacle| {
+            let is_on_side = match side {
+                Side::Left => obstacle.position.y.is_sign_positive(),
+                Side::Right => obstacle.position.y.is_sign_negative(),
+            };
+            obstacle.kind.is_robot()
+                && is_on_side
+                && obstacle.position.coords.norm() < self.parameters.arms_behind_back_radius
+        })
+    }
+
     fn arm_motion_with_obstacles(&self, side: Side) -> ArmMotion {
         if self.obstacles.iter().any(|obstacle| {
             let is_on_relevant_side = match side {
@@ -139,13 +188,14 @@ impl<'cycle> WalkAndStand<'cycle> {
 
     pub fn execute(
         &self,
-        target_pose: Isometry2<f32>,
+        target_pose: Pose<Ground>,
         head: HeadMotion,
         path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
     ) -> Option<MotionCommand> {
         let robot_to_field = self.world_state.robot.robot_to_field?;
-        let distance_to_walk = target_pose.translation.vector.norm();
-        let angle_to_walk = target_pose.rotation.angle();
+        let target_pose = self.legal_target_pose(target_pose, robot_to_field);
+        let distance_to_walk = target_pose.inner.translation.vector.norm();
+        let angle_to_walk = target_pose.inner.rotation.angle();
         let was_standing_last_cycle =
             matches!(self.last_motion_command, MotionCommand::Stand { .. });
         let is_reached = less_than_with_hysteresis(
@@ -160,7 +210,7 @@ impl<'cycle> WalkAndStand<'cycle> {
             self.parameters.hysteresis.y,
         );
         let orientation_mode = hybrid_alignment(
-            target_pose,
+            target_pose.inner,
             self.parameters.hybrid_align_distance,
             self.parameters.distance_to_be_aligned,
         );
@@ -172,7 +222,7 @@ impl<'cycle> WalkAndStand<'cycle> {
             })
         } else {
             let path = self.walk_path_planner.plan(
-                target_pose * Point2::origin(),
+                target_pose.position(),
                 robot_to_field,
                 self.world_state.ball.map(|ball| ball.ball_in_ground),
                 1.0,
@@ -187,15 +237,49 @@ impl<'cycle> WalkAndStand<'cycle> {
             ))
         }
     }
+
+    /// Keeps targets in ready and set away from positions which are penalized in set
+    fn legal_target_pose(
+        &self,
+        target_pose: Pose<Ground>,
+        robot_to_field: Transform<Ground, Field>,
+    ) -> Pose<Ground> {
+        let is_ready_or_set = matches!(
+            self.world_state.filtered_game_state,
+            Some(FilteredGameState::Ready { .. } | FilteredGameState::Set)
+        );
+        let is_penalty_kick = matches!(
+            self.world_state.game_controller_state,
+            Some(GameControllerState {
+                sub_state: Some(SubState::PenaltyKick),
+                ..
+            })
+        );
+        if !is_ready_or_set || is_penalty_kick {
+            return target_pose;
+        }
+        let target_pose_in_field = robot_to_field * target_pose;
+        let legal_position = nearest_legal_position(
+            target_pose_in_field.position(),
+            self.world_state,
+            self.walk_path_planner.field_dimensions,
+            &self.parameters.illegal_position_avoidance,
+        );
+        robot_to_field.inverse()
+            * Pose::wrap(Isometry2::from_parts(
+                legal_position.inner.coords.into(),
+                target_pose_in_field.inner.rotation,
+            ))
+    }
 }
 
 pub fn hybrid_alignment(
     target_pose: Isometry2<f32>,
-    hybrid_align_distance: f32,
-    distance_to_be_aligned: f32,
+    hybrid_align_distance: Meters,
+    distance_to_be_aligned: Meters,
 ) -> OrientationMode {
     assert!(hybrid_align_distance > distance_to_be_aligned);
-    let distance_to_target = target_pose.translation.vector.norm();
+    let distance_to_target = Meters(target_pose.translation.vector.norm());
     if distance_to_target >= hybrid_align_distance {
         return OrientationMode::AlignWithPath;
     }
@@ -206,3 +290,139 @@ pub fn hybrid_alignment(
         .clamp(0.0, 1.0);
     OrientationMode::Override(target_pose.rotation.slerp(&target_facing_rotation, t))
 }
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::vector;
+    use spl_network_messages::Team;
+    use types::{parameters::IllegalPositionAvoidance, test_support::WorldStateBuilder};
+
+    use super::*;
+
+    fn arms_of(command: MotionCommand) -> (ArmMotion, ArmMotion) {
+        match command {
+            MotionCommand::Walk {
+                left_arm,
+                right_arm,
+                ..
+            } => (left_arm, right_arm),
+            _ => panic!("expected walk command"),
+        }
+    }
+
+    #[test]
+    fn arms_move_behind_back_only_between_robots() {
+        let field_dimensions = FieldDimensions::default();
+        let parameters = PathPlanningParameters {
+            arms_behind_back_radius: 0.6,
+            ..Default::default()
+        };
+        let walk = |obstacles: &[Obstacle]| {
+            arms_of(
+                WalkPathPlanner::new(&field_dimensions, obstacles, &parameters)
+                    .walk_with_obstacle_avoiding_arms(
+                        HeadMotion::Center,
+                        OrientationMode::AlignWithPath,
+                        vec![],
+                    ),
+            )
+        };
+        let left_robot = Obstacle::robot(point![0.1, 0.3], 0.2, 0.15);
+        let right_robot = Obstacle::robot(point![0.1, -0.3], 0.2, 0.15);
+
+        assert_eq!(
+            walk(&[left_robot, right_robot]),
+            (ArmMotion::BehindBack, ArmMotion::BehindBack)
+        );
+        assert_eq!(
+            walk(&[left_robot]),
+            (ArmMotion::PullTight, ArmMotion::Swing)
+        );
+        assert_eq!(walk(&[]), (ArmMotion::Swing, ArmMotion::Swing));
+    }
+
+    fn walk_and_stand(world_state: &WorldState, target_pose: Isometry2<f32>) -> MotionCommand {
+        let field_dimensions = FieldDimensions {
+            length: Meters(9.0),
+            width: Meters(6.0),
+            ..Default::default()
+        };
+        let path_planning = PathPlanningParameters::default();
+        let walk_path_planner = WalkPathPlanner::new(&field_dimensions, &[], &path_planning);
+        let parameters = WalkAndStandParameters {
+            target_reached_thresholds: vector![0.1, 0.1],
+            hybrid_align_distance: Meters(1.0),
+            distance_to_be_aligned: Meters(0.5),
+            illegal_position_avoidance: IllegalPositionAvoidance {
+                margin: 0.1,
+                maximum_number_of_defenders_in_penalty_area: 2,
+            },
+            ..Default::default()
+        };
+        let mut path_obstacles = None;
+        WalkAndStand::new(
+            world_state,
+            &parameters,
+            &walk_path_planner,
+            &MotionCommand::Unstiff,
+        )
+        .execute(
+            Pose::wrap(target_pose),
+            HeadMotion::Center,
+            &mut AdditionalOutput::new(false, &mut path_obstacles),
+        )
+        .unwrap()
+    }
+
+    fn path_end_and_orientation_mode_of(command: MotionCommand) -> (Point2<f32>, OrientationMode) {
+        match command {
+            MotionCommand::Walk {
+                path,
+                orientation_mode,
+                ..
+            } => (path.last().unwrap().end(), orientation_mode),
+            _ => panic!("expected walk command"),
+        }
+    }
+
+    #[test]
+    fn robots_stand_at_reached_targets() {
+        let world_state = WorldStateBuilder::default().build();
+
+        assert_eq!(
+            walk_and_stand(&world_state, Isometry2::translation(0.05, 0.0)),
+            MotionCommand::Stand {
+                head: HeadMotion::Center,
+                is_energy_saving: true,
+            }
+        );
+    }
+
+    #[test]
+    fn robots_align_with_the_path_to_distant_targets() {
+        let world_state = WorldStateBuilder::default().build();
+
+        let (path_end, orientation_mode) = path_end_and_orientation_mode_of(walk_and_stand(
+            &world_state,
+            Isometry2::translation(3.0, 0.0),
+        ));
+        assert_relative_eq!(path_end, point![3.0, 0.0], epsilon = 1e-5);
+        assert_eq!(orientation_mode, OrientationMode::AlignWithPath);
+    }
+
+    #[test]
+    fn targets_in_ready_are_kept_in_the_own_half() {
+        let world_state = WorldStateBuilder::default()
+            .with_filtered_game_state(FilteredGameState::Ready {
+                kicking_team: Team::Opponent,
+            })
+            .build();
+
+        let (path_end, _) = path_end_and_orientation_mode_of(walk_and_stand(
+            &world_state,
+            Isometry2::translation(1.0, 2.0),
+        ));
+        assert_relative_eq!(path_end, point![-0.1, 2.0], epsilon = 1e-5);
+    }
+}
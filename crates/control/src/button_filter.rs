@@ -2,12 +2,12 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use color_eyre::Result;
 use context_attribute::context;
-use filtering::tap_detector::TapDetector;
+use filtering::tap_detector::MultiTapDetector;
 use framework::MainOutput;
 use types::{Buttons, CycleTime, SensorData};
 
 pub struct ButtonFilter {
-    chest_button_tap_detector: TapDetector,
+    chest_button_tap_detector: MultiTapDetector,
     head_buttons_touched: SystemTime,
     last_head_buttons_touched: bool,
     calibration_buttons_touched: SystemTime,
@@ -29,6 +29,7 @@ pub struct CycleContext {
     pub calibration_buttons_timeout:
         Parameter<Duration, "button_filter.calibration_buttons_timeout">,
     pub head_buttons_timeout: Parameter<Duration, "button_filter.head_buttons_timeout">,
+    pub multi_tap_timeout: Parameter<Duration, "button_filter.multi_tap_timeout">,
 }
 
 #[context]
@@ -40,7 +41,7 @@ pub struct MainOutputs {
 impl ButtonFilter {
     pub fn new(_context: CreationContext) -> Result<Self> {
         Ok(Self {
-            chest_button_tap_detector: TapDetector::default(),
+            chest_button_tap_detector: MultiTapDetector::default(),
             head_buttons_touched: UNIX_EPOCH,
             last_head_buttons_touched: false,
             calibration_buttons_touched: UNIX_EPOCH,
@@ -53,11 +54,14 @@ impl ButtonFilter {
         let calibration_buttons_timeout = *context.calibration_buttons_timeout;
         let touch_sensors = &context.sensor_data.touch_sensors;
 
-        self.chest_button_tap_detector
-            .update(touch_sensors.chest_button);
+        self.chest_button_tap_detector.update(
+            touch_sensors.chest_button,
+            context.cycle_time.start_time,
+            *context.multi_tap_timeout,
+        );
+        let number_of_chest_button_taps = self.chest_button_tap_detector.number_of_completed_taps();
 
-        let head_buttons_touched =
-            touch_sensors.head_front && touch_sensors.head_middle && touch_sensors.head_rear;
+        let head_buttons_touched = touch_sensors.head_front && touch_sensors.head_rear;
 
         let head_buttons_touched_initially =
             head_buttons_touched && !self.last_head_buttons_touched;
@@ -93,7 +97,9 @@ impl ButtonFilter {
 
         Ok(MainOutputs {
             buttons: Buttons {
-                is_chest_button_pressed: self.chest_button_tap_detector.is_single_tapped(),
+                is_chest_button_pressed: number_of_chest_button_taps == 1,
+                is_chest_button_pressed_twice: number_of_chest_button_taps == 2,
+                is_chest_button_pressed_thrice: number_of_chest_button_taps >= 3,
                 head_buttons_touched: debounced_head_buttons_touched,
                 calibration_buttons_touched: debounced_calibration_buttons_touched,
             }
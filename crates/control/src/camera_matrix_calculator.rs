@@ -4,8 +4,10 @@ use framework::{AdditionalOutput, MainOutput};
 use nalgebra::{point, vector, Isometry3, Rotation3, UnitQuaternion, Vector3};
 use projection::Projection;
 use types::{
-    parameters::CameraMatrixParameters, CameraMatrices, CameraMatrix, CameraPosition,
-    FieldDimensions, Line, Line2, ProjectedFieldLines, RobotDimensions, RobotKinematics,
+    parameters::CameraMatrixParameters,
+    units::{Degrees, Radians},
+    CameraMatrices, CameraMatrix, CameraPosition, FieldDimensions, Line, Line2,
+    ProjectedFieldLines, RobotDimensions, RobotKinematics,
 };
 
 pub struct CameraMatrixCalculator {}
@@ -103,9 +105,9 @@ impl CameraMatrixCalculator {
 
 pub fn camera_to_head(
     camera_position: CameraPosition,
-    extrinsic_rotation: Vector3<f32>,
+    extrinsic_rotation: Vector3<Degrees>,
 ) -> Isometry3<f32> {
-    let extrinsic_angles_in_radians = extrinsic_rotation.map(|a: f32| a.to_radians());
+    let extrinsic_angles_in_radians = extrinsic_rotation.map(|angle| Radians::from(angle).0);
     let extrinsic_rotation = UnitQuaternion::from_euler_angles(
         extrinsic_angles_in_radians.x,
         extrinsic_angles_in_radians.y,
@@ -128,10 +130,10 @@ fn project_penalty_area_on_images(
     field_dimensions: &FieldDimensions,
     camera_matrix: &CameraMatrix,
 ) -> Option<Vec<Line2>> {
-    let field_length = &field_dimensions.length;
-    let field_width = &field_dimensions.width;
-    let penalty_area_length = &field_dimensions.penalty_area_length;
-    let penalty_area_width = &field_dimensions.penalty_area_width;
+    let field_length = &field_dimensions.length.0;
+    let field_width = &field_dimensions.width.0;
+    let penalty_area_length = &field_dimensions.penalty_area_length.0;
+    let penalty_area_width = &field_dimensions.penalty_area_width.0;
 
     let penalty_top_left = camera_matrix
         .ground_to_pixel(point![field_length / 2.0, penalty_area_width / 2.0])
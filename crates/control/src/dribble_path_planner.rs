@@ -1,15 +1,17 @@
 use color_eyre::Result;
 use context_attribute::context;
 use framework::{AdditionalOutput, MainOutput};
-use nalgebra::Point2;
+use nalgebra::{distance, Isometry2, Point2};
 use spl_network_messages::Team;
-use std::f32::consts::PI;
+use std::{f32::consts::PI, iter::once, time::SystemTime};
 use types::{
-    parameters::Behavior, FieldDimensions, GameControllerState, PathObstacle, PathSegment,
-    WorldState,
+    coordinate_systems::{Field, Point, Pose},
+    parameters::Behavior,
+    path_length, Arc, CycleTime, FieldDimensions, GameControllerState, LineSegment, Obstacle,
+    PathObstacle, PathObstacleShape, PathSegment, RuleObstacle, WorldState,
 };
 
-use crate::behavior::walk_to_pose::WalkPathPlanner;
+use crate::{behavior::walk_to_pose::WalkPathPlanner, path_planner::PathPlanner};
 
 #[context]
 pub struct CreationContext {}
@@ -19,6 +21,8 @@ pub struct CycleContext {
     pub parameters: Parameter<Behavior, "behavior">,
     pub field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
     pub path_obstacles: AdditionalOutput<Vec<PathObstacle>, "time_to_reach_obstacles">,
+    pub number_of_replans: AdditionalOutput<usize, "dribble_path_replans">,
+    pub cycle_time: Input<CycleTime, "cycle_time">,
     pub world_state: Input<WorldState, "world_state">,
 }
 
@@ -28,10 +32,26 @@ pub struct MainOutputs {
     pub dribble_path: MainOutput<Option<Vec<PathSegment>>>,
 }
 
-pub struct DribblePath {}
+/// The previous path is kept in field coordinates and reused as long as it stays valid
+struct CachedPath {
+    path_in_field: Vec<PathSegment>,
+    target_in_field: Point<Field>,
+    ball_in_field: Point<Field>,
+    avoids_ball: bool,
+    last_check: SystemTime,
+}
+
+pub struct DribblePath {
+    cached_path: Option<CachedPath>,
+    number_of_replans: usize,
+}
+
 impl DribblePath {
     pub fn new(_context: CreationContext) -> Result<Self> {
-        Ok(Self {})
+        Ok(Self {
+            cached_path: None,
+            number_of_replans: 0,
+        })
     }
 
     pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
@@ -54,27 +74,31 @@ impl DribblePath {
             Some(ball_position) => (ball_position.ball_in_ground, ball_position.ball_in_field),
             None => return Ok(MainOutputs::default()),
         };
-        let best_pose = best_kick_decision.kick_pose;
-        let Some(robot_to_field) = world_state.robot.robot_to_field else { return Ok(MainOutputs::default()) };
-        let robot_to_ball = ball_position_in_ground.coords;
-        let dribble_pose_to_ball = ball_position_in_ground.coords - best_pose.translation.vector;
+        let best_pose = Pose::wrap(best_kick_decision.kick_pose);
+        let Some(robot_to_field) = world_state.robot.robot_to_field else {
+            return Ok(MainOutputs::default());
+        };
+        let robot_to_ball = ball_position_in_ground.inner.coords;
+        let dribble_pose_to_ball =
+            ball_position_in_ground.inner.coords - best_pose.inner.translation.vector;
 
         let angle = robot_to_ball.angle(&dribble_pose_to_ball);
-        let should_avoid_ball = angle > dribbling_parameters.angle_to_approach_ball_from_threshold;
+        let should_avoid_ball =
+            angle > dribbling_parameters.angle_to_approach_ball_from_threshold.0;
         let ball_obstacle = should_avoid_ball.then_some(ball_position_in_ground);
 
         let ball_is_between_robot_and_own_goal =
-            ball_position_in_field.coords.x - robot_to_field.translation.x < 0.0f32;
+            ball_position_in_field.inner.x - robot_to_field.inner.translation.x < 0.0f32;
         let ball_obstacle_radius_factor = if ball_is_between_robot_and_own_goal {
             1.0f32
         } else {
-            (angle - dribbling_parameters.angle_to_approach_ball_from_threshold)
-                / (PI - dribbling_parameters.angle_to_approach_ball_from_threshold)
+            (angle - dribbling_parameters.angle_to_approach_ball_from_threshold.0)
+                / (PI - dribbling_parameters.angle_to_approach_ball_from_threshold.0)
         };
 
         let is_near_ball = matches!(
             world_state.ball,
-            Some(ball) if ball.ball_in_ground.coords.norm() < dribbling_parameters.ignore_robot_when_near_ball_radius,
+            Some(ball) if ball.ball_in_ground.inner.coords.norm() < dribbling_parameters.ignore_robot_when_near_ball_radius.0,
         );
         let obstacles = if is_near_ball {
             &[]
@@ -94,17 +118,211 @@ impl DribblePath {
             world_state.rule_obstacles.as_slice()
         };
 
-        let path = Some(walk_path_planner.plan(
-            best_pose * Point2::origin(),
-            robot_to_field,
-            ball_obstacle,
-            ball_obstacle_radius_factor,
-            obstacles,
-            rule_obstacles,
-            path_obstacles_output,
-        ));
+        let now = context.cycle_time.start_time;
+        let replanning = &dribbling_parameters.path_replanning;
+        let target_in_field = (robot_to_field * best_pose).position();
+        let remaining_cached_path = self.cached_path.as_ref().and_then(|cached_path| {
+            let is_unchanged = distance(&cached_path.target_in_field.inner, &target_in_field.inner)
+                <= replanning.maximum_target_drift
+                && distance(
+                    &cached_path.ball_in_field.inner,
+                    &ball_position_in_field.inner,
+                ) <= replanning.maximum_ball_drift
+                && cached_path.avoids_ball == should_avoid_ball;
+            if !is_unchanged {
+                return None;
+            }
+            let path_in_robot: Vec<_> = cached_path
+                .path_in_field
+                .iter()
+                .map(|segment| segment.transform(robot_to_field.inverse().inner))
+                .collect();
+            let remaining_path = remaining_path(&path_in_robot, replanning.maximum_path_deviation)?;
+            let is_blocked = is_blocked(
+                &remaining_path,
+                obstacles,
+                rule_obstacles,
+                robot_to_field.inverse().inner,
+                path_planning_parameters.robot_radius_at_hip_height,
+            );
+            (!is_blocked).then_some(remaining_path)
+        });
+
+        let mut plan = || {
+            walk_path_planner.plan(
+                best_pose.position(),
+                robot_to_field,
+                ball_obstacle,
+                ball_obstacle_radius_factor,
+                obstacles,
+                rule_obstacles,
+                path_obstacles_output,
+            )
+        };
+        let is_check_due = self.cached_path.as_ref().map_or(true, |cached_path| {
+            now.duration_since(cached_path.last_check)
+                .map_or(true, |duration| {
+                    duration >= replanning.better_path_check_interval
+                })
+        });
+        let (path, is_replanned) = match remaining_cached_path {
+            Some(remaining_path) if !is_check_due => (remaining_path, false),
+            Some(remaining_path) => {
+                let candidate_path = plan();
+                let is_significantly_better = path_length(&candidate_path)
+                    < path_length(&remaining_path) * (1.0 - replanning.minimum_improvement);
+                if let Some(cached_path) = &mut self.cached_path {
+                    cached_path.last_check = now;
+                }
+                if is_significantly_better {
+                    (candidate_path, true)
+                } else {
+                    (remaining_path, false)
+                }
+            }
+            None => (plan(), true),
+        };
+        if is_replanned {
+            self.number_of_replans += 1;
+            self.cached_path = Some(CachedPath {
+                path_in_field: path
+                    .iter()
+                    .map(|segment| segment.transform(robot_to_field.inner))
+                    .collect(),
+                target_in_field,
+                ball_in_field: ball_position_in_field,
+                avoids_ball: should_avoid_ball,
+                last_check: now,
+            });
+        }
+        context
+            .number_of_replans
+            .fill_if_subscribed(|| self.number_of_replans);
+
         Ok(MainOutputs {
-            dribble_path: path.into(),
+            dribble_path: Some(path).into(),
+        })
+    }
+}
+
+/// Cuts off the part of the path the robot already walked, the robot is at the origin
+///
+/// Returns `None` if the robot deviated too far from the path.
+fn remaining_path(
+    path_in_robot: &[PathSegment],
+    maximum_deviation: f32,
+) -> Option<Vec<PathSegment>> {
+    let robot_position = Point2::origin();
+    let (closest_index, deviation) = path_in_robot
+        .iter()
+        .map(|segment| segment.shortest_distance_to_point(robot_position))
+        .enumerate()
+        .min_by(|(_, distance), (_, other_distance)| distance.total_cmp(other_distance))?;
+    if deviation > maximum_deviation {
+        return None;
+    }
+    let current_segment = match &path_in_robot[closest_index] {
+        PathSegment::LineSegment(LineSegment(_, end)) => {
+            PathSegment::LineSegment(LineSegment(robot_position, *end))
+        }
+        PathSegment::Arc(arc, orientation) => {
+            let start = arc.circle.center
+                + (robot_position - arc.circle.center).normalize() * arc.circle.radius;
+            PathSegment::Arc(Arc::new(arc.circle, start, arc.end), *orientation)
+        }
+    };
+    Some(
+        once(current_segment)
+            .chain(path_in_robot[closest_index + 1..].iter().cloned())
+            .collect(),
+    )
+}
+
+/// A path is blocked if it passes through an obstacle or a rule obstacle the planner would avoid
+fn is_blocked(
+    path: &[PathSegment],
+    obstacles: &[Obstacle],
+    rule_obstacles: &[RuleObstacle],
+    field_to_robot: Isometry2<f32>,
+    robot_radius: f32,
+) -> bool {
+    let mut planner = PathPlanner::default();
+    planner.with_obstacles(obstacles, robot_radius);
+    planner.with_rule_obstacles(field_to_robot, rule_obstacles, robot_radius);
+    planner.obstacles.iter().any(|obstacle| {
+        path.iter().any(|segment| match (&obstacle.shape, segment) {
+            (PathObstacleShape::Circle(circle), segment) => {
+                segment.shortest_distance_to_point(circle.center) < circle.radius
+            }
+            (PathObstacleShape::LineSegment(edge), PathSegment::LineSegment(line_segment)) => {
+                edge.intersects_line_segment(*line_segment)
+            }
+            (PathObstacleShape::LineSegment(edge), PathSegment::Arc(arc, orientation)) => {
+                edge.overlaps_arc(*arc, *orientation)
+            }
         })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+    use types::{Circle, Rectangle};
+
+    use super::*;
+
+    #[test]
+    fn walked_part_of_the_path_is_cut_off() {
+        let path = [
+            PathSegment::LineSegment(LineSegment(point![-2.0, 0.1], point![0.5, 0.1])),
+            PathSegment::LineSegment(LineSegment(point![0.5, 0.1], point![0.5, 1.0])),
+        ];
+
+        let remaining = remaining_path(&path, 0.2).unwrap();
+
+        assert_eq!(remaining.len(), 2);
+        assert_relative_eq!(
+            remaining[0],
+            PathSegment::LineSegment(LineSegment(Point2::origin(), point![0.5, 0.1]))
+        );
+        assert_relative_eq!(path_length(&remaining), 1.4, epsilon = 0.01);
+        assert!(remaining_path(&path, 0.05).is_none());
+    }
+
+    #[test]
+    fn paths_through_obstacles_are_blocked() {
+        let path = [PathSegment::LineSegment(LineSegment(
+            Point2::origin(),
+            point![2.0, 0.0],
+        ))];
+        let obstacle = Obstacle::robot(point![1.0, 0.1], 0.2, 0.3);
+
+        assert!(is_blocked(
+            &path,
+            &[obstacle],
+            &[],
+            Isometry2::identity(),
+            0.1
+        ));
+        assert!(!is_blocked(&path, &[], &[], Isometry2::identity(), 0.1));
+    }
+
+    #[test]
+    fn paths_through_rule_obstacles_are_blocked() {
+        let path = [PathSegment::LineSegment(LineSegment(
+            Point2::origin(),
+            point![2.0, 0.0],
+        ))];
+        let field_to_robot = Isometry2::translation(-1.0, 0.0);
+        let circle = RuleObstacle::Circle(Circle::new(point![2.0, 0.35], 0.3));
+        let rectangle = RuleObstacle::Rectangle(Rectangle {
+            min: point![1.5, -1.0],
+            max: point![2.5, 1.0],
+        });
+
+        assert!(is_blocked(&path, &[], &[circle], field_to_robot, 0.1));
+        assert!(is_blocked(&path, &[], &[rectangle], field_to_robot, 0.1));
+        assert!(!is_blocked(&path, &[], &[circle], field_to_robot, 0.0));
     }
 }
@@ -1,13 +1,16 @@
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use color_eyre::Result;
 use context_attribute::context;
 use framework::MainOutput;
-use nalgebra::Isometry2;
 use spl_network_messages::HulkMessage;
 use types::{
-    parameters::CameraMatrixParameters, BallPosition, CycleTime, FallState, FilteredGameState,
-    GameControllerState, HeadJoints, Obstacle, PenaltyShotDirection, PrimaryState, SensorData,
+    coordinate_systems::{Field, Ground, Transform},
+    localization::LocalizationQuality,
+    parameters::{CameraMatrixParameters, ScanProfiles},
+    BallPosition, BlindBallContact, CycleTime, FallState, FilteredGameState, GameControllerState,
+    HeadJoints, JointFault, MotionCommand, NetworkQuality, Obstacle, PenaltyShotDirection,
+    PrimaryState, SensorData, SystemWarning,
 };
 
 pub struct FakeData {}
@@ -15,6 +18,7 @@ pub struct FakeData {}
 #[context]
 pub struct CreationContext {
     pub maximum_velocity: Parameter<HeadJoints<f32>, "head_motion.maximum_velocity">,
+    pub scan_profiles: Parameter<ScanProfiles, "head_motion.scan_profiles">,
     pub top_camera_matrix_parameters:
         Parameter<CameraMatrixParameters, "camera_matrix_parameters.vision_top">,
 }
@@ -26,19 +30,26 @@ pub struct CycleContext {}
 #[derive(Default)]
 pub struct MainOutputs {
     pub ball_position: MainOutput<Option<BallPosition>>,
+    pub blind_ball_contact: MainOutput<Option<BlindBallContact>>,
     pub cycle_time: MainOutput<CycleTime>,
     pub fall_state: MainOutput<FallState>,
     pub filtered_game_state: MainOutput<Option<FilteredGameState>>,
     pub game_controller_state: MainOutput<Option<GameControllerState>>,
     pub has_ground_contact: MainOutput<bool>,
     pub hulk_messages: MainOutput<Vec<HulkMessage>>,
+    pub joint_faults: MainOutput<Vec<JointFault>>,
+    pub localization_quality: MainOutput<Option<LocalizationQuality>>,
+    pub motion_test_command: MainOutput<Option<MotionCommand>>,
+    pub network_quality: MainOutput<NetworkQuality>,
     pub obstacles: MainOutput<Vec<Obstacle>>,
+    pub last_obstacle_measurement: MainOutput<Option<SystemTime>>,
     pub penalty_shot_direction: MainOutput<Option<PenaltyShotDirection>>,
     pub primary_state: MainOutput<PrimaryState>,
-    pub robot_to_field: MainOutput<Option<Isometry2<f32>>>,
+    pub robot_to_field: MainOutput<Option<Transform<Ground, Field>>>,
     pub sensor_data: MainOutput<SensorData>,
     pub stand_up_front_estimated_remaining_duration: MainOutput<Option<Duration>>,
     pub stand_up_back_estimated_remaining_duration: MainOutput<Option<Duration>>,
+    pub system_warnings: MainOutput<Vec<SystemWarning>>,
 }
 
 impl FakeData {
@@ -0,0 +1,147 @@
+use std::time::SystemTime;
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::MainOutput;
+use types::{
+    parameters::FootBumperBallDetection as FootBumperBallDetectionParameters, units::Meters,
+    BallPosition, BlindBallContact, CycleTime, SensorData, Side, WalkCommand,
+};
+
+/// Interprets foot bumper presses while walking forward as contact with a ball which was last
+/// seen close in front of the robot, but is now hidden by the own body
+pub struct FootBumperBallDetection {
+    last_left_foot_pressed: bool,
+    last_right_foot_pressed: bool,
+    blind_ball_contact: Option<BlindBallContact>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    pub ball_position: Input<Option<BallPosition>, "ball_position?">,
+    pub cycle_time: Input<CycleTime, "cycle_time">,
+    pub sensor_data: Input<SensorData, "sensor_data">,
+
+    pub parameters: Parameter<FootBumperBallDetectionParameters, "foot_bumper_ball_detection">,
+
+    // the walk command of this cycle is derived from the world state, hence the one of the last
+    // cycle is used
+    pub last_walk_command: PersistentState<WalkCommand, "last_walk_command">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub blind_ball_contact: MainOutput<Option<BlindBallContact>>,
+}
+
+impl FootBumperBallDetection {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            last_left_foot_pressed: false,
+            last_right_foot_pressed: false,
+            blind_ball_contact: None,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let touch_sensors = &context.sensor_data.touch_sensors;
+        let left_foot_pressed = touch_sensors.left_foot_left || touch_sensors.left_foot_right;
+        let right_foot_pressed = touch_sensors.right_foot_left || touch_sensors.right_foot_right;
+        let pressed_side = if left_foot_pressed && !self.last_left_foot_pressed {
+            Some(Side::Left)
+        } else if right_foot_pressed && !self.last_right_foot_pressed {
+            Some(Side::Right)
+        } else {
+            None
+        };
+        self.last_left_foot_pressed = left_foot_pressed;
+        self.last_right_foot_pressed = right_foot_pressed;
+
+        let is_walking_forward =
+            matches!(context.last_walk_command, WalkCommand::Walk(step) if step.forward > Meters(0.0));
+        if let (Some(side), Some(ball), true) =
+            (pressed_side, context.ball_position, is_walking_forward)
+        {
+            if is_ball_close_ahead(ball, now, context.parameters) {
+                self.blind_ball_contact = Some(BlindBallContact {
+                    position: ball.position,
+                    side,
+                    touched_at: now,
+                });
+            }
+        }
+
+        self.blind_ball_contact = self.blind_ball_contact.filter(|contact| {
+            now.duration_since(contact.touched_at)
+                .map_or(true, |age| age <= context.parameters.hint_duration)
+        });
+
+        Ok(MainOutputs {
+            blind_ball_contact: self.blind_ball_contact.into(),
+        })
+    }
+}
+
+fn is_ball_close_ahead(
+    ball: &BallPosition,
+    now: SystemTime,
+    parameters: &FootBumperBallDetectionParameters,
+) -> bool {
+    let is_recently_seen = now
+        .duration_since(ball.last_seen)
+        .map_or(true, |age| age <= parameters.maximum_ball_age);
+    let is_ahead = ball.position.x > 0.0;
+    let is_close = ball.position.coords.norm() < parameters.maximum_ball_distance;
+    is_recently_seen && is_ahead && is_close
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use nalgebra::{point, Vector2};
+
+    use super::*;
+
+    #[test]
+    fn only_recently_seen_balls_close_ahead_are_touched() {
+        let parameters = FootBumperBallDetectionParameters {
+            maximum_ball_age: Duration::from_secs(1),
+            maximum_ball_distance: 0.3,
+            hint_duration: Duration::from_secs(2),
+        };
+        let now = UNIX_EPOCH + Duration::from_secs(10);
+        let ball = |position, last_seen| BallPosition {
+            position,
+            velocity: Vector2::zeros(),
+            last_seen,
+            confidence: 1.0,
+        };
+
+        assert!(is_ball_close_ahead(
+            &ball(point![0.15, 0.05], now - Duration::from_millis(500)),
+            now,
+            &parameters
+        ));
+        assert!(!is_ball_close_ahead(
+            &ball(point![0.15, 0.05], now - Duration::from_secs(3)),
+            now,
+            &parameters
+        ));
+        assert!(!is_ball_close_ahead(
+            &ball(point![-0.15, 0.05], now),
+            now,
+            &parameters
+        ));
+        assert!(!is_ball_close_ahead(
+            &ball(point![1.0, 0.0], now),
+            now,
+            &parameters
+        ));
+    }
+}
@@ -1,13 +1,19 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use color_eyre::Result;
 use context_attribute::context;
 use framework::{MainOutput, PerceptionInput};
-use types::{messages::IncomingMessage, CycleTime, GameControllerState, SensorData};
+use log::warn;
+use spl_network_messages::{GameControllerStateMessage, HULKS_TEAM_NUMBER};
+use types::{
+    messages::IncomingMessage, CycleTime, GameControllerFreshness, GameControllerState, SensorData,
+};
 
 pub struct GameControllerFilter {
     game_controller_state: Option<GameControllerState>,
     last_game_state_change: Option<SystemTime>,
+    last_packet_number: Option<u8>,
+    last_message_received: Option<SystemTime>,
 }
 
 #[context]
@@ -18,6 +24,9 @@ pub struct CycleContext {
     pub sensor_data: Input<SensorData, "sensor_data">,
     pub cycle_time: Input<CycleTime, "cycle_time">,
     pub network_message: PerceptionInput<IncomingMessage, "SplNetwork", "message">,
+
+    pub stale_timeout: Parameter<Duration, "game_controller_filter.stale_timeout">,
+    pub timeout: Parameter<Duration, "game_controller_filter.timeout">,
 }
 
 #[context]
@@ -31,45 +40,127 @@ impl GameControllerFilter {
         Ok(Self {
             game_controller_state: None,
             last_game_state_change: None,
+            last_packet_number: None,
+            last_message_received: None,
         })
     }
 
     pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
-        for game_controller_state_message in context
-            .network_message
-            .persistent
-            .values()
-            .flatten()
-            .filter_map(|message| match message {
-                IncomingMessage::GameController(message) => Some(message),
-                IncomingMessage::Spl(_) => None,
-            })
-        {
-            let game_state_changed = match &self.game_controller_state {
-                Some(game_controller_state) => {
-                    game_controller_state.game_state != game_controller_state_message.game_state
+        for (received_at, messages) in &context.network_message.persistent {
+            for message in messages {
+                let message = match message {
+                    IncomingMessage::GameController(message) => message,
+                    IncomingMessage::Spl(_) => continue,
+                };
+                if self.is_accepted(message, *received_at, *context.stale_timeout) {
+                    self.update(message, *received_at, context.cycle_time.start_time);
                 }
-                None => true,
-            };
-            if game_state_changed {
-                self.last_game_state_change = Some(context.cycle_time.start_time);
             }
-            self.game_controller_state = Some(GameControllerState {
-                game_state: game_controller_state_message.game_state,
-                game_phase: game_controller_state_message.game_phase,
-                kicking_team: game_controller_state_message.kicking_team,
-                last_game_state_change: self.last_game_state_change.unwrap(),
-                penalties: game_controller_state_message.hulks_team.clone().into(),
-                remaining_amount_of_messages: game_controller_state_message
-                    .hulks_team
-                    .remaining_amount_of_messages,
-                sub_state: game_controller_state_message.sub_state,
-                hulks_team_is_home_after_coin_toss: game_controller_state_message
-                    .hulks_team_is_home_after_coin_toss,
-            });
         }
+
+        let time_since_last_message = self
+            .last_message_received
+            .and_then(|last_message_received| {
+                context
+                    .cycle_time
+                    .start_time
+                    .duration_since(last_message_received)
+                    .ok()
+            })
+            .unwrap_or_default();
+        if time_since_last_message > *context.timeout {
+            // The GameController disappeared, primary state falls back to the chest button
+            self.game_controller_state = None;
+            self.last_packet_number = None;
+            self.last_message_received = None;
+        } else if let Some(game_controller_state) = &mut self.game_controller_state {
+            game_controller_state.freshness = if time_since_last_message > *context.stale_timeout {
+                GameControllerFreshness::Stale
+            } else {
+                GameControllerFreshness::Fresh
+            };
+        }
+
         Ok(MainOutputs {
             game_controller_state: self.game_controller_state.into(),
         })
     }
+
+    fn is_accepted(
+        &self,
+        message: &GameControllerStateMessage,
+        received_at: SystemTime,
+        stale_timeout: Duration,
+    ) -> bool {
+        if message.hulks_team.team_number != HULKS_TEAM_NUMBER {
+            warn!(
+                "Discarding GameController message for team {}",
+                message.hulks_team.team_number
+            );
+            return false;
+        }
+        // A restarted GameController begins counting at zero again, resynchronize after a while
+        let sequence_is_reset = self
+            .last_message_received
+            .and_then(|last_message_received| {
+                received_at.duration_since(last_message_received).ok()
+            })
+            .map_or(true, |duration| duration > stale_timeout);
+        sequence_is_reset || is_newer_packet(message.packet_number, self.last_packet_number)
+    }
+
+    fn update(
+        &mut self,
+        message: &GameControllerStateMessage,
+        received_at: SystemTime,
+        now: SystemTime,
+    ) {
+        let game_state_changed = match &self.game_controller_state {
+            Some(game_controller_state) => game_controller_state.game_state != message.game_state,
+            None => true,
+        };
+        if game_state_changed {
+            self.last_game_state_change = Some(now);
+        }
+        self.last_packet_number = Some(message.packet_number);
+        self.last_message_received = Some(received_at);
+        self.game_controller_state = Some(GameControllerState {
+            game_state: message.game_state,
+            game_phase: message.game_phase,
+            kicking_team: message.kicking_team,
+            last_game_state_change: self.last_game_state_change.unwrap(),
+            penalties: message.hulks_team.clone().into(),
+            remaining_amount_of_messages: message.hulks_team.remaining_amount_of_messages,
+            sub_state: message.sub_state,
+            hulks_team_is_home_after_coin_toss: message.hulks_team_is_home_after_coin_toss,
+            freshness: GameControllerFreshness::Fresh,
+            hulks_team_colors: (&message.hulks_team).into(),
+            opponent_team_colors: (&message.opponent_team).into(),
+        });
+    }
+}
+
+/// Packet numbers wrap around, packets up to half the range ahead are considered newer
+fn is_newer_packet(packet_number: u8, last_packet_number: Option<u8>) -> bool {
+    match last_packet_number {
+        Some(last_packet_number) => {
+            (1..=u8::MAX / 2).contains(&packet_number.wrapping_sub(last_packet_number))
+        }
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_packets_are_detected_across_wraparound() {
+        assert!(is_newer_packet(0, None));
+        assert!(is_newer_packet(11, Some(10)));
+        assert!(is_newer_packet(2, Some(254)));
+        assert!(!is_newer_packet(10, Some(10)));
+        assert!(!is_newer_packet(9, Some(10)));
+        assert!(!is_newer_packet(250, Some(3)));
+    }
 }
@@ -0,0 +1,100 @@
+use std::time::SystemTime;
+
+use color_eyre::{eyre::WrapErr, Result};
+use context_attribute::context;
+use hardware::NetworkInterface;
+use spl_network_messages::{GameControllerReturnMessage, PlayerNumber};
+use types::{
+    coordinate_systems::{Field, Ground, Transform},
+    messages::OutgoingMessage,
+    parameters::SplNetwork,
+    BallPosition, CycleTime, FallState, FieldDimensions, InitialPose, Players, PrimaryState,
+};
+
+use crate::localization::generate_initial_pose;
+
+/// Sends the return message the GameController uses to show the robot as alive, fallen and where
+/// it believes itself and the ball to be.
+pub struct GameControllerReturnMessageSender {
+    last_transmitted_game_controller_return_message: Option<SystemTime>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    pub ball_position: Input<Option<BallPosition>, "ball_position?">,
+    pub cycle_time: Input<CycleTime, "cycle_time">,
+    pub fall_state: Input<FallState, "fall_state">,
+    pub primary_state: Input<PrimaryState, "primary_state">,
+    pub robot_to_field: Input<Option<Transform<Ground, Field>>, "robot_to_field?">,
+
+    pub field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    pub initial_poses: Parameter<Players<InitialPose>, "localization.initial_poses">,
+    pub player_number: Parameter<PlayerNumber, "player_number">,
+    pub spl_network: Parameter<SplNetwork, "spl_network">,
+
+    pub hardware: HardwareInterface,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {}
+
+impl GameControllerReturnMessageSender {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            last_transmitted_game_controller_return_message: None,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext<impl NetworkInterface>) -> Result<MainOutputs> {
+        let cycle_start_time = context.cycle_time.start_time;
+        let is_message_due = match self.last_transmitted_game_controller_return_message {
+            Some(last_transmitted_game_controller_return_message) => {
+                cycle_start_time.duration_since(last_transmitted_game_controller_return_message)?
+                    > context.spl_network.game_controller_return_message_interval
+            }
+            None => true,
+        };
+        if !is_message_due {
+            return Ok(MainOutputs {});
+        }
+
+        let robot_to_field = context
+            .robot_to_field
+            .map(|robot_to_field| robot_to_field.inner)
+            .unwrap_or_else(|| match context.primary_state {
+                PrimaryState::Initial => generate_initial_pose(
+                    &context.initial_poses[*context.player_number],
+                    context.field_dimensions,
+                ),
+                _ => Default::default(),
+            });
+        let ball_position =
+            context
+                .ball_position
+                .map(|ball_position| spl_network_messages::BallPosition {
+                    age: cycle_start_time
+                        .duration_since(ball_position.last_seen)
+                        .unwrap_or_default(),
+                    relative_position: ball_position.position,
+                });
+
+        self.last_transmitted_game_controller_return_message = Some(cycle_start_time);
+        context
+            .hardware
+            .write_to_network(OutgoingMessage::GameController(
+                GameControllerReturnMessage {
+                    player_number: *context.player_number,
+                    fallen: matches!(context.fall_state, FallState::Fallen { .. }),
+                    robot_to_field,
+                    ball_position,
+                },
+            ))
+            .wrap_err("failed to write GameControllerReturnMessage to hardware")?;
+
+        Ok(MainOutputs {})
+    }
+}
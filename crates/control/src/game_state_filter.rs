@@ -184,9 +184,9 @@ fn ball_detected_far_from_any_goal(
         Some(ball) => {
             let ball_on_field = robot_to_field * ball.position;
             ball_on_field.x.abs()
-                < field_dimensions.length / 2.0 - whistle_acceptance_goal_distance.x
+                < field_dimensions.length.0 / 2.0 - whistle_acceptance_goal_distance.x
                 || ball_on_field.y.abs()
-                    > field_dimensions.goal_inner_width / 2.0 + whistle_acceptance_goal_distance.y
+                    > field_dimensions.goal_inner_width.0 / 2.0 + whistle_acceptance_goal_distance.y
         }
         None => false,
     }
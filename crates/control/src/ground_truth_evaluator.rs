@@ -0,0 +1,160 @@
+use std::time::{Duration, SystemTime};
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::{MainOutput, PerceptionInput};
+use nalgebra::{distance, Isometry2};
+use spl_network_messages::PlayerNumber;
+use types::{
+    coordinate_systems::{Field, Ground, Transform},
+    BallPosition, CycleTime, GroundTruth, GroundTruthErrors,
+};
+
+/// Compares localization and ball filter with the ground truth of the `GroundTruth` cycler
+///
+/// The ball is compared in field coordinates using the measured pose of the robot, such that
+/// localization errors do not distort the error of the ball filter.
+pub struct GroundTruthEvaluator {
+    last_ground_truth: Option<(SystemTime, GroundTruth)>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    pub cycle_time: Input<CycleTime, "cycle_time">,
+    pub robot_to_field: Input<Option<Transform<Ground, Field>>, "robot_to_field?">,
+    pub ball_position: Input<Option<BallPosition>, "ball_position?">,
+    pub ground_truth: PerceptionInput<GroundTruth, "GroundTruth", "ground_truth">,
+
+    pub maximum_age: Parameter<Duration, "ground_truth_evaluator.maximum_age">,
+    pub player_number: Parameter<PlayerNumber, "player_number">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub ground_truth_errors: MainOutput<Option<GroundTruthErrors>>,
+}
+
+impl GroundTruthEvaluator {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            last_ground_truth: None,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        for (received_at, ground_truths) in &context.ground_truth.persistent {
+            if let Some(ground_truth) = ground_truths.last() {
+                self.last_ground_truth = Some((*received_at, (*ground_truth).clone()));
+            }
+        }
+
+        let ground_truth_errors = self
+            .last_ground_truth
+            .as_ref()
+            .filter(|(received_at, _)| {
+                context
+                    .cycle_time
+                    .start_time
+                    .duration_since(*received_at)
+                    .map_or(true, |age| age <= *context.maximum_age)
+            })
+            .map(|(_, ground_truth)| {
+                evaluate(
+                    ground_truth,
+                    *context.player_number,
+                    context
+                        .robot_to_field
+                        .map(|robot_to_field| robot_to_field.inner),
+                    context.ball_position,
+                )
+            });
+
+        Ok(MainOutputs {
+            ground_truth_errors: ground_truth_errors.into(),
+        })
+    }
+}
+
+fn evaluate(
+    ground_truth: &GroundTruth,
+    player_number: PlayerNumber,
+    robot_to_field: Option<Isometry2<f32>>,
+    ball_position: Option<&BallPosition>,
+) -> GroundTruthErrors {
+    let measured_robot_to_field = ground_truth.robot_to_field[player_number];
+    let localized_and_measured = robot_to_field.zip(measured_robot_to_field);
+    GroundTruthErrors {
+        robot_position: localized_and_measured.map(|(localized, measured)| {
+            (localized.translation.vector - measured.translation.vector).norm()
+        }),
+        robot_orientation: localized_and_measured
+            .map(|(localized, measured)| measured.rotation.angle_to(&localized.rotation).abs()),
+        ball_position: measured_robot_to_field
+            .zip(ground_truth.ball_in_field)
+            .zip(ball_position)
+            .map(|((robot_to_field, ball_in_field), ball)| {
+                distance(&(robot_to_field * ball.position), &ball_in_field)
+            }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use approx::assert_relative_eq;
+    use nalgebra::{point, vector};
+    use types::Players;
+
+    use super::*;
+
+    #[test]
+    fn errors_are_computed_in_field_coordinates() {
+        let measured_robot_to_field = Isometry2::new(vector![1.0, 0.0], FRAC_PI_2);
+        let ground_truth = GroundTruth {
+            robot_to_field: Players {
+                three: Some(measured_robot_to_field),
+                ..Default::default()
+            },
+            ball_in_field: Some(point![1.0, 2.0]),
+        };
+        let ball_position = BallPosition {
+            position: point![2.0, 0.5],
+            ..Default::default()
+        };
+
+        let errors = evaluate(
+            &ground_truth,
+            PlayerNumber::Three,
+            Some(Isometry2::new(vector![1.3, 0.4], FRAC_PI_2 - 0.1)),
+            Some(&ball_position),
+        );
+
+        assert_relative_eq!(errors.robot_position.unwrap(), 0.5, epsilon = 1e-6);
+        assert_relative_eq!(errors.robot_orientation.unwrap(), 0.1, epsilon = 1e-6);
+        assert_relative_eq!(errors.ball_position.unwrap(), 0.5, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn errors_of_untracked_robots_are_unknown() {
+        let ground_truth = GroundTruth {
+            ball_in_field: Some(point![1.0, 2.0]),
+            ..Default::default()
+        };
+
+        let errors = evaluate(
+            &ground_truth,
+            PlayerNumber::Three,
+            Some(Isometry2::identity()),
+            Some(&BallPosition::default()),
+        );
+
+        assert!(errors.robot_position.is_none());
+        assert!(errors.robot_orientation.is_none());
+        assert!(errors.ball_position.is_none());
+    }
+}
@@ -0,0 +1,242 @@
+use std::time::SystemTime;
+
+use color_eyre::{eyre::WrapErr, Result};
+use context_attribute::context;
+use framework::MainOutput;
+use hardware::{IdInterface, PathsInterface};
+use log::warn;
+use nalgebra::Vector3;
+use parameters::{
+    background::BackgroundSerializer,
+    directory::{Id, Location, Scope},
+};
+use types::{
+    parameters::ImuCalibrationRoutine, CycleTime, ImuCalibration, ImuCalibrationQuality,
+    PrimaryState, PrimaryStateTransition, SensorData,
+};
+
+const GRAVITATIONAL_ACCELERATION: f32 = 9.81;
+
+/// Estimates gyro bias and accelerometer offset while the robot stands still in initial
+pub struct ImuCalibrator {
+    samples: Vec<(Vector3<f32>, Vector3<f32>)>,
+    still_since: Option<SystemTime>,
+    is_calibrated: bool,
+    quality: Option<ImuCalibrationQuality>,
+    serializer: BackgroundSerializer,
+}
+
+#[context]
+pub struct CreationContext {
+    pub hardware_interface: HardwareInterface,
+}
+
+#[context]
+pub struct CycleContext {
+    pub cycle_time: Input<CycleTime, "cycle_time">,
+    pub has_ground_contact: Input<bool, "has_ground_contact">,
+    pub primary_state: Input<PrimaryState, "primary_state">,
+    pub primary_state_transition:
+        Input<Option<PrimaryStateTransition>, "primary_state_transition?">,
+    pub sensor_data: Input<SensorData, "sensor_data">,
+
+    pub imu_calibration: Parameter<ImuCalibration, "imu_calibration">,
+    pub routine: Parameter<ImuCalibrationRoutine, "imu_calibration_routine">,
+
+    pub estimated_imu_calibration:
+        PersistentState<Option<ImuCalibration>, "estimated_imu_calibration">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub imu_calibration_quality: MainOutput<Option<ImuCalibrationQuality>>,
+}
+
+impl ImuCalibrator {
+    pub fn new(context: CreationContext<impl IdInterface + PathsInterface>) -> Result<Self> {
+        let ids = context.hardware_interface.get_ids();
+        let serializer = BackgroundSerializer::spawn(
+            context.hardware_interface.get_paths().parameters,
+            ids.body_id,
+            ids.head_id,
+        )
+        .wrap_err("failed to spawn parameter serializer")?;
+        Ok(Self {
+            samples: Vec::new(),
+            still_since: None,
+            is_calibrated: false,
+            quality: None,
+            serializer,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        if let Some(PrimaryStateTransition {
+            to: PrimaryState::Initial,
+            ..
+        }) = context.primary_state_transition
+        {
+            self.is_calibrated = false;
+        }
+        let is_calibrating = !self.is_calibrated
+            && *context.primary_state == PrimaryState::Initial
+            && *context.has_ground_contact;
+        if !is_calibrating {
+            self.reset();
+            return Ok(MainOutputs {
+                imu_calibration_quality: self.quality.into(),
+            });
+        }
+
+        let imu = &context.sensor_data.inertial_measurement_unit;
+        self.samples
+            .push((imu.angular_velocity, imu.linear_acceleration));
+        let quality = quality_of(&self.samples);
+        let is_still = quality.gyro_standard_deviation
+            <= context.routine.maximum_gyro_standard_deviation
+            && quality.accelerometer_standard_deviation
+                <= context.routine.maximum_accelerometer_standard_deviation;
+        if !is_still {
+            self.reset();
+            return Ok(MainOutputs {
+                imu_calibration_quality: self.quality.into(),
+            });
+        }
+
+        let now = context.cycle_time.start_time;
+        let still_since = *self.still_since.get_or_insert(now);
+        let still_duration = now.duration_since(still_since).unwrap_or_default();
+        if still_duration >= context.routine.stillness_duration {
+            // Readings are already corrected by the calibration applied so far
+            let residual = residual_calibration(&self.samples);
+            let applied = context
+                .estimated_imu_calibration
+                .unwrap_or(*context.imu_calibration);
+            let calibration = ImuCalibration {
+                gyro_bias: applied.gyro_bias + residual.gyro_bias,
+                accelerometer_offset: applied.accelerometer_offset + residual.accelerometer_offset,
+            };
+            *context.estimated_imu_calibration = Some(calibration);
+            if context.routine.store_calibration {
+                self.store_calibration(calibration);
+            }
+            self.is_calibrated = true;
+            self.quality = Some(quality);
+            self.reset();
+        }
+
+        Ok(MainOutputs {
+            imu_calibration_quality: self.quality.into(),
+        })
+    }
+
+    fn reset(&mut self) {
+        self.samples.clear();
+        self.still_since = None;
+    }
+
+    /// Writes the calibration into the parameter file of this body in the background
+    fn store_calibration(&self, calibration: ImuCalibration) {
+        let scope = Scope {
+            location: Location::All,
+            id: Id::Body,
+        };
+        if let Err(error) = self
+            .serializer
+            .serialize(&calibration, scope, "imu_calibration")
+        {
+            warn!("Failed to store IMU calibration: {error:?}");
+        }
+    }
+}
+
+fn quality_of(samples: &[(Vector3<f32>, Vector3<f32>)]) -> ImuCalibrationQuality {
+    let (gyro_mean, accelerometer_mean) = mean(samples);
+    let gyro_variance = samples
+        .iter()
+        .map(|(angular_velocity, _)| (angular_velocity - gyro_mean).norm_squared())
+        .sum::<f32>()
+        / samples.len() as f32;
+    let accelerometer_variance = samples
+        .iter()
+        .map(|(_, linear_acceleration)| (linear_acceleration - accelerometer_mean).norm_squared())
+        .sum::<f32>()
+        / samples.len() as f32;
+    ImuCalibrationQuality {
+        number_of_samples: samples.len(),
+        gyro_standard_deviation: gyro_variance.sqrt(),
+        accelerometer_standard_deviation: accelerometer_variance.sqrt(),
+    }
+}
+
+fn mean(samples: &[(Vector3<f32>, Vector3<f32>)]) -> (Vector3<f32>, Vector3<f32>) {
+    let (gyro_sum, accelerometer_sum) = samples.iter().fold(
+        (Vector3::zeros(), Vector3::zeros()),
+        |(gyro_sum, accelerometer_sum), (angular_velocity, linear_acceleration)| {
+            (
+                gyro_sum + angular_velocity,
+                accelerometer_sum + linear_acceleration,
+            )
+        },
+    );
+    let number_of_samples = samples.len().max(1) as f32;
+    (
+        gyro_sum / number_of_samples,
+        accelerometer_sum / number_of_samples,
+    )
+}
+
+/// A resting gyro measures no rotation, a resting accelerometer measures only gravity
+///
+/// Without knowing the orientation, only the accelerometer offset along gravity is observable.
+fn residual_calibration(samples: &[(Vector3<f32>, Vector3<f32>)]) -> ImuCalibration {
+    let (gyro_mean, accelerometer_mean) = mean(samples);
+    let accelerometer_offset = accelerometer_mean
+        .try_normalize(f32::EPSILON)
+        .map_or(Vector3::zeros(), |gravity_direction| {
+            accelerometer_mean - gravity_direction * GRAVITATIONAL_ACCELERATION
+        });
+    ImuCalibration {
+        gyro_bias: gyro_mean,
+        accelerometer_offset,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::vector;
+
+    use super::*;
+
+    #[test]
+    fn resting_readings_yield_bias_and_offset() {
+        let samples = [
+            (vector![0.01, 0.0, -0.02], vector![0.0, 0.0, 10.01]),
+            (vector![0.03, 0.0, -0.02], vector![0.0, 0.0, 10.01]),
+        ];
+
+        let calibration = residual_calibration(&samples);
+
+        assert_relative_eq!(calibration.gyro_bias, vector![0.02, 0.0, -0.02]);
+        assert_relative_eq!(
+            calibration.accelerometer_offset,
+            vector![0.0, 0.0, 0.2],
+            epsilon = 0.001
+        );
+    }
+
+    #[test]
+    fn moving_readings_have_low_quality() {
+        let still = [(Vector3::zeros(), vector![0.0, 0.0, 9.81]); 3];
+        let moving = [
+            (vector![0.5, 0.0, 0.0], vector![0.0, 0.0, 9.81]),
+            (vector![-0.5, 0.0, 0.0], vector![0.0, 0.0, 9.81]),
+        ];
+
+        assert_relative_eq!(quality_of(&still).gyro_standard_deviation, 0.0);
+        assert_relative_eq!(quality_of(&moving).gyro_standard_deviation, 0.5);
+        assert_eq!(quality_of(&moving).number_of_samples, 2);
+    }
+}
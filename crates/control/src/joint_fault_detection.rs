@@ -0,0 +1,206 @@
+use std::{iter::repeat, time::SystemTime};
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::MainOutput;
+use types::{
+    parameters::JointFaultDetection as JointFaultDetectionParameters, CycleTime, JointFault,
+    JointFaultKind, JointGroup, Joints, JointsCommand, SensorData,
+};
+
+/// Compares commanded and measured joint states to find broken gears, stuck and overheating joints
+pub struct JointFaultDetection {
+    following_error_since: Vec<Option<(SystemTime, JointFaultKind)>>,
+    last_temperature_sample: Option<(SystemTime, Vec<f32>)>,
+    temperature_rise_rates: Vec<f32>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    pub cycle_time: Input<CycleTime, "cycle_time">,
+    pub sensor_data: Input<SensorData, "sensor_data">,
+
+    pub parameters: Parameter<JointFaultDetectionParameters, "joint_fault_detection">,
+
+    pub last_joints_command: PersistentState<JointsCommand<f32>, "last_joints_command">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub joint_faults: MainOutput<Vec<JointFault>>,
+}
+
+impl JointFaultDetection {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            following_error_since: Vec::new(),
+            last_temperature_sample: None,
+            temperature_rise_rates: Vec::new(),
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let parameters = context.parameters;
+        let groups = joint_groups(&context.sensor_data.positions);
+        let commanded_positions = flatten(&context.last_joints_command.positions);
+        let commanded_stiffnesses = flatten(&context.last_joints_command.stiffnesses);
+        let measured_positions = flatten(&context.sensor_data.positions);
+        let currents = flatten(&context.sensor_data.currents);
+        let temperatures = flatten(&context.sensor_data.temperature_sensors);
+
+        self.following_error_since.resize(groups.len(), None);
+        for (index, following_error_since) in self.following_error_since.iter_mut().enumerate() {
+            let kind = following_error_kind(
+                commanded_positions[index],
+                commanded_stiffnesses[index],
+                measured_positions[index],
+                currents[index],
+                parameters,
+            );
+            *following_error_since = match (*following_error_since, kind) {
+                (Some((since, last_kind)), Some(kind)) if last_kind == kind => Some((since, kind)),
+                (_, Some(kind)) => Some((now, kind)),
+                (_, None) => None,
+            };
+        }
+        self.update_temperature_rise_rates(now, &temperatures, parameters);
+
+        let mut joint_faults = Vec::new();
+        for (index, group) in groups.into_iter().enumerate() {
+            let persistent_following_error =
+                self.following_error_since[index].and_then(|(since, kind)| {
+                    let duration = now.duration_since(since).unwrap_or_default();
+                    (duration >= parameters.fault_duration).then_some(kind)
+                });
+            let is_overheating = self
+                .temperature_rise_rates
+                .get(index)
+                .map_or(false, |&rate| {
+                    rate > parameters.maximum_temperature_rise_rate
+                        && temperatures[index] > parameters.minimum_overheating_temperature
+                });
+            let kinds = persistent_following_error
+                .into_iter()
+                .chain(is_overheating.then_some(JointFaultKind::Overheating));
+            for kind in kinds {
+                let fault = JointFault { group, kind };
+                if !joint_faults.contains(&fault) {
+                    joint_faults.push(fault);
+                }
+            }
+        }
+
+        Ok(MainOutputs {
+            joint_faults: joint_faults.into(),
+        })
+    }
+
+    fn update_temperature_rise_rates(
+        &mut self,
+        now: SystemTime,
+        temperatures: &[f32],
+        parameters: &JointFaultDetectionParameters,
+    ) {
+        let elapsed = match &self.last_temperature_sample {
+            Some((timestamp, _)) => now.duration_since(*timestamp).unwrap_or_default(),
+            None => {
+                self.last_temperature_sample = Some((now, temperatures.to_vec()));
+                return;
+            }
+        };
+        if elapsed < parameters.temperature_sample_interval {
+            return;
+        }
+        if let Some((_, last_temperatures)) = &self.last_temperature_sample {
+            self.temperature_rise_rates = temperatures
+                .iter()
+                .zip(last_temperatures)
+                .map(|(temperature, last_temperature)| {
+                    (temperature - last_temperature) / elapsed.as_secs_f32()
+                })
+                .collect();
+        }
+        self.last_temperature_sample = Some((now, temperatures.to_vec()));
+    }
+}
+
+/// Classifies a joint not following its command by the current the motor draws
+fn following_error_kind(
+    commanded_position: f32,
+    commanded_stiffness: f32,
+    measured_position: f32,
+    current: f32,
+    parameters: &JointFaultDetectionParameters,
+) -> Option<JointFaultKind> {
+    let is_following = commanded_stiffness < parameters.minimum_stiffness
+        || (commanded_position - measured_position).abs() < parameters.position_error_threshold;
+    if is_following {
+        return None;
+    }
+    if current < parameters.slipping_gear_maximum_current {
+        Some(JointFaultKind::SlippingGear)
+    } else if current > parameters.stuck_minimum_current {
+        Some(JointFaultKind::Stuck)
+    } else {
+        None
+    }
+}
+
+fn flatten(joints: &Joints<f32>) -> Vec<f32> {
+    joints.as_vec().into_iter().flatten().collect()
+}
+
+fn joint_groups(joints: &Joints<f32>) -> Vec<JointGroup> {
+    joints
+        .as_vec()
+        .iter()
+        .zip(JointGroup::ALL)
+        .flat_map(|(values, group)| repeat(group).take(values.len()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters() -> JointFaultDetectionParameters {
+        JointFaultDetectionParameters {
+            minimum_stiffness: 0.5,
+            position_error_threshold: 0.15,
+            slipping_gear_maximum_current: 0.2,
+            stuck_minimum_current: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn following_errors_are_classified_by_current() {
+        let parameters = parameters();
+
+        assert_eq!(following_error_kind(0.5, 1.0, 0.45, 0.1, &parameters), None);
+        assert_eq!(
+            following_error_kind(0.5, 1.0, 0.0, 0.1, &parameters),
+            Some(JointFaultKind::SlippingGear)
+        );
+        assert_eq!(
+            following_error_kind(0.5, 1.0, 0.0, 1.5, &parameters),
+            Some(JointFaultKind::Stuck)
+        );
+        assert_eq!(following_error_kind(0.5, 0.0, 0.0, 0.1, &parameters), None);
+    }
+
+    #[test]
+    fn joint_groups_follow_joint_order() {
+        let groups = joint_groups(&Joints::default());
+
+        assert_eq!(groups.len(), 26);
+        assert_eq!(groups[0], JointGroup::Head);
+        assert_eq!(groups[2], JointGroup::LeftArm);
+        assert_eq!(groups[25], JointGroup::RightLeg);
+    }
+}
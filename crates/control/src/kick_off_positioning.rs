@@ -0,0 +1,227 @@
+use std::time::SystemTime;
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::{AdditionalOutput, MainOutput, PerceptionInput};
+use nalgebra::{distance, Isometry2, Point2, Translation2};
+use spl_network_messages::{PlayerNumber, Team};
+use types::{
+    coordinate_systems::{Field, Ground, Point, Transform},
+    messages::IncomingMessage,
+    parameters::KickOffPositioning as KickOffPositioningParameters,
+    rotate_towards, CycleTime, GameControllerState, Players, Role,
+};
+
+/// Distributes the field players onto the kick off slots such that their total walking distance
+/// is minimal
+pub struct KickOffPositioning {
+    teammate_poses: Players<Option<(SystemTime, Isometry2<f32>)>>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    pub slot_assignment: AdditionalOutput<Players<Option<Point2<f32>>>, "kick_off_slot_assignment">,
+
+    pub cycle_time: Input<CycleTime, "cycle_time">,
+    pub game_controller_state: Input<Option<GameControllerState>, "game_controller_state?">,
+    pub robot_to_field: Input<Option<Transform<Ground, Field>>, "robot_to_field?">,
+    pub role: Input<Role, "role">,
+    pub network_message: PerceptionInput<IncomingMessage, "SplNetwork", "message">,
+
+    pub parameters: Parameter<KickOffPositioningParameters, "kick_off_positioning">,
+    pub player_number: Parameter<PlayerNumber, "player_number">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    /// Pose to walk to in ready, in field coordinates
+    pub kick_off_pose: MainOutput<Option<Isometry2<f32>>>,
+}
+
+impl KickOffPositioning {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            teammate_poses: Default::default(),
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let parameters = context.parameters;
+
+        for (received_at, messages) in &context.network_message.persistent {
+            for message in messages {
+                let IncomingMessage::Spl(message) = message else {
+                    continue;
+                };
+                if message.player_number == *context.player_number {
+                    continue;
+                }
+                let is_keeper =
+                    matches!(message.role, Some(Role::Keeper | Role::ReplacementKeeper));
+                self.teammate_poses[message.player_number] =
+                    (!is_keeper).then_some((*received_at, message.robot_to_field));
+            }
+        }
+
+        let (Some(game_controller_state), Some(robot_to_field)) =
+            (context.game_controller_state, context.robot_to_field)
+        else {
+            return Ok(MainOutputs::default());
+        };
+        if matches!(*context.role, Role::Keeper | Role::ReplacementKeeper) {
+            return Ok(MainOutputs::default());
+        }
+
+        let mut players = Players::<Option<Point2<f32>>>::default();
+        players[*context.player_number] = Some((*robot_to_field * Point::origin()).inner);
+        for (player_number, teammate_pose) in self.teammate_poses.iter() {
+            if player_number == *context.player_number
+                || game_controller_state.penalties[player_number].is_some()
+            {
+                continue;
+            }
+            let Some((received_at, teammate_to_field)) = teammate_pose else {
+                continue;
+            };
+            let is_outdated = now
+                .duration_since(*received_at)
+                .map_or(false, |age| age > parameters.teammate_pose_timeout);
+            if !is_outdated {
+                players[player_number] = Some(teammate_to_field * Point2::origin());
+            }
+        }
+
+        let slots = match game_controller_state.kicking_team {
+            Team::Hulks => &parameters.own_kick_off_slots,
+            _ => &parameters.opponent_kick_off_slots,
+        };
+        let slot_assignment = assign_slots(&players, slots);
+        let kick_off_pose = slot_assignment[*context.player_number].map(|slot| {
+            Isometry2::from_parts(
+                Translation2::from(slot),
+                rotate_towards(slot, Point2::origin()),
+            )
+        });
+
+        context
+            .slot_assignment
+            .fill_if_subscribed(|| slot_assignment);
+
+        Ok(MainOutputs {
+            kick_off_pose: kick_off_pose.into(),
+        })
+    }
+}
+
+/// Assigns the first slots to the players, minimizing the sum of walking distances
+///
+/// An assignment with minimal total distance never contains crossing paths: Swapping the targets
+/// of two crossing paths would always be shorter. Slots are ordered by priority, if there are more
+/// players than slots, the players with the highest numbers stay unassigned.
+fn assign_slots(
+    players: &Players<Option<Point2<f32>>>,
+    slots: &[Point2<f32>],
+) -> Players<Option<Point2<f32>>> {
+    let positions: Vec<_> = players
+        .iter()
+        .filter_map(|(player_number, position)| position.map(|position| (player_number, position)))
+        .take(slots.len())
+        .collect();
+    let slots = &slots[..positions.len()];
+
+    let mut best_permutation = (0..slots.len()).collect::<Vec<_>>();
+    let mut best_distance = f32::INFINITY;
+    let mut permutation = Vec::with_capacity(slots.len());
+    find_shortest_permutation(
+        &positions,
+        slots,
+        &mut permutation,
+        0.0,
+        &mut best_permutation,
+        &mut best_distance,
+    );
+
+    let mut assignment = Players::<Option<Point2<f32>>>::default();
+    for ((player_number, _), slot_index) in positions.iter().zip(best_permutation) {
+        assignment[*player_number] = Some(slots[slot_index]);
+    }
+    assignment
+}
+
+fn find_shortest_permutation(
+    positions: &[(PlayerNumber, Point2<f32>)],
+    slots: &[Point2<f32>],
+    permutation: &mut Vec<usize>,
+    accumulated_distance: f32,
+    best_permutation: &mut Vec<usize>,
+    best_distance: &mut f32,
+) {
+    if accumulated_distance >= *best_distance {
+        return;
+    }
+    let Some((_, position)) = positions.get(permutation.len()) else {
+        *best_distance = accumulated_distance;
+        best_permutation.clone_from(permutation);
+        return;
+    };
+    for slot_index in 0..slots.len() {
+        if permutation.contains(&slot_index) {
+            continue;
+        }
+        permutation.push(slot_index);
+        find_shortest_permutation(
+            positions,
+            slots,
+            permutation,
+            accumulated_distance + distance(position, &slots[slot_index]),
+            best_permutation,
+            best_distance,
+        );
+        permutation.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn players_walk_to_the_closest_slots_without_crossing() {
+        let slots = [point![-0.5, 0.0], point![-1.0, 2.0], point![-1.0, -2.0]];
+        let players = Players {
+            two: Some(point![-3.0, -3.0]),
+            three: Some(point![-3.0, 3.0]),
+            four: Some(point![-1.0, 0.5]),
+            ..Default::default()
+        };
+
+        let assignment = assign_slots(&players, &slots);
+
+        assert_eq!(assignment.two, Some(point![-1.0, -2.0]));
+        assert_eq!(assignment.three, Some(point![-1.0, 2.0]));
+        assert_eq!(assignment.four, Some(point![-0.5, 0.0]));
+        assert_eq!(assignment.one, None);
+    }
+
+    #[test]
+    fn only_prioritized_slots_are_filled() {
+        let slots = [point![-0.5, 0.0], point![-1.0, 2.0], point![-1.0, -2.0]];
+        let players = Players {
+            two: Some(point![-1.0, -2.0]),
+            three: Some(point![-1.0, 2.5]),
+            ..Default::default()
+        };
+
+        let assignment = assign_slots(&players, &slots);
+
+        assert_eq!(assignment.two, Some(point![-0.5, 0.0]));
+        assert_eq!(assignment.three, Some(point![-1.0, 2.0]));
+    }
+}
@@ -7,9 +7,11 @@ use itertools::iproduct;
 use nalgebra::{distance, point, vector, Isometry2, Point2, UnitComplex, Vector2};
 use ordered_float::NotNan;
 use types::{
+    coordinate_systems::{Field, Ground, Transform},
+    localization::LocalizationQuality,
     parameters::{FindKickTargets, InWalkKickInfo, InWalkKicks},
-    rotate_towards, BallState, Circle, CycleTime, FieldDimensions, KickDecision, KickTarget,
-    KickVariant, LineSegment, Obstacle, Side, TwoLineSegments,
+    rotate_towards, BallState, Circle, CycleTime, FieldDimensions, JointFault, KickDecision,
+    KickTarget, KickVariant, LineSegment, Obstacle, ObstacleKind, Side, TwoLineSegments,
 };
 
 pub struct KickSelector {}
@@ -22,9 +24,11 @@ pub struct CycleContext {
     pub cycle_time: Input<CycleTime, "cycle_time">,
     pub invisible_ball_timeout: Parameter<Duration, "kick_selector.invisible_ball_timeout">,
 
-    pub robot_to_field: RequiredInput<Option<Isometry2<f32>>, "robot_to_field?">,
+    pub robot_to_field: RequiredInput<Option<Transform<Ground, Field>>, "robot_to_field?">,
+    pub localization_quality: Input<Option<LocalizationQuality>, "localization_quality?">,
     pub ball_state: RequiredInput<Option<BallState>, "ball_state?">,
     pub obstacles: Input<Vec<Obstacle>, "obstacles">,
+    pub joint_faults: Input<Vec<JointFault>, "joint_faults">,
 
     pub field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
 
@@ -36,10 +40,13 @@ pub struct CycleContext {
     pub ball_radius_for_kick_target_selection:
         Parameter<f32, "kick_selector.ball_radius_for_kick_target_selection">,
     pub closer_threshold: Parameter<f32, "kick_selector.closer_threshold">,
+    pub opponent_lane_clearance: Parameter<f32, "kick_selector.opponent_lane_clearance">,
+    pub opponent_lane_weight: Parameter<f32, "kick_selector.opponent_lane_weight">,
     pub find_kick_targets: Parameter<FindKickTargets, "kick_selector.find_kick_targets">,
 
     pub default_kick_strength: Parameter<f32, "kick_selector.default_kick_strength">,
     pub corner_kick_strength: Parameter<f32, "kick_selector.corner_kick_strength">,
+    pub conservative_kick_strength: Parameter<f32, "kick_selector.conservative_kick_strength">,
 
     pub kick_targets: AdditionalOutput<Vec<KickTarget>, "kick_targets">,
     pub instant_kick_targets: AdditionalOutput<Vec<Point2<f32>>, "instant_kick_targets">,
@@ -58,15 +65,16 @@ impl KickSelector {
     }
 
     pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
-        let ball_position = context.ball_state.ball_in_ground;
+        let ball_position = context.ball_state.ball_in_ground.inner;
+        let robot_to_field = context.robot_to_field.inner;
         let ball_is_visible = context
             .cycle_time
             .start_time
-            .duration_since(context.ball_state.last_seen_ball)
+            .duration_since(context.ball_state.last_seen)
             .expect("time ran backwards")
             <= *context.invisible_ball_timeout;
 
-        let sides = [Side::Left, Side::Right];
+        let sides = kicking_sides(context.joint_faults);
         let mut kick_variants = Vec::new();
         if context.in_walk_kicks.forward.enabled {
             kick_variants.push(KickVariant::Forward)
@@ -91,20 +99,25 @@ impl KickSelector {
             ball_is_visible,
             &obstacle_circles,
             context.field_dimensions,
-            *context.robot_to_field,
+            robot_to_field,
             *context.closer_threshold,
             &mut context.instant_kick_targets,
             *context.default_kick_strength,
         );
 
+        let is_localization_reliable = context
+            .localization_quality
+            .map_or(true, |quality| quality.is_reliable);
         let kick_targets = collect_kick_targets(
-            *context.robot_to_field,
+            robot_to_field,
             context.field_dimensions,
             &obstacle_circles,
             ball_position,
             *context.max_kick_around_obstacle_angle,
             context.find_kick_targets,
             *context.corner_kick_strength,
+            is_localization_reliable,
+            *context.conservative_kick_strength,
         );
 
         context
@@ -138,9 +151,23 @@ impl KickSelector {
                 *context.kick_pose_obstacle_radius,
             );
             let distance_to_left =
-                distance_to_kick_pose(left.kick_pose, *context.angle_distance_weight);
+                distance_to_kick_pose(left.kick_pose, *context.angle_distance_weight)
+                    + *context.opponent_lane_weight
+                        * opponent_lane_cost(
+                            ball_position,
+                            left.target,
+                            context.obstacles,
+                            *context.opponent_lane_clearance,
+                        );
             let distance_to_right =
-                distance_to_kick_pose(right.kick_pose, *context.angle_distance_weight);
+                distance_to_kick_pose(right.kick_pose, *context.angle_distance_weight)
+                    + *context.opponent_lane_weight
+                        * opponent_lane_cost(
+                            ball_position,
+                            right.target,
+                            context.obstacles,
+                            *context.opponent_lane_clearance,
+                        );
             match (left_in_obstacle, right_in_obstacle) {
                 (true, false) => Ordering::Less,
                 (false, true) => Ordering::Greater,
@@ -155,6 +182,23 @@ impl KickSelector {
     }
 }
 
+/// Avoids kicking with a damaged leg unless both legs are damaged
+fn kicking_sides(joint_faults: &[JointFault]) -> Vec<Side> {
+    let sides: Vec<_> = [Side::Left, Side::Right]
+        .into_iter()
+        .filter(|side| {
+            !joint_faults
+                .iter()
+                .any(|fault| fault.group.leg_side() == Some(*side))
+        })
+        .collect();
+    if sides.is_empty() {
+        vec![Side::Left, Side::Right]
+    } else {
+        sides
+    }
+}
+
 fn generate_obstacle_circles(
     obstacles: &[Obstacle],
     ball_radius_for_kick_target_selection: f32,
@@ -174,7 +218,7 @@ fn generate_obstacle_circles(
 
 #[allow(clippy::too_many_arguments)]
 fn generate_decisions_for_instant_kicks(
-    sides: &[Side; 2],
+    sides: &[Side],
     kick_variants: &[KickVariant],
     in_walk_kicks: &InWalkKicks,
     ball_position: Point2<f32>,
@@ -203,15 +247,15 @@ fn generate_decisions_for_instant_kicks(
                 .iter()
                 .any(|circle| circle.intersects_line_segment(&ball_to_target));
             let opponent_goal_center =
-                robot_to_field.inverse() * point![field_dimensions.length / 2.0, 0.0];
+                robot_to_field.inverse() * point![field_dimensions.length.0 / 2.0, 0.0];
             let own_goal_center =
-                robot_to_field.inverse() * point![-field_dimensions.length / 2.0, 0.0];
+                robot_to_field.inverse() * point![-field_dimensions.length.0 / 2.0, 0.0];
             let is_target_closer_to_opponent_goal = (distance(&target, &opponent_goal_center)
                 + closer_threshold)
                 < distance(&ball_position, &opponent_goal_center);
             let goal_box_radius = vector![
-                field_dimensions.goal_box_area_length,
-                field_dimensions.goal_box_area_width / 2.0
+                field_dimensions.goal_box_area_length.0,
+                field_dimensions.goal_box_area_width.0 / 2.0
             ]
             .norm();
             let is_ball_close_to_own_goal =
@@ -234,6 +278,7 @@ fn generate_decisions_for_instant_kicks(
                     variant,
                     kicking_side,
                     kick_pose,
+                    target,
                     strength: default_kick_strength,
                     visible: ball_is_visible,
                 })
@@ -253,17 +298,18 @@ fn is_scoring_goal(
     let ball_to_target = LineSegment::new(robot_to_field * ball_position, robot_to_field * target);
     let opponent_goal_line = LineSegment::new(
         point![
-            field_dimensions.length / 2.0,
-            field_dimensions.goal_inner_width / 2.0
+            field_dimensions.length.0 / 2.0,
+            field_dimensions.goal_inner_width.0 / 2.0
         ],
         point![
-            field_dimensions.length / 2.0,
-            -field_dimensions.goal_inner_width / 2.0
+            field_dimensions.length.0 / 2.0,
+            -field_dimensions.goal_inner_width.0 / 2.0
         ],
     );
     ball_to_target.intersects_line_segment(opponent_goal_line)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn collect_kick_targets(
     robot_to_field: Isometry2<f32>,
     field_dimensions: &FieldDimensions,
@@ -272,11 +318,24 @@ fn collect_kick_targets(
     max_kick_around_obstacle_angle: f32,
     parameters: &FindKickTargets,
     corner_kick_strength: f32,
+    is_localization_reliable: bool,
+    conservative_kick_strength: f32,
 ) -> Vec<KickTarget> {
     let field_to_robot = robot_to_field.inverse();
     let mut kick_targets = Vec::new();
 
-    if is_ball_in_opponents_corners(&ball_position, parameters, field_dimensions, robot_to_field) {
+    if !is_localization_reliable {
+        // the field center stays in place even if the pose is mirrored
+        kick_targets.push(KickTarget::new_with_strength(
+            field_to_robot * Point2::origin(),
+            conservative_kick_strength,
+        ));
+    } else if is_ball_in_opponents_corners(
+        &ball_position,
+        parameters,
+        field_dimensions,
+        robot_to_field,
+    ) {
         kick_targets.extend(generate_corner_kick_targets(
             parameters,
             field_dimensions,
@@ -345,7 +404,7 @@ fn generate_corner_kick_targets(
     corner_kick_strength: f32,
 ) -> Vec<KickTarget> {
     let from_corner_kick_target_x =
-        field_dimensions.length / 2.0 - parameters.corner_kick_target_distance_to_goal;
+        field_dimensions.length.0 / 2.0 - parameters.corner_kick_target_distance_to_goal;
     let position = field_to_robot * point![from_corner_kick_target_x, 0.0];
     vec![KickTarget {
         position,
@@ -359,13 +418,13 @@ fn generate_goal_line_kick_targets(
 ) -> Vec<KickTarget> {
     let left_goal_half = field_to_robot
         * point![
-            field_dimensions.length / 2.0,
-            field_dimensions.goal_inner_width / 4.0
+            field_dimensions.length.0 / 2.0,
+            field_dimensions.goal_inner_width.0 / 4.0
         ];
     let right_goal_half = field_to_robot
         * point![
-            field_dimensions.length / 2.0,
-            -field_dimensions.goal_inner_width / 4.0
+            field_dimensions.length.0 / 2.0,
+            -field_dimensions.goal_inner_width.0 / 4.0
         ];
     vec![
         KickTarget::new(left_goal_half),
@@ -392,6 +451,7 @@ fn kick_decisions_from_targets(
                     variant,
                     kicking_side,
                     kick_pose,
+                    target: position,
                     strength: strength.unwrap_or(default_strength),
                     visible: ball_is_visible,
                 }
@@ -404,6 +464,26 @@ fn distance_to_kick_pose(kick_pose: Isometry2<f32>, angle_distance_weight: f32)
     kick_pose.translation.vector.norm() + angle_distance_weight * kick_pose.rotation.angle().abs()
 }
 
+/// Sums up how far opponents reach into the clearance around the shooting lane
+///
+/// Teammates are ignored since they do not try to intercept the ball.
+fn opponent_lane_cost(
+    ball_position: Point2<f32>,
+    target: Point2<f32>,
+    obstacles: &[Obstacle],
+    opponent_lane_clearance: f32,
+) -> f32 {
+    let ball_to_target = LineSegment(ball_position, target);
+    obstacles
+        .iter()
+        .filter(|obstacle| matches!(obstacle.kind, ObstacleKind::Opponent))
+        .map(|obstacle| {
+            let distance_to_lane = ball_to_target.shortest_distance_to_point(obstacle.position);
+            (opponent_lane_clearance - distance_to_lane).max(0.0)
+        })
+        .sum()
+}
+
 fn is_inside_any_obstacle(
     kick_pose: Isometry2<f32>,
     obstacles: &[Obstacle],
@@ -423,7 +503,7 @@ fn mirror_kick_offset(kick_offset: Vector2<f32>) -> Vector2<f32> {
     vector![kick_offset.x, -kick_offset.y]
 }
 
-fn compute_kick_pose(
+pub fn compute_kick_pose(
     ball_position: Point2<f32>,
     target_to_kick_to: Point2<f32>,
     kick_info: &InWalkKickInfo,
@@ -451,12 +531,43 @@ fn is_ball_in_opponents_corners(
     robot_to_field: Isometry2<f32>,
 ) -> bool {
     let global_ball = robot_to_field * ball_position;
-    let left_opponent_corner = point![field_dimensions.length / 2.0, field_dimensions.width / 2.0];
-    let right_opponent_corner =
-        point![field_dimensions.length / 2.0, -field_dimensions.width / 2.0];
+    let left_opponent_corner = point![
+        field_dimensions.length.0 / 2.0,
+        field_dimensions.width.0 / 2.0
+    ];
+    let right_opponent_corner = point![
+        field_dimensions.length.0 / 2.0,
+        -field_dimensions.width.0 / 2.0
+    ];
     let ball_near_left_opponent_corner =
         distance(&global_ball, &left_opponent_corner) < parameters.distance_from_corner;
     let ball_near_right_opponent_corner =
         distance(&global_ball, &right_opponent_corner) < parameters.distance_from_corner;
     ball_near_left_opponent_corner || ball_near_right_opponent_corner
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_opponents_close_to_the_lane_are_penalized() {
+        let ball_position = point![0.0, 0.0];
+        let target = point![4.0, 0.0];
+        let mut opponent = Obstacle::robot(point![2.0, 0.2], 0.3, 0.2);
+        opponent.kind = ObstacleKind::Opponent;
+        let mut teammate = Obstacle::robot(point![2.0, 0.0], 0.3, 0.2);
+        teammate.kind = ObstacleKind::Teammate;
+        let mut far_opponent = Obstacle::robot(point![2.0, 2.0], 0.3, 0.2);
+        far_opponent.kind = ObstacleKind::Opponent;
+
+        let cost = opponent_lane_cost(
+            ball_position,
+            target,
+            &[opponent, teammate, far_opponent],
+            0.5,
+        );
+
+        assert!((cost - 0.3).abs() < 1e-6);
+    }
+}
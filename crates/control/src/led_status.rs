@@ -3,9 +3,10 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use color_eyre::Result;
 use context_attribute::context;
 use framework::{MainOutput, PerceptionInput};
+use spl_network_messages::{PlayerNumber, Team};
 use types::{
-    messages::IncomingMessage, Ball, CycleTime, Ear, Eye, FilteredWhistle, Leds, PrimaryState, Rgb,
-    Role, SensorData,
+    messages::IncomingMessage, Ball, Battery, CycleTime, Ear, Eye, FilteredWhistle,
+    GameControllerState, JointFault, Leds, Players, PrimaryState, Rgb, Role, SystemWarning,
 };
 
 pub struct LedStatus {
@@ -14,6 +15,7 @@ pub struct LedStatus {
     last_ball_data_top: SystemTime,
     last_ball_data_bottom: SystemTime,
     last_game_controller_message: Option<SystemTime>,
+    last_teammate_messages: Players<Option<SystemTime>>,
 }
 
 #[context]
@@ -25,11 +27,18 @@ pub struct CycleContext {
     pub cycle_time: Input<CycleTime, "cycle_time">,
     pub filtered_whistle: Input<FilteredWhistle, "filtered_whistle">,
     pub role: Input<Role, "role">,
+    pub game_controller_state: Input<Option<GameControllerState>, "game_controller_state?">,
+    pub battery: Input<Option<Battery>, "battery?">,
+    pub system_warnings: Input<Vec<SystemWarning>, "system_warnings">,
+    pub joint_faults: Input<Vec<JointFault>, "joint_faults">,
+    pub localization_score: Input<Option<f32>, "localization_score?">,
+
+    pub minimum_localization_score: Parameter<f32, "led_status.minimum_localization_score">,
+    pub player_number: Parameter<PlayerNumber, "player_number">,
 
     pub balls_bottom: PerceptionInput<Option<Vec<Ball>>, "VisionBottom", "balls?">,
     pub balls_top: PerceptionInput<Option<Vec<Ball>>, "VisionTop", "balls?">,
     pub network_message: PerceptionInput<IncomingMessage, "SplNetwork", "message">,
-    pub sensor_data: Input<SensorData, "sensor_data">,
 }
 
 #[context]
@@ -46,6 +55,7 @@ impl LedStatus {
             last_ball_data_top: UNIX_EPOCH,
             last_ball_data_bottom: UNIX_EPOCH,
             last_game_controller_message: None,
+            last_teammate_messages: Default::default(),
         })
     }
 
@@ -148,6 +158,13 @@ impl LedStatus {
             .unwrap()
             > Duration::from_secs(1);
 
+        let is_localization_unreliable = matches!(
+            context.primary_state,
+            PrimaryState::Ready | PrimaryState::Set | PrimaryState::Playing
+        ) && context
+            .localization_score
+            .map_or(true, |score| *score < *context.minimum_localization_score);
+
         let (left_eye, right_eye) = Self::get_eyes(
             context.cycle_time.start_time,
             context.primary_state,
@@ -156,6 +173,7 @@ impl LedStatus {
             at_least_one_ball_data_bottom,
             last_ball_data_top_too_old,
             last_ball_data_bottom_too_old,
+            is_localization_unreliable && !self.blink_state,
         );
 
         if let Some(latest_game_controller_message_time) = context
@@ -173,26 +191,55 @@ impl LedStatus {
             self.last_game_controller_message = Some(*latest_game_controller_message_time);
         };
 
-        let ears = Self::get_ears(
+        for (timestamp, messages) in context.network_message.persistent.iter() {
+            for message in messages {
+                if let IncomingMessage::Spl(message) = message {
+                    self.last_teammate_messages[message.player_number] = Some(*timestamp);
+                }
+            }
+        }
+        let number_of_connected_teammates = self
+            .last_teammate_messages
+            .iter()
+            .filter(|(player_number, last_message)| {
+                *player_number != *context.player_number
+                    && last_message.is_some_and(|timestamp| {
+                        context
+                            .cycle_time
+                            .start_time
+                            .duration_since(timestamp)
+                            .map_or(true, |duration| duration < Duration::from_millis(5000))
+                    })
+            })
+            .count();
+        let maximum_number_of_teammates = self.last_teammate_messages.iter().count() - 1;
+
+        let (left_ear, right_ear) = Self::get_ears(
             context.filtered_whistle.is_detected,
             context.cycle_time.start_time,
             self.last_game_controller_message,
             self.blink_state,
-            context
-                .sensor_data
-                .temperature_sensors
-                .as_vec()
-                .into_iter()
-                .flatten()
-                .fold(0.0, f32::max),
+            context.battery.map(|battery| battery.charge),
+            !context.system_warnings.is_empty() || !context.joint_faults.is_empty(),
+            number_of_connected_teammates,
+            maximum_number_of_teammates,
         );
 
+        let foot = match context.game_controller_state {
+            Some(GameControllerState {
+                kicking_team: Team::Hulks,
+                ..
+            }) => Rgb::WHITE,
+            Some(_) => Rgb::BLACK,
+            None => Rgb::GREEN,
+        };
+
         let leds = Leds {
-            left_ear: ears,
-            right_ear: ears,
+            left_ear,
+            right_ear,
             chest,
-            left_foot: Rgb::GREEN,
-            right_foot: Rgb::GREEN,
+            left_foot: foot,
+            right_foot: foot,
             left_eye,
             right_eye,
         };
@@ -200,15 +247,19 @@ impl LedStatus {
         Ok(MainOutputs { leds: leds.into() })
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn get_ears(
         filter_whistle_detected: bool,
         cycle_start_time: SystemTime,
         last_game_controller_message: Option<SystemTime>,
         blink_state: bool,
-        current_maximum_temperature: f32,
-    ) -> Ear {
+        battery_charge: Option<f32>,
+        has_system_warnings: bool,
+        number_of_connected_teammates: usize,
+        maximum_number_of_teammates: usize,
+    ) -> (Ear, Ear) {
         if filter_whistle_detected {
-            return Ear::full_ears(1.0);
+            return (Ear::full_ears(1.0), Ear::full_ears(1.0));
         }
 
         if last_game_controller_message.is_some_and(|timestamp| {
@@ -218,21 +269,28 @@ impl LedStatus {
                 > Duration::from_millis(5000)
         }) {
             if blink_state {
-                return Ear::full_ears(1.0);
+                return (Ear::full_ears(1.0), Ear::full_ears(1.0));
             } else {
-                return Ear::full_ears(0.0);
+                return (Ear::full_ears(0.0), Ear::full_ears(0.0));
             }
         }
 
-        let minimum_temperature = 30.0;
-        let maximum_temperature = 105.0;
+        let battery_ear = if has_system_warnings && !blink_state {
+            Ear::full_ears(0.0)
+        } else {
+            Ear::percentage_ears(1.0, battery_charge.unwrap_or(1.0))
+        };
 
-        let relative_temperature = (current_maximum_temperature - minimum_temperature)
-            / (maximum_temperature - minimum_temperature).floor();
+        let relative_number_of_connected_teammates =
+            number_of_connected_teammates as f32 / maximum_number_of_teammates as f32;
 
-        Ear::percentage_ears(1.0, relative_temperature)
+        (
+            battery_ear,
+            Ear::percentage_ears(1.0, relative_number_of_connected_teammates),
+        )
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn get_eyes(
         cycle_start_time: SystemTime,
         primary_state: &PrimaryState,
@@ -241,6 +299,7 @@ impl LedStatus {
         at_least_one_ball_data_bottom: bool,
         last_ball_data_top_too_old: bool,
         last_ball_data_bottom_too_old: bool,
+        hide_role: bool,
     ) -> (Eye, Eye) {
         match primary_state {
             PrimaryState::Unstiff => {
@@ -275,6 +334,7 @@ impl LedStatus {
                     Role::Striker => Rgb::RED,
                     Role::StrikerSupporter => Rgb::TURQUOISE,
                 };
+                let right_color = if hide_role { Rgb::BLACK } else { right_color };
                 (
                     Eye {
                         color_at_0: ball_color_top
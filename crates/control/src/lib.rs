@@ -1,6 +1,7 @@
 pub mod a_star;
 pub mod active_vision;
 pub mod ball_filter;
+pub mod ball_search_heatmap;
 pub mod ball_state_composer;
 pub mod behavior;
 pub mod button_filter;
@@ -9,28 +10,40 @@ pub mod center_of_mass_provider;
 pub mod dribble_path_planner;
 pub mod fake_data;
 pub mod fall_state_estimation;
+pub mod foot_bumper_ball_detection;
 pub mod game_controller_filter;
+pub mod game_controller_return_message_sender;
 pub mod game_state_filter;
 pub mod ground_contact_detector;
 pub mod ground_provider;
+pub mod ground_truth_evaluator;
+pub mod imu_calibrator;
+pub mod joint_fault_detection;
+pub mod kick_off_positioning;
 pub mod kick_selector;
 pub mod kinematics_provider;
 pub mod led_status;
 pub mod localization;
 pub mod localization_recorder;
 pub mod motion;
+pub mod motion_tester;
+pub mod network_quality_estimation;
 pub mod obstacle_filter;
 pub mod odometry;
 pub mod orientation_filter;
 pub mod path_planner;
 pub mod penalty_shot_direction_estimation;
-pub mod primary_state_filter;
+pub mod primary_state_machine;
 pub mod role_assignment;
 pub mod rule_obstacle_composer;
 pub mod sensor_data_receiver;
 pub mod sole_pressure_filter;
 pub mod sonar_filter;
+pub mod sound_cues;
 pub mod support_foot_estimation;
+pub mod symmetry_flip_detector;
+pub mod system_monitor;
+pub mod time_to_reach;
 pub mod time_to_reach_kick_position;
 pub mod visual_referee_filter;
 pub mod whistle_filter;
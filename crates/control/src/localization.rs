@@ -1,6 +1,7 @@
 use std::{
     f32::consts::{FRAC_PI_2, PI},
     mem::take,
+    time::{Duration, SystemTime},
 };
 
 use approx::assert_relative_eq;
@@ -15,25 +16,39 @@ use nalgebra::{
 use ordered_float::NotNan;
 use spl_network_messages::{GamePhase, Penalty, PlayerNumber, Team};
 use types::{
+    coordinate_systems::{Field, Ground, Transform},
     field_marks_from_field_dimensions,
-    localization::{ScoredPose, Update},
+    localization::{LocalizationQuality, ScoredPose, Update},
     multivariate_normal_distribution::MultivariateNormalDistribution,
-    CorrespondencePoints, Direction, FieldDimensions, FieldMark, GameControllerState, InitialPose,
-    Line, Line2, LineData, Players, PrimaryState, Side,
+    parameters::{InitialPoseDetection, LocalizationReliability},
+    CorrespondencePoints, CycleTime, Direction, FieldDimensions, FieldMark, GameControllerState,
+    InitialPose, Line, Line2, LineData, Players, PrimaryState, PrimaryStateTransition, Side,
 };
 
 pub struct Localization {
+    /// The field dimensions the field marks were generated from
+    field_dimensions: FieldDimensions,
     field_marks: Vec<FieldMark>,
-    last_primary_state: PrimaryState,
     hypotheses: Vec<ScoredPose>,
     hypotheses_when_entered_playing: Vec<ScoredPose>,
     is_penalized_with_motion_in_set: bool,
     was_picked_up_while_penalized_with_motion_in_set: bool,
+    last_measurement_time: Option<SystemTime>,
+    /// Whether the hypotheses were seeded from a pose restored from a snapshot which has not been
+    /// left yet by a primary state transition
+    has_restored_pose: bool,
 }
 
 #[context]
 pub struct CreationContext {
     pub field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    pub initial_hypothesis_covariance:
+        Parameter<Matrix3<f32>, "localization.initial_hypothesis_covariance">,
+    pub initial_hypothesis_score: Parameter<f32, "localization.initial_hypothesis_score">,
+
+    /// Restored after a restart to continue a game from the last known pose
+    #[snapshot]
+    pub robot_to_field: PersistentState<Isometry2<f32>, "robot_to_field">,
 }
 
 #[context]
@@ -48,9 +63,12 @@ pub struct CycleContext {
     pub current_odometry_to_last_odometry:
         HistoricInput<Option<Isometry2<f32>>, "current_odometry_to_last_odometry?">,
 
+    pub cycle_time: Input<CycleTime, "cycle_time">,
     pub game_controller_state: Input<Option<GameControllerState>, "game_controller_state?">,
     pub has_ground_contact: Input<bool, "has_ground_contact">,
     pub primary_state: Input<PrimaryState, "primary_state">,
+    pub primary_state_transition:
+        Input<Option<PrimaryStateTransition>, "primary_state_transition?">,
 
     pub circle_measurement_noise: Parameter<Vector2<f32>, "localization.circle_measurement_noise">,
     pub field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
@@ -66,6 +84,8 @@ pub struct CycleContext {
     pub initial_hypothesis_covariance:
         Parameter<Matrix3<f32>, "localization.initial_hypothesis_covariance">,
     pub initial_hypothesis_score: Parameter<f32, "localization.initial_hypothesis_score">,
+    pub initial_pose_detection:
+        Parameter<InitialPoseDetection, "localization.initial_pose_detection">,
     pub initial_poses: Parameter<Players<InitialPose>, "localization.initial_poses">,
     pub line_length_acceptance_factor: Parameter<f32, "localization.line_length_acceptance_factor">,
     pub line_measurement_noise: Parameter<Vector2<f32>, "localization.line_measurement_noise">,
@@ -76,6 +96,7 @@ pub struct CycleContext {
     pub minimum_fit_error: Parameter<f32, "localization.minimum_fit_error">,
     pub odometry_noise: Parameter<Vector3<f32>, "localization.odometry_noise">,
     pub player_number: Parameter<PlayerNumber, "player_number">,
+    pub reliability: Parameter<LocalizationReliability, "localization.reliability">,
     pub score_per_good_match: Parameter<f32, "localization.score_per_good_match">,
     pub use_line_measurements: Parameter<bool, "localization.use_line_measurements">,
     pub injected_robot_to_field_of_home_after_coin_toss_before_second_half: Parameter<
@@ -87,41 +108,57 @@ pub struct CycleContext {
     pub line_data_top: PerceptionInput<Option<LineData>, "VisionTop", "line_data?">,
 
     pub robot_to_field: PersistentState<Isometry2<f32>, "robot_to_field">,
+    pub symmetry_flip_requested: PersistentState<bool, "symmetry_flip_requested">,
 }
 
 #[context]
 #[derive(Default)]
 pub struct MainOutputs {
-    pub robot_to_field: MainOutput<Option<Isometry2<f32>>>,
+    pub robot_to_field: MainOutput<Option<Transform<Ground, Field>>>,
     pub robot_to_field_of_home_after_coin_toss_before_second_half:
         MainOutput<Option<Isometry2<f32>>>,
+    pub localization_score: MainOutput<Option<f32>>,
+    pub localization_quality: MainOutput<Option<LocalizationQuality>>,
 }
 
 impl Localization {
     pub fn new(context: CreationContext) -> Result<Self> {
+        let hypotheses = vec![ScoredPose::from_isometry(
+            *context.robot_to_field,
+            *context.initial_hypothesis_covariance,
+            *context.initial_hypothesis_score,
+        )];
         Ok(Self {
-            field_marks: field_marks_from_field_dimensions(context.field_dimensions)
-                .into_iter()
-                .chain(goal_support_structure_line_marks_from_field_dimensions(
-                    context.field_dimensions,
-                ))
-                .collect(),
-            last_primary_state: PrimaryState::Unstiff,
-            hypotheses: vec![],
-            hypotheses_when_entered_playing: vec![],
+            field_dimensions: context.field_dimensions.clone(),
+            field_marks: generate_field_marks(context.field_dimensions),
+            hypotheses_when_entered_playing: hypotheses.clone(),
+            hypotheses,
             is_penalized_with_motion_in_set: false,
             was_picked_up_while_penalized_with_motion_in_set: false,
+            last_measurement_time: None,
+            has_restored_pose: *context.robot_to_field != Isometry2::identity(),
         })
     }
 
+    /// Whether the transition keeps the restored pose instead of resetting the hypotheses
+    ///
+    /// After a restart the first transition leaves `Unstiff` which would otherwise discard the
+    /// restored pose by placing the robot at the sideline.
+    fn keeps_restored_pose(&mut self, transition: PrimaryStateTransition) -> bool {
+        take(&mut self.has_restored_pose) && transition.from == PrimaryState::Unstiff
+    }
+
     fn reset_state(
         &mut self,
-        primary_state: PrimaryState,
+        transition: PrimaryStateTransition,
         game_phase: Option<GamePhase>,
         context: &CycleContext,
         penalty: &Option<Penalty>,
     ) {
-        match (self.last_primary_state, primary_state, game_phase) {
+        if self.keeps_restored_pose(transition) {
+            return;
+        }
+        match (transition.from, transition.to, game_phase) {
             (PrimaryState::Initial, PrimaryState::Ready, _) => {
                 let initial_pose = generate_initial_pose(
                     &context.initial_poses[*context.player_number],
@@ -142,8 +179,8 @@ impl Localization {
                 }),
             ) => {
                 let penalty_shoot_out_striker_pose = Isometry2::translation(
-                    -context.field_dimensions.penalty_area_length
-                        + (context.field_dimensions.length / 2.0),
+                    -context.field_dimensions.penalty_area_length.0
+                        + (context.field_dimensions.length.0 / 2.0),
                     0.0,
                 );
                 self.hypotheses = vec![ScoredPose::from_isometry(
@@ -161,7 +198,7 @@ impl Localization {
                 }),
             ) => {
                 let penalty_shoot_out_keeper_pose =
-                    Isometry2::translation(-context.field_dimensions.length / 2.0, 0.0);
+                    Isometry2::translation(-context.field_dimensions.length.0 / 2.0, 0.0);
                 self.hypotheses = vec![ScoredPose::from_isometry(
                     penalty_shoot_out_keeper_pose,
                     *context.initial_hypothesis_covariance,
@@ -181,7 +218,7 @@ impl Localization {
                     None => {}
                 };
             }
-            (PrimaryState::Penalized, _, _) if primary_state != PrimaryState::Penalized => {
+            (PrimaryState::Penalized, _, _) => {
                 if self.is_penalized_with_motion_in_set {
                     if self.was_picked_up_while_penalized_with_motion_in_set {
                         self.hypotheses = take(&mut self.hypotheses_when_entered_playing);
@@ -201,32 +238,12 @@ impl Localization {
                     self.is_penalized_with_motion_in_set = false;
                     self.was_picked_up_while_penalized_with_motion_in_set = false;
                 } else {
-                    let penalized_poses = generate_penalized_poses(context.field_dimensions);
-                    self.hypotheses = penalized_poses
-                        .into_iter()
-                        .map(|pose| {
-                            ScoredPose::from_isometry(
-                                pose,
-                                *context.initial_hypothesis_covariance,
-                                *context.initial_hypothesis_score,
-                            )
-                        })
-                        .collect();
+                    self.hypotheses = generate_sideline_hypotheses(context);
                     self.hypotheses_when_entered_playing = self.hypotheses.clone();
                 }
             }
             (PrimaryState::Unstiff, _, _) => {
-                let penalized_poses = generate_penalized_poses(context.field_dimensions);
-                self.hypotheses = penalized_poses
-                    .into_iter()
-                    .map(|pose| {
-                        ScoredPose::from_isometry(
-                            pose,
-                            *context.initial_hypothesis_covariance,
-                            *context.initial_hypothesis_score,
-                        )
-                    })
-                    .collect();
+                self.hypotheses = generate_sideline_hypotheses(context);
                 self.hypotheses_when_entered_playing = self.hypotheses.clone();
             }
             _ => {}
@@ -333,6 +350,9 @@ impl Localization {
                     let clamped_fit_error = fit_error.max(*context.minimum_fit_error);
                     let number_of_measurements_weight =
                         1.0 / field_mark_correspondences.len() as f32;
+                    if !field_mark_correspondences.is_empty() {
+                        self.last_measurement_time = Some(*line_data_top_timestamp);
+                    }
 
                     for field_mark_correspondence in field_mark_correspondences {
                         let update = match field_mark_correspondence.field_mark {
@@ -455,6 +475,10 @@ impl Localization {
     }
 
     pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        if *context.field_dimensions != self.field_dimensions {
+            self.field_dimensions = context.field_dimensions.clone();
+            self.field_marks = generate_field_marks(context.field_dimensions);
+        }
         let primary_state = *context.primary_state;
         let penalty = context
             .game_controller_state
@@ -465,8 +489,16 @@ impl Localization {
             .game_controller_state
             .map(|game_controller_state| game_controller_state.game_phase);
 
-        self.reset_state(primary_state, game_phase, &context, &penalty);
-        self.last_primary_state = primary_state;
+        if let Some(&transition) = context.primary_state_transition {
+            self.reset_state(transition, game_phase, &context, &penalty);
+        }
+
+        if *context.symmetry_flip_requested {
+            for hypothesis in &mut self.hypotheses {
+                *hypothesis = mirror_hypothesis(hypothesis);
+            }
+            *context.symmetry_flip_requested = false;
+        }
 
         if self.is_penalized_with_motion_in_set && !context.has_ground_contact {
             self.was_picked_up_while_penalized_with_motion_in_set = true;
@@ -475,10 +507,30 @@ impl Localization {
         let robot_to_field = match primary_state {
             PrimaryState::Ready | PrimaryState::Set | PrimaryState::Playing => {
                 self.update_state(&mut context)?;
-                Some(*context.robot_to_field)
+                Some(Transform::wrap(*context.robot_to_field))
             }
             _ => None,
         };
+        let localization_score = robot_to_field
+            .and_then(|_| self.get_best_hypothesis())
+            .map(|best_hypothesis| best_hypothesis.score);
+        let time_since_last_measurement = self.last_measurement_time.map(|measurement_time| {
+            context
+                .cycle_time
+                .start_time
+                .duration_since(measurement_time)
+                .unwrap_or_default()
+        });
+        let localization_quality =
+            robot_to_field
+                .and_then(|_| self.get_best_hypothesis())
+                .map(|best_hypothesis| {
+                    assess_quality(
+                        &best_hypothesis.state.covariance,
+                        time_since_last_measurement,
+                        context.reliability,
+                    )
+                });
         let robot_to_field_of_home_after_coin_toss_before_second_half = context
             .injected_robot_to_field_of_home_after_coin_toss_before_second_half
             .copied()
@@ -492,9 +544,9 @@ impl Localization {
                             Isometry2::from_parts(
                                 Translation2::default(),
                                 Rotation2::new(PI).into(),
-                            ) * robot_to_field
+                            ) * robot_to_field.inner
                         } else {
-                            robot_to_field
+                            robot_to_field.inner
                         }
                     })
             });
@@ -502,6 +554,8 @@ impl Localization {
             robot_to_field: robot_to_field.into(),
             robot_to_field_of_home_after_coin_toss_before_second_half:
                 robot_to_field_of_home_after_coin_toss_before_second_half.into(),
+            localization_score: localization_score.into(),
+            localization_quality: localization_quality.into(),
         })
     }
 
@@ -512,20 +566,29 @@ impl Localization {
     }
 }
 
+fn generate_field_marks(field_dimensions: &FieldDimensions) -> Vec<FieldMark> {
+    field_marks_from_field_dimensions(field_dimensions)
+        .into_iter()
+        .chain(goal_support_structure_line_marks_from_field_dimensions(
+            field_dimensions,
+        ))
+        .collect()
+}
+
 fn goal_support_structure_line_marks_from_field_dimensions(
     field_dimensions: &FieldDimensions,
 ) -> Vec<FieldMark> {
-    let goal_width = field_dimensions.goal_inner_width + field_dimensions.goal_post_diameter;
-    let goal_depth = field_dimensions.goal_depth;
+    let goal_width = field_dimensions.goal_inner_width.0 + field_dimensions.goal_post_diameter.0;
+    let goal_depth = field_dimensions.goal_depth.0;
     vec![
         FieldMark::Line {
             line: Line(
                 point![
-                    -field_dimensions.length / 2.0 - goal_depth,
+                    -field_dimensions.length.0 / 2.0 - goal_depth,
                     -goal_width / 2.0
                 ],
                 point![
-                    -field_dimensions.length / 2.0 - goal_depth,
+                    -field_dimensions.length.0 / 2.0 - goal_depth,
                     goal_width / 2.0
                 ],
             ),
@@ -534,38 +597,41 @@ fn goal_support_structure_line_marks_from_field_dimensions(
         FieldMark::Line {
             line: Line(
                 point![
-                    -field_dimensions.length / 2.0 - goal_depth,
+                    -field_dimensions.length.0 / 2.0 - goal_depth,
                     -goal_width / 2.0
                 ],
-                point![-field_dimensions.length / 2.0, -goal_width / 2.0],
+                point![-field_dimensions.length.0 / 2.0, -goal_width / 2.0],
             ),
             direction: Direction::PositiveX,
         },
         FieldMark::Line {
             line: Line(
                 point![
-                    -field_dimensions.length / 2.0 - goal_depth,
+                    -field_dimensions.length.0 / 2.0 - goal_depth,
                     goal_width / 2.0
                 ],
-                point![-field_dimensions.length / 2.0, goal_width / 2.0],
+                point![-field_dimensions.length.0 / 2.0, goal_width / 2.0],
             ),
             direction: Direction::PositiveX,
         },
         FieldMark::Line {
             line: Line(
                 point![
-                    field_dimensions.length / 2.0 + goal_depth,
+                    field_dimensions.length.0 / 2.0 + goal_depth,
                     -goal_width / 2.0
                 ],
-                point![field_dimensions.length / 2.0 + goal_depth, goal_width / 2.0],
+                point![
+                    field_dimensions.length.0 / 2.0 + goal_depth,
+                    goal_width / 2.0
+                ],
             ),
             direction: Direction::PositiveY,
         },
         FieldMark::Line {
             line: Line(
-                point![field_dimensions.length / 2.0, -goal_width / 2.0],
+                point![field_dimensions.length.0 / 2.0, -goal_width / 2.0],
                 point![
-                    field_dimensions.length / 2.0 + goal_depth,
+                    field_dimensions.length.0 / 2.0 + goal_depth,
                     -goal_width / 2.0
                 ],
             ),
@@ -573,8 +639,11 @@ fn goal_support_structure_line_marks_from_field_dimensions(
         },
         FieldMark::Line {
             line: Line(
-                point![field_dimensions.length / 2.0, goal_width / 2.0],
-                point![field_dimensions.length / 2.0 + goal_depth, goal_width / 2.0],
+                point![field_dimensions.length.0 / 2.0, goal_width / 2.0],
+                point![
+                    field_dimensions.length.0 / 2.0 + goal_depth,
+                    goal_width / 2.0
+                ],
             ),
             direction: Direction::PositiveX,
         },
@@ -937,47 +1006,238 @@ pub fn generate_initial_pose(
         Side::Left => Isometry2::new(
             vector!(
                 initial_pose.center_line_offset_x,
-                field_dimensions.width * 0.5
+                field_dimensions.width.0 * 0.5
             ),
             -FRAC_PI_2,
         ),
         Side::Right => Isometry2::new(
             vector!(
                 initial_pose.center_line_offset_x,
-                -field_dimensions.width * 0.5
+                -field_dimensions.width.0 * 0.5
             ),
             FRAC_PI_2,
         ),
     }
 }
 
+/// Hypotheses of a robot placed at the sideline after being penalized or woken up
+///
+/// Without GameController the robot may have been placed anywhere along the touchlines of the own
+/// half, otherwise it is placed at the penalty marker.
+fn generate_sideline_hypotheses(context: &CycleContext) -> Vec<ScoredPose> {
+    if context.initial_pose_detection.enable && context.game_controller_state.is_none() {
+        generate_touchline_poses(
+            context.field_dimensions,
+            context.initial_pose_detection.hypothesis_spacing,
+        )
+        .into_iter()
+        .map(|pose| {
+            ScoredPose::from_isometry(
+                pose,
+                context.initial_pose_detection.hypothesis_covariance,
+                *context.initial_hypothesis_score,
+            )
+        })
+        .collect()
+    } else {
+        generate_penalized_poses(context.field_dimensions)
+            .into_iter()
+            .map(|pose| {
+                ScoredPose::from_isometry(
+                    pose,
+                    *context.initial_hypothesis_covariance,
+                    *context.initial_hypothesis_score,
+                )
+            })
+            .collect()
+    }
+}
+
 fn generate_penalized_poses(field_dimensions: &FieldDimensions) -> Vec<Isometry2<f32>> {
     vec![
         Isometry2::new(
             vector!(
-                -field_dimensions.length * 0.5 + field_dimensions.penalty_marker_distance,
-                -field_dimensions.width * 0.5
+                -field_dimensions.length.0 * 0.5 + field_dimensions.penalty_marker_distance.0,
+                -field_dimensions.width.0 * 0.5
             ),
             FRAC_PI_2,
         ),
         Isometry2::new(
             vector!(
-                -field_dimensions.length * 0.5 + field_dimensions.penalty_marker_distance,
-                field_dimensions.width * 0.5
+                -field_dimensions.length.0 * 0.5 + field_dimensions.penalty_marker_distance.0,
+                field_dimensions.width.0 * 0.5
             ),
             -FRAC_PI_2,
         ),
     ]
 }
 
+/// Mirrors the pose at the field center, the covariance is unchanged by the rotation of half a turn
+fn mirror_hypothesis(hypothesis: &ScoredPose) -> ScoredPose {
+    let field_center_rotation = Isometry2::rotation(PI);
+    ScoredPose::from_isometry(
+        field_center_rotation * hypothesis.state.as_isometry(),
+        hypothesis.state.covariance,
+        hypothesis.score,
+    )
+}
+
+fn assess_quality(
+    pose_covariance: &Matrix3<f32>,
+    time_since_last_measurement: Option<Duration>,
+    reliability: &LocalizationReliability,
+) -> LocalizationQuality {
+    let pose_covariance_eigenvalues = pose_covariance.symmetric_eigenvalues();
+    let is_covariance_small = pose_covariance_eigenvalues
+        .iter()
+        .all(|eigenvalue| *eigenvalue <= reliability.maximum_covariance_eigenvalue);
+    let is_measurement_recent = time_since_last_measurement.map_or(false, |duration| {
+        duration <= reliability.maximum_time_since_last_measurement
+    });
+    LocalizationQuality {
+        pose_covariance_eigenvalues,
+        time_since_last_measurement,
+        is_reliable: is_covariance_small && is_measurement_recent,
+    }
+}
+
+/// Poses along both touchlines of the own half facing into the field
+///
+/// Used when the robot was placed manually without GameController. Restricting the poses to the
+/// own half avoids the point symmetry of the field, the first line and circle observations then
+/// quickly rule out the wrong poses.
+fn generate_touchline_poses(
+    field_dimensions: &FieldDimensions,
+    hypothesis_spacing: f32,
+) -> Vec<Isometry2<f32>> {
+    let half_length = field_dimensions.length.0 * 0.5;
+    let number_of_poses_per_touchline = (half_length / hypothesis_spacing).floor() as usize + 1;
+    (0..number_of_poses_per_touchline)
+        .map(|index| -half_length + index as f32 * hypothesis_spacing)
+        .flat_map(|x| {
+            [
+                Isometry2::new(vector!(x, field_dimensions.width.0 * 0.5), -FRAC_PI_2),
+                Isometry2::new(vector!(x, -field_dimensions.width.0 * 0.5), FRAC_PI_2),
+            ]
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::f32::consts::FRAC_PI_4;
 
     use nalgebra::point;
+    use types::units::Meters;
 
     use super::*;
 
+    #[test]
+    fn mirrored_hypothesis_faces_the_other_way() {
+        let hypothesis = ScoredPose::from_isometry(
+            Isometry2::new(vector![-2.0, 1.0], FRAC_PI_4),
+            Matrix3::identity(),
+            3.0,
+        );
+
+        let mirrored = mirror_hypothesis(&hypothesis).state.as_isometry();
+
+        assert_relative_eq!(
+            mirrored.translation.vector,
+            vector![2.0, -1.0],
+            epsilon = 1e-6
+        );
+        assert_relative_eq!(mirrored.rotation.angle(), FRAC_PI_4 - PI, epsilon = 1e-6);
+    }
+
+    fn localization_from_snapshot(mut robot_to_field: Isometry2<f32>) -> Localization {
+        Localization::new(CreationContext {
+            field_dimensions: &FieldDimensions::default(),
+            initial_hypothesis_covariance: &Matrix3::identity(),
+            initial_hypothesis_score: &1.0,
+            robot_to_field: &mut robot_to_field,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn restored_pose_survives_only_the_first_transition_out_of_unstiff() {
+        let mut localization =
+            localization_from_snapshot(Isometry2::new(vector![1.0, -2.0], FRAC_PI_4));
+        let leaving_unstiff = PrimaryStateTransition {
+            from: PrimaryState::Unstiff,
+            to: PrimaryState::Playing,
+        };
+
+        assert!(localization.keeps_restored_pose(leaving_unstiff));
+        assert!(!localization.keeps_restored_pose(leaving_unstiff));
+    }
+
+    #[test]
+    fn restored_pose_is_forgotten_after_other_transitions() {
+        let mut localization =
+            localization_from_snapshot(Isometry2::new(vector![1.0, -2.0], FRAC_PI_4));
+
+        assert!(!localization.keeps_restored_pose(PrimaryStateTransition {
+            from: PrimaryState::Initial,
+            to: PrimaryState::Ready,
+        }));
+        assert!(!localization.keeps_restored_pose(PrimaryStateTransition {
+            from: PrimaryState::Unstiff,
+            to: PrimaryState::Initial,
+        }));
+    }
+
+    #[test]
+    fn default_pose_is_reset_when_leaving_unstiff() {
+        let mut localization = localization_from_snapshot(Isometry2::identity());
+
+        assert!(!localization.keeps_restored_pose(PrimaryStateTransition {
+            from: PrimaryState::Unstiff,
+            to: PrimaryState::Initial,
+        }));
+    }
+
+    #[test]
+    fn dead_reckoned_poses_are_unreliable() {
+        let reliability = LocalizationReliability {
+            maximum_covariance_eigenvalue: 0.1,
+            maximum_time_since_last_measurement: Duration::from_secs(5),
+        };
+        let small_covariance = Matrix3::from_diagonal(&vector![0.01, 0.02, 0.01]);
+        let large_covariance = Matrix3::from_diagonal(&vector![0.5, 0.02, 0.01]);
+
+        let recently = Some(Duration::from_secs(1));
+        let long_ago = Some(Duration::from_secs(6));
+
+        let quality = assess_quality(&small_covariance, recently, &reliability);
+        assert!(quality.is_reliable);
+        assert_relative_eq!(quality.pose_covariance_eigenvalues.max(), 0.02);
+
+        assert!(!assess_quality(&large_covariance, recently, &reliability).is_reliable);
+        assert!(!assess_quality(&small_covariance, long_ago, &reliability).is_reliable);
+        assert!(!assess_quality(&small_covariance, None, &reliability).is_reliable);
+    }
+
+    #[test]
+    fn touchline_poses_cover_own_half_facing_inward() {
+        let field_dimensions = FieldDimensions {
+            length: Meters(9.0),
+            width: Meters(6.0),
+            ..Default::default()
+        };
+
+        let poses = generate_touchline_poses(&field_dimensions, 1.0);
+
+        assert_eq!(poses.len(), 10);
+        for pose in poses {
+            assert!((-4.5..=0.0).contains(&pose.translation.x));
+            assert_relative_eq!(pose.translation.y.abs(), 3.0);
+            let forward = pose * vector![1.0, 0.0];
+            assert_relative_eq!(forward.y, -pose.translation.y.signum(), epsilon = 1e-6);
+        }
+    }
+
     #[test]
     fn signed_angle() {
         let vector0 = vector![1.0_f32, 0.0_f32];
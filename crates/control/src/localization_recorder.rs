@@ -11,7 +11,10 @@ use context_attribute::context;
 use framework::{HistoricInput, PerceptionInput};
 use nalgebra::Isometry2;
 use serde::{Deserialize, Serialize};
-use types::{GameControllerState, LineData, PrimaryState};
+use types::{
+    coordinate_systems::{Field, Ground, Transform},
+    GameControllerState, LineData, PrimaryState,
+};
 
 pub struct LocalizationRecorder {
     recording: Option<BufWriter<File>>,
@@ -34,7 +37,7 @@ pub struct CycleContext {
     pub game_controller_state: Input<Option<GameControllerState>, "game_controller_state?">,
     pub has_ground_contact: Input<bool, "has_ground_contact">,
     pub primary_state: Input<PrimaryState, "primary_state">,
-    pub robot_to_field: Input<Option<Isometry2<f32>>, "robot_to_field?">,
+    pub robot_to_field: Input<Option<Transform<Ground, Field>>, "robot_to_field?">,
 
     pub line_data_bottom: PerceptionInput<Option<LineData>, "VisionBottom", "line_data?">,
     pub line_data_top: PerceptionInput<Option<LineData>, "VisionTop", "line_data?">,
@@ -148,7 +151,7 @@ pub struct RecordedCycleContext {
     pub game_controller_state: Option<GameControllerState>,
     pub has_ground_contact: bool,
     pub primary_state: PrimaryState,
-    pub robot_to_field: Option<Isometry2<f32>>,
+    pub robot_to_field: Option<Transform<Ground, Field>>,
 
     pub line_data_bottom_persistent: BTreeMap<SystemTime, Vec<Option<LineData>>>,
     pub line_data_bottom_temporary: BTreeMap<SystemTime, Vec<Option<LineData>>>,
@@ -1,24 +1,29 @@
+use std::time::Duration;
+
 use color_eyre::Result;
 use context_attribute::context;
-use filtering::low_pass_filter::LowPassFilter;
+use filtering::low_pass_filter::ExponentialMovingAverage;
 use framework::MainOutput;
 use nalgebra::Vector3;
-use types::{ConditionInput, FallState, SensorData};
+use types::{ConditionInput, CycleTime, FallState, SensorData};
 
 #[derive(Default)]
 pub struct ConditionInputProvider {
-    angular_velocity_filter: LowPassFilter<Vector3<f32>>,
+    angular_velocity_filter: ExponentialMovingAverage<Vector3<f32>>,
 }
 
 #[context]
 pub struct CreationContext {
-    pub angular_velocity_smoothing_factor: Parameter<f32, "angular_velocity_smoothing_factor">,
+    pub gyro_time_constant: Parameter<Duration, "stand_up.gyro_time_constant">,
 }
 
 #[context]
 pub struct CycleContext {
+    pub cycle_time: Input<CycleTime, "cycle_time">,
     pub sensor_data: Input<SensorData, "sensor_data">,
     pub fall_state: Input<FallState, "fall_state">,
+
+    pub gyro_time_constant: Parameter<Duration, "stand_up.gyro_time_constant">,
 }
 
 #[context]
@@ -30,19 +35,22 @@ pub struct MainOutputs {
 impl ConditionInputProvider {
     pub fn new(context: CreationContext) -> Result<Self> {
         Ok(Self {
-            angular_velocity_filter: LowPassFilter::with_smoothing_factor(
+            angular_velocity_filter: ExponentialMovingAverage::new(
                 Default::default(),
-                *context.angular_velocity_smoothing_factor,
+                *context.gyro_time_constant,
             ),
         })
     }
 
     pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        self.angular_velocity_filter
+            .set_time_constant(*context.gyro_time_constant);
         self.angular_velocity_filter.update(
             context
                 .sensor_data
                 .inertial_measurement_unit
                 .angular_velocity,
+            context.cycle_time.last_cycle_duration,
         );
         Ok(MainOutputs {
             condition_input: ConditionInput {
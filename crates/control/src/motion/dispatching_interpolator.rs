@@ -94,6 +94,7 @@ impl DispatchingInterpolator {
                 MotionType::StandUpBack => *context.stand_up_back_positions,
                 MotionType::StandUpFront => *context.stand_up_front_positions,
                 MotionType::Unstiff => panic!("Dispatching Unstiff doesn't make sense"),
+                MotionType::WakeUp => panic!("Waking up starts at the current positions"),
                 MotionType::Walk => Joints::from_head_and_body(
                     HeadJoints::fill(0.0),
                     context.walk_joints_command.positions,
@@ -1,9 +1,13 @@
-use std::f32::consts::PI;
+use std::{
+    f32::consts::{PI, TAU},
+    time::{Duration, SystemTime},
+};
 
 use color_eyre::Result;
 use context_attribute::context;
 use framework::MainOutput;
 use types::{
+    parameters::{HeadMotionLimits, ScanProfileParameters, ScanProfiles},
     CycleTime, HeadJoints, HeadJointsCommand, HeadMotion as HeadMotionCommand, MotionCommand,
     SensorData,
 };
@@ -11,6 +15,7 @@ use types::{
 #[derive(Default)]
 pub struct HeadMotion {
     last_positions: HeadJoints<f32>,
+    scan_started_at: Option<SystemTime>,
 }
 
 #[context]
@@ -29,6 +34,8 @@ pub struct CycleContext {
     pub maximum_velocity: Parameter<HeadJoints<f32>, "head_motion.maximum_velocity">,
     pub outer_maximum_pitch: Parameter<f32, "head_motion.outer_maximum_pitch">,
     pub outer_yaw: Parameter<f32, "head_motion.outer_yaw">,
+    pub head_motion_limits: Parameter<HeadMotionLimits, "head_motion_limits">,
+    pub scan_profiles: Parameter<ScanProfiles, "head_motion.scan_profiles">,
 
     pub look_around: Input<HeadJoints<f32>, "look_around">,
     pub look_at: Input<HeadJoints<f32>, "look_at">,
@@ -48,16 +55,28 @@ impl HeadMotion {
     pub fn new(_context: CreationContext) -> Result<Self> {
         Ok(Self {
             last_positions: Default::default(),
+            scan_started_at: None,
         })
     }
 
     pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let is_scanning = matches!(
+            context.motion_command.head_motion(),
+            Some(HeadMotionCommand::LookAround { .. })
+        );
+        self.scan_started_at = is_scanning.then(|| self.scan_started_at.unwrap_or(now));
+        let scan_duration = self
+            .scan_started_at
+            .and_then(|scan_started_at| now.duration_since(scan_started_at).ok())
+            .unwrap_or_default();
+
         let HeadJointsCommand {
             positions: raw_positions,
             stiffnesses,
         } = context
             .has_ground_contact
-            .then(|| Self::joints_from_motion(&context))
+            .then(|| Self::joints_from_motion(&context, scan_duration))
             .unwrap_or_else(|| HeadJointsCommand {
                 positions: Default::default(),
                 stiffnesses: HeadJoints::fill(0.8),
@@ -101,24 +120,41 @@ impl HeadMotion {
         })
     }
 
-    pub fn joints_from_motion(context: &CycleContext) -> HeadJointsCommand<f32> {
+    pub fn joints_from_motion(
+        context: &CycleContext,
+        scan_duration: Duration,
+    ) -> HeadJointsCommand<f32> {
         let stiffnesses = HeadJoints::fill(0.8);
         match context.motion_command.head_motion() {
             Some(HeadMotionCommand::Center) => HeadJointsCommand {
                 positions: *context.center_head_position,
                 stiffnesses,
             },
-            Some(HeadMotionCommand::LookAround | HeadMotionCommand::SearchForLostBall) => {
-                HeadJointsCommand {
-                    positions: *context.look_around,
-                    stiffnesses,
-                }
-            }
+            Some(HeadMotionCommand::LookAround { profile }) => HeadJointsCommand {
+                positions: scan_positions(&context.scan_profiles[profile], scan_duration),
+                stiffnesses,
+            },
+            Some(HeadMotionCommand::SearchForLostBall) => HeadJointsCommand {
+                positions: *context.look_around,
+                stiffnesses,
+            },
             Some(HeadMotionCommand::LookAt { .. })
             | Some(HeadMotionCommand::LookLeftAndRightOf { .. }) => HeadJointsCommand {
                 positions: *context.look_at,
                 stiffnesses,
             },
+            // requested angles are arbitrary, e.g. from teleoperation, hence are kept within the
+            // range the head joints can reach
+            Some(HeadMotionCommand::Angles { yaw, pitch }) => HeadJointsCommand {
+                positions: HeadJoints {
+                    yaw: yaw.clamp(
+                        -context.head_motion_limits.maximum_yaw,
+                        context.head_motion_limits.maximum_yaw,
+                    ),
+                    pitch: pitch.clamp(0.0, *context.inner_maximum_pitch),
+                },
+                stiffnesses,
+            },
             Some(HeadMotionCommand::Unstiff) => HeadJointsCommand {
                 positions: context.sensor_data.positions.head,
                 stiffnesses: HeadJoints::fill(0.0),
@@ -130,3 +166,57 @@ impl HeadMotion {
         }
     }
 }
+
+/// Sweeps the head yaw sinusoidally, starting at the center and moving to the left first
+///
+/// A profile without a period does not sweep and holds the center of the scan.
+pub fn scan_positions(profile: &ScanProfileParameters, scan_duration: Duration) -> HeadJoints<f32> {
+    let phase = if profile.period.is_zero() {
+        0.0
+    } else {
+        scan_duration.as_secs_f32() / profile.period.as_secs_f32()
+    };
+    HeadJoints {
+        yaw: profile.yaw_amplitude * (TAU * phase).sin(),
+        pitch: profile.pitch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn scan_reaches_both_sides_within_one_period() {
+        let profile = ScanProfileParameters {
+            yaw_amplitude: 1.0,
+            pitch: 0.3,
+            period: Duration::from_secs(4),
+        };
+
+        assert_relative_eq!(scan_positions(&profile, Duration::ZERO).yaw, 0.0);
+        assert_relative_eq!(scan_positions(&profile, Duration::from_secs(1)).yaw, 1.0);
+        assert_relative_eq!(
+            scan_positions(&profile, Duration::from_secs(3)).yaw,
+            -1.0,
+            epsilon = 0.001
+        );
+        assert_relative_eq!(scan_positions(&profile, Duration::from_secs(3)).pitch, 0.3);
+    }
+
+    #[test]
+    fn scan_without_period_holds_the_center() {
+        let profile = ScanProfileParameters {
+            yaw_amplitude: 1.0,
+            pitch: 0.3,
+            period: Duration::ZERO,
+        };
+
+        let positions = scan_positions(&profile, Duration::from_secs(1));
+
+        assert_relative_eq!(positions.yaw, 0.0);
+        assert_relative_eq!(positions.pitch, 0.3);
+    }
+}
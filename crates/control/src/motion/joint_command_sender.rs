@@ -3,8 +3,8 @@ use context_attribute::context;
 use framework::AdditionalOutput;
 use hardware::ActuatorInterface;
 use types::{
-    BodyJointsCommand, HeadJoints, HeadJointsCommand, Joints, JointsCommand, Leds, MotionSafeExits,
-    MotionSelection, MotionType, SensorData,
+    hardware::ActuatorDiagnostics, BodyJointsCommand, HeadJoints, HeadJointsCommand, Joints,
+    JointsCommand, Leds, MotionSafeExits, MotionSelection, MotionType, SensorData,
 };
 
 pub struct JointCommandSender {}
@@ -19,7 +19,9 @@ pub struct CycleContext {
     pub positions_difference: AdditionalOutput<Joints<f32>, "positions_difference">,
     pub stiffnesses: AdditionalOutput<Joints<f32>, "stiffnesses">,
     pub motion_safe_exits_output: AdditionalOutput<MotionSafeExits, "motion_safe_exits_output">,
+    pub actuator_diagnostics: AdditionalOutput<ActuatorDiagnostics, "actuator_diagnostics">,
 
+    pub last_joints_command: PersistentState<JointsCommand<f32>, "last_joints_command">,
     pub motion_safe_exits: PersistentState<MotionSafeExits, "motion_safe_exits">,
 
     pub center_head_position: Parameter<HeadJoints<f32>, "center_head_position">,
@@ -39,6 +41,7 @@ pub struct CycleContext {
     pub sit_down_joints_command: Input<JointsCommand<f32>, "sit_down_joints_command">,
     pub stand_up_back_positions: Input<Joints<f32>, "stand_up_back_positions">,
     pub stand_up_front_positions: Input<Joints<f32>, "stand_up_front_positions">,
+    pub wake_up_joints_command: Input<JointsCommand<f32>, "wake_up_joints_command">,
     pub walk_joints_command: Input<BodyJointsCommand<f32>, "walk_joints_command">,
     pub hardware_interface: HardwareInterface,
     pub leds: Input<Leds, "leds">,
@@ -69,6 +72,7 @@ impl JointCommandSender {
         let sit_down = context.sit_down_joints_command;
         let stand_up_back_positions = context.stand_up_back_positions;
         let stand_up_front_positions = context.stand_up_front_positions;
+        let wake_up = context.wake_up_joints_command;
         let walk = context.walk_joints_command;
 
         let (positions, stiffnesses) = match motion_selection.current_motion {
@@ -89,6 +93,7 @@ impl JointCommandSender {
             MotionType::StandUpBack => (*stand_up_back_positions, Joints::fill(1.0)),
             MotionType::StandUpFront => (*stand_up_front_positions, Joints::fill(1.0)),
             MotionType::Unstiff => (current_positions, Joints::fill(0.0)),
+            MotionType::WakeUp => (wake_up.positions, wake_up.stiffnesses),
             MotionType::Walk => (
                 Joints::from_head_and_body(head_joints_command.positions, walk.positions),
                 Joints::from_head_and_body(head_joints_command.stiffnesses, walk.stiffnesses),
@@ -113,6 +118,10 @@ impl JointCommandSender {
             .hardware_interface
             .write_to_actuators(compensated_positions, stiffnesses, *context.leds)
             .wrap_err("failed to write to actuators")?;
+        *context.last_joints_command = JointsCommand {
+            positions,
+            stiffnesses,
+        };
 
         context.positions.fill_if_subscribed(|| positions);
 
@@ -128,6 +137,9 @@ impl JointCommandSender {
         context
             .motion_safe_exits_output
             .fill_if_subscribed(|| context.motion_safe_exits.clone());
+        context
+            .actuator_diagnostics
+            .fill_if_subscribed(|| context.hardware_interface.get_actuator_diagnostics());
 
         Ok(MainOutputs {})
     }
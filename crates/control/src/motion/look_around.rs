@@ -44,12 +44,6 @@ impl LookAround {
 
     pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
         match context.motion_command.head_motion() {
-            Some(HeadMotion::LookAround) => {
-                self.look_around(
-                    context.cycle_time.start_time,
-                    context.config.look_around_timeout,
-                );
-            }
             Some(HeadMotion::SearchForLostBall) => self.quick_search(
                 context.cycle_time.start_time,
                 context.config.quick_search_timeout,
@@ -84,47 +78,6 @@ impl LookAround {
         })
     }
 
-    fn look_around(&mut self, start_time: SystemTime, time_at_each_position: Duration) {
-        if start_time.duration_since(self.last_mode_switch).unwrap() < time_at_each_position {
-            return;
-        }
-        self.last_mode_switch = start_time;
-        self.current_mode = match self.current_mode {
-            Mode::Center {
-                moving_towards: Side::Left,
-            } => Mode::HalfwayLeft {
-                moving_towards: Side::Left,
-            },
-            Mode::Center {
-                moving_towards: Side::Right,
-            } => Mode::HalfwayRight {
-                moving_towards: Side::Right,
-            },
-            Mode::Left => Mode::HalfwayLeft {
-                moving_towards: Side::Right,
-            },
-            Mode::Right => Mode::HalfwayRight {
-                moving_towards: Side::Left,
-            },
-            Mode::HalfwayLeft {
-                moving_towards: Side::Left,
-            } => Mode::Left,
-            Mode::HalfwayLeft {
-                moving_towards: Side::Right,
-            } => Mode::Center {
-                moving_towards: Side::Right,
-            },
-            Mode::HalfwayRight {
-                moving_towards: Side::Left,
-            } => Mode::Center {
-                moving_towards: Side::Left,
-            },
-            Mode::HalfwayRight {
-                moving_towards: Side::Right,
-            } => Mode::Right,
-        }
-    }
-
     fn quick_search(&mut self, start_time: SystemTime, time_at_each_position: Duration) {
         if start_time.duration_since(self.last_mode_switch).unwrap() < time_at_each_position {
             return;
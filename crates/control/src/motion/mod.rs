@@ -14,5 +14,6 @@ pub mod sit_down;
 pub mod stand_up_back;
 pub mod stand_up_front;
 pub mod step_planner;
+pub mod wake_up;
 pub mod walk_manager;
 pub mod walking_engine;
@@ -39,25 +39,20 @@ impl MotionSelector {
 
     pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
         let motion_safe_to_exit = context.motion_safe_exits[self.current_motion];
-        let requested_motion =
-            motion_type_from_command(context.motion_command, *context.enable_energy_saving_stand);
+        let requested_motion = motion_to_reach(
+            self.current_motion,
+            motion_type_from_command(context.motion_command, *context.enable_energy_saving_stand),
+            *context.has_ground_contact,
+        );
 
-        self.current_motion = transition_motion(
+        self.current_motion = next_motion(
             self.current_motion,
             requested_motion,
             motion_safe_to_exit,
             *context.has_ground_contact,
         );
-
-        self.dispatching_motion = if self.current_motion == MotionType::Dispatching {
-            if requested_motion == MotionType::Unstiff {
-                Some(MotionType::SitDown)
-            } else {
-                Some(requested_motion)
-            }
-        } else {
-            None
-        };
+        self.dispatching_motion =
+            (self.current_motion == MotionType::Dispatching).then_some(requested_motion);
 
         Ok(MainOutputs {
             motion_selection: MotionSelection {
@@ -101,25 +96,155 @@ fn motion_type_from_command(
     }
 }
 
-fn transition_motion(
-    from: MotionType,
-    to: MotionType,
+fn next_motion(
+    current: MotionType,
+    requested: MotionType,
     motion_safe_to_exit: bool,
     has_ground_contact: bool,
 ) -> MotionType {
-    match (from, motion_safe_to_exit, to, has_ground_contact) {
-        (MotionType::SitDown, true, MotionType::Unstiff, _) => MotionType::Unstiff,
-        (_, _, MotionType::Unstiff, false) => MotionType::Unstiff,
-        (MotionType::Dispatching, true, MotionType::Unstiff, true) => MotionType::SitDown,
-        (MotionType::StandUpFront, _, MotionType::FallProtection, _) => MotionType::StandUpFront,
-        (MotionType::StandUpBack, _, MotionType::FallProtection, _) => MotionType::StandUpBack,
-        (MotionType::StandUpFront, true, MotionType::StandUpFront, _) => MotionType::Dispatching,
-        (MotionType::StandUpBack, true, MotionType::StandUpBack, _) => MotionType::Dispatching,
-        (_, _, MotionType::FallProtection, _) => MotionType::FallProtection,
-        (MotionType::Dispatching, true, _, _) => to,
-        (MotionType::Stand, _, MotionType::Walk, _) => MotionType::Walk,
-        (MotionType::Walk, _, MotionType::Stand, _) => MotionType::Stand,
-        (from, true, to, _) if from != to => MotionType::Dispatching,
-        _ => from,
+    match (
+        transition(current, requested, has_ground_contact),
+        motion_safe_to_exit,
+    ) {
+        (Transition::Preempt, _) | (Transition::OnSafeExit, true) => requested,
+        (Transition::Dispatch, true) => MotionType::Dispatching,
+        _ => current,
+    }
+}
+
+/// Robots with ground contact sit down before becoming unstiff and wake up before anything else
+fn motion_to_reach(
+    current: MotionType,
+    requested: MotionType,
+    has_ground_contact: bool,
+) -> MotionType {
+    match (current, requested) {
+        (MotionType::SitDown | MotionType::Unstiff, MotionType::Unstiff) => MotionType::Unstiff,
+        (_, MotionType::Unstiff) if has_ground_contact => MotionType::SitDown,
+        (MotionType::Unstiff, _) if has_ground_contact => MotionType::WakeUp,
+        _ => requested,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Transition {
+    /// Switch immediately, regardless of whether the current motion is safe to exit
+    Preempt,
+    /// Switch directly once the current motion is safe to exit
+    OnSafeExit,
+    /// Interpolate to the requested motion once the current motion is safe to exit
+    Dispatch,
+    /// Keep executing the current motion
+    Reject,
+}
+
+/// Which motion may follow which
+fn transition(from: MotionType, to: MotionType, has_ground_contact: bool) -> Transition {
+    match (from, to) {
+        (_, MotionType::Unstiff) if !has_ground_contact => Transition::Preempt,
+        (MotionType::SitDown, MotionType::Unstiff) | (MotionType::Unstiff, MotionType::WakeUp) => {
+            Transition::OnSafeExit
+        }
+        (MotionType::StandUpFront | MotionType::StandUpBack, MotionType::FallProtection) => {
+            Transition::Reject
+        }
+        (_, MotionType::FallProtection) => Transition::Preempt,
+        (MotionType::Stand, MotionType::Walk) | (MotionType::Walk, MotionType::Stand) => {
+            Transition::Preempt
+        }
+        (MotionType::Dispatching, _) => Transition::OnSafeExit,
+        // Standing up again after a failed attempt starts over from the first pose
+        (MotionType::StandUpFront, MotionType::StandUpFront)
+        | (MotionType::StandUpBack, MotionType::StandUpBack) => Transition::Dispatch,
+        (from, to) if from == to => Transition::Reject,
+        _ => Transition::Dispatch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unstiff_is_reached_by_sitting_down_first() {
+        let requested = motion_to_reach(MotionType::Walk, MotionType::Unstiff, true);
+        assert_eq!(requested, MotionType::SitDown);
+        assert_eq!(
+            next_motion(MotionType::Walk, requested, true, true),
+            MotionType::Dispatching
+        );
+        assert_eq!(
+            next_motion(MotionType::Dispatching, requested, true, true),
+            MotionType::SitDown
+        );
+
+        let requested = motion_to_reach(MotionType::SitDown, MotionType::Unstiff, true);
+        assert_eq!(
+            next_motion(MotionType::SitDown, requested, false, true),
+            MotionType::SitDown
+        );
+        assert_eq!(
+            next_motion(MotionType::SitDown, requested, true, true),
+            MotionType::Unstiff
+        );
+    }
+
+    #[test]
+    fn unstiff_robots_wake_up_before_standing() {
+        let requested = motion_to_reach(MotionType::Unstiff, MotionType::Stand, true);
+        assert_eq!(requested, MotionType::WakeUp);
+        assert_eq!(
+            next_motion(MotionType::Unstiff, requested, true, true),
+            MotionType::WakeUp
+        );
+
+        let requested = motion_to_reach(MotionType::WakeUp, MotionType::Stand, true);
+        assert_eq!(
+            next_motion(MotionType::WakeUp, requested, false, true),
+            MotionType::WakeUp
+        );
+        assert_eq!(
+            next_motion(MotionType::WakeUp, requested, true, true),
+            MotionType::Dispatching
+        );
+
+        assert_eq!(
+            motion_to_reach(MotionType::Unstiff, MotionType::Stand, false),
+            MotionType::Stand
+        );
+    }
+
+    #[test]
+    fn fall_protection_preempts_all_but_standing_up() {
+        assert_eq!(
+            next_motion(MotionType::Walk, MotionType::FallProtection, false, true),
+            MotionType::FallProtection
+        );
+        assert_eq!(
+            next_motion(
+                MotionType::StandUpFront,
+                MotionType::FallProtection,
+                true,
+                true
+            ),
+            MotionType::StandUpFront
+        );
+    }
+
+    #[test]
+    fn failed_stand_up_is_restarted_via_dispatching() {
+        assert_eq!(
+            next_motion(
+                MotionType::StandUpBack,
+                MotionType::StandUpBack,
+                false,
+                true
+            ),
+            MotionType::StandUpBack
+        );
+        assert_eq!(
+            next_motion(MotionType::StandUpBack, MotionType::StandUpBack, true, true),
+            MotionType::Dispatching
+        );
     }
 }
@@ -27,8 +27,6 @@ pub struct CycleContext {
     pub motion_selection: Input<MotionSelection, "motion_selection">,
     pub sensor_data: Input<SensorData, "sensor_data">,
 
-    pub gyro_low_pass_filter_coefficient:
-        Parameter<f32, "stand_up.gyro_low_pass_filter_coefficient">,
     pub gyro_low_pass_filter_tolerance: Parameter<f32, "stand_up.gyro_low_pass_filter_tolerance">,
     pub maximum_velocity: Parameter<JointsVelocity, "maximum_joint_velocities">,
 
@@ -54,17 +52,12 @@ impl StandUpBack {
     }
 
     pub fn advance_interpolator(&mut self, context: CycleContext) {
-        let last_cycle_duration = context.cycle_time.last_cycle_duration;
-        let condition_input = context.condition_input;
+        self.interpolator.advance_by(
+            context.cycle_time.last_cycle_duration,
+            context.condition_input,
+        );
 
-        context.motion_safe_exits[MotionType::StandUpBack] = false;
-
-        self.interpolator
-            .advance_by(last_cycle_duration, condition_input);
-
-        if self.interpolator.is_finished() {
-            context.motion_safe_exits[MotionType::StandUpBack] = true;
-        }
+        context.motion_safe_exits[MotionType::StandUpBack] = self.interpolator.is_finished();
     }
 
     pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
@@ -1,8 +1,15 @@
 use color_eyre::{eyre::eyre, Result};
 use context_attribute::context;
-use framework::MainOutput;
-use nalgebra::{Isometry2, UnitComplex};
-use types::{MotionCommand, OrientationMode, PathSegment, SensorData, Step, SupportFoot};
+use framework::{AdditionalOutput, MainOutput};
+use types::{
+    parameters::PositioningGaitActivation, units::Meters, ArrivalTolerance, BallPosition,
+    MotionCommand, Obstacle, OrientationMode, PathSegment, SensorData, Side, SpeedProfile, Step,
+    SupportFoot, WalkGait,
+};
+
+use self::footstep_planner::FootstepPlanner;
+
+mod footstep_planner;
 
 pub struct StepPlanner {}
 
@@ -11,23 +18,33 @@ pub struct CreationContext {}
 
 #[context]
 pub struct CycleContext {
+    pub ball_position: Input<Option<BallPosition>, "ball_position?">,
     pub motion_command: Input<MotionCommand, "motion_command">,
+    pub obstacles: Input<Vec<Obstacle>, "obstacles">,
     pub sensor_data: Input<SensorData, "sensor_data">,
     pub support_foot: Input<SupportFoot, "support_foot">,
 
     pub injected_step: Parameter<Option<Step>, "step_planner.injected_step?">,
     pub max_step_size: Parameter<Step, "step_planner.max_step_size">,
-    pub max_step_size_backwards: Parameter<f32, "step_planner.max_step_size_backwards">,
+    pub max_step_size_backwards: Parameter<Meters, "step_planner.max_step_size_backwards">,
     pub rotation_exponent: Parameter<f32, "step_planner.rotation_exponent">,
     pub translation_exponent: Parameter<f32, "step_planner.translation_exponent">,
+    pub footstep_preview_length: Parameter<usize, "step_planner.footstep_preview_length">,
+    pub lookahead_distance: Parameter<f32, "step_planner.lookahead_distance">,
+    pub obstacle_clearance: Parameter<f32, "step_planner.obstacle_clearance">,
+    pub positioning_gait: Parameter<PositioningGaitActivation, "step_planner.positioning_gait">,
 
     pub walk_return_offset: PersistentState<Step, "walk_return_offset">,
+    pub walk_swing_side: PersistentState<Option<Side>, "walk_swing_side">,
+
+    pub footstep_preview: AdditionalOutput<Vec<Step>, "footstep_preview">,
 }
 
 #[context]
 #[derive(Default)]
 pub struct MainOutputs {
     pub step_plan: MainOutput<Step>,
+    pub walk_gait: MainOutput<WalkGait>,
 }
 
 impl StepPlanner {
@@ -35,68 +52,77 @@ impl StepPlanner {
         Ok(Self {})
     }
 
-    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
-        let (path, orientation_mode) = match context.motion_command {
-            MotionCommand::Walk {
-                path,
-                orientation_mode,
-                ..
-            } => (path, orientation_mode),
-            _ => {
-                return Ok(MainOutputs {
-                    step_plan: Step {
-                        forward: 0.0,
-                        left: 0.0,
-                        turn: 0.0,
-                    }
-                    .into(),
-                })
-            }
-        };
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let (path, orientation_mode, speed_profile, arrival_tolerance) =
+            match context.motion_command {
+                MotionCommand::Walk {
+                    path,
+                    orientation_mode,
+                    speed_profile,
+                    arrival_tolerance,
+                    ..
+                } => (path, orientation_mode, speed_profile, arrival_tolerance),
+                _ => {
+                    return Ok(MainOutputs {
+                        step_plan: Step::zero().into(),
+                        walk_gait: WalkGait::Normal.into(),
+                    })
+                }
+            };
+
+        if path.is_empty() {
+            return Err(eyre!("empty path provided"));
+        }
 
-        let segment = path
-            .iter()
-            .scan(0.0f32, |distance, segment| {
-                let result = if *distance < context.max_step_size.forward {
-                    Some(segment)
-                } else {
-                    None
-                };
-                *distance += segment.length();
-                result
-            })
-            .last()
-            .ok_or_else(|| eyre!("empty path provided"))?;
-
-        let target_pose = match segment {
-            PathSegment::LineSegment(line_segment) => {
-                let direction = line_segment.1;
-                let rotation = if direction.coords.norm_squared() < f32::EPSILON {
-                    UnitComplex::identity()
-                } else {
-                    UnitComplex::from_cos_sin_unchecked(direction.x, direction.y)
-                };
-                Isometry2::from_parts(line_segment.1.into(), rotation)
-            }
-            PathSegment::Arc(arc, orientation) => {
-                let direction = orientation
-                    .rotate_vector_90_degrees(arc.start - arc.circle.center)
-                    .normalize();
-                Isometry2::from_parts(
-                    (arc.start + direction * 1.0).into(),
-                    UnitComplex::from_cos_sin_unchecked(direction.x, direction.y),
-                )
-            }
+        let distance_to_target: f32 = path.iter().map(PathSegment::length).sum();
+        let has_arrived = is_target_reached(
+            distance_to_target,
+            orientation_mode,
+            arrival_tolerance.as_ref(),
+        );
+        let walk_gait = select_walk_gait(
+            distance_to_target,
+            context.ball_position,
+            context.positioning_gait,
+        );
+        let step_size_factor = step_size_factor(
+            distance_to_target,
+            speed_profile.as_ref(),
+            walk_gait,
+            context.positioning_gait,
+        );
+        let max_step_size = Step {
+            forward: context.max_step_size.forward * step_size_factor,
+            left: context.max_step_size.left * step_size_factor,
+            turn: context.max_step_size.turn,
         };
+        let max_step_size_backwards = *context.max_step_size_backwards * step_size_factor;
 
-        let mut step = Step {
-            forward: target_pose.translation.x,
-            left: target_pose.translation.y,
-            turn: match orientation_mode {
-                OrientationMode::AlignWithPath => target_pose.rotation,
-                OrientationMode::Override(orientation) => *orientation,
-            }
-            .angle(),
+        let mut step = if has_arrived {
+            Step::zero()
+        } else {
+            let footstep_planner = FootstepPlanner {
+                max_step_size: &max_step_size,
+                max_step_size_backwards,
+                translation_exponent: *context.translation_exponent,
+                rotation_exponent: *context.rotation_exponent,
+                lookahead_distance: *context.lookahead_distance,
+                obstacle_clearance: *context.obstacle_clearance,
+                obstacles: context.obstacles,
+            };
+            let first_swing_side = context
+                .walk_swing_side
+                .map_or(Side::Left, |swing_side| swing_side.opposite());
+            let footsteps = footstep_planner.plan(
+                path,
+                *orientation_mode,
+                first_swing_side,
+                (*context.footstep_preview_length).max(1),
+            );
+            context
+                .footstep_preview
+                .fill_if_subscribed(|| footsteps.clone());
+            footsteps[0]
         };
 
         if let Some(injected_step) = context.injected_step {
@@ -106,18 +132,69 @@ impl StepPlanner {
         let step = compensate_with_return_offset(step, *context.walk_return_offset);
         let step = clamp_step_to_walk_volume(
             step,
-            context.max_step_size,
-            *context.max_step_size_backwards,
+            &max_step_size,
+            max_step_size_backwards,
             *context.translation_exponent,
             *context.rotation_exponent,
         );
 
         Ok(MainOutputs {
             step_plan: step.into(),
+            walk_gait: walk_gait.into(),
         })
     }
 }
 
+fn is_target_reached(
+    distance_to_target: f32,
+    orientation_mode: &OrientationMode,
+    arrival_tolerance: Option<&ArrivalTolerance>,
+) -> bool {
+    let angle_to_target = match orientation_mode {
+        OrientationMode::AlignWithPath => 0.0,
+        OrientationMode::Override(orientation) => orientation.angle(),
+        OrientationMode::FaceTowards(facing_target) => facing_target.y.atan2(facing_target.x),
+    };
+    match arrival_tolerance {
+        Some(arrival_tolerance) => {
+            arrival_tolerance.is_reached(distance_to_target, angle_to_target)
+        }
+        None => false,
+    }
+}
+
+fn step_size_factor(
+    distance_to_target: f32,
+    speed_profile: Option<&SpeedProfile>,
+    walk_gait: WalkGait,
+    positioning_gait: &PositioningGaitActivation,
+) -> f32 {
+    let speed_fraction = match speed_profile {
+        Some(speed_profile) => speed_profile.fraction(distance_to_target),
+        None => 1.0,
+    };
+    match walk_gait {
+        WalkGait::Normal => speed_fraction,
+        WalkGait::Positioning => speed_fraction * positioning_gait.step_size_factor,
+    }
+}
+
+fn select_walk_gait(
+    distance_to_target: f32,
+    ball_position: Option<&BallPosition>,
+    activation: &PositioningGaitActivation,
+) -> WalkGait {
+    let is_close_to_target = distance_to_target < activation.distance_to_target;
+    let is_close_to_ball = ball_position.map_or(false, |ball| {
+        ball.position.coords.norm() < activation.distance_to_ball
+    });
+    if is_close_to_target || is_close_to_ball {
+        WalkGait::Positioning
+    } else {
+        WalkGait::Normal
+    }
+}
+
 fn compensate_with_return_offset(step: Step, walk_return_offset: Step) -> Step {
     step - walk_return_offset
 }
@@ -125,7 +202,7 @@ fn compensate_with_return_offset(step: Step, walk_return_offset: Step) -> Step {
 fn clamp_step_to_walk_volume(
     request: Step,
     max_step_size: &Step,
-    max_step_size_backwards: f32,
+    max_step_size_backwards: Meters,
     translation_exponent: f32,
     rotation_exponent: f32,
 ) -> Step {
@@ -164,11 +241,11 @@ fn clamp_step_to_walk_volume(
 fn calculate_walk_volume(
     request: Step,
     max_step_size: &Step,
-    max_step_size_backwards: f32,
+    max_step_size_backwards: Meters,
     translation_exponent: f32,
     rotation_exponent: f32,
 ) -> f32 {
-    let is_walking_forward = request.forward.is_sign_positive();
+    let is_walking_forward = request.forward.0.is_sign_positive();
     let max_forward = if is_walking_forward {
         max_step_size.forward
     } else {
@@ -186,11 +263,11 @@ fn calculate_walk_volume(
 fn calculate_max_step_size_in_walk_volume(
     request: Step,
     max_step_size: &Step,
-    max_step_size_backwards: f32,
+    max_step_size_backwards: Meters,
     translation_exponent: f32,
     rotation_exponent: f32,
-) -> (f32, f32) {
-    let is_walking_forward = request.forward.is_sign_positive();
+) -> (Meters, Meters) {
+    let is_walking_forward = request.forward.0.is_sign_positive();
     let max_forward = if is_walking_forward {
         max_step_size.forward
     } else {
@@ -206,3 +283,96 @@ fn calculate_max_step_size_in_walk_volume(
     .powf(1.0 / translation_exponent);
     (request.forward * scale, request.left * scale)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use nalgebra::{point, UnitComplex, Vector2};
+
+    use super::*;
+
+    const ARRIVAL_TOLERANCE: ArrivalTolerance = ArrivalTolerance {
+        distance: 0.05,
+        angle: 0.1,
+    };
+
+    #[test]
+    fn targets_within_arrival_tolerance_are_reached() {
+        let aligned = OrientationMode::AlignWithPath;
+        let turned = OrientationMode::Override(UnitComplex::new(0.5));
+
+        assert!(is_target_reached(0.04, &aligned, Some(&ARRIVAL_TOLERANCE)));
+        assert!(!is_target_reached(0.06, &aligned, Some(&ARRIVAL_TOLERANCE)));
+        assert!(!is_target_reached(0.04, &turned, Some(&ARRIVAL_TOLERANCE)));
+        assert!(!is_target_reached(0.0, &aligned, None));
+    }
+
+    #[test]
+    fn facing_targets_count_as_reached_when_faced() {
+        let ahead = OrientationMode::FaceTowards(point![1.0, 0.05]);
+        let to_the_left = OrientationMode::FaceTowards(point![0.0, 1.0]);
+
+        assert!(is_target_reached(0.04, &ahead, Some(&ARRIVAL_TOLERANCE)));
+        assert!(!is_target_reached(
+            0.04,
+            &to_the_left,
+            Some(&ARRIVAL_TOLERANCE)
+        ));
+    }
+
+    #[test]
+    fn speed_profile_slows_down_close_to_the_target() {
+        let speed_profile = SpeedProfile {
+            cruise_fraction: 0.8,
+            creep_fraction: 0.2,
+            slow_down_radius: 0.5,
+        };
+        let positioning_gait = PositioningGaitActivation::default();
+
+        let factor = |distance_to_target, speed_profile| {
+            step_size_factor(
+                distance_to_target,
+                speed_profile,
+                WalkGait::Normal,
+                &positioning_gait,
+            )
+        };
+        assert_eq!(factor(1.0, Some(&speed_profile)), 0.8);
+        assert_eq!(factor(0.3, Some(&speed_profile)), 0.2);
+        assert_eq!(factor(0.3, None), 1.0);
+    }
+
+    #[test]
+    fn positioning_gait_is_selected_close_to_target_or_ball() {
+        let activation = PositioningGaitActivation {
+            distance_to_target: 0.3,
+            distance_to_ball: 0.5,
+            step_size_factor: 0.5,
+        };
+        let ball_at = |position| BallPosition {
+            position,
+            velocity: Vector2::zeros(),
+            last_seen: UNIX_EPOCH,
+            confidence: 1.0,
+        };
+
+        assert_eq!(select_walk_gait(1.0, None, &activation), WalkGait::Normal);
+        assert_eq!(
+            select_walk_gait(0.2, None, &activation),
+            WalkGait::Positioning
+        );
+        assert_eq!(
+            select_walk_gait(1.0, Some(&ball_at(point![0.4, 0.0])), &activation),
+            WalkGait::Positioning
+        );
+        assert_eq!(
+            select_walk_gait(1.0, Some(&ball_at(point![2.0, 0.0])), &activation),
+            WalkGait::Normal
+        );
+        assert_eq!(
+            step_size_factor(0.2, None, WalkGait::Positioning, &activation),
+            0.5
+        );
+    }
+}
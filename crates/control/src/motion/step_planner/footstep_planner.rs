@@ -0,0 +1,256 @@
+use nalgebra::{vector, Isometry2, Point2, UnitComplex, Vector2};
+use types::{
+    point_at_distance,
+    units::{Meters, Radians},
+    Obstacle, OrientationMode, PathSegment, Side, Step,
+};
+
+use super::clamp_step_to_walk_volume;
+
+pub struct FootstepPlanner<'cycle> {
+    pub max_step_size: &'cycle Step,
+    pub max_step_size_backwards: Meters,
+    pub translation_exponent: f32,
+    pub rotation_exponent: f32,
+    pub lookahead_distance: f32,
+    pub obstacle_clearance: f32,
+    pub obstacles: &'cycle [Obstacle],
+}
+
+impl FootstepPlanner<'_> {
+    /// Plans footsteps along the path, each relative to the robot pose after the previous one
+    pub fn plan(
+        &self,
+        path: &[PathSegment],
+        orientation_mode: OrientationMode,
+        first_swing_side: Side,
+        number_of_steps: usize,
+    ) -> Vec<Step> {
+        let path_length: f32 = path.iter().map(PathSegment::length).sum();
+        let mut pose = Isometry2::identity();
+        let mut walked_distance = 0.0;
+        let mut swing_side = first_swing_side;
+
+        (0..number_of_steps)
+            .map(|_| {
+                let target_distance = (walked_distance + self.lookahead_distance).min(path_length);
+                let target = pose.inverse() * point_at_distance(path, target_distance);
+                let orientation = match orientation_mode {
+                    OrientationMode::AlignWithPath
+                        if target.coords.norm_squared() < f32::EPSILON =>
+                    {
+                        UnitComplex::identity()
+                    }
+                    OrientationMode::AlignWithPath => {
+                        UnitComplex::rotation_between(&Vector2::x(), &target.coords)
+                    }
+                    OrientationMode::Override(orientation) => pose.rotation.inverse() * orientation,
+                    OrientationMode::FaceTowards(facing_target) => {
+                        let facing_target = pose.inverse() * facing_target;
+                        UnitComplex::rotation_between(&Vector2::x(), &facing_target.coords)
+                    }
+                };
+
+                let step = clamp_step_to_walk_volume(
+                    Step {
+                        forward: Meters(target.x),
+                        left: Meters(target.y),
+                        turn: Radians(orientation.angle()),
+                    },
+                    self.max_step_size,
+                    self.max_step_size_backwards,
+                    self.translation_exponent,
+                    self.rotation_exponent,
+                );
+                let step = avoid_self_collision(step, swing_side);
+                let step = self.clip_to_obstacle_clearance(step, pose);
+
+                let translation = vector![step.forward.0, step.left.0];
+                walked_distance += translation.norm();
+                pose *= Isometry2::new(translation, step.turn.0);
+                swing_side = swing_side.opposite();
+                step
+            })
+            .collect()
+    }
+
+    fn clip_to_obstacle_clearance(&self, step: Step, pose: Isometry2<f32>) -> Step {
+        let start = Point2::from(pose.translation.vector);
+        let translation = pose.rotation * vector![step.forward.0, step.left.0];
+        let fraction = self
+            .obstacles
+            .iter()
+            .map(|obstacle| {
+                reachable_fraction(
+                    start,
+                    translation,
+                    obstacle.position,
+                    obstacle.radius_at_foot_height + self.obstacle_clearance,
+                )
+            })
+            .fold(1.0, f32::min);
+        Step {
+            forward: step.forward * fraction,
+            left: step.left * fraction,
+            turn: step.turn,
+        }
+    }
+}
+
+/// The swing foot cannot step sideways into the support foot
+fn avoid_self_collision(step: Step, swing_side: Side) -> Step {
+    let moves_towards_support_foot = match swing_side {
+        Side::Left => step.left < Meters(0.0),
+        Side::Right => step.left > Meters(0.0),
+    };
+    if moves_towards_support_foot {
+        Step {
+            left: Meters(0.0),
+            ..step
+        }
+    } else {
+        step
+    }
+}
+
+/// Fraction of `translation` walkable from `start` before coming closer than `distance` to `center`
+fn reachable_fraction(
+    start: Point2<f32>,
+    translation: Vector2<f32>,
+    center: Point2<f32>,
+    distance: f32,
+) -> f32 {
+    let center_to_start = start - center;
+    let a = translation.norm_squared();
+    let b = 2.0 * center_to_start.dot(&translation);
+    let c = center_to_start.norm_squared() - distance.powi(2);
+    let is_approaching = a > f32::EPSILON && b < 0.0;
+    if !is_approaching {
+        return 1.0;
+    }
+    if c <= 0.0 {
+        return 0.0;
+    }
+    let discriminant = b.powi(2) - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return 1.0;
+    }
+    ((-b - discriminant.sqrt()) / (2.0 * a)).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+    use types::{Arc, Circle, LineSegment, Orientation};
+
+    use super::*;
+
+    const MAX_STEP_SIZE: Step = Step {
+        forward: Meters(0.055),
+        left: Meters(0.14),
+        turn: Radians(1.2),
+    };
+
+    fn planner(obstacles: &[Obstacle]) -> FootstepPlanner {
+        FootstepPlanner {
+            max_step_size: &MAX_STEP_SIZE,
+            max_step_size_backwards: Meters(0.04),
+            translation_exponent: 1.5,
+            rotation_exponent: 2.0,
+            lookahead_distance: 0.3,
+            obstacle_clearance: 0.03,
+            obstacles,
+        }
+    }
+
+    #[test]
+    fn straight_path_is_walked_with_full_steps() {
+        let path = [PathSegment::LineSegment(LineSegment(
+            Point2::origin(),
+            point![1.0, 0.0],
+        ))];
+
+        let footsteps = planner(&[]).plan(&path, OrientationMode::AlignWithPath, Side::Left, 4);
+
+        assert_eq!(footsteps.len(), 4);
+        for step in footsteps {
+            assert_relative_eq!(step.forward.0, 0.055, epsilon = 0.001);
+            assert_relative_eq!(step.left.0, 0.0, epsilon = 0.001);
+            assert_relative_eq!(step.turn.0, 0.0, epsilon = 0.001);
+        }
+    }
+
+    #[test]
+    fn sideways_steps_alternate_with_swing_side() {
+        let path = [PathSegment::LineSegment(LineSegment(
+            Point2::origin(),
+            point![0.0, -1.0],
+        ))];
+        let orientation_mode = OrientationMode::Override(UnitComplex::identity());
+
+        let footsteps = planner(&[]).plan(&path, orientation_mode, Side::Left, 2);
+
+        assert_relative_eq!(footsteps[0].left.0, 0.0);
+        assert!(footsteps[1].left < Meters(0.0));
+    }
+
+    #[test]
+    fn steps_turn_towards_the_facing_target() {
+        let path = [PathSegment::LineSegment(LineSegment(
+            Point2::origin(),
+            point![1.0, 0.0],
+        ))];
+
+        let footsteps_ahead = planner(&[]).plan(
+            &path,
+            OrientationMode::FaceTowards(point![2.0, 0.0]),
+            Side::Left,
+            2,
+        );
+        let footsteps_to_the_left = planner(&[]).plan(
+            &path,
+            OrientationMode::FaceTowards(point![0.0, 1.0]),
+            Side::Left,
+            2,
+        );
+
+        for step in footsteps_ahead {
+            assert_relative_eq!(step.turn.0, 0.0, epsilon = 0.001);
+        }
+        assert_relative_eq!(footsteps_to_the_left[0].turn.0, 1.2, epsilon = 0.001);
+        assert!(footsteps_to_the_left[1].turn.0 > 0.0);
+    }
+
+    #[test]
+    fn steps_keep_clearance_to_obstacles() {
+        let obstacles = [Obstacle::robot(point![0.1, 0.0], 0.02, 0.02)];
+        let path = [PathSegment::LineSegment(LineSegment(
+            Point2::origin(),
+            point![1.0, 0.0],
+        ))];
+
+        let footsteps =
+            planner(&obstacles).plan(&path, OrientationMode::AlignWithPath, Side::Left, 2);
+
+        assert_relative_eq!(footsteps[0].forward.0, 0.05, epsilon = 0.001);
+        assert_relative_eq!(footsteps[1].forward.0, 0.0, epsilon = 0.001);
+    }
+
+    #[test]
+    fn points_on_arcs_follow_orientation() {
+        let arc = Arc::new(
+            Circle::new(Point2::origin(), 1.0),
+            point![1.0, 0.0],
+            point![0.0, -1.0],
+        );
+        let path = [PathSegment::Arc(arc, Orientation::Clockwise)];
+
+        assert_relative_eq!(
+            point_at_distance(&path, std::f32::consts::FRAC_PI_2),
+            point![0.0, -1.0],
+            epsilon = 0.001
+        );
+        assert_relative_eq!(point_at_distance(&path, 10.0), point![0.0, -1.0]);
+    }
+}
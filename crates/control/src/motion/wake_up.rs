@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::MainOutput;
+use hardware::PathsInterface;
+use motionfile::{MotionFile, MotionInterpolator};
+use types::{
+    parameters::WakeUp as WakeUpParameters, ConditionInput, CycleTime, Joints, JointsCommand,
+    MotionSafeExits, MotionSelection, MotionType, SensorData,
+};
+
+/// Brings an unstiff robot from sitting back to standing
+///
+/// The stiffness is ramped up while holding the current positions first, such that joints far
+/// from their last command do not jerk. The robot then stands up from sitting starting at its
+/// current positions.
+pub struct WakeUp {
+    interpolator: MotionInterpolator<Joints<f32>>,
+    start_positions: Option<Joints<f32>>,
+    time_since_start: Duration,
+}
+
+#[context]
+pub struct CreationContext {
+    pub hardware_interface: HardwareInterface,
+}
+
+#[context]
+pub struct CycleContext {
+    pub condition_input: Input<ConditionInput, "condition_input">,
+    pub cycle_time: Input<CycleTime, "cycle_time">,
+    pub motion_selection: Input<MotionSelection, "motion_selection">,
+    pub sensor_data: Input<SensorData, "sensor_data">,
+
+    pub parameters: Parameter<WakeUpParameters, "wake_up">,
+
+    pub motion_safe_exits: PersistentState<MotionSafeExits, "motion_safe_exits">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub wake_up_joints_command: MainOutput<JointsCommand<f32>>,
+}
+
+impl WakeUp {
+    pub fn new(context: CreationContext<impl PathsInterface>) -> Result<Self> {
+        let paths = context.hardware_interface.get_paths();
+        Ok(Self {
+            interpolator: MotionFile::from_path(paths.motions.join("wake_up.json"))?.try_into()?,
+            start_positions: None,
+            time_since_start: Duration::ZERO,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        if context.motion_selection.current_motion != MotionType::WakeUp {
+            self.interpolator.reset();
+            self.start_positions = None;
+            self.time_since_start = Duration::ZERO;
+            context.motion_safe_exits[MotionType::WakeUp] = false;
+            return Ok(MainOutputs::default());
+        }
+
+        let start_positions = *self.start_positions.get_or_insert_with(|| {
+            self.interpolator
+                .set_initial_positions(context.sensor_data.positions);
+            context.sensor_data.positions
+        });
+        self.time_since_start += context.cycle_time.last_cycle_duration;
+
+        let stiffness_factor = ramp_progress(
+            self.time_since_start,
+            context.parameters.stiffness_ramp_duration,
+        );
+        let positions = if stiffness_factor < 1.0 {
+            start_positions
+        } else {
+            self.interpolator.advance_by(
+                context.cycle_time.last_cycle_duration,
+                context.condition_input,
+            );
+            self.interpolator.value()
+        };
+
+        context.motion_safe_exits[MotionType::WakeUp] = self.interpolator.is_finished();
+
+        Ok(MainOutputs {
+            wake_up_joints_command: JointsCommand {
+                positions,
+                stiffnesses: Joints::fill(context.parameters.stiffness * stiffness_factor),
+            }
+            .into(),
+        })
+    }
+}
+
+fn ramp_progress(time_since_start: Duration, ramp_duration: Duration) -> f32 {
+    if ramp_duration.is_zero() {
+        return 1.0;
+    }
+    (time_since_start.as_secs_f32() / ramp_duration.as_secs_f32()).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stiffness_is_ramped_up_linearly() {
+        let ramp_duration = Duration::from_millis(500);
+
+        assert_eq!(ramp_progress(Duration::ZERO, ramp_duration), 0.0);
+        assert_eq!(
+            ramp_progress(Duration::from_millis(250), ramp_duration),
+            0.5
+        );
+        assert_eq!(ramp_progress(Duration::from_secs(1), ramp_duration), 1.0);
+        assert_eq!(ramp_progress(Duration::ZERO, Duration::ZERO), 1.0);
+    }
+}
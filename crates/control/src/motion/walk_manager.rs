@@ -10,6 +10,8 @@ pub struct CreationContext {}
 
 #[context]
 pub struct CycleContext {
+    pub last_walk_command: PersistentState<WalkCommand, "last_walk_command">,
+
     pub motion_command: Input<MotionCommand, "motion_command">,
     pub motion_selection: Input<MotionSelection, "motion_selection">,
     pub step_plan: Input<Step, "step_plan">,
@@ -43,6 +45,7 @@ impl WalkManager {
             ) => WalkCommand::Kick(*kick, *kicking_side, *strength),
             _ => WalkCommand::Stand,
         };
+        *context.last_walk_command = command;
 
         Ok(MainOutputs {
             walk_command: command.into(),
@@ -9,15 +9,16 @@ use nalgebra::{Isometry3, Point3, Vector2, Vector3};
 use serde::{Deserialize, Serialize};
 use types::{
     parameters::{KickSteps, WalkingEngine as WalkingEngineParameters},
+    units::{Meters, Radians},
     ArmJoints, BodyJoints, BodyJointsCommand, CycleTime, InertialMeasurementUnitData, Joints,
     KickVariant, LegJoints, MotionCommand, MotionSafeExits, MotionType, RobotKinematics,
-    SensorData, Side, Step, StepAdjustment, WalkCommand,
+    SensorData, Side, Step, StepAdjustment, WalkCommand, WalkGait,
 };
 
 use self::{
     arms::SwingingArm,
     balancing::{step_adjustment, support_leg_gyro_balancing, swing_leg_foot_leveling},
-    engine::{calculate_foot_to_robot, parabolic_return, parabolic_step},
+    engine::{calculate_foot_to_robot, parabolic_return, parabolic_step, swing_time},
     foot_offsets::FootOffsets,
     kicking::apply_joint_overrides,
     walk_state::WalkState,
@@ -74,6 +75,8 @@ pub struct WalkingEngine {
     t_on_last_phase_end: Duration,
     /// The duration the currently executed step is planned to take
     planned_step_duration: Duration,
+    /// Fraction of the currently executed step both feet stay on the ground
+    double_support_ratio: f32,
     /// Fix the side of the swing foot for an entire walk phase
     swing_side: Side,
     /// Low pass filter the gyro for balance adjustment
@@ -130,6 +133,8 @@ pub struct CycleContext {
 
     pub motion_safe_exits: PersistentState<MotionSafeExits, "motion_safe_exits">,
     pub walk_return_offset: PersistentState<Step, "walk_return_offset">,
+    pub walk_swing_side: PersistentState<Option<Side>, "walk_swing_side">,
+    pub walk_support_side: PersistentState<Option<Side>, "walk_support_side">,
 
     pub motion_command: Input<MotionCommand, "motion_command">,
     pub robot_kinematics: Input<RobotKinematics, "robot_kinematics">,
@@ -137,6 +142,7 @@ pub struct CycleContext {
     pub cycle_time: Input<CycleTime, "cycle_time">,
     pub has_ground_contact: Input<bool, "has_ground_contact">,
     pub walk_command: Input<WalkCommand, "walk_command">,
+    pub walk_gait: Input<WalkGait, "walk_gait">,
 }
 
 #[context]
@@ -187,6 +193,7 @@ impl WalkingEngine {
             if is_step_started_this_cycle {
                 self.initialize_step_states_from_request(
                     *context.walk_command,
+                    *context.walk_gait,
                     self.swing_side,
                     context.config,
                     context.kick_steps,
@@ -252,11 +259,11 @@ impl WalkingEngine {
                 .torso_tilt_compensation(&context.config.swinging_arms)?;
 
         let (mut left_leg, mut right_leg) = self.calculate_leg_joints(
-            context.config.torso_shift_offset,
-            context.config.walk_hip_height,
+            context.config.torso_shift_offset.0,
+            context.config.walk_hip_height.0,
         );
-        left_leg.hip_pitch += arm_compensation - context.config.torso_tilt_offset;
-        right_leg.hip_pitch += arm_compensation - context.config.torso_tilt_offset;
+        left_leg.hip_pitch += arm_compensation - context.config.torso_tilt_offset.0;
+        right_leg.hip_pitch += arm_compensation - context.config.torso_tilt_offset.0;
 
         if let WalkState::Kicking(kick_variant, _, kick_step_i, strength) = self.walk_state {
             let swing_leg = match self.swing_side {
@@ -320,17 +327,35 @@ impl WalkingEngine {
 
         *context.walk_return_offset = match self.swing_side {
             Side::Left => Step {
-                forward: self.left_foot.forward,
-                left: self.left_foot.left,
-                turn: self.turn,
+                forward: Meters(self.left_foot.forward),
+                left: Meters(self.left_foot.left),
+                turn: Radians(self.turn),
             },
             Side::Right => Step {
-                forward: self.right_foot.forward,
-                left: self.right_foot.left,
-                turn: self.turn,
+                forward: Meters(self.right_foot.forward),
+                left: Meters(self.right_foot.left),
+                turn: Radians(self.turn),
             },
         };
 
+        *context.walk_swing_side = match self.walk_state {
+            WalkState::Standing => None,
+            WalkState::Starting(_)
+            | WalkState::Walking(_)
+            | WalkState::Kicking(..)
+            | WalkState::Stopping => Some(self.swing_side),
+        };
+        // the swing side is only swapped at the start of the next step, but the foot that has
+        // just landed already carries the robot from the next cycle on
+        let has_step_phase_ended = self.t.is_zero();
+        *context.walk_support_side = context.walk_swing_side.map(|swing_side| {
+            if has_step_phase_ended {
+                swing_side
+            } else {
+                swing_side.opposite()
+            }
+        });
+
         context.motion_safe_exits[MotionType::Walk] =
             matches!(self.walk_state, WalkState::Standing);
 
@@ -383,6 +408,7 @@ impl WalkingEngine {
     fn initialize_step_states_from_request(
         &mut self,
         walk_command: WalkCommand,
+        walk_gait: WalkGait,
         swing_side: Side,
         config: &WalkingEngineParameters,
         kick_steps: &KickSteps,
@@ -390,6 +416,7 @@ impl WalkingEngine {
         self.left_foot_t0 = self.left_foot;
         self.right_foot_t0 = self.right_foot;
         self.turn_t0 = self.turn;
+        self.double_support_ratio = 0.0;
         self.walk_state =
             self.walk_state
                 .next_walk_state(walk_command, self.swing_side, kick_steps);
@@ -398,7 +425,7 @@ impl WalkingEngine {
             self.current_step = config.emergency_step;
             self.planned_step_duration = config.emergency_step_duration;
             self.swing_side = swing_side;
-            self.max_swing_foot_lift = config.emergency_foot_lift;
+            self.max_swing_foot_lift = config.emergency_foot_lift.0;
             self.number_of_timeouted_steps = 0;
             return;
         }
@@ -412,7 +439,7 @@ impl WalkingEngine {
             self.current_step = Step::zero();
             self.planned_step_duration = config.base_step_duration;
             self.swing_side = swing_side.opposite();
-            self.max_swing_foot_lift = config.base_foot_lift;
+            self.max_swing_foot_lift = config.base_foot_lift.0;
             return;
         }
 
@@ -428,7 +455,7 @@ impl WalkingEngine {
                 self.current_step = Step::zero();
                 self.planned_step_duration = config.starting_step_duration;
                 self.swing_side = swing_side.opposite();
-                self.max_swing_foot_lift = config.starting_step_foot_lift;
+                self.max_swing_foot_lift = config.starting_step_foot_lift.0;
             }
             WalkState::Walking(requested_step) => {
                 let next_support_side = swing_side;
@@ -444,26 +471,25 @@ impl WalkingEngine {
                         + forward_acceleration.min(config.max_forward_acceleration),
                     ..requested_step
                 };
-                let absolute_next_step = Step {
-                    forward: requested_step.forward.abs(),
-                    left: requested_step.left.abs(),
-                    turn: requested_step.turn.abs(),
-                };
-
-                let step_duration_increase = absolute_next_step * config.step_duration_increase;
-                let duration_increase = Duration::from_secs_f32(step_duration_increase.sum());
+                let duration_increase = Duration::from_secs_f32(
+                    config.step_duration_increase.weighted_sum(requested_step),
+                );
                 self.planned_step_duration = config.base_step_duration + duration_increase;
+                if walk_gait == WalkGait::Positioning {
+                    self.planned_step_duration += config.positioning_gait.additional_step_duration;
+                    self.double_support_ratio = config.positioning_gait.double_support_ratio;
+                }
 
                 self.swing_side = next_swing_side;
 
-                let step_foot_lift_increase = absolute_next_step * config.step_foot_lift_increase;
-                self.max_swing_foot_lift = config.base_foot_lift + step_foot_lift_increase.sum();
+                self.max_swing_foot_lift = config.base_foot_lift.0
+                    + config.step_foot_lift_increase.weighted_sum(requested_step);
             }
             WalkState::Stopping => {
                 self.current_step = Step::zero();
                 self.planned_step_duration = config.base_step_duration;
                 self.swing_side = swing_side.opposite();
-                self.max_swing_foot_lift = config.base_foot_lift;
+                self.max_swing_foot_lift = config.base_foot_lift.0;
             }
             WalkState::Kicking(kick_variant, kick_side, kick_step_i, _) => {
                 let kick_steps = match kick_variant {
@@ -478,7 +504,8 @@ impl WalkingEngine {
                 };
                 self.planned_step_duration = config.base_step_duration;
                 self.swing_side = swing_side.opposite();
-                self.max_swing_foot_lift = config.base_foot_lift + config.additional_kick_foot_lift;
+                self.max_swing_foot_lift =
+                    config.base_foot_lift.0 + config.additional_kick_foot_lift.0;
             }
         }
     }
@@ -498,6 +525,7 @@ impl WalkingEngine {
         self.t = Duration::ZERO;
         self.t_on_last_phase_end = Duration::ZERO;
         self.planned_step_duration = Duration::ZERO;
+        self.double_support_ratio = 0.0;
         self.swing_side = Side::Left;
         self.filtered_gyro.reset(Vector2::default());
         self.filtered_imu_pitch.reset(0.0);
@@ -549,26 +577,27 @@ impl WalkingEngine {
     ) -> (FootOffsets, FootOffsets, f32, f32, f32) {
         let linear_time =
             (self.t.as_secs_f32() / self.planned_step_duration.as_secs_f32()).clamp(0.0, 1.0);
-        let parabolic_time = parabolic_step(linear_time);
+        let swing_foot_time = swing_time(linear_time, self.double_support_ratio);
+        let parabolic_time = parabolic_step(swing_foot_time);
 
         let support_foot = FootOffsets {
             forward: support_foot_t0.forward
-                + (-planned_step.forward / 2.0 - support_foot_t0.forward) * linear_time,
+                + (-planned_step.forward.0 / 2.0 - support_foot_t0.forward) * linear_time,
             left: support_foot_t0.left
-                + (-planned_step.left / 2.0 - support_foot_t0.left) * linear_time,
+                + (-planned_step.left.0 / 2.0 - support_foot_t0.left) * linear_time,
         };
 
         let swing_foot = FootOffsets {
             forward: swing_foot_t0.forward
-                + (planned_step.forward / 2.0 - swing_foot_t0.forward) * parabolic_time,
+                + (planned_step.forward.0 / 2.0 - swing_foot_t0.forward) * parabolic_time,
             left: swing_foot_t0.left
-                + (planned_step.left / 2.0 - swing_foot_t0.left) * parabolic_time,
+                + (planned_step.left.0 / 2.0 - swing_foot_t0.left) * parabolic_time,
         };
 
         let turn_left_right = if self.swing_side == Side::Left {
-            planned_step.turn
+            planned_step.turn.0
         } else {
-            -1.0 * planned_step.turn
+            -1.0 * planned_step.turn.0
         };
         let turn = self.turn_t0 + (turn_left_right / 2.0 - self.turn_t0) * linear_time;
 
@@ -578,7 +607,7 @@ impl WalkingEngine {
                     + linear_time)
                     .clamp(0.0, 1.0),
             );
-        let swing_foot_lift = self.max_swing_foot_lift * parabolic_return(linear_time);
+        let swing_foot_lift = self.max_swing_foot_lift * parabolic_return(swing_foot_time);
 
         (
             support_foot,
@@ -628,14 +657,14 @@ impl WalkingEngine {
             next_right_walk_request,
             self.last_left_walk_request,
             self.last_right_walk_request,
-            config.forward_foot_support_offset,
-            config.backward_foot_support_offset,
-            config.max_step_adjustment,
+            config.forward_foot_support_offset.0,
+            config.backward_foot_support_offset.0,
+            config.max_step_adjustment.0,
             step_adjustment_output,
             next_left_foot_lift,
             next_right_foot_lift,
             config.stabilization_foot_lift_multiplier,
-            config.stabilization_foot_lift_offset,
+            config.stabilization_foot_lift_offset.0,
             config.stabilization_hysteresis,
             self.remaining_stabilizing_steps,
             &mut self.forward_adjustment_was_active,
@@ -735,17 +764,17 @@ fn clamp_to_anatomic_constraints(
     support_side: Side,
     inside_turn_ratio: f32,
 ) -> Step {
-    let sideways_direction = if request.left.is_sign_positive() {
+    let sideways_direction = if request.left.0.is_sign_positive() {
         Side::Left
     } else {
         Side::Right
     };
     let clamped_left = if sideways_direction == support_side {
-        0.0
+        Meters(0.0)
     } else {
         request.left
     };
-    let turn_direction = if request.turn.is_sign_positive() {
+    let turn_direction = if request.turn.0.is_sign_positive() {
         Side::Left
     } else {
         Side::Right
@@ -755,7 +784,7 @@ fn clamp_to_anatomic_constraints(
     } else {
         1.0 - inside_turn_ratio
     };
-    let clamped_turn = turn_ratio * request.turn;
+    let clamped_turn = request.turn * turn_ratio;
     Step {
         forward: request.forward,
         left: clamped_left,
@@ -20,18 +20,27 @@ enum State {
     #[default]
     Swing,
     PullingBack {
+        #[serialize_hierarchy(leaf)]
         interpolator: SplineInterpolator<ArmJoints<f32>>,
     },
     PullingTight {
+        #[serialize_hierarchy(leaf)]
         interpolator: SplineInterpolator<ArmJoints<f32>>,
     },
     Back,
     ReleasingTight {
+        #[serialize_hierarchy(leaf)]
         interpolator: SplineInterpolator<ArmJoints<f32>>,
     },
     ReleasingBack {
+        #[serialize_hierarchy(leaf)]
         interpolator: SplineInterpolator<ArmJoints<f32>>,
     },
+    MovingBehindBack {
+        #[serialize_hierarchy(leaf)]
+        interpolator: SplineInterpolator<ArmJoints<f32>>,
+    },
+    BehindBack,
 }
 
 impl SwingingArm {
@@ -59,10 +68,64 @@ impl SwingingArm {
             Side::Left => config.pull_tight_joints,
             Side::Right => config.pull_tight_joints.mirrored(),
         };
+        let behind_back_joints = match self.side {
+            Side::Left => config.behind_back_joints,
+            Side::Right => config.behind_back_joints.mirrored(),
+        };
         let swinging_arm_joints = self.swinging_arm_joints(foot, config);
         let center_arm_joints = self.swinging_arm_joints(FootOffsets::zero(), config);
+        let current_joints = joints_in_state(
+            &self.state,
+            swinging_arm_joints,
+            pull_tight_joints,
+            behind_back_joints,
+        );
 
         self.state = match (&mut self.state, requested_arm_motion) {
+            (
+                State::MovingBehindBack {
+                    ref mut interpolator,
+                },
+                ArmMotion::BehindBack,
+            ) => {
+                interpolator.advance_by(cycle_duration);
+                if interpolator.is_finished() {
+                    State::BehindBack
+                } else {
+                    State::MovingBehindBack {
+                        interpolator: interpolator.clone(),
+                    }
+                }
+            }
+            (State::BehindBack, ArmMotion::BehindBack) => State::BehindBack,
+            (_, ArmMotion::BehindBack) => State::MovingBehindBack {
+                interpolator: TimedSpline::try_new_transition_timed(
+                    current_joints,
+                    behind_back_joints,
+                    config.moving_behind_back_duration,
+                )?
+                .into(),
+            },
+            (State::MovingBehindBack { .. } | State::BehindBack, ArmMotion::Swing) => {
+                State::ReleasingBack {
+                    interpolator: TimedSpline::try_new_transition_timed(
+                        current_joints,
+                        center_arm_joints,
+                        config.moving_behind_back_duration,
+                    )?
+                    .into(),
+                }
+            }
+            (State::MovingBehindBack { .. } | State::BehindBack, ArmMotion::PullTight) => {
+                State::PullingBack {
+                    interpolator: TimedSpline::try_new_transition_timed(
+                        current_joints,
+                        pull_back_joints,
+                        config.pulling_back_duration,
+                    )?
+                    .into(),
+                }
+            }
             (State::Swing, ArmMotion::Swing) => State::Swing,
             (State::Swing, ArmMotion::PullTight) => State::PullingBack {
                 interpolator: TimedSpline::try_new_transition_timed(
@@ -199,14 +262,12 @@ impl SwingingArm {
                 }
             }
         };
-        Ok(match &self.state {
-            State::Swing => swinging_arm_joints,
-            State::PullingBack { interpolator }
-            | State::ReleasingBack { interpolator }
-            | State::ReleasingTight { interpolator }
-            | State::PullingTight { interpolator } => interpolator.value(),
-            State::Back => pull_tight_joints,
-        })
+        Ok(joints_in_state(
+            &self.state,
+            swinging_arm_joints,
+            pull_tight_joints,
+            behind_back_joints,
+        ))
     }
 
     pub fn torso_tilt_compensation(&self, config: &SwingingArms) -> Result<f32> {
@@ -215,8 +276,10 @@ impl SwingingArm {
             State::PullingBack { interpolator }
             | State::ReleasingBack { interpolator }
             | State::ReleasingTight { interpolator }
-            | State::PullingTight { interpolator } => interpolator.value().shoulder_pitch,
+            | State::PullingTight { interpolator }
+            | State::MovingBehindBack { interpolator } => interpolator.value().shoulder_pitch,
             State::Back => config.pull_tight_joints.shoulder_pitch,
+            State::BehindBack => config.behind_back_joints.shoulder_pitch,
         };
         Ok((shoulder_pitch - FRAC_PI_2) * config.torso_tilt_compensation_factor)
     }
@@ -259,3 +322,21 @@ impl SwingingArm {
         }
     }
 }
+
+fn joints_in_state(
+    state: &State,
+    swinging_arm_joints: ArmJoints<f32>,
+    pull_tight_joints: ArmJoints<f32>,
+    behind_back_joints: ArmJoints<f32>,
+) -> ArmJoints<f32> {
+    match state {
+        State::Swing => swinging_arm_joints,
+        State::PullingBack { interpolator }
+        | State::ReleasingBack { interpolator }
+        | State::ReleasingTight { interpolator }
+        | State::PullingTight { interpolator }
+        | State::MovingBehindBack { interpolator } => interpolator.value(),
+        State::Back => pull_tight_joints,
+        State::BehindBack => behind_back_joints,
+    }
+}
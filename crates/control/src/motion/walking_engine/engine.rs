@@ -40,6 +40,16 @@ pub fn parabolic_return(x: f32) -> f32 {
     8.0 * x * x
 }
 
+/// Maps the time of the step to the time of the swing foot movement, which is shortened by double
+/// support phases at the start and end of the step
+pub fn swing_time(linear_time: f32, double_support_ratio: f32) -> f32 {
+    let swing_duration = 1.0 - double_support_ratio;
+    if swing_duration <= 0.0 {
+        return if linear_time < 0.5 { 0.0 } else { 1.0 };
+    }
+    ((linear_time - double_support_ratio / 2.0) / swing_duration).clamp(0.0, 1.0)
+}
+
 pub fn parabolic_step(x: f32) -> f32 {
     if x < 0.5 {
         2.0 * x * x
@@ -51,3 +61,32 @@ pub fn parabolic_step(x: f32) -> f32 {
 pub fn non_continuous_quadratic_return(x: f32) -> f32 {
     -(x * x) + 1.0
 }
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn swing_time_is_linear_without_double_support() {
+        for linear_time in [0.0, 0.25, 0.5, 1.0] {
+            assert_relative_eq!(swing_time(linear_time, 0.0), linear_time);
+        }
+    }
+
+    #[test]
+    fn swing_foot_rests_during_double_support() {
+        assert_relative_eq!(swing_time(0.1, 0.4), 0.0);
+        assert_relative_eq!(swing_time(0.2, 0.4), 0.0);
+        assert_relative_eq!(swing_time(0.5, 0.4), 0.5);
+        assert_relative_eq!(swing_time(0.8, 0.4), 1.0);
+        assert_relative_eq!(swing_time(0.9, 0.4), 1.0);
+    }
+
+    #[test]
+    fn swing_foot_steps_at_once_with_full_double_support() {
+        assert_relative_eq!(swing_time(0.4, 1.0), 0.0);
+        assert_relative_eq!(swing_time(0.6, 1.0), 1.0);
+    }
+}
@@ -0,0 +1,233 @@
+use std::time::{Duration, SystemTime};
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::MainOutput;
+use log::warn;
+use types::{
+    parameters::{MotionTest, MotionTestRequest, TestMotion},
+    CycleTime, FallState, HeadMotion, MotionCommand,
+};
+
+/// Repeats a motion requested from the bench, standing before and after each repetition
+///
+/// The test is aborted as soon as the robot is lifted or falls unexpectedly. Behavior then takes
+/// over again, e.g. to fall safely.
+pub struct MotionTester {
+    requested_at: Option<SystemTime>,
+    phase: Phase,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Phase {
+    Stand {
+        since: SystemTime,
+        next_repetition: usize,
+    },
+    Motion {
+        since: SystemTime,
+        repetition: usize,
+        has_fallen: bool,
+    },
+    Done,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    pub cycle_time: Input<CycleTime, "cycle_time">,
+    pub fall_state: Input<FallState, "fall_state">,
+    pub has_ground_contact: Input<bool, "has_ground_contact">,
+
+    pub parameters: Parameter<MotionTest, "motion_test">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub motion_test_command: MainOutput<Option<MotionCommand>>,
+}
+
+impl MotionTester {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            requested_at: None,
+            phase: Phase::Done,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let Some(request) = context.parameters.request else {
+            self.requested_at = None;
+            self.phase = Phase::Done;
+            return Ok(MainOutputs::default());
+        };
+        if self.requested_at != Some(request.requested_at) {
+            self.requested_at = Some(request.requested_at);
+            self.phase = Phase::Stand {
+                since: now,
+                next_repetition: 0,
+            };
+        }
+
+        let is_safe = match self.phase {
+            Phase::Motion { .. } if request.motion == TestMotion::StandUp => true,
+            Phase::Stand { .. } | Phase::Motion { .. } => {
+                *context.has_ground_contact && matches!(context.fall_state, FallState::Upright)
+            }
+            Phase::Done => true,
+        };
+        if !is_safe {
+            warn!("Aborted motion test {:?}", request.motion);
+            self.phase = Phase::Done;
+        }
+
+        self.phase = next_phase(
+            self.phase,
+            &request,
+            *context.fall_state,
+            context.parameters,
+            now,
+        );
+        let command = motion_test_command(self.phase, request.motion, *context.fall_state);
+        Ok(MainOutputs {
+            motion_test_command: command.into(),
+        })
+    }
+}
+
+fn next_phase(
+    phase: Phase,
+    request: &MotionTestRequest,
+    fall_state: FallState,
+    parameters: &MotionTest,
+    now: SystemTime,
+) -> Phase {
+    let elapsed_since = |since| now.duration_since(since).unwrap_or(Duration::ZERO);
+    match phase {
+        Phase::Stand {
+            since,
+            next_repetition,
+        } if elapsed_since(since) >= parameters.stand_duration => {
+            if next_repetition < request.repetitions {
+                Phase::Motion {
+                    since: now,
+                    repetition: next_repetition,
+                    has_fallen: false,
+                }
+            } else {
+                Phase::Done
+            }
+        }
+        Phase::Motion {
+            since,
+            repetition,
+            has_fallen,
+        } => {
+            let has_fallen = has_fallen || matches!(fall_state, FallState::Fallen { .. });
+            let is_finished = match request.motion {
+                TestMotion::InWalkKick { .. } => elapsed_since(since) >= parameters.kick_duration,
+                TestMotion::StandUp => has_fallen && matches!(fall_state, FallState::Upright),
+            };
+            if is_finished {
+                Phase::Stand {
+                    since: now,
+                    next_repetition: repetition + 1,
+                }
+            } else {
+                Phase::Motion {
+                    since,
+                    repetition,
+                    has_fallen,
+                }
+            }
+        }
+        phase => phase,
+    }
+}
+
+fn motion_test_command(
+    phase: Phase,
+    motion: TestMotion,
+    fall_state: FallState,
+) -> Option<MotionCommand> {
+    let stand = MotionCommand::Stand {
+        head: HeadMotion::Center,
+        is_energy_saving: false,
+    };
+    match (phase, motion, fall_state) {
+        (Phase::Done, ..) => None,
+        (Phase::Stand { .. }, ..) => Some(stand),
+        (
+            Phase::Motion { .. },
+            TestMotion::InWalkKick {
+                kick,
+                kicking_side,
+                strength,
+            },
+            _,
+        ) => Some(MotionCommand::InWalkKick {
+            head: HeadMotion::Center,
+            kick,
+            kicking_side,
+            strength,
+        }),
+        (Phase::Motion { .. }, TestMotion::StandUp, FallState::Fallen { facing }) => {
+            Some(MotionCommand::StandUp { facing })
+        }
+        (Phase::Motion { .. }, TestMotion::StandUp, _) => Some(stand),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use types::{KickVariant, Side};
+
+    use super::*;
+
+    #[test]
+    fn kicks_are_repeated_with_stands_in_between() {
+        let parameters = MotionTest {
+            request: None,
+            stand_duration: Duration::from_secs(2),
+            kick_duration: Duration::from_secs(1),
+        };
+        let request = MotionTestRequest {
+            motion: TestMotion::InWalkKick {
+                kick: KickVariant::Forward,
+                kicking_side: Side::Left,
+                strength: 1.0,
+            },
+            repetitions: 2,
+            requested_at: UNIX_EPOCH,
+        };
+        let at = |seconds| UNIX_EPOCH + Duration::from_secs(seconds);
+        let mut phase = Phase::Stand {
+            since: at(0),
+            next_repetition: 0,
+        };
+        let mut phases = vec![];
+        for seconds in 1..=10 {
+            phase = next_phase(
+                phase,
+                &request,
+                FallState::Upright,
+                &parameters,
+                at(seconds),
+            );
+            phases.push(phase);
+        }
+
+        assert!(matches!(phases[0], Phase::Stand { .. }));
+        assert!(matches!(phases[1], Phase::Motion { repetition: 0, .. }));
+        assert!(matches!(phases[2], Phase::Stand { .. }));
+        assert!(matches!(phases[4], Phase::Motion { repetition: 1, .. }));
+        assert!(matches!(phases[5], Phase::Stand { .. }));
+        assert_eq!(phases[7], Phase::Done);
+    }
+}
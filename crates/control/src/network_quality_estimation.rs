@@ -0,0 +1,232 @@
+use std::time::{Duration, SystemTime};
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::{MainOutput, PerceptionInput};
+use spl_network_messages::HulkMessage;
+use types::{messages::IncomingMessage, CycleTime, NetworkQuality, Players, TeammateConnection};
+
+pub struct NetworkQualityEstimation {
+    teammates: Players<Option<TeammateConnection>>,
+    last_game_controller_message: Option<SystemTime>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    pub cycle_time: Input<CycleTime, "cycle_time">,
+    pub network_message: PerceptionInput<IncomingMessage, "SplNetwork", "message">,
+
+    pub inter_arrival_smoothing_factor:
+        Parameter<f32, "network_quality.inter_arrival_smoothing_factor">,
+    pub maximum_packet_loss: Parameter<f32, "network_quality.maximum_packet_loss">,
+    pub packet_loss_smoothing_factor:
+        Parameter<f32, "network_quality.packet_loss_smoothing_factor">,
+    pub stale_teammate_timeout: Parameter<Duration, "network_quality.stale_teammate_timeout">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub network_quality: MainOutput<NetworkQuality>,
+}
+
+impl NetworkQualityEstimation {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            teammates: Default::default(),
+            last_game_controller_message: None,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        for (&received_at, messages) in context.network_message.persistent.iter() {
+            for message in messages {
+                match message {
+                    IncomingMessage::GameController(_) => {
+                        self.last_game_controller_message = Some(received_at);
+                    }
+                    IncomingMessage::Spl(message) => {
+                        let connection = &mut self.teammates[message.player_number];
+                        *connection = Some(update_connection(
+                            *connection,
+                            message,
+                            received_at,
+                            *context.inter_arrival_smoothing_factor,
+                            *context.packet_loss_smoothing_factor,
+                        ));
+                    }
+                }
+            }
+        }
+
+        let now = context.cycle_time.start_time;
+        for connection in [
+            &mut self.teammates.one,
+            &mut self.teammates.two,
+            &mut self.teammates.three,
+            &mut self.teammates.four,
+            &mut self.teammates.five,
+            &mut self.teammates.six,
+            &mut self.teammates.seven,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            let is_stale = now
+                .duration_since(connection.last_received)
+                .map_or(false, |age| age > *context.stale_teammate_timeout);
+            connection.is_reliable =
+                !is_stale && connection.packet_loss <= *context.maximum_packet_loss;
+        }
+
+        let game_controller_message_age = self
+            .last_game_controller_message
+            .and_then(|last_message| now.duration_since(last_message).ok());
+
+        Ok(MainOutputs {
+            network_quality: NetworkQuality {
+                teammates: self.teammates,
+                game_controller_message_age,
+            }
+            .into(),
+        })
+    }
+}
+
+fn update_connection(
+    connection: Option<TeammateConnection>,
+    message: &HulkMessage,
+    received_at: SystemTime,
+    inter_arrival_smoothing_factor: f32,
+    packet_loss_smoothing_factor: f32,
+) -> TeammateConnection {
+    let Some(connection) = connection else {
+        return TeammateConnection {
+            last_received: received_at,
+            last_sequence_number: message.sequence_number,
+            mean_inter_arrival_time: None,
+            packet_loss: 0.0,
+            is_reliable: true,
+        };
+    };
+
+    let inter_arrival_time = received_at
+        .duration_since(connection.last_received)
+        .unwrap_or_default();
+    let mean_inter_arrival_time = match connection.mean_inter_arrival_time {
+        Some(mean) => {
+            mean.mul_f32(1.0 - inter_arrival_smoothing_factor)
+                + inter_arrival_time.mul_f32(inter_arrival_smoothing_factor)
+        }
+        None => inter_arrival_time,
+    };
+
+    // every message skipped in the sequence counts as a lost packet, duplicates, reordered and
+    // unnumbered messages are not counted
+    let sequence_gap = message
+        .sequence_number
+        .zip(connection.last_sequence_number)
+        .map(|(sequence_number, last_sequence_number)| {
+            sequence_number.wrapping_sub(last_sequence_number)
+        });
+    let is_reordered = sequence_gap.map_or(false, |sequence_gap| sequence_gap > u16::MAX / 2);
+    let lost_packets = match sequence_gap {
+        Some(sequence_gap) if sequence_gap != 0 && !is_reordered => sequence_gap - 1,
+        _ => 0,
+    };
+    let mut packet_loss = connection.packet_loss;
+    for _ in 0..lost_packets.min(100) {
+        packet_loss += (1.0 - packet_loss) * packet_loss_smoothing_factor;
+    }
+    packet_loss -= packet_loss * packet_loss_smoothing_factor;
+
+    TeammateConnection {
+        last_received: received_at,
+        last_sequence_number: if is_reordered {
+            connection.last_sequence_number
+        } else {
+            message.sequence_number.or(connection.last_sequence_number)
+        },
+        mean_inter_arrival_time: Some(mean_inter_arrival_time),
+        packet_loss,
+        is_reliable: connection.is_reliable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn message(sequence_number: Option<u16>) -> HulkMessage {
+        HulkMessage {
+            sequence_number,
+            ..Default::default()
+        }
+    }
+
+    fn receive(
+        connection: Option<TeammateConnection>,
+        sequence_number: Option<u16>,
+        received_at_seconds: f32,
+    ) -> TeammateConnection {
+        update_connection(
+            connection,
+            &message(sequence_number),
+            UNIX_EPOCH + Duration::from_secs_f32(received_at_seconds),
+            0.5,
+            0.5,
+        )
+    }
+
+    #[test]
+    fn skipped_sequence_numbers_count_as_lost_packets() {
+        let connection = receive(None, Some(1), 1.0);
+        assert_relative_eq!(connection.packet_loss, 0.0);
+
+        let connection = receive(Some(connection), Some(3), 2.0);
+        assert_relative_eq!(connection.packet_loss, 0.25);
+
+        let connection = receive(Some(connection), Some(4), 3.0);
+        assert_relative_eq!(connection.packet_loss, 0.125);
+    }
+
+    #[test]
+    fn reordered_and_unnumbered_messages_are_not_counted_as_lost() {
+        let connection = receive(None, Some(10), 1.0);
+        let connection = receive(Some(connection), Some(8), 2.0);
+        assert_relative_eq!(connection.packet_loss, 0.0);
+        assert_eq!(connection.last_sequence_number, Some(10));
+
+        let connection = receive(Some(connection), None, 3.0);
+        assert_relative_eq!(connection.packet_loss, 0.0);
+        assert_eq!(connection.last_sequence_number, Some(10));
+
+        let connection = receive(Some(connection), Some(11), 4.0);
+        assert_relative_eq!(connection.packet_loss, 0.0);
+    }
+
+    #[test]
+    fn inter_arrival_time_is_smoothed() {
+        let connection = receive(None, Some(1), 1.0);
+        assert_eq!(connection.mean_inter_arrival_time, None);
+
+        let connection = receive(Some(connection), Some(2), 2.0);
+        assert_relative_eq!(
+            connection.mean_inter_arrival_time.unwrap().as_secs_f32(),
+            1.0
+        );
+
+        let connection = receive(Some(connection), Some(3), 5.0);
+        assert_relative_eq!(
+            connection.mean_inter_arrival_time.unwrap().as_secs_f32(),
+            2.0
+        );
+    }
+}
@@ -5,17 +5,21 @@ use context_attribute::context;
 use filtering::kalman_filter::KalmanFilter;
 use framework::{AdditionalOutput, HistoricInput, MainOutput, PerceptionInput};
 use itertools::{chain, iproduct};
-use nalgebra::{distance, point, Isometry2, Matrix2, Point2};
+use nalgebra::{distance, point, Isometry2, Matrix2, Point2, Vector2};
 use types::{
-    detected_feet::DetectedFeet, detected_robots::DetectedRobots,
-    multivariate_normal_distribution::MultivariateNormalDistribution, obstacle_filter::Hypothesis,
-    parameters::ObstacleFilter as ObstacleFilterParameters, CycleTime, FieldDimensions, Obstacle,
-    ObstacleKind, PrimaryState, SonarObstacle,
+    coordinate_systems::{Field, Ground, Point, Transform},
+    detected_feet::DetectedFeet,
+    detected_robots::DetectedRobots,
+    multivariate_normal_distribution::MultivariateNormalDistribution,
+    obstacle_filter::Hypothesis,
+    parameters::ObstacleFilter as ObstacleFilterParameters,
+    CycleTime, FieldDimensions, Obstacle, ObstacleKind, PrimaryState, PrimaryStateTransition,
+    SonarObstacle,
 };
 
 pub struct ObstacleFilter {
     hypotheses: Vec<Hypothesis>,
-    last_primary_state: PrimaryState,
+    last_measurement: Option<SystemTime>,
 }
 
 #[context]
@@ -31,11 +35,12 @@ pub struct CycleContext {
     pub current_odometry_to_last_odometry:
         HistoricInput<Option<Isometry2<f32>>, "current_odometry_to_last_odometry?">,
     pub network_robot_obstacles: HistoricInput<Vec<Point2<f32>>, "network_robot_obstacles">,
-    pub robot_to_field: HistoricInput<Option<Isometry2<f32>>, "robot_to_field?">,
+    pub robot_to_field: HistoricInput<Option<Transform<Ground, Field>>, "robot_to_field?">,
     pub sonar_obstacles: HistoricInput<Vec<SonarObstacle>, "sonar_obstacles">,
 
     pub cycle_time: Input<CycleTime, "cycle_time">,
-    pub primary_state: Input<PrimaryState, "primary_state">,
+    pub primary_state_transition:
+        Input<Option<PrimaryStateTransition>, "primary_state_transition?">,
 
     pub field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
     pub goal_post_obstacle_radius: Parameter<f32, "obstacle_filter.goal_post_obstacle_radius">,
@@ -56,13 +61,15 @@ pub struct CycleContext {
 #[derive(Default)]
 pub struct MainOutputs {
     pub obstacles: MainOutput<Vec<Obstacle>>,
+    /// Detection time of the latest vision measurement the obstacles were updated with
+    pub last_obstacle_measurement: MainOutput<Option<SystemTime>>,
 }
 
 impl ObstacleFilter {
     pub fn new(_context: CreationContext) -> Result<Self> {
         Ok(Self {
             hypotheses: Vec::new(),
-            last_primary_state: PrimaryState::Unstiff,
+            last_measurement: None,
         })
     }
 
@@ -78,6 +85,7 @@ impl ObstacleFilter {
             .zip(context.detected_robots_bottom.persistent.values());
         for ((((detection_time, feet_top), feet_bottom), robots_top), robots_bottom) in measurements
         {
+            self.last_measurement = self.last_measurement.max(Some(*detection_time));
             let current_odometry_to_last_odometry = context
                 .current_odometry_to_last_odometry
                 .get(detection_time)
@@ -96,7 +104,7 @@ impl ObstacleFilter {
             for network_robot_obstacle in network_robot_obstacles {
                 self.update_hypotheses_with_measurement(
                     *network_robot_obstacle,
-                    ObstacleKind::Robot,
+                    ObstacleKind::Teammate,
                     *detection_time,
                     context
                         .obstacle_filter_parameters
@@ -106,6 +114,7 @@ impl ObstacleFilter {
                             .obstacle_filter_parameters
                             .network_robot_measurement_noise,
                     ),
+                    context.obstacle_filter_parameters.velocity_smoothing_factor,
                 );
             }
 
@@ -129,6 +138,7 @@ impl ObstacleFilter {
                         Matrix2::from_diagonal(
                             &context.obstacle_filter_parameters.feet_measurement_noise,
                         ),
+                        context.obstacle_filter_parameters.velocity_smoothing_factor,
                     );
                 }
             }
@@ -137,15 +147,15 @@ impl ObstacleFilter {
                 .obstacle_filter_parameters
                 .use_robot_detection_measurements
             {
-                let measured_positions_in_control_cycle = robots_top
+                let measured_robots_in_control_cycle = robots_top
                     .iter()
                     .chain(robots_bottom.iter())
                     .flat_map(|obstacles| obstacles.on_ground.iter());
 
-                for position in measured_positions_in_control_cycle {
+                for robot in measured_robots_in_control_cycle {
                     self.update_hypotheses_with_measurement(
-                        *position,
-                        ObstacleKind::Robot,
+                        robot.position,
+                        robot.kind,
                         *detection_time,
                         context
                             .obstacle_filter_parameters
@@ -153,6 +163,7 @@ impl ObstacleFilter {
                         Matrix2::from_diagonal(
                             &context.obstacle_filter_parameters.robot_measurement_noise,
                         ),
+                        context.obstacle_filter_parameters.velocity_smoothing_factor,
                     );
                 }
             }
@@ -178,6 +189,7 @@ impl ObstacleFilter {
                         Matrix2::from_diagonal(
                             &context.obstacle_filter_parameters.sonar_measurement_noise,
                         ),
+                        context.obstacle_filter_parameters.velocity_smoothing_factor,
                     );
                 }
             }
@@ -189,12 +201,13 @@ impl ObstacleFilter {
             context.obstacle_filter_parameters.hypothesis_merge_distance,
         );
 
-        if self.last_primary_state == PrimaryState::Penalized
-            && *context.primary_state != PrimaryState::Penalized
+        if let Some(PrimaryStateTransition {
+            from: PrimaryState::Penalized,
+            ..
+        }) = context.primary_state_transition
         {
             self.hypotheses = Vec::new();
         }
-        self.last_primary_state = *context.primary_state;
 
         let robot_obstacles = self
             .hypotheses
@@ -211,7 +224,7 @@ impl ObstacleFilter {
                         *context.goal_post_obstacle_radius,
                         *context.goal_post_obstacle_radius,
                     ),
-                    ObstacleKind::Robot => (
+                    kind if kind.is_robot() => (
                         *context.robot_obstacle_radius_at_hip_height,
                         *context.robot_obstacle_radius_at_foot_height,
                     ),
@@ -221,18 +234,24 @@ impl ObstacleFilter {
                     ),
                     _ => panic!("Unexpected obstacle radius"),
                 };
+                let velocity = if hypothesis.obstacle_kind.is_robot() {
+                    hypothesis.velocity
+                } else {
+                    Vector2::zeros()
+                };
                 Obstacle {
                     position: hypothesis.state.mean.into(),
                     kind: hypothesis.obstacle_kind,
                     radius_at_hip_height,
                     radius_at_foot_height,
+                    velocity,
                 }
             })
             .collect::<Vec<_>>();
         let current_robot_to_field = context.robot_to_field.get(&cycle_start_time);
         let goal_posts = calculate_goal_post_positions(current_robot_to_field, field_dimensions);
         let goal_post_obstacles = goal_posts.into_iter().map(|goal_post| {
-            Obstacle::goal_post(goal_post, field_dimensions.goal_post_diameter / 2.0)
+            Obstacle::goal_post(goal_post, field_dimensions.goal_post_diameter.0 / 2.0)
         });
         context
             .obstacle_filter_hypotheses
@@ -241,6 +260,7 @@ impl ObstacleFilter {
             obstacles: chain!(robot_obstacles, goal_post_obstacles)
                 .collect::<Vec<_>>()
                 .into(),
+            last_obstacle_measurement: self.last_measurement.into(),
         })
     }
 
@@ -260,7 +280,8 @@ impl ObstacleFilter {
                 control_input_model,
                 odometry_translation,
                 process_noise,
-            )
+            );
+            hypothesis.velocity = last_odometry_to_current_odometry.rotation * hypothesis.velocity;
         }
     }
 
@@ -271,6 +292,7 @@ impl ObstacleFilter {
         detection_time: SystemTime,
         matching_distance: f32,
         measurement_noise: Matrix2<f32>,
+        velocity_smoothing_factor: f32,
     ) {
         let mut matching_hypotheses = self
             .hypotheses
@@ -289,16 +311,23 @@ impl ObstacleFilter {
             return;
         }
         matching_hypotheses.for_each(|hypothesis| {
+            let previous_mean = hypothesis.state.mean;
             hypothesis.state.update(
                 Matrix2::identity(),
                 detected_position.coords,
                 measurement_noise * detected_position.coords.norm_squared(),
             );
-            hypothesis.obstacle_kind = match hypothesis.obstacle_kind {
-                ObstacleKind::Robot => hypothesis.obstacle_kind,
-                ObstacleKind::Unknown => detected_obstacle_kind,
-                _ => panic!("Unexpected obstacle kind"),
-            };
+            let elapsed = detection_time
+                .duration_since(hypothesis.last_update)
+                .unwrap_or_default();
+            if !elapsed.is_zero() {
+                let measured_velocity =
+                    (hypothesis.state.mean - previous_mean) / elapsed.as_secs_f32();
+                hypothesis.velocity +=
+                    (measured_velocity - hypothesis.velocity) * velocity_smoothing_factor;
+            }
+            hypothesis.obstacle_kind =
+                merge_obstacle_kinds(hypothesis.obstacle_kind, detected_obstacle_kind);
             hypothesis.measurement_count += 1;
             hypothesis.last_update = detection_time;
         });
@@ -317,6 +346,7 @@ impl ObstacleFilter {
                 mean: initial_state,
                 covariance: initial_covariance,
             },
+            velocity: Vector2::zeros(),
             obstacle_kind,
             measurement_count: 1,
             last_update: detection_time,
@@ -351,11 +381,10 @@ impl ObstacleFilter {
                         hypothesis.state.mean,
                         hypothesis.state.covariance,
                     );
-                    existing_hypothesis.obstacle_kind = match existing_hypothesis.obstacle_kind {
-                        ObstacleKind::Robot => existing_hypothesis.obstacle_kind,
-                        ObstacleKind::Unknown => hypothesis.obstacle_kind,
-                        _ => panic!("Unexpected obstacle kind"),
-                    };
+                    existing_hypothesis.obstacle_kind = merge_obstacle_kinds(
+                        existing_hypothesis.obstacle_kind,
+                        hypothesis.obstacle_kind,
+                    );
                 }
                 None => deduplicated_hypotheses.push(hypothesis),
             }
@@ -364,26 +393,62 @@ impl ObstacleFilter {
     }
 }
 
+/// Refines the kind of a hypothesis, a recognized jersey overrides earlier classifications
+fn merge_obstacle_kinds(existing: ObstacleKind, measured: ObstacleKind) -> ObstacleKind {
+    match (existing, measured) {
+        (ObstacleKind::Unknown, _) | (_, ObstacleKind::Teammate | ObstacleKind::Opponent) => {
+            measured
+        }
+        (ObstacleKind::Robot | ObstacleKind::Teammate | ObstacleKind::Opponent, _) => existing,
+        _ => panic!("Unexpected obstacle kind"),
+    }
+}
+
 fn calculate_goal_post_positions(
-    current_robot_to_field: Option<&Isometry2<f32>>,
+    current_robot_to_field: Option<&Transform<Ground, Field>>,
     field_dimensions: &FieldDimensions,
 ) -> Vec<Point2<f32>> {
     current_robot_to_field
         .map(|robot_to_field| {
             let field_to_robot = robot_to_field.inverse();
             iproduct!([-1.0, 1.0], [-1.0, 1.0]).map(move |(x_sign, y_sign)| {
-                let radius = field_dimensions.goal_post_diameter / 2.0;
-                let position_on_field = point![
+                let radius = field_dimensions.goal_post_diameter.0 / 2.0;
+                let position_on_field = Point::<Field>::wrap(point![
                     x_sign
-                        * (field_dimensions.length / 2.0
-                            + field_dimensions.goal_post_diameter / 2.0
-                            - field_dimensions.line_width / 2.0),
-                    y_sign * (field_dimensions.goal_inner_width / 2.0 + radius)
-                ];
-                field_to_robot * position_on_field
+                        * (field_dimensions.length.0 / 2.0
+                            + field_dimensions.goal_post_diameter.0 / 2.0
+                            - field_dimensions.line_width.0 / 2.0),
+                    y_sign * (field_dimensions.goal_inner_width.0 / 2.0 + radius)
+                ]);
+                (field_to_robot * position_on_field).inner
             })
         })
         .into_iter()
         .flatten()
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jersey_classifications_refine_robot_hypotheses() {
+        assert!(matches!(
+            merge_obstacle_kinds(ObstacleKind::Unknown, ObstacleKind::Robot),
+            ObstacleKind::Robot
+        ));
+        assert!(matches!(
+            merge_obstacle_kinds(ObstacleKind::Robot, ObstacleKind::Opponent),
+            ObstacleKind::Opponent
+        ));
+        assert!(matches!(
+            merge_obstacle_kinds(ObstacleKind::Teammate, ObstacleKind::Robot),
+            ObstacleKind::Teammate
+        ));
+        assert!(matches!(
+            merge_obstacle_kinds(ObstacleKind::Opponent, ObstacleKind::Unknown),
+            ObstacleKind::Opponent
+        ));
+    }
+}
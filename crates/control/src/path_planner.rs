@@ -4,7 +4,7 @@ use ordered_float::NotNan;
 use smallvec::SmallVec;
 
 use types::{
-    Arc, Circle, FieldDimensions, LineSegment, Obstacle, Orientation, PathObstacle,
+    Arc, Circle, FieldDimensions, LineSegment, Obstacle, ObstacleKind, Orientation, PathObstacle,
     PathObstacleShape, PathSegment, RuleObstacle,
 };
 
@@ -34,22 +34,81 @@ pub struct PathPlanner {
     /// The first node is always the start, the second the destination
     pub nodes: Vec<PathNode>,
     pub obstacles: Vec<PathObstacle>,
+    rotation_penalty_factor: f32,
+    inflation_radius: f32,
+    inflation_weight: f32,
+    walking_speed: f32,
+    maximum_prediction_time: f32,
+    teammate_radius_reduction: f32,
 }
 
 impl PathPlanner {
+    /// Adds `factor` meters of cost per radian the robot has to turn, i.e. towards the first
+    /// segment and along arcs
+    ///
+    /// Paths are planned in robot coordinates, i.e. the robot faces the positive x axis. Tight arcs
+    /// turn more per walked distance and are thereby more expensive than wide ones.
+    pub fn with_rotation_penalty(&mut self, factor: f32) {
+        self.rotation_penalty_factor = factor;
+    }
+
+    /// Penalizes segments passing obstacles closer than `radius`
+    ///
+    /// The cost of a segment touching an obstacle is scaled by `1.0 + weight` and falls off
+    /// linearly to its length at a clearance of `radius`.
+    pub fn with_inflation(&mut self, radius: f32, weight: f32) {
+        self.inflation_radius = radius;
+        self.inflation_weight = weight;
+    }
+
+    /// Places moving obstacles where they are when the robot, walking at `walking_speed` from the
+    /// origin, would reach them, but at most `maximum_prediction_time` seconds ahead
+    ///
+    /// Has to be configured before adding obstacles.
+    pub fn with_obstacle_prediction(&mut self, walking_speed: f32, maximum_prediction_time: f32) {
+        self.walking_speed = walking_speed;
+        self.maximum_prediction_time = maximum_prediction_time;
+    }
+
+    /// Shrinks teammates by `reduction` meters, they avoid collisions themselves and can be passed
+    /// more closely than opponents
+    ///
+    /// Has to be configured before adding obstacles.
+    pub fn with_teammate_radius_reduction(&mut self, reduction: f32) {
+        self.teammate_radius_reduction = reduction;
+    }
+
     pub fn with_obstacles(&mut self, obstacles: &[Obstacle], own_robot_radius: f32) {
-        let new_obstacles = obstacles.iter().map(|obstacle| {
-            let position = obstacle.position;
-            let radius = obstacle.radius_at_hip_height + own_robot_radius;
-            PathObstacle::from(PathObstacleShape::Circle(Circle {
-                center: position,
-                radius,
-            }))
-        });
+        let new_obstacles: Vec<_> = obstacles
+            .iter()
+            .map(|obstacle| {
+                let position = self.predict_position(obstacle);
+                let obstacle_radius = match obstacle.kind {
+                    ObstacleKind::Teammate => {
+                        (obstacle.radius_at_hip_height - self.teammate_radius_reduction).max(0.0)
+                    }
+                    _ => obstacle.radius_at_hip_height,
+                };
+                let radius = obstacle_radius + own_robot_radius;
+                PathObstacle::from(PathObstacleShape::Circle(Circle {
+                    center: position,
+                    radius,
+                }))
+            })
+            .collect();
 
         self.obstacles.extend(new_obstacles);
     }
 
+    fn predict_position(&self, obstacle: &Obstacle) -> Point2<f32> {
+        if self.walking_speed <= 0.0 {
+            return obstacle.position;
+        }
+        let time_to_reach = (obstacle.position.coords.norm() / self.walking_speed)
+            .min(self.maximum_prediction_time);
+        obstacle.position + obstacle.velocity * time_to_reach
+    }
+
     pub fn with_rule_obstacles(
         &mut self,
         field_to_robot: Isometry2<f32>,
@@ -166,11 +225,13 @@ impl PathPlanner {
         field_to_robot: Isometry2<f32>,
         field_dimensions: &FieldDimensions,
     ) {
-        let goal_post_x = field_dimensions.length / 2.0 + field_dimensions.goal_post_diameter / 2.0
-            - field_dimensions.line_width / 2.0;
+        let goal_post_x = field_dimensions.length.0 / 2.0
+            + field_dimensions.goal_post_diameter.0 / 2.0
+            - field_dimensions.line_width.0 / 2.0;
         let goal_post_y =
-            field_dimensions.goal_inner_width / 2.0 + field_dimensions.goal_post_diameter / 2.0;
-        let field_border_x = field_dimensions.length / 2.0 + field_dimensions.border_strip_width;
+            field_dimensions.goal_inner_width.0 / 2.0 + field_dimensions.goal_post_diameter.0 / 2.0;
+        let field_border_x =
+            field_dimensions.length.0 / 2.0 + field_dimensions.border_strip_width.0;
 
         let post_to_border = |x_sign: f32, y_sign: f32| {
             LineSegment(
@@ -193,6 +254,66 @@ impl PathPlanner {
         );
     }
 
+    /// Keeps paths on the carpet, sides the robot is already outside of are left open to walk back
+    pub fn with_carpet_boundary(
+        &mut self,
+        robot_to_field: Isometry2<f32>,
+        field_dimensions: &FieldDimensions,
+    ) {
+        let own_position = robot_to_field * Point2::origin();
+        let field_to_robot = robot_to_field.inverse();
+        let x = field_dimensions.length.0 / 2.0 + field_dimensions.border_strip_width.0;
+        let y = field_dimensions.width.0 / 2.0 + field_dimensions.border_strip_width.0;
+
+        let sides = [
+            (own_position.x > -x, point![-x, -y], point![-x, y]),
+            (own_position.y < y, point![-x, y], point![x, y]),
+            (own_position.x < x, point![x, y], point![x, -y]),
+            (own_position.y > -y, point![x, -y], point![-x, -y]),
+        ];
+
+        self.obstacles.extend(
+            sides
+                .into_iter()
+                .filter(|(robot_is_inside, _, _)| *robot_is_inside)
+                .map(|(_, start, end)| {
+                    PathObstacle::from(LineSegment(field_to_robot * start, field_to_robot * end))
+                }),
+        );
+    }
+
+    /// Adds the posts of both goals and the net behind the own goal
+    pub fn with_goal_posts(
+        &mut self,
+        field_to_robot: Isometry2<f32>,
+        field_dimensions: &FieldDimensions,
+        own_robot_radius: f32,
+    ) {
+        let goal_post_x = field_dimensions.length.0 / 2.0
+            + field_dimensions.goal_post_diameter.0 / 2.0
+            - field_dimensions.line_width.0 / 2.0;
+        let goal_post_y =
+            field_dimensions.goal_inner_width.0 / 2.0 + field_dimensions.goal_post_diameter.0 / 2.0;
+        let radius = field_dimensions.goal_post_diameter.0 / 2.0 + own_robot_radius;
+
+        let goal_posts = [
+            point![goal_post_x, goal_post_y],
+            point![goal_post_x, -goal_post_y],
+            point![-goal_post_x, goal_post_y],
+            point![-goal_post_x, -goal_post_y],
+        ]
+        .map(|goal_post| PathObstacle::from(Circle::new(field_to_robot * goal_post, radius)));
+
+        let own_goal_net_x = -(field_dimensions.length.0 / 2.0 + field_dimensions.goal_depth.0);
+        let own_goal_net = PathObstacle::from(LineSegment(
+            field_to_robot * point![own_goal_net_x, -goal_post_y],
+            field_to_robot * point![own_goal_net_x, goal_post_y],
+        ));
+
+        self.obstacles.extend(goal_posts);
+        self.obstacles.push(own_goal_net);
+    }
+
     fn generate_start_destination_tangents(&mut self) {
         let direct_path = LineSegment(self.nodes[0].position, self.nodes[1].position);
         let direct_path_blocked = self
@@ -429,6 +550,54 @@ impl PathPlanner {
             };
         }
     }
+
+    fn inflation_factor(&self, clearance: f32) -> f32 {
+        if self.inflation_radius <= 0.0 {
+            return 1.0;
+        }
+        let proximity = (1.0 - clearance / self.inflation_radius).clamp(0.0, 1.0);
+        1.0 + self.inflation_weight * proximity
+    }
+
+    fn line_cost(&self, from: usize, to: usize) -> f32 {
+        let from_node = &self.nodes[from];
+        let to_node = &self.nodes[to];
+        let line_segment = LineSegment(from_node.position, to_node.position);
+        let clearance = self
+            .obstacles
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| {
+                Some(*index) != from_node.obstacle && Some(*index) != to_node.obstacle
+            })
+            .filter_map(|(_, obstacle)| obstacle.shape.as_circle())
+            .map(|circle| line_segment.shortest_distance_to_point(circle.center) - circle.radius)
+            .fold(f32::INFINITY, f32::min);
+        let rotation_cost = if from == 0 {
+            let direction = line_segment.1 - line_segment.0;
+            self.rotation_penalty_factor * direction.y.atan2(direction.x).abs()
+        } else {
+            0.0
+        };
+        line_segment.norm() * self.inflation_factor(clearance) + rotation_cost
+    }
+
+    fn arc_cost(&self, obstacle_index: usize, arc: Arc, orientation: Orientation) -> f32 {
+        let segment = PathSegment::Arc(arc, orientation);
+        let clearance = self
+            .obstacles
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != obstacle_index)
+            .filter_map(|(_, obstacle)| obstacle.shape.as_circle())
+            .map(|circle| {
+                let (closest_point, _) = segment.closest_point(circle.center);
+                distance(&closest_point, &circle.center) - circle.radius
+            })
+            .fold(f32::INFINITY, f32::min);
+        let rotation_cost = self.rotation_penalty_factor * arc.sweep_angle(orientation).abs();
+        arc.length(orientation) * self.inflation_factor(clearance) + rotation_cost
+    }
 }
 
 impl DynamicMap for PathPlanner {
@@ -439,12 +608,12 @@ impl DynamicMap for PathPlanner {
     fn get_available_exits(&mut self, index: usize) -> SmallVec<[(usize, f32); 10]> {
         let mut vector = SmallVec::new();
         if let Some(pair_index) = self.nodes[index].pair_node {
-            vector.push((pair_index, self.get_pathing_distance(index, pair_index)));
+            vector.push((pair_index, self.line_cost(index, pair_index)));
             self.nodes[pair_index].allow_local_exits = true;
         } else {
             for pair_index in 0..self.nodes.len() {
                 if self.nodes[pair_index].pair_node == Some(index) {
-                    vector.push((pair_index, self.get_pathing_distance(index, pair_index)));
+                    vector.push((pair_index, self.line_cost(index, pair_index)));
                     self.nodes[pair_index].allow_local_exits = true;
                 }
             }
@@ -476,7 +645,10 @@ impl DynamicMap for PathPlanner {
                             .filter(|(index, _)| *index != obstacle_index)
                             .all(|(_, obstacle)| !obstacle.shape.overlaps_arc(arc, orientation))
                         {
-                            vector.push((*other_node, arc.length(orientation)));
+                            vector.push((
+                                *other_node,
+                                self.arc_cost(obstacle_index, arc, orientation),
+                            ));
                         }
                     }
                 }
@@ -492,10 +664,10 @@ mod tests {
     use std::f32::consts::PI;
 
     use approx::assert_relative_eq;
-    use nalgebra::point;
+    use nalgebra::{point, vector};
 
     use super::*;
-    use types::Circle;
+    use types::{units::Meters, Circle};
 
     fn run_test_scenario(
         start: Point2<f32>,
@@ -800,4 +972,203 @@ mod tests {
             .expect("Path error")
             .is_none());
     }
+
+    fn first_segment_end(path: &[PathSegment]) -> Point2<f32> {
+        match path.first() {
+            Some(PathSegment::LineSegment(line_segment)) => line_segment.1,
+            segment => panic!("expected a line segment, got {segment:?}"),
+        }
+    }
+
+    #[test]
+    fn rotation_penalty_prefers_less_turning() {
+        let obstacles = [
+            Obstacle::ball(point![1.3, -0.2], 0.5),
+            Obstacle::ball(point![2.7, 0.2], 0.5),
+        ];
+        let mut planner = PathPlanner::default();
+        planner.with_obstacles(&obstacles, 0.0);
+        let shortest_path = planner
+            .plan(Point2::origin(), point![4.0, 0.0])
+            .unwrap()
+            .unwrap();
+
+        let mut planner = PathPlanner::default();
+        planner.with_rotation_penalty(1.0);
+        planner.with_obstacles(&obstacles, 0.0);
+        let least_turning_path = planner
+            .plan(Point2::origin(), point![4.0, 0.0])
+            .unwrap()
+            .unwrap();
+
+        // the shortest path winds between the obstacles, the least turning one passes both above
+        assert!(shortest_path.iter().any(|segment| segment.end().y < 0.0));
+        assert!(least_turning_path
+            .iter()
+            .all(|segment| segment.end().y >= 0.0));
+    }
+
+    #[test]
+    fn inflation_avoids_passing_close_to_obstacles() {
+        let mut planner = PathPlanner::default();
+        planner.with_inflation(0.5, 1.0);
+        planner.with_obstacles(
+            &[
+                Obstacle::ball(point![0.0, 0.0], 0.5),
+                Obstacle::ball(point![0.0, 0.9], 0.2),
+            ],
+            0.0,
+        );
+
+        let path = planner
+            .plan(point![-2.0, 0.0], point![2.0, 0.0])
+            .unwrap()
+            .unwrap();
+
+        assert!(first_segment_end(&path).y < 0.0);
+    }
+
+    #[test]
+    fn arcs_are_inflated_by_clearance_to_other_obstacles() {
+        let circle = Circle::new(Point2::origin(), 1.0);
+        let arc = Arc::new(circle, point![-1.0, 0.0], point![1.0, 0.0]);
+        let mut planner = PathPlanner::default();
+        planner.with_inflation(0.5, 1.0);
+        planner.with_obstacles(
+            &[
+                Obstacle::ball(point![0.0, 0.0], 1.0),
+                Obstacle::ball(point![0.0, 1.5], 0.25),
+                Obstacle::ball(point![0.0, -3.0], 0.25),
+            ],
+            0.0,
+        );
+
+        assert_relative_eq!(
+            planner.arc_cost(0, arc, Orientation::Clockwise),
+            PI * 1.5,
+            epsilon = 0.001
+        );
+        assert_relative_eq!(
+            planner.arc_cost(0, arc, Orientation::Counterclockwise),
+            PI,
+            epsilon = 0.001
+        );
+    }
+
+    #[test]
+    fn turning_along_arcs_is_penalized() {
+        let wide_arc = Arc::new(
+            Circle::new(Point2::origin(), 2.0),
+            point![0.0, -2.0],
+            point![2.0, 0.0],
+        );
+        let tight_arc = Arc::new(
+            Circle::new(Point2::origin(), 0.5),
+            point![0.0, -0.5],
+            point![0.0, 0.5],
+        );
+        let mut planner = PathPlanner::default();
+        planner.with_rotation_penalty(0.5);
+
+        assert_relative_eq!(
+            planner.arc_cost(0, wide_arc, Orientation::Counterclockwise),
+            PI + 0.25 * PI,
+            epsilon = 0.001
+        );
+        assert_relative_eq!(
+            planner.arc_cost(0, tight_arc, Orientation::Counterclockwise),
+            0.5 * PI + 0.5 * PI,
+            epsilon = 0.001
+        );
+    }
+
+    #[test]
+    fn moving_obstacles_are_avoided_at_predicted_position() {
+        let obstacle = Obstacle {
+            velocity: vector![0.0, 1.0],
+            ..Obstacle::robot(point![1.0, 0.0], 0.3, 0.3)
+        };
+        let mut planner = PathPlanner::default();
+        planner.with_obstacle_prediction(1.0, 2.0);
+        planner.with_obstacles(&[obstacle], 0.0);
+
+        run_test_scenario(
+            Point2::origin(),
+            point![2.0, 0.0],
+            &mut planner,
+            &[PathSegment::LineSegment(LineSegment(
+                Point2::origin(),
+                point![2.0, 0.0],
+            ))],
+            2.0,
+        );
+    }
+
+    #[test]
+    fn teammates_are_passed_more_closely() {
+        let teammate = Obstacle {
+            kind: ObstacleKind::Teammate,
+            ..Obstacle::robot(point![1.0, 0.0], 0.3, 0.3)
+        };
+        let mut planner = PathPlanner::default();
+        planner.with_teammate_radius_reduction(0.1);
+        planner.with_obstacles(
+            &[teammate, Obstacle::robot(point![2.0, 0.0], 0.3, 0.3)],
+            0.2,
+        );
+
+        let radii: Vec<_> = planner
+            .obstacles
+            .iter()
+            .map(|obstacle| match &obstacle.shape {
+                PathObstacleShape::Circle(circle) => circle.radius,
+                _ => panic!("obstacles should be circles"),
+            })
+            .collect();
+        assert_relative_eq!(radii[0], 0.4);
+        assert_relative_eq!(radii[1], 0.5);
+    }
+
+    fn field_dimensions() -> FieldDimensions {
+        FieldDimensions {
+            length: Meters(9.0),
+            width: Meters(6.0),
+            line_width: Meters(0.05),
+            border_strip_width: Meters(0.7),
+            goal_inner_width: Meters(1.5),
+            goal_post_diameter: Meters(0.1),
+            goal_depth: Meters(0.5),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn paths_stay_on_carpet() {
+        let mut planner = PathPlanner::default();
+        planner.with_carpet_boundary(Isometry2::identity(), &field_dimensions());
+        planner.with_obstacles(&[Obstacle::robot(point![0.0, 3.5], 0.5, 0.5)], 0.0);
+
+        let path = planner
+            .plan(point![-1.0, 3.5], point![1.0, 3.5])
+            .expect("Path error")
+            .expect("No path found");
+
+        assert!(path.iter().all(|segment| match segment {
+            PathSegment::LineSegment(LineSegment(start, end)) => start.y <= 3.7 && end.y <= 3.7,
+            PathSegment::Arc(arc, _) => arc.start.y < arc.circle.center.y,
+        }));
+    }
+
+    #[test]
+    fn paths_avoid_goal_posts() {
+        let mut planner = PathPlanner::default();
+        planner.with_goal_posts(Isometry2::identity(), &field_dimensions(), 0.2);
+
+        let path = planner
+            .plan(point![4.525, 0.3], point![4.525, 1.3])
+            .expect("Path error")
+            .expect("No path found");
+
+        assert!(path.len() > 1);
+    }
 }
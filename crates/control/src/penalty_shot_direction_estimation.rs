@@ -53,7 +53,7 @@ impl PenaltyShotDirectionEstimation {
             (PrimaryState::Playing, GamePhase::PenaltyShootout { .. }) => {
                 if let PenaltyShotDirection::NotMoving = self.last_shot_direction {
                     if (context.ball_position.position.x
-                        - context.field_dimensions.penalty_marker_distance)
+                        - context.field_dimensions.penalty_marker_distance.0)
                         .abs()
                         > *context.moving_distance_threshold
                     {
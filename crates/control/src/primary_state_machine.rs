@@ -0,0 +1,221 @@
+use color_eyre::Result;
+use context_attribute::context;
+use framework::MainOutput;
+use spl_network_messages::PlayerNumber;
+use types::{
+    Buttons, FilteredGameState, FilteredWhistle, GameControllerState, PrimaryState,
+    PrimaryStateTransition,
+};
+
+pub struct PrimaryStateMachine {
+    last_primary_state: PrimaryState,
+}
+
+#[context]
+pub struct CreationContext {
+    pub player_number: Parameter<PlayerNumber, "player_number">,
+}
+
+#[context]
+pub struct CycleContext {
+    pub buttons: Input<Buttons, "buttons">,
+    pub filtered_game_state: Input<Option<FilteredGameState>, "filtered_game_state?">,
+    pub filtered_whistle: Input<FilteredWhistle, "filtered_whistle">,
+    pub game_controller_state: Input<Option<GameControllerState>, "game_controller_state?">,
+
+    pub player_number: Parameter<PlayerNumber, "player_number">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub primary_state: MainOutput<PrimaryState>,
+    pub primary_state_transition: MainOutput<Option<PrimaryStateTransition>>,
+}
+
+impl PrimaryStateMachine {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            last_primary_state: PrimaryState::Unstiff,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let is_penalized = match context.game_controller_state {
+            Some(game_controller_state) => {
+                game_controller_state.penalties[*context.player_number].is_some()
+            }
+            None => false,
+        };
+
+        let primary_state = next_primary_state(
+            self.last_primary_state,
+            context.buttons,
+            context.filtered_game_state.copied(),
+            context.filtered_whistle.started_this_cycle,
+            is_penalized,
+        );
+        let primary_state_transition =
+            (primary_state != self.last_primary_state).then_some(PrimaryStateTransition {
+                from: self.last_primary_state,
+                to: primary_state,
+            });
+        self.last_primary_state = primary_state;
+
+        Ok(MainOutputs {
+            primary_state: primary_state.into(),
+            primary_state_transition: primary_state_transition.into(),
+        })
+    }
+}
+
+fn next_primary_state(
+    last_primary_state: PrimaryState,
+    buttons: &Buttons,
+    filtered_game_state: Option<FilteredGameState>,
+    whistle_started: bool,
+    is_penalized: bool,
+) -> PrimaryState {
+    match (
+        last_primary_state,
+        buttons.is_chest_button_pressed_thrice,
+        buttons.is_chest_button_pressed,
+        buttons.calibration_buttons_touched,
+        filtered_game_state,
+    ) {
+        // Unstiff transitions (entering and exiting)
+        (_, true, _, _, _) => PrimaryState::Unstiff,
+
+        // Sitting down (left again by pressing the chest button)
+        (last_primary_state, _, _, _, _)
+            if buttons.head_buttons_touched && last_primary_state != PrimaryState::Unstiff =>
+        {
+            PrimaryState::Finished
+        }
+
+        (PrimaryState::Initial, _, _, true, _) => PrimaryState::Calibration,
+
+        // GameController transitions (entering listening mode and staying within)
+        (PrimaryState::Unstiff, _, true, _, Some(game_state))
+        | (PrimaryState::Finished, _, true, _, Some(game_state)) => {
+            game_state_to_primary_state(game_state, is_penalized)
+        }
+        (last_primary_state, _, _, _, Some(game_state))
+            if last_primary_state != PrimaryState::Unstiff
+                && last_primary_state != PrimaryState::Finished =>
+        {
+            game_state_to_primary_state(game_state, is_penalized)
+        }
+
+        // The GameController disappeared in set, the whistle still starts the game
+        (PrimaryState::Set, _, _, _, None) if whistle_started => PrimaryState::Playing,
+
+        // non-GameController transitions
+        (PrimaryState::Unstiff, _, true, _, None) => PrimaryState::Initial,
+        (PrimaryState::Finished, _, true, _, None) => PrimaryState::Initial,
+        (PrimaryState::Initial, _, true, _, None) => PrimaryState::Penalized,
+        (PrimaryState::Penalized, _, true, _, None) => PrimaryState::Playing,
+        (PrimaryState::Playing, _, true, _, None) => PrimaryState::Penalized,
+
+        (last_primary_state, _, _, _, _) => last_primary_state,
+    }
+}
+
+fn game_state_to_primary_state(game_state: FilteredGameState, is_penalized: bool) -> PrimaryState {
+    if is_penalized {
+        return PrimaryState::Penalized;
+    }
+    match game_state {
+        FilteredGameState::Ready { .. } => PrimaryState::Ready,
+        FilteredGameState::Initial => PrimaryState::Initial,
+        FilteredGameState::Set => PrimaryState::Set,
+        FilteredGameState::Playing { .. } => PrimaryState::Playing,
+        FilteredGameState::Finished => PrimaryState::Finished,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chest_button_pressed() -> Buttons {
+        Buttons {
+            is_chest_button_pressed: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn chest_button_cycles_states_without_game_controller() {
+        let buttons = chest_button_pressed();
+        let mut primary_state = PrimaryState::Unstiff;
+        let mut visited_states = Vec::new();
+        for _ in 0..4 {
+            primary_state = next_primary_state(primary_state, &buttons, None, false, false);
+            visited_states.push(primary_state);
+        }
+
+        assert_eq!(
+            visited_states,
+            [
+                PrimaryState::Initial,
+                PrimaryState::Penalized,
+                PrimaryState::Playing,
+                PrimaryState::Penalized
+            ]
+        );
+    }
+
+    #[test]
+    fn chest_button_wakes_up_into_game_controller_state() {
+        let buttons = chest_button_pressed();
+
+        assert_eq!(
+            next_primary_state(
+                PrimaryState::Unstiff,
+                &buttons,
+                Some(FilteredGameState::Playing { ball_is_free: true }),
+                false,
+                false
+            ),
+            PrimaryState::Playing
+        );
+        assert_eq!(
+            next_primary_state(
+                PrimaryState::Unstiff,
+                &buttons,
+                Some(FilteredGameState::Set),
+                false,
+                true
+            ),
+            PrimaryState::Penalized
+        );
+    }
+
+    #[test]
+    fn game_controller_overrides_buttons_once_listening() {
+        let primary_state = next_primary_state(
+            PrimaryState::Initial,
+            &chest_button_pressed(),
+            Some(FilteredGameState::Set),
+            false,
+            false,
+        );
+
+        assert_eq!(primary_state, PrimaryState::Set);
+    }
+
+    #[test]
+    fn whistle_starts_playing_after_game_controller_loss_in_set() {
+        let buttons = Buttons::default();
+
+        assert_eq!(
+            next_primary_state(PrimaryState::Set, &buttons, None, true, false),
+            PrimaryState::Playing
+        );
+        assert_eq!(
+            next_primary_state(PrimaryState::Set, &buttons, None, false, false),
+            PrimaryState::Set
+        );
+    }
+}
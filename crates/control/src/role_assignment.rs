@@ -1,29 +1,33 @@
-use color_eyre::{eyre::WrapErr, Result};
+use color_eyre::Result;
 use context_attribute::context;
 use framework::{MainOutput, PerceptionInput};
 use hardware::NetworkInterface;
 use nalgebra::{Isometry2, Point2, Vector2};
 use spl_network_messages::{
-    GameControllerReturnMessage, GamePhase, HulkMessage, Penalty, PlayerNumber, Team,
+    GamePhase, HulkMessage, Intention, Penalty, PlayerNumber, Team, TeamMessage,
 };
 use std::time::{Duration, SystemTime};
 use types::{
+    coordinate_systems::{Field, Ground, Pose, Transform},
     messages::{IncomingMessage, OutgoingMessage},
     parameters::SplNetwork,
-    BallPosition, CycleTime, FallState, FieldDimensions, GameControllerState, InitialPose, Players,
-    PrimaryState, Role,
+    BallPosition, CycleTime, FallState, FieldDimensions, GameControllerState, InitialPose,
+    NetworkQuality, Players, PrimaryState, Role,
 };
 
 use crate::localization::generate_initial_pose;
 
 pub struct RoleAssignment {
     last_received_spl_striker_message: Option<SystemTime>,
-    last_system_time_transmitted_game_controller_return_message: Option<SystemTime>,
     last_transmitted_spl_striker_message: Option<SystemTime>,
     role: Role,
     role_initialized: bool,
     team_ball: Option<BallPosition>,
     last_time_keeper_penalized: Option<SystemTime>,
+    sequence_number: u16,
+    teammate_intentions: Players<Option<Intention>>,
+    teammate_poses: Players<Option<Pose<Field>>>,
+    last_received_teammate_messages: Players<Option<SystemTime>>,
 }
 
 #[context]
@@ -38,11 +42,13 @@ pub struct CycleContext {
     pub ball_position: Input<Option<BallPosition>, "ball_position?">,
     pub fall_state: Input<FallState, "fall_state">,
     pub game_controller_state: Input<Option<GameControllerState>, "game_controller_state?">,
+    pub network_quality: Input<NetworkQuality, "network_quality">,
     pub primary_state: Input<PrimaryState, "primary_state">,
-    pub robot_to_field: Input<Option<Isometry2<f32>>, "robot_to_field?">,
+    pub robot_to_field: Input<Option<Transform<Ground, Field>>, "robot_to_field?">,
     pub cycle_time: Input<CycleTime, "cycle_time">,
     pub network_message: PerceptionInput<IncomingMessage, "SplNetwork", "message">,
     pub time_to_reach_kick_position: PersistentState<Duration, "time_to_reach_kick_position">,
+    pub intention: PersistentState<Option<Intention>, "intention">,
 
     pub field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
     pub forced_role: Parameter<Option<Role>, "role_assignment.forced_role?">,
@@ -62,18 +68,25 @@ pub struct MainOutputs {
     pub team_ball: MainOutput<Option<BallPosition>>,
     pub network_robot_obstacles: MainOutput<Vec<Point2<f32>>>,
     pub role: MainOutput<Role>,
+    pub teammate_intentions: MainOutput<Players<Option<Intention>>>,
+    pub teammate_poses: MainOutput<Players<Option<Pose<Field>>>>,
+    /// When the role was last confirmed by the team, i.e. by a received or sent message
+    pub last_role_update: MainOutput<Option<SystemTime>>,
 }
 
 impl RoleAssignment {
     pub fn new(_context: CreationContext) -> Result<Self> {
         Ok(Self {
             last_received_spl_striker_message: None,
-            last_system_time_transmitted_game_controller_return_message: None,
             last_transmitted_spl_striker_message: None,
             role: Role::Striker,
             role_initialized: false,
             team_ball: None,
             last_time_keeper_penalized: None,
+            sequence_number: 0,
+            teammate_intentions: Default::default(),
+            teammate_poses: Default::default(),
+            last_received_teammate_messages: Default::default(),
         })
     }
 
@@ -82,17 +95,16 @@ impl RoleAssignment {
         let primary_state = *context.primary_state;
         let mut role = self.role;
 
-        let robot_to_field =
-            context
-                .robot_to_field
-                .copied()
-                .unwrap_or_else(|| match context.primary_state {
-                    PrimaryState::Initial => generate_initial_pose(
-                        &context.initial_poses[*context.player_number],
-                        context.field_dimensions,
-                    ),
-                    _ => Default::default(),
-                });
+        let robot_to_field = context
+            .robot_to_field
+            .map(|robot_to_field| robot_to_field.inner)
+            .unwrap_or_else(|| match context.primary_state {
+                PrimaryState::Initial => generate_initial_pose(
+                    &context.initial_poses[*context.player_number],
+                    context.field_dimensions,
+                ),
+                _ => Default::default(),
+            });
 
         if !self.role_initialized
             || primary_state == PrimaryState::Ready
@@ -136,14 +148,6 @@ impl RoleAssignment {
             self.team_ball = None;
         }
 
-        let send_game_controller_return_message = self
-            .last_system_time_transmitted_game_controller_return_message
-            .is_none()
-            || cycle_start_time.duration_since(
-                self.last_system_time_transmitted_game_controller_return_message
-                    .unwrap(),
-            )? > context.spl_network.game_controller_return_message_interval;
-
         let mut send_spl_striker_message = self.last_transmitted_spl_striker_message.is_none()
             || cycle_start_time
                 .duration_since(self.last_transmitted_spl_striker_message.unwrap())?
@@ -165,25 +169,6 @@ impl RoleAssignment {
             None => true,
         };
 
-        if send_game_controller_return_message {
-            self.last_system_time_transmitted_game_controller_return_message =
-                Some(cycle_start_time);
-            context
-                .hardware
-                .write_to_network(OutgoingMessage::GameController(
-                    GameControllerReturnMessage {
-                        player_number: *context.player_number,
-                        fallen: matches!(context.fall_state, FallState::Fallen { .. }),
-                        robot_to_field,
-                        ball_position: seen_ball_to_network_ball_position(
-                            context.ball_position,
-                            cycle_start_time,
-                        ),
-                    },
-                ))
-                .wrap_err("failed to write GameControllerReturnMessage to hardware")?;
-        }
-
         let mut team_ball = self.team_ball;
 
         if spl_striker_message_timeout {
@@ -241,7 +226,16 @@ impl RoleAssignment {
                     (robot_to_field.inverse() * spl_message.robot_to_field) * Point2::origin();
                 if spl_message.player_number != *context.player_number {
                     network_robot_obstacles.push(sender_position);
+                    self.teammate_intentions[spl_message.player_number] = spl_message.intention;
+                    self.teammate_poses[spl_message.player_number] =
+                        Some(Pose::wrap(spl_message.robot_to_field));
+                    self.last_received_teammate_messages[spl_message.player_number] =
+                        Some(cycle_start_time);
                 }
+                let is_sender_reliable = context.network_quality.teammates
+                    [spl_message.player_number]
+                    .map_or(true, |connection| connection.is_reliable);
+                let previous_team_ball = team_ball;
                 (role, send_spl_striker_message, team_ball) = process_role_state_machine(
                     role,
                     robot_to_field,
@@ -257,9 +251,19 @@ impl RoleAssignment {
                     context.spl_network.striker_trusts_team_ball,
                     context.optional_roles,
                 );
+                // balls reported by peers with lossy or stale connections are likely outdated
+                if !is_sender_reliable {
+                    team_ball = previous_team_ball;
+                }
             }
         }
 
+        self.forget_outdated_teammates(
+            cycle_start_time,
+            context.game_controller_state,
+            context.spl_network.teammate_message_timeout,
+        );
+
         if let Some(last_time_keeper_penalized) = self.last_time_keeper_penalized {
             let deny_replacement_keeper_switch = cycle_start_time
                 .duration_since(last_time_keeper_penalized)
@@ -296,13 +300,22 @@ impl RoleAssignment {
                     };
                     context
                         .hardware
-                        .write_to_network(OutgoingMessage::Spl(HulkMessage {
-                            player_number: *context.player_number,
-                            fallen: matches!(context.fall_state, FallState::Fallen { .. }),
-                            robot_to_field,
-                            ball_position,
-                            time_to_reach_kick_position: Some(*context.time_to_reach_kick_position),
+                        .write_to_network(OutgoingMessage::Spl(TeamMessage {
+                            protocol: context.spl_network.team_message_protocol,
+                            message: HulkMessage {
+                                player_number: *context.player_number,
+                                sequence_number: Some(self.sequence_number),
+                                fallen: matches!(context.fall_state, FallState::Fallen { .. }),
+                                robot_to_field,
+                                role: Some(role),
+                                ball_position,
+                                time_to_reach_kick_position: Some(
+                                    *context.time_to_reach_kick_position,
+                                ),
+                                intention: *context.intention,
+                            },
                         }))?;
+                    self.sequence_number = self.sequence_number.wrapping_add(1);
                 }
             }
         }
@@ -324,8 +337,40 @@ impl RoleAssignment {
             role: self.role.into(),
             team_ball: self.team_ball.into(),
             network_robot_obstacles: network_robot_obstacles.into(),
+            teammate_intentions: self.teammate_intentions.into(),
+            teammate_poses: self.teammate_poses.into(),
+            last_role_update: self.last_received_spl_striker_message.into(),
         })
     }
+
+    /// Drops intentions and poses of teammates which are penalized or did not send for too long
+    fn forget_outdated_teammates(
+        &mut self,
+        now: SystemTime,
+        game_controller_state: Option<&GameControllerState>,
+        timeout: Duration,
+    ) {
+        let outdated_teammates: Vec<_> = self
+            .last_received_teammate_messages
+            .iter()
+            .filter(|(player_number, last_received)| {
+                let is_penalized = game_controller_state.map_or(false, |game_controller_state| {
+                    game_controller_state.penalties[*player_number].is_some()
+                });
+                let is_timed_out = last_received.map_or(true, |last_received| {
+                    now.duration_since(last_received)
+                        .map_or(false, |age| age > timeout)
+                });
+                is_penalized || is_timed_out
+            })
+            .map(|(player_number, _)| player_number)
+            .collect();
+        for player_number in outdated_teammates {
+            self.last_received_teammate_messages[player_number] = None;
+            self.teammate_intentions[player_number] = None;
+            self.teammate_poses[player_number] = None;
+        }
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -642,6 +687,8 @@ fn team_ball_from_spl_message(
             position: spl_message.robot_to_field * ball_position.relative_position,
             velocity: Vector2::zeros(),
             last_seen: cycle_start_time - ball_position.age,
+            // teammates only communicate balls their filter trusts
+            confidence: 1.0,
         })
 }
 
@@ -654,6 +701,7 @@ fn team_ball_from_seen_ball(
         position: (current_pose * ball.position),
         velocity: Vector2::zeros(),
         last_seen: cycle_start_time,
+        confidence: ball.confidence,
     })
 }
 
@@ -58,7 +58,7 @@ impl RuleObstacleComposer {
                 Some(ball),
             ) => {
                 let obstacle = RuleObstacle::Circle(Circle::new(
-                    ball.ball_in_field,
+                    ball.ball_in_field.inner,
                     free_kick_obstacle_radius,
                 ));
                 rule_obstacles.push(obstacle);
@@ -76,7 +76,7 @@ impl RuleObstacleComposer {
             ) => {
                 let obstacle = RuleObstacle::Circle(Circle::new(
                     Point2::origin(),
-                    context.field_dimensions.center_circle_diameter / 2.0,
+                    context.field_dimensions.center_circle_diameter.0 / 2.0,
                 ));
                 rule_obstacles.push(obstacle);
             }
@@ -111,14 +111,14 @@ pub fn create_penalty_box(field_dimensions: &FieldDimensions, kicking_team: Team
         // Striker may still enter opponent penalty box so this doesn't stop us from defending our own goal
         Team::Uncertain => 1.0,
     };
-    let half_field_length = field_dimensions.length / 2.0;
-    let half_penalty_area_length = field_dimensions.penalty_area_length / 2.0;
+    let half_field_length = field_dimensions.length.0 / 2.0;
+    let half_penalty_area_length = field_dimensions.penalty_area_length.0 / 2.0;
     let center_x = side_factor * (half_field_length - half_penalty_area_length);
     RuleObstacle::Rectangle(Rectangle::new_with_center_and_size(
         point![center_x, 0.0],
         vector![
-            field_dimensions.penalty_area_length,
-            field_dimensions.penalty_area_width
+            field_dimensions.penalty_area_length.0,
+            field_dimensions.penalty_area_width.0
         ],
     ))
 }
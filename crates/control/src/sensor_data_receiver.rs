@@ -4,7 +4,7 @@ use color_eyre::{eyre::WrapErr, Result};
 use context_attribute::context;
 use framework::{AdditionalOutput, MainOutput};
 use hardware::{SensorInterface, TimeInterface};
-use types::{CycleTime, Joints, SensorData};
+use types::{CycleTime, ImuCalibration, Joints, SensorData};
 
 pub struct SensorDataReceiver {
     last_cycle_start: SystemTime,
@@ -16,8 +16,12 @@ pub struct CreationContext {}
 #[context]
 pub struct CycleContext {
     pub hardware_interface: HardwareInterface,
+    pub imu_calibration: Parameter<ImuCalibration, "imu_calibration">,
     pub joint_calibration_offsets: Parameter<Joints<f32>, "joint_calibration_offsets">,
 
+    pub estimated_imu_calibration:
+        PersistentState<Option<ImuCalibration>, "estimated_imu_calibration">,
+
     pub maximum_temperature: AdditionalOutput<f32, "maximum_temperature">,
 }
 
@@ -44,6 +48,12 @@ impl SensorDataReceiver {
             .wrap_err("failed to read from sensors")?;
 
         sensor_data.positions = sensor_data.positions - (*context.joint_calibration_offsets);
+        let imu_calibration = context
+            .estimated_imu_calibration
+            .unwrap_or(*context.imu_calibration);
+        sensor_data.inertial_measurement_unit.angular_velocity -= imu_calibration.gyro_bias;
+        sensor_data.inertial_measurement_unit.linear_acceleration -=
+            imu_calibration.accelerometer_offset;
 
         let now = context.hardware_interface.get_now();
         let cycle_time = CycleTime {
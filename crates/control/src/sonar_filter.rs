@@ -1,11 +1,15 @@
+use std::collections::VecDeque;
+
 use color_eyre::Result;
 use context_attribute::context;
-use filtering::low_pass_filter::LowPassFilter;
+use filtering::{low_pass_filter::LowPassFilter, statistics::median};
 use framework::{AdditionalOutput, MainOutput};
 use nalgebra::point;
 use types::{FallState, SensorData, SonarObstacle, SonarValues};
 
 pub struct SonarFilter {
+    raw_sonar_left: VecDeque<f32>,
+    raw_sonar_right: VecDeque<f32>,
     filtered_sonar_left: LowPassFilter<f32>,
     filtered_sonar_right: LowPassFilter<f32>,
 }
@@ -23,9 +27,12 @@ pub struct CreationContext {
 pub struct CycleContext {
     pub sonar_values: AdditionalOutput<SonarValues, "sonar_values">,
 
+    pub cross_echo_tolerance: Parameter<f32, "sonar_filter.cross_echo_tolerance">,
     pub low_pass_filter_coefficient: Parameter<f32, "sonar_filter.low_pass_filter_coefficient">,
     pub maximal_detectable_distance: Parameter<f32, "sonar_filter.maximal_detectable_distance">,
     pub maximal_reliable_distance: Parameter<f32, "sonar_filter.maximal_reliable_distance">,
+    pub median_window_size: Parameter<usize, "sonar_filter.median_window_size">,
+    pub minimal_cross_echo_jump: Parameter<f32, "sonar_filter.minimal_cross_echo_jump">,
     pub minimal_reliable_distance: Parameter<f32, "sonar_filter.minimal_reliable_distance">,
     pub middle_merge_threshold: Parameter<f32, "sonar_filter.middle_merge_threshold">,
     pub sensor_angle: Parameter<f32, "sonar_obstacle.sensor_angle">,
@@ -43,6 +50,8 @@ pub struct MainOutputs {
 impl SonarFilter {
     pub fn new(context: CreationContext) -> Result<Self> {
         Ok(Self {
+            raw_sonar_left: VecDeque::new(),
+            raw_sonar_right: VecDeque::new(),
             filtered_sonar_left: LowPassFilter::with_smoothing_factor(
                 *context.maximal_detectable_distance,
                 *context.low_pass_filter_coefficient,
@@ -58,8 +67,41 @@ impl SonarFilter {
         let sonar_sensors = &context.sensor_data.sonar_sensors;
         let fall_state = context.fall_state;
 
-        self.filtered_sonar_left.update(sonar_sensors.left);
-        self.filtered_sonar_right.update(sonar_sensors.right);
+        let last_sonar_left = self.raw_sonar_left.back().copied();
+        let last_sonar_right = self.raw_sonar_right.back().copied();
+        let sonar_left = reject_cross_echo(
+            sonar_sensors.left,
+            last_sonar_left,
+            last_sonar_right,
+            *context.cross_echo_tolerance,
+            *context.minimal_cross_echo_jump,
+        );
+        let sonar_right = reject_cross_echo(
+            sonar_sensors.right,
+            last_sonar_right,
+            last_sonar_left,
+            *context.cross_echo_tolerance,
+            *context.minimal_cross_echo_jump,
+        );
+        if let Some(sonar_left) = sonar_left {
+            push_with_limit(
+                &mut self.raw_sonar_left,
+                sonar_left,
+                *context.median_window_size,
+            );
+        }
+        if let Some(sonar_right) = sonar_right {
+            push_with_limit(
+                &mut self.raw_sonar_right,
+                sonar_right,
+                *context.median_window_size,
+            );
+        }
+
+        self.filtered_sonar_left
+            .update(median(self.raw_sonar_left.make_contiguous()));
+        self.filtered_sonar_right
+            .update(median(self.raw_sonar_right.make_contiguous()));
 
         let acceptance_range =
             *context.minimal_reliable_distance..*context.maximal_reliable_distance;
@@ -119,3 +161,98 @@ impl SonarFilter {
         })
     }
 }
+
+/// The sonar emitters share the medium: an echo of the other side's emission shows up as a sudden
+/// jump to a distance the other sensor just measured. Such readings are rejected and must not
+/// enter the history, otherwise the echo becomes the last own reading of the next cycle.
+fn reject_cross_echo(
+    reading: f32,
+    last_own_reading: Option<f32>,
+    last_other_reading: Option<f32>,
+    cross_echo_tolerance: f32,
+    minimal_cross_echo_jump: f32,
+) -> Option<f32> {
+    match (last_own_reading, last_other_reading) {
+        (Some(last_own_reading), Some(last_other_reading))
+            if (reading - last_other_reading).abs() < cross_echo_tolerance
+                && (reading - last_own_reading).abs() > minimal_cross_echo_jump =>
+        {
+            None
+        }
+        _ => Some(reading),
+    }
+}
+
+fn push_with_limit(readings: &mut VecDeque<f32>, reading: f32, maximum_length: usize) {
+    readings.push_back(reading);
+    while readings.len() > maximum_length.max(1) {
+        readings.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CROSS_ECHO_TOLERANCE: f32 = 0.05;
+    const MINIMAL_CROSS_ECHO_JUMP: f32 = 0.3;
+
+    fn reject(
+        reading: f32,
+        last_own_reading: Option<f32>,
+        last_other_reading: Option<f32>,
+    ) -> Option<f32> {
+        reject_cross_echo(
+            reading,
+            last_own_reading,
+            last_other_reading,
+            CROSS_ECHO_TOLERANCE,
+            MINIMAL_CROSS_ECHO_JUMP,
+        )
+    }
+
+    #[test]
+    fn readings_without_history_are_accepted() {
+        assert_eq!(reject(0.4, None, Some(0.4)), Some(0.4));
+        assert_eq!(reject(0.4, Some(2.0), None), Some(0.4));
+    }
+
+    #[test]
+    fn jumps_to_the_reading_of_the_other_side_are_rejected() {
+        assert_eq!(reject(0.42, Some(2.0), Some(0.4)), None);
+    }
+
+    #[test]
+    fn small_changes_close_to_the_other_side_are_accepted() {
+        assert_eq!(reject(0.42, Some(0.5), Some(0.4)), Some(0.42));
+    }
+
+    #[test]
+    fn jumps_away_from_the_other_side_are_accepted() {
+        assert_eq!(reject(1.0, Some(2.0), Some(0.4)), Some(1.0));
+    }
+
+    #[test]
+    fn rejected_readings_do_not_enter_the_history() {
+        let mut history = VecDeque::new();
+        push_with_limit(&mut history, 2.0, 5);
+
+        for _ in 0..3 {
+            if let Some(reading) = reject(0.4, history.back().copied(), Some(0.4)) {
+                push_with_limit(&mut history, reading, 5);
+            }
+        }
+
+        assert_eq!(history, [2.0]);
+    }
+
+    #[test]
+    fn history_is_limited_to_the_window_size() {
+        let mut history = VecDeque::new();
+        for reading in [1.0, 2.0, 3.0, 4.0] {
+            push_with_limit(&mut history, reading, 3);
+        }
+
+        assert_eq!(history, [2.0, 3.0, 4.0]);
+    }
+}
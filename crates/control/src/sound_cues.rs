@@ -0,0 +1,91 @@
+use color_eyre::Result;
+use context_attribute::context;
+use hardware::AudioOutputInterface;
+use types::{BallState, GameControllerState, JointFault, PrimaryState, Sound, SystemWarning};
+
+/// Plays sounds on key events to make the state of the robot audible while testing
+pub struct SoundCues {
+    was_penalized: bool,
+    was_ball_seen: bool,
+    was_battery_low: bool,
+    had_joint_faults: bool,
+    was_localized: bool,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    pub ball_state: Input<Option<BallState>, "ball_state?">,
+    pub game_controller_state: Input<Option<GameControllerState>, "game_controller_state?">,
+    pub localization_score: Input<Option<f32>, "localization_score?">,
+    pub primary_state: Input<PrimaryState, "primary_state">,
+    pub system_warnings: Input<Vec<SystemWarning>, "system_warnings">,
+    pub joint_faults: Input<Vec<JointFault>, "joint_faults">,
+
+    pub enable: Parameter<bool, "sound_cues.enable">,
+    pub minimum_localization_score: Parameter<f32, "sound_cues.minimum_localization_score">,
+    pub silent_during_games: Parameter<bool, "sound_cues.silent_during_games">,
+
+    pub hardware: HardwareInterface,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {}
+
+impl SoundCues {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            was_penalized: false,
+            was_ball_seen: false,
+            was_battery_low: false,
+            had_joint_faults: false,
+            was_localized: false,
+        })
+    }
+
+    pub fn cycle(
+        &mut self,
+        context: CycleContext<impl AudioOutputInterface>,
+    ) -> Result<MainOutputs> {
+        let is_penalized = *context.primary_state == PrimaryState::Penalized;
+        let is_ball_seen = context.ball_state.is_some();
+        let is_battery_low = context.system_warnings.iter().any(|warning| {
+            matches!(
+                warning,
+                SystemWarning::LowBattery | SystemWarning::CriticalBattery
+            )
+        });
+        let has_joint_faults = !context.joint_faults.is_empty();
+        let is_localized = context
+            .localization_score
+            .map_or(false, |score| *score >= *context.minimum_localization_score);
+
+        let cues = [
+            (is_penalized && !self.was_penalized, Sound::Ouch),
+            (is_ball_seen && !self.was_ball_seen, Sound::Ball),
+            (is_battery_low && !self.was_battery_low, Sound::Donk),
+            (has_joint_faults && !self.had_joint_faults, Sound::Donk),
+            (!is_localized && self.was_localized, Sound::Drift),
+        ];
+        self.was_penalized = is_penalized;
+        self.was_ball_seen = is_ball_seen;
+        self.was_battery_low = is_battery_low;
+        self.had_joint_faults = has_joint_faults;
+        self.was_localized = is_localized;
+
+        let is_in_game = context.game_controller_state.is_some();
+        let is_silent = !*context.enable || (*context.silent_during_games && is_in_game);
+        if is_silent {
+            return Ok(MainOutputs {});
+        }
+        for (is_triggered, sound) in cues {
+            if is_triggered {
+                context.hardware.play_sound(sound)?;
+            }
+        }
+        Ok(MainOutputs {})
+    }
+}
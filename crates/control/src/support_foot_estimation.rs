@@ -2,7 +2,10 @@ use color_eyre::Result;
 use context_attribute::context;
 use filtering::hysteresis::greater_than_with_hysteresis;
 use framework::MainOutput;
-use types::{SensorData, Side, SupportFoot};
+use nalgebra::{point, Isometry3, Point2, Vector2};
+use types::{
+    Foot, ForceSensitiveResistors, RobotDimensions, RobotKinematics, SensorData, Side, SupportFoot,
+};
 
 pub struct SupportFootEstimation {
     last_support_side: Side,
@@ -16,8 +19,12 @@ pub struct CreationContext {
 #[context]
 pub struct CycleContext {
     pub hysteresis: Parameter<f32, "support_foot_estimation.hysteresis">,
+    pub minimal_pressure: Parameter<f32, "support_foot_estimation.minimal_pressure">,
+
+    pub walk_support_side: PersistentState<Option<Side>, "walk_support_side">,
 
     pub has_ground_contact: Input<bool, "has_ground_contact">,
+    pub robot_kinematics: Input<RobotKinematics, "robot_kinematics">,
     pub sensor_data: Input<SensorData, "sensor_data">,
 }
 
@@ -40,34 +47,182 @@ impl SupportFootEstimation {
                 support_foot: SupportFoot {
                     support_side: None,
                     changed_this_cycle: false,
+                    center_of_pressure: None,
                 }
                 .into(),
             });
         }
 
-        let left_sum = context.sensor_data.force_sensitive_resistors.left.sum();
-        let right_sum = context.sensor_data.force_sensitive_resistors.right.sum();
+        let force_sensitive_resistors = &context.sensor_data.force_sensitive_resistors;
+        let center_of_pressure = center_of_pressure(
+            &force_sensitive_resistors.left,
+            &force_sensitive_resistors.right,
+            context.robot_kinematics,
+            *context.minimal_pressure,
+        );
 
-        let last_has_left_more_pressure = self.last_support_side == Side::Left;
-        let has_left_more_pressure = greater_than_with_hysteresis(
-            last_has_left_more_pressure,
-            left_sum,
-            right_sum,
+        let support_side = support_side(
+            *context.walk_support_side,
+            self.last_support_side,
+            force_sensitive_resistors,
             *context.hysteresis,
         );
-        let support_side = if has_left_more_pressure {
-            Side::Left
-        } else {
-            Side::Right
-        };
         let changed_this_cycle = support_side != self.last_support_side;
         self.last_support_side = support_side;
         Ok(MainOutputs {
             support_foot: SupportFoot {
                 support_side: Some(support_side),
                 changed_this_cycle,
+                center_of_pressure,
             }
             .into(),
         })
     }
 }
+
+fn support_side(
+    walk_support_side: Option<Side>,
+    last_support_side: Side,
+    force_sensitive_resistors: &ForceSensitiveResistors,
+    hysteresis: f32,
+) -> Side {
+    // while walking, the support exchange is defined by the walking engine's phase to let
+    // in-walk kicks and odometry agree on which foot is carrying the robot
+    if let Some(walk_support_side) = walk_support_side {
+        return walk_support_side;
+    }
+    let has_left_more_pressure = greater_than_with_hysteresis(
+        last_support_side == Side::Left,
+        force_sensitive_resistors.left.sum(),
+        force_sensitive_resistors.right.sum(),
+        hysteresis,
+    );
+    if has_left_more_pressure {
+        Side::Left
+    } else {
+        Side::Right
+    }
+}
+
+fn center_of_pressure(
+    left_foot: &Foot,
+    right_foot: &Foot,
+    robot_kinematics: &RobotKinematics,
+    minimal_pressure: f32,
+) -> Option<Point2<f32>> {
+    let total_pressure = left_foot.sum() + right_foot.sum();
+    if total_pressure < minimal_pressure {
+        return None;
+    }
+    let weighted_sum = weighted_sensor_positions(
+        left_foot,
+        &RobotDimensions::LEFT_SOLE_TO_FORCE_SENSITIVE_RESISTORS,
+        robot_kinematics.left_sole_to_robot,
+    ) + weighted_sensor_positions(
+        right_foot,
+        &RobotDimensions::RIGHT_SOLE_TO_FORCE_SENSITIVE_RESISTORS,
+        robot_kinematics.right_sole_to_robot,
+    );
+    Some(Point2::from(weighted_sum / total_pressure))
+}
+
+fn weighted_sensor_positions(
+    foot: &Foot,
+    sensor_positions: &[Vector2<f32>; 4],
+    sole_to_robot: Isometry3<f32>,
+) -> Vector2<f32> {
+    let pressures = [
+        foot.front_left,
+        foot.front_right,
+        foot.rear_left,
+        foot.rear_right,
+    ];
+    pressures
+        .iter()
+        .zip(sensor_positions.iter())
+        .map(|(pressure, position)| {
+            let position_in_robot = sole_to_robot * point![position.x, position.y, 0.0];
+            position_in_robot.coords.xy() * *pressure
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::vector;
+
+    use super::*;
+
+    fn pressures(left: f32, right: f32) -> ForceSensitiveResistors {
+        let foot = |pressure| Foot {
+            front_left: pressure / 4.0,
+            front_right: pressure / 4.0,
+            rear_left: pressure / 4.0,
+            rear_right: pressure / 4.0,
+        };
+        ForceSensitiveResistors {
+            left: foot(left),
+            right: foot(right),
+        }
+    }
+
+    #[test]
+    fn walking_phase_defines_support_side() {
+        assert_eq!(
+            support_side(Some(Side::Right), Side::Left, &pressures(2.0, 0.0), 0.1),
+            Side::Right
+        );
+        assert_eq!(
+            support_side(Some(Side::Left), Side::Right, &pressures(0.0, 2.0), 0.1),
+            Side::Left
+        );
+    }
+
+    #[test]
+    fn support_side_follows_pressure_with_hysteresis_when_not_walking() {
+        assert_eq!(
+            support_side(None, Side::Left, &pressures(1.0, 1.05), 0.1),
+            Side::Left
+        );
+        assert_eq!(
+            support_side(None, Side::Left, &pressures(1.0, 1.5), 0.1),
+            Side::Right
+        );
+        assert_eq!(
+            support_side(None, Side::Right, &pressures(1.05, 1.0), 0.1),
+            Side::Right
+        );
+    }
+
+    #[test]
+    fn center_of_pressure_is_weighted_by_sensor_pressures() {
+        let robot_kinematics = RobotKinematics::default();
+        let front_of_left_foot = Foot {
+            front_left: 1.0,
+            front_right: 1.0,
+            ..Default::default()
+        };
+
+        let pressure_center = center_of_pressure(
+            &front_of_left_foot,
+            &Foot::default(),
+            &robot_kinematics,
+            0.5,
+        )
+        .unwrap();
+
+        let front_sensors = &RobotDimensions::LEFT_SOLE_TO_FORCE_SENSITIVE_RESISTORS[0..2];
+        let expected = (front_sensors[0] + front_sensors[1]) / 2.0;
+        assert_relative_eq!(pressure_center.coords, vector![expected.x, expected.y]);
+        assert_eq!(
+            center_of_pressure(
+                &front_of_left_foot,
+                &Foot::default(),
+                &robot_kinematics,
+                5.0
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use std::time::SystemTime;
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::{AdditionalOutput, PerceptionInput};
+use log::info;
+use nalgebra::distance;
+use spl_network_messages::PlayerNumber;
+use types::{
+    coordinate_systems::{Field, Ground, Point, Transform},
+    messages::IncomingMessage,
+    parameters::SymmetryFlipDetection,
+    BallPosition, CycleTime, Players,
+};
+
+/// Detects poses mirrored at the field center by comparing the own ball with the ones of teammates
+///
+/// The field is point symmetric, such that a mirrored pose fits the field lines just as well as
+/// the correct one. Each teammate votes for a flip once its balls were close to the mirrored own
+/// ball often enough in a row, a teammate ball close to the own ball withdraws its vote. A flip is
+/// requested if a majority of the recently voting teammates sees the ball mirrored and none agrees
+/// with the own ball. Votes are cleared during the cool-down after a flip, since teammates may have
+/// sent their balls before it.
+pub struct SymmetryFlipDetector {
+    votes: Players<TeammateVotes>,
+    last_flip: Option<SystemTime>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct TeammateVotes {
+    /// Consecutive balls of the teammate close to the mirrored own ball
+    number_of_mirrored_balls: usize,
+    is_agreeing: bool,
+    last_vote: Option<SystemTime>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    pub symmetry_flip_votes: AdditionalOutput<usize, "symmetry_flip_votes">,
+
+    pub cycle_time: Input<CycleTime, "cycle_time">,
+    pub ball_position: Input<Option<BallPosition>, "ball_position?">,
+    pub robot_to_field: Input<Option<Transform<Ground, Field>>, "robot_to_field?">,
+    pub network_message: PerceptionInput<IncomingMessage, "SplNetwork", "message">,
+
+    pub parameters: Parameter<SymmetryFlipDetection, "symmetry_flip_detection">,
+    pub player_number: Parameter<PlayerNumber, "player_number">,
+
+    pub symmetry_flip_requested: PersistentState<bool, "symmetry_flip_requested">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Agreeing,
+    Mirrored,
+    Inconclusive,
+}
+
+impl SymmetryFlipDetector {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            votes: Default::default(),
+            last_flip: None,
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let parameters = context.parameters;
+        let own_ball_in_field = context
+            .robot_to_field
+            .zip(context.ball_position)
+            .filter(|(_, ball)| {
+                now.duration_since(ball.last_seen).unwrap_or_default()
+                    <= parameters.maximum_ball_age
+            })
+            .map(|(robot_to_field, ball)| *robot_to_field * Point::wrap(ball.position));
+        let teammate_balls_in_field = context
+            .network_message
+            .persistent
+            .iter()
+            .flat_map(|(received_at, messages)| {
+                messages.iter().map(move |message| (*received_at, message))
+            })
+            .filter_map(|(received_at, message)| match message {
+                IncomingMessage::GameController(_) => None,
+                IncomingMessage::Spl(message) => Some((received_at, message)),
+            })
+            .filter(|(_, message)| message.player_number != *context.player_number)
+            .filter_map(|(received_at, message)| {
+                let ball = message.ball_position?;
+                (ball.age <= parameters.maximum_ball_age).then(|| {
+                    (
+                        message.player_number,
+                        received_at,
+                        Transform::<Ground, Field>::wrap(message.robot_to_field)
+                            * Point::wrap(ball.relative_position),
+                    )
+                })
+            });
+
+        if self.update(now, own_ball_in_field, teammate_balls_in_field, parameters) {
+            info!("Teammates see the ball mirrored, requesting a symmetry flip");
+            *context.symmetry_flip_requested = true;
+        }
+        context
+            .symmetry_flip_votes
+            .fill_if_subscribed(|| self.number_of_mirrored_teammates(now, parameters));
+
+        Ok(MainOutputs::default())
+    }
+
+    /// Collects the votes of the teammate balls and returns whether a flip is due
+    fn update(
+        &mut self,
+        now: SystemTime,
+        own_ball_in_field: Option<Point<Field>>,
+        teammate_balls_in_field: impl Iterator<Item = (PlayerNumber, SystemTime, Point<Field>)>,
+        parameters: &SymmetryFlipDetection,
+    ) -> bool {
+        let is_cooling_down = self.last_flip.is_some_and(|last_flip| {
+            now.duration_since(last_flip).unwrap_or_default() < parameters.cool_down
+        });
+        if is_cooling_down {
+            self.votes = Default::default();
+            return false;
+        }
+        let Some(own_ball_in_field) = own_ball_in_field else {
+            return false;
+        };
+        for (player_number, received_at, teammate_ball_in_field) in teammate_balls_in_field {
+            let votes = &mut self.votes[player_number];
+            match compare(
+                own_ball_in_field,
+                teammate_ball_in_field,
+                parameters.agreement_distance,
+            ) {
+                Comparison::Agreeing => {
+                    votes.number_of_mirrored_balls = 0;
+                    votes.is_agreeing = true;
+                    votes.last_vote = Some(received_at);
+                }
+                Comparison::Mirrored => {
+                    votes.number_of_mirrored_balls += 1;
+                    votes.is_agreeing = false;
+                    votes.last_vote = Some(received_at);
+                }
+                Comparison::Inconclusive => {}
+            }
+        }
+
+        let recent_votes: Vec<_> = self
+            .votes
+            .iter()
+            .map(|(_, votes)| votes)
+            .filter(|votes| is_recent(votes, now, parameters))
+            .collect();
+        let is_any_teammate_agreeing = recent_votes.iter().any(|votes| votes.is_agreeing);
+        let number_of_mirrored_teammates = self.number_of_mirrored_teammates(now, parameters);
+        let is_flip_due = !is_any_teammate_agreeing
+            && number_of_mirrored_teammates >= parameters.minimum_number_of_mirrored_teammates
+            && 2 * number_of_mirrored_teammates > recent_votes.len();
+        if is_flip_due {
+            self.last_flip = Some(now);
+            self.votes = Default::default();
+        }
+        is_flip_due
+    }
+
+    fn number_of_mirrored_teammates(
+        &self,
+        now: SystemTime,
+        parameters: &SymmetryFlipDetection,
+    ) -> usize {
+        self.votes
+            .iter()
+            .filter(|(_, votes)| {
+                is_recent(votes, now, parameters)
+                    && votes.number_of_mirrored_balls >= parameters.minimum_number_of_votes
+            })
+            .count()
+    }
+}
+
+fn is_recent(votes: &TeammateVotes, now: SystemTime, parameters: &SymmetryFlipDetection) -> bool {
+    votes.last_vote.is_some_and(|last_vote| {
+        now.duration_since(last_vote).unwrap_or_default() <= parameters.maximum_vote_age
+    })
+}
+
+/// Balls close to the field center agree in both cases and are never counted as mirrored
+fn compare(
+    own_ball_in_field: Point<Field>,
+    teammate_ball_in_field: Point<Field>,
+    agreement_distance: f32,
+) -> Comparison {
+    let (own_ball_in_field, teammate_ball_in_field) =
+        (own_ball_in_field.inner, teammate_ball_in_field.inner);
+    let mirrored_own_ball_in_field = -own_ball_in_field;
+    if distance(&own_ball_in_field, &teammate_ball_in_field) <= agreement_distance {
+        Comparison::Agreeing
+    } else if distance(&mirrored_own_ball_in_field, &teammate_ball_in_field) <= agreement_distance {
+        Comparison::Mirrored
+    } else {
+        Comparison::Inconclusive
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use nalgebra::{point, Point2};
+
+    use super::*;
+
+    #[test]
+    fn mirrored_balls_are_detected() {
+        let own_ball = Point::wrap(point![2.0, 1.0]);
+
+        assert_eq!(
+            compare(own_ball, Point::wrap(point![2.2, 0.9]), 0.5),
+            Comparison::Agreeing
+        );
+        assert_eq!(
+            compare(own_ball, Point::wrap(point![-2.1, -1.2]), 0.5),
+            Comparison::Mirrored
+        );
+        assert_eq!(
+            compare(own_ball, Point::wrap(point![0.0, 3.0]), 0.5),
+            Comparison::Inconclusive
+        );
+    }
+
+    #[test]
+    fn balls_at_center_are_never_mirrored() {
+        assert_eq!(
+            compare(
+                Point::wrap(point![0.1, 0.0]),
+                Point::wrap(point![-0.1, 0.0]),
+                0.5
+            ),
+            Comparison::Agreeing
+        );
+    }
+
+    const OWN_BALL: Point<Field> = Point::wrap(Point2::new(2.0, 1.0));
+    const MIRRORED_BALL: Point<Field> = Point::wrap(Point2::new(-2.0, -1.0));
+
+    fn parameters() -> SymmetryFlipDetection {
+        SymmetryFlipDetection {
+            maximum_ball_age: Duration::from_millis(500),
+            agreement_distance: 0.5,
+            minimum_number_of_votes: 2,
+            minimum_number_of_mirrored_teammates: 2,
+            maximum_vote_age: Duration::from_secs(5),
+            cool_down: Duration::from_secs(10),
+        }
+    }
+
+    fn at(seconds: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    fn detector() -> SymmetryFlipDetector {
+        SymmetryFlipDetector::new(CreationContext {}).unwrap()
+    }
+
+    #[test]
+    fn repeated_votes_of_a_single_teammate_do_not_flip() {
+        let mut detector = detector();
+        let parameters = parameters();
+
+        for second in 0..10 {
+            let balls = [(PlayerNumber::Two, at(second), MIRRORED_BALL)];
+            assert!(!detector.update(at(second), Some(OWN_BALL), balls.into_iter(), &parameters));
+        }
+    }
+
+    #[test]
+    fn majority_of_mirrored_teammates_flips() {
+        let mut detector = detector();
+        let parameters = parameters();
+        let balls = |second| {
+            [
+                (PlayerNumber::Two, at(second), MIRRORED_BALL),
+                (PlayerNumber::Three, at(second), MIRRORED_BALL),
+                (
+                    PlayerNumber::Four,
+                    at(second),
+                    Point::wrap(point![0.0, 3.0]),
+                ),
+            ]
+        };
+
+        assert!(!detector.update(at(0), Some(OWN_BALL), balls(0).into_iter(), &parameters));
+        assert!(detector.update(at(1), Some(OWN_BALL), balls(1).into_iter(), &parameters));
+    }
+
+    #[test]
+    fn agreeing_teammate_prevents_flip() {
+        let mut detector = detector();
+        let parameters = parameters();
+        let balls = |second| {
+            [
+                (PlayerNumber::Two, at(second), MIRRORED_BALL),
+                (PlayerNumber::Three, at(second), MIRRORED_BALL),
+                (PlayerNumber::Four, at(second), MIRRORED_BALL),
+                (
+                    PlayerNumber::Five,
+                    at(second),
+                    Point::wrap(point![2.1, 1.1]),
+                ),
+            ]
+        };
+
+        for second in 0..5 {
+            assert!(!detector.update(
+                at(second),
+                Some(OWN_BALL),
+                balls(second).into_iter(),
+                &parameters
+            ));
+        }
+    }
+
+    #[test]
+    fn outdated_votes_are_not_counted() {
+        let mut detector = detector();
+        let parameters = parameters();
+        let balls = |player_number, second| [(player_number, at(second), MIRRORED_BALL)];
+
+        detector.update(
+            at(0),
+            Some(OWN_BALL),
+            balls(PlayerNumber::Two, 0).into_iter(),
+            &parameters,
+        );
+        detector.update(
+            at(1),
+            Some(OWN_BALL),
+            balls(PlayerNumber::Two, 1).into_iter(),
+            &parameters,
+        );
+        detector.update(
+            at(10),
+            Some(OWN_BALL),
+            balls(PlayerNumber::Three, 10).into_iter(),
+            &parameters,
+        );
+
+        assert!(!detector.update(
+            at(11),
+            Some(OWN_BALL),
+            balls(PlayerNumber::Three, 11).into_iter(),
+            &parameters
+        ));
+    }
+
+    #[test]
+    fn votes_are_ignored_during_cool_down() {
+        let mut detector = detector();
+        let parameters = parameters();
+        let balls = |second| {
+            [
+                (PlayerNumber::Two, at(second), MIRRORED_BALL),
+                (PlayerNumber::Three, at(second), MIRRORED_BALL),
+            ]
+        };
+
+        detector.update(at(0), Some(OWN_BALL), balls(0).into_iter(), &parameters);
+        assert!(detector.update(at(1), Some(OWN_BALL), balls(1).into_iter(), &parameters));
+        for second in 2..11 {
+            assert!(!detector.update(
+                at(second),
+                Some(OWN_BALL),
+                balls(second).into_iter(),
+                &parameters
+            ));
+        }
+        assert!(!detector.update(at(11), Some(OWN_BALL), balls(11).into_iter(), &parameters));
+        assert!(detector.update(at(12), Some(OWN_BALL), balls(12).into_iter(), &parameters));
+    }
+}
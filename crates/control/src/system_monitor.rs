@@ -0,0 +1,124 @@
+use std::{collections::VecDeque, time::Duration};
+
+use color_eyre::Result;
+use context_attribute::context;
+use filtering::hysteresis::{greater_than_with_hysteresis, less_than_with_hysteresis};
+use framework::{AdditionalOutput, MainOutput};
+use types::{Battery, CycleTime, SensorData, SystemMonitorSample, SystemWarning};
+
+pub struct SystemMonitor {
+    is_battery_low: bool,
+    is_battery_critical: bool,
+    is_joint_temperature_high: bool,
+    history: VecDeque<SystemMonitorSample>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    pub history: AdditionalOutput<Vec<SystemMonitorSample>, "system_monitor.history">,
+
+    pub cycle_time: Input<CycleTime, "cycle_time">,
+    pub sensor_data: Input<SensorData, "sensor_data">,
+
+    pub battery_charge_hysteresis: Parameter<f32, "system_monitor.battery_charge_hysteresis">,
+    pub critical_battery_charge: Parameter<f32, "system_monitor.critical_battery_charge">,
+    pub history_length: Parameter<usize, "system_monitor.history_length">,
+    pub history_sample_interval: Parameter<Duration, "system_monitor.history_sample_interval">,
+    pub low_battery_charge: Parameter<f32, "system_monitor.low_battery_charge">,
+    pub maximum_joint_temperature: Parameter<f32, "system_monitor.maximum_joint_temperature">,
+    pub joint_temperature_hysteresis: Parameter<f32, "system_monitor.joint_temperature_hysteresis">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub battery: MainOutput<Option<Battery>>,
+    pub maximum_joint_temperature: MainOutput<f32>,
+    pub system_warnings: MainOutput<Vec<SystemWarning>>,
+}
+
+impl SystemMonitor {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            is_battery_low: false,
+            is_battery_critical: false,
+            is_joint_temperature_high: false,
+            history: VecDeque::new(),
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let battery = context.sensor_data.battery;
+        let maximum_joint_temperature = context
+            .sensor_data
+            .temperature_sensors
+            .as_vec()
+            .into_iter()
+            .flatten()
+            .fold(0.0, f32::max);
+
+        if let Some(battery) = battery {
+            self.is_battery_low = less_than_with_hysteresis(
+                self.is_battery_low,
+                battery.charge,
+                *context.low_battery_charge,
+                *context.battery_charge_hysteresis,
+            );
+            self.is_battery_critical = less_than_with_hysteresis(
+                self.is_battery_critical,
+                battery.charge,
+                *context.critical_battery_charge,
+                *context.battery_charge_hysteresis,
+            );
+        }
+        self.is_joint_temperature_high = greater_than_with_hysteresis(
+            self.is_joint_temperature_high,
+            maximum_joint_temperature,
+            *context.maximum_joint_temperature,
+            *context.joint_temperature_hysteresis,
+        );
+
+        let system_warnings: Vec<_> = [
+            (self.is_battery_low, SystemWarning::LowBattery),
+            (self.is_battery_critical, SystemWarning::CriticalBattery),
+            (
+                self.is_joint_temperature_high,
+                SystemWarning::HighJointTemperature,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(is_active, warning)| is_active.then_some(warning))
+        .collect();
+
+        let now = context.cycle_time.start_time;
+        let is_sample_due = self.history.back().map_or(true, |last_sample| {
+            now.duration_since(last_sample.timestamp)
+                .map_or(true, |duration| {
+                    duration >= *context.history_sample_interval
+                })
+        });
+        if is_sample_due {
+            self.history.push_back(SystemMonitorSample {
+                timestamp: now,
+                battery_charge: battery.map(|battery| battery.charge),
+                battery_current: battery.map(|battery| battery.current),
+                maximum_joint_temperature,
+            });
+            while self.history.len() > *context.history_length {
+                self.history.pop_front();
+            }
+        }
+        context
+            .history
+            .fill_if_subscribed(|| self.history.iter().copied().collect());
+
+        Ok(MainOutputs {
+            battery: battery.into(),
+            maximum_joint_temperature: maximum_joint_temperature.into(),
+            system_warnings: system_warnings.into(),
+        })
+    }
+}
@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use nalgebra::{Isometry2, UnitComplex, Vector2};
+use types::{parameters::PathPlanning, PathSegment};
+
+/// Estimates the duration to walk along `path` and end up in `target_pose`, both in robot
+/// coordinates
+///
+/// The path is expected to be planned around obstacles already. Turning towards the path at the
+/// start and towards the target orientation at the end is added, as well as the time to get up
+/// if the robot is currently standing up.
+pub fn time_to_reach(
+    path: &[PathSegment],
+    target_pose: Isometry2<f32>,
+    stand_up_duration: Duration,
+    parameters: &PathPlanning,
+) -> Duration {
+    let walk_time: f32 = path
+        .iter()
+        .map(|segment| match segment {
+            PathSegment::LineSegment(_) => segment.length() / parameters.line_walking_speed,
+            PathSegment::Arc(_, _) => segment.length() / parameters.arc_walking_speed,
+        })
+        .sum();
+
+    let initial_turn = path
+        .first()
+        .and_then(|segment| rotation_of(segment.tangent_at(0.0)))
+        .map_or(0.0, |direction| direction.angle().abs());
+    let final_turn = path
+        .last()
+        .and_then(|segment| rotation_of(segment.tangent_at(1.0)))
+        .map_or(0.0, |direction| {
+            direction.angle_to(&target_pose.rotation).abs()
+        });
+    let turn_time = (initial_turn + final_turn) / parameters.turning_speed;
+
+    Duration::from_secs_f32(walk_time + turn_time).saturating_add(stand_up_duration)
+}
+
+fn rotation_of(direction: Vector2<f32>) -> Option<UnitComplex<f32>> {
+    (direction.norm_squared() > f32::EPSILON)
+        .then(|| UnitComplex::rotation_between(&Vector2::x(), &direction))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use approx::assert_relative_eq;
+    use nalgebra::{point, vector, Point2};
+    use types::LineSegment;
+
+    use super::*;
+
+    fn parameters() -> PathPlanning {
+        PathPlanning {
+            line_walking_speed: 0.25,
+            arc_walking_speed: 0.2,
+            turning_speed: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn straight_walk_without_turning() {
+        let path = [PathSegment::LineSegment(LineSegment(
+            Point2::origin(),
+            point![1.0, 0.0],
+        ))];
+
+        let duration = time_to_reach(
+            &path,
+            Isometry2::new(vector![1.0, 0.0], 0.0),
+            Duration::ZERO,
+            &parameters(),
+        );
+
+        assert_relative_eq!(duration.as_secs_f32(), 4.0, epsilon = 0.001);
+    }
+
+    #[test]
+    fn turning_and_standing_up_take_time() {
+        let path = [PathSegment::LineSegment(LineSegment(
+            Point2::origin(),
+            point![0.0, 1.0],
+        ))];
+
+        let duration = time_to_reach(
+            &path,
+            Isometry2::new(vector![0.0, 1.0], 0.0),
+            Duration::from_secs(2),
+            &parameters(),
+        );
+
+        assert_relative_eq!(
+            duration.as_secs_f32(),
+            4.0 + 2.0 * FRAC_PI_2 + 2.0,
+            epsilon = 0.001
+        );
+    }
+}
@@ -1,16 +1,17 @@
 use color_eyre::Result;
-use framework::AdditionalOutput;
-use types::{parameters::Behavior, PathSegment};
+use framework::MainOutput;
+use types::{parameters::Behavior, KickDecision, PathSegment};
 
 use std::time::Duration;
 
 use context_attribute::context;
+
+use crate::time_to_reach::time_to_reach;
+
 #[context]
 pub struct CycleContext {
     pub dribble_path: Input<Option<Vec<PathSegment>>, "dribble_path?">,
-
-    pub time_to_reach_kick_position_output:
-        AdditionalOutput<Option<Duration>, "time_to_reach_kick_position_output">,
+    pub kick_decisions: Input<Option<Vec<KickDecision>>, "kick_decisions?">,
 
     pub time_to_reach_kick_position: PersistentState<Duration, "time_to_reach_kick_position">,
 
@@ -26,7 +27,10 @@ pub struct CycleContext {
 pub struct CreationContext {}
 
 #[context]
-pub struct MainOutputs {}
+#[derive(Default)]
+pub struct MainOutputs {
+    pub time_to_reach_kick_position: MainOutput<Option<Duration>>,
+}
 
 pub struct TimeToReachKickPosition {}
 
@@ -35,49 +39,39 @@ impl TimeToReachKickPosition {
         Ok(Self {})
     }
 
-    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
-        let walk_time = context
-            .dribble_path
-            .as_ref()
-            .map(|path| {
-                path.iter()
-                    .map(|segment: &PathSegment| {
-                        let length = segment.length();
-                        match segment {
-                            PathSegment::LineSegment(_) => {
-                                length / context.configuration.path_planning.line_walking_speed
-                            }
-                            PathSegment::Arc(_, _) => {
-                                length / context.configuration.path_planning.arc_walking_speed
-                            }
-                        }
-                    })
-                    .sum()
-            })
-            .map(Duration::from_secs_f32);
-        let time_to_reach_kick_position = walk_time.map(|walk_time| {
-            [
-                walk_time,
-                *context
-                    .stand_up_back_estimated_remaining_duration
-                    .unwrap_or(&Duration::ZERO),
-                *context
-                    .stand_up_front_estimated_remaining_duration
-                    .unwrap_or(&Duration::ZERO),
-            ]
-            .into_iter()
-            .fold(Duration::ZERO, Duration::saturating_add)
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let stand_up_duration = [
+            context.stand_up_back_estimated_remaining_duration,
+            context.stand_up_front_estimated_remaining_duration,
+        ]
+        .into_iter()
+        .flatten()
+        .fold(Duration::ZERO, |sum, duration| {
+            sum.saturating_add(*duration)
         });
+        let kick_pose = context
+            .kick_decisions
+            .and_then(|kick_decisions| kick_decisions.first())
+            .map(|kick_decision| kick_decision.kick_pose);
+        let time_to_reach_kick_position = match (context.dribble_path, kick_pose) {
+            (Some(path), Some(kick_pose)) => Some(time_to_reach(
+                path,
+                kick_pose,
+                stand_up_duration,
+                &context.configuration.path_planning,
+            )),
+            _ => None,
+        };
 
-        context
-            .time_to_reach_kick_position_output
-            .fill_if_subscribed(|| time_to_reach_kick_position);
         // 1800 seconds is 30 minutes, which is essentially maximum as it pertains to game time.
         // Prevents Duration::MAX from breaking the behavior simulator.
+        // The role assignment runs before this node and therefore reads last cycle's estimate.
         *context.time_to_reach_kick_position = time_to_reach_kick_position
             .unwrap_or(Duration::MAX)
             .min(Duration::from_secs(1800));
 
-        Ok(MainOutputs {})
+        Ok(MainOutputs {
+            time_to_reach_kick_position: time_to_reach_kick_position.into(),
+        })
     }
 }
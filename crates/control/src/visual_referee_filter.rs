@@ -7,18 +7,19 @@ use context_attribute::context;
 use hardware::NetworkInterface;
 use spl_network_messages::VisualRefereeDecision;
 use spl_network_messages::{PlayerNumber, VisualRefereeMessage};
-use types::{messages::OutgoingMessage, CycleTime, FilteredWhistle, PrimaryState};
+use types::{
+    messages::OutgoingMessage, CycleTime, FilteredWhistle, PrimaryState, PrimaryStateTransition,
+};
 
-pub struct VisualRefereeFilter {
-    last_primary_state: PrimaryState,
-}
+pub struct VisualRefereeFilter {}
 
 #[context]
 pub struct CreationContext {}
 
 #[context]
 pub struct CycleContext {
-    pub primary_state: Input<PrimaryState, "primary_state">,
+    pub primary_state_transition:
+        Input<Option<PrimaryStateTransition>, "primary_state_transition?">,
     pub cycle_time: Input<CycleTime, "cycle_time">,
     pub filtered_whistle: Input<FilteredWhistle, "filtered_whistle">,
     pub player_number: Parameter<PlayerNumber, "player_number">,
@@ -32,21 +33,20 @@ pub struct MainOutputs {}
 
 impl VisualRefereeFilter {
     pub fn new(_context: CreationContext) -> Result<Self> {
-        Ok(Self {
-            last_primary_state: PrimaryState::Unstiff,
-        })
+        Ok(Self {})
     }
 
     pub fn cycle(&mut self, context: CycleContext<impl NetworkInterface>) -> Result<MainOutputs> {
         let send_visual_referee_message = matches!(
-            (self.last_primary_state, *context.primary_state),
-            (PrimaryState::Set, PrimaryState::Playing)
-                | (
-                    PrimaryState::Playing,
-                    PrimaryState::Finished | PrimaryState::Ready
-                )
+            context.primary_state_transition,
+            Some(PrimaryStateTransition {
+                from: PrimaryState::Set,
+                to: PrimaryState::Playing,
+            }) | Some(PrimaryStateTransition {
+                from: PrimaryState::Playing,
+                to: PrimaryState::Finished | PrimaryState::Ready,
+            })
         );
-        self.last_primary_state = *context.primary_state;
 
         // Initially a random visual referee decision
         let mut rng = thread_rng();
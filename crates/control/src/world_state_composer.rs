@@ -1,14 +1,26 @@
+use std::time::SystemTime;
+
 use color_eyre::Result;
 use context_attribute::context;
 use framework::MainOutput;
-use nalgebra::{Isometry2, Point2};
-use spl_network_messages::PlayerNumber;
+use nalgebra::Point2;
+use spl_network_messages::{Intention, PlayerNumber};
 use types::{
-    BallState, FallState, FilteredGameState, GameControllerState, KickDecision, Obstacle,
-    PenaltyShotDirection, PrimaryState, RobotState, Role, RuleObstacle, WorldState,
+    coordinate_systems::{Field, Ground, Pose, Transform},
+    localization::LocalizationQuality,
+    parameters::WorldStateComposer as WorldStateComposerParameters,
+    BallState, BlindBallContact, CycleTime, FallState, FilteredGameState, GameControllerFreshness,
+    GameControllerState, InputProvenance, KickDecision, Obstacle, PenaltyShotDirection, Players,
+    PrimaryState, RobotState, Role, RuleObstacle, SystemWarning, WorldState, WorldStateProvenance,
 };
 
-pub struct WorldStateComposer {}
+/// Assembles the world state for behavior and annotates when each of its inputs was last updated
+///
+/// Inputs missing in a cycle keep the time of their last update, such that they become stale.
+pub struct WorldStateComposer {
+    last_localization_measurement: Option<SystemTime>,
+    last_game_state_update: Option<SystemTime>,
+}
 
 #[context]
 pub struct CreationContext {
@@ -19,15 +31,21 @@ pub struct CreationContext {
 pub struct CycleContext {
     pub ball: Input<Option<BallState>, "ball_state?">,
     pub rule_ball: Input<Option<BallState>, "rule_ball_state?">,
+    pub blind_ball_contact: Input<Option<BlindBallContact>, "blind_ball_contact?">,
     pub filtered_game_state: Input<Option<FilteredGameState>, "filtered_game_state?">,
     pub game_controller_state: Input<Option<GameControllerState>, "game_controller_state?">,
     pub penalty_shot_direction: Input<Option<PenaltyShotDirection>, "penalty_shot_direction?">,
-    pub robot_to_field: Input<Option<Isometry2<f32>>, "robot_to_field?">,
+    pub robot_to_field: Input<Option<Transform<Ground, Field>>, "robot_to_field?">,
+    pub localization_quality: Input<Option<LocalizationQuality>, "localization_quality?">,
     pub kick_decisions: Input<Option<Vec<KickDecision>>, "kick_decisions?">,
+    pub last_obstacle_measurement: Input<Option<SystemTime>, "last_obstacle_measurement?">,
+    pub last_role_update: Input<Option<SystemTime>, "last_role_update?">,
     pub instant_kick_decisions: Input<Option<Vec<KickDecision>>, "instant_kick_decisions?">,
 
     pub player_number: Parameter<PlayerNumber, "player_number">,
+    pub parameters: Parameter<WorldStateComposerParameters, "world_state_composer">,
 
+    pub cycle_time: Input<CycleTime, "cycle_time">,
     pub fall_state: Input<FallState, "fall_state">,
     pub has_ground_contact: Input<bool, "has_ground_contact">,
     pub obstacles: Input<Vec<Obstacle>, "obstacles">,
@@ -35,6 +53,9 @@ pub struct CycleContext {
     pub primary_state: Input<PrimaryState, "primary_state">,
     pub role: Input<Role, "role">,
     pub position_of_interest: Input<Point2<f32>, "position_of_interest">,
+    pub system_warnings: Input<Vec<SystemWarning>, "system_warnings">,
+    pub teammate_intentions: Input<Players<Option<Intention>>, "teammate_intentions">,
+    pub teammate_poses: Input<Players<Option<Pose<Field>>>, "teammate_poses">,
 }
 
 #[context]
@@ -45,22 +66,30 @@ pub struct MainOutputs {
 
 impl WorldStateComposer {
     pub fn new(_context: CreationContext) -> Result<Self> {
-        Ok(Self {})
+        Ok(Self {
+            last_localization_measurement: None,
+            last_game_state_update: None,
+        })
     }
 
     pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let provenance = self.update_provenance(&context);
+
         let robot = RobotState {
             robot_to_field: context.robot_to_field.copied(),
+            localization_quality: context.localization_quality.copied(),
             role: *context.role,
             primary_state: *context.primary_state,
             fall_state: *context.fall_state,
             has_ground_contact: *context.has_ground_contact,
             player_number: *context.player_number,
+            system_warnings: context.system_warnings.clone(),
         };
 
         let world_state = WorldState {
             ball: context.ball.copied(),
             rule_ball: context.rule_ball.copied(),
+            blind_ball_contact: context.blind_ball_contact.copied(),
             filtered_game_state: context.filtered_game_state.copied(),
             obstacles: context.obstacles.clone(),
             rule_obstacles: context.rule_obstacles.clone(),
@@ -69,10 +98,109 @@ impl WorldStateComposer {
             kick_decisions: context.kick_decisions.cloned(),
             instant_kick_decisions: context.instant_kick_decisions.cloned(),
             game_controller_state: context.game_controller_state.copied(),
+            teammate_intentions: *context.teammate_intentions,
+            teammate_poses: *context.teammate_poses,
+            provenance,
         };
 
         Ok(MainOutputs {
             world_state: world_state.into(),
         })
     }
+
+    fn update_provenance(&mut self, context: &CycleContext) -> WorldStateProvenance {
+        let now = context.cycle_time.start_time;
+        let parameters = context.parameters;
+
+        if context.robot_to_field.is_some() {
+            self.last_localization_measurement =
+                last_localization_measurement(context.localization_quality, now);
+        }
+        let game_controller_is_fresh = context.game_controller_state.map_or(true, |state| {
+            state.freshness == GameControllerFreshness::Fresh
+        });
+        if context.filtered_game_state.is_some() && game_controller_is_fresh {
+            self.last_game_state_update = Some(now);
+        }
+
+        WorldStateProvenance {
+            ball: InputProvenance::new(
+                context.ball.map(|ball| ball.last_seen),
+                now,
+                parameters.maximum_ball_age,
+            ),
+            robot_to_field: InputProvenance::new(
+                self.last_localization_measurement,
+                now,
+                parameters.maximum_localization_age,
+            ),
+            obstacles: InputProvenance::new(
+                context.last_obstacle_measurement.copied(),
+                now,
+                parameters.maximum_obstacle_age,
+            ),
+            role: InputProvenance::new(
+                context.last_role_update.copied(),
+                now,
+                parameters.maximum_role_age,
+            ),
+            filtered_game_state: InputProvenance::new(
+                self.last_game_state_update,
+                now,
+                parameters.maximum_game_state_age,
+            ),
+        }
+    }
+}
+
+/// Without localization quality the pose is assumed to be measured in this cycle
+fn last_localization_measurement(
+    localization_quality: Option<&LocalizationQuality>,
+    now: SystemTime,
+) -> Option<SystemTime> {
+    match localization_quality {
+        Some(quality) => quality
+            .time_since_last_measurement
+            .and_then(|age| now.checked_sub(age)),
+        None => Some(now),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use nalgebra::Vector3;
+
+    use super::*;
+
+    #[test]
+    fn inputs_are_stale_after_maximum_age() {
+        let now = UNIX_EPOCH + Duration::from_secs(10);
+        let maximum_age = Duration::from_secs(2);
+
+        assert!(
+            !InputProvenance::new(Some(now - Duration::from_secs(1)), now, maximum_age).is_stale
+        );
+        assert!(
+            InputProvenance::new(Some(now - Duration::from_secs(3)), now, maximum_age).is_stale
+        );
+        assert!(InputProvenance::new(None, now, maximum_age).is_stale);
+    }
+
+    #[test]
+    fn localization_is_dated_back_to_last_measurement() {
+        let now = UNIX_EPOCH + Duration::from_secs(10);
+        let quality = LocalizationQuality {
+            pose_covariance_eigenvalues: Vector3::zeros(),
+            time_since_last_measurement: Some(Duration::from_secs(4)),
+            is_reliable: false,
+        };
+
+        assert_eq!(
+            last_localization_measurement(Some(&quality), now),
+            Some(UNIX_EPOCH + Duration::from_secs(6))
+        );
+        assert_eq!(last_localization_measurement(None, now), Some(now));
+    }
 }
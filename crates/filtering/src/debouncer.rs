@@ -0,0 +1,59 @@
+use std::time::{Duration, SystemTime};
+
+/// Reports a change of a boolean signal only after the signal kept its new value for a minimum
+/// duration, e.g. to ignore short flickers of sensors or detections
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Debouncer {
+    state: bool,
+    pending_since: Option<SystemTime>,
+}
+
+impl Debouncer {
+    pub fn new(initial_state: bool) -> Self {
+        Self {
+            state: initial_state,
+            pending_since: None,
+        }
+    }
+
+    pub fn update(&mut self, value: bool, now: SystemTime, minimum_duration: Duration) -> bool {
+        if value == self.state {
+            self.pending_since = None;
+            return self.state;
+        }
+        let pending_since = *self.pending_since.get_or_insert(now);
+        let is_stable = now
+            .duration_since(pending_since)
+            .map_or(false, |duration| duration >= minimum_duration);
+        if is_stable {
+            self.state = value;
+            self.pending_since = None;
+        }
+        self.state
+    }
+
+    pub fn state(&self) -> bool {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use super::*;
+
+    #[test]
+    fn short_flickers_are_ignored() {
+        let minimum_duration = Duration::from_millis(100);
+        let mut debouncer = Debouncer::new(false);
+        let at = |milliseconds| UNIX_EPOCH + Duration::from_millis(milliseconds);
+
+        assert!(!debouncer.update(true, at(0), minimum_duration));
+        assert!(!debouncer.update(false, at(50), minimum_duration));
+        assert!(!debouncer.update(true, at(60), minimum_duration));
+        assert!(!debouncer.update(true, at(140), minimum_duration));
+        assert!(debouncer.update(true, at(160), minimum_duration));
+        assert!(debouncer.update(false, at(170), minimum_duration));
+    }
+}
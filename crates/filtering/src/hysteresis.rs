@@ -27,3 +27,47 @@ pub fn less_than_with_hysteresis(
                 -hysteresis
             }
 }
+
+/// Tracks whether a value exceeds a threshold, switching only once the value crossed the threshold
+/// by more than the hysteresis
+#[derive(Clone, Copy, Debug)]
+pub struct HysteresisThreshold {
+    threshold: f32,
+    hysteresis: f32,
+    is_above: bool,
+}
+
+impl HysteresisThreshold {
+    pub fn new(threshold: f32, hysteresis: f32) -> Self {
+        Self {
+            threshold,
+            hysteresis,
+            is_above: false,
+        }
+    }
+
+    pub fn update(&mut self, value: f32) -> bool {
+        self.is_above =
+            greater_than_with_hysteresis(self.is_above, value, self.threshold, self.hysteresis);
+        self.is_above
+    }
+
+    pub fn is_above(&self) -> bool {
+        self.is_above
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn threshold_switches_only_beyond_hysteresis() {
+        let mut threshold = HysteresisThreshold::new(1.0, 0.1);
+
+        assert!(!threshold.update(1.05));
+        assert!(threshold.update(1.15));
+        assert!(threshold.update(0.95));
+        assert!(!threshold.update(0.85));
+    }
+}
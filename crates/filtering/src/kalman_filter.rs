@@ -51,3 +51,92 @@ impl<const STATE_DIMENSION: usize> KalmanFilter<STATE_DIMENSION>
         self.covariance -= kalman_gain * measurement_prediction * self.covariance;
     }
 }
+
+/// Kalman filter of a single value whose dynamics are unknown, e.g. a slowly drifting offset
+///
+/// Equivalent to the one-dimensional [`KalmanFilter`] with identity state prediction and
+/// measurement model, without the overhead of matrices.
+#[derive(Clone, Copy, Debug)]
+pub struct ScalarKalmanFilter {
+    pub mean: f32,
+    pub variance: f32,
+}
+
+impl ScalarKalmanFilter {
+    pub fn new(mean: f32, variance: f32) -> Self {
+        Self { mean, variance }
+    }
+
+    pub fn predict(&mut self, process_noise: f32) {
+        self.variance += process_noise;
+    }
+
+    pub fn update(&mut self, measurement: f32, measurement_noise: f32) {
+        let kalman_gain = self.variance / (self.variance + measurement_noise);
+        self.mean += kalman_gain * (measurement - self.mean);
+        self.variance -= kalman_gain * self.variance;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::{matrix, vector, Matrix1, Matrix2, Vector1};
+
+    use super::*;
+
+    #[test]
+    fn scalar_filter_agrees_with_one_dimensional_filter() {
+        let mut scalar = ScalarKalmanFilter::new(0.0, 1.0);
+        let mut distribution = MultivariateNormalDistribution {
+            mean: Vector1::new(0.0),
+            covariance: Matrix1::new(1.0),
+        };
+
+        for measurement in [1.0, 1.2, 0.8] {
+            scalar.predict(0.01);
+            scalar.update(measurement, 0.5);
+            distribution.predict(
+                Matrix1::identity(),
+                Matrix1::zeros(),
+                Vector1::zeros(),
+                Matrix1::new(0.01),
+            );
+            distribution.update(
+                Matrix1::identity(),
+                Vector1::new(measurement),
+                Matrix1::new(0.5),
+            );
+        }
+
+        assert_relative_eq!(scalar.mean, distribution.mean.x, epsilon = 1e-6);
+        assert_relative_eq!(scalar.variance, distribution.covariance.x, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn constant_velocity_is_estimated_from_positions() {
+        let time_step = 0.1;
+        let state_prediction = matrix![1.0, time_step; 0.0, 1.0];
+        let mut distribution = MultivariateNormalDistribution {
+            mean: vector![0.0, 0.0],
+            covariance: Matrix2::identity(),
+        };
+
+        for step in 1..=50 {
+            distribution.predict(
+                state_prediction,
+                Matrix2::zeros(),
+                vector![0.0, 0.0],
+                Matrix2::identity() * 1e-4,
+            );
+            let position = 0.5 * step as f32 * time_step;
+            distribution.update(
+                matrix![1.0, 0.0],
+                Vector1::new(position),
+                Matrix1::new(1e-3),
+            );
+        }
+
+        assert_relative_eq!(distribution.mean.y, 0.5, epsilon = 0.05);
+    }
+}
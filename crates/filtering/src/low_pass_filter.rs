@@ -1,6 +1,7 @@
 use std::{
     f32::consts::PI,
     ops::{Add, Mul, Sub},
+    time::Duration,
 };
 
 use serde::{Deserialize, Serialize};
@@ -44,3 +45,80 @@ where
         self.state = state;
     }
 }
+
+/// Low-pass filter parametrized by a time constant instead of a per-cycle smoothing factor
+///
+/// The smoothing factor is derived from the time elapsed since the last update, such that the
+/// filter responds the same regardless of the cycle rate. After one time constant, the state
+/// covers about 63 % of a step in the input.
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct ExponentialMovingAverage<State> {
+    time_constant: Duration,
+    state: State,
+}
+
+impl<State> ExponentialMovingAverage<State>
+where
+    State: Copy + Add<Output = State> + Sub<Output = State> + Mul<f32, Output = State>,
+{
+    pub fn new(initial_state: State, time_constant: Duration) -> Self {
+        Self {
+            time_constant,
+            state: initial_state,
+        }
+    }
+
+    pub fn update(&mut self, value: State, elapsed: Duration) {
+        let smoothing_factor = if self.time_constant.is_zero() {
+            1.0
+        } else {
+            1.0 - (-elapsed.as_secs_f32() / self.time_constant.as_secs_f32()).exp()
+        };
+        self.state = self.state + (value - self.state) * smoothing_factor;
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn reset(&mut self, state: State) {
+        self.state = state;
+    }
+
+    pub fn set_time_constant(&mut self, time_constant: Duration) {
+        self.time_constant = time_constant;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn moving_average_is_independent_of_cycle_rate() {
+        let time_constant = Duration::from_millis(100);
+        let mut fast = ExponentialMovingAverage::new(0.0, time_constant);
+        let mut slow = ExponentialMovingAverage::new(0.0, time_constant);
+
+        for _ in 0..100 {
+            fast.update(1.0, Duration::from_millis(1));
+        }
+        for _ in 0..10 {
+            slow.update(1.0, Duration::from_millis(10));
+        }
+
+        assert_relative_eq!(fast.state(), slow.state(), epsilon = 1e-4);
+        assert_relative_eq!(fast.state(), 1.0 - (-1.0_f32).exp(), epsilon = 1e-4);
+    }
+
+    #[test]
+    fn moving_average_without_time_constant_follows_input() {
+        let mut average = ExponentialMovingAverage::new(0.0, Duration::ZERO);
+
+        average.update(2.0, Duration::from_millis(12));
+
+        assert_eq!(average.state(), 2.0);
+    }
+}
@@ -15,3 +15,14 @@ pub fn variance(data: &[f32], mean: f32) -> f32 {
 pub fn standard_deviation(data: &[f32], mean: f32) -> f32 {
     variance(data, mean).sqrt()
 }
+
+pub fn median(data: &[f32]) -> f32 {
+    let mut sorted = data.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let middle = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+    } else {
+        sorted[middle]
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::{Duration, SystemTime};
+
 /// Detects an falling edge of two state sensor reading
 #[derive(Default)]
 pub struct TapDetector {
@@ -19,3 +21,40 @@ impl TapDetector {
         self.is_single_tapped
     }
 }
+
+/// Counts taps that follow each other within a timeout and reports the number of taps once the
+/// timeout has elapsed without another tap
+#[derive(Default)]
+pub struct MultiTapDetector {
+    tap_detector: TapDetector,
+    number_of_pending_taps: usize,
+    last_tap: Option<SystemTime>,
+    number_of_completed_taps: usize,
+}
+
+impl MultiTapDetector {
+    pub fn update(&mut self, sensor_reading: bool, now: SystemTime, timeout: Duration) {
+        self.tap_detector.update(sensor_reading);
+        self.number_of_completed_taps = 0;
+        if self.tap_detector.is_single_tapped() {
+            self.number_of_pending_taps += 1;
+            self.last_tap = Some(now);
+            return;
+        }
+        let Some(last_tap) = self.last_tap else {
+            return;
+        };
+        let is_timeout_elapsed = now
+            .duration_since(last_tap)
+            .map_or(false, |duration| duration >= timeout);
+        if !sensor_reading && is_timeout_elapsed {
+            self.number_of_completed_taps = self.number_of_pending_taps;
+            self.number_of_pending_taps = 0;
+            self.last_tap = None;
+        }
+    }
+
+    pub fn number_of_completed_taps(&self) -> usize {
+        self.number_of_completed_taps
+    }
+}
@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+use log::warn;
+use serde::Deserialize;
+use types::CycleOverruns;
+
+/// Target frequency of a cycler and how it catches up after exceeding the period of it
+///
+/// The default has no target frequency, i.e. cycles are neither monitored nor nodes skipped.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct CycleBudget {
+    pub target_frequency: Option<f32>,
+    /// Whether the nodes in `skippable_nodes` are skipped while the cycler is behind its budget
+    pub skip_nodes_on_overrun: bool,
+    /// Names of nodes the cycler works without, e.g. ones mostly filling additional outputs
+    pub skippable_nodes: Vec<String>,
+}
+
+/// Compares cycle durations with the period of the target frequency and decides which nodes to
+/// skip to catch up
+///
+/// A cycler is behind its budget if the previous cycle overran or the current cycle already took
+/// longer than the budget. Skipped nodes have their main outputs reset to their defaults like
+/// nodes with missing required inputs.
+pub struct CycleBudgetMonitor {
+    cycler_name: String,
+    budget: Option<Duration>,
+    is_skippable: Vec<bool>,
+    node_names: Vec<&'static str>,
+    skipped_nodes: Vec<&'static str>,
+    last_cycle_overran: bool,
+    overruns: CycleOverruns,
+}
+
+impl CycleBudgetMonitor {
+    pub fn new(cycler_name: String, node_names: &[&'static str], budget: CycleBudget) -> Self {
+        for skippable_node in &budget.skippable_nodes {
+            if !node_names.contains(&skippable_node.as_str()) {
+                warn!("skippable node `{skippable_node}` is not part of cycler `{cycler_name}`");
+            }
+        }
+        let period = budget
+            .target_frequency
+            .filter(|frequency| *frequency > 0.0)
+            .map(|frequency| Duration::from_secs_f32(1.0 / frequency));
+        Self {
+            cycler_name,
+            budget: period,
+            is_skippable: node_names
+                .iter()
+                .map(|name| {
+                    budget.skip_nodes_on_overrun
+                        && budget
+                            .skippable_nodes
+                            .iter()
+                            .any(|skippable| skippable == name)
+                })
+                .collect(),
+            node_names: node_names.to_vec(),
+            skipped_nodes: Vec::new(),
+            last_cycle_overran: false,
+            overruns: CycleOverruns {
+                budget: period,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Decides whether to skip the node at `node_index` in the order of the node names, given the
+    /// time elapsed since the start of the current cycle
+    pub fn should_skip(&mut self, node_index: usize, elapsed: Duration) -> bool {
+        let Some(budget) = self.budget else {
+            return false;
+        };
+        let is_behind = self.last_cycle_overran || elapsed > budget;
+        let should_skip = self.is_skippable[node_index] && is_behind;
+        if should_skip {
+            self.skipped_nodes.push(self.node_names[node_index]);
+        }
+        should_skip
+    }
+
+    pub fn record_cycle(&mut self, duration: Duration) {
+        let Some(budget) = self.budget else {
+            return;
+        };
+        self.overruns.skipped_nodes = self
+            .skipped_nodes
+            .drain(..)
+            .map(|name| name.to_string())
+            .collect();
+        let overran = duration > budget;
+        if overran {
+            if !self.last_cycle_overran {
+                warn!(
+                    "cycler `{}` took {duration:?} exceeding its budget of {budget:?}",
+                    self.cycler_name
+                );
+            }
+            self.overruns.total_overruns += 1;
+            self.overruns.last_overrun = Some(duration);
+        }
+        self.last_cycle_overran = overran;
+    }
+
+    pub fn overruns(&self) -> CycleOverruns {
+        self.overruns.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor() -> CycleBudgetMonitor {
+        CycleBudgetMonitor::new(
+            "Control".to_string(),
+            &["Behavior", "LocalizationRecorder"],
+            CycleBudget {
+                target_frequency: Some(100.0),
+                skip_nodes_on_overrun: true,
+                skippable_nodes: vec!["LocalizationRecorder".to_string()],
+            },
+        )
+    }
+
+    #[test]
+    fn only_skippable_nodes_are_skipped_when_behind() {
+        let mut monitor = monitor();
+
+        assert!(!monitor.should_skip(1, Duration::from_millis(5)));
+        assert!(!monitor.should_skip(0, Duration::from_millis(15)));
+        assert!(monitor.should_skip(1, Duration::from_millis(15)));
+        monitor.record_cycle(Duration::from_millis(15));
+
+        let overruns = monitor.overruns();
+        assert_eq!(overruns.total_overruns, 1);
+        assert_eq!(overruns.skipped_nodes, vec!["LocalizationRecorder"]);
+        assert!(monitor.should_skip(1, Duration::ZERO));
+    }
+
+    #[test]
+    fn cyclers_without_target_frequency_are_not_monitored() {
+        let mut monitor = CycleBudgetMonitor::new(
+            "Control".to_string(),
+            &["LocalizationRecorder"],
+            CycleBudget {
+                target_frequency: None,
+                skip_nodes_on_overrun: true,
+                skippable_nodes: vec!["LocalizationRecorder".to_string()],
+            },
+        );
+
+        assert!(!monitor.should_skip(0, Duration::from_secs(1)));
+        monitor.record_cycle(Duration::from_secs(1));
+        assert_eq!(monitor.overruns().total_overruns, 0);
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    any::Any,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use color_eyre::{eyre::eyre, Result};
+use log::error;
+use serde::Deserialize;
+use types::{NodeFailure, NodeFailures};
+
+/// How many failures of a single node a cycler tolerates before terminating
+///
+/// The default tolerates no failure, i.e. the first error or panic of a node terminates the
+/// cycler like before.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+pub struct FailureTolerance {
+    /// Cycles in a row a node may fail, its main outputs are reset to their defaults meanwhile
+    pub maximum_consecutive_failures: usize,
+}
+
+/// Catches errors and panics of node executions and tracks them per node
+pub struct FailureContainment {
+    tolerance: FailureTolerance,
+    nodes: Vec<NodeFailure>,
+}
+
+impl FailureContainment {
+    pub fn new(node_names: &[&'static str], tolerance: FailureTolerance) -> Self {
+        Self {
+            tolerance,
+            nodes: node_names
+                .iter()
+                .map(|name| NodeFailure {
+                    name: name.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    /// Executes the node at `node_index` in the order of the node names
+    ///
+    /// Returns `None` if the node failed within the tolerance and an error once the node failed
+    /// more often in a row than tolerated.
+    pub fn execute<MainOutputs>(
+        &mut self,
+        node_index: usize,
+        cycle: impl FnOnce() -> Result<MainOutputs>,
+    ) -> Result<Option<MainOutputs>> {
+        let error = match catch_unwind(AssertUnwindSafe(cycle)) {
+            Ok(Ok(main_outputs)) => {
+                self.nodes[node_index].consecutive_failures = 0;
+                return Ok(Some(main_outputs));
+            }
+            Ok(Err(error)) => error,
+            Err(payload) => eyre!("panicked: {}", panic_message(payload.as_ref())),
+        };
+        let node = &mut self.nodes[node_index];
+        node.consecutive_failures += 1;
+        node.total_failures += 1;
+        node.last_message = Some(format!("{error:#}"));
+        if node.consecutive_failures > self.tolerance.maximum_consecutive_failures {
+            return Err(error);
+        }
+        error!(
+            "`{}` failed ({} of {} tolerated failures in a row): {error:?}",
+            node.name, node.consecutive_failures, self.tolerance.maximum_consecutive_failures
+        );
+        Ok(None)
+    }
+
+    pub fn failures(&self) -> NodeFailures {
+        NodeFailures {
+            nodes: self.nodes.clone(),
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic payload"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::bail;
+
+    use super::*;
+
+    #[test]
+    fn failures_within_tolerance_are_contained() {
+        let mut containment = FailureContainment::new(
+            &["Behavior"],
+            FailureTolerance {
+                maximum_consecutive_failures: 2,
+            },
+        );
+
+        assert_eq!(containment.execute(0, || Ok(42)).unwrap(), Some(42));
+        assert_eq!(
+            containment
+                .execute(0, || -> Result<usize> { panic!("no hypothesis") })
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            containment
+                .execute(0, || -> Result<usize> { bail!("invalid state") })
+                .unwrap(),
+            None
+        );
+        assert!(containment
+            .execute(0, || -> Result<usize> { bail!("invalid state") })
+            .is_err());
+        assert_eq!(
+            containment.failures().nodes,
+            vec![NodeFailure {
+                name: "Behavior".to_string(),
+                consecutive_failures: 3,
+                total_failures: 3,
+                last_message: Some("invalid state".to_string()),
+            }]
+        );
+
+        assert_eq!(containment.execute(0, || Ok(42)).unwrap(), Some(42));
+        assert_eq!(containment.failures().nodes[0].consecutive_failures, 0);
+    }
+}
@@ -1,4 +1,6 @@
 mod additional_output;
+mod cycle_budget;
+mod failure_containment;
 mod future_queue;
 mod historic_databases;
 mod historic_input;
@@ -6,8 +8,14 @@ mod main_output;
 mod multiple_buffer;
 mod perception_databases;
 mod perception_input;
+mod recording;
+mod snapshot;
+mod thread_scheduling;
+mod timing_window;
 
 pub use additional_output::{should_be_filled, AdditionalOutput};
+pub use cycle_budget::{CycleBudget, CycleBudgetMonitor};
+pub use failure_containment::{FailureContainment, FailureTolerance};
 pub use future_queue::{future_queue, Consumer, Item, Producer, Update, Updates};
 pub use historic_databases::HistoricDatabases;
 pub use historic_input::HistoricInput;
@@ -15,3 +23,7 @@ pub use main_output::MainOutput;
 pub use multiple_buffer::{multiple_buffer_with_slots, Reader, ReaderGuard, Writer, WriterGuard};
 pub use perception_databases::PerceptionDatabases;
 pub use perception_input::PerceptionInput;
+pub use recording::{Recorder, RecordingFrame, RecordingReader};
+pub use snapshot::SnapshotFile;
+pub use thread_scheduling::ThreadScheduling;
+pub use timing_window::TimingWindow;
@@ -0,0 +1,234 @@
+use std::{
+    fs::{create_dir_all, File},
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+    marker::PhantomData,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{sync_channel, SyncSender, TrySendError},
+    },
+    thread::{Builder, JoinHandle},
+    time::SystemTime,
+};
+
+use bincode::{deserialize, serialize};
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use log::{error, warn};
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Number of serialized frames waiting for the writer thread after which new frames are dropped
+const MAXIMUM_NUMBER_OF_PENDING_FRAMES: usize = 64;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RecordingFrame<T> {
+    pub timestamp: SystemTime,
+    pub data: T,
+}
+
+/// Writes timestamped frames as length-prefixed Bincode into a Gzip-compressed file
+///
+/// Frames are serialized on the calling thread, compression and disk I/O happen on a separate
+/// writer thread. If the writer cannot keep up, frames are dropped with a warning instead of
+/// piling up in memory or blocking the cycler. Dropping the recorder flushes all pending frames.
+pub struct Recorder {
+    frame_sender: Option<Mutex<SyncSender<Vec<u8>>>>,
+    writer: Option<JoinHandle<Result<()>>>,
+    number_of_dropped_frames: AtomicUsize,
+}
+
+impl Recorder {
+    pub fn start(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(directory) = path.parent() {
+            create_dir_all(directory)
+                .wrap_err_with(|| format!("failed to create recording directory {directory:?}"))?;
+        }
+        let file =
+            File::create(path).wrap_err_with(|| format!("failed to create recording {path:?}"))?;
+        let (frame_sender, frame_receiver) =
+            sync_channel::<Vec<u8>>(MAXIMUM_NUMBER_OF_PENDING_FRAMES);
+        let writer = Builder::new()
+            .name("recorder".to_string())
+            .spawn(move || {
+                let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::fast());
+                for frame in frame_receiver {
+                    encoder
+                        .write_all(&(frame.len() as u64).to_le_bytes())
+                        .wrap_err("failed to write frame length")?;
+                    encoder
+                        .write_all(&frame)
+                        .wrap_err("failed to write frame")?;
+                }
+                encoder
+                    .finish()
+                    .wrap_err("failed to finish compression")?
+                    .flush()
+                    .wrap_err("failed to flush recording")
+            })
+            .wrap_err("failed to spawn recorder thread")?;
+        Ok(Self {
+            frame_sender: Some(Mutex::new(frame_sender)),
+            writer: Some(writer),
+            number_of_dropped_frames: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn record<T>(&self, timestamp: SystemTime, data: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        let frame =
+            serialize(&RecordingFrame { timestamp, data }).wrap_err("failed to serialize frame")?;
+        let result = self
+            .frame_sender
+            .as_ref()
+            .expect("frame sender should only be taken on drop")
+            .lock()
+            .try_send(frame);
+        match result {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => {
+                let number_of_dropped_frames = self
+                    .number_of_dropped_frames
+                    .fetch_add(1, Ordering::Relaxed)
+                    + 1;
+                warn!(
+                    "recorder cannot keep up, dropped frame ({number_of_dropped_frames} in total)"
+                );
+                Ok(())
+            }
+            Err(TrySendError::Disconnected(_)) => Err(eyre!("recorder thread has stopped")),
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.frame_sender.take();
+        if let Some(writer) = self.writer.take() {
+            match writer.join() {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => error!("failed to write recording: {error:?}"),
+                Err(_) => error!("recorder thread panicked"),
+            }
+        }
+    }
+}
+
+/// Iterates over the frames of a recording written by a `Recorder`
+///
+/// A truncated last frame (e.g. after a crash of the recording process) ends the iteration.
+pub struct RecordingReader<T> {
+    decoder: GzDecoder<BufReader<File>>,
+    data: PhantomData<T>,
+}
+
+impl<T> RecordingReader<T> {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file =
+            File::open(path).wrap_err_with(|| format!("failed to open recording {path:?}"))?;
+        Ok(Self {
+            decoder: GzDecoder::new(BufReader::new(file)),
+            data: PhantomData,
+        })
+    }
+
+    fn read_frame(&mut self) -> io::Result<Vec<u8>> {
+        let mut length = [0; 8];
+        self.decoder.read_exact(&mut length)?;
+        let mut frame = vec![0; u64::from_le_bytes(length) as usize];
+        self.decoder.read_exact(&mut frame)?;
+        Ok(frame)
+    }
+}
+
+impl<T> Iterator for RecordingReader<T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<RecordingFrame<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_frame() {
+            Ok(frame) => Some(deserialize(&frame).wrap_err("failed to deserialize frame")),
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => None,
+            Err(error) => Some(Err(error).wrap_err("failed to read frame")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, time::Duration};
+
+    use super::*;
+
+    #[test]
+    fn recorded_frames_are_read_back_in_order() {
+        let path = temp_dir().join(format!("recording_test_{}.bincode.gz", std::process::id()));
+        let start = SystemTime::UNIX_EPOCH;
+        let number_of_frames = MAXIMUM_NUMBER_OF_PENDING_FRAMES as u32;
+        {
+            let recorder = Recorder::start(&path).unwrap();
+            for index in 0..number_of_frames {
+                recorder
+                    .record(start + Duration::from_millis(index.into()), &vec![index; 3])
+                    .unwrap();
+            }
+        }
+
+        let frames = RecordingReader::<Vec<u32>>::open(&path)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(frames.len(), number_of_frames as usize);
+        for (index, frame) in (0..number_of_frames).zip(frames) {
+            assert_eq!(frame.timestamp, start + Duration::from_millis(index.into()));
+            assert_eq!(frame.data, vec![index; 3]);
+        }
+    }
+
+    #[test]
+    fn frames_exceeding_the_pending_frames_are_dropped() {
+        let path = temp_dir().join(format!(
+            "recording_drop_test_{}.bincode.gz",
+            std::process::id()
+        ));
+        let start = SystemTime::UNIX_EPOCH;
+        let number_of_frames = 100 * MAXIMUM_NUMBER_OF_PENDING_FRAMES as u32;
+        let number_of_dropped_frames = {
+            let recorder = Recorder::start(&path).unwrap();
+            for index in 0..number_of_frames {
+                recorder
+                    .record(
+                        start + Duration::from_millis(index.into()),
+                        &vec![index; 1000],
+                    )
+                    .unwrap();
+            }
+            recorder.number_of_dropped_frames.load(Ordering::Relaxed)
+        };
+
+        let frames = RecordingReader::<Vec<u32>>::open(&path)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            frames.len() + number_of_dropped_frames,
+            number_of_frames as usize
+        );
+        assert!(frames
+            .windows(2)
+            .all(|frames| frames[0].timestamp < frames[1].timestamp));
+    }
+}
@@ -0,0 +1,205 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, read_to_string, rename, File},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Sender},
+    thread::{Builder, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use log::{error, warn};
+use serde_json::{value::Serializer, Value};
+use serialize_hierarchy::SerializeHierarchy;
+
+/// Minimum time between two periodic snapshots
+const WRITE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Saves selected entries of a persistent state as JSON and restores them on the next start
+///
+/// Entries are addressed by their path in the persistent state. The file is replaced atomically
+/// by writing a temporary file and renaming it, a crash while writing keeps the previous
+/// snapshot. Serialization happens on the calling thread, disk I/O on a separate writer thread.
+pub struct SnapshotFile {
+    path: PathBuf,
+    entry_paths: Vec<String>,
+    last_write: Option<Instant>,
+    entries_sender: Option<Sender<BTreeMap<String, Value>>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl SnapshotFile {
+    pub fn start(path: impl Into<PathBuf>, entry_paths: &[&str]) -> Result<Self> {
+        let path = path.into();
+        if let Some(directory) = path.parent() {
+            create_dir_all(directory)
+                .wrap_err_with(|| format!("failed to create snapshot directory {directory:?}"))?;
+        }
+        let (entries_sender, entries_receiver) = channel::<BTreeMap<String, Value>>();
+        let writer = Builder::new()
+            .name("snapshot".to_string())
+            .spawn({
+                let path = path.clone();
+                move || {
+                    while let Ok(entries) = entries_receiver.recv() {
+                        // older snapshots which were not written yet are superseded
+                        let entries = entries_receiver.try_iter().last().unwrap_or(entries);
+                        if let Err(error) = write_atomically(&path, &entries) {
+                            error!("failed to write snapshot {path:?}: {error:?}");
+                        }
+                    }
+                }
+            })
+            .wrap_err("failed to spawn snapshot thread")?;
+        Ok(Self {
+            path,
+            entry_paths: entry_paths.iter().map(|path| path.to_string()).collect(),
+            last_write: None,
+            entries_sender: Some(entries_sender),
+            writer: Some(writer),
+        })
+    }
+
+    /// Deserializes the entries of a previously written snapshot into the state
+    ///
+    /// A missing snapshot leaves the state untouched. Entries which cannot be restored, e.g.
+    /// because their type changed since the snapshot was written, are skipped.
+    pub fn restore<State>(&self, state: &mut State) -> Result<()>
+    where
+        State: SerializeHierarchy,
+    {
+        let content = match read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(error) => {
+                return Err(error)
+                    .wrap_err_with(|| format!("failed to read snapshot {:?}", self.path))
+            }
+        };
+        let mut entries: BTreeMap<String, Value> = match serde_json::from_str(&content) {
+            Ok(entries) => entries,
+            Err(error) => {
+                warn!("ignoring unparsable snapshot {:?}: {error}", self.path);
+                return Ok(());
+            }
+        };
+        for path in &self.entry_paths {
+            let Some(value) = entries.remove(path) else {
+                continue;
+            };
+            if let Err(error) = state.deserialize_path(path, value) {
+                warn!(
+                    "failed to restore `{path}` from snapshot {:?}: {error}",
+                    self.path
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a snapshot if the last one is older than the write interval
+    pub fn write_if_due<State>(&mut self, state: &State) -> Result<()>
+    where
+        State: SerializeHierarchy,
+    {
+        let is_due = self
+            .last_write
+            .map_or(true, |last_write| last_write.elapsed() >= WRITE_INTERVAL);
+        if is_due {
+            self.write(state)?;
+        }
+        Ok(())
+    }
+
+    pub fn write<State>(&mut self, state: &State) -> Result<()>
+    where
+        State: SerializeHierarchy,
+    {
+        let entries = self
+            .entry_paths
+            .iter()
+            .map(|path| {
+                let value = state
+                    .serialize_path(path, Serializer)
+                    .wrap_err_with(|| format!("failed to serialize `{path}`"))?;
+                Ok((path.clone(), value))
+            })
+            .collect::<Result<_>>()?;
+        self.last_write = Some(Instant::now());
+        self.entries_sender
+            .as_ref()
+            .expect("entries sender should only be taken on drop")
+            .send(entries)
+            .map_err(|_| eyre!("snapshot thread has stopped"))
+    }
+}
+
+impl Drop for SnapshotFile {
+    fn drop(&mut self) {
+        self.entries_sender.take();
+        if let Some(writer) = self.writer.take() {
+            if writer.join().is_err() {
+                error!("snapshot thread panicked");
+            }
+        }
+    }
+}
+
+fn write_atomically(path: &Path, entries: &BTreeMap<String, Value>) -> Result<()> {
+    let temporary_path = path.with_extension("tmp");
+    let mut file = File::create(&temporary_path)
+        .wrap_err_with(|| format!("failed to create {temporary_path:?}"))?;
+    serde_json::to_writer_pretty(&mut file, entries).wrap_err("failed to serialize snapshot")?;
+    file.flush().wrap_err("failed to flush snapshot")?;
+    file.sync_all().wrap_err("failed to synchronize snapshot")?;
+    rename(&temporary_path, path)
+        .wrap_err_with(|| format!("failed to rename {temporary_path:?} to {path:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, Default, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
+    struct State {
+        pose: Vec<f32>,
+        counter: usize,
+    }
+
+    #[test]
+    fn selected_entries_are_restored() {
+        let path = temp_dir()
+            .join(format!("snapshot_test_{}", std::process::id()))
+            .join("Control.json");
+        {
+            let mut snapshot_file = SnapshotFile::start(&path, &["pose"]).unwrap();
+            snapshot_file
+                .write(&State {
+                    pose: vec![1.0, 2.0],
+                    counter: 42,
+                })
+                .unwrap();
+        }
+
+        let snapshot_file = SnapshotFile::start(&path, &["pose"]).unwrap();
+        let mut state = State::default();
+        snapshot_file.restore(&mut state).unwrap();
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(
+            state,
+            State {
+                pose: vec![1.0, 2.0],
+                counter: 0,
+            }
+        );
+    }
+}
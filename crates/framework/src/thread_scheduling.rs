@@ -0,0 +1,94 @@
+use std::{io, thread::current};
+
+use log::warn;
+use serde::Deserialize;
+
+/// CPU cores and real-time priority of a runtime thread
+///
+/// The defaults keep the scheduling of the operating system, i.e. all cores and no real-time
+/// priority. Real-time priorities require the `CAP_SYS_NICE` capability or a raised `RLIMIT_RTPRIO`.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ThreadScheduling {
+    /// Cores the thread is allowed to run on, all cores if empty
+    pub cores: Vec<usize>,
+    /// Priority for the `SCHED_FIFO` policy between 1 (lowest) and 99 (highest)
+    pub realtime_priority: Option<i32>,
+}
+
+impl ThreadScheduling {
+    /// Applies the scheduling to the calling thread
+    ///
+    /// Failures, e.g. because of missing permissions, are logged as warnings and the thread keeps
+    /// the scheduling of the operating system instead of failing the runtime.
+    pub fn apply_to_current_thread(&self) {
+        let thread = current();
+        let name = thread.name().unwrap_or("unnamed");
+        if !self.cores.is_empty() {
+            if let Err(error) = pin_current_thread(&self.cores) {
+                warn!(
+                    "Failed to pin thread `{name}` to cores {:?}, running on all cores: {error}",
+                    self.cores
+                );
+            }
+        }
+        if let Some(priority) = self.realtime_priority {
+            if let Err(error) = set_realtime_priority_of_current_thread(priority) {
+                warn!(
+                    "Failed to set real-time priority {priority} of thread `{name}`, keeping the default priority: {error}"
+                );
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn pin_current_thread(cores: &[usize]) -> io::Result<()> {
+    use std::mem::{size_of, zeroed};
+
+    use libc::{cpu_set_t, sched_setaffinity, CPU_SET, CPU_SETSIZE};
+
+    let mut set: cpu_set_t = unsafe { zeroed() };
+    for &core in cores {
+        if core >= CPU_SETSIZE as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("core {core} exceeds the maximum number of cores"),
+            ));
+        }
+        unsafe { CPU_SET(core, &mut set) };
+    }
+    // pid 0 refers to the calling thread
+    if unsafe { sched_setaffinity(0, size_of::<cpu_set_t>(), &set) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn set_realtime_priority_of_current_thread(priority: i32) -> io::Result<()> {
+    use libc::{pthread_self, pthread_setschedparam, sched_param, SCHED_FIFO};
+
+    let parameters = sched_param {
+        sched_priority: priority,
+    };
+    match unsafe { pthread_setschedparam(pthread_self(), SCHED_FIFO, &parameters) } {
+        0 => Ok(()),
+        error => Err(io::Error::from_raw_os_error(error)),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(_cores: &[usize]) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "pinning threads to cores is only supported on Linux",
+    ))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_realtime_priority_of_current_thread(_priority: i32) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "real-time priorities are only supported on Linux",
+    ))
+}
@@ -0,0 +1,92 @@
+use std::{collections::VecDeque, time::Duration};
+
+use types::{NodeTiming, TimingStatistics, Timings};
+
+const WINDOW_SIZE: usize = 100;
+
+/// Keeps the execution durations of the nodes and of the whole cycle for the last 100 cycles
+pub struct TimingWindow {
+    cycle: VecDeque<Duration>,
+    nodes: Vec<(&'static str, VecDeque<Duration>)>,
+}
+
+impl TimingWindow {
+    pub fn new(node_names: &[&'static str]) -> Self {
+        Self {
+            cycle: VecDeque::with_capacity(WINDOW_SIZE),
+            nodes: node_names
+                .iter()
+                .map(|name| (*name, VecDeque::with_capacity(WINDOW_SIZE)))
+                .collect(),
+        }
+    }
+
+    /// Records the duration of the node at `node_index` in the order of the node names
+    pub fn record_node(&mut self, node_index: usize, duration: Duration) {
+        push(&mut self.nodes[node_index].1, duration);
+    }
+
+    pub fn record_cycle(&mut self, duration: Duration) {
+        push(&mut self.cycle, duration);
+    }
+
+    pub fn timings(&self) -> Timings {
+        Timings {
+            cycle: statistics(&self.cycle),
+            nodes: self
+                .nodes
+                .iter()
+                .map(|(name, durations)| NodeTiming {
+                    name: name.to_string(),
+                    statistics: statistics(durations),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn push(durations: &mut VecDeque<Duration>, duration: Duration) {
+    if durations.len() == WINDOW_SIZE {
+        durations.pop_front();
+    }
+    durations.push_back(duration);
+}
+
+fn statistics(durations: &VecDeque<Duration>) -> TimingStatistics {
+    if durations.is_empty() {
+        return Default::default();
+    }
+    let sum: Duration = durations.iter().sum();
+    TimingStatistics {
+        minimum: durations.iter().copied().min().unwrap_or_default(),
+        mean: sum / durations.len() as u32,
+        maximum: durations.iter().copied().max().unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics_only_contain_durations_of_window() {
+        let mut window = TimingWindow::new(&["node"]);
+        window.record_node(0, Duration::from_secs(1000));
+        for milliseconds in 1..=WINDOW_SIZE as u64 {
+            window.record_node(0, Duration::from_millis(milliseconds));
+        }
+
+        let timings = window.timings();
+
+        assert_eq!(timings.cycle, TimingStatistics::default());
+        assert_eq!(timings.nodes[0].name, "node");
+        assert_eq!(
+            timings.nodes[0].statistics,
+            TimingStatistics {
+                minimum: Duration::from_millis(1),
+                mean: Duration::from_micros(50_500),
+                maximum: Duration::from_millis(100),
+            }
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddrV4},
+};
+
+use log::warn;
+use nalgebra::{point, vector, Isometry2};
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::net::UdpSocket;
+use types::{GroundTruth, Players};
+
+/// Receives the ground truth as JSON datagrams, e.g. from a bridge of a motion capture system
+///
+/// Every datagram contains the poses of the tracked robots by player number and the position of
+/// the ball, all in field coordinates. Untracked robots and an untracked ball are omitted:
+///
+/// ```json
+/// {"robots": {"three": {"x": -1.5, "y": 0.2, "orientation": 1.57}}, "ball": [0.3, -0.1]}
+/// ```
+pub struct Endpoint {
+    socket: UdpSocket,
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("failed to bind socket")]
+    CannotBind(io::Error),
+    #[error("failed to read from socket")]
+    ReadError(io::Error),
+}
+
+impl Endpoint {
+    pub async fn new(port: u16) -> Result<Self, Error> {
+        let socket = UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, port))
+            .await
+            .map_err(Error::CannotBind)?;
+        Ok(Self { socket })
+    }
+
+    pub async fn read(&self) -> Result<GroundTruth, Error> {
+        loop {
+            let mut buffer = [0; 4096];
+            let received_bytes = self
+                .socket
+                .recv(&mut buffer)
+                .await
+                .map_err(Error::ReadError)?;
+            match parse(&buffer[0..received_bytes]) {
+                Ok(ground_truth) => break Ok(ground_truth),
+                Err(error) => {
+                    warn!("Failed to parse ground truth message (will be discarded): {error:?}");
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GroundTruthMessage {
+    #[serde(default)]
+    robots: Players<Option<PoseMessage>>,
+    #[serde(default)]
+    ball: Option<[f32; 2]>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+struct PoseMessage {
+    x: f32,
+    y: f32,
+    orientation: f32,
+}
+
+fn parse(datagram: &[u8]) -> serde_json::Result<GroundTruth> {
+    let message: GroundTruthMessage = serde_json::from_slice(datagram)?;
+    let mut robot_to_field = Players::<Option<Isometry2<f32>>>::default();
+    for (player_number, pose) in message.robots.iter() {
+        robot_to_field[player_number] =
+            pose.map(|pose| Isometry2::new(vector![pose.x, pose.y], pose.orientation));
+    }
+    Ok(GroundTruth {
+        robot_to_field,
+        ball_in_field: message.ball.map(|[x, y]| point![x, y]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn omitted_robots_and_ball_are_unknown() {
+        let ground_truth =
+            parse(br#"{"robots": {"three": {"x": -1.5, "y": 0.2, "orientation": 1.57}}}"#).unwrap();
+
+        let pose = ground_truth.robot_to_field.three.unwrap();
+        assert_relative_eq!(pose.translation.vector, vector![-1.5, 0.2]);
+        assert_relative_eq!(pose.rotation.angle(), 1.57);
+        assert!(ground_truth.robot_to_field.one.is_none());
+        assert!(ground_truth.ball_in_field.is_none());
+    }
+
+    #[test]
+    fn ball_is_parsed() {
+        let ground_truth = parse(br#"{"ball": [0.3, -0.1]}"#).unwrap();
+
+        assert_eq!(ground_truth.ball_in_field, Some(point![0.3, -0.1]));
+        assert!(parse(br#"{"ball": [0.3]}"#).is_err());
+    }
+}
@@ -0,0 +1,39 @@
+use color_eyre::{eyre::WrapErr, Result};
+use context_attribute::context;
+use framework::MainOutput;
+use hardware::GroundTruthInterface;
+use types::GroundTruth;
+
+pub struct GroundTruthReceiver {}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    pub hardware_interface: HardwareInterface,
+}
+
+#[context]
+pub struct MainOutputs {
+    pub ground_truth: MainOutput<GroundTruth>,
+}
+
+impl GroundTruthReceiver {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {})
+    }
+
+    pub fn cycle(
+        &mut self,
+        context: CycleContext<impl GroundTruthInterface>,
+    ) -> Result<MainOutputs> {
+        let ground_truth = context
+            .hardware_interface
+            .read_ground_truth()
+            .wrap_err("failed to read ground truth")?;
+        Ok(MainOutputs {
+            ground_truth: ground_truth.into(),
+        })
+    }
+}
@@ -0,0 +1,2 @@
+pub mod endpoint;
+pub mod ground_truth_receiver;
@@ -1,14 +1,32 @@
-use std::time::SystemTime;
+pub mod recording;
+
+use std::{
+    io,
+    time::{Duration, SystemTime},
+};
 
 use color_eyre::eyre::Result;
+use thiserror::Error;
 use types::{
-    hardware::{Ids, Paths},
+    hardware::{ActuatorDiagnostics, CameraDiagnostics, Ids, Paths},
     messages::{IncomingMessage, OutgoingMessage},
     samples::Samples,
     ycbcr422_image::YCbCr422Image,
-    CameraPosition, Joints, Leds, SensorData,
+    CameraPosition, GroundTruth, Joints, Leds, SensorData, Sound,
 };
 
+/// Failures of the underlying hardware which callers may distinguish via `downcast_ref`
+#[derive(Debug, Error)]
+pub enum HardwareError {
+    #[error("lost connection to {device}")]
+    Disconnected {
+        device: &'static str,
+        source: io::Error,
+    },
+    #[error("{device} did not provide new data for {age:?}")]
+    StaleData { device: &'static str, age: Duration },
+}
+
 pub trait ActuatorInterface {
     fn write_to_actuators(
         &self,
@@ -16,10 +34,30 @@ pub trait ActuatorInterface {
         stiffnesses: Joints<f32>,
         leds: Leds,
     ) -> Result<()>;
+
+    /// Hardware interfaces without physical actuators have nothing to report
+    fn get_actuator_diagnostics(&self) -> ActuatorDiagnostics {
+        Default::default()
+    }
+}
+
+pub trait AudioOutputInterface {
+    /// Starts playing the sound without waiting for it to finish
+    fn play_sound(&self, sound: Sound) -> Result<()>;
 }
 
 pub trait CameraInterface {
     fn read_from_camera(&self, camera_position: CameraPosition) -> Result<YCbCr422Image>;
+
+    /// Hardware interfaces without physical cameras have nothing to report
+    fn get_camera_diagnostics(&self, _camera_position: CameraPosition) -> CameraDiagnostics {
+        Default::default()
+    }
+}
+
+pub trait GroundTruthInterface {
+    /// Blocks until the next ground truth arrives, which never happens without a configured source
+    fn read_ground_truth(&self) -> Result<GroundTruth>;
 }
 
 pub trait IdInterface {
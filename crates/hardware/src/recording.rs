@@ -0,0 +1,179 @@
+use std::{path::Path, time::SystemTime};
+
+use color_eyre::{eyre::WrapErr, Result};
+use framework::Recorder;
+use serde::{Deserialize, Serialize};
+use types::{
+    hardware::{ActuatorDiagnostics, CameraDiagnostics, Ids, Paths},
+    messages::{IncomingMessage, OutgoingMessage},
+    samples::Samples,
+    ycbcr422_image::YCbCr422Image,
+    CameraPosition, GroundTruth, Joints, Leds, SensorData, Sound,
+};
+
+use crate::{
+    ActuatorInterface, AudioOutputInterface, CameraInterface, GroundTruthInterface, IdInterface,
+    MicrophoneInterface, NetworkInterface, PathsInterface, SensorInterface, TimeInterface,
+};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum RecordedInput {
+    Ids(Ids),
+    Camera {
+        camera_position: CameraPosition,
+        image: YCbCr422Image,
+    },
+    GroundTruth(GroundTruth),
+    Microphones(Samples),
+    Network(IncomingMessage),
+    Sensors(SensorData),
+}
+
+/// Hardware interface recording everything read from the wrapped hardware interface
+///
+/// The first recorded input contains the robot's IDs, every following input is timestamped with
+/// the wrapped hardware's clock at the time of reading.
+pub struct RecordingInterface<Hardware> {
+    hardware: Hardware,
+    recorder: Recorder,
+}
+
+impl<Hardware> RecordingInterface<Hardware>
+where
+    Hardware: IdInterface + TimeInterface,
+{
+    pub fn new(hardware: Hardware, path: impl AsRef<Path>) -> Result<Self> {
+        let recorder = Recorder::start(path).wrap_err("failed to start input recorder")?;
+        recorder
+            .record(hardware.get_now(), &RecordedInput::Ids(hardware.get_ids()))
+            .wrap_err("failed to record IDs")?;
+        Ok(Self { hardware, recorder })
+    }
+
+    fn record(&self, input: RecordedInput) -> Result<()> {
+        self.recorder
+            .record(self.hardware.get_now(), &input)
+            .wrap_err("failed to record input")
+    }
+}
+
+impl<Hardware> ActuatorInterface for RecordingInterface<Hardware>
+where
+    Hardware: ActuatorInterface,
+{
+    fn write_to_actuators(
+        &self,
+        positions: Joints<f32>,
+        stiffnesses: Joints<f32>,
+        leds: Leds,
+    ) -> Result<()> {
+        self.hardware
+            .write_to_actuators(positions, stiffnesses, leds)
+    }
+
+    fn get_actuator_diagnostics(&self) -> ActuatorDiagnostics {
+        self.hardware.get_actuator_diagnostics()
+    }
+}
+
+impl<Hardware> AudioOutputInterface for RecordingInterface<Hardware>
+where
+    Hardware: AudioOutputInterface,
+{
+    fn play_sound(&self, sound: Sound) -> Result<()> {
+        self.hardware.play_sound(sound)
+    }
+}
+
+impl<Hardware> CameraInterface for RecordingInterface<Hardware>
+where
+    Hardware: CameraInterface + IdInterface + TimeInterface,
+{
+    fn read_from_camera(&self, camera_position: CameraPosition) -> Result<YCbCr422Image> {
+        let image = self.hardware.read_from_camera(camera_position)?;
+        self.record(RecordedInput::Camera {
+            camera_position,
+            image: image.clone(),
+        })?;
+        Ok(image)
+    }
+
+    fn get_camera_diagnostics(&self, camera_position: CameraPosition) -> CameraDiagnostics {
+        self.hardware.get_camera_diagnostics(camera_position)
+    }
+}
+
+impl<Hardware> GroundTruthInterface for RecordingInterface<Hardware>
+where
+    Hardware: GroundTruthInterface + IdInterface + TimeInterface,
+{
+    fn read_ground_truth(&self) -> Result<GroundTruth> {
+        let ground_truth = self.hardware.read_ground_truth()?;
+        self.record(RecordedInput::GroundTruth(ground_truth.clone()))?;
+        Ok(ground_truth)
+    }
+}
+
+impl<Hardware> IdInterface for RecordingInterface<Hardware>
+where
+    Hardware: IdInterface,
+{
+    fn get_ids(&self) -> Ids {
+        self.hardware.get_ids()
+    }
+}
+
+impl<Hardware> MicrophoneInterface for RecordingInterface<Hardware>
+where
+    Hardware: IdInterface + MicrophoneInterface + TimeInterface,
+{
+    fn read_from_microphones(&self) -> Result<Samples> {
+        let samples = self.hardware.read_from_microphones()?;
+        self.record(RecordedInput::Microphones(samples.clone()))?;
+        Ok(samples)
+    }
+}
+
+impl<Hardware> NetworkInterface for RecordingInterface<Hardware>
+where
+    Hardware: IdInterface + NetworkInterface + TimeInterface,
+{
+    fn read_from_network(&self) -> Result<IncomingMessage> {
+        let message = self.hardware.read_from_network()?;
+        self.record(RecordedInput::Network(message.clone()))?;
+        Ok(message)
+    }
+
+    fn write_to_network(&self, message: OutgoingMessage) -> Result<()> {
+        self.hardware.write_to_network(message)
+    }
+}
+
+impl<Hardware> PathsInterface for RecordingInterface<Hardware>
+where
+    Hardware: PathsInterface,
+{
+    fn get_paths(&self) -> Paths {
+        self.hardware.get_paths()
+    }
+}
+
+impl<Hardware> SensorInterface for RecordingInterface<Hardware>
+where
+    Hardware: IdInterface + SensorInterface + TimeInterface,
+{
+    fn read_from_sensors(&self) -> Result<SensorData> {
+        let sensor_data = self.hardware.read_from_sensors()?;
+        self.record(RecordedInput::Sensors(sensor_data.clone()))?;
+        Ok(sensor_data)
+    }
+}
+
+impl<Hardware> TimeInterface for RecordingInterface<Hardware>
+where
+    Hardware: TimeInterface,
+{
+    fn get_now(&self) -> SystemTime {
+        self.hardware.get_now()
+    }
+}
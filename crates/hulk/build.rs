@@ -1,7 +1,10 @@
+use std::{env::var, fs::write, path::PathBuf};
+
 use code_generation::{generate, write_to_file::WriteToFile};
 use color_eyre::eyre::{Result, WrapErr};
 use source_analyzer::{
     cyclers::{CyclerKind, Cyclers},
+    graph::Graph,
     manifest::{CyclerManifest, FrameworkManifest},
     pretty::to_string_pretty,
     structs::Structs,
@@ -17,6 +20,7 @@ fn main() -> Result<()> {
                 setup_nodes: vec!["vision::image_receiver"],
                 nodes: vec![
                     "vision::ball_detection",
+                    "vision::camera_intrinsics_calibrator",
                     "vision::camera_matrix_extractor",
                     "vision::feet_detection",
                     "vision::field_border_detection",
@@ -37,6 +41,7 @@ fn main() -> Result<()> {
                 nodes: vec![
                     "control::active_vision",
                     "control::ball_filter",
+                    "control::ball_search_heatmap",
                     "control::ball_state_composer",
                     "control::behavior::node",
                     "control::button_filter",
@@ -44,10 +49,16 @@ fn main() -> Result<()> {
                     "control::center_of_mass_provider",
                     "control::dribble_path_planner",
                     "control::fall_state_estimation",
+                    "control::foot_bumper_ball_detection",
                     "control::game_controller_filter",
+                    "control::game_controller_return_message_sender",
                     "control::game_state_filter",
                     "control::ground_contact_detector",
                     "control::ground_provider",
+                    "control::ground_truth_evaluator",
+                    "control::imu_calibrator",
+                    "control::joint_fault_detection",
+                    "control::kick_off_positioning",
                     "control::kick_selector",
                     "control::kinematics_provider",
                     "control::led_status",
@@ -69,18 +80,24 @@ fn main() -> Result<()> {
                     "control::motion::stand_up_back",
                     "control::motion::stand_up_front",
                     "control::motion::step_planner",
+                    "control::motion::wake_up",
                     "control::motion::walk_manager",
                     "control::motion::walking_engine",
+                    "control::motion_tester",
+                    "control::network_quality_estimation",
                     "control::obstacle_filter",
                     "control::odometry",
                     "control::orientation_filter",
                     "control::penalty_shot_direction_estimation",
-                    "control::primary_state_filter",
+                    "control::primary_state_machine",
                     "control::role_assignment",
                     "control::rule_obstacle_composer",
                     "control::sole_pressure_filter",
                     "control::sonar_filter",
+                    "control::sound_cues",
                     "control::support_foot_estimation",
+                    "control::symmetry_flip_detector",
+                    "control::system_monitor",
                     "control::time_to_reach_kick_position",
                     "control::visual_referee_filter",
                     "control::whistle_filter",
@@ -94,6 +111,13 @@ fn main() -> Result<()> {
                 setup_nodes: vec!["spl_network::message_receiver"],
                 nodes: vec![],
             },
+            CyclerManifest {
+                name: "GroundTruth",
+                kind: CyclerKind::Perception,
+                instances: vec![""],
+                setup_nodes: vec!["ground_truth::ground_truth_receiver"],
+                nodes: vec![],
+            },
             CyclerManifest {
                 name: "Audio",
                 kind: CyclerKind::Perception,
@@ -114,6 +138,13 @@ fn main() -> Result<()> {
     println!();
     println!("{}", to_string_pretty(&cyclers)?);
 
+    let graph = Graph::from_cyclers(&cyclers);
+    let out_dir = PathBuf::from(var("OUT_DIR")?);
+    write(out_dir.join("module_graph.dot"), graph.to_dot()?)
+        .wrap_err("failed to write module graph as DOT")?;
+    write(out_dir.join("module_graph.json"), graph.to_json()?)
+        .wrap_err("failed to write module graph as JSON")?;
+
     let structs = Structs::try_from_cyclers(&cyclers)?;
     generate(&cyclers, &structs)
         .write_to_file("generated_code.rs")
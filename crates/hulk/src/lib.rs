@@ -1,13 +1,16 @@
 #![recursion_limit = "256"]
 
 use hardware::{
-    ActuatorInterface, CameraInterface, IdInterface, MicrophoneInterface, NetworkInterface,
-    PathsInterface, SensorInterface, TimeInterface,
+    recording::RecordingInterface, ActuatorInterface, AudioOutputInterface, CameraInterface,
+    GroundTruthInterface, IdInterface, MicrophoneInterface, NetworkInterface, PathsInterface,
+    SensorInterface, TimeInterface,
 };
 
 pub trait HardwareInterface:
     ActuatorInterface
+    + AudioOutputInterface
     + CameraInterface
+    + GroundTruthInterface
     + IdInterface
     + MicrophoneInterface
     + PathsInterface
@@ -17,4 +20,6 @@ pub trait HardwareInterface:
 {
 }
 
+impl<Hardware> HardwareInterface for RecordingInterface<Hardware> where Hardware: HardwareInterface {}
+
 include!(concat!(env!("OUT_DIR"), "/generated_code.rs"));
@@ -0,0 +1,181 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::sleep,
+    time::{Duration, Instant, SystemTime},
+};
+
+use color_eyre::{eyre::bail, Result};
+use hardware::{
+    ActuatorInterface, AudioOutputInterface, CameraInterface, GroundTruthInterface, IdInterface,
+    MicrophoneInterface, NetworkInterface, PathsInterface, SensorInterface, TimeInterface,
+};
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+use types::{
+    hardware::{Ids, Paths},
+    messages::{IncomingMessage, OutgoingMessage},
+    samples::Samples,
+    ycbcr422_image::YCbCr422Image,
+    CameraPosition, GroundTruth, Joints, Leds, SensorData, Sound,
+};
+
+const SENSOR_PERIOD: Duration = Duration::from_millis(12);
+const CAMERA_PERIOD: Duration = Duration::from_millis(33);
+const MICROPHONES_PERIOD: Duration = Duration::from_millis(50);
+const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const IMAGE_WIDTH: u32 = 640;
+const IMAGE_HEIGHT: u32 = 480;
+const HEADLESS_ID: &str = "headless";
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Parameters {
+    pub communication_addresses: Option<String>,
+    pub communication_token: Option<String>,
+    pub logging: Option<logging::Parameters>,
+    pub paths: Paths,
+}
+
+/// Hardware interface without hardware, e.g. to start the robot binary in CI
+///
+/// Sensors, cameras and microphones produce zeroed data at roughly the rates of the NAO, actuator
+/// and network writes are discarded and neither network messages nor ground truth are ever
+/// received. If a maximum number of cycles is given, termination is requested after that many
+/// sensor readings.
+pub struct HardwareInterface {
+    paths: Paths,
+    keep_running: CancellationToken,
+    maximum_number_of_cycles: Option<usize>,
+    number_of_cycles: AtomicUsize,
+}
+
+impl HardwareInterface {
+    pub fn new(
+        keep_running: CancellationToken,
+        parameters: Parameters,
+        maximum_number_of_cycles: Option<usize>,
+    ) -> Self {
+        Self {
+            paths: Paths {
+                recordings: None,
+                snapshots: None,
+                ..parameters.paths
+            },
+            keep_running,
+            maximum_number_of_cycles,
+            number_of_cycles: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn has_reached_maximum_number_of_cycles(&self) -> bool {
+        self.maximum_number_of_cycles
+            .map_or(false, |maximum_number_of_cycles| {
+                self.number_of_cycles.load(Ordering::SeqCst) >= maximum_number_of_cycles
+            })
+    }
+
+    fn sleep_unless_cancelled(&self, duration: Duration) -> Result<()> {
+        let deadline = Instant::now() + duration;
+        loop {
+            if self.keep_running.is_cancelled() {
+                bail!("termination requested");
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(());
+            }
+            sleep(CANCELLATION_POLL_INTERVAL.min(deadline - now));
+        }
+    }
+}
+
+impl ActuatorInterface for HardwareInterface {
+    fn write_to_actuators(
+        &self,
+        _positions: Joints<f32>,
+        _stiffnesses: Joints<f32>,
+        _leds: Leds,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl AudioOutputInterface for HardwareInterface {
+    fn play_sound(&self, _sound: Sound) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl CameraInterface for HardwareInterface {
+    fn read_from_camera(&self, _camera_position: CameraPosition) -> Result<YCbCr422Image> {
+        self.sleep_unless_cancelled(CAMERA_PERIOD)?;
+        Ok(YCbCr422Image::zero(IMAGE_WIDTH, IMAGE_HEIGHT))
+    }
+}
+
+impl GroundTruthInterface for HardwareInterface {
+    fn read_ground_truth(&self) -> Result<GroundTruth> {
+        loop {
+            self.sleep_unless_cancelled(CANCELLATION_POLL_INTERVAL)?;
+        }
+    }
+}
+
+impl IdInterface for HardwareInterface {
+    fn get_ids(&self) -> Ids {
+        Ids {
+            body_id: HEADLESS_ID.to_string(),
+            head_id: HEADLESS_ID.to_string(),
+        }
+    }
+}
+
+impl MicrophoneInterface for HardwareInterface {
+    fn read_from_microphones(&self) -> Result<Samples> {
+        self.sleep_unless_cancelled(MICROPHONES_PERIOD)?;
+        Ok(Samples {
+            rate: 0,
+            channels_of_samples: Arc::new(vec![]),
+        })
+    }
+}
+
+impl NetworkInterface for HardwareInterface {
+    fn read_from_network(&self) -> Result<IncomingMessage> {
+        loop {
+            self.sleep_unless_cancelled(CANCELLATION_POLL_INTERVAL)?;
+        }
+    }
+
+    fn write_to_network(&self, _message: OutgoingMessage) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl PathsInterface for HardwareInterface {
+    fn get_paths(&self) -> Paths {
+        self.paths.clone()
+    }
+}
+
+impl SensorInterface for HardwareInterface {
+    fn read_from_sensors(&self) -> Result<SensorData> {
+        if self.has_reached_maximum_number_of_cycles() {
+            self.keep_running.cancel();
+            bail!("maximum number of cycles reached");
+        }
+        self.sleep_unless_cancelled(SENSOR_PERIOD)?;
+        self.number_of_cycles.fetch_add(1, Ordering::SeqCst);
+        Ok(SensorData::default())
+    }
+}
+
+impl TimeInterface for HardwareInterface {
+    fn get_now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+impl hulk::HardwareInterface for HardwareInterface {}
@@ -0,0 +1,63 @@
+#![recursion_limit = "256"]
+use std::{env::args, fs::File, sync::Arc};
+
+use color_eyre::{
+    eyre::{Result, WrapErr},
+    install,
+};
+use ctrlc::set_handler;
+use hardware::{IdInterface, PathsInterface};
+use hulk::run::run;
+use hulk_headless::hardware_interface::{HardwareInterface, Parameters};
+use serde_json::from_reader;
+use tokio_util::sync::CancellationToken;
+
+fn main() -> Result<()> {
+    install()?;
+    let hardware_parameters_path = args()
+        .nth(1)
+        .unwrap_or("etc/parameters/hardware.json".to_string());
+    let maximum_number_of_cycles = args()
+        .nth(2)
+        .map(|argument| argument.parse::<usize>())
+        .transpose()
+        .wrap_err("failed to parse maximum number of cycles")?;
+    let keep_running = CancellationToken::new();
+    set_handler({
+        let keep_running = keep_running.clone();
+        move || {
+            keep_running.cancel();
+        }
+    })?;
+    let file =
+        File::open(hardware_parameters_path).wrap_err("failed to open hardware parameters")?;
+    let hardware_parameters: Parameters =
+        from_reader(file).wrap_err("failed to parse hardware parameters")?;
+    logging::setup(hardware_parameters.logging.as_ref()).wrap_err("failed to set up logging")?;
+    let communication_addresses = hardware_parameters.communication_addresses.clone();
+    let communication_token = hardware_parameters.communication_token.clone();
+    let hardware_interface = Arc::new(HardwareInterface::new(
+        keep_running.clone(),
+        hardware_parameters,
+        maximum_number_of_cycles,
+    ));
+    let ids = hardware_interface.get_ids();
+    let paths = hardware_interface.get_paths();
+    let result = run(
+        hardware_interface.clone(),
+        communication_addresses,
+        communication_token,
+        Default::default(),
+        Default::default(),
+        Default::default(),
+        paths.parameters,
+        ids.body_id,
+        ids.head_id,
+        keep_running,
+    );
+    // cyclers exit with errors on the requested termination, which ends bounded runs regularly
+    if hardware_interface.has_reached_maximum_number_of_cycles() {
+        return Ok(());
+    }
+    result
+}
@@ -0,0 +1,174 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::Deserialize;
+use types::{hardware::ActuatorDiagnostics, Joints, Leds};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Parameters {
+    /// LED changes are sent at most once per interval, joint commands are not delayed
+    pub led_update_interval: Duration,
+    /// Unchanged commands are sent again after this duration to keep HULA's watchdog satisfied
+    pub resend_interval: Duration,
+}
+
+/// Decides which actuator commands are actually written to HULA
+///
+/// Commands equal to the previously written one are suppressed. LED changes arriving faster than
+/// the LED update interval are postponed, i.e. the previously written LEDs are sent instead.
+pub struct ActuatorFilter {
+    parameters: Parameters,
+    last_write: Option<Write>,
+    diagnostics: ActuatorDiagnostics,
+}
+
+struct Write {
+    positions: Joints<f32>,
+    stiffnesses: Joints<f32>,
+    leds: Leds,
+    written_at: Instant,
+    leds_written_at: Instant,
+}
+
+impl ActuatorFilter {
+    pub fn new(parameters: Parameters) -> Self {
+        Self {
+            parameters,
+            last_write: None,
+            diagnostics: Default::default(),
+        }
+    }
+
+    /// Returns the LEDs to write together with the joint command or `None` if nothing is written
+    pub fn filter(
+        &mut self,
+        positions: Joints<f32>,
+        stiffnesses: Joints<f32>,
+        leds: Leds,
+        now: Instant,
+    ) -> Option<Leds> {
+        let Some(last_write) = &mut self.last_write else {
+            self.last_write = Some(Write {
+                positions,
+                stiffnesses,
+                leds,
+                written_at: now,
+                leds_written_at: now,
+            });
+            return Some(leds);
+        };
+
+        let leds_changed = leds != last_write.leds;
+        let leds_are_due =
+            now.duration_since(last_write.leds_written_at) >= self.parameters.led_update_interval;
+        let leds_to_write = if leds_changed && leds_are_due {
+            last_write.leds_written_at = now;
+            leds
+        } else {
+            if leds_changed {
+                self.diagnostics.number_of_postponed_led_updates += 1;
+            }
+            last_write.leds
+        };
+
+        let is_redundant = positions == last_write.positions
+            && stiffnesses == last_write.stiffnesses
+            && leds_to_write == last_write.leds;
+        let resend_is_due =
+            now.duration_since(last_write.written_at) >= self.parameters.resend_interval;
+        if is_redundant && !resend_is_due {
+            self.diagnostics.number_of_suppressed_writes += 1;
+            return None;
+        }
+
+        last_write.positions = positions;
+        last_write.stiffnesses = stiffnesses;
+        last_write.leds = leds_to_write;
+        last_write.written_at = now;
+        Some(leds_to_write)
+    }
+
+    /// Ensures the next command is written, e.g. after the previous write failed
+    pub fn forget_last_write(&mut self) {
+        self.last_write = None;
+    }
+
+    pub fn record_write(&mut self, actuation_latency: Duration) {
+        self.diagnostics.number_of_writes += 1;
+        self.diagnostics.last_write = Some(SystemTime::now());
+        self.diagnostics.actuation_latency = actuation_latency;
+    }
+
+    pub fn diagnostics(&self) -> ActuatorDiagnostics {
+        self.diagnostics.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{Eye, Rgb};
+
+    use super::*;
+
+    fn filter() -> ActuatorFilter {
+        ActuatorFilter::new(Parameters {
+            led_update_interval: Duration::from_millis(100),
+            resend_interval: Duration::from_secs(1),
+        })
+    }
+
+    fn leds_with_chest(chest: Rgb) -> Leds {
+        Leds {
+            chest,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn identical_commands_are_suppressed_until_resend_is_due() {
+        let mut filter = filter();
+        let start = Instant::now();
+        let positions = Joints::fill(0.5);
+        let stiffnesses = Joints::fill(1.0);
+
+        let written = [0, 12, 24, 1012]
+            .map(|milliseconds| {
+                filter.filter(
+                    positions,
+                    stiffnesses,
+                    Leds::default(),
+                    start + Duration::from_millis(milliseconds),
+                )
+            })
+            .map(|leds| leds.is_some());
+
+        assert_eq!(written, [true, false, false, true]);
+        assert_eq!(filter.diagnostics().number_of_suppressed_writes, 2);
+    }
+
+    #[test]
+    fn led_changes_are_postponed_to_next_led_update() {
+        let mut filter = filter();
+        let start = Instant::now();
+        let positions = Joints::fill(0.5);
+        let stiffnesses = Joints::fill(1.0);
+
+        filter.filter(positions, stiffnesses, Leds::default(), start);
+        let early = filter.filter(
+            positions,
+            stiffnesses,
+            leds_with_chest(Rgb::RED),
+            start + Duration::from_millis(12),
+        );
+        let due = filter.filter(
+            Joints::fill(0.6),
+            stiffnesses,
+            leds_with_chest(Rgb::RED),
+            start + Duration::from_millis(112),
+        );
+
+        assert_eq!(early, None);
+        assert_eq!(due.map(|leds| leds.chest), Some(Rgb::RED));
+        assert_eq!(due.map(|leds| leds.left_eye), Some(Eye::default()));
+        assert_eq!(filter.diagnostics().number_of_postponed_led_updates, 1);
+    }
+}
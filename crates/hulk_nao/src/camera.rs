@@ -1,23 +1,26 @@
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 
 use color_eyre::{
     eyre::{bail, Context},
     Result,
 };
+use log::warn;
 use nao_camera::{reset_camera_device, Camera as NaoCamera, Parameters, PollingError};
 use parking_lot::Mutex;
-use types::{ycbcr422_image::YCbCr422Image, CameraPosition};
+use types::{hardware::CameraDiagnostics, ycbcr422_image::YCbCr422Image, CameraPosition};
 
 pub struct Camera {
     camera: Option<NaoCamera>,
     path: PathBuf,
     camera_position: CameraPosition,
     parameters: Parameters,
+    stall_timeout: Duration,
     i2c_head_mutex: Arc<Mutex<()>>,
+    diagnostics: Arc<Mutex<CameraDiagnostics>>,
 }
 
 impl Camera {
@@ -25,6 +28,7 @@ impl Camera {
         path: impl AsRef<Path>,
         camera_position: CameraPosition,
         parameters: Parameters,
+        stall_timeout: Duration,
         i2c_head_mutex: Arc<Mutex<()>>,
     ) -> Result<Self> {
         let mut camera = Self {
@@ -32,12 +36,23 @@ impl Camera {
             path: path.as_ref().to_path_buf(),
             camera_position,
             parameters,
+            stall_timeout,
             i2c_head_mutex,
+            diagnostics: Default::default(),
         };
         camera.reset().wrap_err("failed to reset")?;
         Ok(camera)
     }
 
+    pub fn camera_position(&self) -> CameraPosition {
+        self.camera_position
+    }
+
+    /// Diagnostics shared with the camera, updated on each reset
+    pub fn diagnostics(&self) -> Arc<Mutex<CameraDiagnostics>> {
+        self.diagnostics.clone()
+    }
+
     pub fn read(&mut self) -> Result<YCbCr422Image> {
         self.wait_for_device()
             .wrap_err("failed to wait for device")?;
@@ -63,16 +78,17 @@ impl Camera {
     fn wait_for_device(&mut self) -> Result<()> {
         const MAXIMUM_NUMBER_OF_RETRIES: i32 = 10;
         for _ in 0..MAXIMUM_NUMBER_OF_RETRIES {
-            const IMAGE_CAPTURE_TIMEOUT: Duration = Duration::from_secs(1);
-            match self
-                .camera
-                .as_ref()
-                .unwrap()
-                .poll(Some(IMAGE_CAPTURE_TIMEOUT))
-            {
+            match self.camera.as_ref().unwrap().poll(Some(self.stall_timeout)) {
                 Ok(_) => {}
                 Err(PollingError::DevicePollingTimedOut) => {
+                    warn!(
+                        "{:?} camera stalled for {:?}, resetting",
+                        self.camera_position, self.stall_timeout
+                    );
                     self.reset().wrap_err("failed to reset")?;
+                    let mut diagnostics = self.diagnostics.lock();
+                    diagnostics.number_of_resets += 1;
+                    diagnostics.last_reset = Some(SystemTime::now());
                     continue;
                 }
                 error => error.wrap_err("failed to poll")?,
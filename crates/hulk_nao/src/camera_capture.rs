@@ -0,0 +1,131 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use log::debug;
+use parking_lot::{Condvar, Mutex};
+use tokio_util::sync::CancellationToken;
+use types::{hardware::CameraDiagnostics, ycbcr422_image::YCbCr422Image, CameraPosition};
+
+use super::camera::Camera;
+
+const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Continuously captures images of a camera in a separate thread
+///
+/// The capture thread reads into one buffer while the most recent image waits in the other one.
+/// Readers take the most recent image and only wait if it was already taken. Images which are
+/// overwritten before being taken are counted as dropped.
+pub struct CameraCapture {
+    camera_position: CameraPosition,
+    diagnostics: Arc<Mutex<CameraDiagnostics>>,
+    latest: Arc<Latest>,
+    number_of_dropped_frames: Arc<AtomicUsize>,
+    number_of_reported_dropped_frames: AtomicUsize,
+    keep_capturing: CancellationToken,
+    capture_thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Default)]
+struct Latest {
+    image: Mutex<Option<Result<YCbCr422Image>>>,
+    image_changed: Condvar,
+}
+
+impl CameraCapture {
+    pub fn start(mut camera: Camera, keep_running: CancellationToken) -> Result<Self> {
+        let camera_position = camera.camera_position();
+        let diagnostics = camera.diagnostics();
+        let latest = Arc::new(Latest::default());
+        let number_of_dropped_frames = Arc::new(AtomicUsize::new(0));
+        let keep_capturing = keep_running.child_token();
+        let capture_thread = thread::Builder::new()
+            .name(format!("capture_{camera_position:?}").to_lowercase())
+            .spawn({
+                let latest = latest.clone();
+                let number_of_dropped_frames = number_of_dropped_frames.clone();
+                let keep_capturing = keep_capturing.clone();
+                move || {
+                    while !keep_capturing.is_cancelled() {
+                        let image = camera.read();
+                        let is_error = image.is_err();
+                        let previous_image = latest.image.lock().replace(image);
+                        latest.image_changed.notify_all();
+                        if matches!(previous_image, Some(Ok(_))) {
+                            number_of_dropped_frames.fetch_add(1, Ordering::Relaxed);
+                        }
+                        if is_error {
+                            break;
+                        }
+                    }
+                }
+            })
+            .wrap_err("failed to spawn capture thread")?;
+        Ok(Self {
+            camera_position,
+            diagnostics,
+            latest,
+            number_of_dropped_frames,
+            number_of_reported_dropped_frames: AtomicUsize::new(0),
+            keep_capturing,
+            capture_thread: Some(capture_thread),
+        })
+    }
+
+    pub fn number_of_dropped_frames(&self) -> usize {
+        self.number_of_dropped_frames.load(Ordering::Relaxed)
+    }
+
+    pub fn diagnostics(&self) -> CameraDiagnostics {
+        CameraDiagnostics {
+            number_of_dropped_frames: self.number_of_dropped_frames(),
+            ..self.diagnostics.lock().clone()
+        }
+    }
+
+    /// Takes the most recent image, waiting for the next one if it was already taken
+    pub fn read(&self) -> Result<YCbCr422Image> {
+        let mut image = self.latest.image.lock();
+        let image = loop {
+            if let Some(image) = image.take() {
+                break image;
+            }
+            if self.keep_capturing.is_cancelled() {
+                bail!("termination requested");
+            }
+            self.latest
+                .image_changed
+                .wait_for(&mut image, CANCELLATION_POLL_INTERVAL);
+        };
+        let number_of_dropped_frames = self.number_of_dropped_frames();
+        let number_of_reported_dropped_frames = self
+            .number_of_reported_dropped_frames
+            .swap(number_of_dropped_frames, Ordering::Relaxed);
+        if number_of_dropped_frames > number_of_reported_dropped_frames {
+            debug!(
+                "{:?} camera dropped {} frames, {number_of_dropped_frames} in total",
+                self.camera_position,
+                number_of_dropped_frames - number_of_reported_dropped_frames,
+            );
+        }
+        image.wrap_err("failed to capture image")
+    }
+}
+
+impl Drop for CameraCapture {
+    fn drop(&mut self) {
+        self.keep_capturing.cancel();
+        if let Some(capture_thread) = self.capture_thread.take() {
+            let _ = capture_thread.join();
+        }
+    }
+}
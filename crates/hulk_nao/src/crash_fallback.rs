@@ -0,0 +1,152 @@
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use color_eyre::{eyre::WrapErr, Result};
+use hardware::{ActuatorInterface, SensorInterface};
+use log::{info, warn};
+use motionfile::MotionFile;
+use serde::Deserialize;
+use types::{Joints, Leds};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Parameters {
+    pub enable: bool,
+    /// Duration of the interpolation from the current to the sitting positions
+    pub sit_down_duration: Duration,
+    /// Stiffness while sitting down, low enough to not damage a robot lying on the ground
+    pub stiffness: f32,
+}
+
+/// Loads the pose the sit down motion ends in, the robot sits down into the same pose after a crash
+pub fn load_sitting_positions(motions_directory: &Path) -> Result<Joints<f32>> {
+    let motion_file = MotionFile::from_path(motions_directory.join("sit_down.json"))?;
+    Ok(final_positions(&motion_file))
+}
+
+fn final_positions(motion_file: &MotionFile<Joints<f32>>) -> Joints<f32> {
+    motion_file
+        .motion
+        .iter()
+        .flat_map(|frame| &frame.keyframes)
+        .last()
+        .map_or(motion_file.initial_positions, |keyframe| keyframe.positions)
+}
+
+/// Sits the robot down and releases its stiffness after the runtime exited with an error
+///
+/// Without cyclers no actuator commands are written anymore and the robot keeps the stiffness of
+/// the last command until HULA's watchdog kicks in. A robot frozen while walking falls over
+/// stiffly, sitting down first lets it come to rest safely.
+pub fn sit_down_and_unstiff(
+    hardware_interface: &(impl ActuatorInterface + SensorInterface),
+    parameters: &Parameters,
+    sitting_positions: Joints<f32>,
+) -> Result<()> {
+    info!("Sitting down and releasing stiffness after the runtime crashed");
+    let start_positions = match hardware_interface.read_from_sensors() {
+        Ok(sensor_data) => sensor_data.positions,
+        Err(error) => {
+            warn!("failed to read joint positions, moving directly to sitting: {error:?}");
+            sitting_positions
+        }
+    };
+    let stiffnesses = Joints::fill(parameters.stiffness);
+    let start = Instant::now();
+    loop {
+        let progress = interpolation_progress(start.elapsed(), parameters.sit_down_duration);
+        let positions = interpolate(start_positions, sitting_positions, progress);
+        hardware_interface
+            .write_to_actuators(positions, stiffnesses, Leds::default())
+            .wrap_err("failed to write sitting positions")?;
+        if progress >= 1.0 {
+            break;
+        }
+        // reading blocks until the next state of HULA, which paces the interpolation
+        if let Err(error) = hardware_interface.read_from_sensors() {
+            warn!("failed to read from sensors while sitting down: {error:?}");
+        }
+    }
+    hardware_interface
+        .write_to_actuators(sitting_positions, Joints::fill(0.0), Leds::default())
+        .wrap_err("failed to release stiffness")
+}
+
+fn interpolation_progress(elapsed: Duration, duration: Duration) -> f32 {
+    if duration.is_zero() {
+        return 1.0;
+    }
+    (elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.0)
+}
+
+fn interpolate(start: Joints<f32>, end: Joints<f32>, progress: f32) -> Joints<f32> {
+    start + (end - start) * progress
+}
+
+#[cfg(test)]
+mod tests {
+    use motionfile::{KeyFrame, MotionFileFrame};
+
+    use super::*;
+
+    #[test]
+    fn sitting_positions_are_the_last_keyframe_of_the_motion() {
+        let keyframe = |position| KeyFrame {
+            duration: Duration::from_secs(1),
+            positions: Joints::fill(position),
+        };
+        let frame = |keyframes| MotionFileFrame {
+            name: None,
+            entry_condition: None,
+            interrupt_conditions: Vec::new(),
+            keyframes,
+            exit_condition: None,
+        };
+        let mut motion_file = MotionFile {
+            interpolation_mode: Default::default(),
+            initial_positions: Joints::fill(0.0),
+            motion: vec![
+                frame(vec![keyframe(1.0), keyframe(2.0)]),
+                frame(vec![keyframe(3.0)]),
+                frame(Vec::new()),
+            ],
+        };
+
+        assert_eq!(final_positions(&motion_file), Joints::fill(3.0));
+        motion_file.motion.clear();
+        assert_eq!(final_positions(&motion_file), Joints::fill(0.0));
+    }
+
+    #[test]
+    fn sitting_positions_are_loaded_from_sit_down_motion() {
+        let motions_directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../etc/motions");
+
+        let sitting_positions = load_sitting_positions(&motions_directory).unwrap();
+
+        assert!(sitting_positions.left_leg.knee_pitch > 2.0);
+        assert!(sitting_positions.right_leg.knee_pitch > 2.0);
+    }
+
+    #[test]
+    fn interpolation_ends_at_sitting_positions() {
+        let start = Joints::fill(0.0);
+        let end = Joints::fill(1.0);
+        let duration = Duration::from_secs(2);
+
+        let halfway = interpolate(
+            start,
+            end,
+            interpolation_progress(Duration::from_secs(1), duration),
+        );
+        let overdue = interpolate(
+            start,
+            end,
+            interpolation_progress(Duration::from_secs(3), duration),
+        );
+
+        assert_eq!(halfway, Joints::fill(0.5));
+        assert_eq!(overdue, end);
+        assert_eq!(interpolation_progress(Duration::ZERO, Duration::ZERO), 1.0);
+    }
+}
@@ -4,9 +4,10 @@ use std::{
     os::unix::{io::AsRawFd, prelude::RawFd},
     ptr::null_mut,
     slice::from_raw_parts_mut,
+    time::Duration,
 };
 
-use libc::{fd_set, select, FD_SET, FD_ZERO};
+use libc::{fd_set, select, suseconds_t, time_t, timeval, FD_SET, FD_ZERO};
 
 const NUMBER_OF_BUFFERS: usize = 2;
 
@@ -60,6 +61,7 @@ where
                 .reader
                 .read(&mut buffer[self.number_of_read_bytes_in_active_buffer..])
             {
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
                 Ok(number_of_read_bytes) => {
                     self.number_of_read_bytes_in_active_buffer += number_of_read_bytes;
                     assert!(self.number_of_read_bytes_in_active_buffer <= size_of::<Item>());
@@ -86,26 +88,35 @@ pub trait Poll {
     fn poll(&mut self, file_descriptor: RawFd) -> io::Result<()>;
 }
 
-pub struct SelectPoller;
+/// Waits for the file descriptor to become readable, failing with `ErrorKind::TimedOut` if this
+/// takes longer than the timeout
+pub struct SelectPoller {
+    pub timeout: Duration,
+}
 
 impl Poll for SelectPoller {
     fn poll(&mut self, file_descriptor: RawFd) -> io::Result<()> {
-        unsafe {
+        let mut timeout = timeval {
+            tv_sec: self.timeout.as_secs() as time_t,
+            tv_usec: self.timeout.subsec_micros() as suseconds_t,
+        };
+        let number_of_ready_file_descriptors = unsafe {
             let mut set = MaybeUninit::<fd_set>::uninit();
             FD_ZERO(set.as_mut_ptr());
             let mut set = set.assume_init();
             FD_SET(file_descriptor, &mut set);
-            if select(
+            select(
                 file_descriptor + 1,
                 &mut set,
                 null_mut(),
                 null_mut(),
-                null_mut(),
-            ) < 0
-            {
-                return Err(io::Error::last_os_error());
-            }
-            Ok(())
+                &mut timeout,
+            )
+        };
+        match number_of_ready_file_descriptors {
+            error if error < 0 => Err(io::Error::last_os_error()),
+            0 => Err(ErrorKind::TimedOut.into()),
+            _ => Ok(()),
         }
     }
 }
@@ -176,6 +187,28 @@ mod tests {
         assert_eq!(error.kind(), ErrorKind::ConnectionAborted);
     }
 
+    #[test]
+    fn closed_reader_is_returned_as_error() {
+        struct Reader;
+        impl AsRawFd for Reader {
+            fn as_raw_fd(&self) -> RawFd {
+                panic!("should not be called");
+            }
+        }
+        impl Read for Reader {
+            fn read(&mut self, _buffer: &mut [u8]) -> io::Result<usize> {
+                Ok(0)
+            }
+        }
+
+        let mut double_buffered_reader =
+            DoubleBufferedReader::<u16, _, _>::from_reader_and_poller(Reader, PanickingPoller);
+        let result = double_buffered_reader.draining_read();
+        assert!(result.is_err());
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+
     #[test]
     fn poll_error_is_returned() {
         struct Reader;
@@ -1,13 +1,18 @@
-use std::{sync::Arc, time::SystemTime};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use ::hardware::{
-    ActuatorInterface, CameraInterface, IdInterface, MicrophoneInterface, NetworkInterface,
-    SensorInterface, TimeInterface,
+    ActuatorInterface, AudioOutputInterface, CameraInterface, GroundTruthInterface, IdInterface,
+    MicrophoneInterface, NetworkInterface, SensorInterface, TimeInterface,
 };
 use color_eyre::{
     eyre::{eyre, Error, WrapErr},
     Result,
 };
+use framework::{CycleBudget, FailureTolerance, ThreadScheduling};
 use hardware::PathsInterface;
 use parking_lot::Mutex;
 use serde::Deserialize;
@@ -18,37 +23,58 @@ use tokio::{
 };
 use tokio_util::sync::CancellationToken;
 use types::{
-    hardware::{Ids, Paths},
+    hardware::{ActuatorDiagnostics, CameraDiagnostics, Ids, Paths},
     messages::{IncomingMessage, OutgoingMessage},
     samples::Samples,
     ycbcr422_image::YCbCr422Image,
-    CameraPosition, Joints, Leds, SensorData,
+    CameraPosition, GroundTruth, Joints, Leds, SensorData, Sound,
 };
 
 use super::{
+    actuator_filter,
     camera::Camera,
+    camera_capture::CameraCapture,
+    crash_fallback,
     hula_wrapper::HulaWrapper,
     microphones::{self, Microphones},
+    speakers::Speakers,
 };
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Parameters {
+    pub actuators: actuator_filter::Parameters,
     pub camera_top: nao_camera::Parameters,
     pub camera_bottom: nao_camera::Parameters,
+    /// Cameras are reset if no image arrives within this duration
+    pub camera_stall_timeout: Duration,
     pub communication_addresses: Option<String>,
+    pub communication_token: Option<String>,
+    /// Actuator commands written after the runtime exited with an error
+    pub crash_fallback: crash_fallback::Parameters,
+    /// Target frequencies of the cyclers and nodes skipped when falling behind by instance name
+    pub cycle_budgets: HashMap<String, CycleBudget>,
+    /// Failures of single nodes contained before the whole cycler is terminated
+    pub failure_tolerance: FailureTolerance,
+    /// UDP port of the ground truth feed, no ground truth is received if absent
+    pub ground_truth_port: Option<u16>,
+    pub logging: Option<logging::Parameters>,
     pub microphones: microphones::Parameters,
     pub paths: Paths,
     pub spl_network_ports: Ports,
+    /// Cores and real-time priorities of the cycler and communication threads by instance name
+    pub thread_scheduling: HashMap<String, ThreadScheduling>,
 }
 
 pub struct HardwareInterface {
     hula_wrapper: Mutex<HulaWrapper>,
     microphones: Mutex<Microphones>,
+    speakers: Speakers,
     paths: Paths,
     spl_network_endpoint: Endpoint,
+    ground_truth_endpoint: Option<ground_truth::endpoint::Endpoint>,
     async_runtime: Runtime,
-    camera_top: Mutex<Camera>,
-    camera_bottom: Mutex<Camera>,
+    camera_top: CameraCapture,
+    camera_bottom: CameraCapture,
     keep_running: CancellationToken,
 }
 
@@ -62,35 +88,49 @@ impl HardwareInterface {
 
         Ok(Self {
             hula_wrapper: Mutex::new(
-                HulaWrapper::new().wrap_err("failed to initialize HULA wrapper")?,
+                HulaWrapper::new(parameters.actuators)
+                    .wrap_err("failed to initialize HULA wrapper")?,
             ),
             microphones: Mutex::new(
                 Microphones::new(parameters.microphones)
                     .wrap_err("failed to initialize microphones")?,
             ),
+            speakers: Speakers::new(parameters.paths.sounds.clone())
+                .wrap_err("failed to initialize speakers")?,
             paths: parameters.paths,
             spl_network_endpoint: runtime
                 .block_on(Endpoint::new(parameters.spl_network_ports))
                 .wrap_err("failed to initialize SPL network")?,
+            ground_truth_endpoint: parameters
+                .ground_truth_port
+                .map(|port| runtime.block_on(ground_truth::endpoint::Endpoint::new(port)))
+                .transpose()
+                .wrap_err("failed to initialize ground truth endpoint")?,
             async_runtime: runtime,
-            camera_top: Mutex::new(
+            camera_top: CameraCapture::start(
                 Camera::new(
                     "/dev/video-top",
                     CameraPosition::Top,
                     parameters.camera_top,
+                    parameters.camera_stall_timeout,
                     i2c_head_mutex.clone(),
                 )
                 .wrap_err("failed to initialize top camera")?,
-            ),
-            camera_bottom: Mutex::new(
+                keep_running.clone(),
+            )
+            .wrap_err("failed to start capturing top camera")?,
+            camera_bottom: CameraCapture::start(
                 Camera::new(
                     "/dev/video-bottom",
                     CameraPosition::Bottom,
                     parameters.camera_bottom,
+                    parameters.camera_stall_timeout,
                     i2c_head_mutex,
                 )
                 .wrap_err("failed to initialize bottom camera")?,
-            ),
+                keep_running.clone(),
+            )
+            .wrap_err("failed to start capturing bottom camera")?,
             keep_running,
         })
     }
@@ -107,17 +147,53 @@ impl ActuatorInterface for HardwareInterface {
             .lock()
             .write_to_actuators(positions, stiffnesses, leds)
     }
+
+    fn get_actuator_diagnostics(&self) -> ActuatorDiagnostics {
+        self.hula_wrapper.lock().get_actuator_diagnostics()
+    }
+}
+
+impl AudioOutputInterface for HardwareInterface {
+    fn play_sound(&self, sound: Sound) -> Result<()> {
+        self.speakers.play_sound(sound)
+    }
 }
 
 impl CameraInterface for HardwareInterface {
     fn read_from_camera(&self, camera_position: CameraPosition) -> Result<YCbCr422Image> {
         match camera_position {
-            CameraPosition::Top => self.camera_top.lock().read(),
-            CameraPosition::Bottom => self.camera_bottom.lock().read(),
+            CameraPosition::Top => self.camera_top.read(),
+            CameraPosition::Bottom => self.camera_bottom.read(),
+        }
+    }
+
+    fn get_camera_diagnostics(&self, camera_position: CameraPosition) -> CameraDiagnostics {
+        match camera_position {
+            CameraPosition::Top => self.camera_top.diagnostics(),
+            CameraPosition::Bottom => self.camera_bottom.diagnostics(),
         }
     }
 }
 
+impl GroundTruthInterface for HardwareInterface {
+    fn read_ground_truth(&self) -> Result<GroundTruth> {
+        self.async_runtime.block_on(async {
+            let Some(ground_truth_endpoint) = &self.ground_truth_endpoint else {
+                self.keep_running.cancelled().await;
+                return Err(eyre!("termination requested"));
+            };
+            select! {
+                result = ground_truth_endpoint.read() => {
+                    result.map_err(Error::from)
+                },
+                _ = self.keep_running.cancelled() => {
+                    Err(eyre!("termination requested"))
+                }
+            }
+        })
+    }
+}
+
 impl IdInterface for HardwareInterface {
     fn get_ids(&self) -> Ids {
         self.hula_wrapper.lock().get_ids()
@@ -1,6 +1,10 @@
-use std::{io::Write, mem::size_of, os::unix::net::UnixStream, slice::from_raw_parts};
+use std::{
+    io::{self, Write},
+    mem::size_of,
+    os::unix::net::UnixStream,
+    slice::from_raw_parts,
+};
 
-use color_eyre::{eyre::Context, Result};
 use nalgebra::{vector, Vector2, Vector3};
 use types::{self, ArmJoints, HeadJoints, Joints, LegJoints};
 
@@ -24,6 +28,17 @@ pub struct Battery {
     pub temperature: f32,
 }
 
+impl From<Battery> for types::Battery {
+    fn from(battery: Battery) -> Self {
+        types::Battery {
+            charge: battery.charge,
+            status: battery.status,
+            current: battery.current,
+            temperature: battery.temperature,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[repr(C)]
 pub struct Vertex2 {
@@ -267,7 +282,7 @@ impl From<JointsArray> for Joints<f32> {
 #[repr(C)]
 pub struct StateStorage {
     /// Seconds since proxy start
-    pub received_at: f32,
+    pub received_at: f64,
     pub robot_configuration: RobotConfiguration,
     pub battery: Battery,
     pub inertial_measurement_unit: InertialMeasurementUnit,
@@ -375,13 +390,11 @@ pub struct ControlStorage {
 
 pub fn read_from_hula(
     reader: &mut DoubleBufferedReader<StateStorage, UnixStream, SelectPoller>,
-) -> Result<StateStorage> {
-    Ok(*reader
-        .draining_read()
-        .wrap_err("failed to drain from stream")?)
+) -> io::Result<StateStorage> {
+    reader.draining_read().copied()
 }
 
-pub fn write_to_hula(stream: &mut UnixStream, control_storage: ControlStorage) -> Result<()> {
+pub fn write_to_hula(stream: &mut UnixStream, control_storage: ControlStorage) -> io::Result<()> {
     let control_storage_buffer = unsafe {
         from_raw_parts(
             &control_storage as *const ControlStorage as *const u8,
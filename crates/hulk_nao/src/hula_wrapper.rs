@@ -1,40 +1,74 @@
 use std::{
+    io,
     os::unix::net::UnixStream,
     str::from_utf8,
-    time::{Duration, SystemTime, UNIX_EPOCH},
+    thread::sleep,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use color_eyre::{eyre::WrapErr, Result};
-use types::{hardware::Ids, Joints, Leds, SensorData};
+use hardware::HardwareError;
+use log::{info, warn};
+use types::{
+    hardware::{ActuatorDiagnostics, Ids},
+    Joints, Leds, SensorData,
+};
 
 use super::{
+    actuator_filter::{self, ActuatorFilter},
     double_buffered_reader::{DoubleBufferedReader, SelectPoller},
     hula::{read_from_hula, write_to_hula, ControlStorage, StateStorage},
 };
 use constants::HULA_SOCKET_PATH;
 
+const DEVICE: &str = "HULA";
+/// HULA sends state every 12 ms
+const SENSOR_DATA_INTERVAL: Duration = Duration::from_millis(12);
+/// Waiting longer than this for fresh state means the daemon got stuck
+const SENSOR_DATA_TIMEOUT: Duration = Duration::from_millis(100);
+const RECONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const RECONNECT_INTERVAL: Duration = Duration::from_millis(100);
+
+struct Connection {
+    stream: UnixStream,
+    reader: DoubleBufferedReader<StateStorage, UnixStream, SelectPoller>,
+}
+
+impl Connection {
+    fn open() -> io::Result<Self> {
+        let stream = UnixStream::connect(HULA_SOCKET_PATH)?;
+        stream.set_nonblocking(true)?;
+        let reader = DoubleBufferedReader::from_reader_and_poller(
+            stream.try_clone()?,
+            SelectPoller {
+                timeout: SENSOR_DATA_TIMEOUT,
+            },
+        );
+        Ok(Self { stream, reader })
+    }
+}
+
+/// Connection to the HULA daemon which survives restarts of the daemon
+///
+/// If reading or writing fails, the socket is reopened until `RECONNECT_TIMEOUT` elapsed.
+/// Sensor data without advancing timestamp is skipped instead of being passed on, only if no fresh
+/// data arrives within `SENSOR_DATA_TIMEOUT` reading fails as stale.
 pub struct HulaWrapper {
     now: SystemTime,
+    /// Offset of the proxy clock to the time of the wrapper, changes if the daemon restarted
+    proxy_clock_offset: Duration,
+    is_reconnected: bool,
+    last_fresh_data: Instant,
     ids: Ids,
-    stream: UnixStream,
-    hula_reader: DoubleBufferedReader<StateStorage, UnixStream, SelectPoller>,
+    connection: Option<Connection>,
+    actuator_filter: ActuatorFilter,
 }
 
 impl HulaWrapper {
-    pub fn new() -> Result<Self> {
-        let stream =
-            UnixStream::connect(HULA_SOCKET_PATH).wrap_err("failed to open HULA socket")?;
-        stream
-            .set_nonblocking(true)
-            .wrap_err("failed to set HULA socket to non-blocking mode")?;
-        let mut hula_reader = DoubleBufferedReader::from_reader_and_poller(
-            stream
-                .try_clone()
-                .wrap_err("failed to clone HULA socket for reading")?,
-            SelectPoller,
-        );
+    pub fn new(actuator_parameters: actuator_filter::Parameters) -> Result<Self> {
+        let mut connection = Connection::open().wrap_err("failed to open HULA socket")?;
         let state_storage =
-            read_from_hula(&mut hula_reader).wrap_err("failed to read from HULA")?;
+            read_from_hula(&mut connection.reader).wrap_err("failed to read from HULA")?;
         let ids = Ids {
             body_id: from_utf8(&state_storage.robot_configuration.body_id)
                 .wrap_err("failed to convert body ID into UTF-8")?
@@ -45,9 +79,12 @@ impl HulaWrapper {
         };
         Ok(Self {
             now: UNIX_EPOCH,
+            proxy_clock_offset: Duration::ZERO,
+            is_reconnected: false,
+            last_fresh_data: Instant::now(),
             ids,
-            stream,
-            hula_reader,
+            connection: Some(connection),
+            actuator_filter: ActuatorFilter::new(actuator_parameters),
         })
     }
 
@@ -59,11 +96,39 @@ impl HulaWrapper {
         self.ids.clone()
     }
 
-    pub fn read_from_hula(&mut self) -> Result<SensorData> {
-        let state_storage =
-            read_from_hula(&mut self.hula_reader).wrap_err("failed to read from HULA")?;
+    pub fn get_actuator_diagnostics(&self) -> ActuatorDiagnostics {
+        self.actuator_filter.diagnostics()
+    }
 
-        self.now = UNIX_EPOCH + Duration::from_secs_f32(state_storage.received_at);
+    pub fn read_from_hula(&mut self) -> Result<SensorData> {
+        let state_storage = loop {
+            let state_storage = self.read_state_storage()?;
+            let since_proxy_start =
+                Duration::try_from_secs_f64(state_storage.received_at).unwrap_or_default();
+            if self.is_reconnected
+                && UNIX_EPOCH + self.proxy_clock_offset + since_proxy_start <= self.now
+            {
+                // the daemon restarted and its clock with it, continue where the old clock stopped
+                self.proxy_clock_offset = self.now.duration_since(UNIX_EPOCH).unwrap_or_default()
+                    + SENSOR_DATA_INTERVAL
+                    - since_proxy_start;
+            }
+            self.is_reconnected = false;
+            let received_at = UNIX_EPOCH + self.proxy_clock_offset + since_proxy_start;
+            if received_at > self.now {
+                self.now = received_at;
+                break state_storage;
+            }
+            if self.last_fresh_data.elapsed() > SENSOR_DATA_TIMEOUT {
+                return Err(HardwareError::StaleData {
+                    device: DEVICE,
+                    age: self.last_fresh_data.elapsed(),
+                }
+                .into());
+            }
+            // skip the stale state, the next read waits for the following one
+        };
+        self.last_fresh_data = Instant::now();
 
         let positions = state_storage.position.into();
         let inertial_measurement_unit = state_storage.inertial_measurement_unit.into();
@@ -71,6 +136,8 @@ impl HulaWrapper {
         let force_sensitive_resistors = state_storage.force_sensitive_resistors.into();
         let touch_sensors = state_storage.touch_sensors.into();
         let temperature_sensors = state_storage.temperature.into();
+        let currents = state_storage.current.into();
+        let battery = Some(state_storage.battery.into());
 
         Ok(SensorData {
             positions,
@@ -79,6 +146,8 @@ impl HulaWrapper {
             force_sensitive_resistors,
             touch_sensors,
             temperature_sensors,
+            currents,
+            battery,
         })
     }
 
@@ -88,6 +157,12 @@ impl HulaWrapper {
         stiffnesses: Joints<f32>,
         leds: Leds,
     ) -> Result<()> {
+        let Some(leds) = self
+            .actuator_filter
+            .filter(positions, stiffnesses, leds, Instant::now())
+        else {
+            return Ok(());
+        };
         let control_storage = ControlStorage {
             left_eye: leds.left_eye.into(),
             right_eye: leds.right_eye.into(),
@@ -100,6 +175,72 @@ impl HulaWrapper {
             stiffness: stiffnesses.into(),
         };
 
-        write_to_hula(&mut self.stream, control_storage).wrap_err("failed to write to HULA")
+        let connection = self.connect()?;
+        if let Err(source) = write_to_hula(&mut connection.stream, control_storage) {
+            // the next read reconnects, actuator commands of this cycle are lost anyway
+            warn!("failed to write to HULA: {source}");
+            self.connection = None;
+            self.actuator_filter.forget_last_write();
+            return Err(HardwareError::Disconnected {
+                device: DEVICE,
+                source,
+            }
+            .into());
+        }
+        self.actuator_filter
+            .record_write(self.last_fresh_data.elapsed());
+        Ok(())
+    }
+
+    fn read_state_storage(&mut self) -> Result<StateStorage> {
+        let connection = self.connect()?;
+        match read_from_hula(&mut connection.reader) {
+            Ok(state_storage) => return Ok(state_storage),
+            Err(error) if error.kind() == io::ErrorKind::TimedOut => {
+                return Err(HardwareError::StaleData {
+                    device: DEVICE,
+                    age: self.last_fresh_data.elapsed(),
+                }
+                .into());
+            }
+            Err(error) => {
+                warn!("failed to read from HULA, reconnecting: {error}");
+                self.connection = None;
+            }
+        }
+        let connection = self.connect()?;
+        read_from_hula(&mut connection.reader).map_err(|source| {
+            HardwareError::Disconnected {
+                device: DEVICE,
+                source,
+            }
+            .into()
+        })
+    }
+
+    fn connect(&mut self) -> Result<&mut Connection, HardwareError> {
+        if self.connection.is_none() {
+            let connection = reconnect()?;
+            info!("reconnected to HULA");
+            self.connection = Some(connection);
+            self.is_reconnected = true;
+        }
+        Ok(self.connection.as_mut().expect("connection should be open"))
+    }
+}
+
+fn reconnect() -> Result<Connection, HardwareError> {
+    let start = Instant::now();
+    loop {
+        match Connection::open() {
+            Ok(connection) => return Ok(connection),
+            Err(source) if start.elapsed() >= RECONNECT_TIMEOUT => {
+                return Err(HardwareError::Disconnected {
+                    device: DEVICE,
+                    source,
+                })
+            }
+            Err(_) => sleep(RECONNECT_INTERVAL),
+        }
     }
 }
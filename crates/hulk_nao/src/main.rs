@@ -1,43 +1,39 @@
 #![recursion_limit = "256"]
-use std::{env::args, fs::File, io::stdout, sync::Arc};
+use std::{
+    env::args,
+    fs::File,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use color_eyre::{
     eyre::{Result, WrapErr},
     install,
 };
+use crash_fallback::{load_sitting_positions, sit_down_and_unstiff};
 use ctrlc::set_handler;
-use hardware::{IdInterface, PathsInterface};
+use hardware::{
+    recording::RecordingInterface, ActuatorInterface, IdInterface, PathsInterface, SensorInterface,
+};
 use hardware_interface::{HardwareInterface, Parameters};
 use hulk::run::run;
+use log::error;
 use serde_json::from_reader;
 use tokio_util::sync::CancellationToken;
+use types::Joints;
 
+mod actuator_filter;
 mod camera;
+mod camera_capture;
+mod crash_fallback;
 mod double_buffered_reader;
 mod hardware_interface;
 mod hula;
 mod hula_wrapper;
 mod microphones;
-
-pub fn setup_logger() -> Result<(), fern::InitError> {
-    fern::Dispatch::new()
-        .format(|out, message, record| {
-            out.finish(format_args!(
-                "{}  {:<18}  {:>5}  {}",
-                chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
-                record.target(),
-                record.level(),
-                message
-            ))
-        })
-        .level(log::LevelFilter::Debug)
-        .chain(stdout())
-        .apply()?;
-    Ok(())
-}
+mod speakers;
 
 fn main() -> Result<()> {
-    setup_logger()?;
     install()?;
     let hardware_parameters_path = args()
         .nth(1)
@@ -51,19 +47,88 @@ fn main() -> Result<()> {
     })?;
     let file =
         File::open(hardware_parameters_path).wrap_err("failed to open hardware parameters")?;
-    let hardware_parameters: Parameters =
+    let mut hardware_parameters: Parameters =
         from_reader(file).wrap_err("failed to parse hardware parameters")?;
+    logging::setup(hardware_parameters.logging.as_ref()).wrap_err("failed to set up logging")?;
+    if let Some(recordings) = hardware_parameters.paths.recordings.as_mut() {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .wrap_err("failed to get seconds since Unix epoch")?
+            .as_secs();
+        recordings.push(seconds.to_string());
+    }
     let communication_addresses = hardware_parameters.communication_addresses.clone();
+    let communication_token = hardware_parameters.communication_token.clone();
+    let thread_scheduling = hardware_parameters.thread_scheduling.clone();
+    let failure_tolerance = hardware_parameters.failure_tolerance;
+    let cycle_budgets = hardware_parameters.cycle_budgets.clone();
+    let crash_fallback = hardware_parameters.crash_fallback.clone();
     let hardware_interface = HardwareInterface::new(keep_running.clone(), hardware_parameters)
         .wrap_err("failed to create hardware interface")?;
     let ids = hardware_interface.get_ids();
     let paths = hardware_interface.get_paths();
-    run(
-        Arc::new(hardware_interface),
-        communication_addresses,
-        paths.parameters,
-        ids.body_id,
-        ids.head_id,
-        keep_running,
-    )
+    let sitting_positions = load_sitting_positions(&paths.motions)
+        .wrap_err("failed to load sitting positions of the crash fallback")?;
+    match paths.recordings {
+        Some(recordings) => {
+            let hardware_interface = Arc::new(
+                RecordingInterface::new(hardware_interface, recordings.join("inputs.bincode.gz"))
+                    .wrap_err("failed to create recording hardware interface")?,
+            );
+            let result = run(
+                hardware_interface.clone(),
+                communication_addresses,
+                communication_token,
+                thread_scheduling,
+                failure_tolerance,
+                cycle_budgets,
+                paths.parameters,
+                ids.body_id,
+                ids.head_id,
+                keep_running,
+            );
+            fall_back_on_error(
+                result,
+                &*hardware_interface,
+                &crash_fallback,
+                sitting_positions,
+            )
+        }
+        None => {
+            let hardware_interface = Arc::new(hardware_interface);
+            let result = run(
+                hardware_interface.clone(),
+                communication_addresses,
+                communication_token,
+                thread_scheduling,
+                failure_tolerance,
+                cycle_budgets,
+                paths.parameters,
+                ids.body_id,
+                ids.head_id,
+                keep_running,
+            );
+            fall_back_on_error(
+                result,
+                &*hardware_interface,
+                &crash_fallback,
+                sitting_positions,
+            )
+        }
+    }
+}
+
+fn fall_back_on_error(
+    result: Result<()>,
+    hardware_interface: &(impl ActuatorInterface + SensorInterface),
+    parameters: &crash_fallback::Parameters,
+    sitting_positions: Joints<f32>,
+) -> Result<()> {
+    if result.is_err() && parameters.enable {
+        if let Err(error) = sit_down_and_unstiff(hardware_interface, parameters, sitting_positions)
+        {
+            error!("failed to execute crash fallback: {error:?}");
+        }
+    }
+    result
 }
@@ -0,0 +1,90 @@
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    sync::mpsc::{sync_channel, SyncSender, TrySendError},
+    thread,
+};
+
+use alsa::{
+    pcm::{Access, Format, HwParams},
+    Direction, ValueOr, PCM,
+};
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use lewton::inside_ogg::OggStreamReader;
+use log::{debug, warn};
+use types::Sound;
+
+const MAXIMUM_QUEUED_SOUNDS: usize = 2;
+
+/// Plays sounds in a separate thread to not block the calling cycler
+pub struct Speakers {
+    sound_sender: SyncSender<Sound>,
+}
+
+impl Speakers {
+    pub fn new(sounds_directory: PathBuf) -> Result<Self> {
+        let (sound_sender, sound_receiver) = sync_channel::<Sound>(MAXIMUM_QUEUED_SOUNDS);
+        thread::Builder::new()
+            .name("speakers".to_string())
+            .spawn(move || {
+                for sound in sound_receiver {
+                    let path = sounds_directory.join(sound.file_name());
+                    if let Err(error) = play(&path) {
+                        warn!("failed to play {path:?}: {error:?}");
+                    }
+                }
+            })
+            .wrap_err("failed to spawn speakers thread")?;
+        Ok(Self { sound_sender })
+    }
+
+    pub fn play_sound(&self, sound: Sound) -> Result<()> {
+        match self.sound_sender.try_send(sound) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(sound)) => {
+                debug!("dropping {sound:?}, too many sounds are queued");
+                Ok(())
+            }
+            Err(TrySendError::Disconnected(_)) => bail!("speakers thread terminated"),
+        }
+    }
+}
+
+fn play(path: &Path) -> Result<()> {
+    let file = File::open(path).wrap_err("failed to open sound file")?;
+    let mut reader = OggStreamReader::new(file).wrap_err("failed to read Ogg stream")?;
+    let device =
+        PCM::new("default", Direction::Playback, false).wrap_err("failed to open audio device")?;
+    {
+        let hardware_parameters =
+            HwParams::any(&device).wrap_err("failed to create hardware parameters")?;
+        hardware_parameters
+            .set_access(Access::RWInterleaved)
+            .wrap_err("failed to set access")?;
+        hardware_parameters
+            .set_format(Format::s16())
+            .wrap_err("failed to set format")?;
+        hardware_parameters
+            .set_rate_near(reader.ident_hdr.audio_sample_rate, ValueOr::Nearest)
+            .wrap_err("failed to set sample rate")?;
+        hardware_parameters
+            .set_channels(reader.ident_hdr.audio_channels as u32)
+            .wrap_err("failed to set channels")?;
+        device
+            .hw_params(&hardware_parameters)
+            .wrap_err("failed to set hardware parameters")?;
+    }
+    let io_device = device.io_i16().wrap_err("failed to create I/O device")?;
+    while let Some(interleaved_samples) = reader
+        .read_dec_packet_itl()
+        .wrap_err("failed to decode Ogg packet")?
+    {
+        io_device
+            .writei(&interleaved_samples)
+            .wrap_err("failed to write audio data")?;
+    }
+    device.drain().wrap_err("failed to drain audio device")
+}
@@ -0,0 +1,359 @@
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use framework::{RecordingFrame, RecordingReader};
+use hardware::{
+    recording::RecordedInput, ActuatorInterface, AudioOutputInterface, CameraInterface,
+    GroundTruthInterface, IdInterface, MicrophoneInterface, NetworkInterface, PathsInterface,
+    SensorInterface, TimeInterface,
+};
+use parking_lot::{Condvar, Mutex, MutexGuard};
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+use types::{
+    hardware::{Ids, Paths},
+    messages::{IncomingMessage, OutgoingMessage},
+    samples::Samples,
+    ycbcr422_image::YCbCr422Image,
+    CameraPosition, GroundTruth, Joints, Leds, SensorData, Sound,
+};
+
+const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Parameters {
+    pub communication_addresses: Option<String>,
+    pub communication_token: Option<String>,
+    pub paths: Paths,
+}
+
+/// Hardware interface replaying inputs recorded by a `RecordingInterface`
+///
+/// Recorded sensor data drives the clock: Each sensor read advances the time to the timestamp of
+/// the next recorded sensor data. All other inputs are handed out once the clock reached their
+/// timestamp, and the clock only advances after all due inputs were read. This keeps the
+/// interleaving of cyclers as it was during recording, independent of the replay speed.
+///
+/// The replay can be paused, stepped cycle by cycle, and seeked to an offset from the start of the
+/// recording. Seeking backwards restarts reading the recording, i.e. the clock jumps backwards.
+pub struct HardwareInterface {
+    ids: Ids,
+    paths: Paths,
+    recording_path: PathBuf,
+    state: Mutex<ReplayState>,
+    state_changed: Condvar,
+    keep_running: CancellationToken,
+}
+
+struct ReplayState {
+    recording: RecordingReader<RecordedInput>,
+    start: SystemTime,
+    now: SystemTime,
+    is_finished: bool,
+    is_paused: bool,
+    pending_steps: usize,
+    seek_target: Option<SystemTime>,
+    top_images: VecDeque<RecordingFrame<YCbCr422Image>>,
+    bottom_images: VecDeque<RecordingFrame<YCbCr422Image>>,
+    ground_truths: VecDeque<RecordingFrame<GroundTruth>>,
+    samples: VecDeque<RecordingFrame<Samples>>,
+    messages: VecDeque<RecordingFrame<IncomingMessage>>,
+}
+
+impl ReplayState {
+    fn has_due_inputs(&self) -> bool {
+        let is_due = |timestamp: Option<SystemTime>| {
+            timestamp.map_or(false, |timestamp| timestamp <= self.now)
+        };
+        is_due(self.top_images.front().map(|frame| frame.timestamp))
+            || is_due(self.bottom_images.front().map(|frame| frame.timestamp))
+            || is_due(self.ground_truths.front().map(|frame| frame.timestamp))
+            || is_due(self.samples.front().map(|frame| frame.timestamp))
+            || is_due(self.messages.front().map(|frame| frame.timestamp))
+    }
+
+    fn clear_inputs(&mut self) {
+        self.top_images.clear();
+        self.bottom_images.clear();
+        self.ground_truths.clear();
+        self.samples.clear();
+        self.messages.clear();
+    }
+
+    fn read_until_next_sensor_data(&mut self) -> Result<Option<RecordingFrame<SensorData>>> {
+        for frame in self.recording.by_ref() {
+            let RecordingFrame { timestamp, data } = frame.wrap_err("failed to read recording")?;
+            match data {
+                RecordedInput::Ids(_) => {}
+                RecordedInput::Camera {
+                    camera_position: CameraPosition::Top,
+                    image,
+                } => self.top_images.push_back(RecordingFrame {
+                    timestamp,
+                    data: image,
+                }),
+                RecordedInput::Camera {
+                    camera_position: CameraPosition::Bottom,
+                    image,
+                } => self.bottom_images.push_back(RecordingFrame {
+                    timestamp,
+                    data: image,
+                }),
+                RecordedInput::GroundTruth(ground_truth) => {
+                    self.ground_truths.push_back(RecordingFrame {
+                        timestamp,
+                        data: ground_truth,
+                    })
+                }
+                RecordedInput::Microphones(samples) => self.samples.push_back(RecordingFrame {
+                    timestamp,
+                    data: samples,
+                }),
+                RecordedInput::Network(message) => self.messages.push_back(RecordingFrame {
+                    timestamp,
+                    data: message,
+                }),
+                RecordedInput::Sensors(sensor_data) => {
+                    return Ok(Some(RecordingFrame {
+                        timestamp,
+                        data: sensor_data,
+                    }))
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl HardwareInterface {
+    pub fn new(
+        keep_running: CancellationToken,
+        parameters: Parameters,
+        recording_path: impl AsRef<Path>,
+    ) -> Result<Self> {
+        let recording_path = recording_path.as_ref().to_path_buf();
+        let (recording, start, ids) = open_recording(&recording_path)?;
+        let paths = Paths {
+            recordings: None,
+            snapshots: None,
+            ..parameters.paths
+        };
+
+        Ok(Self {
+            ids,
+            paths,
+            recording_path,
+            state: Mutex::new(ReplayState {
+                recording,
+                start,
+                now: start,
+                is_finished: false,
+                is_paused: false,
+                pending_steps: 0,
+                seek_target: None,
+                top_images: Default::default(),
+                bottom_images: Default::default(),
+                ground_truths: Default::default(),
+                samples: Default::default(),
+                messages: Default::default(),
+            }),
+            state_changed: Condvar::new(),
+            keep_running,
+        })
+    }
+
+    pub fn pause(&self) {
+        self.state.lock().is_paused = true;
+    }
+
+    pub fn resume(&self) {
+        let mut state = self.state.lock();
+        state.is_paused = false;
+        state.pending_steps = 0;
+        drop(state);
+        self.state_changed.notify_all();
+    }
+
+    /// Advances a paused replay by one recorded sensor data, i.e. one control cycle
+    pub fn step(&self) {
+        self.state.lock().pending_steps += 1;
+        self.state_changed.notify_all();
+    }
+
+    /// Continues the replay at the first recorded sensor data after the offset from the start
+    pub fn seek(&self, offset: Duration) {
+        let mut state = self.state.lock();
+        state.seek_target = Some(state.start + offset);
+        drop(state);
+        self.state_changed.notify_all();
+    }
+
+    fn wait_until(
+        &self,
+        mut condition: impl FnMut(&mut ReplayState) -> bool,
+    ) -> Result<MutexGuard<ReplayState>> {
+        let mut state = self.state.lock();
+        while !condition(&mut *state) {
+            if self.keep_running.is_cancelled() {
+                bail!("termination requested");
+            }
+            self.state_changed
+                .wait_for(&mut state, CANCELLATION_POLL_INTERVAL);
+        }
+        Ok(state)
+    }
+
+    fn pop_due<T>(
+        &self,
+        queue: impl Fn(&mut ReplayState) -> &mut VecDeque<RecordingFrame<T>>,
+    ) -> Result<T> {
+        let mut state = self.wait_until(|state| {
+            let now = state.now;
+            state.is_finished
+                || queue(state)
+                    .front()
+                    .map_or(false, |frame| frame.timestamp <= now)
+        })?;
+        let frame = queue(&mut *state)
+            .pop_front()
+            .ok_or_else(|| eyre!("recording finished"))?;
+        drop(state);
+        self.state_changed.notify_all();
+        Ok(frame.data)
+    }
+}
+
+fn open_recording(path: &Path) -> Result<(RecordingReader<RecordedInput>, SystemTime, Ids)> {
+    let mut recording =
+        RecordingReader::<RecordedInput>::open(path).wrap_err("failed to open recording")?;
+    match recording.next() {
+        Some(Ok(RecordingFrame {
+            timestamp,
+            data: RecordedInput::Ids(ids),
+        })) => Ok((recording, timestamp, ids)),
+        Some(Err(error)) => Err(error).wrap_err("failed to read IDs from recording"),
+        _ => bail!("expected IDs as first recorded input"),
+    }
+}
+
+impl ActuatorInterface for HardwareInterface {
+    fn write_to_actuators(
+        &self,
+        _positions: Joints<f32>,
+        _stiffnesses: Joints<f32>,
+        _leds: Leds,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl AudioOutputInterface for HardwareInterface {
+    fn play_sound(&self, _sound: Sound) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl CameraInterface for HardwareInterface {
+    fn read_from_camera(&self, camera_position: CameraPosition) -> Result<YCbCr422Image> {
+        match camera_position {
+            CameraPosition::Top => self.pop_due(|state| &mut state.top_images),
+            CameraPosition::Bottom => self.pop_due(|state| &mut state.bottom_images),
+        }
+    }
+}
+
+impl GroundTruthInterface for HardwareInterface {
+    fn read_ground_truth(&self) -> Result<GroundTruth> {
+        self.pop_due(|state| &mut state.ground_truths)
+    }
+}
+
+impl IdInterface for HardwareInterface {
+    fn get_ids(&self) -> Ids {
+        self.ids.clone()
+    }
+}
+
+impl MicrophoneInterface for HardwareInterface {
+    fn read_from_microphones(&self) -> Result<Samples> {
+        self.pop_due(|state| &mut state.samples)
+    }
+}
+
+impl NetworkInterface for HardwareInterface {
+    fn read_from_network(&self) -> Result<IncomingMessage> {
+        self.pop_due(|state| &mut state.messages)
+    }
+
+    fn write_to_network(&self, _message: OutgoingMessage) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl PathsInterface for HardwareInterface {
+    fn get_paths(&self) -> Paths {
+        self.paths.clone()
+    }
+}
+
+impl SensorInterface for HardwareInterface {
+    fn read_from_sensors(&self) -> Result<SensorData> {
+        let mut state = self.wait_until(|state| {
+            state.seek_target.is_some()
+                || (!state.has_due_inputs() && (!state.is_paused || state.pending_steps > 0))
+        })?;
+        state.pending_steps = state.pending_steps.saturating_sub(1);
+        let seek_target = state.seek_target.take();
+        if let Some(seek_target) = seek_target {
+            if seek_target < state.now {
+                match open_recording(&self.recording_path) {
+                    Ok((recording, _start, _ids)) => state.recording = recording,
+                    Err(error) => {
+                        state.is_finished = true;
+                        drop(state);
+                        self.state_changed.notify_all();
+                        self.keep_running.cancel();
+                        return Err(error);
+                    }
+                }
+            }
+            state.clear_inputs();
+        }
+        let frame = loop {
+            let frame = state.read_until_next_sensor_data();
+            match (&frame, seek_target) {
+                (Ok(Some(frame)), Some(seek_target)) if frame.timestamp < seek_target => {
+                    state.clear_inputs();
+                }
+                _ => break frame,
+            }
+        };
+        if !matches!(frame, Ok(Some(_))) {
+            state.is_finished = true;
+            drop(state);
+            self.state_changed.notify_all();
+            self.keep_running.cancel();
+            return Err(frame.err().unwrap_or_else(|| eyre!("recording finished")));
+        }
+        let frame = frame?.expect("frame should be available");
+        state.now = frame.timestamp;
+        drop(state);
+        self.state_changed.notify_all();
+        Ok(frame.data)
+    }
+}
+
+impl TimeInterface for HardwareInterface {
+    fn get_now(&self) -> SystemTime {
+        self.state.lock().now
+    }
+}
+
+impl hulk::HardwareInterface for HardwareInterface {}
@@ -0,0 +1,114 @@
+#![recursion_limit = "256"]
+use std::{
+    env::args,
+    fs::File,
+    io::{stdin, stdout, BufRead},
+    path::PathBuf,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use color_eyre::{
+    eyre::{eyre, Result, WrapErr},
+    install,
+};
+use ctrlc::set_handler;
+use hardware::{IdInterface, PathsInterface};
+use hardware_interface::{HardwareInterface, Parameters};
+use hulk::run::run;
+use log::{error, info};
+use serde_json::from_reader;
+use tokio_util::sync::CancellationToken;
+
+mod hardware_interface;
+
+pub fn setup_logger() -> Result<(), fern::InitError> {
+    fern::Dispatch::new()
+        .format(|out, message, record| {
+            out.finish(format_args!(
+                "{}  {:<18}  {:>5}  {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                record.target(),
+                record.level(),
+                message
+            ))
+        })
+        .level(log::LevelFilter::Debug)
+        .chain(stdout())
+        .apply()?;
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    setup_logger()?;
+    install()?;
+    let recording_directory = args()
+        .nth(1)
+        .map(PathBuf::from)
+        .ok_or_else(|| eyre!("expected recording directory as first argument"))?;
+    let hardware_parameters_path = args()
+        .nth(2)
+        .unwrap_or("etc/parameters/hardware.json".to_string());
+    let keep_running = CancellationToken::new();
+    set_handler({
+        let keep_running = keep_running.clone();
+        move || {
+            keep_running.cancel();
+        }
+    })?;
+    let file =
+        File::open(hardware_parameters_path).wrap_err("failed to open hardware parameters")?;
+    let hardware_parameters: Parameters =
+        from_reader(file).wrap_err("failed to parse hardware parameters")?;
+    let communication_addresses = hardware_parameters.communication_addresses.clone();
+    let communication_token = hardware_parameters.communication_token.clone();
+    let hardware_interface = HardwareInterface::new(
+        keep_running.clone(),
+        hardware_parameters,
+        recording_directory.join("inputs.bincode.gz"),
+    )
+    .wrap_err("failed to create hardware interface")?;
+    let ids = hardware_interface.get_ids();
+    let paths = hardware_interface.get_paths();
+    let hardware_interface = Arc::new(hardware_interface);
+    thread::Builder::new()
+        .name("replay_control".to_string())
+        .spawn({
+            let hardware_interface = hardware_interface.clone();
+            move || control_replay(&hardware_interface)
+        })
+        .wrap_err("failed to spawn replay control thread")?;
+    run(
+        hardware_interface,
+        communication_addresses,
+        communication_token,
+        Default::default(),
+        Default::default(),
+        Default::default(),
+        paths.parameters,
+        ids.body_id,
+        ids.head_id,
+        keep_running,
+    )
+}
+
+/// Controls the replay with commands read from stdin: `pause`, `resume`, `step` and `seek <seconds>`
+fn control_replay(hardware_interface: &HardwareInterface) {
+    for line in stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["pause"] => hardware_interface.pause(),
+            ["resume"] => hardware_interface.resume(),
+            ["step"] => hardware_interface.step(),
+            ["seek", seconds] => match seconds.parse().map(Duration::try_from_secs_f32) {
+                Ok(Ok(offset)) => hardware_interface.seek(offset),
+                _ => error!("expected non-negative seconds as seek offset, got {seconds:?}"),
+            },
+            [] => {}
+            _ => info!("unknown command {line:?}, expected pause, resume, step or seek <seconds>"),
+        }
+    }
+}
@@ -12,8 +12,8 @@ use color_eyre::{
     Result,
 };
 use hardware::{
-    ActuatorInterface, CameraInterface, IdInterface, MicrophoneInterface, NetworkInterface,
-    PathsInterface, SensorInterface, TimeInterface,
+    ActuatorInterface, AudioOutputInterface, CameraInterface, GroundTruthInterface, IdInterface,
+    MicrophoneInterface, NetworkInterface, PathsInterface, SensorInterface, TimeInterface,
 };
 use serde::Deserialize;
 use spl_network::endpoint::{Endpoint, Ports};
@@ -27,7 +27,7 @@ use types::{
     messages::{IncomingMessage, OutgoingMessage},
     samples::Samples,
     ycbcr422_image::YCbCr422Image,
-    CameraPosition, Joints, Leds, SensorData,
+    CameraPosition, GroundTruth, Joints, Leds, SensorData, Sound,
 };
 use webots::Robot;
 
@@ -43,6 +43,10 @@ pub const SIMULATION_TIME_STEP: i32 = 10;
 #[derive(Clone, Debug, Deserialize)]
 pub struct Parameters {
     pub communication_addresses: Option<String>,
+    pub communication_token: Option<String>,
+    /// UDP port of the ground truth feed, no ground truth is received if absent
+    pub ground_truth_port: Option<u16>,
+    pub logging: Option<logging::Parameters>,
     pub paths: Paths,
     pub spl_network_ports: Ports,
 }
@@ -61,6 +65,7 @@ pub struct HardwareInterface {
     bottom_camera_requested: AtomicBool,
     paths: Paths,
     spl_network_endpoint: Endpoint,
+    ground_truth_endpoint: Option<ground_truth::endpoint::Endpoint>,
     async_runtime: Runtime,
     keep_running: CancellationToken,
     simulator_audio_synchronization: Barrier,
@@ -89,6 +94,11 @@ impl HardwareInterface {
             spl_network_endpoint: runtime
                 .block_on(Endpoint::new(parameters.spl_network_ports))
                 .wrap_err("failed to initialize SPL network")?,
+            ground_truth_endpoint: parameters
+                .ground_truth_port
+                .map(|port| runtime.block_on(ground_truth::endpoint::Endpoint::new(port)))
+                .transpose()
+                .wrap_err("failed to initialize ground truth endpoint")?,
             async_runtime: runtime,
             keep_running,
             simulator_audio_synchronization: Barrier::new(2),
@@ -272,6 +282,12 @@ impl ActuatorInterface for HardwareInterface {
     }
 }
 
+impl AudioOutputInterface for HardwareInterface {
+    fn play_sound(&self, _sound: Sound) -> Result<()> {
+        Ok(())
+    }
+}
+
 impl CameraInterface for HardwareInterface {
     fn read_from_camera(&self, camera_position: CameraPosition) -> Result<YCbCr422Image> {
         let result = match camera_position {
@@ -295,6 +311,25 @@ impl CameraInterface for HardwareInterface {
     }
 }
 
+impl GroundTruthInterface for HardwareInterface {
+    fn read_ground_truth(&self) -> Result<GroundTruth> {
+        self.async_runtime.block_on(async {
+            let Some(ground_truth_endpoint) = &self.ground_truth_endpoint else {
+                self.keep_running.cancelled().await;
+                return Err(eyre!("termination requested"));
+            };
+            select! {
+                result = ground_truth_endpoint.read() => {
+                    result.map_err(Error::from)
+                },
+                _ = self.keep_running.cancelled() => {
+                    Err(eyre!("termination requested"))
+                }
+            }
+        })
+    }
+}
+
 impl IdInterface for HardwareInterface {
     fn get_ids(&self) -> Ids {
         let name = from_utf8(Robot::get_name()).expect("robot name must be valid UTF-8");
@@ -373,6 +408,7 @@ impl SensorInterface for HardwareInterface {
             .wrap_err("failed to get force sensitive resistor values")?;
         let touch_sensors = self.keyboard.get_touch_sensors();
         let temperature_sensors = Joints::default();
+        let currents = Joints::default();
 
         self.update_cameras().wrap_err("failed to update cameras")?;
 
@@ -383,6 +419,8 @@ impl SensorInterface for HardwareInterface {
             force_sensitive_resistors,
             touch_sensors,
             temperature_sensors,
+            currents,
+            battery: None,
         })
     }
 }
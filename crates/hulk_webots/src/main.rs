@@ -1,14 +1,21 @@
 #![recursion_limit = "256"]
-use std::{env::args, fs::File, io::stdout, sync::Arc};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
+use clap::{Parser, ValueEnum};
 use color_eyre::{
     eyre::{Result, WrapErr},
     install,
 };
 use ctrlc::set_handler;
-use hardware::{IdInterface, PathsInterface};
+use hardware::{recording::RecordingInterface, IdInterface, PathsInterface};
 use hardware_interface::{HardwareInterface, Parameters};
 use hulk::run::run;
+use serde::de::DeserializeOwned;
 use serde_json::from_reader;
 use tokio_util::sync::CancellationToken;
 
@@ -20,29 +27,27 @@ mod joint_devices;
 mod keyboard_device;
 mod sonar_sensor_devices;
 
-pub fn setup_logger() -> Result<(), fern::InitError> {
-    fern::Dispatch::new()
-        .format(|out, message, record| {
-            out.finish(format_args!(
-                "{}  {:<18}  {:>5}  {}",
-                chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
-                record.target(),
-                record.level(),
-                message
-            ))
-        })
-        .level(log::LevelFilter::Debug)
-        .chain(stdout())
-        .apply()?;
-    Ok(())
+/// Runs the robot binary with a simulated hardware interface
+#[derive(Parser)]
+struct Arguments {
+    /// Simulated hardware the robot binary runs with
+    #[arg(long, value_enum, default_value_t = Hardware::Webots)]
+    hardware: Hardware,
+    #[arg(default_value = "etc/parameters/hardware.json")]
+    hardware_parameters_path: PathBuf,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Hardware {
+    /// Physics simulation in Webots including cameras, run as Webots controller
+    Webots,
+    /// Synthetic sensor data without a simulator, e.g. for CI runs
+    Headless,
 }
 
 fn main() -> Result<()> {
-    setup_logger()?;
     install()?;
-    let hardware_parameters_path = args()
-        .nth(1)
-        .unwrap_or("etc/parameters/hardware.json".to_string());
+    let arguments = Arguments::parse();
     let keep_running = CancellationToken::new();
     set_handler({
         let keep_running = keep_running.clone();
@@ -50,21 +55,96 @@ fn main() -> Result<()> {
             keep_running.cancel();
         }
     })?;
-    let file =
-        File::open(hardware_parameters_path).wrap_err("failed to open hardware parameters")?;
-    let hardware_parameters: Parameters =
-        from_reader(file).wrap_err("failed to parse hardware parameters")?;
-    let communication_addresses = hardware_parameters.communication_addresses.clone();
-    let hardware_interface = HardwareInterface::new(keep_running.clone(), hardware_parameters)
-        .wrap_err("failed to create hardware interface")?;
+    match arguments.hardware {
+        Hardware::Webots => {
+            let mut hardware_parameters: Parameters =
+                read_hardware_parameters(&arguments.hardware_parameters_path)?;
+            logging::setup(hardware_parameters.logging.as_ref())
+                .wrap_err("failed to set up logging")?;
+            if let Some(recordings) = hardware_parameters.paths.recordings.as_mut() {
+                let seconds = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .wrap_err("failed to get seconds since Unix epoch")?
+                    .as_secs();
+                recordings.push(seconds.to_string());
+            }
+            let communication_addresses = hardware_parameters.communication_addresses.clone();
+            let communication_token = hardware_parameters.communication_token.clone();
+            let hardware_interface =
+                HardwareInterface::new(keep_running.clone(), hardware_parameters)
+                    .wrap_err("failed to create hardware interface")?;
+            start(
+                hardware_interface,
+                communication_addresses,
+                communication_token,
+                keep_running,
+            )
+        }
+        Hardware::Headless => {
+            let hardware_parameters: hulk_headless::hardware_interface::Parameters =
+                read_hardware_parameters(&arguments.hardware_parameters_path)?;
+            logging::setup(hardware_parameters.logging.as_ref())
+                .wrap_err("failed to set up logging")?;
+            let communication_addresses = hardware_parameters.communication_addresses.clone();
+            let communication_token = hardware_parameters.communication_token.clone();
+            let hardware_interface = hulk_headless::hardware_interface::HardwareInterface::new(
+                keep_running.clone(),
+                hardware_parameters,
+            );
+            start(
+                hardware_interface,
+                communication_addresses,
+                communication_token,
+                keep_running,
+            )
+        }
+    }
+}
+
+fn read_hardware_parameters<HardwareParameters: DeserializeOwned>(
+    path: &Path,
+) -> Result<HardwareParameters> {
+    let file = File::open(path).wrap_err("failed to open hardware parameters")?;
+    from_reader(file).wrap_err("failed to parse hardware parameters")
+}
+
+fn start(
+    hardware_interface: impl hulk::HardwareInterface + Send + Sync + 'static,
+    communication_addresses: Option<String>,
+    communication_token: Option<String>,
+    keep_running: CancellationToken,
+) -> Result<()> {
     let ids = hardware_interface.get_ids();
     let paths = hardware_interface.get_paths();
-    run(
-        Arc::new(hardware_interface),
-        communication_addresses,
-        paths.parameters,
-        ids.body_id,
-        ids.head_id,
-        keep_running,
-    )
+    match paths.recordings {
+        Some(recordings) => {
+            let hardware_interface =
+                RecordingInterface::new(hardware_interface, recordings.join("inputs.bincode.gz"))
+                    .wrap_err("failed to create recording hardware interface")?;
+            run(
+                Arc::new(hardware_interface),
+                communication_addresses,
+                communication_token,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                paths.parameters,
+                ids.body_id,
+                ids.head_id,
+                keep_running,
+            )
+        }
+        None => run(
+            Arc::new(hardware_interface),
+            communication_addresses,
+            communication_token,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            paths.parameters,
+            ids.body_id,
+            ids.head_id,
+            keep_running,
+        ),
+    }
 }
@@ -0,0 +1,125 @@
+use std::{cell::Cell, io::stdout, path::PathBuf, sync::OnceLock, thread, time::SystemTime};
+
+use color_eyre::{eyre::WrapErr, Result};
+use fern::Dispatch;
+use log::{LevelFilter, Log, Metadata, Record};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, Receiver, Sender};
+
+use rotating_file::RotatingFile;
+
+mod rotating_file;
+
+const LOG_ENTRY_CAPACITY: usize = 1024;
+
+thread_local! {
+    static CYCLE_INDEX: Cell<Option<usize>> = Cell::new(None);
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Parameters {
+    pub path: PathBuf,
+    pub maximum_file_size: u64,
+    pub maximum_number_of_files: usize,
+}
+
+/// A single structured log message as written to the log file and sent to remote subscribers
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LogEntry {
+    pub timestamp: SystemTime,
+    pub level: String,
+    pub target: String,
+    pub thread: Option<String>,
+    pub cycle_index: Option<usize>,
+    pub message: String,
+}
+
+/// Attaches the given cycle index to all following log entries of the calling thread
+pub fn set_cycle_index(cycle_index: usize) {
+    CYCLE_INDEX.with(|index| index.set(Some(cycle_index)));
+}
+
+/// Subscribes to all log entries emitted after this call
+pub fn subscribe() -> Receiver<LogEntry> {
+    log_entries().subscribe()
+}
+
+fn log_entries() -> &'static Sender<LogEntry> {
+    static LOG_ENTRIES: OnceLock<Sender<LogEntry>> = OnceLock::new();
+    LOG_ENTRIES.get_or_init(|| broadcast::channel(LOG_ENTRY_CAPACITY).0)
+}
+
+/// Installs the global logger
+///
+/// Messages are printed to stdout as before. Additionally, every message is published to
+/// subscribers as `LogEntry` and, if parameters are given, appended as JSON line to a log file
+/// which is rotated after reaching the maximum file size.
+pub fn setup(parameters: Option<&Parameters>) -> Result<()> {
+    let file = parameters
+        .map(|parameters| {
+            RotatingFile::open(
+                &parameters.path,
+                parameters.maximum_file_size,
+                parameters.maximum_number_of_files,
+            )
+        })
+        .transpose()
+        .wrap_err("failed to open log file")?;
+    let structured_logger: Box<dyn Log> = Box::new(StructuredLogger {
+        file: file.map(Mutex::new),
+    });
+    Dispatch::new()
+        .level(LevelFilter::Debug)
+        .chain(
+            Dispatch::new()
+                .format(|out, message, record| {
+                    out.finish(format_args!(
+                        "{}  {:<18}  {:>5}  {}",
+                        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                        record.target(),
+                        record.level(),
+                        message
+                    ))
+                })
+                .chain(stdout()),
+        )
+        .chain(structured_logger)
+        .apply()
+        .wrap_err("failed to install logger")
+}
+
+struct StructuredLogger {
+    file: Option<Mutex<RotatingFile>>,
+}
+
+impl Log for StructuredLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        let entry = LogEntry {
+            timestamp: SystemTime::now(),
+            level: record.level().to_string(),
+            target: record.target().to_string(),
+            thread: thread::current().name().map(ToString::to_string),
+            cycle_index: CYCLE_INDEX.with(Cell::get),
+            message: record.args().to_string(),
+        };
+        if let Some(file) = &self.file {
+            // errors cannot be logged from within the logger, stdout already got the message
+            if let Ok(line) = serde_json::to_string(&entry) {
+                let _ = file.lock().write_line(&line);
+            }
+        }
+        // sending only fails if nobody is subscribed
+        let _ = log_entries().send(entry);
+    }
+
+    fn flush(&self) {
+        if let Some(file) = &self.file {
+            let _ = file.lock().flush();
+        }
+    }
+}
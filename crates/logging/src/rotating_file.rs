@@ -0,0 +1,117 @@
+use std::{
+    ffi::OsString,
+    fs::{create_dir_all, rename, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Appends lines to a file and moves it to `<path>.1` once it exceeds the maximum size
+///
+/// Older files are shifted to `<path>.2`, `<path>.3`, ... and the oldest one is overwritten when
+/// the maximum number of files is reached.
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    maximum_file_size: u64,
+    maximum_number_of_files: usize,
+}
+
+impl RotatingFile {
+    pub fn open(
+        path: impl AsRef<Path>,
+        maximum_file_size: u64,
+        maximum_number_of_files: usize,
+    ) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(directory) = path.parent() {
+            create_dir_all(directory)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            size,
+            maximum_file_size,
+            maximum_number_of_files,
+        })
+    }
+
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let length = line.len() as u64 + 1;
+        if self.size > 0 && self.size + length > self.maximum_file_size {
+            self.rotate()?;
+        }
+        self.file.write_all(format!("{line}\n").as_bytes())?;
+        self.size += length;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        for index in (1..self.maximum_number_of_files).rev() {
+            let source = self.rotated_path(index - 1);
+            if source.exists() {
+                rename(source, self.rotated_path(index))?;
+            }
+        }
+        self.file = File::create(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        if index == 0 {
+            return self.path.clone();
+        }
+        let mut path = OsString::from(self.path.as_os_str());
+        path.push(format!(".{index}"));
+        path.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env::temp_dir,
+        fs::{read_to_string, remove_dir_all},
+    };
+
+    use super::*;
+
+    #[test]
+    fn files_are_rotated_after_exceeding_maximum_size() {
+        let directory = temp_dir().join(format!("rotating_file_test_{}", std::process::id()));
+        let path = directory.join("hulk.log");
+        {
+            let mut file = RotatingFile::open(&path, 10, 3).unwrap();
+            for line in ["first", "second", "third", "fourth"] {
+                file.write_line(line).unwrap();
+            }
+        }
+
+        let read = |name: &str| read_to_string(directory.join(name)).ok();
+        let files = [
+            read("hulk.log"),
+            read("hulk.log.1"),
+            read("hulk.log.2"),
+            read("hulk.log.3"),
+        ];
+        remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            files,
+            [
+                Some("fourth\n".to_string()),
+                Some("third\n".to_string()),
+                Some("second\n".to_string()),
+                None,
+            ]
+        );
+    }
+}
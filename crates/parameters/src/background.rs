@@ -0,0 +1,159 @@
+use std::{
+    io,
+    path::PathBuf,
+    sync::mpsc::{sync_channel, SyncSender, TrySendError},
+    thread,
+};
+
+use log::warn;
+use serde::Serialize;
+use serde_json::{to_value, Value};
+use tokio::runtime;
+
+use super::{
+    directory::{serialize, Scope},
+    json::nest_value_at_path,
+};
+
+/// Number of queued serializations after which further ones are rejected
+const MAXIMUM_NUMBER_OF_PENDING_SERIALIZATIONS: usize = 8;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackgroundSerializationError {
+    #[error("failed to convert value into dynamic JSON object")]
+    ValueNotConvertedToJsonValue(#[source] serde_json::Error),
+    #[error("too many pending serializations, dropped {path:?}")]
+    TooManyPendingSerializations { path: String },
+    #[error("the serialization thread is gone")]
+    SerializationThreadGone,
+}
+
+struct Serialization {
+    parameters: Value,
+    scope: Scope,
+    path: String,
+}
+
+/// Serializes parameters into the parameter files on a separate thread
+///
+/// Nodes use this to store results like calibrations without blocking their cycler on the file
+/// system. Values are written like the `StoreParameters` request of communication does, i.e. only
+/// values differing from the currently stored parameters end up in the file of the scope and files
+/// are replaced atomically.
+pub struct BackgroundSerializer {
+    sender: SyncSender<Serialization>,
+}
+
+impl BackgroundSerializer {
+    pub fn spawn(
+        parameters_root_path: PathBuf,
+        body_id: String,
+        head_id: String,
+    ) -> io::Result<Self> {
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let (sender, receiver) =
+            sync_channel::<Serialization>(MAXIMUM_NUMBER_OF_PENDING_SERIALIZATIONS);
+        thread::Builder::new()
+            .name("parameter_serializer".to_string())
+            .spawn(move || {
+                for Serialization {
+                    parameters,
+                    scope,
+                    path,
+                } in receiver
+                {
+                    if let Err(error) = runtime.block_on(serialize(
+                        &parameters,
+                        scope,
+                        &path,
+                        &parameters_root_path,
+                        &body_id,
+                        &head_id,
+                    )) {
+                        warn!("Failed to serialize {path:?} into parameters: {error:?}");
+                    }
+                }
+            })?;
+        Ok(Self { sender })
+    }
+
+    /// Queues the value to be written at the path, e.g. `"imu_calibration"`, into the file of the
+    /// scope
+    pub fn serialize(
+        &self,
+        value: &impl Serialize,
+        scope: Scope,
+        path: &str,
+    ) -> Result<(), BackgroundSerializationError> {
+        let value =
+            to_value(value).map_err(BackgroundSerializationError::ValueNotConvertedToJsonValue)?;
+        let serialization = Serialization {
+            parameters: nest_value_at_path(path, value),
+            scope,
+            path: path.to_string(),
+        };
+        self.sender
+            .try_send(serialization)
+            .map_err(|error| match error {
+                TrySendError::Full(_) => {
+                    BackgroundSerializationError::TooManyPendingSerializations {
+                        path: path.to_string(),
+                    }
+                }
+                TrySendError::Disconnected(_) => {
+                    BackgroundSerializationError::SerializationThreadGone
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, fs, time::Duration};
+
+    use serde_json::{from_str, json};
+
+    use crate::directory::{Id, Location};
+
+    use super::*;
+
+    #[test]
+    fn values_are_serialized_into_the_file_of_the_scope() {
+        let root = temp_dir().join(format!("parameters_background_test_{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("default.json"),
+            json!({ "a": { "b": 1, "c": 2 } }).to_string(),
+        )
+        .unwrap();
+        let body_file_path = root.join("body.body_id.json");
+
+        let serializer =
+            BackgroundSerializer::spawn(root.clone(), "body_id".to_string(), "head_id".to_string())
+                .unwrap();
+        serializer
+            .serialize(
+                &json!({ "b": 1, "c": 3 }),
+                Scope {
+                    location: Location::All,
+                    id: Id::Body,
+                },
+                "a",
+            )
+            .unwrap();
+        drop(serializer);
+        let mut body = None;
+        for _ in 0..100 {
+            if let Ok(content) = fs::read_to_string(&body_file_path) {
+                body = Some(from_str::<Value>(&content).unwrap());
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(body, Some(json!({ "a": { "c": 3 } })));
+    }
+}
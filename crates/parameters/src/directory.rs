@@ -1,13 +1,18 @@
 use std::{
+    collections::BTreeMap,
     io,
     path::{Path, PathBuf},
 };
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{error, from_str, from_value, to_string_pretty, to_value, Value};
-use tokio::fs::{read_to_string, write};
+use tokio::fs::{read_to_string, rename, write};
+use types::{FieldDimensions, FieldDimensionsPreset};
 
-use super::json::{clone_nested_value, merge_json, prune_equal_branches};
+use super::{
+    json::{clone_nested_value, merge_json, nest_value_at_path, prune_equal_branches},
+    provenance::{Provenance, Source},
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum DirectoryError {
@@ -29,6 +34,14 @@ pub enum DirectoryError {
     ParametersNotConvertedToJsonValue(#[source] error::Error),
     #[error("failed to set head parameters of location")]
     HeadParametersOfLocationNotSet(#[source] SerializationError),
+    #[error("path {0:?} does not exist in parameters")]
+    PathNotExisting(String),
+    #[error("failed to get parameters of overlay file")]
+    OverlayParametersNotGet(#[source] SerializationError),
+    #[error("failed to set parameters of overlay file")]
+    OverlayParametersNotSet(#[source] SerializationError),
+    #[error("failed to fill field dimensions from preset")]
+    FieldDimensionsNotFilledFromPreset(#[source] error::Error),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -52,60 +65,101 @@ pub async fn deserialize<Parameters>(
 where
     Parameters: DeserializeOwned,
 {
-    let default_file_path = parameters_root_path.as_ref().join("default.json");
-    let mut parameters = read_from_file(default_file_path)
-        .await
-        .map_err(DirectoryError::DefaultParametersNotGet)?;
-
-    let location_directory = parameters_root_path
-        .as_ref()
-        .join(location_directory_from_head_id(head_id));
-
-    let location_default_file_path = location_directory.join("default.json");
-    if location_default_file_path.exists() {
-        let location_default_parameters = read_from_file(location_default_file_path)
-            .await
-            .map_err(DirectoryError::DefaultParametersOfLocationNotGet)?;
-        merge_json(&mut parameters, &location_default_parameters);
-    }
+    let (parameters, _provenance) =
+        deserialize_with_provenance(parameters_root_path, body_id, head_id).await?;
+    Ok(parameters)
+}
 
-    let body_file_path = parameters_root_path
-        .as_ref()
-        .join(format!("body.{}.json", body_id));
-    if body_file_path.exists() {
-        let body_parameters = read_from_file(body_file_path)
-            .await
-            .map_err(DirectoryError::BodyParametersNotGet)?;
-        merge_json(&mut parameters, &body_parameters);
+/// Merges all parameter files and records which file each value originates from
+pub async fn deserialize_with_provenance<Parameters>(
+    parameters_root_path: impl AsRef<Path>,
+    body_id: &str,
+    head_id: &str,
+) -> Result<(Parameters, Provenance), DirectoryError>
+where
+    Parameters: DeserializeOwned,
+{
+    let mut parameters = Value::Object(Default::default());
+    let mut provenance = Provenance::default();
+    for (file_path, is_required, into_error) in
+        layer_file_paths(parameters_root_path.as_ref(), body_id, head_id)
+    {
+        if !is_required && !file_path.exists() {
+            continue;
+        }
+        let layer = read_from_file(&file_path).await.map_err(into_error)?;
+        merge_json(&mut parameters, &layer);
+        provenance.merge(&layer, Source::File(file_path));
     }
+    fill_field_dimensions_from_preset(&mut parameters)
+        .map_err(DirectoryError::FieldDimensionsNotFilledFromPreset)?;
 
-    let head_file_path = parameters_root_path
-        .as_ref()
-        .join(format!("head.{}.json", head_id));
-    if head_file_path.exists() {
-        let head_parameters = read_from_file(head_file_path)
-            .await
-            .map_err(DirectoryError::HeadParametersNotGet)?;
-        merge_json(&mut parameters, &head_parameters);
-    }
+    let parameters =
+        from_value(parameters).map_err(DirectoryError::JsonValueNotConvertedToParameters)?;
+    Ok((parameters, provenance))
+}
 
-    let location_body_file_path = location_directory.join(format!("body.{}.json", body_id));
-    if location_body_file_path.exists() {
-        let location_body_parameters = read_from_file(location_body_file_path)
-            .await
-            .map_err(DirectoryError::BodyParametersOfLocationNotGet)?;
-        merge_json(&mut parameters, &location_body_parameters);
+/// Fills the `field_dimensions` from the preset selected by `field_dimensions_preset`
+///
+/// Values given in a `field_dimensions` object override the ones of the preset, e.g. a location
+/// only overriding the length of the field.
+fn fill_field_dimensions_from_preset(parameters: &mut Value) -> Result<(), error::Error> {
+    let Some(preset) = parameters.get("field_dimensions_preset") else {
+        return Ok(());
+    };
+    let preset: FieldDimensionsPreset = from_value(preset.clone())?;
+    let mut field_dimensions = to_value(FieldDimensions::from_preset(preset))?;
+    if let Some(given_field_dimensions) = parameters.get("field_dimensions") {
+        merge_json(&mut field_dimensions, given_field_dimensions);
     }
+    parameters["field_dimensions"] = field_dimensions;
+    Ok(())
+}
 
-    let location_head_file_path = location_directory.join(format!("head.{}.json", head_id));
-    if location_head_file_path.exists() {
-        let location_head_parameters = read_from_file(location_head_file_path)
-            .await
-            .map_err(DirectoryError::HeadParametersOfLocationNotGet)?;
-        merge_json(&mut parameters, &location_head_parameters);
-    }
+type LayerError = fn(SerializationError) -> DirectoryError;
 
-    from_value(parameters).map_err(DirectoryError::JsonValueNotConvertedToParameters)
+/// Parameter files in ascending precedence, i.e. values of later files override earlier ones
+///
+/// Only the default file is required. Values missing in all files fall back to the defaults of
+/// the parameters themselves.
+fn layer_file_paths(
+    parameters_root_path: &Path,
+    body_id: &str,
+    head_id: &str,
+) -> [(PathBuf, bool, LayerError); 6] {
+    let location_directory = parameters_root_path.join(location_directory_from_head_id(head_id));
+    [
+        (
+            parameters_root_path.join("default.json"),
+            true,
+            DirectoryError::DefaultParametersNotGet,
+        ),
+        (
+            location_directory.join("default.json"),
+            false,
+            DirectoryError::DefaultParametersOfLocationNotGet,
+        ),
+        (
+            parameters_root_path.join(format!("body.{}.json", body_id)),
+            false,
+            DirectoryError::BodyParametersNotGet,
+        ),
+        (
+            parameters_root_path.join(format!("head.{}.json", head_id)),
+            false,
+            DirectoryError::HeadParametersNotGet,
+        ),
+        (
+            location_directory.join(format!("body.{}.json", body_id)),
+            false,
+            DirectoryError::BodyParametersOfLocationNotGet,
+        ),
+        (
+            location_directory.join(format!("head.{}.json", head_id)),
+            false,
+            DirectoryError::HeadParametersOfLocationNotGet,
+        ),
+    ]
 }
 
 pub async fn serialize<Parameters>(
@@ -152,6 +206,80 @@ where
         .map_err(DirectoryError::HeadParametersOfLocationNotSet)
 }
 
+/// Writes the current values at the paths back into the parameter files providing them
+///
+/// Every value is written into the file with the highest precedence which already contains it,
+/// values not contained in any file are written into the head file of the current location. Files
+/// are replaced atomically.
+pub async fn store<Parameters>(
+    parameters: &Parameters,
+    paths: &[String],
+    parameters_root_path: impl AsRef<Path>,
+    body_id: &str,
+    head_id: &str,
+) -> Result<(), DirectoryError>
+where
+    Parameters: Serialize,
+{
+    let parameters =
+        to_value(parameters).map_err(DirectoryError::ParametersNotConvertedToJsonValue)?;
+    let (_stored_parameters, stored_provenance) =
+        deserialize_with_provenance::<Value>(&parameters_root_path, body_id, head_id).await?;
+    let fallback_file_path = file_path_from_scope(
+        Scope {
+            location: Location::Current,
+            id: Id::Head,
+        },
+        &parameters_root_path,
+        body_id,
+        head_id,
+    );
+
+    let mut file_updates: BTreeMap<PathBuf, Value> = BTreeMap::new();
+    for path in paths {
+        let value = parameters
+            .pointer(&format!("/{}", path.replace('.', "/")))
+            .ok_or_else(|| DirectoryError::PathNotExisting(path.clone()))?;
+        let mut leaves = Provenance::default();
+        leaves.replace(path, value, Source::Runtime);
+        for (leaf_path, _source) in leaves.iter() {
+            let file_path = stored_provenance
+                .sources(leaf_path)
+                .into_values()
+                .find_map(|source| match source {
+                    Source::File(file_path) => Some(file_path),
+                    Source::Default | Source::Runtime => None,
+                })
+                .unwrap_or_else(|| fallback_file_path.clone());
+            let leaf_value = parameters
+                .pointer(&format!("/{}", leaf_path.replace('.', "/")))
+                .cloned()
+                .unwrap_or(Value::Null);
+            merge_json(
+                file_updates
+                    .entry(file_path)
+                    .or_insert_with(|| Value::Object(Default::default())),
+                &nest_value_at_path(leaf_path, leaf_value),
+            );
+        }
+    }
+
+    for (file_path, update) in file_updates {
+        let mut file_parameters = if file_path.exists() {
+            read_from_file(&file_path)
+                .await
+                .map_err(DirectoryError::OverlayParametersNotGet)?
+        } else {
+            Value::Object(Default::default())
+        };
+        merge_json(&mut file_parameters, &update);
+        write_to_file(file_path, file_parameters)
+            .await
+            .map_err(DirectoryError::OverlayParametersNotSet)?;
+    }
+    Ok(())
+}
+
 #[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Scope {
     pub location: Location,
@@ -225,10 +353,96 @@ async fn write_to_file(
             source,
             path: file_path.as_ref().to_path_buf(),
         })? + "\n";
-    write(&file_path, file_contents.as_bytes())
+    // write into a temporary file first to never leave a partially written file behind
+    let temporary_file_path = file_path.as_ref().with_extension("json.tmp");
+    write(&temporary_file_path, file_contents.as_bytes())
+        .await
+        .map_err(|source| SerializationError::FileNotWritten {
+            source,
+            path: temporary_file_path.clone(),
+        })?;
+    rename(&temporary_file_path, &file_path)
         .await
         .map_err(|source| SerializationError::FileNotWritten {
             source,
             path: file_path.as_ref().to_path_buf(),
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, fs};
+
+    use serde_json::json;
+    use types::units::Meters;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn field_dimensions_are_filled_from_preset_and_overridden_per_value() {
+        let root = temp_dir().join(format!("parameters_preset_test_{}", std::process::id()));
+        fs::create_dir_all(root.join("nao_location")).unwrap();
+        fs::write(
+            root.join("default.json"),
+            json!({ "field_dimensions_preset": "SplFullSize" }).to_string(),
+        )
+        .unwrap();
+        fs::write(
+            root.join("nao_location/default.json"),
+            json!({ "field_dimensions": { "length": 7.5 } }).to_string(),
+        )
+        .unwrap();
+
+        let field_dimensions: FieldDimensions = deserialize::<Value>(&root, "body_id", "head_id")
+            .await
+            .map(|parameters| from_value(parameters["field_dimensions"].clone()).unwrap())
+            .unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            field_dimensions,
+            FieldDimensions {
+                length: Meters(7.5),
+                ..FieldDimensions::from_preset(FieldDimensionsPreset::SplFullSize)
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn stored_values_are_written_into_the_files_providing_them() {
+        let root = temp_dir().join(format!("parameters_store_test_{}", std::process::id()));
+        fs::create_dir_all(root.join("nao_location")).unwrap();
+        fs::write(
+            root.join("default.json"),
+            json!({ "a": { "b": 1, "c": 2 } }).to_string(),
+        )
+        .unwrap();
+        fs::write(
+            root.join("body.body_id.json"),
+            json!({ "a": { "c": 3 } }).to_string(),
+        )
+        .unwrap();
+
+        let parameters = json!({ "a": { "b": 10, "c": 30 }, "d": true });
+        store(
+            &parameters,
+            &["a".to_string(), "d".to_string()],
+            &root,
+            "body_id",
+            "head_id",
+        )
+        .await
+        .unwrap();
+
+        let read =
+            |path: PathBuf| -> Value { from_str(&fs::read_to_string(path).unwrap()).unwrap() };
+        let default = read(root.join("default.json"));
+        let body = read(root.join("body.body_id.json"));
+        let location_head = read(root.join("nao_location/head.head_id.json"));
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(default, json!({ "a": { "b": 10, "c": 2 } }));
+        assert_eq!(body, json!({ "a": { "c": 30 } }));
+        assert_eq!(location_head, json!({ "d": true }));
+    }
+}
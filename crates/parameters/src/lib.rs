@@ -1,2 +1,4 @@
+pub mod background;
 pub mod directory;
 pub mod json;
+pub mod provenance;
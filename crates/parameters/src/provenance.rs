@@ -0,0 +1,169 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Origin of the value of a parameter
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Source {
+    /// Not contained in any parameter file, the framework default of the parameter applies
+    Default,
+    /// Parameter file which set the value last
+    File(PathBuf),
+    /// Updated at runtime via communication and not stored to disk since
+    Runtime,
+}
+
+/// Sources of parameter values, tracked per leaf path, e.g. `"localization.odometry_noise"`
+///
+/// Leaves are all JSON values which are not objects. Arrays are considered as a whole.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Provenance {
+    sources: BTreeMap<String, Source>,
+}
+
+impl Provenance {
+    /// Records the source of all leaves of a parameter layer merged via `merge_json()`
+    ///
+    /// Leaves which are not contained in the layer keep their previous source.
+    pub fn merge(&mut self, layer: &Value, source: Source) {
+        self.merge_at("", layer, &source);
+    }
+
+    fn merge_at(&mut self, path: &str, layer: &Value, source: &Source) {
+        match layer {
+            Value::Object(object) => {
+                for (key, value) in object {
+                    self.merge_at(&join(path, key), value, source);
+                }
+            }
+            _ => self.replace(path, layer, source.clone()),
+        }
+    }
+
+    /// Records the source of a value replacing everything at the path, e.g. after an update
+    pub fn replace(&mut self, path: &str, value: &Value, source: Source) {
+        self.sources.retain(|leaf_path, _source| {
+            !is_at_or_below(leaf_path, path) && !is_at_or_below(path, leaf_path)
+        });
+        insert_leaves(&mut self.sources, path, value, &source);
+    }
+
+    /// Returns the sources of all leaves at or below the path
+    ///
+    /// Paths below a leaf, e.g. into an array, inherit the source of the leaf. Paths without any
+    /// recorded leaf have the `Default` source.
+    pub fn sources(&self, path: &str) -> BTreeMap<String, Source> {
+        let sources: BTreeMap<_, _> = self
+            .sources
+            .iter()
+            .filter(|(leaf_path, _source)| is_at_or_below(leaf_path, path))
+            .map(|(leaf_path, source)| (leaf_path.clone(), source.clone()))
+            .collect();
+        if !sources.is_empty() {
+            return sources;
+        }
+        let source = self
+            .sources
+            .iter()
+            .find(|(leaf_path, _source)| is_at_or_below(path, leaf_path))
+            .map_or(Source::Default, |(_leaf_path, source)| source.clone());
+        [(path.to_string(), source)].into()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Source)> {
+        self.sources.iter()
+    }
+}
+
+fn is_at_or_below(path: &str, ancestor: &str) -> bool {
+    ancestor.is_empty()
+        || path
+            .strip_prefix(ancestor)
+            .map_or(false, |suffix| suffix.is_empty() || suffix.starts_with('.'))
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn insert_leaves(
+    sources: &mut BTreeMap<String, Source>,
+    path: &str,
+    value: &Value,
+    source: &Source,
+) {
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            for (key, value) in object {
+                insert_leaves(sources, &join(path, key), value, source);
+            }
+        }
+        // the root itself is no parameter
+        _ if path.is_empty() => {}
+        _ => {
+            sources.insert(path.to_string(), source.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn later_records_override_earlier_ones() {
+        let mut provenance = Provenance::default();
+        provenance.merge(
+            &json!({ "a": { "b": 1, "c": [1, 2] }, "d": true }),
+            Source::File("default.json".into()),
+        );
+        provenance.merge(
+            &json!({ "a": { "b": 2 } }),
+            Source::File("head.json".into()),
+        );
+        provenance.replace("a.c", &json!([3]), Source::Runtime);
+
+        assert_eq!(
+            provenance.sources("a"),
+            [
+                ("a.b".to_string(), Source::File("head.json".into())),
+                ("a.c".to_string(), Source::Runtime),
+            ]
+            .into()
+        );
+        assert_eq!(
+            provenance.sources("a.c.0"),
+            [("a.c.0".to_string(), Source::Runtime)].into()
+        );
+        assert_eq!(
+            provenance.sources("e"),
+            [("e".to_string(), Source::Default)].into()
+        );
+    }
+
+    #[test]
+    fn replacing_a_value_replaces_all_leaves_below() {
+        let mut provenance = Provenance::default();
+        provenance.merge(
+            &json!({ "a": { "b": { "x": 1, "y": 2 }, "bc": 2 } }),
+            Source::File("default.json".into()),
+        );
+        provenance.replace("a.b", &json!({ "x": 1 }), Source::Runtime);
+
+        assert_eq!(
+            provenance.sources(""),
+            [
+                ("a.b.x".to_string(), Source::Runtime),
+                ("a.bc".to_string(), Source::File("default.json".into())),
+            ]
+            .into()
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use nalgebra::{point, vector, Point2, Point3, Vector2, Vector3};
+use nalgebra::{matrix, point, vector, Matrix2, Matrix3, Point2, Point3, Vector2, Vector3};
 use thiserror::Error;
 use types::CameraMatrix;
 
@@ -21,6 +21,16 @@ pub trait Projection {
         pixel_coordinates: Point2<f32>,
         z: f32,
     ) -> Result<Point2<f32>, Error>;
+    /// Projects the pixel onto the plane at height z and propagates the covariance of the pixel
+    /// and the covariance of small rotations of the camera around the axes of the ground frame
+    /// into the covariance of the projected point
+    fn pixel_to_ground_with_z_and_covariance(
+        &self,
+        pixel_coordinates: Point2<f32>,
+        z: f32,
+        pixel_covariance: Matrix2<f32>,
+        rotation_covariance: Matrix3<f32>,
+    ) -> Result<(Point2<f32>, Matrix2<f32>), Error>;
     fn ground_to_pixel(&self, ground_coordinates: Point2<f32>) -> Result<Point2<f32>, Error>;
     fn ground_with_z_to_pixel(
         &self,
@@ -86,6 +96,41 @@ impl Projection for CameraMatrix {
         Ok(point![intersection_point.x, intersection_point.y])
     }
 
+    fn pixel_to_ground_with_z_and_covariance(
+        &self,
+        pixel_coordinates: Point2<f32>,
+        z: f32,
+        pixel_covariance: Matrix2<f32>,
+        rotation_covariance: Matrix3<f32>,
+    ) -> Result<(Point2<f32>, Matrix2<f32>), Error> {
+        let ground_coordinates = self.pixel_to_ground_with_z(pixel_coordinates, z)?;
+
+        let rotation = self
+            .camera_to_ground
+            .rotation
+            .to_rotation_matrix()
+            .into_inner();
+        let camera_ray_over_ground = rotation * self.pixel_to_camera(pixel_coordinates);
+        let distance_to_plane = z - self.camera_to_ground.translation.z;
+        // the intersection is translation + ray * distance_to_plane / ray.z
+        let ray_to_intersection = matrix![
+            1.0, 0.0, -camera_ray_over_ground.x / camera_ray_over_ground.z;
+            0.0, 1.0, -camera_ray_over_ground.y / camera_ray_over_ground.z;
+        ] * (distance_to_plane / camera_ray_over_ground.z);
+        let pixel_to_camera_ray = matrix![
+            0.0, 0.0;
+            -1.0 / self.focal_length.x, 0.0;
+            0.0, -1.0 / self.focal_length.y;
+        ];
+        let pixel_jacobian = ray_to_intersection * rotation * pixel_to_camera_ray;
+        // a small rotation by the angles a changes the ray by a x ray = -ray x a
+        let rotation_jacobian = ray_to_intersection * -camera_ray_over_ground.cross_matrix();
+
+        let covariance = pixel_jacobian * pixel_covariance * pixel_jacobian.transpose()
+            + rotation_jacobian * rotation_covariance * rotation_jacobian.transpose();
+        Ok((ground_coordinates, covariance))
+    }
+
     fn ground_to_pixel(&self, ground_coordinates: Point2<f32>) -> Result<Point2<f32>, Error> {
         self.ground_with_z_to_pixel(ground_coordinates, 0.0)
     }
@@ -143,3 +188,111 @@ impl Projection for CameraMatrix {
         Ok(resolution.y as f32 * angle / self.field_of_view.y)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::{Isometry3, Translation3, UnitQuaternion};
+
+    use super::*;
+
+    fn camera_matrix_looking_down(pitch: f32) -> CameraMatrix {
+        CameraMatrix::from_normalized_focal_and_center(
+            vector![0.95, 1.27],
+            point![0.5, 0.5],
+            vector![640.0, 480.0],
+            Isometry3::identity(),
+            Isometry3::identity(),
+            Isometry3::from_parts(
+                Translation3::new(0.0, 0.0, 0.5),
+                UnitQuaternion::from_euler_angles(0.0, pitch, 0.0),
+            ),
+        )
+    }
+
+    #[test]
+    fn covariance_matches_numerical_propagation() {
+        let camera_matrix = camera_matrix_looking_down(0.3);
+        let pixel = point![400.0, 300.0];
+        let central_difference =
+            |positive: &CameraMatrix, negative: &CameraMatrix, offset: Vector2<f32>, step: f32| {
+                (positive
+                    .pixel_to_ground_with_z(pixel + offset, 0.05)
+                    .unwrap()
+                    - negative
+                        .pixel_to_ground_with_z(pixel - offset, 0.05)
+                        .unwrap())
+                    / (2.0 * step)
+            };
+
+        let (_, pixel_covariance) = camera_matrix
+            .pixel_to_ground_with_z_and_covariance(
+                pixel,
+                0.05,
+                Matrix2::from_diagonal(&vector![4.0, 9.0]),
+                Matrix3::zeros(),
+            )
+            .unwrap();
+        let horizontal = central_difference(&camera_matrix, &camera_matrix, vector![0.5, 0.0], 0.5);
+        let vertical = central_difference(&camera_matrix, &camera_matrix, vector![0.0, 0.5], 0.5);
+        let expected =
+            horizontal * horizontal.transpose() * 4.0 + vertical * vertical.transpose() * 9.0;
+        assert_relative_eq!(
+            pixel_covariance,
+            expected,
+            epsilon = 1e-9,
+            max_relative = 0.01
+        );
+
+        let (_, rotation_covariance) = camera_matrix
+            .pixel_to_ground_with_z_and_covariance(
+                pixel,
+                0.05,
+                Matrix2::zeros(),
+                Matrix3::from_diagonal_element(0.0001),
+            )
+            .unwrap();
+        let expected = [Vector3::x(), Vector3::y(), Vector3::z()].into_iter().fold(
+            Matrix2::zeros(),
+            |covariance, axis| {
+                let rotated = |angle: f32| {
+                    let mut rotated = camera_matrix.clone();
+                    rotated.camera_to_ground.rotation =
+                        UnitQuaternion::from_scaled_axis(axis * angle)
+                            * camera_matrix.camera_to_ground.rotation;
+                    rotated
+                };
+                let derivative =
+                    central_difference(&rotated(0.001), &rotated(-0.001), Vector2::zeros(), 0.001);
+                covariance + derivative * derivative.transpose() * 0.0001
+            },
+        );
+        assert_relative_eq!(
+            rotation_covariance,
+            expected,
+            epsilon = 1e-9,
+            max_relative = 0.01
+        );
+    }
+
+    #[test]
+    fn far_points_are_more_uncertain() {
+        let camera_matrix = camera_matrix_looking_down(0.3);
+        let covariance_at = |pixel| {
+            camera_matrix
+                .pixel_to_ground_with_z_and_covariance(
+                    pixel,
+                    0.0,
+                    Matrix2::identity(),
+                    Matrix3::from_diagonal_element(0.0001),
+                )
+                .unwrap()
+                .1
+        };
+
+        let close = covariance_at(point![320.0, 450.0]);
+        let far = covariance_at(point![320.0, 100.0]);
+
+        assert!(far[(0, 0)] > 10.0 * close[(0, 0)]);
+    }
+}
@@ -19,4 +19,6 @@ where
     },
     #[error("unexpected path segment {segment}")]
     UnexpectedPathSegment { segment: String },
+    #[error("variant {variant} is not the active variant")]
+    InactiveVariant { variant: String },
 }
@@ -1,6 +1,8 @@
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    hash::Hash,
     ops::{Deref, Range},
+    str::FromStr,
     sync::Arc,
 };
 
@@ -74,6 +76,90 @@ where
     }
 }
 
+/// Maps are addressed by their keys as path segments, e.g. `"Control.timings"` of a
+/// `BTreeMap<String, _>`, missing keys serialize as `None`
+macro_rules! implement_for_map {
+    ($map:ident, $($key_bounds:tt)+) => {
+        impl<K, V> SerializeHierarchy for $map<K, V>
+        where
+            K: FromStr + $($key_bounds)+,
+            V: SerializeHierarchy + Serialize + DeserializeOwned,
+        {
+            fn serialize_path<S>(
+                &self,
+                path: &str,
+                serializer: S,
+            ) -> Result<S::Ok, Error<S::Error>>
+            where
+                S: Serializer,
+            {
+                let (key, suffix) = match path.split_once('.') {
+                    Some((key, suffix)) => (key, Some(suffix)),
+                    None => (path, None),
+                };
+                let key = K::from_str(key).map_err(|_| Error::UnexpectedPathSegment {
+                    segment: key.to_string(),
+                })?;
+                match (self.get(&key), suffix) {
+                    (Some(value), Some(suffix)) => value.serialize_path(suffix, serializer),
+                    (Some(value), None) => value
+                        .serialize(serializer)
+                        .map_err(Error::SerializationFailed),
+                    (None, _) => (None as Option<()>)
+                        .serialize(serializer)
+                        .map_err(Error::SerializationFailed),
+                }
+            }
+
+            fn deserialize_path<'de, D>(
+                &mut self,
+                path: &str,
+                deserializer: D,
+            ) -> Result<(), Error<D::Error>>
+            where
+                D: Deserializer<'de>,
+            {
+                let (key, suffix) = match path.split_once('.') {
+                    Some((key, suffix)) => (key, Some(suffix)),
+                    None => (path, None),
+                };
+                let parsed_key = K::from_str(key).map_err(|_| Error::UnexpectedPathSegment {
+                    segment: key.to_string(),
+                })?;
+                match suffix {
+                    Some(suffix) => match self.get_mut(&parsed_key) {
+                        Some(value) => value.deserialize_path(suffix, deserializer),
+                        None => Err(Error::UnexpectedPathSegment {
+                            segment: key.to_string(),
+                        }),
+                    },
+                    None => {
+                        let value =
+                            V::deserialize(deserializer).map_err(Error::DeserializationFailed)?;
+                        self.insert(parsed_key, value);
+                        Ok(())
+                    }
+                }
+            }
+
+            fn exists(path: &str) -> bool {
+                match path.split_once('.') {
+                    Some((key, suffix)) => K::from_str(key).is_ok() && V::exists(suffix),
+                    None => K::from_str(path).is_ok(),
+                }
+            }
+
+            fn get_fields() -> BTreeSet<String> {
+                // keys are only known at runtime
+                Default::default()
+            }
+        }
+    };
+}
+
+implement_for_map!(BTreeMap, Ord);
+implement_for_map!(HashMap, Eq + Hash);
+
 impl<T> SerializeHierarchy for Range<T>
 where
     T: SerializeHierarchy + Serialize,
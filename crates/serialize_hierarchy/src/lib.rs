@@ -33,6 +33,8 @@ pub trait SerializeHierarchy {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use serde::{Deserialize, Serialize};
 
     use crate as serialize_hierarchy;
@@ -49,6 +51,13 @@ mod tests {
         field: bool,
     }
 
+    #[derive(Deserialize, Serialize, SerializeHierarchy)]
+    enum Command {
+        Stop,
+        Forward(Inner),
+        Kick { strength: f32, inner: Inner },
+    }
+
     #[test]
     fn primitive_fields_are_empty() {
         assert_eq!(bool::get_fields(), Default::default());
@@ -66,4 +75,84 @@ mod tests {
             ["inner".to_string(), "inner.field".to_string()].into()
         );
     }
+
+    #[test]
+    fn enum_fields_contain_variants_with_data() {
+        assert_eq!(
+            Command::get_fields(),
+            [
+                "Forward".to_string(),
+                "Forward.field".to_string(),
+                "Kick".to_string(),
+                "Kick.strength".to_string(),
+                "Kick.inner".to_string(),
+                "Kick.inner.field".to_string(),
+            ]
+            .into()
+        );
+        assert!(Command::exists("Kick.inner.field"));
+        assert!(!Command::exists("Stop"));
+    }
+
+    #[test]
+    fn enum_paths_serialize_active_variant() {
+        let command = Command::Kick {
+            strength: 0.5,
+            inner: Inner { field: true },
+        };
+        let serialize = |path| {
+            command
+                .serialize_path(path, serde_json::value::Serializer)
+                .unwrap()
+        };
+
+        assert_eq!(serialize("Kick.strength"), serde_json::json!(0.5));
+        assert_eq!(serialize("Kick.inner.field"), serde_json::json!(true));
+        assert_eq!(
+            serialize("Kick"),
+            serde_json::json!({"strength": 0.5, "inner": {"field": true}})
+        );
+        assert_eq!(serialize("Forward.field"), serde_json::Value::Null);
+    }
+
+    #[test]
+    fn enum_paths_deserialize_into_active_variant() {
+        let mut command = Command::Forward(Inner { field: false });
+
+        command
+            .deserialize_path("Forward.field", serde_json::json!(true))
+            .unwrap();
+        assert!(matches!(command, Command::Forward(Inner { field: true })));
+        assert!(matches!(
+            command.deserialize_path("Kick.strength", serde_json::json!(1.0)),
+            Err(Error::InactiveVariant { .. })
+        ));
+    }
+
+    #[test]
+    fn map_paths_address_values_by_key() {
+        let mut map = BTreeMap::from([("left".to_string(), Inner { field: false })]);
+
+        map.deserialize_path("left.field", serde_json::json!(true))
+            .unwrap();
+        map.deserialize_path("right", serde_json::json!({"field": true}))
+            .unwrap();
+
+        assert_eq!(
+            map.serialize_path("left.field", serde_json::value::Serializer)
+                .unwrap(),
+            serde_json::json!(true)
+        );
+        assert_eq!(
+            map.serialize_path("right", serde_json::value::Serializer)
+                .unwrap(),
+            serde_json::json!({"field": true})
+        );
+        assert_eq!(
+            map.serialize_path("center", serde_json::value::Serializer)
+                .unwrap(),
+            serde_json::Value::Null
+        );
+        assert!(BTreeMap::<String, Inner>::exists("center.field"));
+    }
 }
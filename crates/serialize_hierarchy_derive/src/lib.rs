@@ -2,10 +2,11 @@ use std::collections::HashSet;
 
 use proc_macro2::TokenStream;
 use proc_macro_error::{abort, proc_macro_error};
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
-    parse_macro_input, punctuated::Punctuated, Data, DataStruct, DeriveInput, Generics, Ident, Lit,
-    Meta, MetaNameValue, NestedMeta, Token, Type, WherePredicate,
+    ext::IdentExt, parse_macro_input, punctuated::Punctuated, Data, DataEnum, DataStruct,
+    DeriveInput, Fields, Generics, Ident, Lit, Meta, MetaNameValue, NestedMeta, Token, Type,
+    WherePredicate,
 };
 
 #[proc_macro_derive(SerializeHierarchy, attributes(serialize_hierarchy))]
@@ -18,7 +19,10 @@ pub fn serialize_hierarchy(input: proc_macro::TokenStream) -> proc_macro::TokenS
 fn process_input(mut input: DeriveInput) -> TokenStream {
     let fields = match &input.data {
         Data::Struct(data) => read_fields(data),
-        Data::Enum(..) => Vec::new(),
+        Data::Enum(data) => {
+            let data = data.clone();
+            return process_enum(input, &data);
+        }
         Data::Union(data) => {
             abort!(
                 data.union_token,
@@ -147,6 +151,386 @@ fn process_input(mut input: DeriveInput) -> TokenStream {
     implementation
 }
 
+fn process_enum(mut input: DeriveInput, data: &DataEnum) -> TokenStream {
+    let type_attributes = parse_attributes(&input.attrs);
+    if type_attributes.contains(&TypeAttribute::AsJpeg) {
+        abort!(input.ident, "`as_jpeg` is not supported for `enum`");
+    }
+    extend_where_clause_from_attributes(&mut input.generics, type_attributes);
+
+    let name = &input.ident;
+    let type_name = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let variants: Vec<_> = read_variants(data)
+        .into_iter()
+        .filter(|variant| !variant.fields.is_empty())
+        .collect();
+    let path_serializations = variants.iter().map(generate_variant_serialization);
+    let path_deserializations = variants
+        .iter()
+        .map(|variant| generate_variant_deserialization(variant, &type_name));
+    let path_exists_getters = variants.iter().map(generate_variant_exists_getter);
+    let variant_names = variants
+        .iter()
+        .map(|variant| variant.identifier.to_string());
+    let variant_exists_getter = if variants.is_empty() {
+        quote! { false }
+    } else {
+        quote! { matches!(path, #(#variant_names)|*) }
+    };
+    let field_chains = variants.iter().map(generate_variant_field_chain);
+
+    quote! {
+        // inactive variants are unreachable for enums with a single variant
+        #[allow(unreachable_patterns)]
+        impl #impl_generics serialize_hierarchy::SerializeHierarchy for #name #ty_generics #where_clause {
+            fn serialize_path<S>(
+                &self,
+                path: &str,
+                serializer: S,
+            ) -> Result<S::Ok, serialize_hierarchy::Error<S::Error>>
+            where
+                S: serde::Serializer,
+            {
+                let (variant, suffix) = match path.split_once('.') {
+                    Some((variant, suffix)) => (variant, Some(suffix)),
+                    None => (path, None),
+                };
+                match variant {
+                    #(#path_serializations,)*
+                    segment => Err(serialize_hierarchy::Error::UnexpectedPathSegment {
+                        segment: segment.to_string(),
+                    }),
+                }
+            }
+
+            fn deserialize_path<'de, D>(
+                &mut self,
+                path: &str,
+                deserializer: D,
+            ) -> Result<(), serialize_hierarchy::Error<D::Error>>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let (variant, suffix) = match path.split_once('.') {
+                    Some((variant, suffix)) => (variant, Some(suffix)),
+                    None => (path, None),
+                };
+                match variant {
+                    #(#path_deserializations,)*
+                    segment => Err(serialize_hierarchy::Error::UnexpectedPathSegment {
+                        segment: segment.to_string(),
+                    }),
+                }
+            }
+
+            fn exists(path: &str) -> bool {
+                let split = path.split_once('.');
+                match split {
+                    Some((variant, suffix)) => match variant {
+                        #(#path_exists_getters,)*
+                        _ => false,
+                    },
+                    None => #variant_exists_getter,
+                }
+            }
+
+            fn get_fields() -> std::collections::BTreeSet<String> {
+                std::iter::empty::<std::string::String>()
+                    #(#field_chains)*
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Binds the serializable fields of the variant as `field_<name>`, skipped fields are ignored
+fn generate_variant_pattern(variant: &Variant) -> TokenStream {
+    let identifier = &variant.identifier;
+    let bindings = variant.fields.iter().map(|field| &field.binding);
+    if variant.is_tuple {
+        let bindings = variant.all_members.iter().map(|member| {
+            match variant.fields.iter().find(|field| &field.name == member) {
+                Some(field) => field.binding.to_token_stream(),
+                None => quote! { _ },
+            }
+        });
+        quote! { Self::#identifier(#(#bindings),*) }
+    } else {
+        let members = variant.fields.iter().map(|field| &field.member);
+        quote! { Self::#identifier { #(#members: #bindings,)* .. } }
+    }
+}
+
+fn generate_variant_serialization(variant: &Variant) -> TokenStream {
+    let pattern_name = variant.identifier.to_string();
+    let pattern = generate_variant_pattern(variant);
+    let (whole_serialization, field_serializations) = match variant.newtype_field() {
+        Some(field) => {
+            let binding = &field.binding;
+            let field_serialization = if field.is_leaf() {
+                quote! {
+                    Err(serialize_hierarchy::Error::UnexpectedPathSegment {
+                        segment: path.to_string(),
+                    })
+                }
+            } else {
+                quote! { #binding.serialize_path(path, serializer) }
+            };
+            (
+                quote! {
+                    serde::Serialize::serialize(#binding, serializer)
+                        .map_err(serialize_hierarchy::Error::SerializationFailed)
+                },
+                field_serialization,
+            )
+        }
+        None => {
+            let whole_serialization = if variant.is_tuple {
+                let bindings = variant.fields.iter().map(|field| &field.binding);
+                quote! {
+                    serde::Serialize::serialize(&(#(#bindings,)*), serializer)
+                        .map_err(serialize_hierarchy::Error::SerializationFailed)
+                }
+            } else {
+                let number_of_fields = variant.fields.len();
+                let fields = variant.fields.iter().map(|field| {
+                    let name = &field.name;
+                    let binding = &field.binding;
+                    quote! {
+                        state
+                            .serialize_field(#name, #binding)
+                            .map_err(serialize_hierarchy::Error::SerializationFailed)?;
+                    }
+                });
+                quote! {{
+                    use serde::ser::SerializeStruct;
+                    let mut state = serializer
+                        .serialize_struct(#pattern_name, #number_of_fields)
+                        .map_err(serialize_hierarchy::Error::SerializationFailed)?;
+                    #(#fields)*
+                    state
+                        .end()
+                        .map_err(serialize_hierarchy::Error::SerializationFailed)
+                }}
+            };
+            let path_serializations = variant.path_fields().map(|field| {
+                let name = &field.name;
+                let binding = &field.binding;
+                quote! {
+                    #name => #binding.serialize_path(suffix, serializer)
+                }
+            });
+            let serde_serializations = variant.fields.iter().map(|field| {
+                let name = &field.name;
+                let binding = &field.binding;
+                quote! {
+                    #name => serde::Serialize::serialize(#binding, serializer)
+                        .map_err(serialize_hierarchy::Error::SerializationFailed)
+                }
+            });
+            let suffix = variant.suffix_binding();
+            (
+                whole_serialization,
+                quote! {
+                    match path.split_once('.') {
+                        Some((name, #suffix)) => match name {
+                            #(#path_serializations,)*
+                            segment => Err(serialize_hierarchy::Error::UnexpectedPathSegment {
+                                segment: segment.to_string(),
+                            }),
+                        },
+                        None => match path {
+                            #(#serde_serializations,)*
+                            segment => Err(serialize_hierarchy::Error::UnexpectedPathSegment {
+                                segment: segment.to_string(),
+                            }),
+                        },
+                    }
+                },
+            )
+        }
+    };
+    quote! {
+        #pattern_name => {
+            match self {
+                #pattern => match suffix {
+                    None => #whole_serialization,
+                    Some(path) => #field_serializations,
+                },
+                _ => serializer
+                    .serialize_none()
+                    .map_err(serialize_hierarchy::Error::SerializationFailed),
+            }
+        }
+    }
+}
+
+fn generate_variant_deserialization(variant: &Variant, type_name: &str) -> TokenStream {
+    let pattern_name = variant.identifier.to_string();
+    let pattern = generate_variant_pattern(variant);
+    let (whole_deserialization, field_deserializations) = match variant.newtype_field() {
+        Some(field) => {
+            let binding = &field.binding;
+            let ty = &field.ty;
+            let field_deserialization = if field.is_leaf() {
+                quote! {
+                    Err(serialize_hierarchy::Error::UnexpectedPathSegment {
+                        segment: path.to_string(),
+                    })
+                }
+            } else {
+                quote! { #binding.deserialize_path(path, deserializer) }
+            };
+            (
+                quote! {{
+                    *#binding = <#ty as serde::Deserialize>::deserialize(deserializer)
+                        .map_err(serialize_hierarchy::Error::DeserializationFailed)?;
+                    Ok(())
+                }},
+                field_deserialization,
+            )
+        }
+        None => {
+            let path_deserializations = variant.path_fields().map(|field| {
+                let name = &field.name;
+                let binding = &field.binding;
+                quote! {
+                    #name => #binding.deserialize_path(suffix, deserializer)
+                }
+            });
+            let serde_deserializations = variant.fields.iter().map(|field| {
+                let name = &field.name;
+                let binding = &field.binding;
+                let ty = &field.ty;
+                quote! {
+                    #name => {
+                        *#binding = <#ty as serde::Deserialize>::deserialize(deserializer)
+                            .map_err(serialize_hierarchy::Error::DeserializationFailed)?;
+                        Ok(())
+                    }
+                }
+            });
+            let suffix = variant.suffix_binding();
+            (
+                quote! {
+                    Err(serialize_hierarchy::Error::TypeDoesNotSupportDeserialization {
+                        type_name: #type_name,
+                        path: variant.to_string(),
+                    })
+                },
+                quote! {
+                    match path.split_once('.') {
+                        Some((name, #suffix)) => match name {
+                            #(#path_deserializations,)*
+                            segment => Err(serialize_hierarchy::Error::UnexpectedPathSegment {
+                                segment: segment.to_string(),
+                            }),
+                        },
+                        None => match path {
+                            #(#serde_deserializations,)*
+                            segment => Err(serialize_hierarchy::Error::UnexpectedPathSegment {
+                                segment: segment.to_string(),
+                            }),
+                        },
+                    }
+                },
+            )
+        }
+    };
+    quote! {
+        #pattern_name => {
+            match self {
+                #pattern => match suffix {
+                    None => #whole_deserialization,
+                    Some(path) => #field_deserializations,
+                },
+                _ => Err(serialize_hierarchy::Error::InactiveVariant {
+                    variant: variant.to_string(),
+                }),
+            }
+        }
+    }
+}
+
+fn generate_variant_exists_getter(variant: &Variant) -> TokenStream {
+    let pattern_name = variant.identifier.to_string();
+    match variant.newtype_field() {
+        Some(field) if field.is_leaf() => quote! {
+            #pattern_name => false
+        },
+        Some(field) => {
+            let ty = &field.ty;
+            quote! {
+                #pattern_name => <#ty as serialize_hierarchy::SerializeHierarchy>::exists(suffix)
+            }
+        }
+        None => {
+            let path_exists_getters = variant.path_fields().map(|field| {
+                let name = &field.name;
+                let ty = &field.ty;
+                quote! {
+                    #name => <#ty as serialize_hierarchy::SerializeHierarchy>::exists(suffix)
+                }
+            });
+            let names = variant.fields.iter().map(|field| &field.name);
+            let suffix = variant.suffix_binding();
+            quote! {
+                #pattern_name => match suffix.split_once('.') {
+                    Some((name, #suffix)) => match name {
+                        #(#path_exists_getters,)*
+                        _ => false,
+                    },
+                    None => matches!(suffix, #(#names)|*),
+                }
+            }
+        }
+    }
+}
+
+fn generate_variant_field_chain(variant: &Variant) -> TokenStream {
+    let variant_name = variant.identifier.to_string();
+    match variant.newtype_field() {
+        Some(field) if field.is_leaf() => quote! {
+            .chain(std::iter::once(#variant_name.to_string()))
+        },
+        Some(field) => {
+            let ty = &field.ty;
+            let pattern = format!("{variant_name}.{{}}");
+            quote! {
+                .chain(std::iter::once(#variant_name.to_string()))
+                .chain(
+                    <#ty as serialize_hierarchy::SerializeHierarchy>::get_fields()
+                        .into_iter()
+                        .map(|name| format!(#pattern, name))
+                )
+            }
+        }
+        None => {
+            let field_names = variant
+                .fields
+                .iter()
+                .map(|field| format!("{variant_name}.{}", field.name));
+            let path_field_chains = variant.path_fields().map(|field| {
+                let pattern = format!("{variant_name}.{}.{{}}", field.name);
+                let ty = &field.ty;
+                quote! {
+                    .chain(
+                        <#ty as serialize_hierarchy::SerializeHierarchy>::get_fields()
+                            .into_iter()
+                            .map(|name| format!(#pattern, name))
+                    )
+                }
+            });
+            quote! {
+                .chain(std::iter::once(#variant_name.to_string()))
+                #(.chain(std::iter::once(#field_names.to_string())))*
+                #(#path_field_chains)*
+            }
+        }
+    }
+}
+
 fn extend_where_clause_from_attributes(
     generics: &mut Generics,
     type_attributes: HashSet<TypeAttribute>,
@@ -333,6 +717,53 @@ struct Field {
     ty: Type,
 }
 
+#[derive(Debug)]
+struct Variant {
+    identifier: Ident,
+    is_tuple: bool,
+    /// Names of all fields including skipped ones, i.e. the indices of tuple variants
+    all_members: Vec<String>,
+    fields: Vec<VariantField>,
+}
+
+impl Variant {
+    /// A tuple variant with a single field is transparent, i.e. paths continue in its field
+    fn newtype_field(&self) -> Option<&VariantField> {
+        match self.fields.as_slice() {
+            [field] if self.is_tuple && self.all_members.len() == 1 => Some(field),
+            _ => None,
+        }
+    }
+
+    fn path_fields(&self) -> impl Iterator<Item = &VariantField> {
+        self.fields.iter().filter(|field| !field.is_leaf())
+    }
+
+    /// Binding of the remaining path after a field name, unused if all fields are leafs
+    fn suffix_binding(&self) -> Ident {
+        if self.path_fields().next().is_some() {
+            format_ident!("suffix")
+        } else {
+            format_ident!("_suffix")
+        }
+    }
+}
+
+#[derive(Debug)]
+struct VariantField {
+    attributes: HashSet<FieldAttribute>,
+    member: syn::Member,
+    name: String,
+    binding: Ident,
+    ty: Type,
+}
+
+impl VariantField {
+    fn is_leaf(&self) -> bool {
+        self.attributes.contains(&FieldAttribute::Leaf)
+    }
+}
+
 fn parse_meta_items(attribute: &syn::Attribute) -> Vec<NestedMeta> {
     if !attribute.path.is_ident("serialize_hierarchy") {
         return Vec::new();
@@ -349,31 +780,7 @@ fn read_fields(input: &DataStruct) -> Vec<Field> {
         .fields
         .iter()
         .map(|field| {
-            let attributes = field
-                .attrs
-                .iter()
-                .flat_map(parse_meta_items)
-                .map(|meta| match meta {
-                    NestedMeta::Meta(Meta::Path(word)) if word.is_ident("skip") => {
-                        FieldAttribute::Skip
-                    }
-                    NestedMeta::Meta(Meta::Path(word)) if word.is_ident("leaf") => {
-                        FieldAttribute::Leaf
-                    }
-                    NestedMeta::Meta(meta_item) => {
-                        let path = meta_item
-                            .path()
-                            .into_token_stream()
-                            .to_string()
-                            .replace(' ', "");
-                        abort!(meta_item.path(), "unknown attribute `{}`", path)
-                    }
-
-                    NestedMeta::Lit(lit) => {
-                        abort!(lit, "unexpected literal in attribute")
-                    }
-                })
-                .collect();
+            let attributes = parse_field_attributes(field);
             let identifier = field
                 .ident
                 .clone()
@@ -387,3 +794,68 @@ fn read_fields(input: &DataStruct) -> Vec<Field> {
         })
         .collect()
 }
+
+fn parse_field_attributes(field: &syn::Field) -> HashSet<FieldAttribute> {
+    field
+        .attrs
+        .iter()
+        .flat_map(parse_meta_items)
+        .map(|meta| match meta {
+            NestedMeta::Meta(Meta::Path(word)) if word.is_ident("skip") => FieldAttribute::Skip,
+            NestedMeta::Meta(Meta::Path(word)) if word.is_ident("leaf") => FieldAttribute::Leaf,
+            NestedMeta::Meta(meta_item) => {
+                let path = meta_item
+                    .path()
+                    .into_token_stream()
+                    .to_string()
+                    .replace(' ', "");
+                abort!(meta_item.path(), "unknown attribute `{}`", path)
+            }
+
+            NestedMeta::Lit(lit) => {
+                abort!(lit, "unexpected literal in attribute")
+            }
+        })
+        .collect()
+}
+
+fn read_variants(input: &DataEnum) -> Vec<Variant> {
+    input
+        .variants
+        .iter()
+        .map(|variant| {
+            let members: Vec<_> = variant
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| match &field.ident {
+                    Some(identifier) => identifier.unraw().to_string(),
+                    None => index.to_string(),
+                })
+                .collect();
+            let fields = variant
+                .fields
+                .iter()
+                .enumerate()
+                .zip(members.iter())
+                .map(|((index, field), name)| VariantField {
+                    attributes: parse_field_attributes(field),
+                    member: match &field.ident {
+                        Some(identifier) => syn::Member::Named(identifier.clone()),
+                        None => syn::Member::Unnamed(index.into()),
+                    },
+                    name: name.clone(),
+                    binding: format_ident!("field_{}", name),
+                    ty: field.ty.clone(),
+                })
+                .filter(|field| !field.attributes.contains(&FieldAttribute::Skip))
+                .collect();
+            Variant {
+                identifier: variant.ident.clone(),
+                is_tuple: matches!(variant.fields, Fields::Unnamed(..)),
+                all_members: members,
+                fields,
+            }
+        })
+        .collect()
+}
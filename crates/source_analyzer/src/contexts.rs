@@ -1,4 +1,8 @@
-use syn::{Expr, ExprLit, File, GenericArgument, Ident, Item, Lit, PathArguments, Type};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Attribute, Expr, ExprLit, File, GenericArgument, Ident, Item, Lit, PathArguments, Token, Type,
+};
 
 use crate::{
     error::ParseError,
@@ -101,6 +105,16 @@ pub enum Field {
         name: Ident,
         path: Path,
     },
+    /// Whether any additional output of the node is subscribed in this cycle
+    AdditionalOutputsSubscribed {
+        name: Ident,
+    },
+    DecimatedInput {
+        data_type: Type,
+        decimation: usize,
+        name: Ident,
+        path: Path,
+    },
     HardwareInterface {
         name: Ident,
     },
@@ -112,15 +126,19 @@ pub enum Field {
     Input {
         cycler_instance: Option<String>,
         data_type: Type,
+        metadata: FieldMetadata,
         name: Ident,
         path: Path,
     },
     MainOutput {
         data_type: Type,
+        metadata: FieldMetadata,
         name: Ident,
     },
     Parameter {
+        constraints: ParameterConstraints,
         data_type: Type,
+        metadata: FieldMetadata,
         name: Ident,
         path: Path,
     },
@@ -134,6 +152,8 @@ pub enum Field {
         data_type: Type,
         name: Ident,
         path: Path,
+        /// Whether the state is saved on shutdown and restored on startup, see `#[snapshot]`
+        snapshot: bool,
     },
     RequiredInput {
         cycler_instance: Option<String>,
@@ -171,6 +191,48 @@ impl Field {
                 format!("{context_name} may not contain members of type {field_type}"),
             ));
         }
+        if field_type != "Parameter" {
+            if let Some(attribute) = field
+                .attrs
+                .iter()
+                .find(|attribute| attribute.path.is_ident("parameter"))
+            {
+                return Err(ParseError::new_spanned(
+                    attribute,
+                    format!("unexpected `parameter` attribute on {field_type} `{field_name}`"),
+                ));
+            }
+        }
+        if !matches!(field_type.as_str(), "Input" | "MainOutput" | "Parameter") {
+            if let Some(attribute) = field
+                .attrs
+                .iter()
+                .find(|attribute| attribute.path.is_ident("metadata"))
+            {
+                return Err(ParseError::new_spanned(
+                    attribute,
+                    format!("unexpected `metadata` attribute on {field_type} `{field_name}`"),
+                ));
+            }
+        }
+        let snapshot_attribute = field
+            .attrs
+            .iter()
+            .find(|attribute| attribute.path.is_ident("snapshot"));
+        if let Some(attribute) = snapshot_attribute {
+            if field_type != "PersistentState" {
+                return Err(ParseError::new_spanned(
+                    attribute,
+                    format!("unexpected `snapshot` attribute on {field_type} `{field_name}`"),
+                ));
+            }
+            if !attribute.tokens.is_empty() {
+                return Err(ParseError::new_spanned(
+                    &attribute.tokens,
+                    "expected `#[snapshot]` without arguments",
+                ));
+            }
+        }
         match field_type.as_str() {
             "AdditionalOutput" => {
                 let (data_type, path) = extract_two_arguments(&first_segment.arguments, false)?;
@@ -187,6 +249,27 @@ impl Field {
                     path,
                 })
             }
+            "AdditionalOutputsSubscribed" => {
+                if !first_segment.arguments.is_empty() {
+                    return Err(ParseError::new_spanned(
+                        &first_segment.arguments,
+                        "expected no generic parameters",
+                    ));
+                }
+                Ok(Field::AdditionalOutputsSubscribed {
+                    name: field_name.clone(),
+                })
+            }
+            "DecimatedInput" => {
+                let (data_type, path, decimation) =
+                    extract_decimated_arguments(&first_segment.arguments)?;
+                Ok(Field::DecimatedInput {
+                    data_type: data_type.to_absolute(uses),
+                    decimation,
+                    name: field_name.clone(),
+                    path,
+                })
+            }
             "HardwareInterface" => Ok(Field::HardwareInterface {
                 name: field_name.clone(),
             }),
@@ -220,6 +303,7 @@ impl Field {
                 Ok(Field::Input {
                     cycler_instance,
                     data_type: data_type.to_absolute(uses),
+                    metadata: FieldMetadata::try_from_attributes(&field.attrs)?,
                     name: field_name.clone(),
                     path,
                 })
@@ -228,13 +312,27 @@ impl Field {
                 let data_type = extract_one_argument(&first_segment.arguments)?;
                 Ok(Field::MainOutput {
                     data_type: data_type.to_absolute(uses),
+                    metadata: FieldMetadata::try_from_attributes(&field.attrs)?,
                     name: field_name.clone(),
                 })
             }
             "Parameter" => {
                 let (data_type, path) = extract_two_arguments(&first_segment.arguments, true)?;
+                let constraints = ParameterConstraints::try_from_attributes(&field.attrs)?;
+                let is_optional_leaf = path
+                    .segments
+                    .last()
+                    .map_or(false, |segment| segment.is_optional);
+                if is_optional_leaf && constraints.default.is_some() {
+                    return Err(ParseError::new_spanned(
+                        field_name,
+                        format!("unexpected default value for optional parameter `{field_name}`, missing optional parameters are `None`"),
+                    ));
+                }
                 Ok(Field::Parameter {
+                    constraints,
                     data_type: data_type.to_absolute(uses),
+                    metadata: FieldMetadata::try_from_attributes(&field.attrs)?,
                     name: field_name.clone(),
                     path,
                 })
@@ -255,6 +353,7 @@ impl Field {
                     data_type: data_type.to_absolute(uses),
                     name: field_name.clone(),
                     path,
+                    snapshot: snapshot_attribute.is_some(),
                 })
             }
             "RequiredInput" => {
@@ -294,6 +393,157 @@ impl Field {
     }
 }
 
+/// Default value and validation of a `Parameter` declared via `#[parameter(...)]`
+///
+/// ```ignore
+/// #[parameter(default = 0.5, range = 0.0..=1.0)]
+/// ratio: Parameter<f32, "ball_filter.ratio">,
+/// #[parameter(non_empty)]
+/// names: Parameter<Vec<String>, "names">,
+/// ```
+///
+/// Expressions are inserted into the generated parameter structs and therefore need to be
+/// self-contained, i.e. use absolute paths.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ParameterConstraints {
+    /// Value used if the key is missing in the configuration
+    pub default: Option<Expr>,
+    /// Range the value needs to be contained in
+    pub range: Option<Expr>,
+    /// Whether `is_empty()` of the value needs to be `false`
+    pub non_empty: bool,
+}
+
+impl ParameterConstraints {
+    fn try_from_attributes(attributes: &[Attribute]) -> Result<Self, ParseError> {
+        let mut constraints = Self::default();
+        for attribute in attributes
+            .iter()
+            .filter(|attribute| attribute.path.is_ident("parameter"))
+        {
+            let options = attribute
+                .parse_args_with(Punctuated::<ParameterOption, Token![,]>::parse_terminated)?;
+            for option in options {
+                match (option.name.to_string().as_str(), option.value) {
+                    ("default", Some(value)) if constraints.default.is_none() => {
+                        constraints.default = Some(value);
+                    }
+                    ("range", Some(value)) if constraints.range.is_none() => {
+                        constraints.range = Some(value);
+                    }
+                    ("non_empty", None) if !constraints.non_empty => {
+                        constraints.non_empty = true;
+                    }
+                    ("default" | "range" | "non_empty", _) => {
+                        return Err(ParseError::new_spanned(
+                            &option.name,
+                            format!("unexpected `{}`, expected `default = <expression>`, `range = <range expression>`, or `non_empty` at most once each", option.name),
+                        ))
+                    }
+                    _ => {
+                        return Err(ParseError::new_spanned(
+                            &option.name,
+                            format!("unknown parameter option `{}`, try one of `default`, `range`, `non_empty`", option.name),
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(constraints)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.default.is_none() && self.range.is_none() && !self.non_empty
+    }
+}
+
+/// Human-readable description and unit of a field declared via `#[metadata(...)]`
+///
+/// ```ignore
+/// #[metadata(doc = "Distance of the ball to the robot", unit = "m")]
+/// ball_distance: MainOutput<f32>,
+/// ```
+///
+/// Supported on `Input`, `MainOutput`, and `Parameter` fields. Metadata of inputs is attached to
+/// the output they read.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct FieldMetadata {
+    pub doc: Option<String>,
+    pub unit: Option<String>,
+}
+
+impl FieldMetadata {
+    fn try_from_attributes(attributes: &[Attribute]) -> Result<Self, ParseError> {
+        let mut metadata = Self::default();
+        for attribute in attributes
+            .iter()
+            .filter(|attribute| attribute.path.is_ident("metadata"))
+        {
+            let options = attribute
+                .parse_args_with(Punctuated::<ParameterOption, Token![,]>::parse_terminated)?;
+            for option in options {
+                let value = match &option.value {
+                    Some(Expr::Lit(ExprLit {
+                        lit: Lit::Str(value),
+                        ..
+                    })) => value.value(),
+                    _ => {
+                        return Err(ParseError::new_spanned(
+                            &option.name,
+                            format!("expected `{} = \"...\"`", option.name),
+                        ))
+                    }
+                };
+                match option.name.to_string().as_str() {
+                    "doc" if metadata.doc.is_none() => metadata.doc = Some(value),
+                    "unit" if metadata.unit.is_none() => metadata.unit = Some(value),
+                    "doc" | "unit" => {
+                        return Err(ParseError::new_spanned(
+                            &option.name,
+                            format!(
+                                "unexpected `{}`, expected `doc` and `unit` at most once each",
+                                option.name
+                            ),
+                        ))
+                    }
+                    _ => {
+                        return Err(ParseError::new_spanned(
+                            &option.name,
+                            format!(
+                                "unknown metadata option `{}`, try one of `doc`, `unit`",
+                                option.name
+                            ),
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(metadata)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.doc.is_none() && self.unit.is_none()
+    }
+}
+
+struct ParameterOption {
+    name: Ident,
+    value: Option<Expr>,
+}
+
+impl Parse for ParameterOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { name, value })
+    }
+}
+
 fn extract_one_argument(arguments: &PathArguments) -> Result<Type, ParseError> {
     match arguments {
         PathArguments::AngleBracketed(arguments) => {
@@ -349,6 +599,49 @@ fn extract_two_arguments(
     }
 }
 
+fn extract_decimated_arguments(
+    arguments: &PathArguments,
+) -> Result<(Type, Path, usize), ParseError> {
+    match arguments {
+        PathArguments::AngleBracketed(arguments) => {
+            if arguments.args.len() != 3 {
+                return Err(ParseError::new_spanned(
+                    &arguments.args,
+                    "expected exactly three generic parameters",
+                ));
+            }
+            match (&arguments.args[0], &arguments.args[1], &arguments.args[2]) {
+                (GenericArgument::Type(type_argument), GenericArgument::Const(Expr::Lit(
+                    ExprLit {
+                        lit: Lit::Str(path_argument), ..
+                    },
+                )), GenericArgument::Const(Expr::Lit(
+                    ExprLit {
+                        lit: Lit::Int(decimation_argument), ..
+                    },
+                ))) => {
+                    let decimation = decimation_argument.base10_parse()?;
+                    if decimation == 0 {
+                        return Err(ParseError::new_spanned(decimation_argument, "expected decimation of at least one cycle"));
+                    }
+                    Ok((
+                        type_argument.clone(),
+                        Path::try_new(path_argument.token().to_string().trim_matches('"'), true).map_err(|message| ParseError::new_spanned(arguments, message))?,
+                        decimation,
+                    ))
+                }
+                _ => Err(
+                    ParseError::new_spanned(&arguments.args,"expected type in first generic parameter, string literal in second generic parameter, and integer literal in third generic parameter")
+                ),
+            }
+        }
+        _ => Err(ParseError::new_spanned(
+            arguments,
+            "expected exactly three generic parameters",
+        )),
+    }
+}
+
 fn extract_three_arguments(
     arguments: &PathArguments,
     allow_optionals: bool,
@@ -392,6 +685,8 @@ fn member_type_allowed(context_name: &str, field_type: &str) -> bool {
         "CreationContext" => ["HardwareInterface", "Parameter", "PersistentState"].as_slice(),
         "CycleContext" => [
             "AdditionalOutput",
+            "AdditionalOutputsSubscribed",
+            "DecimatedInput",
             "HardwareInterface",
             "HistoricInput",
             "Input",
@@ -477,6 +772,7 @@ mod tests {
                 data_type,
                 name,
                 path: Path { segments },
+                ..
             } if data_type == type_option_usize
                 && name == "name"
                 && segments.len() == 3
@@ -533,8 +829,9 @@ mod tests {
         )
         .unwrap();
         match parsed_field {
-            Field::MainOutput { data_type, name }
-                if data_type == type_option_usize && name == "name" => {}
+            Field::MainOutput {
+                data_type, name, ..
+            } if data_type == type_option_usize && name == "name" => {}
             _ => panic!("Unexpected parsed field from {field:?}: {parsed_field:?}"),
         }
 
@@ -549,7 +846,9 @@ mod tests {
         )
         .unwrap();
         match parsed_field {
-            Field::MainOutput { data_type, name } if data_type == type_usize && name == "name" => {}
+            Field::MainOutput {
+                data_type, name, ..
+            } if data_type == type_usize && name == "name" => {}
             _ => panic!("Unexpected parsed field from {field:?}: {parsed_field:?}"),
         }
 
@@ -569,6 +868,7 @@ mod tests {
                 data_type,
                 name,
                 path: Path { segments },
+                ..
             } if data_type == type_option_usize
                 && name == "name"
                 && segments.len() == 3
@@ -600,6 +900,7 @@ mod tests {
                 data_type,
                 name,
                 path: Path { segments },
+                ..
             } if cycler_instance == "Control"
                 && data_type == type_option_usize
                 && name == "name"
@@ -642,6 +943,7 @@ mod tests {
                 data_type,
                 name,
                 path: Path { segments },
+                ..
             } if data_type == type_usize
                 && name == "name"
                 && segments.len() == 3
@@ -672,6 +974,7 @@ mod tests {
                 data_type,
                 name,
                 path: Path { segments },
+                ..
             } if data_type == type_option_usize
                 && name == "name"
                 && segments.len() == 3
@@ -687,6 +990,77 @@ mod tests {
             _ => panic!("Unexpected parsed field from {field:?}: {parsed_field:?}"),
         }
 
+        // with metadata
+        let field = "#[metadata(doc = \"Distance\", unit = \"m\")] name: MainOutput<f32>";
+        let fields = format!("{{ {field} }}");
+        let named_fields: FieldsNamed = parse_str(&fields).unwrap();
+        let parsed_field = Field::try_from_field(
+            named_fields.named.first().unwrap(),
+            &empty_uses,
+            "MainOutputs",
+        )
+        .unwrap();
+        match parsed_field {
+            Field::MainOutput {
+                metadata: FieldMetadata { doc, unit },
+                ..
+            } if doc.as_deref() == Some("Distance") && unit.as_deref() == Some("m") => {}
+            _ => panic!("Unexpected parsed field from {field:?}: {parsed_field:?}"),
+        }
+
+        // metadata is only supported with string values on inputs, outputs, and parameters
+        for field in [
+            "#[metadata(unit = 42)] name: Parameter<usize, \"a.b.c\">",
+            "#[metadata(range = \"0..1\")] name: Parameter<usize, \"a.b.c\">",
+            "#[metadata(unit = \"m\")] name: PersistentState<usize, \"a.b.c\">",
+        ] {
+            let fields = format!("{{ {field} }}");
+            let named_fields: FieldsNamed = parse_str(&fields).unwrap();
+            assert!(Field::try_from_field(
+                named_fields.named.first().unwrap(),
+                &empty_uses,
+                "CycleContext"
+            )
+            .is_err());
+        }
+
+        // decimated
+        let field = "DecimatedInput<Option<usize>, \"a.b?\", 10>";
+        let fields = format!("{{ name: {field} }}");
+        let named_fields: FieldsNamed = parse_str(&fields).unwrap();
+        let parsed_field = Field::try_from_field(
+            named_fields.named.first().unwrap(),
+            &empty_uses,
+            "CycleContext",
+        )
+        .unwrap();
+        match parsed_field {
+            Field::DecimatedInput {
+                data_type,
+                decimation: 10,
+                name,
+                path: Path { segments },
+            } if data_type == type_option_usize
+                && name == "name"
+                && segments.len() == 2
+                && segments[0].name == "a"
+                && !segments[0].is_optional
+                && segments[1].name == "b"
+                && segments[1].is_optional => {}
+            _ => panic!("Unexpected parsed field from {field:?}: {parsed_field:?}"),
+        }
+
+        // decimation needs to be at least one
+        let field = "DecimatedInput<usize, \"a.b.c\", 0>";
+        let fields = format!("{{ name: {field} }}");
+        let named_fields: FieldsNamed = parse_str(&fields).unwrap();
+        assert!(Field::try_from_field(
+            named_fields.named.first().unwrap(),
+            &empty_uses,
+            "CycleContext"
+        )
+        .is_err());
+
         // without optionals
         let field = "PerceptionInput<usize, \"Control\", \"a.b.c\">";
         let fields = format!("{{ name: {field} }}");
@@ -766,6 +1140,7 @@ mod tests {
                 data_type,
                 name,
                 path: Path { segments },
+                snapshot: false,
             } if data_type == type_usize
                 && name == "name"
                 && segments.len() == 3
@@ -781,6 +1156,36 @@ mod tests {
             _ => panic!("Unexpected parsed field from {field:?}: {parsed_field:?}"),
         }
 
+        // marked for snapshots
+        let field = "#[snapshot] name: PersistentState<usize, \"a.b.c\">";
+        let fields = format!("{{ {field} }}");
+        let named_fields: FieldsNamed = parse_str(&fields).unwrap();
+        let parsed_field = Field::try_from_field(
+            named_fields.named.first().unwrap(),
+            &empty_uses,
+            "CreationContext",
+        )
+        .unwrap();
+        match parsed_field {
+            Field::PersistentState { snapshot: true, .. } => {}
+            _ => panic!("Unexpected parsed field from {field:?}: {parsed_field:?}"),
+        }
+
+        // snapshot attributes are only supported for persistent state without arguments
+        for field in [
+            "#[snapshot] name: Input<usize, \"a.b.c\">",
+            "#[snapshot(always)] name: PersistentState<usize, \"a.b.c\">",
+        ] {
+            let fields = format!("{{ {field} }}");
+            let named_fields: FieldsNamed = parse_str(&fields).unwrap();
+            assert!(Field::try_from_field(
+                named_fields.named.first().unwrap(),
+                &empty_uses,
+                "CycleContext"
+            )
+            .is_err());
+        }
+
         // // optionals are supported
         // let field = "PersistentState<usize, \"a.b?.c\">";
         // let fields = format!("{{ name: {field} }}");
@@ -877,4 +1282,136 @@ mod tests {
             _ => panic!("Unexpected parsed field from {field:?}: {parsed_field:?}"),
         }
     }
+
+    #[test]
+    fn parameter_defaults_and_validation_are_parsed() {
+        let named_fields: FieldsNamed = parse_str(
+            r#"{
+                #[parameter(default = 0.5, range = 0.0..=1.0)]
+                ratio: Parameter<f32, "a.ratio">,
+                #[parameter(non_empty)]
+                names: Parameter<Vec<String>, "a.names">,
+                plain: Parameter<f32, "a.plain">,
+            }"#,
+        )
+        .unwrap();
+        let constraints: Vec<_> = named_fields
+            .named
+            .iter()
+            .map(|field| {
+                match Field::try_from_field(field, &Uses::new(), "CycleContext").unwrap() {
+                    Field::Parameter { constraints, .. } => constraints,
+                    parsed_field => panic!("Unexpected parsed field: {parsed_field:?}"),
+                }
+            })
+            .collect();
+
+        assert_eq!(
+            constraints[0],
+            ParameterConstraints {
+                default: Some(parse_str("0.5").unwrap()),
+                range: Some(parse_str("0.0..=1.0").unwrap()),
+                non_empty: false,
+            }
+        );
+        assert_eq!(
+            constraints[1],
+            ParameterConstraints {
+                default: None,
+                range: None,
+                non_empty: true,
+            }
+        );
+        assert!(constraints[2].is_empty());
+    }
+
+    #[test]
+    fn invalid_parameter_constraints_are_rejected() {
+        let fields = [
+            // missing optional parameters are `None`
+            r#"#[parameter(default = 1.0)] name: Parameter<Option<f32>, "a.b?">"#,
+            r#"#[parameter(default = 1.0, default = 2.0)] name: Parameter<f32, "a.b">"#,
+            r#"#[parameter(minimum = 1.0)] name: Parameter<f32, "a.b">"#,
+            r#"#[parameter(non_empty = true)] name: Parameter<Vec<f32>, "a.b">"#,
+            r#"#[parameter(default = 1.0)] name: Input<f32, "a.b">"#,
+            r#"#[parameter(non_empty)] name: Input<Vec<f32>, "a.b">"#,
+        ];
+        for field in fields {
+            let named_fields: FieldsNamed = parse_str(&format!("{{ {field} }}")).unwrap();
+            assert!(
+                Field::try_from_field(
+                    named_fields.named.first().unwrap(),
+                    &Uses::new(),
+                    "CycleContext"
+                )
+                .is_err(),
+                "{field} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn decimated_inputs_are_parsed() {
+        let named_fields: FieldsNamed =
+            parse_str(r#"{ name: DecimatedInput<Option<usize>, "a?.b", 4> }"#).unwrap();
+        let parsed_field = Field::try_from_field(
+            named_fields.named.first().unwrap(),
+            &Uses::new(),
+            "CycleContext",
+        )
+        .unwrap();
+        match parsed_field {
+            Field::DecimatedInput {
+                data_type,
+                decimation: 4,
+                name,
+                path: Path { segments },
+            } if data_type == parse_str::<Type>("Option<usize>").unwrap()
+                && name == "name"
+                && segments.len() == 2
+                && segments[0].name == "a"
+                && segments[0].is_optional
+                && segments[1].name == "b"
+                && !segments[1].is_optional => {}
+            _ => panic!("Unexpected parsed field: {parsed_field:?}"),
+        }
+
+        let invalid_fields = [
+            ("CycleContext", r#"DecimatedInput<usize, "a", 0>"#),
+            ("CycleContext", r#"DecimatedInput<usize, "a">"#),
+            ("CycleContext", r#"DecimatedInput<usize, "a", "4">"#),
+            ("CreationContext", r#"DecimatedInput<usize, "a", 4>"#),
+        ];
+        for (context_name, field) in invalid_fields {
+            let named_fields: FieldsNamed = parse_str(&format!("{{ name: {field} }}")).unwrap();
+            assert!(
+                Field::try_from_field(
+                    named_fields.named.first().unwrap(),
+                    &Uses::new(),
+                    context_name
+                )
+                .is_err(),
+                "{field} was accepted in {context_name}"
+            );
+        }
+    }
+
+    #[test]
+    fn additional_outputs_subscribed_is_parsed_only_without_generics_in_cycle_context() {
+        let parse = |context_name, field: &str| {
+            let named_fields: FieldsNamed = parse_str(&format!("{{ name: {field} }}")).unwrap();
+            Field::try_from_field(
+                named_fields.named.first().unwrap(),
+                &Uses::new(),
+                context_name,
+            )
+        };
+
+        match parse("CycleContext", "AdditionalOutputsSubscribed") {
+            Ok(Field::AdditionalOutputsSubscribed { name }) if name == "name" => {}
+            result => panic!("Unexpected result: {result:?}"),
+        }
+        assert!(parse("CycleContext", "AdditionalOutputsSubscribed<bool>").is_err());
+        assert!(parse("CreationContext", "AdditionalOutputsSubscribed").is_err());
+    }
 }
@@ -1,14 +1,13 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     path::Path,
 };
 
-use serde::Deserialize;
-use topological_sort::TopologicalSort;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     contexts::Field,
-    error::Error,
+    error::{Dependency, Error, Location},
     manifest::{CyclerManifest, FrameworkManifest},
     node::Node,
 };
@@ -36,12 +35,74 @@ impl Cyclers {
     }
 
     pub fn sort_nodes(&mut self) -> Result<(), Error> {
+        self.check_foreign_inputs()?;
         for cycler in &mut self.cyclers {
             cycler.sort_nodes()?;
         }
         Ok(())
     }
 
+    /// Checks that every input from another cycler instance is produced by a node of its cycler
+    fn check_foreign_inputs(&self) -> Result<(), Error> {
+        for cycler in &self.cyclers {
+            for node in cycler.iter_nodes() {
+                for field in &node.contexts.cycle_context {
+                    let (name, cycler_instance, path) = match field {
+                        Field::Input {
+                            cycler_instance: Some(cycler_instance),
+                            name,
+                            path,
+                            ..
+                        }
+                        | Field::PerceptionInput {
+                            cycler_instance,
+                            name,
+                            path,
+                            ..
+                        }
+                        | Field::RequiredInput {
+                            cycler_instance: Some(cycler_instance),
+                            name,
+                            path,
+                            ..
+                        } => (name, cycler_instance, path),
+                        _ => continue,
+                    };
+                    let Some(output) = path.segments.first().map(|segment| &segment.name) else {
+                        continue;
+                    };
+                    let location = Location::new(&node.file_path, name);
+                    let Some(producing_cycler) = self
+                        .cyclers
+                        .iter()
+                        .find(|cycler| cycler.instances.contains(cycler_instance))
+                    else {
+                        return Err(Error::UnknownCyclerInstance {
+                            node: node.name.clone(),
+                            output: output.clone(),
+                            cycler_instance: cycler_instance.clone(),
+                            location,
+                        });
+                    };
+                    let is_produced = producing_cycler.iter_nodes().any(|node| {
+                        node.contexts.main_outputs.iter().any(
+                            |field| matches!(field, Field::MainOutput { name, .. } if name == output),
+                        )
+                    });
+                    if !is_produced {
+                        return Err(Error::MissingOutput {
+                            node: node.name.clone(),
+                            output: output.clone(),
+                            cycler_instance: cycler_instance.clone(),
+                            location,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn number_of_instances(&self) -> usize {
         self.cyclers
             .iter()
@@ -84,7 +145,7 @@ impl Cyclers {
     }
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum CyclerKind {
     Perception,
     RealTime,
@@ -126,83 +187,144 @@ impl Cycler {
         })
     }
 
+    /// Orders the nodes such that every node is executed after the producers of its inputs
+    ///
+    /// Nodes without dependencies between each other keep their order from the manifest. This
+    /// keeps the generated code deterministic.
     pub fn sort_nodes(&mut self) -> Result<(), Error> {
-        let output_name_to_setup_node: HashMap<_, _> = self
-            .setup_nodes
-            .iter()
-            .flat_map(|node| {
-                node.contexts
-                    .main_outputs
-                    .iter()
-                    .filter_map(move |field| match field {
-                        Field::MainOutput { name, .. } => Some((name.to_string(), node)),
-                        _ => None,
-                    })
-            })
-            .collect();
+        self.check_unique_outputs()?;
+        let output_name_to_setup_node = output_to_node(&self.setup_nodes);
         let sorted_setup_nodes = sort_nodes(
+            &self.name,
             &self.setup_nodes,
             &output_name_to_setup_node,
             &HashSet::new(),
         )?;
 
         let setup_output_names = output_name_to_setup_node.keys().cloned().collect();
-        let output_to_node: HashMap<_, _> = self
-            .cycle_nodes
-            .iter()
-            .flat_map(|node| {
-                node.contexts
-                    .main_outputs
-                    .iter()
-                    .filter_map(move |field| match field {
-                        Field::MainOutput { name, .. } => Some((name.to_string(), node)),
-                        _ => None,
-                    })
-            })
-            .collect();
-        let sorted_cycle_nodes =
-            sort_nodes(&self.cycle_nodes, &output_to_node, &setup_output_names)?;
+        let output_to_node = output_to_node(&self.cycle_nodes);
+        let sorted_cycle_nodes = sort_nodes(
+            &self.name,
+            &self.cycle_nodes,
+            &output_to_node,
+            &setup_output_names,
+        )?;
 
         self.setup_nodes = sorted_setup_nodes;
         self.cycle_nodes = sorted_cycle_nodes;
         Ok(())
     }
 
+    /// Checks that no output is produced by several nodes, the execution order would be ambiguous
+    fn check_unique_outputs(&self) -> Result<(), Error> {
+        let mut output_to_nodes: BTreeMap<String, Vec<&Node>> = BTreeMap::new();
+        for node in self.iter_nodes() {
+            for field in &node.contexts.main_outputs {
+                if let Field::MainOutput { name, .. } = field {
+                    output_to_nodes
+                        .entry(name.to_string())
+                        .or_default()
+                        .push(node);
+                }
+            }
+        }
+        match output_to_nodes
+            .into_iter()
+            .find(|(_output, nodes)| nodes.len() > 1)
+        {
+            Some((output, nodes)) => Err(Error::AmbiguousOutput {
+                cycler: self.name.clone(),
+                output,
+                producers: nodes
+                    .into_iter()
+                    .map(|node| node.file_path.display().to_string())
+                    .collect(),
+            }),
+            None => Ok(()),
+        }
+    }
+
     pub fn iter_nodes(&self) -> impl Iterator<Item = &Node> {
         self.setup_nodes.iter().chain(self.cycle_nodes.iter())
     }
+
+    /// Paths of the persistent state marked with `#[snapshot]` by any node of this cycler
+    pub fn snapshot_paths(&self) -> BTreeSet<String> {
+        self.iter_nodes()
+            .flat_map(|node| {
+                node.contexts
+                    .creation_context
+                    .iter()
+                    .chain(node.contexts.cycle_context.iter())
+            })
+            .filter_map(|field| match field {
+                Field::PersistentState {
+                    path,
+                    snapshot: true,
+                    ..
+                } => Some(
+                    path.segments
+                        .iter()
+                        .map(|segment| segment.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join("."),
+                ),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn output_to_node(nodes: &[Node]) -> HashMap<String, &Node> {
+    nodes
+        .iter()
+        .flat_map(|node| {
+            node.contexts
+                .main_outputs
+                .iter()
+                .filter_map(move |field| match field {
+                    Field::MainOutput { name, .. } => Some((name.to_string(), node)),
+                    _ => None,
+                })
+        })
+        .collect()
 }
 
+/// Sorts the nodes topologically, ties are broken by the order of the nodes in the manifest
 fn sort_nodes(
+    cycler_name: &str,
     nodes: &[Node],
     output_to_node: &HashMap<String, &Node>,
     existing_output_names: &HashSet<OutputName>,
 ) -> Result<Vec<Node>, Error> {
-    let mut topological_sort = TopologicalSort::<&Node>::new();
+    let mut dependencies = Vec::new();
     for node in nodes {
-        topological_sort.insert(node);
-        for dependency in node
-            .contexts
-            .cycle_context
-            .iter()
-            .filter_map(|field| match field {
-                Field::HistoricInput { path, .. }
-                | Field::Input {
-                    path,
-                    cycler_instance: None,
-                    ..
-                }
-                | Field::RequiredInput {
-                    path,
-                    cycler_instance: None,
-                    ..
-                } => {
-                    let first_segment = path.segments.first()?;
-                    Some(first_segment.name.as_str())
-                }
-                _ => None,
-            })
+        for (field_name, dependency) in
+            node.contexts
+                .cycle_context
+                .iter()
+                .filter_map(|field| match field {
+                    Field::DecimatedInput { name, path, .. }
+                    | Field::HistoricInput { name, path, .. }
+                    | Field::Input {
+                        cycler_instance: None,
+                        name,
+                        path,
+                        ..
+                    }
+                    | Field::RequiredInput {
+                        cycler_instance: None,
+                        name,
+                        path,
+                        ..
+                    } => {
+                        let first_segment = path.segments.first()?;
+                        Some((name, first_segment.name.as_str()))
+                    }
+                    _ => None,
+                })
         {
+            let location = Location::new(&node.file_path, field_name);
             let producing_node = match output_to_node.get(dependency) {
                 Some(node) => node,
                 None if existing_output_names.contains(dependency) => continue,
@@ -210,17 +332,330 @@ fn sort_nodes(
                     return Err(Error::MissingOutput {
                         node: node.name.clone(),
                         output: dependency.to_string(),
+                        cycler_instance: cycler_name.to_string(),
+                        location,
                     })
                 }
             };
-            topological_sort.add_dependency(*producing_node, node);
+            dependencies.push(Dependency {
+                consumer: node.name.clone(),
+                producer: producing_node.name.clone(),
+                output: dependency.to_string(),
+                location,
+            });
+        }
+    }
+
+    let index_of = |name: &str| {
+        nodes
+            .iter()
+            .position(|node| node.name == name)
+            .expect("dependencies should only refer to sorted nodes")
+    };
+    let mut number_of_pending_dependencies = vec![0; nodes.len()];
+    for dependency in &dependencies {
+        number_of_pending_dependencies[index_of(&dependency.consumer)] += 1;
+    }
+    let mut ready_nodes: BTreeSet<_> = number_of_pending_dependencies
+        .iter()
+        .enumerate()
+        .filter(|(_index, number_of_dependencies)| **number_of_dependencies == 0)
+        .map(|(index, _number_of_dependencies)| index)
+        .collect();
+    let mut sorted_nodes = Vec::with_capacity(nodes.len());
+    while let Some(index) = ready_nodes.iter().next().copied() {
+        ready_nodes.remove(&index);
+        let node = &nodes[index];
+        for dependency in dependencies
+            .iter()
+            .filter(|dependency| dependency.producer == node.name)
+        {
+            let consumer_index = index_of(&dependency.consumer);
+            number_of_pending_dependencies[consumer_index] -= 1;
+            if number_of_pending_dependencies[consumer_index] == 0 {
+                ready_nodes.insert(consumer_index);
+            }
         }
+        sorted_nodes.push(node.clone());
     }
 
-    let sorted_nodes = topological_sort.by_ref().cloned().collect();
-    if !topological_sort.is_empty() {
-        return Err(Error::CircularDependency);
+    if sorted_nodes.len() != nodes.len() {
+        return Err(Error::CircularDependency {
+            cycler: cycler_name.to_string(),
+            cycle: find_cycle(nodes, &sorted_nodes, &dependencies),
+        });
     }
 
     Ok(sorted_nodes)
 }
+
+/// Follows the dependencies of the nodes left over by the topological sort until a node repeats
+///
+/// Every left over node depends on another left over node, therefore the walk ends in a cycle.
+fn find_cycle(
+    nodes: &[Node],
+    sorted_nodes: &[Node],
+    dependencies: &[Dependency],
+) -> Vec<Dependency> {
+    let unsorted_nodes: HashSet<_> = nodes
+        .iter()
+        .filter(|node| !sorted_nodes.contains(node))
+        .map(|node| node.name.as_str())
+        .collect();
+    let Some(mut current_node) = nodes
+        .iter()
+        .map(|node| node.name.as_str())
+        .find(|name| unsorted_nodes.contains(name))
+    else {
+        return Vec::new();
+    };
+    let mut walk: Vec<&Dependency> = Vec::new();
+    while let Some(dependency) = dependencies.iter().find(|dependency| {
+        dependency.consumer == current_node && unsorted_nodes.contains(dependency.producer.as_str())
+    }) {
+        walk.push(dependency);
+        if let Some(start) = walk
+            .iter()
+            .position(|visited| visited.consumer == dependency.producer)
+        {
+            return walk[start..]
+                .iter()
+                .map(|&dependency| dependency.clone())
+                .collect();
+        }
+        current_node = &dependency.producer;
+    }
+    walk.into_iter().cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn control_cycler(cycle_nodes: Vec<Node>) -> Cycler {
+        Cycler {
+            name: "Control".to_string(),
+            kind: CyclerKind::RealTime,
+            instances: vec!["Control".to_string()],
+            setup_nodes: Vec::new(),
+            cycle_nodes,
+        }
+    }
+
+    #[test]
+    fn missing_producers_are_reported_with_field_location() {
+        let mut cycler = control_cycler(vec![Node::from_fields(
+            "BallFilter",
+            &[
+                r#"ratio: Parameter<f32, "ball_filter.ratio">"#,
+                r#"detections: Input<Vec<f32>, "ball_detections">"#,
+            ],
+            &[],
+        )]);
+
+        match cycler.sort_nodes() {
+            Err(Error::MissingOutput {
+                node,
+                output,
+                cycler_instance,
+                location,
+            }) => {
+                assert_eq!(node, "BallFilter");
+                assert_eq!(output, "ball_detections");
+                assert_eq!(cycler_instance, "Control");
+                assert_eq!(location.path, PathBuf::from("control/src/ball_filter.rs"));
+                assert_eq!(location.line, 6);
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn missing_producers_in_other_cyclers_are_reported_with_field_location() {
+        let vision = Cycler {
+            name: "Vision".to_string(),
+            kind: CyclerKind::Perception,
+            instances: vec!["VisionTop".to_string()],
+            setup_nodes: Vec::new(),
+            cycle_nodes: vec![Node::from_fields(
+                "LineDetection",
+                &[],
+                &["lines: MainOutput<Vec<f32>>"],
+            )],
+        };
+        let cycler_with_input = |field: &str| {
+            control_cycler(vec![Node::from_fields("WorldStateComposer", &[field], &[])])
+        };
+
+        let mut cyclers = Cyclers {
+            cyclers: vec![
+                cycler_with_input(r#"balls: PerceptionInput<Vec<f32>, "VisionTop", "balls">"#),
+                vision,
+            ],
+        };
+        match cyclers.sort_nodes() {
+            Err(Error::MissingOutput {
+                output,
+                cycler_instance,
+                location,
+                ..
+            }) => {
+                assert_eq!(output, "balls");
+                assert_eq!(cycler_instance, "VisionTop");
+                assert_eq!(location.line, 5);
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+
+        cyclers.cyclers[0] =
+            cycler_with_input(r#"lines: PerceptionInput<Vec<f32>, "VisionLeft", "lines">"#);
+        match cyclers.sort_nodes() {
+            Err(Error::UnknownCyclerInstance {
+                cycler_instance,
+                location,
+                ..
+            }) => {
+                assert_eq!(cycler_instance, "VisionLeft");
+                assert_eq!(
+                    location.path,
+                    PathBuf::from("control/src/world_state_composer.rs")
+                );
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+
+        cyclers.cyclers[0] =
+            cycler_with_input(r#"lines: PerceptionInput<Vec<f32>, "VisionTop", "lines">"#);
+        assert!(cyclers.sort_nodes().is_ok());
+    }
+
+    #[test]
+    fn dependency_cycles_are_reported_with_field_locations() {
+        let mut cycler = control_cycler(vec![
+            Node::from_fields(
+                "BallFilter",
+                &[r#"ball_state: Input<f32, "ball_state">"#],
+                &["ball_position: MainOutput<f32>"],
+            ),
+            Node::from_fields(
+                "BallStateComposer",
+                &[
+                    r#"ratio: Parameter<f32, "ratio">"#,
+                    r#"ball_position: Input<f32, "ball_position">"#,
+                ],
+                &["ball_state: MainOutput<f32>"],
+            ),
+        ]);
+
+        match cycler.sort_nodes() {
+            Err(Error::CircularDependency { cycler, cycle }) => {
+                assert_eq!(cycler, "Control");
+                assert_eq!(
+                    cycle
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>(),
+                    [
+                        "`BallFilter` requires `ball_state` from `BallStateComposer` at control/src/ball_filter.rs:5",
+                        "`BallStateComposer` requires `ball_position` from `BallFilter` at control/src/ball_state_composer.rs:6",
+                    ]
+                );
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn independent_nodes_keep_their_manifest_order() {
+        let nodes = || {
+            vec![
+                Node::from_fields(
+                    "MotionSelector",
+                    &[r#"motion_command: Input<f32, "motion_command">"#],
+                    &["motion_selection: MainOutput<f32>"],
+                ),
+                Node::from_fields("LedStatus", &[], &["leds: MainOutput<f32>"]),
+                Node::from_fields("Behavior", &[], &["motion_command: MainOutput<f32>"]),
+                Node::from_fields("WhistleFilter", &[], &["filtered_whistle: MainOutput<f32>"]),
+            ]
+        };
+        let sorted_names = |cycler: &Cycler| {
+            cycler
+                .cycle_nodes
+                .iter()
+                .map(|node| node.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut cycler = control_cycler(nodes());
+        cycler.sort_nodes().unwrap();
+        assert_eq!(
+            sorted_names(&cycler),
+            ["LedStatus", "Behavior", "MotionSelector", "WhistleFilter"]
+        );
+
+        for _ in 0..10 {
+            let mut other_cycler = control_cycler(nodes());
+            other_cycler.sort_nodes().unwrap();
+            assert_eq!(sorted_names(&other_cycler), sorted_names(&cycler));
+        }
+        cycler.sort_nodes().unwrap();
+        assert_eq!(
+            sorted_names(&cycler),
+            ["LedStatus", "Behavior", "MotionSelector", "WhistleFilter"]
+        );
+    }
+
+    #[test]
+    fn outputs_produced_by_multiple_nodes_are_ambiguous() {
+        let mut cycler = control_cycler(vec![
+            Node::from_fields("BallFilter", &[], &["ball_position: MainOutput<f32>"]),
+            Node::from_fields("LedStatus", &[], &["leds: MainOutput<f32>"]),
+            Node::from_fields("BallPredictor", &[], &["ball_position: MainOutput<f32>"]),
+        ]);
+
+        match cycler.sort_nodes() {
+            Err(Error::AmbiguousOutput {
+                cycler,
+                output,
+                producers,
+            }) => {
+                assert_eq!(cycler, "Control");
+                assert_eq!(output, "ball_position");
+                assert_eq!(
+                    producers,
+                    [
+                        "control/src/ball_filter.rs",
+                        "control/src/ball_predictor.rs"
+                    ]
+                );
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn dependency_cycles_are_rejected() {
+        let mut cyclers = Cyclers {
+            cyclers: vec![control_cycler(vec![
+                Node::from_fields("LedStatus", &[], &["leds: MainOutput<f32>"]),
+                Node::from_fields(
+                    "Odometry",
+                    &[r#"previous_odometry: Input<f32, "odometry">"#],
+                    &["odometry: MainOutput<f32>"],
+                ),
+            ])],
+        };
+
+        match cyclers.sort_nodes() {
+            Err(Error::CircularDependency { cycle, .. }) => {
+                assert_eq!(cycle.len(), 1);
+                assert_eq!(cycle[0].consumer, "Odometry");
+                assert_eq!(cycle[0].producer, "Odometry");
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+    }
+}
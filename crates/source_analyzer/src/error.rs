@@ -1,6 +1,10 @@
-use std::{fmt::Display, io, path::PathBuf};
+use std::{
+    fmt::{self, Display},
+    io,
+    path::PathBuf,
+};
 
-use proc_macro2::Span;
+use proc_macro2::{Ident, Span};
 use quote::ToTokens;
 use thiserror::Error;
 use threadbound::ThreadBound;
@@ -19,10 +23,72 @@ pub enum Error {
     },
     #[error("invalid module path")]
     InvalidModulePath,
-    #[error("`{node}` requires output `{output}`, but it is never produced")]
-    MissingOutput { node: String, output: String },
-    #[error("failed to sort nodes, circular dependency detected")]
-    CircularDependency,
+    #[error("`{node}` requires output `{output}` of `{cycler_instance}` at {location}, but it is never produced")]
+    MissingOutput {
+        node: String,
+        output: String,
+        cycler_instance: String,
+        location: Location,
+    },
+    #[error("`{node}` requires output `{output}` of unknown cycler instance `{cycler_instance}` at {location}")]
+    UnknownCyclerInstance {
+        node: String,
+        output: String,
+        cycler_instance: String,
+        location: Location,
+    },
+    #[error("output `{output}` of `{cycler}` is produced by multiple nodes, the execution order is ambiguous: {}", .producers.join(", "))]
+    AmbiguousOutput {
+        cycler: String,
+        output: String,
+        producers: Vec<String>,
+    },
+    #[error("failed to sort nodes of `{cycler}`, circular dependency detected: {}", .cycle.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    CircularDependency {
+        cycler: String,
+        cycle: Vec<Dependency>,
+    },
+}
+
+/// Position of a context field in the source code of its node
+#[derive(Clone, Debug)]
+pub struct Location {
+    pub path: PathBuf,
+    pub line: usize,
+}
+
+impl Location {
+    pub fn new(path: impl Into<PathBuf>, field_name: &Ident) -> Self {
+        Self {
+            path: path.into(),
+            line: field_name.span().start().line,
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}:{}", self.path.display(), self.line)
+    }
+}
+
+/// Input of a node that is produced by another node of the same cycler
+#[derive(Clone, Debug)]
+pub struct Dependency {
+    pub consumer: String,
+    pub producer: String,
+    pub output: String,
+    pub location: Location,
+}
+
+impl Display for Dependency {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "`{}` requires `{}` from `{}` at {}",
+            self.consumer, self.output, self.producer, self.location
+        )
+    }
 }
 
 #[derive(Debug, Error)]
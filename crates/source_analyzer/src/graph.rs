@@ -0,0 +1,407 @@
+use std::{
+    fmt::{self, Write},
+    path::PathBuf,
+};
+
+use quote::ToTokens;
+use serde::Serialize;
+
+use crate::{
+    contexts::Field,
+    cyclers::{Cycler, CyclerKind, CyclerName, Cyclers},
+    node::{Node, NodeName},
+};
+
+/// Data flow between the nodes of all cyclers, exported for visualization
+#[derive(Debug, Serialize)]
+pub struct Graph {
+    pub cyclers: Vec<CyclerVertex>,
+    pub edges: Vec<Edge>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CyclerVertex {
+    pub name: CyclerName,
+    pub kind: CyclerKind,
+    pub instances: Vec<String>,
+    pub nodes: Vec<NodeVertex>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodeVertex {
+    pub name: NodeName,
+    pub module: String,
+    pub file_path: PathBuf,
+    pub is_setup: bool,
+    pub main_outputs: Vec<MainOutput>,
+    pub parameters: Vec<String>,
+    pub additional_outputs: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MainOutput {
+    pub name: String,
+    pub data_type: String,
+}
+
+/// Main output of the producer read by a context field of the consumer
+#[derive(Debug, Serialize)]
+pub struct Edge {
+    pub producer: NodeReference,
+    pub consumer: NodeReference,
+    pub output: String,
+    /// Field kind of the consumer, e.g. `Input` or `PerceptionInput`
+    pub kind: String,
+    /// Cycler instance the output is read from if it is not the own cycler
+    pub cycler_instance: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodeReference {
+    pub cycler: CyclerName,
+    pub node: NodeName,
+}
+
+impl Graph {
+    pub fn from_cyclers(cyclers: &Cyclers) -> Self {
+        let vertices = cyclers.cyclers.iter().map(CyclerVertex::from).collect();
+        let edges = cyclers
+            .cyclers
+            .iter()
+            .flat_map(|cycler| {
+                cycler
+                    .iter_nodes()
+                    .flat_map(move |node| edges_of_consumer(cyclers, cycler, node))
+            })
+            .collect();
+        Self {
+            cyclers: vertices,
+            edges,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Renders the graph in the Graphviz DOT language with one cluster per cycler
+    pub fn to_dot(&self) -> Result<String, fmt::Error> {
+        let mut dot = String::new();
+        writeln!(dot, "digraph modules {{")?;
+        writeln!(dot, "  rankdir=LR;")?;
+        writeln!(dot, "  node [shape=box];")?;
+        for cycler in &self.cyclers {
+            writeln!(dot, "  subgraph \"cluster_{}\" {{", cycler.name)?;
+            writeln!(
+                dot,
+                "    label=\"{} ({:?}) [{}]\";",
+                cycler.name,
+                cycler.kind,
+                cycler.instances.join(", ")
+            )?;
+            for node in &cycler.nodes {
+                let outputs = node
+                    .main_outputs
+                    .iter()
+                    .map(|output| output.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\\n");
+                let style = if node.is_setup { ", style=dashed" } else { "" };
+                writeln!(
+                    dot,
+                    "    \"{}::{}\" [label=\"{}|{}\", shape=record{}];",
+                    cycler.name, node.name, node.name, outputs, style
+                )?;
+            }
+            writeln!(dot, "  }}")?;
+        }
+        for edge in &self.edges {
+            let label = match &edge.cycler_instance {
+                Some(cycler_instance) => format!("{} ({cycler_instance})", edge.output),
+                None => edge.output.clone(),
+            };
+            let style = if edge.kind == "Input" || edge.kind == "RequiredInput" {
+                ""
+            } else {
+                ", style=dashed"
+            };
+            writeln!(
+                dot,
+                "  \"{}::{}\" -> \"{}::{}\" [label=\"{}\"{}];",
+                edge.producer.cycler,
+                edge.producer.node,
+                edge.consumer.cycler,
+                edge.consumer.node,
+                label,
+                style
+            )?;
+        }
+        writeln!(dot, "}}")?;
+        Ok(dot)
+    }
+}
+
+impl From<&Cycler> for CyclerVertex {
+    fn from(cycler: &Cycler) -> Self {
+        let setup_nodes = cycler
+            .setup_nodes
+            .iter()
+            .map(|node| NodeVertex::new(node, true));
+        let cycle_nodes = cycler
+            .cycle_nodes
+            .iter()
+            .map(|node| NodeVertex::new(node, false));
+        Self {
+            name: cycler.name.clone(),
+            kind: cycler.kind,
+            instances: cycler.instances.clone(),
+            nodes: setup_nodes.chain(cycle_nodes).collect(),
+        }
+    }
+}
+
+impl NodeVertex {
+    fn new(node: &Node, is_setup: bool) -> Self {
+        let context_fields = || {
+            node.contexts
+                .creation_context
+                .iter()
+                .chain(node.contexts.cycle_context.iter())
+        };
+        let mut parameters: Vec<_> = context_fields()
+            .filter_map(|field| match field {
+                Field::Parameter { path, .. } => Some(path_to_string(path)),
+                _ => None,
+            })
+            .collect();
+        parameters.sort();
+        parameters.dedup();
+        Self {
+            name: node.name.clone(),
+            module: node.module.to_token_stream().to_string().replace(' ', ""),
+            file_path: node.file_path.clone(),
+            is_setup,
+            main_outputs: node
+                .contexts
+                .main_outputs
+                .iter()
+                .filter_map(|field| match field {
+                    Field::MainOutput {
+                        data_type, name, ..
+                    } => Some(MainOutput {
+                        name: name.to_string(),
+                        data_type: data_type.to_token_stream().to_string(),
+                    }),
+                    _ => None,
+                })
+                .collect(),
+            parameters,
+            additional_outputs: context_fields()
+                .filter_map(|field| match field {
+                    Field::AdditionalOutput { path, .. } => Some(path_to_string(path)),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn edges_of_consumer<'a>(
+    cyclers: &'a Cyclers,
+    cycler: &'a Cycler,
+    node: &'a Node,
+) -> impl Iterator<Item = Edge> + 'a {
+    node.contexts.cycle_context.iter().filter_map(move |field| {
+        let (kind, cycler_instance, path) = match field {
+            Field::DecimatedInput { path, .. } => ("DecimatedInput", None, path),
+            Field::HistoricInput { path, .. } => ("HistoricInput", None, path),
+            Field::Input {
+                cycler_instance,
+                path,
+                ..
+            } => ("Input", cycler_instance.as_ref(), path),
+            Field::PerceptionInput {
+                cycler_instance,
+                path,
+                ..
+            } => ("PerceptionInput", Some(cycler_instance), path),
+            Field::RequiredInput {
+                cycler_instance,
+                path,
+                ..
+            } => ("RequiredInput", cycler_instance.as_ref(), path),
+            _ => return None,
+        };
+        let output = &path.segments.first()?.name;
+        let producing_cycler = match cycler_instance {
+            Some(cycler_instance) => cyclers
+                .cyclers
+                .iter()
+                .find(|cycler| cycler.instances.contains(cycler_instance))?,
+            None => cycler,
+        };
+        let producing_node = producing_cycler.iter_nodes().find(|node| {
+            node.contexts
+                .main_outputs
+                .iter()
+                .any(|field| matches!(field, Field::MainOutput { name, .. } if name == output))
+        })?;
+        Some(Edge {
+            producer: NodeReference {
+                cycler: producing_cycler.name.clone(),
+                node: producing_node.name.clone(),
+            },
+            consumer: NodeReference {
+                cycler: cycler.name.clone(),
+                node: node.name.clone(),
+            },
+            output: output.clone(),
+            kind: kind.to_string(),
+            cycler_instance: cycler_instance.cloned(),
+        })
+    })
+}
+
+fn path_to_string(path: &crate::path::Path) -> String {
+    path.segments
+        .iter()
+        .map(|segment| {
+            let name = if segment.is_variable {
+                format!("${}", segment.name)
+            } else {
+                segment.name.clone()
+            };
+            if segment.is_optional {
+                format!("{name}?")
+            } else {
+                name
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    fn cyclers() -> Cyclers {
+        Cyclers {
+            cyclers: vec![
+                Cycler {
+                    name: "Vision".to_string(),
+                    kind: CyclerKind::Perception,
+                    instances: vec!["VisionTop".to_string(), "VisionBottom".to_string()],
+                    setup_nodes: Vec::new(),
+                    cycle_nodes: vec![Node::from_fields(
+                        "BallDetection",
+                        &[r#"ratio: Parameter<f32, "ball_detection.$cycler_instance.ratio">"#],
+                        &["balls: MainOutput<Vec<f32>>"],
+                    )],
+                },
+                Cycler {
+                    name: "Control".to_string(),
+                    kind: CyclerKind::RealTime,
+                    instances: vec!["Control".to_string()],
+                    setup_nodes: vec![Node::from_fields(
+                        "SensorDataReceiver",
+                        &[],
+                        &["sensor_data: MainOutput<f32>"],
+                    )],
+                    cycle_nodes: vec![Node::from_fields(
+                        "BallFilter",
+                        &[
+                            r#"sensor_data: Input<f32, "sensor_data">"#,
+                            r#"balls: PerceptionInput<Vec<f32>, "VisionTop", "balls">"#,
+                            r#"hypotheses: AdditionalOutput<Vec<f32>, "ball_filter_hypotheses">"#,
+                        ],
+                        &["ball_position: MainOutput<f32>"],
+                    )],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn edges_connect_producers_with_consumers() {
+        let graph = Graph::from_cyclers(&cyclers());
+
+        let edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|edge| {
+                (
+                    format!("{}::{}", edge.producer.cycler, edge.producer.node),
+                    format!("{}::{}", edge.consumer.cycler, edge.consumer.node),
+                    edge.output.as_str(),
+                    edge.kind.as_str(),
+                    edge.cycler_instance.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                (
+                    "Control::SensorDataReceiver".to_string(),
+                    "Control::BallFilter".to_string(),
+                    "sensor_data",
+                    "Input",
+                    None
+                ),
+                (
+                    "Vision::BallDetection".to_string(),
+                    "Control::BallFilter".to_string(),
+                    "balls",
+                    "PerceptionInput",
+                    Some("VisionTop")
+                ),
+            ]
+        );
+        let ball_detection = &graph.cyclers[0].nodes[0];
+        assert_eq!(ball_detection.module, "control::ball_detection");
+        assert_eq!(
+            ball_detection.parameters,
+            ["ball_detection.$cycler_instance.ratio"]
+        );
+        let ball_filter = &graph.cyclers[1].nodes[1];
+        assert!(!ball_filter.is_setup);
+        assert_eq!(ball_filter.additional_outputs, ["ball_filter_hypotheses"]);
+    }
+
+    #[test]
+    fn dot_contains_clusters_nodes_and_edges() {
+        let dot = Graph::from_cyclers(&cyclers()).to_dot().unwrap();
+
+        assert!(dot.starts_with("digraph modules {\n"));
+        assert!(dot.ends_with("}\n"));
+        for line in [
+            "  subgraph \"cluster_Vision\" {",
+            "    label=\"Vision (Perception) [VisionTop, VisionBottom]\";",
+            "    \"Control::SensorDataReceiver\" [label=\"SensorDataReceiver|sensor_data\", shape=record, style=dashed];",
+            "    \"Control::BallFilter\" [label=\"BallFilter|ball_position\", shape=record];",
+            "  \"Control::SensorDataReceiver\" -> \"Control::BallFilter\" [label=\"sensor_data\"];",
+            "  \"Vision::BallDetection\" -> \"Control::BallFilter\" [label=\"balls (VisionTop)\", style=dashed];",
+        ] {
+            assert!(dot.lines().any(|dot_line| dot_line == line), "{line} missing in\n{dot}");
+        }
+    }
+
+    #[test]
+    fn json_contains_cyclers_and_edges() {
+        let json: Value =
+            serde_json::from_str(&Graph::from_cyclers(&cyclers()).to_json().unwrap()).unwrap();
+
+        assert_eq!(json["cyclers"][0]["kind"], "Perception");
+        assert_eq!(json["cyclers"][1]["nodes"][0]["is_setup"], true);
+        assert_eq!(
+            json["cyclers"][1]["nodes"][1]["main_outputs"][0]["name"],
+            "ball_position"
+        );
+        assert_eq!(json["edges"][1]["producer"]["node"], "BallDetection");
+        assert_eq!(json["edges"][1]["cycler_instance"], "VisionTop");
+        assert_eq!(json["edges"][0]["cycler_instance"], Value::Null);
+    }
+}
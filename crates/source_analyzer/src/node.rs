@@ -90,3 +90,25 @@ fn has_new_and_cycle_method(implementation: &ItemImpl) -> bool {
             .iter()
             .any(|item| matches!(item, ImplItem::Method(method) if method.sig.ident == "cycle"))
 }
+
+#[cfg(test)]
+impl Node {
+    /// Creates a node of the `control` crate with one field per line, the first cycle context field
+    /// is on line 5 of the node's source
+    pub(crate) fn from_fields(name: &str, cycle_context: &[&str], main_outputs: &[&str]) -> Self {
+        use convert_case::{Case, Casing};
+
+        let source = format!(
+            "#[context]\npub struct CreationContext {{}}\n#[context]\npub struct CycleContext {{\n{}\n}}\n#[context]\npub struct MainOutputs {{\n{}\n}}\n",
+            cycle_context.join(",\n"),
+            main_outputs.join(",\n"),
+        );
+        let module_name = name.to_case(Case::Snake);
+        Self {
+            name: name.to_string(),
+            module: syn::parse_str(&format!("control::{module_name}")).unwrap(),
+            file_path: PathBuf::from(format!("control/src/{module_name}.rs")),
+            contexts: Contexts::try_from_file(&parse_file(&source).unwrap()).unwrap(),
+        }
+    }
+}
@@ -91,6 +91,10 @@ impl ToWriterPretty for Field {
     fn to_writer_pretty(&self, writer: &mut impl Write) -> fmt::Result {
         match self {
             Field::AdditionalOutput { name, .. } => write!(writer, "{name}: AdditfmtnalOutput"),
+            Field::AdditionalOutputsSubscribed { name } => {
+                write!(writer, "{name}: AdditionalOutputsSubscribed")
+            }
+            Field::DecimatedInput { name, .. } => write!(writer, "{name}: DecimatedInput"),
             Field::HardwareInterface { name, .. } => write!(writer, "{name}: HardwareInterface"),
             Field::HistoricInput { name, .. } => write!(writer, "{name}: HistoricInput"),
             Field::Input { name, .. } => write!(writer, "{name}: Input"),
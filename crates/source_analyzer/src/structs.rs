@@ -8,7 +8,7 @@ use syn::{
 use thiserror::Error;
 
 use crate::{
-    contexts::Field,
+    contexts::{Field, FieldMetadata, ParameterConstraints},
     cyclers::{CyclerName, Cyclers},
     path::Path,
     struct_hierarchy::{HierarchyError, InsertionRule, StructHierarchy},
@@ -20,11 +20,19 @@ pub enum Error {
     Hierarchy(#[from] HierarchyError),
     #[error("unexpected field {0} in `CreationContext` or `CycleContext`")]
     UnexpectedField(String),
+    #[error("parameter `{path}` is declared with different default values or validations")]
+    MismatchingParameterConstraints { path: String },
+    #[error("`{path}` is declared with different metadata")]
+    MismatchingFieldMetadata { path: String },
 }
 
 #[derive(Debug, Default)]
 pub struct Structs {
     pub parameters: StructHierarchy,
+    /// Default values and validations of parameters by their path without optional markers
+    pub parameter_constraints: BTreeMap<String, ParameterConstraints>,
+    /// Descriptions and units of parameters by their path without optional markers
+    pub parameter_metadata: BTreeMap<String, FieldMetadata>,
     pub cyclers: BTreeMap<CyclerName, CyclerStructs>,
 }
 
@@ -37,7 +45,7 @@ impl Structs {
 
             for node in cycler.iter_nodes() {
                 for field in node.contexts.main_outputs.iter() {
-                    add_main_outputs(field, cycler_structs);
+                    add_main_outputs(field, cycler_structs)?;
                 }
                 for field in node
                     .contexts
@@ -75,7 +83,11 @@ impl Structs {
                             }
                         }
                         Field::Parameter {
-                            data_type, path, ..
+                            constraints,
+                            data_type,
+                            metadata,
+                            path,
+                            ..
                         } => {
                             let expanded_paths = path.expand_variables(&cycler.instances);
 
@@ -86,6 +98,16 @@ impl Structs {
                                 };
                                 let insertion_rules = path_to_insertion_rules(&path, &data_type);
                                 structs.parameters.insert(insertion_rules)?;
+                                add_parameter_constraints(
+                                    &path,
+                                    constraints,
+                                    &mut structs.parameter_constraints,
+                                )?;
+                                add_field_metadata(
+                                    path_to_string(&path),
+                                    metadata,
+                                    &mut structs.parameter_metadata,
+                                )?;
                             }
                         }
                         Field::PersistentState {
@@ -105,13 +127,54 @@ impl Structs {
                 }
             }
         }
+
+        // inputs may document outputs of other cyclers, hence all outputs need to be known first
+        for cycler in cyclers.cyclers.iter() {
+            for node in cycler.iter_nodes() {
+                for field in node
+                    .contexts
+                    .creation_context
+                    .iter()
+                    .chain(node.contexts.cycle_context.iter())
+                {
+                    let Field::Input {
+                        cycler_instance,
+                        metadata,
+                        path,
+                        ..
+                    } = field
+                    else {
+                        continue;
+                    };
+                    let producing_cycler = match cycler_instance {
+                        Some(cycler_instance) => cyclers
+                            .cyclers
+                            .iter()
+                            .find(|cycler| cycler.instances.contains(cycler_instance))
+                            .map_or(&cycler.name, |cycler| &cycler.name),
+                        None => &cycler.name,
+                    };
+                    let cycler_structs =
+                        structs.cyclers.entry(producing_cycler.clone()).or_default();
+                    add_field_metadata(
+                        format!("main_outputs.{}", path_to_string(path)),
+                        metadata,
+                        &mut cycler_structs.output_metadata,
+                    )?;
+                }
+            }
+        }
         Ok(structs)
     }
 }
 
-fn add_main_outputs(field: &Field, cycler_structs: &mut CyclerStructs) {
+fn add_main_outputs(field: &Field, cycler_structs: &mut CyclerStructs) -> Result<(), Error> {
     match field {
-        Field::MainOutput { data_type, name } => match &mut cycler_structs.main_outputs {
+        Field::MainOutput {
+            data_type,
+            metadata,
+            name,
+        } => match &mut cycler_structs.main_outputs {
             StructHierarchy::Struct { fields } => {
                 fields.insert(
                     name.to_string(),
@@ -119,6 +182,11 @@ fn add_main_outputs(field: &Field, cycler_structs: &mut CyclerStructs) {
                         data_type: data_type.clone(),
                     },
                 );
+                add_field_metadata(
+                    format!("main_outputs.{name}"),
+                    metadata,
+                    &mut cycler_structs.output_metadata,
+                )
             }
             _ => panic!("unexpected non-struct hierarchy in main outputs: {cycler_structs:?}"),
         },
@@ -128,11 +196,68 @@ fn add_main_outputs(field: &Field, cycler_structs: &mut CyclerStructs) {
     }
 }
 
+/// Nodes sharing a parameter may declare its constraints only once or identically
+fn add_parameter_constraints(
+    path: &Path,
+    constraints: &ParameterConstraints,
+    parameter_constraints: &mut BTreeMap<String, ParameterConstraints>,
+) -> Result<(), Error> {
+    if constraints.is_empty() {
+        return Ok(());
+    }
+    let path = path_to_string(path);
+    match parameter_constraints.get(&path) {
+        Some(existing_constraints) if existing_constraints != constraints => {
+            Err(Error::MismatchingParameterConstraints { path })
+        }
+        Some(_) => Ok(()),
+        None => {
+            parameter_constraints.insert(path, constraints.clone());
+            Ok(())
+        }
+    }
+}
+
+/// Nodes sharing a field may declare each of its metadata only once or identically
+fn add_field_metadata(
+    path: String,
+    metadata: &FieldMetadata,
+    field_metadata: &mut BTreeMap<String, FieldMetadata>,
+) -> Result<(), Error> {
+    if metadata.is_empty() {
+        return Ok(());
+    }
+    let existing_metadata = field_metadata.entry(path.clone()).or_default();
+    for (existing, new) in [
+        (&mut existing_metadata.doc, &metadata.doc),
+        (&mut existing_metadata.unit, &metadata.unit),
+    ] {
+        match (existing.as_ref(), new) {
+            (Some(existing), Some(new)) if existing != new => {
+                return Err(Error::MismatchingFieldMetadata { path });
+            }
+            (None, Some(new)) => *existing = Some(new.clone()),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.segments
+        .iter()
+        .map(|segment| segment.name.as_str())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
 #[derive(Debug, Default)]
 pub struct CyclerStructs {
     pub main_outputs: StructHierarchy,
     pub additional_outputs: StructHierarchy,
     pub persistent_state: StructHierarchy,
+    /// Descriptions and units of outputs by their path in the database, e.g. `main_outputs.ball`
+    pub output_metadata: BTreeMap<String, FieldMetadata>,
 }
 
 fn path_to_insertion_rules<'path>(
@@ -616,4 +741,64 @@ mod tests {
 
         assert_eq!(hierarchy_less_specific_first, hierarchy_more_specific_first);
     }
+
+    #[test]
+    fn metadata_of_shared_fields_is_merged_or_rejected_if_mismatching() {
+        let mut field_metadata = BTreeMap::new();
+        let doc = FieldMetadata {
+            doc: Some("Distance".to_string()),
+            unit: None,
+        };
+        let unit = FieldMetadata {
+            doc: None,
+            unit: Some("m".to_string()),
+        };
+        let other_unit = FieldMetadata {
+            doc: None,
+            unit: Some("mm".to_string()),
+        };
+
+        add_field_metadata("a.b".to_string(), &doc, &mut field_metadata).unwrap();
+        add_field_metadata("a.b".to_string(), &unit, &mut field_metadata).unwrap();
+        assert_eq!(
+            field_metadata["a.b"],
+            FieldMetadata {
+                doc: Some("Distance".to_string()),
+                unit: Some("m".to_string()),
+            }
+        );
+        assert!(matches!(
+            add_field_metadata("a.b".to_string(), &other_unit, &mut field_metadata),
+            Err(Error::MismatchingFieldMetadata { path }) if path == "a.b"
+        ));
+    }
+
+    #[test]
+    fn constraints_of_shared_parameters_are_merged_or_rejected_if_mismatching() {
+        let mut parameter_constraints = BTreeMap::new();
+        let path = Path::try_new("a.b", false).unwrap();
+        let with_default = ParameterConstraints {
+            default: Some(syn::parse_str("42").unwrap()),
+            ..Default::default()
+        };
+        let with_other_default = ParameterConstraints {
+            default: Some(syn::parse_str("1337").unwrap()),
+            ..Default::default()
+        };
+
+        add_parameter_constraints(
+            &path,
+            &ParameterConstraints::default(),
+            &mut parameter_constraints,
+        )
+        .unwrap();
+        assert!(parameter_constraints.is_empty());
+        add_parameter_constraints(&path, &with_default, &mut parameter_constraints).unwrap();
+        add_parameter_constraints(&path, &with_default, &mut parameter_constraints).unwrap();
+        assert_eq!(parameter_constraints["a.b"], with_default);
+        assert!(matches!(
+            add_parameter_constraints(&path, &with_other_default, &mut parameter_constraints),
+            Err(Error::MismatchingParameterConstraints { path }) if path == "a.b"
+        ));
+    }
 }
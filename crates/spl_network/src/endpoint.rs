@@ -5,6 +5,7 @@ use std::{
 
 use log::warn;
 use serde::Deserialize;
+use spl_network_messages::TeamMessage;
 use thiserror::Error;
 use tokio::{net::UdpSocket, select, sync::Mutex};
 use types::messages::{IncomingMessage, OutgoingMessage};
@@ -68,9 +69,9 @@ impl Endpoint {
                 },
                 result = self.spl_socket.recv_from(&mut spl_buffer) => {
                     let (received_bytes, _address) = result.map_err(Error::ReadError)?;
-                    match bincode::deserialize(&spl_buffer[0..received_bytes]) {
+                    match TeamMessage::try_from(&spl_buffer[0..received_bytes]) {
                         Ok(parsed_message) => {
-                            break Ok(IncomingMessage::Spl(parsed_message));
+                            break Ok(IncomingMessage::Spl(parsed_message.message));
                         }
                         Err(error) => {
                             warn!("Failed to parse SPL message (will be discarded): {error:?}");
@@ -89,23 +90,19 @@ impl Endpoint {
                 self.send_game_controller_visual_referee_message(message)
                     .await;
             }
-            OutgoingMessage::Spl(message) => match bincode::serialize(&message) {
-                Ok(message) => {
-                    if let Err(error) = self
-                        .spl_socket
-                        .send_to(
-                            message.as_slice(),
-                            SocketAddr::new(Ipv4Addr::BROADCAST.into(), self.ports.spl),
-                        )
-                        .await
-                    {
-                        warn!("Failed to send UDP datagram via SPL socket: {error:?}")
-                    }
-                }
-                Err(error) => {
-                    warn!("Failed to serialize Hulk Message: {error:?}")
+            OutgoingMessage::Spl(message) => {
+                let message: Vec<u8> = message.into();
+                if let Err(error) = self
+                    .spl_socket
+                    .send_to(
+                        message.as_slice(),
+                        SocketAddr::new(Ipv4Addr::BROADCAST.into(), self.ports.spl),
+                    )
+                    .await
+                {
+                    warn!("Failed to send UDP datagram via SPL socket: {error:?}")
                 }
-            },
+            }
             OutgoingMessage::VisualReferee(message) => {
                 let message: Vec<u8> = message.into();
                 self.send_game_controller_visual_referee_message(message)
@@ -0,0 +1,98 @@
+use color_eyre::{eyre::bail, Result};
+
+/// Appends values with arbitrary bit widths to a byte buffer, most significant bit first
+#[derive(Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    number_of_bits: usize,
+}
+
+impl BitWriter {
+    pub fn write(&mut self, value: u32, number_of_bits: u8) {
+        for bit_index in (0..number_of_bits).rev() {
+            let bit = (value >> bit_index) & 1;
+            if self.number_of_bits % 8 == 0 {
+                self.bytes.push(0);
+            }
+            if bit == 1 {
+                let last_byte = self.bytes.last_mut().unwrap();
+                *last_byte |= 1 << (7 - self.number_of_bits % 8);
+            }
+            self.number_of_bits += 1;
+        }
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write(value as u32, 1);
+    }
+
+    /// Quantizes a value with the given resolution and saturates it to the signed range
+    pub fn write_signed(&mut self, value: f32, resolution: f32, number_of_bits: u8) {
+        let maximum = (1i64 << (number_of_bits - 1)) - 1;
+        let minimum = -(1i64 << (number_of_bits - 1));
+        let quantized = ((value / resolution).round() as i64).clamp(minimum, maximum);
+        self.write((quantized - minimum) as u32, number_of_bits);
+    }
+
+    /// Quantizes a value with the given resolution, the largest representable value is reserved
+    /// for saturated values
+    pub fn write_unsigned_saturating(&mut self, value: f32, resolution: f32, number_of_bits: u8) {
+        let maximum = (1u64 << number_of_bits) - 1;
+        let quantized = ((value / resolution).round() as u64).min(maximum);
+        self.write(quantized as u32, number_of_bits);
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reads values with arbitrary bit widths from a byte buffer, most significant bit first
+pub struct BitReader<'buffer> {
+    bytes: &'buffer [u8],
+    number_of_bits: usize,
+}
+
+impl<'buffer> BitReader<'buffer> {
+    pub fn new(bytes: &'buffer [u8]) -> Self {
+        Self {
+            bytes,
+            number_of_bits: 0,
+        }
+    }
+
+    pub fn read(&mut self, number_of_bits: u8) -> Result<u32> {
+        if self.number_of_bits + number_of_bits as usize > self.bytes.len() * 8 {
+            bail!("buffer too small");
+        }
+        let mut value = 0;
+        for _ in 0..number_of_bits {
+            let byte = self.bytes[self.number_of_bits / 8];
+            let bit = (byte >> (7 - self.number_of_bits % 8)) & 1;
+            value = (value << 1) | bit as u32;
+            self.number_of_bits += 1;
+        }
+        Ok(value)
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool> {
+        Ok(self.read(1)? == 1)
+    }
+
+    pub fn read_signed(&mut self, resolution: f32, number_of_bits: u8) -> Result<f32> {
+        let minimum = -(1i64 << (number_of_bits - 1));
+        let quantized = self.read(number_of_bits)? as i64 + minimum;
+        Ok(quantized as f32 * resolution)
+    }
+
+    /// Returns `None` if the value was saturated while writing
+    pub fn read_unsigned_saturating(
+        &mut self,
+        resolution: f32,
+        number_of_bits: u8,
+    ) -> Result<Option<f32>> {
+        let maximum = (1u64 << number_of_bits) - 1;
+        let quantized = self.read(number_of_bits)? as u64;
+        Ok((quantized < maximum).then_some(quantized as f32 * resolution))
+    }
+}
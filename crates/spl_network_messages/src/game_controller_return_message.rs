@@ -3,6 +3,7 @@ use std::{ffi::c_char, mem::size_of, ptr::read, slice::from_raw_parts, time::Dur
 use color_eyre::{eyre::bail, Report, Result};
 use nalgebra::{point, vector, Isometry2};
 use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
 
 use crate::{
     bindings::{
@@ -12,7 +13,7 @@ use crate::{
     BallPosition, PlayerNumber, HULKS_TEAM_NUMBER,
 };
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct GameControllerReturnMessage {
     pub player_number: PlayerNumber,
     pub fallen: bool,
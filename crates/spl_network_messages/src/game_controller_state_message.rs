@@ -31,6 +31,7 @@ use crate::{
 
 #[derive(Clone, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub struct GameControllerStateMessage {
+    pub packet_number: u8,
     pub competition_phase: CompetitionPhase,
     pub competition_type: CompetitionType,
     pub game_phase: GamePhase,
@@ -129,6 +130,7 @@ impl TryFrom<RoboCupGameControlData> for GameControllerStateMessage {
             })
             .collect::<Result<Vec<_>>>()?;
         Ok(GameControllerStateMessage {
+            packet_number: message.packetNumber,
             competition_phase: CompetitionPhase::try_from(message.competitionPhase)?,
             competition_type: CompetitionType::try_from(message.competitionType)?,
             game_phase: GamePhase::try_from(message.gamePhase, message.kickingTeam)?,
@@ -352,7 +354,7 @@ pub struct TeamState {
     pub players: Vec<Player>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, SerializeHierarchy)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
 pub enum TeamColor {
     Blue,
     Red,
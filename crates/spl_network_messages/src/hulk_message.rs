@@ -0,0 +1,376 @@
+use std::{f32::consts::PI, time::Duration};
+
+use color_eyre::{
+    eyre::{bail, eyre},
+    Report, Result,
+};
+use nalgebra::{point, Isometry2, Point2};
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::{
+    bit_packing::{BitReader, BitWriter},
+    PlayerNumber, Role,
+};
+
+pub const HULK_MESSAGE_CODEC_VERSION: u8 = 4;
+
+const POSITION_RESOLUTION: f32 = 0.001;
+const POSITION_BITS: u8 = 14;
+const ANGLE_RESOLUTION: f32 = 2.0 * PI / 1024.0;
+const ANGLE_BITS: u8 = 10;
+const DURATION_RESOLUTION: f32 = 0.01;
+const BALL_AGE_BITS: u8 = 12;
+const TIME_TO_REACH_KICK_POSITION_BITS: u8 = 16;
+
+const PLAYER_NUMBERS: [PlayerNumber; 7] = [
+    PlayerNumber::One,
+    PlayerNumber::Two,
+    PlayerNumber::Three,
+    PlayerNumber::Four,
+    PlayerNumber::Five,
+    PlayerNumber::Six,
+    PlayerNumber::Seven,
+];
+const ROLES: [Role; 10] = [
+    Role::DefenderLeft,
+    Role::DefenderRight,
+    Role::Keeper,
+    Role::Loser,
+    Role::MidfielderLeft,
+    Role::MidfielderRight,
+    Role::ReplacementKeeper,
+    Role::Searcher,
+    Role::Striker,
+    Role::StrikerSupporter,
+];
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct HulkMessage {
+    pub player_number: PlayerNumber,
+    /// `None` if the sender does not number its messages, e.g. with the standard protocol
+    pub sequence_number: Option<u16>,
+    pub fallen: bool,
+    pub robot_to_field: Isometry2<f32>,
+    /// `None` if the sender does not announce its role, e.g. with the standard protocol
+    pub role: Option<Role>,
+    pub ball_position: Option<BallPosition>,
+    pub time_to_reach_kick_position: Option<Duration>,
+    pub intention: Option<Intention>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BallPosition {
+    pub relative_position: Point2<f32>,
+    pub age: Duration,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct Intention {
+    pub target_pose_in_field: Isometry2<f32>,
+    pub kick_target_in_field: Option<Point2<f32>>,
+}
+
+impl TryFrom<&[u8]> for HulkMessage {
+    type Error = Report;
+
+    fn try_from(buffer: &[u8]) -> Result<Self> {
+        let mut reader = BitReader::new(buffer);
+        let version = reader.read(8)? as u8;
+        if version != HULK_MESSAGE_CODEC_VERSION {
+            bail!("unexpected codec version {version}, expected {HULK_MESSAGE_CODEC_VERSION}");
+        }
+        let player_number = *PLAYER_NUMBERS
+            .get(reader.read(3)? as usize)
+            .ok_or_else(|| eyre!("unexpected player number"))?;
+        let sequence_number = if reader.read_bool()? {
+            Some(reader.read(16)? as u16)
+        } else {
+            None
+        };
+        let fallen = reader.read_bool()?;
+        let role = if reader.read_bool()? {
+            Some(
+                *ROLES
+                    .get(reader.read(4)? as usize)
+                    .ok_or_else(|| eyre!("unexpected role"))?,
+            )
+        } else {
+            None
+        };
+        let robot_to_field = read_pose(&mut reader)?;
+        let ball_position = if reader.read_bool()? {
+            Some(BallPosition {
+                relative_position: read_position(&mut reader)?,
+                age: read_duration(&mut reader, BALL_AGE_BITS)?,
+            })
+        } else {
+            None
+        };
+        let time_to_reach_kick_position = if reader.read_bool()? {
+            Some(read_duration(
+                &mut reader,
+                TIME_TO_REACH_KICK_POSITION_BITS,
+            )?)
+        } else {
+            None
+        };
+        let intention = if reader.read_bool()? {
+            let target_pose_in_field = read_pose(&mut reader)?;
+            let kick_target_in_field = if reader.read_bool()? {
+                Some(read_position(&mut reader)?)
+            } else {
+                None
+            };
+            Some(Intention {
+                target_pose_in_field,
+                kick_target_in_field,
+            })
+        } else {
+            None
+        };
+        Ok(Self {
+            player_number,
+            sequence_number,
+            fallen,
+            robot_to_field,
+            role,
+            ball_position,
+            time_to_reach_kick_position,
+            intention,
+        })
+    }
+}
+
+impl From<HulkMessage> for Vec<u8> {
+    fn from(message: HulkMessage) -> Self {
+        let mut writer = BitWriter::default();
+        writer.write(HULK_MESSAGE_CODEC_VERSION as u32, 8);
+        writer.write(message.player_number as u32, 3);
+        writer.write_bool(message.sequence_number.is_some());
+        if let Some(sequence_number) = message.sequence_number {
+            writer.write(sequence_number as u32, 16);
+        }
+        writer.write_bool(message.fallen);
+        writer.write_bool(message.role.is_some());
+        if let Some(role) = message.role {
+            writer.write(role as u32, 4);
+        }
+        write_pose(&mut writer, message.robot_to_field);
+        writer.write_bool(message.ball_position.is_some());
+        if let Some(ball_position) = message.ball_position {
+            write_position(&mut writer, ball_position.relative_position);
+            write_duration(&mut writer, ball_position.age, BALL_AGE_BITS);
+        }
+        writer.write_bool(message.time_to_reach_kick_position.is_some());
+        if let Some(time_to_reach_kick_position) = message.time_to_reach_kick_position {
+            write_duration(
+                &mut writer,
+                time_to_reach_kick_position,
+                TIME_TO_REACH_KICK_POSITION_BITS,
+            );
+        }
+        writer.write_bool(message.intention.is_some());
+        if let Some(intention) = message.intention {
+            write_pose(&mut writer, intention.target_pose_in_field);
+            writer.write_bool(intention.kick_target_in_field.is_some());
+            if let Some(kick_target_in_field) = intention.kick_target_in_field {
+                write_position(&mut writer, kick_target_in_field);
+            }
+        }
+        writer.into_bytes()
+    }
+}
+
+fn write_position(writer: &mut BitWriter, position: Point2<f32>) {
+    writer.write_signed(position.x, POSITION_RESOLUTION, POSITION_BITS);
+    writer.write_signed(position.y, POSITION_RESOLUTION, POSITION_BITS);
+}
+
+fn read_position(reader: &mut BitReader) -> Result<Point2<f32>> {
+    Ok(point![
+        reader.read_signed(POSITION_RESOLUTION, POSITION_BITS)?,
+        reader.read_signed(POSITION_RESOLUTION, POSITION_BITS)?
+    ])
+}
+
+fn write_pose(writer: &mut BitWriter, pose: Isometry2<f32>) {
+    write_position(writer, pose.translation.vector.into());
+    writer.write_signed(pose.rotation.angle(), ANGLE_RESOLUTION, ANGLE_BITS);
+}
+
+fn read_pose(reader: &mut BitReader) -> Result<Isometry2<f32>> {
+    Ok(Isometry2::new(
+        read_position(reader)?.coords,
+        reader.read_signed(ANGLE_RESOLUTION, ANGLE_BITS)?,
+    ))
+}
+
+fn write_duration(writer: &mut BitWriter, duration: Duration, number_of_bits: u8) {
+    writer.write_unsigned_saturating(duration.as_secs_f32(), DURATION_RESOLUTION, number_of_bits);
+}
+
+/// Durations exceeding the representable range are decoded as `Duration::MAX`
+fn read_duration(reader: &mut BitReader, number_of_bits: u8) -> Result<Duration> {
+    Ok(reader
+        .read_unsigned_saturating(DURATION_RESOLUTION, number_of_bits)?
+        .map_or(Duration::MAX, Duration::from_secs_f32))
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn random_position(rng: &mut StdRng) -> Point2<f32> {
+        point![rng.gen_range(-8.0..8.0), rng.gen_range(-8.0..8.0)]
+    }
+
+    fn random_pose(rng: &mut StdRng) -> Isometry2<f32> {
+        Isometry2::new(
+            random_position(rng).coords,
+            rng.gen_range(-PI + ANGLE_RESOLUTION..PI - ANGLE_RESOLUTION),
+        )
+    }
+
+    fn random_message(rng: &mut StdRng) -> HulkMessage {
+        HulkMessage {
+            player_number: PLAYER_NUMBERS[rng.gen_range(0..PLAYER_NUMBERS.len())],
+            sequence_number: rng.gen::<bool>().then(|| rng.gen()),
+            fallen: rng.gen(),
+            robot_to_field: random_pose(rng),
+            role: rng
+                .gen::<bool>()
+                .then(|| ROLES[rng.gen_range(0..ROLES.len())]),
+            ball_position: rng.gen::<bool>().then(|| BallPosition {
+                relative_position: random_position(rng),
+                age: Duration::from_secs_f32(rng.gen_range(0.0..40.0)),
+            }),
+            time_to_reach_kick_position: rng
+                .gen::<bool>()
+                .then(|| Duration::from_secs_f32(rng.gen_range(0.0..600.0))),
+            intention: rng.gen::<bool>().then(|| Intention {
+                target_pose_in_field: random_pose(rng),
+                kick_target_in_field: rng.gen::<bool>().then(|| random_position(rng)),
+            }),
+        }
+    }
+
+    #[test]
+    fn random_messages_survive_round_trip() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..10000 {
+            let message = random_message(&mut rng);
+            let buffer: Vec<u8> = message.into();
+            let decoded = HulkMessage::try_from(buffer.as_slice()).unwrap();
+
+            assert_eq!(decoded.player_number, message.player_number);
+            assert_eq!(decoded.sequence_number, message.sequence_number);
+            assert_eq!(decoded.fallen, message.fallen);
+            assert_eq!(decoded.role, message.role);
+            assert_relative_eq!(
+                decoded.robot_to_field.translation.vector,
+                message.robot_to_field.translation.vector,
+                epsilon = POSITION_RESOLUTION
+            );
+            assert_relative_eq!(
+                decoded.robot_to_field.rotation.angle(),
+                message.robot_to_field.rotation.angle(),
+                epsilon = ANGLE_RESOLUTION
+            );
+            assert_eq!(
+                decoded.ball_position.is_some(),
+                message.ball_position.is_some()
+            );
+            if let (Some(decoded), Some(original)) = (decoded.ball_position, message.ball_position)
+            {
+                assert_relative_eq!(
+                    decoded.relative_position,
+                    original.relative_position,
+                    epsilon = POSITION_RESOLUTION
+                );
+                assert_relative_eq!(
+                    decoded.age.as_secs_f32(),
+                    original.age.as_secs_f32(),
+                    epsilon = DURATION_RESOLUTION
+                );
+            }
+            assert_eq!(
+                decoded.time_to_reach_kick_position.is_some(),
+                message.time_to_reach_kick_position.is_some()
+            );
+            if let (Some(decoded), Some(original)) = (
+                decoded.time_to_reach_kick_position,
+                message.time_to_reach_kick_position,
+            ) {
+                assert_relative_eq!(
+                    decoded.as_secs_f32(),
+                    original.as_secs_f32(),
+                    epsilon = DURATION_RESOLUTION
+                );
+            }
+            assert_eq!(decoded.intention.is_some(), message.intention.is_some());
+            if let (Some(decoded), Some(original)) = (decoded.intention, message.intention) {
+                assert_relative_eq!(
+                    decoded.target_pose_in_field.translation.vector,
+                    original.target_pose_in_field.translation.vector,
+                    epsilon = POSITION_RESOLUTION
+                );
+                assert_relative_eq!(
+                    decoded.target_pose_in_field.rotation.angle(),
+                    original.target_pose_in_field.rotation.angle(),
+                    epsilon = ANGLE_RESOLUTION
+                );
+                assert_eq!(
+                    decoded.kick_target_in_field.is_some(),
+                    original.kick_target_in_field.is_some()
+                );
+                if let (Some(decoded), Some(original)) =
+                    (decoded.kick_target_in_field, original.kick_target_in_field)
+                {
+                    assert_relative_eq!(decoded, original, epsilon = POSITION_RESOLUTION);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn random_buffers_do_not_panic() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..10000 {
+            let length = rng.gen_range(0..32);
+            let mut buffer: Vec<u8> = (0..length).map(|_| rng.gen()).collect();
+            if let Some(version) = buffer.first_mut() {
+                *version = HULK_MESSAGE_CODEC_VERSION;
+            }
+            let _ = HulkMessage::try_from(buffer.as_slice());
+        }
+    }
+
+    #[test]
+    fn saturated_durations_are_decoded_as_maximum() {
+        let message = HulkMessage {
+            ball_position: Some(BallPosition {
+                relative_position: Point2::origin(),
+                age: Duration::MAX,
+            }),
+            time_to_reach_kick_position: Some(Duration::MAX),
+            ..Default::default()
+        };
+        let buffer: Vec<u8> = message.into();
+        let decoded = HulkMessage::try_from(buffer.as_slice()).unwrap();
+
+        assert_eq!(decoded.ball_position.unwrap().age, Duration::MAX);
+        assert_eq!(decoded.time_to_reach_kick_position, Some(Duration::MAX));
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let mut buffer: Vec<u8> = HulkMessage::default().into();
+        buffer[0] = HULK_MESSAGE_CODEC_VERSION + 1;
+
+        assert!(HulkMessage::try_from(buffer.as_slice()).is_err());
+    }
+}
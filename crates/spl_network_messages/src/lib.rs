@@ -1,14 +1,14 @@
 mod bindings;
+mod bit_packing;
 mod game_controller_return_message;
 mod game_controller_state_message;
+mod hulk_message;
+mod roles;
+mod team_message;
 mod visual_referee_message;
 
-use std::{
-    fmt::{self, Display, Formatter},
-    time::Duration,
-};
+use std::fmt::{self, Display, Formatter};
 
-use nalgebra::{Isometry2, Point2};
 use serde::{Deserialize, Serialize};
 
 pub use game_controller_return_message::GameControllerReturnMessage;
@@ -16,28 +16,27 @@ pub use game_controller_state_message::{
     GameControllerStateMessage, GamePhase, GameState, Half, Penalty, PenaltyShoot, Player,
     SubState, Team, TeamColor, TeamState,
 };
+pub use hulk_message::{BallPosition, HulkMessage, Intention, HULK_MESSAGE_CODEC_VERSION};
+pub use roles::Role;
 use serialize_hierarchy::SerializeHierarchy;
+pub use team_message::{TeamMessage, TeamMessageProtocol, EXTENDED_PROTOCOL_HEADER};
 pub use visual_referee_message::{VisualRefereeDecision, VisualRefereeMessage};
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
-pub struct HulkMessage {
-    pub player_number: PlayerNumber,
-    pub fallen: bool,
-    pub robot_to_field: Isometry2<f32>,
-    pub ball_position: Option<BallPosition>,
-    pub time_to_reach_kick_position: Option<Duration>,
-}
-
-#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
-pub struct BallPosition {
-    pub relative_position: Point2<f32>,
-    pub age: Duration,
-}
-
 pub const HULKS_TEAM_NUMBER: u8 = 24;
 
 #[derive(
-    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize, SerializeHierarchy,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+    SerializeHierarchy,
 )]
 pub enum PlayerNumber {
     One,
@@ -72,20 +71,27 @@ mod tests {
 
     use nalgebra::Isometry2;
 
-    use crate::{BallPosition, HulkMessage, PlayerNumber};
+    use crate::{BallPosition, HulkMessage, Intention, PlayerNumber, Role};
 
     #[test]
     fn maximum_hulk_message_size() {
         let test_message = HulkMessage {
             player_number: PlayerNumber::Seven,
+            sequence_number: Some(u16::MAX),
             fallen: false,
             robot_to_field: Isometry2::identity(),
+            role: Some(Role::StrikerSupporter),
             ball_position: Some(BallPosition {
                 relative_position: nalgebra::OPoint::origin(),
                 age: Duration::MAX,
             }),
             time_to_reach_kick_position: Some(Duration::MAX),
+            intention: Some(Intention {
+                target_pose_in_field: Isometry2::identity(),
+                kick_target_in_field: Some(nalgebra::OPoint::origin()),
+            }),
         };
-        assert!(bincode::serialize(&test_message).unwrap().len() <= 128)
+        assert!(bincode::serialize(&test_message).unwrap().len() <= 128);
+        assert!(Vec::<u8>::from(test_message).len() <= 128);
     }
 }
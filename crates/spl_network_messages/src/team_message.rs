@@ -0,0 +1,194 @@
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Report, Result,
+};
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::{GameControllerReturnMessage, HulkMessage, HULKS_TEAM_NUMBER};
+
+pub const EXTENDED_PROTOCOL_HEADER: [u8; 4] = *b"HULK";
+
+/// Wire format of team messages
+///
+/// The standard protocol reuses the SPL return message layout which is understood by all teams
+/// (e.g. in mixed teams). The extended protocol carries the full [`HulkMessage`] and is only
+/// spoken between robots with the HULKs team number.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
+pub enum TeamMessageProtocol {
+    Standard,
+    #[default]
+    Extended,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct TeamMessage {
+    pub protocol: TeamMessageProtocol,
+    pub message: HulkMessage,
+}
+
+impl TryFrom<&[u8]> for TeamMessage {
+    type Error = Report;
+
+    fn try_from(buffer: &[u8]) -> Result<Self> {
+        match buffer.strip_prefix(&EXTENDED_PROTOCOL_HEADER) {
+            Some(payload) => {
+                let Some((&team_number, payload)) = payload.split_first() else {
+                    bail!("buffer too small");
+                };
+                if team_number != HULKS_TEAM_NUMBER {
+                    bail!("extended protocol is not negotiated with team number {team_number}");
+                }
+                Ok(Self {
+                    protocol: TeamMessageProtocol::Extended,
+                    message: payload
+                        .try_into()
+                        .wrap_err("failed to decode extended team message")?,
+                })
+            }
+            None => {
+                let message: GameControllerReturnMessage = buffer
+                    .try_into()
+                    .wrap_err("failed to decode standard team message")?;
+                Ok(Self {
+                    protocol: TeamMessageProtocol::Standard,
+                    message: message.into(),
+                })
+            }
+        }
+    }
+}
+
+impl From<TeamMessage> for Vec<u8> {
+    fn from(team_message: TeamMessage) -> Self {
+        match team_message.protocol {
+            TeamMessageProtocol::Standard => {
+                GameControllerReturnMessage::from(team_message.message).into()
+            }
+            TeamMessageProtocol::Extended => {
+                let payload: Vec<u8> = team_message.message.into();
+                EXTENDED_PROTOCOL_HEADER
+                    .into_iter()
+                    .chain([HULKS_TEAM_NUMBER])
+                    .chain(payload)
+                    .collect()
+            }
+        }
+    }
+}
+
+/// The standard protocol carries neither a sequence number nor a role, both stay unknown
+impl From<GameControllerReturnMessage> for HulkMessage {
+    fn from(message: GameControllerReturnMessage) -> Self {
+        Self {
+            player_number: message.player_number,
+            sequence_number: None,
+            fallen: message.fallen,
+            robot_to_field: message.robot_to_field,
+            role: None,
+            ball_position: message.ball_position,
+            time_to_reach_kick_position: None,
+            intention: None,
+        }
+    }
+}
+
+impl From<HulkMessage> for GameControllerReturnMessage {
+    fn from(message: HulkMessage) -> Self {
+        Self {
+            player_number: message.player_number,
+            fallen: message.fallen,
+            robot_to_field: message.robot_to_field,
+            ball_position: message.ball_position,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use nalgebra::{point, vector, Isometry2};
+
+    use crate::{BallPosition, PlayerNumber, Role, HULK_MESSAGE_CODEC_VERSION};
+
+    use super::*;
+
+    fn test_message() -> HulkMessage {
+        HulkMessage {
+            player_number: PlayerNumber::Three,
+            sequence_number: Some(42),
+            fallen: true,
+            robot_to_field: Isometry2::new(vector![1.0, -2.0], 0.5),
+            role: Some(Role::DefenderLeft),
+            ball_position: Some(BallPosition {
+                relative_position: point![0.5, 0.25],
+                age: Duration::from_secs(1),
+            }),
+            time_to_reach_kick_position: Some(Duration::from_secs(3)),
+            intention: None,
+        }
+    }
+
+    #[test]
+    fn extended_messages_keep_hulks_specific_fields() {
+        let buffer: Vec<u8> = TeamMessage {
+            protocol: TeamMessageProtocol::Extended,
+            message: test_message(),
+        }
+        .into();
+        let decoded = TeamMessage::try_from(buffer.as_slice()).unwrap();
+
+        assert_eq!(decoded.protocol, TeamMessageProtocol::Extended);
+        assert_eq!(decoded.message.sequence_number, Some(42));
+        assert_eq!(decoded.message.role, Some(Role::DefenderLeft));
+        assert_eq!(
+            decoded.message.time_to_reach_kick_position,
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[test]
+    fn standard_messages_keep_common_fields() {
+        let buffer: Vec<u8> = TeamMessage {
+            protocol: TeamMessageProtocol::Standard,
+            message: test_message(),
+        }
+        .into();
+        let decoded = TeamMessage::try_from(buffer.as_slice()).unwrap();
+
+        assert_eq!(decoded.protocol, TeamMessageProtocol::Standard);
+        assert_eq!(decoded.message.player_number, PlayerNumber::Three);
+        assert!(decoded.message.fallen);
+        assert!(decoded.message.ball_position.is_some());
+        assert_eq!(decoded.message.sequence_number, None);
+        assert_eq!(decoded.message.role, None);
+        assert_eq!(decoded.message.time_to_reach_kick_position, None);
+    }
+
+    #[test]
+    fn extended_messages_from_other_teams_are_rejected() {
+        let mut buffer: Vec<u8> = TeamMessage {
+            protocol: TeamMessageProtocol::Extended,
+            message: test_message(),
+        }
+        .into();
+        buffer[EXTENDED_PROTOCOL_HEADER.len()] = HULKS_TEAM_NUMBER + 1;
+
+        assert!(TeamMessage::try_from(buffer.as_slice()).is_err());
+    }
+
+    #[test]
+    fn extended_messages_with_unknown_version_are_rejected() {
+        let mut buffer: Vec<u8> = TeamMessage {
+            protocol: TeamMessageProtocol::Extended,
+            message: test_message(),
+        }
+        .into();
+        buffer[EXTENDED_PROTOCOL_HEADER.len() + 1] = HULK_MESSAGE_CODEC_VERSION + 1;
+
+        assert!(TeamMessage::try_from(buffer.as_slice()).is_err());
+    }
+}
@@ -47,7 +47,7 @@ pub enum VisualRefereeDecision {
     SubstitutionRed = GAMECONTROLLER_RETURN_STRUCT_VRC_GESTURE_SUBSTITUTION_RED_TEAM,
 }
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct VisualRefereeMessage {
     pub player_number: PlayerNumber,
     pub gesture: VisualRefereeDecision,
@@ -0,0 +1,70 @@
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use types::{ycbcr422_image::YCbCr422Image, Rgb, YCbCr422, YCbCr444};
+
+const ITERATIONS: u32 = 200;
+
+fn measure(name: &str, mut function: impl FnMut()) -> Duration {
+    function();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        function();
+    }
+    let duration = start.elapsed() / ITERATIONS;
+    println!("{name:<40} {duration:>12.2?}");
+    duration
+}
+
+fn main() {
+    let buffer = (0..320 * 480)
+        .map(|index: u32| {
+            let value = (index * 37 % 256) as u8;
+            YCbCr422::new(
+                value,
+                value.wrapping_mul(7),
+                255 - value,
+                value.wrapping_mul(13),
+            )
+        })
+        .collect();
+    let image = YCbCr422Image::from_ycbcr_buffer(320, 480, buffer);
+
+    let iterator_luminance = measure("luminance (per pixel iterator)", || {
+        let luminance: Vec<_> = image
+            .buffer()
+            .iter()
+            .flat_map(|pixel| [pixel.y1, pixel.y2])
+            .collect();
+        black_box(luminance);
+    });
+    let vectorized_luminance = measure("luminance (vectorized)", || {
+        black_box(image.luminance());
+    });
+    println!(
+        "luminance speedup: {:.1}x",
+        iterator_luminance.as_secs_f64() / vectorized_luminance.as_secs_f64()
+    );
+
+    let iterator_rgb = measure("rgb (per pixel iterator)", || {
+        let rgb: Vec<_> = image
+            .buffer()
+            .iter()
+            .flat_map(|&pixel| <[YCbCr444; 2]>::from(pixel))
+            .flat_map(|pixel| {
+                let rgb = Rgb::from(pixel);
+                [rgb.r, rgb.g, rgb.b]
+            })
+            .collect();
+        black_box(rgb);
+    });
+    let vectorized_rgb = measure("rgb (vectorized)", || {
+        black_box(image.to_rgb_image());
+    });
+    println!(
+        "rgb speedup: {:.1}x",
+        iterator_rgb.as_secs_f64() / vectorized_rgb.as_secs_f64()
+    );
+}
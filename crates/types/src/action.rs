@@ -19,13 +19,18 @@ pub enum Action {
     DefendLeft,
     DefendRight,
     DefendPenaltyKick,
+    KickOff,
     Jump,
     PrepareJump,
+    PenaltyKeeper,
+    ReceiveKickOff,
     SupportLeft,
     SupportRight,
     SupportStriker,
     Search,
     SearchForLostBall,
+    Teleoperate,
+    WalkToBall,
     WalkToKickOff,
     WalkToPenaltyKick,
 }
@@ -1,5 +1,5 @@
 use approx::{AbsDiffEq, RelativeEq};
-use nalgebra::Point2;
+use nalgebra::{Matrix2, Point2};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
@@ -17,6 +17,9 @@ pub struct CandidateEvaluation {
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
 pub struct Ball {
     pub position: Point2<f32>,
+    /// Uncertainty of the position in ground coordinates propagated from the image
+    #[serialize_hierarchy(leaf)]
+    pub covariance: Matrix2<f32>,
     pub image_location: Circle,
 }
 
@@ -29,6 +32,7 @@ impl AbsDiffEq for Ball {
 
     fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
         self.position.abs_diff_eq(&other.position, epsilon)
+            && self.covariance.abs_diff_eq(&other.covariance, epsilon)
             && self
                 .image_location
                 .abs_diff_eq(&other.image_location, epsilon)
@@ -48,6 +52,9 @@ impl RelativeEq for Ball {
     ) -> bool {
         self.position
             .relative_eq(&other.position, epsilon, max_relative)
+            && self
+                .covariance
+                .relative_eq(&other.covariance, epsilon, max_relative)
             && self
                 .image_location
                 .relative_eq(&other.image_location, epsilon, max_relative)
@@ -34,6 +34,13 @@ impl Hypothesis {
         }
     }
 
+    /// Validity relative to the validity of a ball which is seen in every cycle
+    pub fn confidence(&self, configuration: &BallFilterConfiguration) -> f32 {
+        let steady_state_validity =
+            1.0 / (1.0 - configuration.visible_validity_exponential_decay_factor);
+        (self.validity / steady_state_validity).clamp(0.0, 1.0)
+    }
+
     pub fn selected_ball_position(&self, configuration: &BallFilterConfiguration) -> BallPosition {
         let selected_state = self.selected_state(configuration);
 
@@ -41,6 +48,7 @@ impl Hypothesis {
             position: Point2::from(selected_state.mean.xy()),
             velocity: vector![selected_state.mean.z, selected_state.mean.w],
             last_seen: self.last_update,
+            confidence: self.confidence(configuration),
         }
     }
 }
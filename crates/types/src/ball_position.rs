@@ -9,6 +9,8 @@ pub struct BallPosition {
     pub position: Point2<f32>,
     pub velocity: Vector2<f32>,
     pub last_seen: SystemTime,
+    /// How much the ball is trusted, between 0.0 and 1.0
+    pub confidence: f32,
 }
 
 impl Default for BallPosition {
@@ -17,6 +19,7 @@ impl Default for BallPosition {
             position: Default::default(),
             velocity: Default::default(),
             last_seen: UNIX_EPOCH,
+            confidence: 0.0,
         }
     }
 }
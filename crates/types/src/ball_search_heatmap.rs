@@ -0,0 +1,72 @@
+use nalgebra::{point, vector, Point2};
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::FieldDimensions;
+
+/// Grid over the field holding how likely the ball is found in each cell, in [0, 1]
+///
+/// Cells are stored row-major, starting at the right corner of the own goal line.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BallSearchHeatmap {
+    pub cell_size: f32,
+    pub rows: usize,
+    pub columns: usize,
+    pub origin: Point2<f32>,
+    pub information: Vec<f32>,
+}
+
+impl BallSearchHeatmap {
+    pub fn new(field_dimensions: &FieldDimensions, cell_size: f32) -> Self {
+        let columns = (field_dimensions.length.0 / cell_size).ceil().max(1.0) as usize;
+        let rows = (field_dimensions.width.0 / cell_size).ceil().max(1.0) as usize;
+        Self {
+            cell_size,
+            rows,
+            columns,
+            origin: point![
+                -field_dimensions.length.0 / 2.0,
+                -field_dimensions.width.0 / 2.0
+            ],
+            information: vec![1.0; rows * columns],
+        }
+    }
+
+    pub fn cell_center(&self, index: usize) -> Point2<f32> {
+        let row = index / self.columns;
+        let column = index % self.columns;
+        self.origin + self.cell_size * vector![column as f32 + 0.5, row as f32 + 0.5]
+    }
+
+    /// Iterates cell centers in field coordinates together with their information
+    pub fn cells(&self) -> impl Iterator<Item = (usize, Point2<f32>, f32)> + '_ {
+        self.information
+            .iter()
+            .enumerate()
+            .map(|(index, information)| (index, self.cell_center(index), *information))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use crate::units::Meters;
+
+    use super::*;
+
+    #[test]
+    fn cells_cover_the_field() {
+        let field_dimensions = FieldDimensions {
+            length: Meters(9.0),
+            width: Meters(6.0),
+            ..Default::default()
+        };
+
+        let heatmap = BallSearchHeatmap::new(&field_dimensions, 1.0);
+
+        assert_eq!(heatmap.information.len(), 54);
+        assert_relative_eq!(heatmap.cell_center(0), point![-4.0, -2.5]);
+        assert_relative_eq!(heatmap.cell_center(53), point![4.0, 2.5]);
+    }
+}
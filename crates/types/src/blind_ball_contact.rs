@@ -0,0 +1,17 @@
+use std::time::SystemTime;
+
+use nalgebra::Point2;
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::Side;
+
+/// Hint that the ball is probably at the feet although the own body hides it from the cameras
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy)]
+pub struct BlindBallContact {
+    /// Last known position of the ball in robot coordinates
+    pub position: Point2<f32>,
+    /// Foot whose bumper was pressed
+    pub side: Side,
+    pub touched_at: SystemTime,
+}
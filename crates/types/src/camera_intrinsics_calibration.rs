@@ -0,0 +1,19 @@
+use nalgebra::{Point2, Vector2};
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+/// Intrinsics of a camera estimated from observations of a checkerboard
+///
+/// Focal lengths and optical center are normalized by the image size like in the camera matrix
+/// parameters. The radial distortion is estimated for inspection only, the projection does not
+/// model distortion.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CameraIntrinsicsCalibration {
+    pub focal_lengths: Vector2<f32>,
+    pub optical_center: Point2<f32>,
+    /// Coefficients of r^2 and r^4 scaling the normalized image coordinates
+    pub radial_distortion: Vector2<f32>,
+    /// Root mean square distance between observed and reprojected corners in pixels
+    pub reprojection_error: f32,
+    pub number_of_observations: usize,
+}
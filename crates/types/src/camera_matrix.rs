@@ -143,6 +143,20 @@ impl CameraMatrix {
             horizon: self.horizon,
         }
     }
+
+    /// Adapts the intrinsics to an image that was resized by `factor`, e.g. 0.5 for half
+    /// resolution, so that projections of pixels in the resized image stay valid
+    pub fn to_scaled(&self, factor: f32) -> Self {
+        CameraMatrix {
+            focal_length: self.focal_length * factor,
+            optical_center: self.optical_center * factor,
+            horizon: Horizon {
+                left_horizon_y: self.horizon.left_horizon_y * factor,
+                right_horizon_y: self.horizon.right_horizon_y * factor,
+            },
+            ..self.clone()
+        }
+    }
 }
 
 impl AbsDiffEq for CameraMatrix {
@@ -273,6 +287,28 @@ mod tests {
 
         assert_relative_eq!(original, corrected, epsilon = 0.001);
     }
+
+    #[test]
+    fn scaled_intrinsics_keep_field_of_view() {
+        let original = CameraMatrix::from_normalized_focal_and_center(
+            vector![0.95, 1.27],
+            point![0.5, 0.5],
+            vector![640.0, 480.0],
+            Isometry3::identity(),
+            Isometry3::identity(),
+            Isometry3::translation(0.0, 0.0, 0.5),
+        );
+
+        let scaled = original.to_scaled(0.5);
+
+        assert_relative_eq!(scaled.focal_length, original.focal_length / 2.0);
+        assert_relative_eq!(scaled.optical_center, point![160.0, 120.0]);
+        assert_relative_eq!(scaled.field_of_view, original.field_of_view);
+        assert_relative_eq!(
+            scaled.horizon.left_horizon_y,
+            original.horizon.left_horizon_y / 2.0
+        );
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -0,0 +1,189 @@
+//! Geometry tagged with the coordinate system it is expressed in
+//!
+//! Points and poses carry their frame as a type parameter and transforms carry the frame they map
+//! from and the one they map to. Applying a transform to a point of the wrong frame, or using a
+//! transform in the wrong direction (e.g. `robot_to_field` instead of its inverse), fails to
+//! compile. The wrapped nalgebra values are accessible via `inner` for computations within a
+//! single frame.
+
+use std::{collections::BTreeSet, marker::PhantomData, ops::Mul};
+
+use nalgebra::{Isometry2, Point2};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serialize_hierarchy::{Error, SerializeHierarchy};
+
+/// The field with its origin at the center and the x axis pointing towards the opponent goal
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Field;
+
+/// The ground below the robot with the x axis pointing forward
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Ground;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Framed<Frame, Inner> {
+    pub inner: Inner,
+    #[serde(skip)]
+    frame: PhantomData<Frame>,
+}
+
+impl<Frame, Inner> Framed<Frame, Inner> {
+    pub const fn wrap(inner: Inner) -> Self {
+        Self {
+            inner,
+            frame: PhantomData,
+        }
+    }
+}
+
+impl<Frame, Inner> SerializeHierarchy for Framed<Frame, Inner>
+where
+    Inner: SerializeHierarchy,
+{
+    fn serialize_path<S>(&self, path: &str, serializer: S) -> Result<S::Ok, Error<S::Error>>
+    where
+        S: Serializer,
+    {
+        self.inner.serialize_path(path, serializer)
+    }
+
+    fn deserialize_path<'de, D>(
+        &mut self,
+        path: &str,
+        deserializer: D,
+    ) -> Result<(), Error<D::Error>>
+    where
+        D: Deserializer<'de>,
+    {
+        self.inner.deserialize_path(path, deserializer)
+    }
+
+    fn exists(path: &str) -> bool {
+        Inner::exists(path)
+    }
+
+    fn get_fields() -> BTreeSet<String> {
+        Inner::get_fields()
+    }
+}
+
+pub type Point<Frame> = Framed<Frame, Point2<f32>>;
+pub type Pose<Frame> = Framed<Frame, Isometry2<f32>>;
+
+impl<Frame> Point<Frame> {
+    pub fn origin() -> Self {
+        Self::wrap(Point2::origin())
+    }
+}
+
+impl<Frame> Pose<Frame> {
+    pub fn position(&self) -> Point<Frame> {
+        Point::wrap(self.inner * Point2::origin())
+    }
+}
+
+/// Maps geometry from the frame `Source` into the frame `Destination`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Transform<Source, Destination> {
+    pub inner: Isometry2<f32>,
+    #[serde(skip)]
+    frames: PhantomData<(Source, Destination)>,
+}
+
+impl<Source, Destination> Transform<Source, Destination> {
+    pub fn wrap(inner: Isometry2<f32>) -> Self {
+        Self {
+            inner,
+            frames: PhantomData,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::wrap(Isometry2::identity())
+    }
+
+    pub fn inverse(&self) -> Transform<Destination, Source> {
+        Transform::wrap(self.inner.inverse())
+    }
+}
+
+impl<Source, Destination> SerializeHierarchy for Transform<Source, Destination> {
+    fn serialize_path<S>(&self, path: &str, serializer: S) -> Result<S::Ok, Error<S::Error>>
+    where
+        S: Serializer,
+    {
+        self.inner.serialize_path(path, serializer)
+    }
+
+    fn deserialize_path<'de, D>(
+        &mut self,
+        path: &str,
+        deserializer: D,
+    ) -> Result<(), Error<D::Error>>
+    where
+        D: Deserializer<'de>,
+    {
+        self.inner.deserialize_path(path, deserializer)
+    }
+
+    fn exists(path: &str) -> bool {
+        Isometry2::<f32>::exists(path)
+    }
+
+    fn get_fields() -> BTreeSet<String> {
+        Isometry2::<f32>::get_fields()
+    }
+}
+
+impl<Source, Destination> Mul<Point<Source>> for Transform<Source, Destination> {
+    type Output = Point<Destination>;
+
+    fn mul(self, point: Point<Source>) -> Self::Output {
+        Point::wrap(self.inner * point.inner)
+    }
+}
+
+impl<Source, Destination> Mul<Pose<Source>> for Transform<Source, Destination> {
+    type Output = Pose<Destination>;
+
+    fn mul(self, pose: Pose<Source>) -> Self::Output {
+        Pose::wrap(self.inner * pose.inner)
+    }
+}
+
+impl<Source, Intermediate, Destination> Mul<Transform<Source, Intermediate>>
+    for Transform<Intermediate, Destination>
+{
+    type Output = Transform<Source, Destination>;
+
+    fn mul(self, transform: Transform<Source, Intermediate>) -> Self::Output {
+        Transform::wrap(self.inner * transform.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::{point, vector};
+
+    use super::*;
+
+    #[test]
+    fn points_are_transformed_between_frames() {
+        let ground_to_field =
+            Transform::<Ground, Field>::wrap(Isometry2::new(vector![1.0, 2.0], 0.0));
+        let point_in_ground = Point::<Ground>::wrap(point![0.5, 0.0]);
+
+        let point_in_field = ground_to_field * point_in_ground;
+        let point_in_ground_again = ground_to_field.inverse() * point_in_field;
+
+        assert_relative_eq!(point_in_field.inner, point![1.5, 2.0]);
+        assert_relative_eq!(point_in_ground_again.inner, point_in_ground.inner);
+        assert_relative_eq!(
+            (ground_to_field.inverse() * ground_to_field).inner,
+            Transform::<Ground, Ground>::identity().inner
+        );
+    }
+}
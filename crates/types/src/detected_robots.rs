@@ -2,10 +2,19 @@ use nalgebra::{Point2, Vector2};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
+use crate::ObstacleKind;
+
 #[derive(Default, Clone, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub struct DetectedRobots {
     pub in_image: Vec<BoundingBox>,
-    pub on_ground: Vec<Point2<f32>>,
+    pub on_ground: Vec<RobotOnGround>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct RobotOnGround {
+    pub position: Point2<f32>,
+    /// Teammate or opponent if the jersey color was recognized
+    pub kind: ObstacleKind,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, SerializeHierarchy)]
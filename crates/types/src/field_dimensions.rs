@@ -1,33 +1,123 @@
-use nalgebra::Point2;
+use nalgebra::{point, Point2};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+use crate::units::Meters;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
 pub struct FieldDimensions {
-    pub ball_radius: f32,
-    pub length: f32,
-    pub width: f32,
-    pub line_width: f32,
-    pub penalty_marker_size: f32,
-    pub goal_box_area_length: f32,
-    pub goal_box_area_width: f32,
-    pub penalty_area_length: f32,
-    pub penalty_area_width: f32,
-    pub penalty_marker_distance: f32,
-    pub center_circle_diameter: f32,
-    pub border_strip_width: f32,
-    pub goal_inner_width: f32,
-    pub goal_post_diameter: f32,
-    pub goal_depth: f32,
+    pub ball_radius: Meters,
+    pub length: Meters,
+    pub width: Meters,
+    pub line_width: Meters,
+    pub penalty_marker_size: Meters,
+    pub goal_box_area_length: Meters,
+    pub goal_box_area_width: Meters,
+    pub penalty_area_length: Meters,
+    pub penalty_area_width: Meters,
+    pub penalty_marker_distance: Meters,
+    pub center_circle_diameter: Meters,
+    pub border_strip_width: Meters,
+    pub goal_inner_width: Meters,
+    pub goal_post_diameter: Meters,
+    pub goal_depth: Meters,
+}
+
+/// Named field dimensions selected by the `field_dimensions_preset` parameter
+///
+/// Values given in the `field_dimensions` parameter override the ones of the preset.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum FieldDimensionsPreset {
+    /// The field of the SPL rules
+    SplFullSize,
+    /// One half of a full size field with goals and penalty areas at both ends, e.g. when
+    /// sharing a field
+    HalfField,
+    /// Small carpet for tests in labs
+    LabCarpet,
 }
 
 impl FieldDimensions {
+    pub fn from_preset(preset: FieldDimensionsPreset) -> Self {
+        let spl_full_size = Self {
+            ball_radius: Meters(0.05),
+            length: Meters(9.0),
+            width: Meters(6.0),
+            line_width: Meters(0.05),
+            penalty_marker_size: Meters(0.1),
+            goal_box_area_length: Meters(0.6),
+            goal_box_area_width: Meters(2.2),
+            penalty_area_length: Meters(1.65),
+            penalty_area_width: Meters(4.0),
+            penalty_marker_distance: Meters(1.3),
+            center_circle_diameter: Meters(1.5),
+            border_strip_width: Meters(0.7),
+            goal_inner_width: Meters(1.5),
+            goal_post_diameter: Meters(0.1),
+            goal_depth: Meters(0.5),
+        };
+        match preset {
+            FieldDimensionsPreset::SplFullSize => spl_full_size,
+            FieldDimensionsPreset::HalfField => Self {
+                length: spl_full_size.length / 2.0,
+                penalty_area_length: Meters(1.0),
+                penalty_marker_distance: Meters(0.9),
+                center_circle_diameter: Meters(1.0),
+                ..spl_full_size
+            },
+            FieldDimensionsPreset::LabCarpet => Self {
+                length: Meters(6.0),
+                width: Meters(4.0),
+                goal_box_area_length: Meters(0.45),
+                goal_box_area_width: Meters(1.6),
+                penalty_area_length: Meters(1.2),
+                penalty_area_width: Meters(3.0),
+                penalty_marker_distance: Meters(1.0),
+                center_circle_diameter: Meters(1.2),
+                border_strip_width: Meters(0.5),
+                ..spl_full_size
+            },
+        }
+    }
+
     pub fn is_inside_field(&self, position: Point2<f32>) -> bool {
-        position.x.abs() < self.length / 2.0 && position.y.abs() < self.width / 2.0
+        position.x.abs() < self.length.0 / 2.0 && position.y.abs() < self.width.0 / 2.0
     }
 
     pub fn is_inside_any_goal_box(&self, position: Point2<f32>) -> bool {
-        position.x.abs() > self.length / 2.0 - self.goal_box_area_length
-            && position.y.abs() < self.goal_box_area_width / 2.0
+        position.x.abs() > self.goal_box_area_front_x().0
+            && position.y.abs() < self.goal_box_area_width.0 / 2.0
+    }
+
+    /// Distance of the front line of the goal boxes to the center line
+    pub fn goal_box_area_front_x(&self) -> Meters {
+        self.length / 2.0 - self.goal_box_area_length
+    }
+
+    /// Distance of the penalty spots to the center line
+    pub fn penalty_spot_x(&self) -> Meters {
+        self.length / 2.0 - self.penalty_marker_distance
+    }
+
+    pub fn own_penalty_spot(&self) -> Point2<f32> {
+        point![-self.penalty_spot_x().0, 0.0]
+    }
+
+    pub fn opponent_penalty_spot(&self) -> Point2<f32> {
+        point![self.penalty_spot_x().0, 0.0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn penalty_spots_are_in_front_of_goals() {
+        let field_dimensions = FieldDimensions::from_preset(FieldDimensionsPreset::SplFullSize);
+
+        assert_eq!(field_dimensions.opponent_penalty_spot(), point![3.2, 0.0]);
+        assert_eq!(field_dimensions.own_penalty_spot(), point![-3.2, 0.0]);
+        assert_eq!(field_dimensions.goal_box_area_front_x(), Meters(3.9));
     }
 }
@@ -147,50 +147,70 @@ pub struct CorrespondencePoints {
 }
 
 pub fn field_marks_from_field_dimensions(field_dimensions: &FieldDimensions) -> Vec<FieldMark> {
+    let own_penalty_spot = field_dimensions.own_penalty_spot();
+    let opponent_penalty_spot = field_dimensions.opponent_penalty_spot();
     vec![
         FieldMark::Line {
             line: Line(
-                point![-field_dimensions.length / 2.0, field_dimensions.width / 2.0],
-                point![field_dimensions.length / 2.0, field_dimensions.width / 2.0],
+                point![
+                    -field_dimensions.length.0 / 2.0,
+                    field_dimensions.width.0 / 2.0
+                ],
+                point![
+                    field_dimensions.length.0 / 2.0,
+                    field_dimensions.width.0 / 2.0
+                ],
             ),
             direction: Direction::PositiveX,
         },
         FieldMark::Line {
             line: Line(
                 point![
-                    -field_dimensions.length / 2.0,
-                    -field_dimensions.width / 2.0
+                    -field_dimensions.length.0 / 2.0,
+                    -field_dimensions.width.0 / 2.0
+                ],
+                point![
+                    field_dimensions.length.0 / 2.0,
+                    -field_dimensions.width.0 / 2.0
                 ],
-                point![field_dimensions.length / 2.0, -field_dimensions.width / 2.0],
             ),
             direction: Direction::PositiveX,
         },
         FieldMark::Line {
             line: Line(
                 point![
-                    -field_dimensions.length / 2.0,
-                    -field_dimensions.width / 2.0
+                    -field_dimensions.length.0 / 2.0,
+                    -field_dimensions.width.0 / 2.0
+                ],
+                point![
+                    -field_dimensions.length.0 / 2.0,
+                    field_dimensions.width.0 / 2.0
                 ],
-                point![-field_dimensions.length / 2.0, field_dimensions.width / 2.0],
             ),
             direction: Direction::PositiveY,
         },
         FieldMark::Line {
             line: Line(
-                point![field_dimensions.length / 2.0, -field_dimensions.width / 2.0],
-                point![field_dimensions.length / 2.0, field_dimensions.width / 2.0],
+                point![
+                    field_dimensions.length.0 / 2.0,
+                    -field_dimensions.width.0 / 2.0
+                ],
+                point![
+                    field_dimensions.length.0 / 2.0,
+                    field_dimensions.width.0 / 2.0
+                ],
             ),
             direction: Direction::PositiveY,
         },
         FieldMark::Line {
             line: Line(
                 point![
-                    -field_dimensions.length / 2.0,
-                    field_dimensions.penalty_area_width / 2.0
+                    -field_dimensions.length.0 / 2.0,
+                    field_dimensions.penalty_area_width.0 / 2.0
                 ],
                 point![
-                    -field_dimensions.length / 2.0 + field_dimensions.penalty_area_length,
-                    field_dimensions.penalty_area_width / 2.0
+                    -field_dimensions.length.0 / 2.0 + field_dimensions.penalty_area_length.0,
+                    field_dimensions.penalty_area_width.0 / 2.0
                 ],
             ),
             direction: Direction::PositiveX,
@@ -198,12 +218,12 @@ pub fn field_marks_from_field_dimensions(field_dimensions: &FieldDimensions) ->
         FieldMark::Line {
             line: Line(
                 point![
-                    -field_dimensions.length / 2.0,
-                    -field_dimensions.penalty_area_width / 2.0
+                    -field_dimensions.length.0 / 2.0,
+                    -field_dimensions.penalty_area_width.0 / 2.0
                 ],
                 point![
-                    -field_dimensions.length / 2.0 + field_dimensions.penalty_area_length,
-                    -field_dimensions.penalty_area_width / 2.0
+                    -field_dimensions.length.0 / 2.0 + field_dimensions.penalty_area_length.0,
+                    -field_dimensions.penalty_area_width.0 / 2.0
                 ],
             ),
             direction: Direction::PositiveX,
@@ -211,12 +231,12 @@ pub fn field_marks_from_field_dimensions(field_dimensions: &FieldDimensions) ->
         FieldMark::Line {
             line: Line(
                 point![
-                    -field_dimensions.length / 2.0 + field_dimensions.penalty_area_length,
-                    -field_dimensions.penalty_area_width / 2.0
+                    -field_dimensions.length.0 / 2.0 + field_dimensions.penalty_area_length.0,
+                    -field_dimensions.penalty_area_width.0 / 2.0
                 ],
                 point![
-                    -field_dimensions.length / 2.0 + field_dimensions.penalty_area_length,
-                    field_dimensions.penalty_area_width / 2.0
+                    -field_dimensions.length.0 / 2.0 + field_dimensions.penalty_area_length.0,
+                    field_dimensions.penalty_area_width.0 / 2.0
                 ],
             ),
             direction: Direction::PositiveY,
@@ -224,12 +244,12 @@ pub fn field_marks_from_field_dimensions(field_dimensions: &FieldDimensions) ->
         FieldMark::Line {
             line: Line(
                 point![
-                    -field_dimensions.length / 2.0,
-                    field_dimensions.goal_box_area_width / 2.0
+                    -field_dimensions.length.0 / 2.0,
+                    field_dimensions.goal_box_area_width.0 / 2.0
                 ],
                 point![
-                    -field_dimensions.length / 2.0 + field_dimensions.goal_box_area_length,
-                    field_dimensions.goal_box_area_width / 2.0
+                    -field_dimensions.length.0 / 2.0 + field_dimensions.goal_box_area_length.0,
+                    field_dimensions.goal_box_area_width.0 / 2.0
                 ],
             ),
             direction: Direction::PositiveX,
@@ -237,12 +257,12 @@ pub fn field_marks_from_field_dimensions(field_dimensions: &FieldDimensions) ->
         FieldMark::Line {
             line: Line(
                 point![
-                    -field_dimensions.length / 2.0,
-                    -field_dimensions.goal_box_area_width / 2.0
+                    -field_dimensions.length.0 / 2.0,
+                    -field_dimensions.goal_box_area_width.0 / 2.0
                 ],
                 point![
-                    -field_dimensions.length / 2.0 + field_dimensions.goal_box_area_length,
-                    -field_dimensions.goal_box_area_width / 2.0
+                    -field_dimensions.length.0 / 2.0 + field_dimensions.goal_box_area_length.0,
+                    -field_dimensions.goal_box_area_width.0 / 2.0
                 ],
             ),
             direction: Direction::PositiveX,
@@ -250,12 +270,12 @@ pub fn field_marks_from_field_dimensions(field_dimensions: &FieldDimensions) ->
         FieldMark::Line {
             line: Line(
                 point![
-                    -field_dimensions.length / 2.0 + field_dimensions.goal_box_area_length,
-                    -field_dimensions.goal_box_area_width / 2.0
+                    -field_dimensions.length.0 / 2.0 + field_dimensions.goal_box_area_length.0,
+                    -field_dimensions.goal_box_area_width.0 / 2.0
                 ],
                 point![
-                    -field_dimensions.length / 2.0 + field_dimensions.goal_box_area_length,
-                    field_dimensions.goal_box_area_width / 2.0
+                    -field_dimensions.length.0 / 2.0 + field_dimensions.goal_box_area_length.0,
+                    field_dimensions.goal_box_area_width.0 / 2.0
                 ],
             ),
             direction: Direction::PositiveY,
@@ -263,12 +283,12 @@ pub fn field_marks_from_field_dimensions(field_dimensions: &FieldDimensions) ->
         FieldMark::Line {
             line: Line(
                 point![
-                    field_dimensions.length / 2.0 - field_dimensions.penalty_area_length,
-                    field_dimensions.penalty_area_width / 2.0
+                    field_dimensions.length.0 / 2.0 - field_dimensions.penalty_area_length.0,
+                    field_dimensions.penalty_area_width.0 / 2.0
                 ],
                 point![
-                    field_dimensions.length / 2.0,
-                    field_dimensions.penalty_area_width / 2.0
+                    field_dimensions.length.0 / 2.0,
+                    field_dimensions.penalty_area_width.0 / 2.0
                 ],
             ),
             direction: Direction::PositiveX,
@@ -276,12 +296,12 @@ pub fn field_marks_from_field_dimensions(field_dimensions: &FieldDimensions) ->
         FieldMark::Line {
             line: Line(
                 point![
-                    field_dimensions.length / 2.0 - field_dimensions.penalty_area_length,
-                    -field_dimensions.penalty_area_width / 2.0
+                    field_dimensions.length.0 / 2.0 - field_dimensions.penalty_area_length.0,
+                    -field_dimensions.penalty_area_width.0 / 2.0
                 ],
                 point![
-                    field_dimensions.length / 2.0,
-                    -field_dimensions.penalty_area_width / 2.0
+                    field_dimensions.length.0 / 2.0,
+                    -field_dimensions.penalty_area_width.0 / 2.0
                 ],
             ),
             direction: Direction::PositiveX,
@@ -289,12 +309,12 @@ pub fn field_marks_from_field_dimensions(field_dimensions: &FieldDimensions) ->
         FieldMark::Line {
             line: Line(
                 point![
-                    field_dimensions.length / 2.0 - field_dimensions.penalty_area_length,
-                    -field_dimensions.penalty_area_width / 2.0
+                    field_dimensions.length.0 / 2.0 - field_dimensions.penalty_area_length.0,
+                    -field_dimensions.penalty_area_width.0 / 2.0
                 ],
                 point![
-                    field_dimensions.length / 2.0 - field_dimensions.penalty_area_length,
-                    field_dimensions.penalty_area_width / 2.0
+                    field_dimensions.length.0 / 2.0 - field_dimensions.penalty_area_length.0,
+                    field_dimensions.penalty_area_width.0 / 2.0
                 ],
             ),
             direction: Direction::PositiveY,
@@ -302,12 +322,12 @@ pub fn field_marks_from_field_dimensions(field_dimensions: &FieldDimensions) ->
         FieldMark::Line {
             line: Line(
                 point![
-                    field_dimensions.length / 2.0 - field_dimensions.goal_box_area_length,
-                    field_dimensions.goal_box_area_width / 2.0
+                    field_dimensions.length.0 / 2.0 - field_dimensions.goal_box_area_length.0,
+                    field_dimensions.goal_box_area_width.0 / 2.0
                 ],
                 point![
-                    field_dimensions.length / 2.0,
-                    field_dimensions.goal_box_area_width / 2.0
+                    field_dimensions.length.0 / 2.0,
+                    field_dimensions.goal_box_area_width.0 / 2.0
                 ],
             ),
             direction: Direction::PositiveX,
@@ -315,12 +335,12 @@ pub fn field_marks_from_field_dimensions(field_dimensions: &FieldDimensions) ->
         FieldMark::Line {
             line: Line(
                 point![
-                    field_dimensions.length / 2.0 - field_dimensions.goal_box_area_length,
-                    -field_dimensions.goal_box_area_width / 2.0
+                    field_dimensions.length.0 / 2.0 - field_dimensions.goal_box_area_length.0,
+                    -field_dimensions.goal_box_area_width.0 / 2.0
                 ],
                 point![
-                    field_dimensions.length / 2.0,
-                    -field_dimensions.goal_box_area_width / 2.0
+                    field_dimensions.length.0 / 2.0,
+                    -field_dimensions.goal_box_area_width.0 / 2.0
                 ],
             ),
             direction: Direction::PositiveX,
@@ -328,82 +348,52 @@ pub fn field_marks_from_field_dimensions(field_dimensions: &FieldDimensions) ->
         FieldMark::Line {
             line: Line(
                 point![
-                    field_dimensions.length / 2.0 - field_dimensions.goal_box_area_length,
-                    -field_dimensions.goal_box_area_width / 2.0
+                    field_dimensions.length.0 / 2.0 - field_dimensions.goal_box_area_length.0,
+                    -field_dimensions.goal_box_area_width.0 / 2.0
                 ],
                 point![
-                    field_dimensions.length / 2.0 - field_dimensions.goal_box_area_length,
-                    field_dimensions.goal_box_area_width / 2.0
+                    field_dimensions.length.0 / 2.0 - field_dimensions.goal_box_area_length.0,
+                    field_dimensions.goal_box_area_width.0 / 2.0
                 ],
             ),
             direction: Direction::PositiveY,
         },
         FieldMark::Line {
             line: Line(
-                point![0.0, -field_dimensions.width / 2.0],
-                point![0.0, field_dimensions.width / 2.0],
+                point![0.0, -field_dimensions.width.0 / 2.0],
+                point![0.0, field_dimensions.width.0 / 2.0],
             ),
             direction: Direction::PositiveY,
         },
         FieldMark::Circle {
             center: Point2::origin(),
-            radius: field_dimensions.center_circle_diameter / 2.0,
+            radius: field_dimensions.center_circle_diameter.0 / 2.0,
         },
         FieldMark::Line {
             line: Line(
-                point![
-                    -field_dimensions.length / 2.0 + field_dimensions.penalty_marker_distance
-                        - field_dimensions.penalty_marker_size / 2.0,
-                    0.0
-                ],
-                point![
-                    -field_dimensions.length / 2.0
-                        + field_dimensions.penalty_marker_distance
-                        + field_dimensions.penalty_marker_size / 2.0,
-                    0.0
-                ],
+                own_penalty_spot - vector![field_dimensions.penalty_marker_size.0 / 2.0, 0.0],
+                own_penalty_spot + vector![field_dimensions.penalty_marker_size.0 / 2.0, 0.0],
             ),
             direction: Direction::PositiveX,
         },
         FieldMark::Line {
             line: Line(
-                point![
-                    -field_dimensions.length / 2.0 + field_dimensions.penalty_marker_distance,
-                    -field_dimensions.penalty_marker_size / 2.0
-                ],
-                point![
-                    -field_dimensions.length / 2.0 + field_dimensions.penalty_marker_distance,
-                    field_dimensions.penalty_marker_size / 2.0
-                ],
+                own_penalty_spot - vector![0.0, field_dimensions.penalty_marker_size.0 / 2.0],
+                own_penalty_spot + vector![0.0, field_dimensions.penalty_marker_size.0 / 2.0],
             ),
             direction: Direction::PositiveY,
         },
         FieldMark::Line {
             line: Line(
-                point![
-                    field_dimensions.length / 2.0
-                        - field_dimensions.penalty_marker_distance
-                        - field_dimensions.penalty_marker_size / 2.0,
-                    0.0
-                ],
-                point![
-                    field_dimensions.length / 2.0 - field_dimensions.penalty_marker_distance
-                        + field_dimensions.penalty_marker_size / 2.0,
-                    0.0
-                ],
+                opponent_penalty_spot - vector![field_dimensions.penalty_marker_size.0 / 2.0, 0.0],
+                opponent_penalty_spot + vector![field_dimensions.penalty_marker_size.0 / 2.0, 0.0],
             ),
             direction: Direction::PositiveX,
         },
         FieldMark::Line {
             line: Line(
-                point![
-                    field_dimensions.length / 2.0 - field_dimensions.penalty_marker_distance,
-                    -field_dimensions.penalty_marker_size / 2.0
-                ],
-                point![
-                    field_dimensions.length / 2.0 - field_dimensions.penalty_marker_distance,
-                    field_dimensions.penalty_marker_size / 2.0
-                ],
+                opponent_penalty_spot - vector![0.0, field_dimensions.penalty_marker_size.0 / 2.0],
+                opponent_penalty_spot + vector![0.0, field_dimensions.penalty_marker_size.0 / 2.0],
             ),
             direction: Direction::PositiveY,
         },
@@ -5,7 +5,7 @@ use serialize_hierarchy::{Error, SerializeHierarchy};
 
 use std::{collections::BTreeSet, f32::consts::PI};
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
 pub enum Orientation {
     Clockwise,
     Counterclockwise,
@@ -30,6 +30,75 @@ pub fn rotate_towards(origin: Point2<f32>, target: Point2<f32>) -> UnitComplex<f
 #[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct LineSegment(pub Point2<f32>, pub Point2<f32>);
 
+impl SerializeHierarchy for LineSegment {
+    fn serialize_path<S>(&self, path: &str, serializer: S) -> Result<S::Ok, Error<S::Error>>
+    where
+        S: Serializer,
+    {
+        match path.split_once('.') {
+            Some(("0", suffix)) => self.0.serialize_path(suffix, serializer),
+            Some(("1", suffix)) => self.1.serialize_path(suffix, serializer),
+            None if path == "0" => self
+                .0
+                .serialize(serializer)
+                .map_err(Error::SerializationFailed),
+            None if path == "1" => self
+                .1
+                .serialize(serializer)
+                .map_err(Error::SerializationFailed),
+            _ => Err(Error::UnexpectedPathSegment {
+                segment: path.to_string(),
+            }),
+        }
+    }
+
+    fn deserialize_path<'de, D>(
+        &mut self,
+        path: &str,
+        deserializer: D,
+    ) -> Result<(), Error<D::Error>>
+    where
+        D: Deserializer<'de>,
+    {
+        match path.split_once('.') {
+            Some(("0", suffix)) => self.0.deserialize_path(suffix, deserializer),
+            Some(("1", suffix)) => self.1.deserialize_path(suffix, deserializer),
+            None if path == "0" => {
+                self.0 = Point2::deserialize(deserializer).map_err(Error::DeserializationFailed)?;
+                Ok(())
+            }
+            None if path == "1" => {
+                self.1 = Point2::deserialize(deserializer).map_err(Error::DeserializationFailed)?;
+                Ok(())
+            }
+            _ => Err(Error::UnexpectedPathSegment {
+                segment: path.to_string(),
+            }),
+        }
+    }
+
+    fn exists(path: &str) -> bool {
+        match path.split_once('.') {
+            Some(("0" | "1", suffix)) => Point2::<f32>::exists(suffix),
+            None => matches!(path, "0" | "1"),
+            _ => false,
+        }
+    }
+
+    fn get_fields() -> BTreeSet<String> {
+        ["0", "1"]
+            .into_iter()
+            .flat_map(|index| {
+                std::iter::once(index.to_string()).chain(
+                    Point2::<f32>::get_fields()
+                        .into_iter()
+                        .map(move |name| format!("{index}.{name}")),
+                )
+            })
+            .collect()
+    }
+}
+
 impl AbsDiffEq for LineSegment {
     type Epsilon = f32;
 
@@ -360,6 +429,23 @@ impl Arc {
         }
         (angle * self.circle.radius).abs()
     }
+
+    /// Signed angle from start to end when moving in `orientation`, positive counterclockwise
+    ///
+    /// Colinear arcs are treated as counterclockwise.
+    pub fn sweep_angle(&self, orientation: Orientation) -> f32 {
+        let angle_of = |point: Point2<f32>| {
+            let vector = point - self.circle.center;
+            vector.y.atan2(vector.x)
+        };
+        let angle_start_to_end = angle_of(self.end) - angle_of(self.start);
+        match orientation {
+            Orientation::Clockwise => -(-angle_start_to_end).rem_euclid(2.0 * PI),
+            Orientation::Counterclockwise | Orientation::Colinear => {
+                angle_start_to_end.rem_euclid(2.0 * PI)
+            }
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
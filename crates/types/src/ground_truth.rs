@@ -0,0 +1,23 @@
+use nalgebra::{Isometry2, Point2};
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::Players;
+
+/// State of the field measured by an external system, e.g. a motion capture system
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct GroundTruth {
+    pub robot_to_field: Players<Option<Isometry2<f32>>>,
+    pub ball_in_field: Option<Point2<f32>>,
+}
+
+/// Deviations of the own estimates from the ground truth, absent if either side is unknown
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct GroundTruthErrors {
+    /// Distance between the localized and the measured robot position in meters
+    pub robot_position: Option<f32>,
+    /// Absolute difference between the localized and the measured robot orientation in radians
+    pub robot_orientation: Option<f32>,
+    /// Distance between the filtered and the measured ball position in meters
+    pub ball_position: Option<f32>,
+}
@@ -1,8 +1,12 @@
-use std::path::PathBuf;
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Ids {
     pub body_id: String,
     pub head_id: String,
@@ -13,4 +17,32 @@ pub struct Paths {
     pub parameters: PathBuf,
     pub motions: PathBuf,
     pub neural_networks: PathBuf,
+    pub sounds: PathBuf,
+    /// Cycler outputs are recorded into a new subdirectory per run if set
+    pub recordings: Option<PathBuf>,
+    /// Selected persistent state is saved into and restored from this directory if set
+    pub snapshots: Option<PathBuf>,
+}
+
+/// Health of a camera as observed by the hardware interface
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CameraDiagnostics {
+    /// Resets performed because the camera stopped delivering images
+    pub number_of_resets: usize,
+    pub last_reset: Option<SystemTime>,
+    /// Captured images which were replaced by newer ones before being read
+    pub number_of_dropped_frames: usize,
+}
+
+/// Statistics of the actuator commands written by the hardware interface
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ActuatorDiagnostics {
+    pub number_of_writes: usize,
+    /// Commands which were not sent because they equaled the previously sent one
+    pub number_of_suppressed_writes: usize,
+    /// LED changes which were postponed to the next LED update
+    pub number_of_postponed_led_updates: usize,
+    pub last_write: Option<SystemTime>,
+    /// Time between receiving the sensor data and writing the resulting command
+    pub actuation_latency: Duration,
 }
@@ -10,7 +10,10 @@ pub struct ImageSegments {
 
 #[derive(Default, Clone, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub struct ScanGrid {
+    /// Columns of the image, the segments span rows from top to bottom
     pub vertical_scan_lines: Vec<ScanLine>,
+    /// Rows of the image below the horizon, the segments span columns from left to right
+    pub horizontal_scan_lines: Vec<ScanLine>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
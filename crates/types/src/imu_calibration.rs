@@ -0,0 +1,17 @@
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+/// Corrections subtracted from the raw inertial measurement unit readings
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
+pub struct ImuCalibration {
+    pub gyro_bias: Vector3<f32>,
+    pub accelerometer_offset: Vector3<f32>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ImuCalibrationQuality {
+    pub number_of_samples: usize,
+    pub gyro_standard_deviation: f32,
+    pub accelerometer_standard_deviation: f32,
+}
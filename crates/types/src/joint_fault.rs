@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::Side;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub struct JointFault {
+    pub group: JointGroup,
+    pub kind: JointFaultKind,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum JointGroup {
+    Head,
+    LeftArm,
+    RightArm,
+    LeftLeg,
+    RightLeg,
+}
+
+impl JointGroup {
+    /// Groups in the order of [`Joints::as_vec()`](crate::Joints::as_vec)
+    pub const ALL: [JointGroup; 5] = [
+        JointGroup::Head,
+        JointGroup::LeftArm,
+        JointGroup::RightArm,
+        JointGroup::LeftLeg,
+        JointGroup::RightLeg,
+    ];
+
+    pub fn leg_side(self) -> Option<Side> {
+        match self {
+            JointGroup::LeftLeg => Some(Side::Left),
+            JointGroup::RightLeg => Some(Side::Right),
+            JointGroup::Head | JointGroup::LeftArm | JointGroup::RightArm => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum JointFaultKind {
+    /// The joint does not follow its command although the motor draws little current
+    SlippingGear,
+    /// The joint does not follow its command although the motor draws high current
+    Stuck,
+    /// The temperature rises faster than the joint can sustain
+    Overheating,
+}
@@ -1,4 +1,4 @@
-use nalgebra::Isometry2;
+use nalgebra::{Isometry2, Point2};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
@@ -9,6 +9,7 @@ pub struct KickDecision {
     pub variant: KickVariant,
     pub kicking_side: Side,
     pub kick_pose: Isometry2<f32>,
+    pub target: Point2<f32>,
     pub strength: f32,
     pub visible: bool,
 }
@@ -3,7 +3,7 @@ use serialize_hierarchy::SerializeHierarchy;
 
 use super::Rgb;
 
-#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, SerializeHierarchy)]
 pub struct Leds {
     pub left_ear: Ear,
     pub right_ear: Ear,
@@ -14,7 +14,7 @@ pub struct Leds {
     pub right_eye: Eye,
 }
 
-#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, SerializeHierarchy)]
 pub struct Eye {
     pub color_at_0: Rgb,
     pub color_at_45: Rgb,
@@ -41,7 +41,7 @@ impl From<Rgb> for Eye {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, SerializeHierarchy)]
 pub struct Ear {
     pub intensity_at_0: f32,
     pub intensity_at_36: f32,
@@ -3,11 +3,15 @@ mod action;
 mod ball;
 pub mod ball_filter;
 mod ball_position;
+mod ball_search_heatmap;
+mod blind_ball_contact;
 mod buttons;
+mod camera_intrinsics_calibration;
 pub mod camera_matrix;
 mod camera_position;
 mod color;
 pub mod condition_input;
+pub mod coordinate_systems;
 mod cycle_time;
 pub mod detected_feet;
 pub mod detected_robots;
@@ -22,12 +26,15 @@ mod filtered_whistle;
 mod game_controller_state;
 mod geometry;
 pub mod grayscale_image;
+mod ground_truth;
 pub mod hardware;
 pub mod horizon;
 mod image_segments;
+mod imu_calibration;
 pub mod initial_look_around;
 mod initial_pose;
 pub mod interpolated;
+mod joint_fault;
 mod joints;
 mod joints_velocity;
 mod kick_decision;
@@ -43,6 +50,8 @@ pub mod messages;
 mod motion_command;
 mod motion_selection;
 pub mod multivariate_normal_distribution;
+mod network_quality;
+mod node_failures;
 pub mod obstacle_filter;
 mod obstacles;
 pub mod orientation_filter;
@@ -57,16 +66,20 @@ mod primary_state;
 mod robot_dimensions;
 mod robot_kinematics;
 mod robot_masses;
-mod roles;
 mod rule_obstacles;
 pub mod samples;
 mod sensor_data;
 mod sole_pressure;
 mod sonar_obstacle;
 mod sonar_values;
+mod sound;
 mod step_adjustment;
 mod step_plan;
 mod support_foot;
+mod system_monitor;
+pub mod test_support;
+mod timings;
+pub mod units;
 mod walk_command;
 mod whistle;
 mod world_state;
@@ -77,7 +90,10 @@ pub mod ycbcr422_image;
 pub use action::Action;
 pub use ball::{Ball, CandidateEvaluation};
 pub use ball_position::BallPosition;
+pub use ball_search_heatmap::BallSearchHeatmap;
+pub use blind_ball_contact::BlindBallContact;
 pub use buttons::Buttons;
+pub use camera_intrinsics_calibration::CameraIntrinsicsCalibration;
 pub use camera_matrix::{CameraMatrices, CameraMatrix, ProjectedFieldLines};
 pub use camera_position::CameraPosition;
 pub use color::{Intensity, Rgb, RgbChannel, YCbCr422, YCbCr444};
@@ -86,19 +102,22 @@ pub use cycle_time::CycleTime;
 pub use fall_state::FallState;
 pub use field_border::FieldBorder;
 pub use field_color::FieldColor;
-pub use field_dimensions::FieldDimensions;
+pub use field_dimensions::{FieldDimensions, FieldDimensionsPreset};
 pub use field_marks::{
     field_marks_from_field_dimensions, CorrespondencePoints, Correspondences, Direction, FieldMark,
 };
 pub use filtered_game_state::FilteredGameState;
 pub use filtered_segments::FilteredSegments;
 pub use filtered_whistle::FilteredWhistle;
-pub use game_controller_state::GameControllerState;
+pub use game_controller_state::{GameControllerFreshness, GameControllerState, TeamColors};
 pub use geometry::{
     rotate_towards, Arc, Circle, LineSegment, Orientation, Rectangle, TwoLineSegments,
 };
+pub use ground_truth::{GroundTruth, GroundTruthErrors};
 pub use image_segments::{EdgeType, ImageSegments, ScanGrid, ScanLine, Segment};
+pub use imu_calibration::{ImuCalibration, ImuCalibrationQuality};
 pub use initial_pose::InitialPose;
+pub use joint_fault::{JointFault, JointFaultKind, JointGroup};
 pub use joints::{
     ArmJoints, BodyJoints, BodyJointsCommand, HeadJoints, HeadJointsCommand, Joints, JointsCommand,
     LegJoints,
@@ -113,33 +132,43 @@ pub use line::{Line, Line2};
 pub use line_data::{ImageLines, LineData, LineDiscardReason};
 pub use message_event::MessageEvent;
 pub use motion_command::{
-    ArmMotion, Facing, FallDirection, GlanceDirection, HeadMotion, JumpDirection, KickDirection,
-    KickVariant, MotionCommand, OrientationMode, SitDirection,
+    ArmMotion, ArrivalTolerance, Facing, FallDirection, GlanceDirection, HeadMotion, JumpDirection,
+    KickDirection, KickVariant, MotionCommand, OrientationMode, ScanProfile, SitDirection,
+    SpeedProfile,
 };
 pub use motion_selection::{MotionSafeExits, MotionSelection, MotionType};
+pub use network_quality::{NetworkQuality, TeammateConnection};
+pub use node_failures::{NodeFailure, NodeFailures};
 pub use obstacles::{Obstacle, ObstacleKind};
 pub use path_obstacles::{PathObstacle, PathObstacleShape};
 pub use penalty_shot_direction::PenaltyShotDirection;
 pub use perspective_grid_candidates::PerspectiveGridCandidates;
-pub use planned_path::{direct_path, PathSegment, PlannedPath};
+pub use planned_path::{
+    cumulative_lengths, direct_path, path_length, point_at_distance, PathSegment, PlannedPath,
+};
 pub use players::Players;
 pub use point_of_interest::PointOfInterest;
-pub use primary_state::PrimaryState;
+pub use primary_state::{PrimaryState, PrimaryStateTransition};
 pub use robot_dimensions::RobotDimensions;
 pub use robot_kinematics::RobotKinematics;
 pub use robot_masses::RobotMass;
-pub use roles::Role;
 pub use rule_obstacles::RuleObstacle;
 pub use sensor_data::{
-    Foot, ForceSensitiveResistors, InertialMeasurementUnitData, SensorData, SonarSensors,
+    Battery, Foot, ForceSensitiveResistors, InertialMeasurementUnitData, SensorData, SonarSensors,
     TouchSensors,
 };
 pub use sole_pressure::SolePressure;
 pub use sonar_obstacle::SonarObstacle;
 pub use sonar_values::SonarValues;
+pub use sound::Sound;
+pub use spl_network_messages::Role;
 pub use step_adjustment::StepAdjustment;
-pub use step_plan::Step;
+pub use step_plan::{Step, StepFactors};
 pub use support_foot::{Side, SupportFoot};
-pub use walk_command::WalkCommand;
+pub use system_monitor::{SystemMonitorSample, SystemWarning};
+pub use timings::{CycleOverruns, NodeTiming, TimingStatistics, Timings};
+pub use walk_command::{WalkCommand, WalkGait};
 pub use whistle::{DetectionInfo, Whistle};
-pub use world_state::{BallState, RobotState, WorldState};
+pub use world_state::{
+    BallSource, BallState, InputProvenance, RobotState, WorldState, WorldStateProvenance,
+};
@@ -1,4 +1,6 @@
-use nalgebra::{vector, Isometry2, Matrix3, Point2};
+use std::time::Duration;
+
+use nalgebra::{vector, Isometry2, Matrix3, Point2, Vector3};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
@@ -35,3 +37,15 @@ impl ScoredPose {
         }
     }
 }
+
+/// How much the pose of the best hypothesis can be trusted
+///
+/// Without line or circle measurements the pose is only dead reckoned from odometry and its
+/// covariance grows with every step.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy)]
+pub struct LocalizationQuality {
+    pub pose_covariance_eigenvalues: Vector3<f32>,
+    /// `None` if no measurement was accepted yet
+    pub time_since_last_measurement: Option<Duration>,
+    pub is_reliable: bool,
+}
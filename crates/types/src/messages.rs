@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 use spl_network_messages::{
-    GameControllerReturnMessage, GameControllerStateMessage, HulkMessage, VisualRefereeMessage,
+    GameControllerReturnMessage, GameControllerStateMessage, HulkMessage, TeamMessage,
+    VisualRefereeMessage,
 };
 
 #[derive(Clone, Debug, Deserialize, Serialize, SerializeHierarchy)]
@@ -19,7 +20,7 @@ impl Default for IncomingMessage {
 #[derive(Clone, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub enum OutgoingMessage {
     GameController(GameControllerReturnMessage),
-    Spl(HulkMessage),
+    Spl(TeamMessage),
     VisualReferee(VisualRefereeMessage),
 }
 
@@ -6,13 +6,15 @@ use crate::CameraPosition;
 
 use super::{PathSegment, Side};
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, SerializeHierarchy)]
 pub enum OrientationMode {
     AlignWithPath,
     Override(UnitComplex<f32>),
+    /// Keeps facing a point in ground coordinates while walking along the path
+    FaceTowards(Point2<f32>),
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
 pub enum MotionCommand {
     ArmsUpSquat,
     FallProtection {
@@ -40,6 +42,8 @@ pub enum MotionCommand {
         left_arm: ArmMotion,
         right_arm: ArmMotion,
         orientation_mode: OrientationMode,
+        speed_profile: Option<SpeedProfile>,
+        arrival_tolerance: Option<ArrivalTolerance>,
     },
     InWalkKick {
         head: HeadMotion,
@@ -66,11 +70,44 @@ impl MotionCommand {
     }
 }
 
+/// Limits the step size relative to the maximum step size depending on the remaining distance
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
+pub struct SpeedProfile {
+    pub cruise_fraction: f32,
+    pub creep_fraction: f32,
+    pub slow_down_radius: f32,
+}
+
+impl SpeedProfile {
+    pub fn fraction(&self, distance_to_target: f32) -> f32 {
+        if distance_to_target > self.slow_down_radius {
+            self.cruise_fraction
+        } else {
+            self.creep_fraction
+        }
+    }
+}
+
+/// The walk target counts as reached if the remaining distance and turn are within these limits
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
+pub struct ArrivalTolerance {
+    pub distance: f32,
+    pub angle: f32,
+}
+
+impl ArrivalTolerance {
+    pub fn is_reached(&self, distance_to_target: f32, angle_to_target: f32) -> bool {
+        distance_to_target <= self.distance && angle_to_target.abs() <= self.angle
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
 pub enum HeadMotion {
     ZeroAngles,
     Center,
-    LookAround,
+    LookAround {
+        profile: ScanProfile,
+    },
     SearchForLostBall,
     LookAt {
         target: Point2<f32>,
@@ -79,13 +116,30 @@ pub enum HeadMotion {
     LookLeftAndRightOf {
         target: Point2<f32>,
     },
+    Angles {
+        yaw: f32,
+        pitch: f32,
+    },
     Unstiff,
 }
 
+/// Pattern the head sweeps in while looking around
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum ScanProfile {
+    /// Slow and wide to see as many landmarks as possible for localization
+    Wide,
+    /// Fast and narrow to keep the surroundings of the path in view while dribbling
+    Narrow,
+    /// Low pitch to check the ground in front of the feet before kicking
+    Down,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
 pub enum ArmMotion {
     Swing,
     PullTight,
+    /// Both arms behind the torso, used when being squeezed between robots
+    BehindBack,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
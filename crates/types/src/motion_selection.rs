@@ -23,6 +23,7 @@ pub enum MotionType {
     StandUpBack,
     StandUpFront,
     Unstiff,
+    WakeUp,
     Walk,
 }
 
@@ -46,6 +47,7 @@ pub struct MotionSafeExits {
     stand_up_front: bool,
     stand: bool,
     unstiff: bool,
+    wake_up: bool,
     walk: bool,
 }
 
@@ -64,6 +66,7 @@ impl Default for MotionSafeExits {
             stand_up_front: false,
             stand: true,
             unstiff: true,
+            wake_up: false,
             walk: false,
         }
     }
@@ -86,6 +89,7 @@ impl Index<MotionType> for MotionSafeExits {
             MotionType::StandUpBack => &self.stand_up_back,
             MotionType::StandUpFront => &self.stand_up_front,
             MotionType::Unstiff => &self.unstiff,
+            MotionType::WakeUp => &self.wake_up,
             MotionType::Walk => &self.walk,
         }
     }
@@ -106,6 +110,7 @@ impl IndexMut<MotionType> for MotionSafeExits {
             MotionType::StandUpBack => &mut self.stand_up_back,
             MotionType::StandUpFront => &mut self.stand_up_front,
             MotionType::Unstiff => &mut self.unstiff,
+            MotionType::WakeUp => &mut self.wake_up,
             MotionType::Walk => &mut self.walk,
         }
     }
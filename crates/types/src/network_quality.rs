@@ -0,0 +1,22 @@
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::Players;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct NetworkQuality {
+    pub teammates: Players<Option<TeammateConnection>>,
+    pub game_controller_message_age: Option<Duration>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct TeammateConnection {
+    pub last_received: SystemTime,
+    /// `None` as long as the teammate did not send a numbered message
+    pub last_sequence_number: Option<u16>,
+    pub mean_inter_arrival_time: Option<Duration>,
+    pub packet_loss: f32,
+    pub is_reliable: bool,
+}
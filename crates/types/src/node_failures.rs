@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+/// Errors and panics of the nodes of a cycler which were contained instead of terminating it
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct NodeFailures {
+    pub nodes: Vec<NodeFailure>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub struct NodeFailure {
+    pub name: String,
+    /// Failed cycles in a row, zero if the node succeeded in its last cycle
+    pub consecutive_failures: usize,
+    pub total_failures: usize,
+    /// Error or panic message of the most recent failure
+    pub last_message: Option<String>,
+}
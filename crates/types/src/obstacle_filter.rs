@@ -1,5 +1,6 @@
 use std::time::SystemTime;
 
+use nalgebra::Vector2;
 use serde::{Deserialize, Serialize};
 
 use crate::{multivariate_normal_distribution::MultivariateNormalDistribution, ObstacleKind};
@@ -7,6 +8,8 @@ use crate::{multivariate_normal_distribution::MultivariateNormalDistribution, Ob
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Hypothesis {
     pub state: MultivariateNormalDistribution<2>,
+    /// Smoothed displacement of the mean between measurements in meters per second
+    pub velocity: Vector2<f32>,
     pub measurement_count: usize,
     pub last_update: SystemTime,
     pub obstacle_kind: ObstacleKind,
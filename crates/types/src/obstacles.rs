@@ -1,4 +1,4 @@
-use nalgebra::Point2;
+use nalgebra::{Point2, Vector2};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
@@ -6,17 +6,28 @@ use serialize_hierarchy::SerializeHierarchy;
 pub enum ObstacleKind {
     Ball,
     GoalPost,
+    /// Robot whose jersey could not be classified
     Robot,
+    Teammate,
+    Opponent,
     #[default]
     Unknown,
 }
 
+impl ObstacleKind {
+    pub fn is_robot(self) -> bool {
+        matches!(self, Self::Robot | Self::Teammate | Self::Opponent)
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub struct Obstacle {
     pub kind: ObstacleKind,
     pub position: Point2<f32>,
     pub radius_at_foot_height: f32,
     pub radius_at_hip_height: f32,
+    /// Estimated movement in ground coordinates in meters per second, zero for static obstacles
+    pub velocity: Vector2<f32>,
 }
 
 impl Obstacle {
@@ -26,6 +37,7 @@ impl Obstacle {
             position,
             radius_at_foot_height: radius,
             radius_at_hip_height: radius,
+            velocity: Vector2::zeros(),
         }
     }
 
@@ -39,6 +51,7 @@ impl Obstacle {
             position,
             radius_at_foot_height,
             radius_at_hip_height,
+            velocity: Vector2::zeros(),
         }
     }
 
@@ -48,6 +61,7 @@ impl Obstacle {
             position,
             radius_at_foot_height: radius,
             radius_at_hip_height: radius,
+            velocity: Vector2::zeros(),
         }
     }
 }
@@ -1,13 +1,18 @@
 use std::ops::{Index, Range};
-use std::{path::PathBuf, time::Duration};
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
 
 use nalgebra::{Matrix3, Point2, Point3, Vector2, Vector3, Vector4};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
+use spl_network_messages::TeamMessageProtocol;
 
 use crate::{
+    units::{Degrees, Meters, MetersPerSecond, Radians},
     ArmJoints, HeadJoints, InitialPose, KickStep, KickVariant, LegJoints, MotionCommand, Players,
-    Role, Step,
+    Role, ScanProfile, Side, Step, StepFactors,
 };
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -32,6 +37,7 @@ pub struct Localization {
     pub hypothesis_retain_factor: f32,
     pub initial_hypothesis_covariance: Matrix3<f32>,
     pub initial_hypothesis_score: f32,
+    pub initial_pose_detection: InitialPoseDetection,
     pub initial_poses: Players<InitialPose>,
     pub line_length_acceptance_factor: f32,
     pub line_measurement_noise: Vector2<f32>,
@@ -39,25 +45,84 @@ pub struct Localization {
     pub maximum_amount_of_outer_iterations: usize,
     pub minimum_fit_error: f32,
     pub odometry_noise: Vector3<f32>,
+    pub reliability: LocalizationReliability,
     pub use_line_measurements: bool,
     pub good_matching_threshold: f32,
     pub score_per_good_match: f32,
     pub hypothesis_score_base_increase: f32,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct LocalizationReliability {
+    /// Limit for every eigenvalue of the pose covariance of the best hypothesis
+    pub maximum_covariance_eigenvalue: f32,
+    pub maximum_time_since_last_measurement: Duration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct SymmetryFlipDetection {
+    /// Older own and teammate balls are not compared
+    pub maximum_ball_age: Duration,
+    /// Balls closer than this are considered the same ball
+    pub agreement_distance: f32,
+    /// Mirrored balls in a row a teammate needs to send to vote for a flip
+    pub minimum_number_of_votes: usize,
+    /// Teammates voting for a flip needed to request it, in addition to being the majority
+    pub minimum_number_of_mirrored_teammates: usize,
+    /// Votes of teammates not sending a conclusive ball for longer than this are not counted
+    pub maximum_vote_age: Duration,
+    pub cool_down: Duration,
+}
+
+/// Inputs of the world state updated longer ago than these are flagged as stale
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct WorldStateComposer {
+    pub maximum_ball_age: Duration,
+    pub maximum_localization_age: Duration,
+    pub maximum_obstacle_age: Duration,
+    pub maximum_role_age: Duration,
+    pub maximum_game_state_age: Duration,
+}
+
+/// Replaces the penalized poses with hypotheses along the touchlines of the own half when leaving
+/// penalized without GameController, e.g. after placing the robot manually during testing
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct InitialPoseDetection {
+    pub enable: bool,
+    /// Distance between neighboring hypotheses along a touchline
+    pub hypothesis_spacing: f32,
+    /// Covers the distance to the neighboring hypotheses
+    pub hypothesis_covariance: Matrix3<f32>,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct StepPlanner {
     pub injected_step: Option<Step>,
     pub max_step_size: Step,
-    pub max_step_size_backwards: f32,
+    pub max_step_size_backwards: Meters,
     pub translation_exponent: f32,
     pub rotation_exponent: f32,
     pub inside_turn_ratio: f32,
+    pub footstep_preview_length: usize,
+    pub lookahead_distance: f32,
+    pub obstacle_clearance: f32,
+    pub positioning_gait: PositioningGaitActivation,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct PositioningGaitActivation {
+    /// The positioning gait is engaged if the remaining path is shorter than this
+    pub distance_to_target: f32,
+    /// The positioning gait is engaged if the ball is closer than this
+    pub distance_to_ball: f32,
+    /// Factor applied to the maximum step size while positioning
+    pub step_size_factor: f32,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct SupportFootEstimation {
     pub hysteresis: f32,
+    pub minimal_pressure: f32,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -81,16 +146,75 @@ pub struct Behavior {
     pub role_positions: RolePositions,
     pub walk_and_stand: WalkAndStand,
     pub dribbling: Dribbling,
+    pub walk_to_ball: WalkToBall,
     pub search: Search,
     pub look_action: LookAction,
     pub intercept_ball: InterceptBall,
+    pub penalize: Penalize,
+    pub penalty_keeper: PenaltyKeeper,
+    pub kick_off_strategy: KickOffStrategy,
+    pub kick_off: KickOff,
+    pub teleoperation: Teleoperation,
     pub initial_lookaround_duration: Duration,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct Teleoperation {
+    /// Replaces the game behavior, the robot stands while no command is received
+    pub enable: bool,
+    /// Injected via the communication, see `InjectCommandRequest::Teleoperate`
+    pub command: Option<TeleoperationCommand>,
+    /// Distance of the walk target in the commanded direction
+    pub walk_target_distance: f32,
+    pub kick_strength: f32,
+}
+
+/// State of the joystick
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct TeleoperationCommand {
+    pub walk_velocity: WalkVelocity,
+    pub head: HeadJoints<f32>,
+    pub kick: Option<TeleoperationKick>,
+}
+
+/// Walk velocities relative to the maximum step size, each in the range [-1, 1]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct WalkVelocity {
+    pub forward: f32,
+    pub left: f32,
+    pub turn: f32,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct TeleoperationKick {
+    pub kick: KickVariant,
+    pub kicking_side: Side,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub enum KickOffStrategy {
+    #[default]
+    Direct,
+    Indirect,
+    /// Plays indirectly if opponents are waiting behind the center circle
+    Adaptive,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct KickOff {
+    /// Time after the start of playing in which the kick off routine takes the ball
+    pub duration: Duration,
+    /// Target of the indirect kick off in field coordinates, mirrored away from opponents
+    pub indirect_target: Point2<f32>,
+    pub indirect_strength: f32,
+    /// Opponents within this distance of the center circle block a direct kick off
+    pub opponent_detection_distance: f32,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct LookAction {
-    pub angle_threshold: f32,
-    pub distance_threshold: f32,
+    pub angle_threshold: Radians,
+    pub distance_threshold: Meters,
     pub look_forward_position: Point2<f32>,
     pub position_of_interest_switch_interval: Duration,
 }
@@ -111,15 +235,47 @@ pub struct RolePositions {
     pub striker_supporter_minimum_x: f32,
     pub keeper_x_offset: f32,
     pub striker_distance_to_non_free_center_circle: f32,
-    pub striker_set_position: Vector2<f32>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct Search {
-    pub position_reached_distance: f32,
+    pub position_reached_distance: Meters,
     pub rotation_per_step: f32,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BallSearchHeatmap {
+    pub cell_size: f32,
+    /// Time until a seen cell is considered as likely to contain the ball as an unseen one
+    pub information_recovery_duration: Duration,
+    pub field_of_view_angle: f32,
+    pub maximum_view_distance: f32,
+    pub teammate_pose_timeout: Duration,
+    /// Information given up per meter of distance when choosing the cell to search
+    pub distance_penalty: f32,
+    /// Distance a cell has to keep to the cells teammates with lower player numbers search
+    pub minimum_distance_between_searchers: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct JerseyClassification {
+    pub enable: bool,
+    /// Weight of luminance differences relative to chrominance differences
+    pub luminance_weight: f32,
+    pub maximum_color_distance: f32,
+    /// The closest team has to be closer than this fraction of the distance to the other team
+    pub maximum_distance_ratio: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct KickOffPositioning {
+    /// Positions in ready when kicking off, ordered by priority
+    pub own_kick_off_slots: Vec<Point2<f32>>,
+    /// Positions in ready when the opponent kicks off, ordered by priority
+    pub opponent_kick_off_slots: Vec<Point2<f32>>,
+    pub teammate_pose_timeout: Duration,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct InWalkKicks {
     pub forward: InWalkKickInfo,
@@ -159,18 +315,46 @@ pub struct InWalkKickInfo {
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct Dribbling {
-    pub hybrid_align_distance: f32,
-    pub distance_to_be_aligned: f32,
-    pub angle_to_approach_ball_from_threshold: f32,
-    pub ignore_robot_when_near_ball_radius: f32,
+    pub hybrid_align_distance: Meters,
+    pub distance_to_be_aligned: Meters,
+    pub angle_to_approach_ball_from_threshold: Radians,
+    pub ignore_robot_when_near_ball_radius: Meters,
+    pub path_replanning: DribblePathReplanning,
+    /// Dribbling is only started on balls the robot has perceived itself within this duration
+    pub maximum_own_ball_age: Duration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct WalkToBall {
+    /// Balls which are not trusted for dribbling are still approached above this confidence
+    pub minimum_team_ball_confidence: f32,
+    pub distance_to_ball: Meters,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct DribblePathReplanning {
+    pub maximum_target_drift: f32,
+    pub maximum_ball_drift: f32,
+    pub maximum_path_deviation: f32,
+    pub better_path_check_interval: Duration,
+    /// Fraction of the remaining path length a new path has to save to replace the current one
+    pub minimum_improvement: f32,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct WalkAndStand {
     pub hysteresis: Vector2<f32>,
     pub target_reached_thresholds: Vector2<f32>,
-    pub hybrid_align_distance: f32,
-    pub distance_to_be_aligned: f32,
+    pub hybrid_align_distance: Meters,
+    pub distance_to_be_aligned: Meters,
+    pub illegal_position_avoidance: IllegalPositionAvoidance,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct IllegalPositionAvoidance {
+    /// Distance kept to the border of illegal areas
+    pub margin: f32,
+    pub maximum_number_of_defenders_in_penalty_area: usize,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -180,11 +364,35 @@ pub struct LostBall {
 
 #[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct InterceptBall {
-    pub maximum_ball_distance: f32,
-    pub minimum_ball_velocity: f32,
-    pub minimum_ball_velocity_towards_robot: f32,
-    pub minimum_ball_velocity_towards_own_half: f32,
-    pub maximum_intercept_distance: f32,
+    pub maximum_ball_distance: Meters,
+    pub minimum_ball_velocity: MetersPerSecond,
+    pub minimum_ball_velocity_towards_robot: MetersPerSecond,
+    pub minimum_ball_velocity_towards_own_half: MetersPerSecond,
+    pub maximum_intercept_distance: Meters,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct Penalize {
+    /// Time the walking engine has to stand still before the head is zeroed
+    pub minimum_stand_duration: Duration,
+    /// Maximum absolute head joint position in radians which counts as zeroed
+    pub head_zero_tolerance: f32,
+    /// The penalized pose is assumed at the latest after being penalized for this duration
+    pub timeout: Duration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct PenaltyKeeper {
+    /// Slower balls are not considered as shots
+    pub minimum_ball_velocity: MetersPerSecond,
+    /// Time from perceiving the shot until the dive blocks the goal
+    pub reaction_time: Duration,
+    /// Lateral distance from the keeper which is blocked by squatting instead of diving
+    pub squat_reach: f32,
+    /// Shots crossing the goal line this far outside of the goal posts are still blocked
+    pub goal_post_margin: f32,
+    /// Distance from the center of the goal line within which the keeper waits for the shot
+    pub position_tolerance: f32,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -196,6 +404,14 @@ pub struct PathPlanning {
     pub minimum_robot_radius_at_foot_height: f32,
     pub robot_radius_at_foot_height: f32,
     pub robot_radius_at_hip_height: f32,
+    pub rotation_penalty_factor: f32,
+    pub inflation_radius: f32,
+    pub inflation_weight: f32,
+    pub maximum_obstacle_prediction_time: f32,
+    pub turning_speed: f32,
+    /// Arms are moved behind the back if robots are this close on both sides
+    pub arms_behind_back_radius: f32,
+    pub teammate_radius_reduction: f32,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -208,47 +424,80 @@ pub struct GameStateFilter {
     pub whistle_acceptance_goal_distance: Vector2<f32>,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ImuCalibrationRoutine {
+    pub stillness_duration: Duration,
+    pub maximum_gyro_standard_deviation: f32,
+    pub maximum_accelerometer_standard_deviation: f32,
+    /// Whether calibrations are written into the parameter file of the body
+    pub store_calibration: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct JointFaultDetection {
+    /// Minimum commanded stiffness for the joint to be expected to follow its command
+    pub minimum_stiffness: f32,
+    pub position_error_threshold: f32,
+    pub slipping_gear_maximum_current: f32,
+    pub stuck_minimum_current: f32,
+    pub fault_duration: Duration,
+    pub temperature_sample_interval: Duration,
+    /// In degrees Celsius per second
+    pub maximum_temperature_rise_rate: f32,
+    pub minimum_overheating_temperature: f32,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct WalkingEngine {
-    pub additional_kick_foot_lift: f32,
+    pub additional_kick_foot_lift: Meters,
     pub arm_stiffness: f32,
-    pub backward_foot_support_offset: f32,
-    pub base_foot_lift: f32,
+    pub backward_foot_support_offset: Meters,
+    pub base_foot_lift: Meters,
     pub base_step_duration: Duration,
-    pub emergency_foot_lift: f32,
+    pub emergency_foot_lift: Meters,
     pub emergency_step: Step,
     pub emergency_step_duration: Duration,
     pub foot_pressure_threshold: f32,
-    pub forward_foot_support_offset: f32,
+    pub forward_foot_support_offset: Meters,
     pub gyro_balance_factors: LegJoints<f32>,
     pub gyro_low_pass_factor: f32,
     pub imu_pitch_low_pass_factor: f32,
     pub inside_turn_ratio: f32,
     pub leg_stiffness_stand: f32,
     pub leg_stiffness_walk: f32,
-    pub max_forward_acceleration: f32,
+    pub max_forward_acceleration: Meters,
     pub max_leg_adjustment_velocity: LegJoints<f32>,
     pub max_number_of_timeouted_steps: usize,
     pub max_number_of_unstable_steps: usize,
-    pub max_step_adjustment: f32,
+    pub max_step_adjustment: Meters,
     pub maximal_step_duration: Duration,
     pub minimal_step_duration: Duration,
     pub number_of_stabilizing_steps: usize,
+    pub positioning_gait: PositioningGait,
     pub stabilization_foot_lift_multiplier: f32,
-    pub stabilization_foot_lift_offset: f32,
+    pub stabilization_foot_lift_offset: Meters,
     pub stabilization_hysteresis: f32,
     pub stable_step_deviation: Duration,
     pub starting_step_duration: Duration,
-    pub starting_step_foot_lift: f32,
-    pub step_duration_increase: Step,
-    pub step_foot_lift_increase: Step,
+    pub starting_step_foot_lift: Meters,
+    /// Seconds per meter and per radian of the requested step
+    pub step_duration_increase: StepFactors,
+    /// Meters per meter and per radian of the requested step
+    pub step_foot_lift_increase: StepFactors,
     pub swing_foot_imu_leveling_factor: f32,
     pub swing_foot_pitch_error_leveling_factor: f32,
     pub swinging_arms: SwingingArms,
     pub tilt_shift_low_pass_factor: f32,
-    pub torso_shift_offset: f32,
-    pub torso_tilt_offset: f32,
-    pub walk_hip_height: f32,
+    pub torso_shift_offset: Meters,
+    pub torso_tilt_offset: Radians,
+    pub walk_hip_height: Meters,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct PositioningGait {
+    /// Fraction of the step duration both feet stay on the ground
+    pub double_support_ratio: f32,
+    pub additional_step_duration: Duration,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -259,8 +508,10 @@ pub struct SwingingArms {
     pub pitch_factor: f32,
     pub pull_back_joints: ArmJoints<f32>,
     pub pull_tight_joints: ArmJoints<f32>,
+    pub behind_back_joints: ArmJoints<f32>,
     pub pulling_back_duration: Duration,
     pub pulling_tight_duration: Duration,
+    pub moving_behind_back_duration: Duration,
     pub torso_tilt_compensation_factor: f32,
 }
 
@@ -300,11 +551,38 @@ pub struct HeadMotion {
     pub inner_maximum_pitch: f32,
     pub outer_yaw: f32,
     pub maximum_velocity: HeadJoints<f32>,
+    pub scan_profiles: ScanProfiles,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ScanProfiles {
+    pub wide: ScanProfileParameters,
+    pub narrow: ScanProfileParameters,
+    pub down: ScanProfileParameters,
+}
+
+impl Index<ScanProfile> for ScanProfiles {
+    type Output = ScanProfileParameters;
+
+    fn index(&self, profile: ScanProfile) -> &Self::Output {
+        match profile {
+            ScanProfile::Wide => &self.wide,
+            ScanProfile::Narrow => &self.narrow,
+            ScanProfile::Down => &self.down,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ScanProfileParameters {
+    pub yaw_amplitude: f32,
+    pub pitch: f32,
+    /// Duration of one sweep from the left to the right and back
+    pub period: Duration,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct LookAround {
-    pub look_around_timeout: Duration,
     pub quick_search_timeout: Duration,
     pub middle_positions: HeadJoints<f32>,
     pub left_positions: HeadJoints<f32>,
@@ -321,8 +599,11 @@ pub struct WhistleFilter {
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct SonarFilter {
+    pub cross_echo_tolerance: f32,
     pub low_pass_filter_coefficient: f32,
     pub maximal_reliable_distance: f32,
+    pub median_window_size: usize,
+    pub minimal_cross_echo_jump: f32,
     pub minimal_reliable_distance: f32,
     pub maximal_detectable_distance: f32,
 }
@@ -340,6 +621,8 @@ pub struct SplNetwork {
     pub spl_striker_message_receive_timeout: Duration,
     pub spl_striker_message_send_interval: Duration,
     pub striker_trusts_team_ball: Duration,
+    pub team_message_protocol: TeamMessageProtocol,
+    pub teammate_message_timeout: Duration,
 }
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -398,6 +681,10 @@ pub struct BallDetection {
     pub image_containment_merge_factor: f32,
     pub cluster_merge_radius_factor: f32,
     pub ball_radius_enlargement_factor: f32,
+    /// Standard deviation of the detected ball center in pixels
+    pub image_position_standard_deviation: f32,
+    /// Standard deviation of the camera rotation around the axes of the ground frame in radians
+    pub camera_rotation_standard_deviation: Vector3<f32>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -426,8 +713,12 @@ pub struct BallFilter {
     pub measurement_matching_distance: f32,
     pub hypothesis_merge_distance: f32,
     pub process_noise: Vector4<f32>,
-    pub measurement_noise_moving: Vector2<f32>,
-    pub measurement_noise_resting: Vector2<f32>,
+    /// Factor scaling the covariance of ball percepts into the measurement noise of the moving
+    /// model
+    pub measurement_noise_factor_moving: f32,
+    /// Factor scaling the covariance of ball percepts into the measurement noise of the resting
+    /// model, which is larger to let it follow single percepts less
+    pub measurement_noise_factor_resting: f32,
     pub initial_covariance: Vector4<f32>,
     pub visible_validity_exponential_decay_factor: f32,
     pub hidden_validity_exponential_decay_factor: f32,
@@ -436,16 +727,61 @@ pub struct BallFilter {
     pub resting_ball_velocity_threshold: f32,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct FootBumperBallDetection {
+    pub maximum_ball_age: Duration,
+    pub maximum_ball_distance: f32,
+    /// Time the ball is assumed to stay at the feet after the bumper was pressed
+    pub hint_duration: Duration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct MotionTest {
+    /// Injected via the communication, see `InjectCommandRequest::RunMotionTest`
+    pub request: Option<MotionTestRequest>,
+    pub stand_duration: Duration,
+    pub kick_duration: Duration,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
+pub struct MotionTestRequest {
+    pub motion: TestMotion,
+    pub repetitions: usize,
+    /// Only identifies the request, it is taken from the clock of the communication server
+    pub requested_at: SystemTime,
+}
+
+/// Motions which can be exercised on the bench by the motion test harness
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
+pub enum TestMotion {
+    InWalkKick {
+        kick: KickVariant,
+        kicking_side: Side,
+        strength: f32,
+    },
+    /// Waits for the robot to be laid down before each repetition
+    StandUp,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct StandUp {
-    pub gyro_low_pass_filter_coefficient: f32,
+    /// Time constant of the gyro filter feeding the conditions of the stand up motions
+    pub gyro_time_constant: Duration,
     pub gyro_low_pass_filter_tolerance: f32,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct WakeUp {
+    /// Duration of the ramp from zero to the full stiffness while holding the current positions
+    pub stiffness_ramp_duration: Duration,
+    pub stiffness: f32,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct ButtonFilter {
     pub head_buttons_timeout: Duration,
     pub calibration_buttons_timeout: Duration,
+    pub multi_tap_timeout: Duration,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -463,6 +799,7 @@ pub struct ObstacleFilter {
     pub sonar_measurement_noise: Vector2<f32>,
     pub network_robot_measurement_noise: Vector2<f32>,
     pub initial_covariance: Vector2<f32>,
+    pub velocity_smoothing_factor: f32,
     pub measurement_count_threshold: usize,
     pub use_feet_detection_measurements: bool,
     pub use_robot_detection_measurements: bool,
@@ -492,11 +829,26 @@ pub struct PerspectiveGridCandidatesProvider {
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct CameraMatrixParameters {
-    pub extrinsic_rotations: Vector3<f32>,
+    pub extrinsic_rotations: Vector3<Degrees>,
     pub focal_lengths: Vector2<f32>,
     pub cc_optical_center: Point2<f32>,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CameraIntrinsicsCalibrationRoutine {
+    pub enable: bool,
+    /// Number of inner corners of the checkerboard along a row
+    pub checkerboard_columns: usize,
+    /// Number of inner corners of the checkerboard along a column
+    pub checkerboard_rows: usize,
+    /// Radius in pixels of the ring sampled around corner candidates
+    pub corner_detection_radius: usize,
+    pub minimum_corner_response: f32,
+    /// Mean distance in pixels the corners have to move since the last observation for a new one
+    pub minimum_corner_displacement: f32,
+    pub number_of_observations: usize,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct FallProtection {
     pub ground_impact_angular_threshold: f32,
@@ -1,9 +1,11 @@
+use std::f32::consts::TAU;
+
 use approx::{AbsDiffEq, RelativeEq};
-use nalgebra::{Isometry2, Point2};
+use nalgebra::{distance, Isometry2, Point2, UnitComplex, Vector2};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
-use super::{Arc, LineSegment, Orientation};
+use super::{Arc, Circle, LineSegment, Orientation};
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, SerializeHierarchy)]
 pub enum PathSegment {
@@ -15,6 +17,31 @@ pub fn direct_path(start: Point2<f32>, destination: Point2<f32>) -> Vec<PathSegm
     vec![PathSegment::LineSegment(LineSegment(start, destination))]
 }
 
+/// Distances along the path at which each of its segments ends
+pub fn cumulative_lengths(path: &[PathSegment]) -> impl Iterator<Item = f32> + '_ {
+    path.iter().scan(0.0, |length, segment| {
+        *length += segment.length();
+        Some(*length)
+    })
+}
+
+pub fn path_length(path: &[PathSegment]) -> f32 {
+    cumulative_lengths(path).last().unwrap_or(0.0)
+}
+
+/// Point at `distance` along the path, the end of the path if it is shorter
+pub fn point_at_distance(path: &[PathSegment], distance: f32) -> Point2<f32> {
+    let mut remaining_distance = distance;
+    for segment in path {
+        let length = segment.length();
+        if remaining_distance <= length && length > 0.0 {
+            return segment.point_at(remaining_distance / length);
+        }
+        remaining_distance -= length;
+    }
+    path.last().map_or(Point2::origin(), PathSegment::end)
+}
+
 impl AbsDiffEq for PathSegment {
     type Epsilon = f32;
 
@@ -72,6 +99,137 @@ impl PathSegment {
             PathSegment::Arc(arc, orientation) => arc.length(*orientation),
         }
     }
+
+    pub fn transform(&self, isometry: Isometry2<f32>) -> Self {
+        match self {
+            PathSegment::LineSegment(LineSegment(start, end)) => {
+                PathSegment::LineSegment(LineSegment(isometry * start, isometry * end))
+            }
+            PathSegment::Arc(arc, orientation) => PathSegment::Arc(
+                Arc::new(
+                    Circle::new(isometry * arc.circle.center, arc.circle.radius),
+                    isometry * arc.start,
+                    isometry * arc.end,
+                ),
+                *orientation,
+            ),
+        }
+    }
+
+    pub fn start(&self) -> Point2<f32> {
+        match self {
+            PathSegment::LineSegment(LineSegment(start, _)) => *start,
+            PathSegment::Arc(arc, _) => arc.start,
+        }
+    }
+
+    pub fn end(&self) -> Point2<f32> {
+        match self {
+            PathSegment::LineSegment(LineSegment(_, end)) => *end,
+            PathSegment::Arc(arc, _) => arc.end,
+        }
+    }
+
+    /// Point at `parameter` along the segment, from 0.0 at the start to 1.0 at the end
+    /// proportional to the walked length
+    pub fn point_at(&self, parameter: f32) -> Point2<f32> {
+        let parameter = parameter.clamp(0.0, 1.0);
+        if parameter >= 1.0 {
+            return self.end();
+        }
+        match self {
+            PathSegment::LineSegment(LineSegment(start, end)) => start + (end - start) * parameter,
+            PathSegment::Arc(arc, orientation) => {
+                let rotation = UnitComplex::new(arc.sweep_angle(*orientation) * parameter);
+                arc.circle.center + rotation * (arc.start - arc.circle.center)
+            }
+        }
+    }
+
+    /// Unit direction of walking at `parameter`, zero for segments without length
+    pub fn tangent_at(&self, parameter: f32) -> Vector2<f32> {
+        let direction = match self {
+            PathSegment::LineSegment(LineSegment(start, end)) => end - start,
+            PathSegment::Arc(arc, orientation) => {
+                orientation.rotate_vector_90_degrees(self.point_at(parameter) - arc.circle.center)
+            }
+        };
+        direction
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector2::zeros)
+    }
+
+    /// Closest point on the segment to `point` together with its parameter as in
+    /// [`PathSegment::point_at`]
+    pub fn closest_point(&self, point: Point2<f32>) -> (Point2<f32>, f32) {
+        let parameter = match self {
+            PathSegment::LineSegment(line_segment) => {
+                if line_segment.norm_squared() > 0.0 {
+                    line_segment.projection_factor(point).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                }
+            }
+            PathSegment::Arc(arc, orientation) => {
+                let sweep_angle = arc.sweep_angle(*orientation);
+                let angle_to_point =
+                    Arc::new(arc.circle, arc.start, point).sweep_angle(*orientation);
+                if sweep_angle != 0.0 && angle_to_point.abs() <= sweep_angle.abs() {
+                    angle_to_point / sweep_angle
+                } else if distance(&arc.start, &point) <= distance(&arc.end, &point) {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+        };
+        (self.point_at(parameter), parameter)
+    }
+
+    /// Splits the segment at `parameter` into the part before and the part after it
+    pub fn split_at(&self, parameter: f32) -> (Self, Self) {
+        let split_point = self.point_at(parameter);
+        match self {
+            PathSegment::LineSegment(LineSegment(start, end)) => (
+                PathSegment::LineSegment(LineSegment(*start, split_point)),
+                PathSegment::LineSegment(LineSegment(split_point, *end)),
+            ),
+            PathSegment::Arc(arc, orientation) => (
+                PathSegment::Arc(Arc::new(arc.circle, arc.start, split_point), *orientation),
+                PathSegment::Arc(Arc::new(arc.circle, split_point, arc.end), *orientation),
+            ),
+        }
+    }
+
+    pub fn shortest_distance_to_point(&self, point: Point2<f32>) -> f32 {
+        match self {
+            PathSegment::LineSegment(line_segment) => {
+                line_segment.shortest_distance_to_point(point)
+            }
+            PathSegment::Arc(arc, orientation) => {
+                if arc_spans_direction_to(arc, *orientation, point) {
+                    (distance(&arc.circle.center, &point) - arc.circle.radius).abs()
+                } else {
+                    distance(&arc.start, &point).min(distance(&arc.end, &point))
+                }
+            }
+        }
+    }
+}
+
+fn arc_spans_direction_to(arc: &Arc, orientation: Orientation, point: Point2<f32>) -> bool {
+    let angle_of = |point: Point2<f32>| {
+        let vector = point - arc.circle.center;
+        vector.y.atan2(vector.x)
+    };
+    let angle_start = angle_of(arc.start);
+    let angle_start_to_point = (angle_of(point) - angle_start).rem_euclid(TAU);
+    let angle_start_to_end = (angle_of(arc.end) - angle_start).rem_euclid(TAU);
+    match orientation {
+        Orientation::Counterclockwise => angle_start_to_point <= angle_start_to_end,
+        Orientation::Clockwise => angle_start_to_point >= angle_start_to_end,
+        Orientation::Colinear => false,
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, SerializeHierarchy, Deserialize)]
@@ -79,3 +237,192 @@ pub struct PlannedPath {
     pub end_pose: Isometry2<f32>,
     pub path: Option<Vec<PathSegment>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn segments() -> Vec<PathSegment> {
+        let quarter_circle = Arc::new(
+            Circle::new(point![1.0, 1.0], 1.0),
+            point![2.0, 1.0],
+            point![1.0, 2.0],
+        );
+        vec![
+            PathSegment::LineSegment(LineSegment(point![-1.0, 0.5], point![2.0, -1.0])),
+            PathSegment::Arc(quarter_circle, Orientation::Counterclockwise),
+            PathSegment::Arc(quarter_circle, Orientation::Clockwise),
+        ]
+    }
+
+    fn parameters() -> impl Iterator<Item = f32> {
+        (0..=10).map(|step| step as f32 / 10.0)
+    }
+
+    fn random_position(rng: &mut StdRng) -> Point2<f32> {
+        point![rng.gen_range(-5.0..5.0), rng.gen_range(-5.0..5.0)]
+    }
+
+    fn random_direction(rng: &mut StdRng) -> UnitComplex<f32> {
+        UnitComplex::new(rng.gen_range(-PI..PI))
+    }
+
+    fn random_segment(rng: &mut StdRng) -> PathSegment {
+        if rng.gen::<bool>() {
+            let start = random_position(rng);
+            let length: f32 = rng.gen_range(0.5..5.0);
+            let end = start + random_direction(rng) * Vector2::x() * length;
+            return PathSegment::LineSegment(LineSegment(start, end));
+        }
+        let circle = Circle::new(random_position(rng), rng.gen_range(0.5..3.0));
+        let start_direction = random_direction(rng);
+        let sweep_angle: f32 = rng.gen_range(0.5..TAU - 0.5);
+        let orientation = if rng.gen::<bool>() {
+            Orientation::Counterclockwise
+        } else {
+            Orientation::Clockwise
+        };
+        let end_direction = match orientation {
+            Orientation::Clockwise => start_direction * UnitComplex::new(-sweep_angle),
+            _ => start_direction * UnitComplex::new(sweep_angle),
+        };
+        let point_on_circle =
+            |direction: UnitComplex<f32>| circle.center + direction * Vector2::x() * circle.radius;
+        PathSegment::Arc(
+            Arc::new(
+                circle,
+                point_on_circle(start_direction),
+                point_on_circle(end_direction),
+            ),
+            orientation,
+        )
+    }
+
+    #[test]
+    fn closest_points_of_points_on_segments_are_themselves() {
+        for segment in segments() {
+            for parameter in parameters() {
+                let point = segment.point_at(parameter);
+
+                let (closest_point, closest_parameter) = segment.closest_point(point);
+
+                assert_relative_eq!(closest_point, point, epsilon = 1e-5);
+                assert_relative_eq!(closest_parameter, parameter, epsilon = 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn split_segments_keep_their_length_and_meet_at_the_split_point() {
+        for segment in segments() {
+            for parameter in parameters() {
+                let (before, after) = segment.split_at(parameter);
+
+                assert_relative_eq!(before.start(), segment.start());
+                assert_relative_eq!(before.end(), after.start());
+                assert_relative_eq!(after.end(), segment.end());
+                assert_relative_eq!(
+                    before.length() + after.length(),
+                    segment.length(),
+                    epsilon = 1e-4
+                );
+                assert_relative_eq!(
+                    before.length(),
+                    parameter * segment.length(),
+                    epsilon = 1e-4
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tangents_are_unit_vectors_along_the_segment() {
+        for segment in segments() {
+            for parameter in parameters() {
+                let tangent = segment.tangent_at(parameter);
+                if let PathSegment::Arc(arc, _) = segment {
+                    let radius = segment.point_at(parameter) - arc.circle.center;
+                    assert_relative_eq!(tangent.dot(&radius), 0.0, epsilon = 1e-5);
+                }
+                assert_relative_eq!(tangent.norm(), 1.0, epsilon = 1e-5);
+            }
+        }
+        let degenerate = PathSegment::LineSegment(LineSegment(Point2::origin(), Point2::origin()));
+        assert_eq!(degenerate.tangent_at(0.5), Vector2::zeros());
+    }
+
+    #[test]
+    fn points_at_distances_are_found_along_the_path() {
+        let path = [
+            PathSegment::LineSegment(LineSegment(point![0.0, 1.0], point![1.0, 1.0])),
+            PathSegment::Arc(
+                Arc::new(
+                    Circle::new(point![1.0, 0.0], 1.0),
+                    point![1.0, 1.0],
+                    point![2.0, 0.0],
+                ),
+                Orientation::Clockwise,
+            ),
+        ];
+
+        assert_relative_eq!(path_length(&path), 1.0 + FRAC_PI_2, epsilon = 1e-5);
+        assert_eq!(
+            cumulative_lengths(&path).collect::<Vec<_>>(),
+            vec![1.0, path_length(&path)]
+        );
+        assert_relative_eq!(point_at_distance(&path, 0.5), point![0.5, 1.0]);
+        assert_relative_eq!(
+            point_at_distance(&path, 1.0 + FRAC_PI_2 / 2.0),
+            point![1.0 + 0.5_f32.sqrt(), 0.5_f32.sqrt()],
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(point_at_distance(&path, 10.0), point![2.0, 0.0]);
+    }
+
+    #[test]
+    fn random_segments_keep_their_length_when_split() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..1000 {
+            let segment = random_segment(&mut rng);
+            let parameter = rng.gen_range(0.01..0.99);
+
+            let (before, after) = segment.split_at(parameter);
+
+            assert_relative_eq!(
+                before.length() + after.length(),
+                segment.length(),
+                epsilon = 1e-3
+            );
+        }
+    }
+
+    #[test]
+    fn random_segments_start_and_end_at_extreme_parameters() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..1000 {
+            let segment = random_segment(&mut rng);
+
+            assert_relative_eq!(segment.point_at(0.0), segment.start(), epsilon = 1e-5);
+            assert_relative_eq!(segment.point_at(1.0), segment.end(), epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    fn closest_points_of_random_points_on_random_segments_are_themselves() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..1000 {
+            let segment = random_segment(&mut rng);
+            let parameter = rng.gen_range(0.01..0.99);
+
+            let (_, closest_parameter) = segment.closest_point(segment.point_at(parameter));
+
+            assert_relative_eq!(closest_parameter, parameter, epsilon = 1e-3);
+        }
+    }
+}
@@ -15,3 +15,10 @@ pub enum PrimaryState {
     Finished,
     Calibration,
 }
+
+/// An edge of the primary state machine, present only in the cycle the state changed
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub struct PrimaryStateTransition {
+    pub from: PrimaryState,
+    pub to: PrimaryState,
+}
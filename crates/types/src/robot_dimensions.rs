@@ -1,4 +1,4 @@
-use nalgebra::{vector, Vector3};
+use nalgebra::{vector, Vector2, Vector3};
 
 #[derive(Debug)]
 pub struct RobotDimensions {}
@@ -18,4 +18,18 @@ impl RobotDimensions {
     pub const ELBOW_TO_WRIST: Vector3<f32> = vector![0.05595, 0.0, 0.0];
     pub const NECK_TO_TOP_CAMERA: Vector3<f32> = vector![0.05871, 0.0, 0.06364];
     pub const NECK_TO_BOTTOM_CAMERA: Vector3<f32> = vector![0.05071, 0.0, 0.01774];
+    /// force sensitive resistor positions in the left sole: front left, front right, rear left, rear right
+    pub const LEFT_SOLE_TO_FORCE_SENSITIVE_RESISTORS: [Vector2<f32>; 4] = [
+        vector![0.07025, 0.0299],
+        vector![0.07025, -0.0231],
+        vector![-0.03025, 0.0299],
+        vector![-0.02965, -0.0191],
+    ];
+    /// force sensitive resistor positions in the right sole: front left, front right, rear left, rear right
+    pub const RIGHT_SOLE_TO_FORCE_SENSITIVE_RESISTORS: [Vector2<f32>; 4] = [
+        vector![0.07025, 0.0231],
+        vector![0.07025, -0.0299],
+        vector![-0.03025, 0.0191],
+        vector![-0.02965, -0.0299],
+    ];
 }
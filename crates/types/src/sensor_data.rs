@@ -56,6 +56,14 @@ pub struct TouchSensors {
     pub right_hand_right: bool,
 }
 
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct Battery {
+    pub charge: f32,
+    pub status: f32,
+    pub current: f32,
+    pub temperature: f32,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct SensorData {
     pub positions: Joints<f32>,
@@ -64,4 +72,6 @@ pub struct SensorData {
     pub force_sensitive_resistors: ForceSensitiveResistors,
     pub touch_sensors: TouchSensors,
     pub temperature_sensors: Joints<f32>,
+    pub currents: Joints<f32>,
+    pub battery: Option<Battery>,
 }
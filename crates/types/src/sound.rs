@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Sound cues played from the sounds directory
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Sound {
+    Ball,
+    Donk,
+    Drift,
+    Ouch,
+}
+
+impl Sound {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Sound::Ball => "ball.ogg",
+            Sound::Donk => "donk.ogg",
+            Sound::Drift => "drift.ogg",
+            Sound::Ouch => "ouch.ogg",
+        }
+    }
+}
@@ -1,21 +1,23 @@
-use std::ops::{Mul, Sub};
+use std::ops::Sub;
 
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
+use crate::units::{Meters, Radians};
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy, Default)]
 pub struct Step {
-    pub forward: f32,
-    pub left: f32,
-    pub turn: f32,
+    pub forward: Meters,
+    pub left: Meters,
+    pub turn: Radians,
 }
 
 impl Step {
     pub fn zero() -> Self {
         Self {
-            forward: 0.0,
-            left: 0.0,
-            turn: 0.0,
+            forward: Meters(0.0),
+            left: Meters(0.0),
+            turn: Radians(0.0),
         }
     }
 
@@ -26,10 +28,6 @@ impl Step {
             turn: -self.turn,
         }
     }
-
-    pub fn sum(&self) -> f32 {
-        self.forward + self.left + self.turn
-    }
 }
 
 impl Sub<Step> for Step {
@@ -44,14 +42,47 @@ impl Sub<Step> for Step {
     }
 }
 
-impl Mul<Step> for Step {
-    type Output = Step;
+/// Factors per meter and per radian of the step components, e.g. seconds of additional step
+/// duration
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy, Default)]
+pub struct StepFactors {
+    pub forward: f32,
+    pub left: f32,
+    pub turn: f32,
+}
 
-    fn mul(self, rhs: Step) -> Self::Output {
-        Step {
-            forward: self.forward * rhs.forward,
-            left: self.left * rhs.left,
-            turn: self.turn * rhs.turn,
-        }
+impl StepFactors {
+    /// Sum of the absolute step components weighted with these factors
+    pub fn weighted_sum(&self, step: Step) -> f32 {
+        self.forward * step.forward.abs().0
+            + self.left * step.left.abs().0
+            + self.turn * step.turn.abs().0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn weighted_sum_ignores_step_direction() {
+        let factors = StepFactors {
+            forward: 1.0,
+            left: 2.0,
+            turn: 0.5,
+        };
+        let step = Step {
+            forward: Meters(0.05),
+            left: Meters(-0.02),
+            turn: Radians(-0.4),
+        };
+
+        assert_eq!(
+            factors.weighted_sum(step),
+            factors.weighted_sum(step.mirrored())
+        );
+        assert_relative_eq!(factors.weighted_sum(step), 0.29, epsilon = 1e-6);
     }
 }
@@ -1,3 +1,4 @@
+use nalgebra::Point2;
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
@@ -26,4 +27,5 @@ impl Default for Side {
 pub struct SupportFoot {
     pub support_side: Option<Side>,
     pub changed_this_cycle: bool,
+    pub center_of_pressure: Option<Point2<f32>>,
 }
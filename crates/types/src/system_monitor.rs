@@ -0,0 +1,19 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum SystemWarning {
+    LowBattery,
+    CriticalBattery,
+    HighJointTemperature,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct SystemMonitorSample {
+    pub timestamp: SystemTime,
+    pub battery_charge: Option<f32>,
+    pub battery_current: Option<f32>,
+    pub maximum_joint_temperature: f32,
+}
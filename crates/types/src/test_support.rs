@@ -0,0 +1,351 @@
+//! Builders for world states as seen by behavior, meant for unit tests of nodes and actions
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use nalgebra::{Point2, Vector2};
+use spl_network_messages::{
+    GamePhase, GameState, Penalty, PlayerNumber, SubState, Team, TeamColor,
+};
+
+use crate::{
+    coordinate_systems::{Field, Ground, Point, Transform},
+    localization::LocalizationQuality,
+    BallSource, BallState, FallState, FilteredGameState, GameControllerFreshness,
+    GameControllerState, Obstacle, PenaltyShotDirection, Players, PrimaryState, Role, RuleObstacle,
+    Side, SystemWarning, TeamColors, WorldState,
+};
+
+/// Builds a [`WorldState`] of a localized, upright robot at the field origin
+#[derive(Clone, Debug)]
+pub struct WorldStateBuilder {
+    world_state: WorldState,
+}
+
+impl Default for WorldStateBuilder {
+    fn default() -> Self {
+        let mut world_state = WorldState::default();
+        world_state.robot.robot_to_field = Some(Transform::identity());
+        world_state.robot.has_ground_contact = true;
+        Self { world_state }
+    }
+}
+
+impl WorldStateBuilder {
+    pub fn with_ball(mut self, ball: BallState) -> Self {
+        self.world_state.ball = Some(ball);
+        self
+    }
+
+    pub fn with_rule_ball(mut self, rule_ball: BallState) -> Self {
+        self.world_state.rule_ball = Some(rule_ball);
+        self
+    }
+
+    pub fn with_filtered_game_state(mut self, filtered_game_state: FilteredGameState) -> Self {
+        self.world_state.filtered_game_state = Some(filtered_game_state);
+        self
+    }
+
+    pub fn with_game_controller_state(
+        mut self,
+        game_controller_state: GameControllerState,
+    ) -> Self {
+        self.world_state.game_controller_state = Some(game_controller_state);
+        self
+    }
+
+    pub fn with_obstacles(mut self, obstacles: Vec<Obstacle>) -> Self {
+        self.world_state.obstacles = obstacles;
+        self
+    }
+
+    pub fn with_rule_obstacles(mut self, rule_obstacles: Vec<RuleObstacle>) -> Self {
+        self.world_state.rule_obstacles = rule_obstacles;
+        self
+    }
+
+    pub fn with_position_of_interest(mut self, position_of_interest: Point2<f32>) -> Self {
+        self.world_state.position_of_interest = position_of_interest;
+        self
+    }
+
+    pub fn with_robot_to_field(mut self, robot_to_field: Transform<Ground, Field>) -> Self {
+        self.world_state.robot.robot_to_field = Some(robot_to_field);
+        self
+    }
+
+    /// The robot is not localized, e.g. after being picked up
+    pub fn without_robot_to_field(mut self) -> Self {
+        self.world_state.robot.robot_to_field = None;
+        self
+    }
+
+    pub fn with_localization_quality(mut self, localization_quality: LocalizationQuality) -> Self {
+        self.world_state.robot.localization_quality = Some(localization_quality);
+        self
+    }
+
+    pub fn with_role(mut self, role: Role) -> Self {
+        self.world_state.robot.role = role;
+        self
+    }
+
+    pub fn with_primary_state(mut self, primary_state: PrimaryState) -> Self {
+        self.world_state.robot.primary_state = primary_state;
+        self
+    }
+
+    pub fn with_fall_state(mut self, fall_state: FallState) -> Self {
+        self.world_state.robot.fall_state = fall_state;
+        self
+    }
+
+    pub fn with_ground_contact(mut self, has_ground_contact: bool) -> Self {
+        self.world_state.robot.has_ground_contact = has_ground_contact;
+        self
+    }
+
+    pub fn with_player_number(mut self, player_number: PlayerNumber) -> Self {
+        self.world_state.robot.player_number = player_number;
+        self
+    }
+
+    pub fn with_system_warning(mut self, system_warning: SystemWarning) -> Self {
+        self.world_state.robot.system_warnings.push(system_warning);
+        self
+    }
+
+    pub fn build(self) -> WorldState {
+        self.world_state
+    }
+}
+
+/// Builds a resting [`BallState`] whose ground and field positions agree with `robot_to_field`
+#[derive(Clone, Debug)]
+pub struct BallStateBuilder {
+    robot_to_field: Transform<Ground, Field>,
+    ball: BallState,
+}
+
+impl Default for BallStateBuilder {
+    fn default() -> Self {
+        Self::new(Transform::identity())
+    }
+}
+
+impl BallStateBuilder {
+    /// Starts with the ball at the center of the field
+    pub fn new(robot_to_field: Transform<Ground, Field>) -> Self {
+        Self {
+            robot_to_field,
+            ball: BallState::new_at_center(robot_to_field),
+        }
+    }
+
+    pub fn in_field(mut self, ball_in_field: Point2<f32>) -> Self {
+        self.ball.ball_in_field = Point::wrap(ball_in_field);
+        self.ball.ball_in_ground = self.robot_to_field.inverse() * self.ball.ball_in_field;
+        self
+    }
+
+    pub fn in_ground(mut self, ball_in_ground: Point2<f32>) -> Self {
+        self.ball.ball_in_ground = Point::wrap(ball_in_ground);
+        self.ball.ball_in_field = self.robot_to_field * self.ball.ball_in_ground;
+        self
+    }
+
+    pub fn with_velocity_in_ground(mut self, ball_in_ground_velocity: Vector2<f32>) -> Self {
+        self.ball.ball_in_ground_velocity = ball_in_ground_velocity;
+        self
+    }
+
+    pub fn with_last_seen(mut self, last_seen: SystemTime) -> Self {
+        self.ball.last_seen = last_seen;
+        self
+    }
+
+    pub fn with_confidence(mut self, confidence: f32) -> Self {
+        self.ball.confidence = confidence;
+        self
+    }
+
+    pub fn with_source(mut self, source: BallSource) -> Self {
+        self.ball.source = source;
+        self
+    }
+
+    pub fn with_penalty_shot_direction(
+        mut self,
+        penalty_shot_direction: PenaltyShotDirection,
+    ) -> Self {
+        self.ball.penalty_shot_direction = Some(penalty_shot_direction);
+        self
+    }
+
+    pub fn with_field_side(mut self, field_side: Side) -> Self {
+        self.ball.field_side = field_side;
+        self
+    }
+
+    pub fn build(self) -> BallState {
+        self.ball
+    }
+}
+
+/// Builds a [`GameControllerState`] of a normal game in playing with us kicking off
+#[derive(Clone, Debug)]
+pub struct GameControllerStateBuilder {
+    game_controller_state: GameControllerState,
+}
+
+impl Default for GameControllerStateBuilder {
+    fn default() -> Self {
+        Self {
+            game_controller_state: GameControllerState {
+                game_state: GameState::Playing,
+                game_phase: GamePhase::Normal,
+                kicking_team: Team::Hulks,
+                last_game_state_change: UNIX_EPOCH,
+                penalties: Default::default(),
+                remaining_amount_of_messages: 1200,
+                sub_state: None,
+                hulks_team_is_home_after_coin_toss: true,
+                freshness: GameControllerFreshness::Fresh,
+                hulks_team_colors: TeamColors {
+                    field_player: TeamColor::Blue,
+                    goal_keeper: TeamColor::Yellow,
+                },
+                opponent_team_colors: TeamColors {
+                    field_player: TeamColor::Red,
+                    goal_keeper: TeamColor::Black,
+                },
+            },
+        }
+    }
+}
+
+impl GameControllerStateBuilder {
+    pub fn with_game_state(mut self, game_state: GameState) -> Self {
+        self.game_controller_state.game_state = game_state;
+        self
+    }
+
+    pub fn with_game_phase(mut self, game_phase: GamePhase) -> Self {
+        self.game_controller_state.game_phase = game_phase;
+        self
+    }
+
+    pub fn with_kicking_team(mut self, kicking_team: Team) -> Self {
+        self.game_controller_state.kicking_team = kicking_team;
+        self
+    }
+
+    pub fn with_last_game_state_change(mut self, last_game_state_change: SystemTime) -> Self {
+        self.game_controller_state.last_game_state_change = last_game_state_change;
+        self
+    }
+
+    pub fn with_penalties(mut self, penalties: Players<Option<Penalty>>) -> Self {
+        self.game_controller_state.penalties = penalties;
+        self
+    }
+
+    pub fn with_sub_state(mut self, sub_state: SubState) -> Self {
+        self.game_controller_state.sub_state = Some(sub_state);
+        self
+    }
+
+    pub fn build(self) -> GameControllerState {
+        self.game_controller_state
+    }
+}
+
+/// Builds a [`FilteredGameState`], the variant is chosen by the finishing method
+#[derive(Clone, Copy, Debug)]
+pub struct FilteredGameStateBuilder {
+    kicking_team: Team,
+    ball_is_free: bool,
+}
+
+impl Default for FilteredGameStateBuilder {
+    fn default() -> Self {
+        Self {
+            kicking_team: Team::Hulks,
+            ball_is_free: true,
+        }
+    }
+}
+
+impl FilteredGameStateBuilder {
+    pub fn with_kicking_team(mut self, kicking_team: Team) -> Self {
+        self.kicking_team = kicking_team;
+        self
+    }
+
+    pub fn with_ball_is_free(mut self, ball_is_free: bool) -> Self {
+        self.ball_is_free = ball_is_free;
+        self
+    }
+
+    pub fn initial(self) -> FilteredGameState {
+        FilteredGameState::Initial
+    }
+
+    pub fn ready(self) -> FilteredGameState {
+        FilteredGameState::Ready {
+            kicking_team: self.kicking_team,
+        }
+    }
+
+    pub fn set(self) -> FilteredGameState {
+        FilteredGameState::Set
+    }
+
+    pub fn playing(self) -> FilteredGameState {
+        FilteredGameState::Playing {
+            ball_is_free: self.ball_is_free,
+        }
+    }
+
+    pub fn finished(self) -> FilteredGameState {
+        FilteredGameState::Finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{point, vector, Isometry2};
+
+    use super::*;
+
+    #[test]
+    fn ball_positions_agree_with_robot_pose() {
+        let robot_to_field = Transform::wrap(Isometry2::new(vector![1.0, 0.0], 0.0));
+
+        let ball = BallStateBuilder::new(robot_to_field)
+            .in_field(point![2.0, 1.0])
+            .build();
+
+        assert_eq!(ball.ball_in_ground.inner, point![1.0, 1.0]);
+        assert_eq!(
+            BallStateBuilder::new(robot_to_field)
+                .in_ground(point![1.0, 1.0])
+                .build()
+                .ball_in_field
+                .inner,
+            point![2.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn default_world_state_is_localized_and_upright() {
+        let world_state = WorldStateBuilder::default().build();
+
+        assert_eq!(
+            world_state.robot.robot_to_field,
+            Some(Transform::identity())
+        );
+        assert!(matches!(world_state.robot.fall_state, FallState::Upright));
+        assert!(world_state.ball.is_none());
+    }
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+/// Execution durations of a cycler over the most recent cycles
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct Timings {
+    pub cycle: TimingStatistics,
+    pub nodes: Vec<NodeTiming>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct NodeTiming {
+    pub name: String,
+    pub statistics: TimingStatistics,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
+pub struct TimingStatistics {
+    pub minimum: Duration,
+    pub mean: Duration,
+    pub maximum: Duration,
+}
+
+/// Cycles exceeding the period of the target frequency of a cycler
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CycleOverruns {
+    /// `None` if the cycler has no target frequency
+    pub budget: Option<Duration>,
+    pub total_overruns: usize,
+    /// Duration of the most recent cycle exceeding the budget
+    pub last_overrun: Option<Duration>,
+    /// Nodes skipped in the most recent cycle to catch up with the budget
+    pub skipped_nodes: Vec<String>,
+}
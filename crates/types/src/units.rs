@@ -0,0 +1,251 @@
+//! Wrappers of `f32` for physical quantities
+//!
+//! The wrappers serialize like plain numbers. They make the unit of a value part of its type,
+//! such that mixing e.g. degrees and radians or millimeters and meters fails to compile.
+//! Conversions between units of the same quantity are implemented via [`From`].
+
+use std::{
+    collections::BTreeSet,
+    f32::consts::PI,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+    time::{Duration, TryFromFloatSecsError},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serialize_hierarchy::{Error, SerializeHierarchy};
+
+macro_rules! quantity {
+    ($(#[$attribute:meta])* $name:ident) => {
+        $(#[$attribute])*
+        #[derive(
+            Clone, Copy, Debug, Default, PartialEq, PartialOrd, Serialize, Deserialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(pub f32);
+
+        impl $name {
+            pub fn abs(self) -> Self {
+                Self(self.0.abs())
+            }
+
+            pub fn clamp(self, minimum: Self, maximum: Self) -> Self {
+                Self(self.0.clamp(minimum.0, maximum.0))
+            }
+
+            pub fn min(self, other: Self) -> Self {
+                Self(self.0.min(other.0))
+            }
+
+            pub fn max(self, other: Self) -> Self {
+                Self(self.0.max(other.0))
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, right: Self) -> Self::Output {
+                Self(self.0 + right.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, right: Self) {
+                self.0 += right.0;
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, right: Self) -> Self::Output {
+                Self(self.0 - right.0)
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, right: Self) {
+                self.0 -= right.0;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self(-self.0)
+            }
+        }
+
+        impl Mul<f32> for $name {
+            type Output = Self;
+
+            fn mul(self, factor: f32) -> Self::Output {
+                Self(self.0 * factor)
+            }
+        }
+
+        impl Div<f32> for $name {
+            type Output = Self;
+
+            fn div(self, divisor: f32) -> Self::Output {
+                Self(self.0 / divisor)
+            }
+        }
+
+        /// The ratio of two values of the same unit has no unit
+        impl Div for $name {
+            type Output = f32;
+
+            fn div(self, divisor: Self) -> Self::Output {
+                self.0 / divisor.0
+            }
+        }
+
+        /// Behaves like the wrapped `f32`, i.e. the value itself is the leaf of the hierarchy
+        impl SerializeHierarchy for $name {
+            fn serialize_path<S>(
+                &self,
+                path: &str,
+                serializer: S,
+            ) -> Result<S::Ok, Error<S::Error>>
+            where
+                S: Serializer,
+            {
+                self.0.serialize_path(path, serializer)
+            }
+
+            fn deserialize_path<'de, D>(
+                &mut self,
+                path: &str,
+                deserializer: D,
+            ) -> Result<(), Error<D::Error>>
+            where
+                D: Deserializer<'de>,
+            {
+                self.0.deserialize_path(path, deserializer)
+            }
+
+            fn exists(path: &str) -> bool {
+                f32::exists(path)
+            }
+
+            fn get_fields() -> BTreeSet<String> {
+                f32::get_fields()
+            }
+        }
+    };
+}
+
+quantity!(Meters);
+quantity!(
+    /// Lengths in the standard messages of the SPL are in millimeters, all others in meters
+    Millimeters
+);
+quantity!(Radians);
+quantity!(
+    /// Used in hand-written parameters like camera extrinsics, computations use radians
+    Degrees
+);
+quantity!(Seconds);
+quantity!(MetersPerSecond);
+
+impl From<Millimeters> for Meters {
+    fn from(millimeters: Millimeters) -> Self {
+        Self(millimeters.0 / 1000.0)
+    }
+}
+
+impl From<Meters> for Millimeters {
+    fn from(meters: Meters) -> Self {
+        Self(meters.0 * 1000.0)
+    }
+}
+
+impl From<Degrees> for Radians {
+    fn from(degrees: Degrees) -> Self {
+        Self(degrees.0 * PI / 180.0)
+    }
+}
+
+impl From<Radians> for Degrees {
+    fn from(radians: Radians) -> Self {
+        Self(radians.0 * 180.0 / PI)
+    }
+}
+
+impl From<Duration> for Seconds {
+    fn from(duration: Duration) -> Self {
+        Self(duration.as_secs_f32())
+    }
+}
+
+impl TryFrom<Seconds> for Duration {
+    type Error = TryFromFloatSecsError;
+
+    fn try_from(seconds: Seconds) -> Result<Self, Self::Error> {
+        Duration::try_from_secs_f32(seconds.0)
+    }
+}
+
+impl Div<Seconds> for Meters {
+    type Output = MetersPerSecond;
+
+    fn div(self, duration: Seconds) -> Self::Output {
+        MetersPerSecond(self.0 / duration.0)
+    }
+}
+
+impl Mul<Seconds> for MetersPerSecond {
+    type Output = Meters;
+
+    fn mul(self, duration: Seconds) -> Self::Output {
+        Meters(self.0 * duration.0)
+    }
+}
+
+impl Div<MetersPerSecond> for Meters {
+    type Output = Seconds;
+
+    fn div(self, velocity: MetersPerSecond) -> Self::Output {
+        Seconds(self.0 / velocity.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn units_of_the_same_quantity_are_converted() {
+        assert_eq!(Meters::from(Millimeters(1500.0)), Meters(1.5));
+        assert_eq!(Millimeters::from(Meters(0.25)), Millimeters(250.0));
+        assert_relative_eq!(Radians::from(Degrees(90.0)).0, PI / 2.0);
+        assert_relative_eq!(Degrees::from(Radians(PI)).0, 180.0);
+        assert_eq!(Seconds::from(Duration::from_millis(500)), Seconds(0.5));
+        assert_eq!(
+            Duration::try_from(Seconds(0.5)),
+            Ok(Duration::from_millis(500))
+        );
+        assert!(Duration::try_from(Seconds(-1.0)).is_err());
+        assert!(Duration::try_from(Seconds(f32::NAN)).is_err());
+    }
+
+    #[test]
+    fn velocities_relate_distances_and_durations() {
+        let velocity = Meters(3.0) / Seconds(2.0);
+
+        assert_eq!(velocity, MetersPerSecond(1.5));
+        assert_eq!(velocity * Seconds(4.0), Meters(6.0));
+        assert_eq!(Meters(3.0) / velocity, Seconds(2.0));
+        assert_eq!(Meters(3.0) / Meters(1.5), 2.0);
+    }
+
+    #[test]
+    fn quantities_serialize_like_numbers() {
+        assert_eq!(serde_json::to_string(&Degrees(1.5)).unwrap(), "1.5");
+        assert_eq!(serde_json::from_str::<Meters>("0.5").unwrap(), Meters(0.5));
+    }
+}
@@ -12,3 +12,11 @@ pub enum WalkCommand {
     Walk(Step),
     Kick(KickVariant, Side, Strength),
 }
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Serialize, Deserialize, SerializeHierarchy)]
+pub enum WalkGait {
+    #[default]
+    Normal,
+    /// Slow and precise small steps with long double support phases, e.g. to align for a kick
+    Positioning,
+}
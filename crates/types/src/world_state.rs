@@ -1,21 +1,26 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use nalgebra::{Isometry2, Point2, Vector2};
+use nalgebra::{Point2, Vector2};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
-use spl_network_messages::PlayerNumber;
+use spl_network_messages::{Intention, PlayerNumber};
 
-use crate::{GameControllerState, KickDecision};
+use crate::{
+    coordinate_systems::{Field, Ground, Point, Pose, Transform},
+    localization::LocalizationQuality,
+    BlindBallContact, GameControllerState, KickDecision, Players,
+};
 
 use crate::PenaltyShotDirection;
 use crate::RuleObstacle;
 
-use super::{FallState, FilteredGameState, Obstacle, PrimaryState, Role, Side};
+use super::{FallState, FilteredGameState, Obstacle, PrimaryState, Role, Side, SystemWarning};
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
 pub struct WorldState {
     pub ball: Option<BallState>,
     pub rule_ball: Option<BallState>,
+    pub blind_ball_contact: Option<BlindBallContact>,
     pub filtered_game_state: Option<FilteredGameState>,
     pub game_controller_state: Option<GameControllerState>,
     pub obstacles: Vec<Obstacle>,
@@ -24,37 +29,98 @@ pub struct WorldState {
     pub kick_decisions: Option<Vec<KickDecision>>,
     pub instant_kick_decisions: Option<Vec<KickDecision>>,
     pub robot: RobotState,
+    pub teammate_intentions: Players<Option<Intention>>,
+    pub teammate_poses: Players<Option<Pose<Field>>>,
+    pub provenance: WorldStateProvenance,
+}
+
+/// When the inputs of the world state were last updated
+///
+/// Lets behavior and debugging tools distinguish fresh inputs from ones kept from earlier cycles.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
+pub struct WorldStateProvenance {
+    /// Based on when the ball was last seen by the robot itself or a teammate
+    pub ball: InputProvenance,
+    /// Based on the last field mark measurement of the localization
+    pub robot_to_field: InputProvenance,
+    /// Based on the latest vision measurement the obstacle filter was updated with
+    pub obstacles: InputProvenance,
+    /// Based on the last message the role assignment received or sent
+    pub role: InputProvenance,
+    /// Based on the last fresh message of the GameController or the last button press without it
+    pub filtered_game_state: InputProvenance,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
+pub struct InputProvenance {
+    /// `None` if the input was never available
+    pub last_updated: Option<SystemTime>,
+    /// Whether the last update was longer ago than tolerated when composing the world state
+    pub is_stale: bool,
+}
+
+impl InputProvenance {
+    pub fn new(last_updated: Option<SystemTime>, now: SystemTime, maximum_age: Duration) -> Self {
+        let is_stale = last_updated.map_or(true, |last_updated| {
+            now.duration_since(last_updated).unwrap_or_default() > maximum_age
+        });
+        Self {
+            last_updated,
+            is_stale,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy)]
 pub struct BallState {
-    pub ball_in_ground: Point2<f32>,
-    pub ball_in_field: Point2<f32>,
+    pub ball_in_ground: Point<Ground>,
+    pub ball_in_field: Point<Field>,
     pub ball_in_ground_velocity: Vector2<f32>,
-    pub last_seen_ball: SystemTime,
+    pub last_seen: SystemTime,
+    /// How much the ball is trusted, between 0.0 and 1.0
+    pub confidence: f32,
+    pub source: BallSource,
     pub penalty_shot_direction: Option<PenaltyShotDirection>,
     pub field_side: Side,
 }
 
 impl BallState {
-    pub fn new_at_center(robot_to_field: Isometry2<f32>) -> Self {
+    pub fn new_at_center(robot_to_field: Transform<Ground, Field>) -> Self {
         Self {
-            ball_in_field: Point2::origin(),
-            ball_in_ground: robot_to_field.inverse() * Point2::origin(),
+            ball_in_field: Point::origin(),
+            ball_in_ground: robot_to_field.inverse() * Point::origin(),
             ball_in_ground_velocity: Vector2::zeros(),
-            last_seen_ball: UNIX_EPOCH,
+            last_seen: UNIX_EPOCH,
+            confidence: 1.0,
+            source: BallSource::Own,
             penalty_shot_direction: Default::default(),
             field_side: Side::Left,
         }
     }
+
+    pub fn age(&self, now: SystemTime) -> Duration {
+        now.duration_since(self.last_seen).unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, SerializeHierarchy)]
+pub enum BallSource {
+    /// Perceived by the own ball filter
+    Own,
+    /// Communicated by a teammate
+    Team,
+    /// Placed by the rules, e.g. the center circle in ready
+    Rules,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
 pub struct RobotState {
-    pub robot_to_field: Option<Isometry2<f32>>,
+    pub robot_to_field: Option<Transform<Ground, Field>>,
+    pub localization_quality: Option<LocalizationQuality>,
     pub role: Role,
     pub primary_state: PrimaryState,
     pub fall_state: FallState,
     pub has_ground_contact: bool,
     pub player_number: PlayerNumber,
+    pub system_warnings: Vec<SystemWarning>,
 }
@@ -1,3 +1,15 @@
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::{
+    int16x8_t, uint8x16x3_t, uint8x8_t, vaddq_s16, vcombine_u8, vdupq_n_s16, vld2q_u8, vld4_u8,
+    vmlaq_n_s16, vmlsq_n_s16, vmovl_u8, vqshrun_n_s16, vreinterpretq_s16_u16, vshll_n_u8, vst1q_u8,
+    vst3q_u8, vsubq_s16, vzip_u8,
+};
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::{
+    __m128i, _mm_add_epi16, _mm_and_si128, _mm_loadu_si128, _mm_mullo_epi16, _mm_or_si128,
+    _mm_packus_epi16, _mm_set1_epi16, _mm_setr_epi8, _mm_setzero_si128, _mm_shuffle_epi8,
+    _mm_slli_epi16, _mm_srai_epi16, _mm_storel_epi64, _mm_storeu_si128, _mm_sub_epi16,
+};
 use std::{
     fmt::Debug,
     mem::{size_of, ManuallyDrop},
@@ -30,7 +42,7 @@ impl EncodeJpeg for YCbCr422Image {
     type Error = ImageError;
 
     fn encode_as_jpeg(&self, quality: u8) -> Result<Vec<u8>, Self::Error> {
-        let rgb_image = rgb_image_from_buffer_422(self.width_422, self.height, &self.buffer);
+        let rgb_image = self.to_rgb_image();
         let mut jpeg_buffer = vec![];
         let mut encoder = JpegEncoder::new_with_quality(&mut jpeg_buffer, quality);
         encoder.encode_image(&rgb_image)?;
@@ -146,7 +158,7 @@ impl YCbCr422Image {
     }
 
     pub fn save_to_rgb_file(&self, file: impl AsRef<Path> + Debug) -> eyre::Result<()> {
-        rgb_image_from_buffer_422(self.width_422, self.height, &self.buffer)
+        self.to_rgb_image()
             .save(&file)
             .wrap_err_with(|| format!("failed to save image to {file:?}"))
     }
@@ -174,6 +186,41 @@ impl YCbCr422Image {
         }
     }
 
+    /// Luminance of all pixels in row-major order
+    pub fn luminance(&self) -> Vec<u8> {
+        let mut luminance = vec![0; 2 * self.buffer.len()];
+        ycbcr_422_to_luminance(&self.buffer, &mut luminance);
+        luminance
+    }
+
+    pub fn to_rgb_image(&self) -> RgbImage {
+        let mut rgb_buffer = vec![0; 6 * self.buffer.len()];
+        ycbcr_422_to_rgb_444(&self.buffer, &mut rgb_buffer);
+        RgbImage::from_raw(self.width(), self.height, rgb_buffer)
+            .expect("buffer should match image size")
+    }
+
+    /// Halves width and height by averaging horizontally adjacent pixels of every second row
+    pub fn downscaled_by_half(&self) -> Self {
+        let width_422 = self.width_422 / 2;
+        let height = self.height / 2;
+        let average = |first: u8, second: u8| ((first as u16 + second as u16) / 2) as u8;
+        let buffer = (0..height)
+            .flat_map(|y| {
+                let row_start = (2 * y * self.width_422) as usize;
+                self.buffer[row_start..row_start + (2 * width_422) as usize]
+                    .chunks_exact(2)
+                    .map(|pixels| YCbCr422 {
+                        y1: average(pixels[0].y1, pixels[0].y2),
+                        cb: average(pixels[0].cb, pixels[1].cb),
+                        y2: average(pixels[1].y1, pixels[1].y2),
+                        cr: average(pixels[0].cr, pixels[1].cr),
+                    })
+            })
+            .collect();
+        Self::from_ycbcr_buffer(width_422, height, buffer)
+    }
+
     pub fn try_at(&self, x: u32, y: u32) -> Option<YCbCr444> {
         if x >= self.width() || y >= self.height() {
             return None;
@@ -197,40 +244,6 @@ impl Index<Point2<usize>> for YCbCr422Image {
     }
 }
 
-fn rgb_image_from_buffer_422(width_422: u32, height: u32, buffer: &[YCbCr422]) -> RgbImage {
-    let mut rgb_image = RgbImage::new(2 * width_422, height);
-
-    for y in 0..height {
-        for x in 0..width_422 {
-            let pixel = buffer[(y * width_422 + x) as usize];
-            let left_color: Rgb = YCbCr444 {
-                y: pixel.y1,
-                cb: pixel.cb,
-                cr: pixel.cr,
-            }
-            .into();
-            let right_color: Rgb = YCbCr444 {
-                y: pixel.y2,
-                cb: pixel.cb,
-                cr: pixel.cr,
-            }
-            .into();
-            rgb_image.put_pixel(
-                x * 2,
-                y,
-                image::Rgb([left_color.r, left_color.g, left_color.b]),
-            );
-            rgb_image.put_pixel(
-                x * 2 + 1,
-                y,
-                image::Rgb([right_color.r, right_color.g, right_color.b]),
-            );
-        }
-    }
-
-    rgb_image
-}
-
 fn buffer_422_from_rgb_image(rgb_image: RgbImage) -> Vec<YCbCr422> {
     rgb_image
         .into_vec()
@@ -252,3 +265,344 @@ fn buffer_422_from_rgb_image(rgb_image: RgbImage) -> Vec<YCbCr422> {
         })
         .collect()
 }
+
+fn ycbcr_422_to_luminance(ycbcr_422: &[YCbCr422], luminance: &mut [u8]) {
+    assert_eq!(2 * ycbcr_422.len(), luminance.len());
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        let vectorized_length = ycbcr_422.len() / 8 * 8;
+        // SSE2 is part of every x86_64 processor
+        unsafe {
+            ycbcr_422_to_luminance_sse2(
+                &ycbcr_422[..vectorized_length],
+                &mut luminance[..2 * vectorized_length],
+            );
+        }
+        ycbcr_422_to_luminance_fallback(
+            &ycbcr_422[vectorized_length..],
+            &mut luminance[2 * vectorized_length..],
+        );
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        let vectorized_length = ycbcr_422.len() / 8 * 8;
+        // NEON is part of every aarch64 processor
+        unsafe {
+            ycbcr_422_to_luminance_neon(
+                &ycbcr_422[..vectorized_length],
+                &mut luminance[..2 * vectorized_length],
+            );
+        }
+        ycbcr_422_to_luminance_fallback(
+            &ycbcr_422[vectorized_length..],
+            &mut luminance[2 * vectorized_length..],
+        );
+    }
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    ycbcr_422_to_luminance_fallback(ycbcr_422, luminance);
+}
+
+#[cfg(target_arch = "x86_64")]
+unsafe fn ycbcr_422_to_luminance_sse2(ycbcr_422: &[YCbCr422], luminance: &mut [u8]) {
+    let luminance_mask = _mm_set1_epi16(0x00ff);
+    for (ycbcr_422, luminance) in ycbcr_422
+        .chunks_exact(8)
+        .zip(luminance.chunks_exact_mut(16))
+    {
+        let first = _mm_loadu_si128(ycbcr_422.as_ptr() as *const __m128i);
+        let second = _mm_loadu_si128(ycbcr_422[4..].as_ptr() as *const __m128i);
+        let first = _mm_and_si128(first, luminance_mask);
+        let second = _mm_and_si128(second, luminance_mask);
+        let result = _mm_packus_epi16(first, second);
+        _mm_storeu_si128(luminance.as_mut_ptr() as *mut __m128i, result);
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+unsafe fn ycbcr_422_to_luminance_neon(ycbcr_422: &[YCbCr422], luminance: &mut [u8]) {
+    for (ycbcr_422, luminance) in ycbcr_422
+        .chunks_exact(8)
+        .zip(luminance.chunks_exact_mut(16))
+    {
+        // deinterleaves the even bytes (luminance) from the odd bytes (chroma)
+        let bytes = vld2q_u8(ycbcr_422.as_ptr() as *const u8);
+        vst1q_u8(luminance.as_mut_ptr(), bytes.0);
+    }
+}
+
+fn ycbcr_422_to_luminance_fallback(ycbcr_422: &[YCbCr422], luminance: &mut [u8]) {
+    for (ycbcr_422, luminance) in ycbcr_422.iter().zip(luminance.chunks_exact_mut(2)) {
+        luminance[0] = ycbcr_422.y1;
+        luminance[1] = ycbcr_422.y2;
+    }
+}
+
+fn ycbcr_422_to_rgb_444(ycbcr_422: &[YCbCr422], rgb_444: &mut [u8]) {
+    assert_eq!(6 * ycbcr_422.len(), rgb_444.len());
+
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("ssse3") {
+        let vectorized_length = ycbcr_422.len() / 4 * 4;
+        unsafe {
+            ycbcr_422_to_rgb_444_ssse3(
+                &ycbcr_422[..vectorized_length],
+                &mut rgb_444[..6 * vectorized_length],
+            );
+        }
+        ycbcr_422_to_rgb_444_fallback(
+            &ycbcr_422[vectorized_length..],
+            &mut rgb_444[6 * vectorized_length..],
+        );
+        return;
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        let vectorized_length = ycbcr_422.len() / 8 * 8;
+        // NEON is part of every aarch64 processor
+        unsafe {
+            ycbcr_422_to_rgb_444_neon(
+                &ycbcr_422[..vectorized_length],
+                &mut rgb_444[..6 * vectorized_length],
+            );
+        }
+        ycbcr_422_to_rgb_444_fallback(
+            &ycbcr_422[vectorized_length..],
+            &mut rgb_444[6 * vectorized_length..],
+        );
+    }
+
+    #[cfg(not(target_arch = "aarch64"))]
+    ycbcr_422_to_rgb_444_fallback(ycbcr_422, rgb_444);
+}
+
+// The vectorized implementation uses integer calculus with all factors multiplied by 64:
+//
+// R = (64 * Y + 32 + 90 * (Cr - 128)) / 64
+// G = (64 * Y + 32 - 22 * (Cb - 128) - 46 * (Cr - 128)) / 64
+// B = (64 * Y + 32 + 113 * (Cb - 128)) / 64
+//
+// The additional 32 rounds to the nearest integer. All intermediate values fit into i16.
+//
+// A 128 bit vector holds 4 YCbCr 422 pixels, i.e. 8 RGB pixels. The luminance and chroma
+// components are shuffled into 8 i16 each, the chroma of a 422 pixel is duplicated for both of
+// its luminance values. The resulting channels are saturated to u8 and packed into the two
+// vectors [ R0 .. R7 G0 .. G7 ] and [ B0 .. B7 0 .. 0 ], which are shuffled into the 24 bytes of
+// interleaved RGB output.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn ycbcr_422_to_rgb_444_ssse3(ycbcr_422: &[YCbCr422], rgb_444: &mut [u8]) {
+    const Z: i8 = -128;
+    let luminance_indices = _mm_setr_epi8(0, Z, 2, Z, 4, Z, 6, Z, 8, Z, 10, Z, 12, Z, 14, Z);
+    let blue_difference_indices = _mm_setr_epi8(1, Z, 1, Z, 5, Z, 5, Z, 9, Z, 9, Z, 13, Z, 13, Z);
+    let red_difference_indices = _mm_setr_epi8(3, Z, 3, Z, 7, Z, 7, Z, 11, Z, 11, Z, 15, Z, 15, Z);
+    let chroma_offset = _mm_set1_epi16(128);
+    let rounding_offset = _mm_set1_epi16(32);
+    let red_from_red_difference = _mm_set1_epi16(90);
+    let green_from_blue_difference = _mm_set1_epi16(22);
+    let green_from_red_difference = _mm_set1_epi16(46);
+    let blue_from_blue_difference = _mm_set1_epi16(113);
+    let first_red_green_indices = _mm_setr_epi8(0, 8, Z, 1, 9, Z, 2, 10, Z, 3, 11, Z, 4, 12, Z, 5);
+    let first_blue_indices = _mm_setr_epi8(Z, Z, 0, Z, Z, 1, Z, Z, 2, Z, Z, 3, Z, Z, 4, Z);
+    let second_red_green_indices = _mm_setr_epi8(13, Z, 6, 14, Z, 7, 15, Z, Z, Z, Z, Z, Z, Z, Z, Z);
+    let second_blue_indices = _mm_setr_epi8(Z, 5, Z, Z, 6, Z, Z, 7, Z, Z, Z, Z, Z, Z, Z, Z);
+
+    for (ycbcr_422, rgb_444) in ycbcr_422.chunks_exact(4).zip(rgb_444.chunks_exact_mut(24)) {
+        let ycbcr_422 = _mm_loadu_si128(ycbcr_422.as_ptr() as *const __m128i);
+        let luminance = _mm_shuffle_epi8(ycbcr_422, luminance_indices);
+        let luminance = _mm_add_epi16(_mm_slli_epi16(luminance, 6), rounding_offset);
+        let blue_difference = _mm_sub_epi16(
+            _mm_shuffle_epi8(ycbcr_422, blue_difference_indices),
+            chroma_offset,
+        );
+        let red_difference = _mm_sub_epi16(
+            _mm_shuffle_epi8(ycbcr_422, red_difference_indices),
+            chroma_offset,
+        );
+
+        let red = _mm_add_epi16(
+            luminance,
+            _mm_mullo_epi16(red_difference, red_from_red_difference),
+        );
+        let green = _mm_sub_epi16(
+            _mm_sub_epi16(
+                luminance,
+                _mm_mullo_epi16(blue_difference, green_from_blue_difference),
+            ),
+            _mm_mullo_epi16(red_difference, green_from_red_difference),
+        );
+        let blue = _mm_add_epi16(
+            luminance,
+            _mm_mullo_epi16(blue_difference, blue_from_blue_difference),
+        );
+
+        let red_green = _mm_packus_epi16(_mm_srai_epi16(red, 6), _mm_srai_epi16(green, 6));
+        let blue = _mm_packus_epi16(_mm_srai_epi16(blue, 6), _mm_setzero_si128());
+
+        let first = _mm_or_si128(
+            _mm_shuffle_epi8(red_green, first_red_green_indices),
+            _mm_shuffle_epi8(blue, first_blue_indices),
+        );
+        let second = _mm_or_si128(
+            _mm_shuffle_epi8(red_green, second_red_green_indices),
+            _mm_shuffle_epi8(blue, second_blue_indices),
+        );
+        _mm_storeu_si128(rgb_444.as_mut_ptr() as *mut __m128i, first);
+        _mm_storel_epi64(rgb_444[16..].as_mut_ptr() as *mut __m128i, second);
+    }
+}
+
+// The NEON implementation uses the same integer calculus as the SSSE3 one. 8 YCbCr 422 pixels are
+// deinterleaved into vectors of their components, the channels are calculated separately for the
+// first and second luminance values and saturated to u8 while shifting. Zipping both halves
+// restores the pixel order of the 16 RGB pixels which are interleaved when storing.
+#[cfg(target_arch = "aarch64")]
+unsafe fn ycbcr_422_to_rgb_444_neon(ycbcr_422: &[YCbCr422], rgb_444: &mut [u8]) {
+    let chroma_offset = vdupq_n_s16(128);
+    let rounding_offset = vdupq_n_s16(32);
+
+    let widen = |components: uint8x8_t| vreinterpretq_s16_u16(vmovl_u8(components));
+    let scale_luminance = |luminance: uint8x8_t| {
+        vaddq_s16(
+            vreinterpretq_s16_u16(vshll_n_u8::<6>(luminance)),
+            rounding_offset,
+        )
+    };
+    let zip = |first: int16x8_t, second: int16x8_t| {
+        let zipped = vzip_u8(vqshrun_n_s16::<6>(first), vqshrun_n_s16::<6>(second));
+        vcombine_u8(zipped.0, zipped.1)
+    };
+
+    for (ycbcr_422, rgb_444) in ycbcr_422.chunks_exact(8).zip(rgb_444.chunks_exact_mut(48)) {
+        let components = vld4_u8(ycbcr_422.as_ptr() as *const u8);
+        let first_luminance = scale_luminance(components.0);
+        let second_luminance = scale_luminance(components.2);
+        let blue_difference = vsubq_s16(widen(components.1), chroma_offset);
+        let red_difference = vsubq_s16(widen(components.3), chroma_offset);
+
+        let red = zip(
+            vmlaq_n_s16(first_luminance, red_difference, 90),
+            vmlaq_n_s16(second_luminance, red_difference, 90),
+        );
+        let green = zip(
+            vmlsq_n_s16(
+                vmlsq_n_s16(first_luminance, blue_difference, 22),
+                red_difference,
+                46,
+            ),
+            vmlsq_n_s16(
+                vmlsq_n_s16(second_luminance, blue_difference, 22),
+                red_difference,
+                46,
+            ),
+        );
+        let blue = zip(
+            vmlaq_n_s16(first_luminance, blue_difference, 113),
+            vmlaq_n_s16(second_luminance, blue_difference, 113),
+        );
+
+        vst3q_u8(rgb_444.as_mut_ptr(), uint8x16x3_t(red, green, blue));
+    }
+}
+
+fn ycbcr_422_to_rgb_444_fallback(ycbcr_422: &[YCbCr422], rgb_444: &mut [u8]) {
+    for (ycbcr_422, rgb_444) in ycbcr_422.iter().zip(rgb_444.chunks_exact_mut(6)) {
+        let blue_difference = ycbcr_422.cb as i16 - 128;
+        let red_difference = ycbcr_422.cr as i16 - 128;
+        for (luminance, rgb) in [ycbcr_422.y1, ycbcr_422.y2]
+            .into_iter()
+            .zip(rgb_444.chunks_exact_mut(3))
+        {
+            let luminance = 64 * luminance as i16 + 32;
+            let red = luminance + 90 * red_difference;
+            let green = luminance - 22 * blue_difference - 46 * red_difference;
+            let blue = luminance + 113 * blue_difference;
+            rgb[0] = (red >> 6).clamp(0, 255) as u8;
+            rgb[1] = (green >> 6).clamp(0, 255) as u8;
+            rgb[2] = (blue >> 6).clamp(0, 255) as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(length: usize) -> Vec<YCbCr422> {
+        (0..length)
+            .map(|index| {
+                let value = (index * 37 % 256) as u8;
+                YCbCr422::new(
+                    value,
+                    value.wrapping_mul(7),
+                    255 - value,
+                    value.wrapping_mul(13),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn vectorized_luminance_matches_fallback() {
+        for length in [0, 1, 7, 8, 9, 17, 320 * 480] {
+            let buffer = pattern(length);
+            let mut luminance = vec![0; 2 * length];
+            ycbcr_422_to_luminance(&buffer, &mut luminance);
+            let mut fallback_luminance = vec![0; 2 * length];
+            ycbcr_422_to_luminance_fallback(&buffer, &mut fallback_luminance);
+            assert_eq!(luminance, fallback_luminance);
+        }
+    }
+
+    #[test]
+    fn vectorized_rgb_matches_fallback() {
+        for length in [0, 1, 3, 4, 5, 9, 320 * 480] {
+            let buffer = pattern(length);
+            let mut rgb = vec![0; 6 * length];
+            ycbcr_422_to_rgb_444(&buffer, &mut rgb);
+            let mut fallback_rgb = vec![0; 6 * length];
+            ycbcr_422_to_rgb_444_fallback(&buffer, &mut fallback_rgb);
+            assert_eq!(rgb, fallback_rgb);
+        }
+    }
+
+    #[test]
+    fn integer_rgb_conversion_is_close_to_floating_point_conversion() {
+        let buffer = pattern(256 * 16);
+        let mut rgb = vec![0; 6 * buffer.len()];
+        ycbcr_422_to_rgb_444(&buffer, &mut rgb);
+        let expected = buffer
+            .iter()
+            .flat_map(|&pixel| <[YCbCr444; 2]>::from(pixel))
+            .flat_map(|pixel| {
+                let rgb = Rgb::from(pixel);
+                [rgb.r, rgb.g, rgb.b]
+            });
+        for (channel, expected_channel) in rgb.into_iter().zip(expected) {
+            assert!(channel.abs_diff(expected_channel) <= 1);
+        }
+    }
+
+    #[test]
+    fn downscaling_averages_adjacent_pixels_of_even_rows() {
+        let image = YCbCr422Image::from_ycbcr_buffer(
+            2,
+            2,
+            vec![
+                YCbCr422::new(10, 100, 20, 150),
+                YCbCr422::new(30, 110, 50, 160),
+                YCbCr422::new(255, 255, 255, 255),
+                YCbCr422::new(255, 255, 255, 255),
+            ],
+        );
+
+        let downscaled = image.downscaled_by_half();
+
+        assert_eq!(downscaled.width(), 2);
+        assert_eq!(downscaled.height(), 1);
+        assert_eq!(downscaled.buffer(), &[YCbCr422::new(15, 105, 40, 155)]);
+    }
+}
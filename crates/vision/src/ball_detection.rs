@@ -3,11 +3,12 @@ use compiled_nn::CompiledNN;
 use context_attribute::context;
 use framework::{AdditionalOutput, MainOutput};
 use hardware::PathsInterface;
-use nalgebra::{point, vector, Vector2};
+use nalgebra::{point, vector, Matrix2, Matrix3, Vector2, Vector3};
 use projection::Projection;
 use types::{
-    parameters::BallDetection as BallDetectionParameters, ycbcr422_image::YCbCr422Image, Ball,
-    CameraMatrix, CandidateEvaluation, Circle, PerspectiveGridCandidates, Rectangle,
+    parameters::BallDetection as BallDetectionParameters, units::Meters,
+    ycbcr422_image::YCbCr422Image, Ball, CameraMatrix, CandidateEvaluation, Circle,
+    PerspectiveGridCandidates, Rectangle,
 };
 
 pub const SAMPLE_SIZE: usize = 32;
@@ -47,7 +48,7 @@ pub struct CycleContext {
     pub image: Input<YCbCr422Image, "image">,
 
     pub parameters: Parameter<BallDetectionParameters, "ball_detection.$cycler_instance">,
-    pub ball_radius: Parameter<f32, "field_dimensions.ball_radius">,
+    pub ball_radius: Parameter<Meters, "field_dimensions.ball_radius">,
 }
 
 #[context]
@@ -126,7 +127,13 @@ impl BallDetection {
             context.parameters.cluster_merge_radius_factor,
         );
 
-        let balls = project_balls_to_ground(&clusters, context.camera_matrix, *context.ball_radius);
+        let balls = project_balls_to_ground(
+            &clusters,
+            context.camera_matrix,
+            context.ball_radius.0,
+            context.parameters.image_position_standard_deviation,
+            context.parameters.camera_rotation_standard_deviation,
+        );
 
         Ok(MainOutputs {
             balls: Some(balls).into(),
@@ -321,14 +328,27 @@ fn project_balls_to_ground(
     clusters: &[BallCluster],
     camera_matrix: &CameraMatrix,
     ball_radius: f32,
+    image_position_standard_deviation: f32,
+    camera_rotation_standard_deviation: Vector3<f32>,
 ) -> Vec<Ball> {
+    let pixel_covariance =
+        Matrix2::from_diagonal_element(image_position_standard_deviation.powi(2));
+    let rotation_covariance = Matrix3::from_diagonal(
+        &camera_rotation_standard_deviation.map(|deviation| deviation.powi(2)),
+    );
     clusters
         .iter()
         .filter_map(|cluster| {
             let position_422 = point![cluster.circle.center.x, cluster.circle.center.y];
-            match camera_matrix.pixel_to_ground_with_z(position_422, ball_radius) {
-                Ok(position) => Some(Ball {
+            match camera_matrix.pixel_to_ground_with_z_and_covariance(
+                position_422,
+                ball_radius,
+                pixel_covariance,
+                rotation_covariance,
+            ) {
+                Ok((position, covariance)) => Some(Ball {
                     position,
+                    covariance,
                     image_location: cluster.circle,
                 }),
                 Err(_) => None,
@@ -466,6 +486,8 @@ mod tests {
             image_containment_merge_factor: 1.0,
             cluster_merge_radius_factor: 1.5,
             ball_radius_enlargement_factor: 2.0,
+            image_position_standard_deviation: 2.0,
+            camera_rotation_standard_deviation: vector![0.01, 0.01, 0.01],
         };
         let perspective_grid_candidates = PerspectiveGridCandidates {
             candidates: vec![Circle {
@@ -496,7 +518,7 @@ mod tests {
                 &mut additional_output_buffer,
             ),
             parameters: &parameters,
-            ball_radius: &0.5,
+            ball_radius: &Meters(0.5),
             camera_matrix: &camera_matrix,
             image: &image,
             perspective_grid_candidates: &perspective_grid_candidates,
@@ -520,17 +542,17 @@ mod tests {
         assert!(balls.value.is_some());
 
         assert_eq!(balls.value.as_ref().unwrap().len(), 1);
+        let ball = &balls.value.unwrap()[0];
+        assert_relative_eq!(ball.position, point![0.374, 0.008], epsilon = 0.01);
         assert_relative_eq!(
-            balls.value.unwrap()[0],
-            Ball {
-                position: point![0.374, 0.008],
-                image_location: Circle {
-                    center: point![308.93, 176.42],
-                    radius: 42.92,
-                }
+            ball.image_location,
+            Circle {
+                center: point![308.93, 176.42],
+                radius: 42.92,
             },
             epsilon = 0.01,
         );
+        assert!(ball.covariance.determinant() > 0.0);
         Ok(())
     }
 }
@@ -0,0 +1,260 @@
+use std::thread::{self, JoinHandle};
+
+use calibration::intrinsics::{self, IntrinsicsError, Solution};
+use color_eyre::{eyre::WrapErr, Result};
+use context_attribute::context;
+use framework::{AdditionalOutput, MainOutput};
+use hardware::{IdInterface, PathsInterface};
+use log::{info, warn};
+use nalgebra::{point, vector, Point2};
+use parameters::{
+    background::BackgroundSerializer,
+    directory::{Id, Location, Scope},
+};
+use serde_json::json;
+use types::{
+    parameters::CameraIntrinsicsCalibrationRoutine, ycbcr422_image::YCbCr422Image,
+    CameraIntrinsicsCalibration, CameraPosition,
+};
+
+use crate::checkerboard::{detect_corners, order_corners};
+
+/// Estimates focal lengths and optical center of the camera from views of a checkerboard
+///
+/// While the routine is enabled, the corners of every image showing the whole checkerboard are
+/// recorded if the checkerboard moved since the last recorded observation. Once enough
+/// observations are recorded, the intrinsics are solved in the background and written into the
+/// parameter file of this head. They are used after the parameters are loaded again, e.g. after a
+/// restart.
+pub struct CameraIntrinsicsCalibrator {
+    observations: Vec<Vec<Point2<f32>>>,
+    solver: Option<JoinHandle<Result<Solution, IntrinsicsError>>>,
+    calibration: Option<CameraIntrinsicsCalibration>,
+    serializer: BackgroundSerializer,
+}
+
+#[context]
+pub struct CreationContext {
+    pub hardware_interface: HardwareInterface,
+}
+
+#[context]
+pub struct CycleContext {
+    pub checkerboard_corners: AdditionalOutput<Vec<Point2<f32>>, "checkerboard_corners">,
+
+    pub image: Input<YCbCr422Image, "image">,
+
+    pub camera_position:
+        Parameter<CameraPosition, "image_receiver.$cycler_instance.camera_position">,
+    pub routine:
+        Parameter<CameraIntrinsicsCalibrationRoutine, "camera_intrinsics_calibration_routine">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub camera_intrinsics_calibration: MainOutput<Option<CameraIntrinsicsCalibration>>,
+}
+
+impl CameraIntrinsicsCalibrator {
+    pub fn new(context: CreationContext<impl IdInterface + PathsInterface>) -> Result<Self> {
+        let ids = context.hardware_interface.get_ids();
+        let serializer = BackgroundSerializer::spawn(
+            context.hardware_interface.get_paths().parameters,
+            ids.body_id,
+            ids.head_id,
+        )
+        .wrap_err("failed to spawn parameter serializer")?;
+        Ok(Self {
+            observations: Vec::new(),
+            solver: None,
+            calibration: None,
+            serializer,
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let routine = context.routine;
+        if !routine.enable {
+            self.observations.clear();
+            // a running solver finishes detached, its solution is discarded
+            self.solver = None;
+            self.calibration = None;
+            return Ok(MainOutputs::default());
+        }
+        if self.calibration.is_some() {
+            return Ok(MainOutputs {
+                camera_intrinsics_calibration: self.calibration.into(),
+            });
+        }
+
+        let width = context.image.width() as usize;
+        let height = context.image.height() as usize;
+        if let Some(solver) = self.solver.take() {
+            if !solver.is_finished() {
+                self.solver = Some(solver);
+                return Ok(MainOutputs::default());
+            }
+            match solver.join() {
+                Ok(Ok(solution)) => {
+                    let calibration =
+                        normalized_calibration(&solution, width, height, self.observations.len());
+                    info!(
+                        "Calibrated {:?} camera intrinsics: {calibration:?}",
+                        context.camera_position
+                    );
+                    self.store_calibration(&calibration, *context.camera_position);
+                    self.calibration = Some(calibration);
+                }
+                Ok(Err(error)) => {
+                    warn!("Failed to calibrate camera intrinsics, starting over: {error}");
+                    self.observations.clear();
+                }
+                Err(_) => {
+                    warn!("Camera intrinsics solver panicked, starting over");
+                    self.observations.clear();
+                }
+            }
+            return Ok(MainOutputs {
+                camera_intrinsics_calibration: self.calibration.into(),
+            });
+        }
+
+        let corners = detect_corners(
+            &context.image.luminance(),
+            width,
+            height,
+            routine.corner_detection_radius,
+            routine.minimum_corner_response,
+        );
+        let ordered_corners = order_corners(
+            &corners,
+            routine.checkerboard_columns,
+            routine.checkerboard_rows,
+        );
+        context
+            .checkerboard_corners
+            .fill_if_subscribed(|| ordered_corners.clone().unwrap_or_else(|| corners.clone()));
+        let Some(ordered_corners) = ordered_corners else {
+            return Ok(MainOutputs::default());
+        };
+        let has_moved = self.observations.last().map_or(true, |last_corners| {
+            mean_displacement(last_corners, &ordered_corners) >= routine.minimum_corner_displacement
+        });
+        if has_moved {
+            self.observations.push(ordered_corners);
+        }
+        if self.observations.len() < routine.number_of_observations {
+            return Ok(MainOutputs::default());
+        }
+
+        let object_points: Vec<_> = (0..routine.checkerboard_rows)
+            .flat_map(|row| (0..routine.checkerboard_columns).map(move |column| (column, row)))
+            .map(|(column, row)| point![column as f64, row as f64])
+            .collect();
+        let views: Vec<Vec<Point2<f64>>> = self
+            .observations
+            .iter()
+            .map(|corners| corners.iter().map(|corner| corner.cast()).collect())
+            .collect();
+        // the optimization takes several cycles, hence runs on its own thread
+        self.solver = Some(
+            thread::Builder::new()
+                .name("camera_intrinsics_solver".to_string())
+                .spawn(move || intrinsics::solve(&object_points, &views))
+                .wrap_err("failed to spawn camera intrinsics solver")?,
+        );
+
+        Ok(MainOutputs::default())
+    }
+
+    /// Writes focal lengths and optical center into the parameter file of this head in the
+    /// background
+    fn store_calibration(
+        &self,
+        calibration: &CameraIntrinsicsCalibration,
+        camera_position: CameraPosition,
+    ) {
+        let cycler_instance = match camera_position {
+            CameraPosition::Top => "vision_top",
+            CameraPosition::Bottom => "vision_bottom",
+        };
+        let scope = Scope {
+            location: Location::All,
+            id: Id::Head,
+        };
+        if let Err(error) = self.serializer.serialize(
+            &json!({
+                "focal_lengths": calibration.focal_lengths,
+                "cc_optical_center": calibration.optical_center,
+            }),
+            scope,
+            &format!("camera_matrix_parameters.{cycler_instance}"),
+        ) {
+            warn!("Failed to store camera intrinsics: {error:?}");
+        }
+    }
+}
+
+fn mean_displacement(first: &[Point2<f32>], second: &[Point2<f32>]) -> f32 {
+    first
+        .iter()
+        .zip(second)
+        .map(|(first, second)| (first - second).norm())
+        .sum::<f32>()
+        / first.len().max(1) as f32
+}
+
+/// Normalizes focal lengths and optical center by the image size like the camera matrix
+/// parameters, which keeps them valid for other resolutions
+fn normalized_calibration(
+    solution: &Solution,
+    width: usize,
+    height: usize,
+    number_of_observations: usize,
+) -> CameraIntrinsicsCalibration {
+    let intrinsics = solution.intrinsics;
+    let image_size = vector![width as f64, height as f64];
+    CameraIntrinsicsCalibration {
+        focal_lengths: intrinsics.focal_lengths.component_div(&image_size).cast(),
+        optical_center: Point2::from(intrinsics.optical_center.coords.component_div(&image_size))
+            .cast(),
+        radial_distortion: intrinsics.radial_distortion.cast(),
+        reprojection_error: solution.reprojection_error as f32,
+        number_of_observations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::Vector2;
+
+    use super::*;
+
+    #[test]
+    fn intrinsics_are_normalized_by_image_size() {
+        let solution = Solution {
+            intrinsics: intrinsics::Intrinsics {
+                focal_lengths: vector![320.0, 480.0],
+                optical_center: point![160.0, 120.0],
+                radial_distortion: Vector2::zeros(),
+            },
+            reprojection_error: 0.2,
+        };
+
+        let calibration = normalized_calibration(&solution, 640, 480, 10);
+
+        assert_relative_eq!(calibration.focal_lengths, vector![0.5, 1.0]);
+        assert_relative_eq!(calibration.optical_center, point![0.25, 0.25]);
+        assert_eq!(calibration.number_of_observations, 10);
+    }
+
+    #[test]
+    fn displacement_is_averaged_over_corners() {
+        let first = [point![0.0, 0.0], point![10.0, 0.0]];
+        let second = [point![3.0, 4.0], point![10.0, 0.0]];
+
+        assert_relative_eq!(mean_displacement(&first, &second), 2.5);
+    }
+}
@@ -13,6 +13,7 @@ pub struct CycleContext {
     pub camera_matrices: RequiredInput<Option<CameraMatrices>, "Control", "camera_matrices?">,
     pub camera_position:
         Parameter<CameraPosition, "image_receiver.$cycler_instance.camera_position">,
+    pub half_resolution: Parameter<bool, "image_receiver.$cycler_instance.half_resolution">,
 }
 
 #[context]
@@ -32,8 +33,14 @@ impl CameraMatrixExtractor {
             CameraPosition::Bottom => &context.camera_matrices.bottom,
         };
 
+        let camera_matrix = if *context.half_resolution {
+            camera_matrix.to_scaled(0.5)
+        } else {
+            camera_matrix.clone()
+        };
+
         Ok(MainOutputs {
-            camera_matrix: Some(camera_matrix.clone()).into(),
+            camera_matrix: Some(camera_matrix).into(),
         })
     }
 }
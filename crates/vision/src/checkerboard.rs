@@ -0,0 +1,292 @@
+use std::f32::consts::TAU;
+
+use calibration::intrinsics::homography;
+use nalgebra::{point, Point2};
+
+const NUMBER_OF_RING_SAMPLES: usize = 16;
+const MAXIMUM_NUMBER_OF_REFINEMENTS: usize = 5;
+
+/// Finds the inner corners of a checkerboard, where two dark and two bright squares touch
+///
+/// Every pixel is rated with the ChESS response (Bennett and Lasenby, 2014) of a ring of samples
+/// around it: opposite samples of an inner corner are similar while samples a quarter turn apart
+/// differ. Local maxima of the response are refined to subpixel precision using the image
+/// gradients around them, which are orthogonal to the direction towards the corner.
+pub fn detect_corners(
+    luminance: &[u8],
+    width: usize,
+    height: usize,
+    radius: usize,
+    minimum_response: f32,
+) -> Vec<Point2<f32>> {
+    let margin = radius + 1;
+    if radius == 0 || width <= 2 * margin || height <= 2 * margin {
+        return Vec::new();
+    }
+    let at = |x: usize, y: usize| luminance[y * width + x] as f32;
+    let ring: Vec<(isize, isize)> = (0..NUMBER_OF_RING_SAMPLES)
+        .map(|index| {
+            let angle = TAU * index as f32 / NUMBER_OF_RING_SAMPLES as f32;
+            (
+                (radius as f32 * angle.cos()).round() as isize,
+                (radius as f32 * angle.sin()).round() as isize,
+            )
+        })
+        .collect();
+
+    let mut responses = vec![0.0; width * height];
+    for y in margin..height - margin {
+        for x in margin..width - margin {
+            let samples: Vec<f32> = ring
+                .iter()
+                .map(|(offset_x, offset_y)| {
+                    at(
+                        x.wrapping_add_signed(*offset_x),
+                        y.wrapping_add_signed(*offset_y),
+                    )
+                })
+                .collect();
+            let sum_response: f32 = (0..4)
+                .map(|index| {
+                    ((samples[index] + samples[index + 8])
+                        - (samples[index + 4] + samples[index + 12]))
+                        .abs()
+                })
+                .sum();
+            let difference_response: f32 = (0..8)
+                .map(|index| (samples[index] - samples[index + 8]).abs())
+                .sum();
+            let ring_mean = samples.iter().sum::<f32>() / NUMBER_OF_RING_SAMPLES as f32;
+            let local_mean =
+                (at(x, y) + at(x - 1, y) + at(x + 1, y) + at(x, y - 1) + at(x, y + 1)) / 5.0;
+            responses[y * width + x] = sum_response
+                - difference_response
+                - NUMBER_OF_RING_SAMPLES as f32 * (local_mean - ring_mean).abs();
+        }
+    }
+
+    let mut corners = Vec::new();
+    for y in margin..height - margin {
+        for x in margin..width - margin {
+            let response = responses[y * width + x];
+            if response < minimum_response {
+                continue;
+            }
+            // ties are resolved towards the first pixel in reading order
+            let is_local_maximum = (y - radius..=y + radius).all(|neighbor_y| {
+                (x - radius..=x + radius).all(|neighbor_x| {
+                    let neighbor = responses[neighbor_y * width + neighbor_x];
+                    let is_before = (neighbor_y, neighbor_x) < (y, x);
+                    neighbor < response || (neighbor == response && !is_before)
+                })
+            });
+            if is_local_maximum {
+                corners.push(refine_corner(
+                    luminance,
+                    width,
+                    height,
+                    point![x as f32, y as f32],
+                    radius,
+                ));
+            }
+        }
+    }
+    corners
+}
+
+/// Moves the corner to the point minimizing the projections of the gradients in the window
+/// around it onto the directions towards it
+fn refine_corner(
+    luminance: &[u8],
+    width: usize,
+    height: usize,
+    corner: Point2<f32>,
+    radius: usize,
+) -> Point2<f32> {
+    let at = |x: usize, y: usize| luminance[y * width + x] as f32;
+    let mut refined = corner;
+    for _ in 0..MAXIMUM_NUMBER_OF_REFINEMENTS {
+        let center_x = refined.x.round() as isize;
+        let center_y = refined.y.round() as isize;
+        let radius = radius as isize;
+        if center_x - radius < 1
+            || center_y - radius < 1
+            || center_x + radius + 1 >= width as isize
+            || center_y + radius + 1 >= height as isize
+        {
+            break;
+        }
+        let (mut xx, mut xy, mut yy, mut target_x, mut target_y) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for y in (center_y - radius..=center_y + radius).map(|y| y as usize) {
+            for x in (center_x - radius..=center_x + radius).map(|x| x as usize) {
+                let gradient_x = (at(x + 1, y) - at(x - 1, y)) / 2.0;
+                let gradient_y = (at(x, y + 1) - at(x, y - 1)) / 2.0;
+                xx += gradient_x * gradient_x;
+                xy += gradient_x * gradient_y;
+                yy += gradient_y * gradient_y;
+                target_x += gradient_x * gradient_x * x as f32 + gradient_x * gradient_y * y as f32;
+                target_y += gradient_x * gradient_y * x as f32 + gradient_y * gradient_y * y as f32;
+            }
+        }
+        let determinant = xx * yy - xy * xy;
+        if determinant.abs() <= f32::EPSILON {
+            break;
+        }
+        let next = point![
+            (yy * target_x - xy * target_y) / determinant,
+            (xx * target_y - xy * target_x) / determinant
+        ];
+        let movement = (next - refined).norm();
+        if movement > radius as f32 {
+            break;
+        }
+        refined = next;
+        if movement < 0.01 {
+            break;
+        }
+    }
+    refined
+}
+
+/// Assigns the detected corners to the inner corners of the checkerboard row by row
+///
+/// The outermost corners are the extremes along the image diagonals, which requires the
+/// checkerboard to be roughly aligned with the image axes. Every inner corner needs a detection
+/// close to where the homography of the outermost corners expects it, spurious or missing
+/// detections reject the whole image.
+pub fn order_corners(
+    corners: &[Point2<f32>],
+    columns: usize,
+    rows: usize,
+) -> Option<Vec<Point2<f32>>> {
+    if columns < 2 || rows < 2 || corners.len() != columns * rows {
+        return None;
+    }
+    let extreme = |key: fn(&Point2<f32>) -> f32| {
+        corners
+            .iter()
+            .max_by(|first, second| key(first).total_cmp(&key(second)))
+            .map(|corner| corner.cast::<f64>())
+    };
+    let top_left = extreme(|corner| -corner.x - corner.y)?;
+    let top_right = extreme(|corner| corner.x - corner.y)?;
+    let bottom_right = extreme(|corner| corner.x + corner.y)?;
+    let bottom_left = extreme(|corner| corner.y - corner.x)?;
+    let grid_corners = [
+        point![0.0, 0.0],
+        point![(columns - 1) as f64, 0.0],
+        point![(columns - 1) as f64, (rows - 1) as f64],
+        point![0.0, (rows - 1) as f64],
+    ];
+
+    // rows of the checkerboard may run horizontally or vertically in the image
+    [
+        [top_left, top_right, bottom_right, bottom_left],
+        [top_left, bottom_left, bottom_right, top_right],
+    ]
+    .into_iter()
+    .find_map(|outermost_corners| {
+        let grid_to_image = homography(&grid_corners, &outermost_corners)?;
+        let mut is_assigned = vec![false; corners.len()];
+        (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column as f64, row as f64)))
+            .map(|(column, row)| {
+                let expected = grid_to_image.transform_point(&point![column, row]);
+                let next_in_row = grid_to_image.transform_point(&point![column + 1.0, row]);
+                let maximum_distance = 0.3 * (next_in_row - expected).norm();
+                let (index, distance) = corners
+                    .iter()
+                    .map(|corner| (corner.cast::<f64>() - expected).norm())
+                    .enumerate()
+                    .min_by(|(_, first), (_, second)| first.total_cmp(second))?;
+                if distance > maximum_distance || is_assigned[index] {
+                    return None;
+                }
+                is_assigned[index] = true;
+                Some(corners[index])
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{vector, Rotation2, Vector2};
+
+    use super::*;
+
+    const SQUARE_SIZE: f32 = 20.0;
+
+    /// Checkerboard with a bright margin of one square on a gray background, rotated around the
+    /// top left inner corner
+    fn render_checkerboard(
+        width: usize,
+        height: usize,
+        columns: usize,
+        rows: usize,
+        top_left: Point2<f32>,
+        rotation: Rotation2<f32>,
+    ) -> Vec<u8> {
+        const SUPERSAMPLING: usize = 4;
+        let mut luminance = vec![0; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = 0.0;
+                for sample in 0..SUPERSAMPLING * SUPERSAMPLING {
+                    let offset = vector![
+                        (sample % SUPERSAMPLING) as f32 + 0.5,
+                        (sample / SUPERSAMPLING) as f32 + 0.5
+                    ] / SUPERSAMPLING as f32
+                        - vector![0.5, 0.5];
+                    let in_board = rotation.inverse()
+                        * (point![x as f32, y as f32] + offset - top_left)
+                        / SQUARE_SIZE;
+                    let square = in_board.map(|coordinate| coordinate.floor() as isize + 1);
+                    let is_on_board = (0..=columns as isize).contains(&square.x)
+                        && (0..=rows as isize).contains(&square.y);
+                    let is_on_margin = (-1..=columns as isize + 1).contains(&square.x)
+                        && (-1..=rows as isize + 1).contains(&square.y);
+                    sum += match (is_on_board, is_on_margin) {
+                        (true, _) if (square.x + square.y) % 2 == 0 => 30.0,
+                        (true, _) => 220.0,
+                        (false, true) => 230.0,
+                        (false, false) => 120.0,
+                    };
+                }
+                luminance[y * width + x] = (sum / (SUPERSAMPLING * SUPERSAMPLING) as f32) as u8;
+            }
+        }
+        luminance
+    }
+
+    #[test]
+    fn corners_of_rotated_checkerboard_are_detected_in_order() {
+        let (width, height, columns, rows) = (200, 160, 5, 4);
+        let top_left = point![60.3, 40.7];
+        let rotation = Rotation2::new(0.2);
+        let luminance = render_checkerboard(width, height, columns, rows, top_left, rotation);
+
+        let corners = detect_corners(&luminance, width, height, 5, 300.0);
+        let ordered = order_corners(&corners, columns, rows).unwrap();
+
+        for (index, corner) in ordered.iter().enumerate() {
+            let grid_position = Vector2::new((index % columns) as f32, (index / columns) as f32);
+            let expected = top_left + rotation * grid_position * SQUARE_SIZE;
+            assert!(
+                (corner - expected).norm() < 0.3,
+                "corner {index} at {corner} instead of {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn incomplete_checkerboards_are_rejected() {
+        let corners: Vec<_> = (0..3)
+            .flat_map(|row| (0..3).map(move |column| point![column as f32, row as f32]))
+            .collect();
+
+        assert!(order_corners(&corners, 3, 3).is_some());
+        assert_eq!(order_corners(&corners[1..], 3, 3), None);
+        assert_eq!(order_corners(&corners, 4, 3), None);
+    }
+}
@@ -1,8 +1,8 @@
 use color_eyre::Result;
 use context_attribute::context;
-use framework::MainOutput;
+use framework::{AdditionalOutput, MainOutput};
 use hardware::CameraInterface;
-use types::{ycbcr422_image::YCbCr422Image, CameraPosition};
+use types::{hardware::CameraDiagnostics, ycbcr422_image::YCbCr422Image, CameraPosition};
 
 pub struct ImageReceiver {}
 
@@ -12,8 +12,10 @@ pub struct CreationContext {}
 #[context]
 pub struct CycleContext {
     pub hardware_interface: HardwareInterface,
+    pub camera_diagnostics: AdditionalOutput<CameraDiagnostics, "camera_diagnostics">,
     pub camera_position:
         Parameter<CameraPosition, "image_receiver.$cycler_instance.camera_position">,
+    pub half_resolution: Parameter<bool, "image_receiver.$cycler_instance.half_resolution">,
 }
 
 #[context]
@@ -26,10 +28,23 @@ impl ImageReceiver {
         Ok(Self {})
     }
 
-    pub fn cycle(&mut self, context: CycleContext<impl CameraInterface>) -> Result<MainOutputs> {
+    pub fn cycle(
+        &mut self,
+        mut context: CycleContext<impl CameraInterface>,
+    ) -> Result<MainOutputs> {
         let image = context
             .hardware_interface
             .read_from_camera(*context.camera_position)?;
+        context.camera_diagnostics.fill_if_subscribed(|| {
+            context
+                .hardware_interface
+                .get_camera_diagnostics(*context.camera_position)
+        });
+        let image = if *context.half_resolution {
+            image.downscaled_by_half()
+        } else {
+            image
+        };
         Ok(MainOutputs {
             image: image.into(),
         })
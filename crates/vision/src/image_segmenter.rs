@@ -48,6 +48,10 @@ pub struct CycleContext {
         Parameter<Interpolated, "image_segmenter.$cycler_instance.vertical_edge_threshold">,
     pub vertical_median_mode:
         Parameter<MedianMode, "image_segmenter.$cycler_instance.vertical_median_mode">,
+    pub horizontal_scan_line_spacing:
+        Parameter<usize, "image_segmenter.$cycler_instance.horizontal_scan_line_spacing">,
+    pub horizontal_edge_threshold:
+        Parameter<Interpolated, "image_segmenter.$cycler_instance.horizontal_edge_threshold">,
 }
 
 #[context]
@@ -93,6 +97,11 @@ impl ImageSegmenter {
                 .evaluate_at(self.robot_to_field_of_home_after_coin_toss_before_second_half)
                 as i16,
             *context.vertical_median_mode,
+            *context.horizontal_scan_line_spacing,
+            context
+                .horizontal_edge_threshold
+                .evaluate_at(self.robot_to_field_of_home_after_coin_toss_before_second_half)
+                as i16,
             projected_limbs,
         );
         let end = Instant::now();
@@ -115,6 +124,8 @@ fn new_grid(
     vertical_edge_detection_source: EdgeDetectionSource,
     vertical_edge_threshold: i16,
     vertical_median_mode: MedianMode,
+    horizontal_scan_line_spacing: usize,
+    horizontal_edge_threshold: i16,
     projected_limbs: &[Limb],
 ) -> ScanGrid {
     let horizon_y_minimum = horizon
@@ -138,6 +149,20 @@ fn new_grid(
                 )
             })
             .collect(),
+        horizontal_scan_lines: (horizon_y_minimum as u32..image.height())
+            .step_by(horizontal_scan_line_spacing)
+            .map(|y| {
+                new_horizontal_scan_line(
+                    image,
+                    field_color,
+                    y,
+                    horizontal_stride,
+                    vertical_edge_detection_source,
+                    horizontal_edge_threshold,
+                    projected_limbs,
+                )
+            })
+            .collect(),
     }
 }
 
@@ -317,6 +342,53 @@ fn new_vertical_scan_line(
     }
 }
 
+fn new_horizontal_scan_line(
+    image: &YCbCr422Image,
+    field_color: &FieldColor,
+    position: u32,
+    stride: usize,
+    edge_detection_source: EdgeDetectionSource,
+    edge_threshold: i16,
+    projected_limbs: &[Limb],
+) -> ScanLine {
+    let mut state = ScanLineState::new(
+        pixel_to_edge_detection_value(image.at(0, position), edge_detection_source) as i16,
+        0,
+        EdgeType::ImageBorder,
+    );
+
+    let mut segments = Vec::with_capacity(image.width() as usize / stride);
+    for x in (0..image.width()).step_by(stride) {
+        let luminance_value =
+            pixel_to_edge_detection_value(image.at(x, position), edge_detection_source) as i16;
+        if let Some(segment) = detect_edge(&mut state, x as u16, luminance_value, edge_threshold) {
+            segments.push(segment);
+        }
+    }
+    segments.push(Segment {
+        start: state.start_position,
+        end: image.width() as u16,
+        start_edge_type: state.start_edge_type,
+        end_edge_type: EdgeType::ImageBorder,
+        color: Default::default(),
+        field_color: Intensity::Low,
+    });
+
+    ScanLine {
+        position: position as u16,
+        segments: segments
+            .into_iter()
+            .filter(|segment| {
+                is_above_limbs(
+                    point![segment.center() as f32, position as f32],
+                    projected_limbs,
+                )
+            })
+            .map(|segment| set_color_in_horizontal_segment(segment, image, position, field_color))
+            .collect(),
+    }
+}
+
 fn pixel_to_edge_detection_value(
     pixel: YCbCr444,
     edge_detection_source: EdgeDetectionSource,
@@ -418,6 +490,29 @@ fn set_color_in_vertical_segment(
     segment
 }
 
+fn set_color_in_horizontal_segment(
+    mut segment: Segment,
+    image: &YCbCr422Image,
+    position: u32,
+    field_color: &FieldColor,
+) -> Segment {
+    segment.color = if segment.length() >= 4 {
+        let spacing = segment.length() / 4;
+        let first_pixel = image.at((segment.start + spacing) as u32, position);
+        let second_pixel = image.at((segment.start + 2 * spacing) as u32, position);
+        let third_pixel = image.at((segment.start + 3 * spacing) as u32, position);
+
+        let y = median_of_three(first_pixel.y, second_pixel.y, third_pixel.y);
+        let cb = median_of_three(first_pixel.cb, second_pixel.cb, third_pixel.cb);
+        let cr = median_of_three(first_pixel.cr, second_pixel.cr, third_pixel.cr);
+        YCbCr444::new(y, cb, cr)
+    } else {
+        image.at(segment.center() as u32, position)
+    };
+    segment.field_color = field_color.get_intensity(segment.color);
+    segment
+}
+
 fn segment_is_below_limbs(
     scan_line_position: u16,
     segment: &Segment,
@@ -739,6 +834,49 @@ mod tests {
         assert_eq!(scan_line.segments[2].end, 12);
     }
 
+    #[test]
+    fn image_with_three_horizontal_increasing_segments() {
+        let image = YCbCr422Image::from_ycbcr_buffer(
+            6,
+            1,
+            vec![
+                YCbCr422::new(0, 0, 0, 0),
+                YCbCr422::new(1, 0, 1, 0),
+                YCbCr422::new(1, 0, 1, 0),
+                YCbCr422::new(2, 0, 2, 0),
+                YCbCr422::new(2, 0, 2, 0),
+                YCbCr422::new(3, 0, 3, 0),
+            ],
+        );
+        let field_color = FieldColor {
+            red_chromaticity_threshold: 0.37,
+            blue_chromaticity_threshold: 0.38,
+            lower_green_chromaticity_threshold: 0.4,
+            upper_green_chromaticity_threshold: 0.43,
+            green_luminance_threshold: 255.0,
+        };
+
+        let scan_line = new_horizontal_scan_line(
+            &image,
+            &field_color,
+            0,
+            2,
+            EdgeDetectionSource::Luminance,
+            1,
+            &[],
+        );
+        assert_eq!(scan_line.position, 0);
+        assert_eq!(scan_line.segments.len(), 3);
+        assert_eq!(scan_line.segments[0].start, 0);
+        assert_eq!(scan_line.segments[0].end, 2);
+        assert_eq!(scan_line.segments[1].start, 2);
+        assert_eq!(scan_line.segments[1].end, 6);
+        assert_eq!(scan_line.segments[2].start, 6);
+        assert_eq!(scan_line.segments[2].end, 12);
+        assert_eq!(scan_line.segments[1].color.y, 1);
+        assert_eq!(scan_line.segments[2].end_edge_type, EdgeType::ImageBorder);
+    }
+
     #[test]
     fn image_with_three_vertical_increasing_segments_with_median() {
         let image = YCbCr422Image::from_ycbcr_buffer(
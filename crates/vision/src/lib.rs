@@ -1,5 +1,7 @@
 pub mod ball_detection;
+pub mod camera_intrinsics_calibrator;
 pub mod camera_matrix_extractor;
+mod checkerboard;
 pub mod feet_detection;
 pub mod field_border_detection;
 pub mod field_color_detection;
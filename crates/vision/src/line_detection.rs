@@ -375,6 +375,7 @@ mod tests {
             FilteredSegments {
                 scan_grid: ScanGrid {
                     vertical_scan_lines,
+                    horizontal_scan_lines: vec![],
                 },
             }
         }
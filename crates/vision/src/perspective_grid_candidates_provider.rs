@@ -6,7 +6,7 @@ use framework::MainOutput;
 use nalgebra::{point, vector, Point2, Vector2};
 use projection::Projection;
 use types::{
-    ycbcr422_image::YCbCr422Image, CameraMatrix, Circle, FilteredSegments, LineData,
+    units::Meters, ycbcr422_image::YCbCr422Image, CameraMatrix, Circle, FilteredSegments, LineData,
     PerspectiveGridCandidates, ScanLine, Segment,
 };
 
@@ -28,7 +28,7 @@ pub struct CycleContext {
     pub line_data: RequiredInput<Option<LineData>, "line_data?">,
     pub image: Input<YCbCr422Image, "image">,
 
-    pub ball_radius: Parameter<f32, "field_dimensions.ball_radius">,
+    pub ball_radius: Parameter<Meters, "field_dimensions.ball_radius">,
     pub fallback_radius:
         Parameter<f32, "perspective_grid_candidates_provider.$cycler_instance.fallback_radius">,
     pub minimum_radius:
@@ -56,7 +56,7 @@ impl PerspectiveGridCandidatesProvider {
             image_size,
             *context.minimum_radius,
             *context.fallback_radius,
-            *context.ball_radius,
+            context.ball_radius.0,
         );
         let candidates = generate_candidates(vertical_scanlines, skip_segments, &rows);
 
@@ -9,13 +9,16 @@ use fast_image_resize::{
 use framework::{AdditionalOutput, MainOutput};
 use hardware::PathsInterface;
 use itertools::Itertools;
-use nalgebra::{vector, Isometry3, Vector2};
+use nalgebra::{vector, Isometry3, Vector2, Vector3};
 use projection::Projection;
+use spl_network_messages::TeamColor;
 use types::{
-    detected_robots::{BoundingBox, DetectedRobots},
+    detected_robots::{BoundingBox, DetectedRobots, RobotOnGround},
     grayscale_image::GrayscaleImage,
+    parameters::JerseyClassification,
     ycbcr422_image::YCbCr422Image,
-    CameraMatrix,
+    CameraMatrix, GameControllerState, ImageSegments, ObstacleKind, Rgb, ScanLine, TeamColors,
+    YCbCr444,
 };
 
 const NUMBER_OF_SCALINGS: usize = 4;
@@ -41,8 +44,11 @@ pub struct CreationContext {
 #[context]
 pub struct CycleContext {
     pub image: Input<YCbCr422Image, "image">,
+    pub image_segments: Input<ImageSegments, "image_segments">,
     pub camera_matrix: RequiredInput<Option<CameraMatrix>, "camera_matrix?">,
     pub robot_to_ground: RequiredInput<Option<Isometry3<f32>>, "Control", "robot_to_ground?">,
+    pub game_controller_state:
+        Input<Option<GameControllerState>, "Control", "game_controller_state?">,
     pub luminance_image: AdditionalOutput<GrayscaleImage, "robot_detection.luminance_image">,
     pub object_threshold: Parameter<f32, "robot_detection.$cycler_instance.object_threshold">,
     pub enable: Parameter<bool, "robot_detection.$cycler_instance.enable">,
@@ -54,6 +60,8 @@ pub struct CycleContext {
         Parameter<f32, "robot_detection.$cycler_instance.lowest_bottom_pixel_position">,
     pub allowed_projected_robot_height:
         Parameter<Range<f32>, "robot_detection.$cycler_instance.allowed_projected_robot_height">,
+    pub jersey_classification:
+        Parameter<JerseyClassification, "robot_detection.$cycler_instance.jersey_classification">,
 }
 
 #[context]
@@ -120,7 +128,19 @@ impl RobotDetection {
             .iter()
             .filter_map(|bounding_box| {
                 let box_bottom = bounding_box.center + vector![0.0, bounding_box.size.y / 2.0];
-                context.camera_matrix.pixel_to_ground(box_bottom).ok()
+                let position = context.camera_matrix.pixel_to_ground(box_bottom).ok()?;
+                let kind = match context.game_controller_state {
+                    Some(game_controller_state) if context.jersey_classification.enable => {
+                        classify_jersey(
+                            context.image_segments,
+                            bounding_box,
+                            game_controller_state,
+                            context.jersey_classification,
+                        )
+                    }
+                    _ => ObstacleKind::Robot,
+                };
+                Some(RobotOnGround { position, kind })
             })
             .collect();
 
@@ -168,12 +188,112 @@ fn filter_by_size(
     grid_boxes
 }
 
-fn generate_luminance_image(image: &YCbCr422Image) -> Result<GrayscaleImage, ImageBufferError> {
-    let grayscale_buffer: Vec<_> = image
-        .buffer()
+/// Compares the torso color inside the bounding box with the jersey colors of both teams
+fn classify_jersey(
+    image_segments: &ImageSegments,
+    bounding_box: &BoundingBox,
+    game_controller_state: &GameControllerState,
+    parameters: &JerseyClassification,
+) -> ObstacleKind {
+    let Some(torso_color) = mean_torso_color(image_segments, bounding_box) else {
+        return ObstacleKind::Robot;
+    };
+    let distance_to_team = |team_colors: &TeamColors| {
+        [team_colors.field_player, team_colors.goal_keeper]
+            .into_iter()
+            .map(|team_color| {
+                color_distance(
+                    torso_color,
+                    YCbCr444::from(reference_color(team_color)),
+                    parameters.luminance_weight,
+                )
+            })
+            .fold(f32::INFINITY, f32::min)
+    };
+    let teammate_distance = distance_to_team(&game_controller_state.hulks_team_colors);
+    let opponent_distance = distance_to_team(&game_controller_state.opponent_team_colors);
+    let (kind, closest_distance, other_distance) = if teammate_distance < opponent_distance {
+        (ObstacleKind::Teammate, teammate_distance, opponent_distance)
+    } else {
+        (ObstacleKind::Opponent, opponent_distance, teammate_distance)
+    };
+    let is_unambiguous = closest_distance < parameters.maximum_color_distance
+        && closest_distance < other_distance * parameters.maximum_distance_ratio;
+    if is_unambiguous {
+        kind
+    } else {
+        ObstacleKind::Robot
+    }
+}
+
+/// Averages the segments crossing the upper half of the body between shoulders and hip, where
+/// the jersey is worn, weighted by the length of the crossing
+fn mean_torso_color(
+    image_segments: &ImageSegments,
+    bounding_box: &BoundingBox,
+) -> Option<Vector3<f32>> {
+    let top = bounding_box.center.y - bounding_box.size.y / 2.0;
+    let columns = (bounding_box.center.x - bounding_box.size.x / 4.0).max(0.0) as u16
+        ..(bounding_box.center.x + bounding_box.size.x / 4.0).max(0.0) as u16;
+    let rows = (top + bounding_box.size.y * 0.2).max(0.0) as u16
+        ..(top + bounding_box.size.y * 0.45).max(0.0) as u16;
+    let scan_grid = &image_segments.scan_grid;
+    let (sum, length) = crossings(&scan_grid.vertical_scan_lines, &columns, &rows)
+        .chain(crossings(&scan_grid.horizontal_scan_lines, &rows, &columns))
+        .fold(
+            (Vector3::zeros(), 0),
+            |(sum, total_length), (color, length)| {
+                (
+                    sum + vector![color.y as f32, color.cb as f32, color.cr as f32] * length as f32,
+                    total_length + length as u32,
+                )
+            },
+        );
+    (length > 0).then(|| sum / length as f32)
+}
+
+/// Colors of the segments of all scan lines positioned in `across`, with the lengths they overlap
+/// `along`
+fn crossings<'a>(
+    scan_lines: &'a [ScanLine],
+    across: &'a Range<u16>,
+    along: &'a Range<u16>,
+) -> impl Iterator<Item = (YCbCr444, u16)> + 'a {
+    scan_lines
         .iter()
-        .flat_map(|pixel| [pixel.y1, pixel.y2])
-        .collect();
+        .filter(|scan_line| across.contains(&scan_line.position))
+        .flat_map(|scan_line| scan_line.segments.iter())
+        .map(|segment| {
+            let overlap = segment
+                .end
+                .min(along.end)
+                .saturating_sub(segment.start.max(along.start));
+            (segment.color, overlap)
+        })
+}
+
+fn color_distance(color: Vector3<f32>, reference: YCbCr444, luminance_weight: f32) -> f32 {
+    let difference = color - vector![reference.y as f32, reference.cb as f32, reference.cr as f32];
+    vector![luminance_weight * difference.x, difference.y, difference.z].norm()
+}
+
+fn reference_color(team_color: TeamColor) -> Rgb {
+    match team_color {
+        TeamColor::Blue => Rgb::new(0, 0, 255),
+        TeamColor::Red => Rgb::new(255, 0, 0),
+        TeamColor::Yellow => Rgb::new(255, 255, 0),
+        TeamColor::Black => Rgb::new(0, 0, 0),
+        TeamColor::White => Rgb::new(255, 255, 255),
+        TeamColor::Green => Rgb::new(0, 128, 0),
+        TeamColor::Orange => Rgb::new(255, 165, 0),
+        TeamColor::Purple => Rgb::new(128, 0, 128),
+        TeamColor::Brown => Rgb::new(139, 69, 19),
+        TeamColor::Gray => Rgb::new(128, 128, 128),
+    }
+}
+
+fn generate_luminance_image(image: &YCbCr422Image) -> Result<GrayscaleImage, ImageBufferError> {
+    let grayscale_buffer = image.luminance();
     let y_image = ImageView::from_buffer(
         NonZeroU32::new(image.width()).unwrap(),
         NonZeroU32::new(image.height()).unwrap(),
@@ -318,3 +438,72 @@ fn box_from_network_data(
         distance: distance * OUTPUT_SCALING,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::point;
+    use types::{test_support::GameControllerStateBuilder, EdgeType, Intensity, ScanGrid, Segment};
+
+    use super::*;
+
+    fn robot_wearing(jersey: Rgb) -> (ImageSegments, BoundingBox) {
+        let segment = Segment {
+            start: 0,
+            end: 40,
+            start_edge_type: EdgeType::ImageBorder,
+            end_edge_type: EdgeType::ImageBorder,
+            color: YCbCr444::from(jersey),
+            field_color: Intensity::Low,
+        };
+        let scan_lines = (0..40)
+            .step_by(4)
+            .map(|position| ScanLine {
+                position,
+                segments: vec![segment],
+            })
+            .collect();
+        let image_segments = ImageSegments {
+            scan_grid: ScanGrid {
+                vertical_scan_lines: scan_lines,
+                horizontal_scan_lines: vec![],
+            },
+        };
+        let bounding_box = BoundingBox {
+            center: point![20.0, 20.0],
+            size: vector![20.0, 40.0],
+            probability: 1.0,
+            distance: 1.0,
+        };
+        (image_segments, bounding_box)
+    }
+
+    #[test]
+    fn jerseys_are_assigned_to_the_closest_team() {
+        let game_controller_state = GameControllerStateBuilder::default().build();
+        let parameters = JerseyClassification {
+            enable: true,
+            luminance_weight: 0.5,
+            maximum_color_distance: 60.0,
+            maximum_distance_ratio: 0.7,
+        };
+        let classify = |jersey| {
+            let (image_segments, bounding_box) = robot_wearing(jersey);
+            classify_jersey(
+                &image_segments,
+                &bounding_box,
+                &game_controller_state,
+                &parameters,
+            )
+        };
+
+        assert!(matches!(
+            classify(Rgb::new(0, 0, 255)),
+            ObstacleKind::Teammate
+        ));
+        assert!(matches!(
+            classify(Rgb::new(240, 10, 10)),
+            ObstacleKind::Opponent
+        ));
+        assert!(matches!(classify(Rgb::new(0, 128, 0)), ObstacleKind::Robot));
+    }
+}
@@ -0,0 +1,45 @@
+local inspect = require 'inspect'
+
+function spawn_robot(number)
+  table.insert(state.robots, create_robot(number))
+end
+
+spawn_robot(1)
+spawn_robot(2)
+spawn_robot(3)
+spawn_robot(4)
+spawn_robot(5)
+spawn_robot(6)
+spawn_robot(7)
+
+state.ball = {
+  position = { 0.0, 0.0 },
+  velocity = { 0.0, 0.0 },
+}
+
+function on_goal()
+  print("Goal scored, score is now " .. inspect(state.score))
+  state.ball = {
+    position = { 0.0, 0.0 },
+    velocity = { 0.0, 0.0 },
+  }
+end
+
+function on_cycle()
+  if state.cycle_count == 100 then
+    start_game("Hulks")
+  end
+
+  -- penalties expire after 45 seconds, i.e. 3750 cycles
+  if state.cycle_count == 6000 then
+    penalize(3, "PlayerPushing")
+  end
+
+  if state.cycle_count == 8000 then
+    free_kick("KickIn", "Opponent")
+  end
+
+  if state.cycle_count == 15000 then
+    state.finished = true
+  end
+end
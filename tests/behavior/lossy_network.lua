@@ -0,0 +1,48 @@
+function spawn_robot(number)
+  table.insert(state.robots, create_robot(number))
+end
+
+spawn_robot(1)
+spawn_robot(2)
+spawn_robot(3)
+spawn_robot(4)
+spawn_robot(5)
+spawn_robot(6)
+spawn_robot(7)
+
+state.ball = {
+  position = { 0.0, 0.0 },
+  velocity = { 0.0, 0.0 },
+}
+
+state.network = {
+  default = {
+    latency_mean = 0.1,
+    latency_standard_deviation = 0.05,
+    drop_probability = 0.3,
+  },
+  links = {
+    -- the keeper barely reaches the striker
+    {
+      sender = "One",
+      receiver = "Five",
+      latency_mean = 0.5,
+      latency_standard_deviation = 0.2,
+      drop_probability = 0.8,
+    },
+  },
+}
+
+function on_goal()
+  state.finished = true
+end
+
+function on_cycle()
+  if state.cycle_count == 100 then
+    start_game("Hulks")
+  end
+
+  if state.cycle_count == 15000 then
+    state.finished = true
+  end
+end
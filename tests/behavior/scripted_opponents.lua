@@ -0,0 +1,34 @@
+for number = 1, 5 do
+  table.insert(state.robots, create_robot(number))
+end
+
+state.scripted_opponents = {
+  { player_number = "One", position = { 4.3, 0.0 }, orientation = math.pi, policy = "Defend" },
+  { player_number = "Two", position = { 2.0, 1.0 }, orientation = math.pi, policy = "ChaseBall" },
+  { player_number = "Three", position = { 2.0, -1.0 }, orientation = math.pi, policy = "ChaseBall" },
+  { player_number = "Four", position = { 1.0, 0.0 }, orientation = math.pi, policy = "Stand" },
+}
+
+state.ball = {
+  position = { 0.0, 0.0 },
+  velocity = { 0.0, 0.0 },
+}
+
+assert_goal_scored_by(300.0, "Hulks")
+
+function on_goal()
+  state.ball = {
+    position = { 0.0, 0.0 },
+    velocity = { 0.0, 0.0 },
+  }
+end
+
+function on_cycle()
+  if state.cycle_count == 100 then
+    start_game("Hulks")
+  end
+
+  if state.cycle_count == 25000 then
+    state.finished = true
+  end
+end
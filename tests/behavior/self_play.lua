@@ -0,0 +1,29 @@
+-- 5 vs 5 game of our behavior against itself, the opponents use the same parameters
+for number = 1, 5 do
+  table.insert(state.robots, create_robot(number))
+  table.insert(state.opponents, create_robot(number))
+end
+
+state.ball = {
+  position = { 0.0, 0.0 },
+  velocity = { 0.0, 0.0 },
+}
+
+function on_goal()
+  print("Goal scored, score is now " .. state.score.hulks .. ":" .. state.score.opponent)
+  state.ball = {
+    position = { 0.0, 0.0 },
+    velocity = { 0.0, 0.0 },
+  }
+end
+
+function on_cycle()
+  if state.cycle_count == 100 then
+    start_game("Hulks")
+  end
+
+  -- 10 minutes
+  if state.cycle_count == 50000 then
+    state.finished = true
+  end
+end
@@ -0,0 +1,39 @@
+function spawn_robot(number)
+  table.insert(state.robots, create_robot(number))
+end
+
+spawn_robot(1)
+spawn_robot(2)
+spawn_robot(3)
+spawn_robot(4)
+spawn_robot(5)
+spawn_robot(6)
+spawn_robot(7)
+
+state.ball = {
+  position = { 0.0, 0.0 },
+  velocity = { 0.0, 0.0 },
+}
+
+-- ready and set take 50 seconds, leave the striker 60 seconds to score
+assert_goal_scored_by(110.0, "Hulks")
+
+function on_goal()
+  checkpoint("goal")
+  state.finished = true
+end
+
+function on_cycle()
+  if state.cycle_count == 100 then
+    start_game("Hulks")
+  end
+
+  if state.cycle_count == 3000 then
+    checkpoint("ready finished")
+    assert_robot_inside(1, { min = { -4.5, -0.75 }, max = { -3.9, 0.75 } }, "keeper inside goal area")
+  end
+
+  if state.cycle_count == 10000 then
+    state.finished = true
+  end
+end
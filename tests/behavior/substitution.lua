@@ -0,0 +1,33 @@
+for number = 1, 5 do
+  table.insert(state.robots, create_robot(number))
+end
+
+function on_goal()
+  place_ball({ 0.0, 0.0 })
+end
+
+function on_cycle()
+  if state.cycle_count == 100 then
+    start_game("Hulks")
+    place_ball({ 0.0, 0.0 })
+  end
+
+  -- the referee whistles early
+  if state.cycle_count == 4000 then
+    whistle()
+  end
+
+  -- the striker is substituted by player 6
+  if state.cycle_count == 6000 then
+    remove_robot(5)
+    add_robot(6)
+  end
+
+  if state.cycle_count == 7000 then
+    set_ball_velocity({ -1.0, 0.5 })
+  end
+
+  if state.cycle_count == 15000 then
+    state.finished = true
+  end
+end
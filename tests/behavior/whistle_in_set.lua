@@ -0,0 +1,43 @@
+function spawn_robot(number)
+  table.insert(state.robots, create_robot(number))
+end
+
+spawn_robot(1)
+spawn_robot(2)
+spawn_robot(3)
+spawn_robot(4)
+spawn_robot(5)
+spawn_robot(6)
+spawn_robot(7)
+
+state.ball = {
+  position = { 0.0, 0.0 },
+  velocity = { 0.0, 0.0 },
+}
+
+-- some robots miss the kick-off whistle, others imagine one in Set
+state.whistle = {
+  detection_probability = 0.7,
+  false_positive_rate = 0.05,
+  game_controller_delay = 2.0,
+  confirmation_timeout = 4.0,
+}
+
+function on_goal()
+  state.finished = true
+end
+
+function on_cycle()
+  if state.cycle_count == 100 then
+    start_game("Hulks")
+  end
+
+  -- a single robot hears a whistle early in Set and has to return to Set
+  if state.cycle_count == 3900 then
+    hear_whistle(4)
+  end
+
+  if state.cycle_count == 10000 then
+    state.finished = true
+  end
+end
@@ -1,7 +1,10 @@
+use std::{env::var, fs::write, path::PathBuf};
+
 use code_generation::{generate, write_to_file::WriteToFile};
 use color_eyre::eyre::{Result, WrapErr};
 use source_analyzer::{
     cyclers::{CyclerKind, Cyclers},
+    graph::Graph,
     manifest::{CyclerManifest, FrameworkManifest},
     pretty::to_string_pretty,
     structs::Structs,
@@ -17,9 +20,11 @@ fn main() -> Result<()> {
                 setup_nodes: vec!["control::fake_data"],
                 nodes: vec![
                     "control::active_vision",
+                    "control::ball_search_heatmap",
                     "control::ball_state_composer",
                     "control::behavior::node",
                     "control::dribble_path_planner",
+                    "control::kick_off_positioning",
                     "control::kick_selector",
                     "control::motion::look_around",
                     "control::role_assignment",
@@ -48,6 +53,13 @@ fn main() -> Result<()> {
     println!();
     println!("{}", to_string_pretty(&cyclers)?);
 
+    let graph = Graph::from_cyclers(&cyclers);
+    let out_dir = PathBuf::from(var("OUT_DIR")?);
+    write(out_dir.join("module_graph.dot"), graph.to_dot()?)
+        .wrap_err("failed to write module graph as DOT")?;
+    write(out_dir.join("module_graph.json"), graph.to_json()?)
+        .wrap_err("failed to write module graph as JSON")?;
+
     let structs = Structs::try_from_cyclers(&cyclers)?;
     generate(&cyclers, &structs)
         .write_to_file("generated_code.rs")
@@ -0,0 +1,139 @@
+use std::{mem::take, path::PathBuf, time::Duration};
+
+use nalgebra::Point2;
+use serde::{Deserialize, Serialize};
+use spl_network_messages::Team;
+
+use crate::state::Event;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Region {
+    pub min: Point2<f32>,
+    pub max: Point2<f32>,
+}
+
+impl Region {
+    pub fn contains(&self, point: Point2<f32>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AssertionResult {
+    pub name: String,
+    pub passed: bool,
+    /// Simulated time in seconds when the assertion was decided
+    pub time: f32,
+    pub message: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Checkpoint {
+    pub name: String,
+    pub time: f32,
+}
+
+/// Outcome of a scenario, serialized as JSON for CI
+#[derive(Clone, Debug, Serialize)]
+pub struct Report {
+    pub scenario: PathBuf,
+    pub passed: bool,
+    pub simulated_time: f32,
+    pub assertions: Vec<AssertionResult>,
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+struct PendingGoal {
+    name: String,
+    scoring_team: Option<Team>,
+    deadline: Duration,
+}
+
+/// Assertions and checkpoints registered by the scenario script
+///
+/// Immediate assertions are decided when they are called. Goal assertions stay pending until the
+/// expected goal happens and fail once their deadline passes or the simulation finishes.
+#[derive(Default)]
+pub struct Assertions {
+    results: Vec<AssertionResult>,
+    checkpoints: Vec<Checkpoint>,
+    pending_goals: Vec<PendingGoal>,
+}
+
+impl Assertions {
+    pub fn assert(&mut self, name: String, passed: bool, message: Option<String>, now: Duration) {
+        self.results.push(AssertionResult {
+            name,
+            passed,
+            time: now.as_secs_f32(),
+            message: (!passed).then_some(message).flatten(),
+        });
+    }
+
+    pub fn expect_goal(&mut self, scoring_team: Option<Team>, deadline: Duration) {
+        let name = match scoring_team {
+            Some(team) => format!("goal scored by {team:?} by {:.1} s", deadline.as_secs_f32()),
+            None => format!("goal scored by {:.1} s", deadline.as_secs_f32()),
+        };
+        self.pending_goals.push(PendingGoal {
+            name,
+            scoring_team,
+            deadline,
+        });
+    }
+
+    pub fn checkpoint(&mut self, name: String, now: Duration) {
+        self.checkpoints.push(Checkpoint {
+            name,
+            time: now.as_secs_f32(),
+        });
+    }
+
+    pub fn update(&mut self, events: &[Event], now: Duration) {
+        for event in events {
+            if let Event::Goal { scoring_team } = event {
+                let (fulfilled, pending): (Vec<_>, Vec<_>) =
+                    self.pending_goals.drain(..).partition(|pending_goal| {
+                        pending_goal
+                            .scoring_team
+                            .map_or(true, |team| team == *scoring_team)
+                    });
+                self.pending_goals = pending;
+                for pending_goal in fulfilled {
+                    self.assert(pending_goal.name, true, None, now);
+                }
+            }
+        }
+        let (expired, pending): (Vec<_>, Vec<_>) = self
+            .pending_goals
+            .drain(..)
+            .partition(|pending_goal| pending_goal.deadline < now);
+        self.pending_goals = pending;
+        for pending_goal in expired {
+            self.assert(
+                pending_goal.name,
+                false,
+                Some("deadline passed without goal".to_string()),
+                now,
+            );
+        }
+    }
+
+    pub fn into_report(mut self, scenario: PathBuf, now: Duration) -> Report {
+        for pending_goal in take(&mut self.pending_goals) {
+            self.assert(
+                pending_goal.name,
+                false,
+                Some("simulation finished without goal".to_string()),
+                now,
+            );
+        }
+        Report {
+            scenario,
+            passed: self.results.iter().all(|result| result.passed),
+            simulated_time: now.as_secs_f32(),
+            assertions: self.results,
+            checkpoints: self.checkpoints,
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    collections::BTreeMap,
+    fs::{read_to_string, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serialize_hierarchy::SerializeHierarchy;
+use tokio_util::sync::CancellationToken;
+
+use crate::{metrics::Metrics, simulator::Simulator};
+
+/// Parameter sweep read from a JSON file
+///
+/// Parameters are addressed by their path, e.g. `"behavior.role_positions.defender_y_offset"`,
+/// and are overridden for every robot after the scenario script created them.
+#[derive(Debug, Deserialize)]
+pub struct Sweep {
+    /// Every configuration is simulated this often, each run with a different seed
+    #[serde(default = "default_runs_per_configuration")]
+    pub runs_per_configuration: usize,
+    #[serde(flatten)]
+    pub mode: SweepMode,
+}
+
+fn default_runs_per_configuration() -> usize {
+    1
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum SweepMode {
+    /// Simulates every combination of the listed values
+    Grid {
+        parameters: BTreeMap<String, Vec<Value>>,
+    },
+    /// Simulates uniformly sampled values from the given ranges
+    Random {
+        samples: usize,
+        #[serde(default)]
+        seed: u64,
+        parameters: BTreeMap<String, Range>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Range {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Sweep {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let content = read_to_string(path).wrap_err("failed to read sweep file")?;
+        serde_json::from_str(&content).wrap_err("failed to parse sweep file")
+    }
+
+    pub fn configurations(&self) -> Vec<BTreeMap<String, Value>> {
+        match &self.mode {
+            SweepMode::Grid { parameters } => {
+                parameters
+                    .iter()
+                    .fold(vec![BTreeMap::new()], |configurations, (path, values)| {
+                        configurations
+                            .iter()
+                            .flat_map(|configuration| {
+                                values.iter().map(move |value| {
+                                    let mut configuration = configuration.clone();
+                                    configuration.insert(path.clone(), value.clone());
+                                    configuration
+                                })
+                            })
+                            .collect()
+                    })
+            }
+            SweepMode::Random {
+                samples,
+                seed,
+                parameters,
+            } => {
+                let mut random_number_generator = StdRng::seed_from_u64(*seed);
+                (0..*samples)
+                    .map(|_| {
+                        parameters
+                            .iter()
+                            .map(|(path, range)| {
+                                let value = if range.min < range.max {
+                                    random_number_generator.gen_range(range.min..range.max)
+                                } else {
+                                    range.min
+                                };
+                                (path.clone(), Value::from(value))
+                            })
+                            .collect()
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunSummary {
+    pub configuration: usize,
+    pub run: usize,
+    pub parameters: BTreeMap<String, Value>,
+    pub passed: bool,
+    pub metrics: Metrics,
+}
+
+pub fn run_batch(
+    scenario_file: &Path,
+    sweep: &Sweep,
+    seed: u64,
+    keep_running: &CancellationToken,
+) -> Result<Vec<RunSummary>> {
+    let configurations = sweep.configurations();
+    let mut summaries = Vec::new();
+    for (configuration_index, parameters) in configurations.iter().enumerate() {
+        for run in 0..sweep.runs_per_configuration {
+            if keep_running.is_cancelled() {
+                return Ok(summaries);
+            }
+            println!(
+                "Configuration {}/{}, run {}/{}",
+                configuration_index + 1,
+                configurations.len(),
+                run + 1,
+                sweep.runs_per_configuration,
+            );
+            let (passed, metrics) = simulate(scenario_file, parameters, seed + run as u64)
+                .wrap_err_with(|| {
+                    format!("failed to simulate configuration {configuration_index}, run {run}")
+                })?;
+            summaries.push(RunSummary {
+                configuration: configuration_index,
+                run,
+                parameters: parameters.clone(),
+                passed,
+                metrics,
+            });
+        }
+    }
+    Ok(summaries)
+}
+
+fn simulate(
+    scenario_file: &Path,
+    parameters: &BTreeMap<String, Value>,
+    seed: u64,
+) -> Result<(bool, Metrics)> {
+    let mut simulator = Simulator::try_new()?;
+    simulator.seed(seed)?;
+    simulator.execute_script(scenario_file)?;
+    {
+        let mut state = simulator.state.lock();
+        for robot in state.robots.values_mut() {
+            for (path, value) in parameters {
+                robot
+                    .parameters
+                    .deserialize_path(path, value.clone())
+                    .wrap_err_with(|| format!("failed to override parameter {path}"))?;
+            }
+        }
+    }
+    simulator.run_to_end()?;
+
+    let report = simulator.report(PathBuf::from(scenario_file));
+    let metrics = simulator.state.lock().metrics.clone();
+    Ok((report.passed, metrics))
+}
+
+/// Writes the summaries as CSV if the file ends with `.csv`, as JSON otherwise
+pub fn write_summary(path: &Path, summaries: &[RunSummary]) -> Result<()> {
+    let file = File::create(path).wrap_err("failed to create summary file")?;
+    let is_csv = path
+        .extension()
+        .map_or(false, |extension| extension == "csv");
+    if is_csv {
+        write_csv(BufWriter::new(file), summaries).wrap_err("failed to write CSV summary")
+    } else {
+        serde_json::to_writer_pretty(file, summaries).wrap_err("failed to write JSON summary")
+    }
+}
+
+fn write_csv(mut writer: impl Write, summaries: &[RunSummary]) -> Result<()> {
+    let Some(first) = summaries.first() else {
+        bail!("no runs to summarize");
+    };
+    let parameter_paths: Vec<_> = first.parameters.keys().collect();
+
+    let mut header = vec!["configuration".to_string(), "run".to_string()];
+    header.extend(parameter_paths.iter().map(|path| escape(path)));
+    header.extend(
+        [
+            "passed",
+            "goals_scored",
+            "goals_conceded",
+            "time_to_first_kick",
+            "falls",
+            "simulated_time",
+        ]
+        .map(String::from),
+    );
+    writeln!(writer, "{}", header.join(","))?;
+
+    for summary in summaries {
+        let mut row = vec![summary.configuration.to_string(), summary.run.to_string()];
+        row.extend(
+            parameter_paths
+                .iter()
+                .map(|path| escape(&summary.parameters[*path].to_string())),
+        );
+        let metrics = &summary.metrics;
+        row.extend([
+            summary.passed.to_string(),
+            metrics.goals_scored.to_string(),
+            metrics.goals_conceded.to_string(),
+            metrics
+                .time_to_first_kick
+                .map(|time| time.to_string())
+                .unwrap_or_default(),
+            metrics.falls.to_string(),
+            metrics.simulated_time.to_string(),
+        ]);
+        writeln!(writer, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -3,9 +3,11 @@ use std::{collections::BTreeMap, sync::Arc, time::SystemTime};
 use color_eyre::{eyre::WrapErr, Result};
 use control::{
     active_vision::{self, ActiveVision},
+    ball_search_heatmap::{self, BallSearchHeatmap},
     ball_state_composer::{self, BallStateComposer},
     behavior::node::{self, Behavior},
     dribble_path_planner::DribblePath,
+    kick_off_positioning::{self, KickOffPositioning},
     kick_selector::{self, KickSelector},
     motion::look_around::LookAround,
     role_assignment::{self, RoleAssignment},
@@ -38,9 +40,11 @@ pub struct BehaviorCycler {
     hardware_interface: Arc<Interfake>,
     own_changed: Arc<Notify>,
     active_vision: ActiveVision,
+    ball_search_heatmap: BallSearchHeatmap,
     ball_state_composer: BallStateComposer,
     behavior: Behavior,
     dribble_path: DribblePath,
+    kick_off_positioning: KickOffPositioning,
     kick_selector: KickSelector,
     look_around: LookAround,
     role_assignment: RoleAssignment,
@@ -62,6 +66,11 @@ impl BehaviorCycler {
             field_dimensions: &parameters.field_dimensions,
         })
         .wrap_err("failed to create node `ActiveVision`")?;
+        let ball_search_heatmap = BallSearchHeatmap::new(ball_search_heatmap::CreationContext {
+            field_dimensions: &parameters.field_dimensions,
+            parameters: &parameters.ball_search_heatmap,
+        })
+        .wrap_err("failed to create node `BallSearchHeatmap`")?;
         let ball_state_composer = BallStateComposer::new(ball_state_composer::CreationContext {})
             .wrap_err("failed to create node `BallStateComposer`")?;
         let dribble_path = control::dribble_path_planner::DribblePath::new(
@@ -74,6 +83,9 @@ impl BehaviorCycler {
             lost_ball_parameters: &parameters.behavior.lost_ball,
         })
         .wrap_err("failed to create node `Behavior`")?;
+        let kick_off_positioning =
+            KickOffPositioning::new(kick_off_positioning::CreationContext {})
+                .wrap_err("failed to create node `KickOffPositioning`")?;
         let kick_selector = KickSelector::new(kick_selector::CreationContext {})
             .wrap_err("failed to create node `KickSelector`")?;
         let look_around = control::motion::look_around::LookAround::new(
@@ -102,10 +114,12 @@ impl BehaviorCycler {
             own_changed,
 
             active_vision,
+            ball_search_heatmap,
             time_to_reach_kick_position,
             ball_state_composer,
             behavior,
             dribble_path,
+            kick_off_positioning,
             kick_selector,
             look_around,
             role_assignment,
@@ -156,10 +170,12 @@ impl BehaviorCycler {
                     ball_position: own_database.main_outputs.ball_position.as_ref(),
                     fall_state: &own_database.main_outputs.fall_state,
                     game_controller_state: own_database.main_outputs.game_controller_state.as_ref(),
+                    network_quality: &own_database.main_outputs.network_quality,
                     primary_state: &own_database.main_outputs.primary_state,
                     robot_to_field: own_database.main_outputs.robot_to_field.as_ref(),
                     cycle_time: &own_database.main_outputs.cycle_time,
                     time_to_reach_kick_position: &mut persistent_state.time_to_reach_kick_position,
+                    intention: &mut persistent_state.intention,
                     field_dimensions: &parameters.field_dimensions,
                     forced_role: parameters.role_assignment.forced_role.as_ref(),
                     keeper_replacementkeeper_switch_time: &parameters
@@ -170,7 +186,7 @@ impl BehaviorCycler {
                     player_number: &parameters.player_number,
                     spl_network: &parameters.spl_network,
                     network_message: PerceptionInput {
-                        persistent: incoming_messages,
+                        persistent: incoming_messages.clone(),
                         temporary: Default::default(),
                     },
                     hardware: &self.hardware_interface,
@@ -180,6 +196,31 @@ impl BehaviorCycler {
             own_database.main_outputs.network_robot_obstacles =
                 main_outputs.network_robot_obstacles.value;
             own_database.main_outputs.role = main_outputs.role.value;
+            own_database.main_outputs.teammate_intentions = main_outputs.teammate_intentions.value;
+            own_database.main_outputs.teammate_poses = main_outputs.teammate_poses.value;
+            own_database.main_outputs.last_role_update = main_outputs.last_role_update.value;
+        }
+        {
+            let main_outputs = self
+                .kick_off_positioning
+                .cycle(kick_off_positioning::CycleContext {
+                    slot_assignment: AdditionalOutput::new(
+                        true,
+                        &mut own_database.additional_outputs.kick_off_slot_assignment,
+                    ),
+                    cycle_time: &own_database.main_outputs.cycle_time,
+                    game_controller_state: own_database.main_outputs.game_controller_state.as_ref(),
+                    robot_to_field: own_database.main_outputs.robot_to_field.as_ref(),
+                    role: &own_database.main_outputs.role,
+                    network_message: PerceptionInput {
+                        persistent: incoming_messages.clone(),
+                        temporary: Default::default(),
+                    },
+                    parameters: &parameters.kick_off_positioning,
+                    player_number: &parameters.player_number,
+                })
+                .wrap_err("failed to execute cycle of node `KickOffPositioning`")?;
+            own_database.main_outputs.kick_off_pose = main_outputs.kick_off_pose.value;
         }
         {
             let main_outputs = self
@@ -196,6 +237,7 @@ impl BehaviorCycler {
                     primary_state: &own_database.main_outputs.primary_state,
                     field_dimensions: &parameters.field_dimensions,
                     game_controller_state: own_database.main_outputs.game_controller_state.as_ref(),
+                    team_ball_timeout: &parameters.ball_state_composer.team_ball_timeout,
                 })
                 .wrap_err("failed to execute cycle of node `BallStateComposer`")?;
             own_database.main_outputs.ball_state = main_outputs.ball_state.value;
@@ -210,6 +252,7 @@ impl BehaviorCycler {
                     rule_ball: own_database.main_outputs.ball_state.as_ref(),
                     cycle_time: &own_database.main_outputs.cycle_time,
                     obstacles: &own_database.main_outputs.obstacles,
+                    field_dimensions: &parameters.field_dimensions,
                     parameters: &parameters.behavior.look_action,
                     robot_to_field: own_database.main_outputs.robot_to_field.as_ref(),
                 })
@@ -230,8 +273,13 @@ impl BehaviorCycler {
                                 .robot_to_field
                                 .as_ref()
                                 .unwrap(),
+                            localization_quality: own_database
+                                .main_outputs
+                                .localization_quality
+                                .as_ref(),
                             ball_state: own_database.main_outputs.ball_state.as_ref().unwrap(),
                             obstacles: &own_database.main_outputs.obstacles,
+                            joint_faults: &own_database.main_outputs.joint_faults,
                             field_dimensions: &parameters.field_dimensions,
                             in_walk_kicks: &parameters.in_walk_kicks,
                             angle_distance_weight: &parameters.kick_selector.angle_distance_weight,
@@ -245,6 +293,10 @@ impl BehaviorCycler {
                                 .kick_selector
                                 .ball_radius_for_kick_target_selection,
                             closer_threshold: &parameters.kick_selector.closer_threshold,
+                            opponent_lane_clearance: &parameters
+                                .kick_selector
+                                .opponent_lane_clearance,
+                            opponent_lane_weight: &parameters.kick_selector.opponent_lane_weight,
                             find_kick_targets: &parameters.kick_selector.find_kick_targets,
                             kick_targets: framework::AdditionalOutput::new(
                                 true,
@@ -256,6 +308,9 @@ impl BehaviorCycler {
                             ),
                             default_kick_strength: &parameters.kick_selector.default_kick_strength,
                             corner_kick_strength: &parameters.kick_selector.corner_kick_strength,
+                            conservative_kick_strength: &parameters
+                                .kick_selector
+                                .conservative_kick_strength,
                             invisible_ball_timeout: &parameters
                                 .kick_selector
                                 .invisible_ball_timeout,
@@ -282,20 +337,32 @@ impl BehaviorCycler {
                         .penalty_shot_direction
                         .as_ref(),
                     robot_to_field: own_database.main_outputs.robot_to_field.as_ref(),
+                    localization_quality: own_database.main_outputs.localization_quality.as_ref(),
                     kick_decisions: own_database.main_outputs.kick_decisions.as_ref(),
                     instant_kick_decisions: own_database
                         .main_outputs
                         .instant_kick_decisions
                         .as_ref(),
                     player_number: &parameters.player_number,
+                    parameters: &parameters.world_state_composer,
+                    cycle_time: &own_database.main_outputs.cycle_time,
                     fall_state: &own_database.main_outputs.fall_state,
                     has_ground_contact: &own_database.main_outputs.has_ground_contact,
                     obstacles: &own_database.main_outputs.obstacles,
                     primary_state: &own_database.main_outputs.primary_state,
                     role: &own_database.main_outputs.role,
                     position_of_interest: &own_database.main_outputs.position_of_interest,
+                    system_warnings: &own_database.main_outputs.system_warnings,
                     rule_ball: own_database.main_outputs.rule_ball_state.as_ref(),
+                    blind_ball_contact: own_database.main_outputs.blind_ball_contact.as_ref(),
                     rule_obstacles: &own_database.main_outputs.rule_obstacles,
+                    teammate_intentions: &own_database.main_outputs.teammate_intentions,
+                    teammate_poses: &own_database.main_outputs.teammate_poses,
+                    last_obstacle_measurement: own_database
+                        .main_outputs
+                        .last_obstacle_measurement
+                        .as_ref(),
+                    last_role_update: own_database.main_outputs.last_role_update.as_ref(),
                 })
                 .wrap_err("failed to execute cycle of node `WorldStateComposer`")?;
             own_database.main_outputs.world_state = main_outputs.world_state.value;
@@ -311,11 +378,39 @@ impl BehaviorCycler {
                             true,
                             &mut own_database.additional_outputs.time_to_reach_obstacles,
                         ),
+                        number_of_replans: framework::AdditionalOutput::new(
+                            true,
+                            &mut own_database.additional_outputs.dribble_path_replans,
+                        ),
+                        cycle_time: &own_database.main_outputs.cycle_time,
                     })
                     .wrap_err("failed to execute cycle of `DribblePath`")?
             };
             own_database.main_outputs.dribble_path = main_outputs.dribble_path.value;
         }
+        {
+            let main_outputs = self
+                .ball_search_heatmap
+                .cycle(ball_search_heatmap::CycleContext {
+                    ball_search_heatmap: AdditionalOutput::new(
+                        true,
+                        &mut own_database.additional_outputs.ball_search_heatmap,
+                    ),
+                    cycle_time: &own_database.main_outputs.cycle_time,
+                    obstacles: &own_database.main_outputs.obstacles,
+                    robot_to_field: own_database.main_outputs.robot_to_field.as_ref(),
+                    sensor_data: &own_database.main_outputs.sensor_data,
+                    network_message: PerceptionInput {
+                        persistent: incoming_messages,
+                        temporary: Default::default(),
+                    },
+                    parameters: &parameters.ball_search_heatmap,
+                    player_number: &parameters.player_number,
+                })
+                .wrap_err("failed to execute cycle of node `BallSearchHeatmap`")?;
+            own_database.main_outputs.ball_search_position =
+                main_outputs.ball_search_position.value;
+        }
         {
             let main_outputs = self
                 .behavior
@@ -331,6 +426,10 @@ impl BehaviorCycler {
                     world_state: &own_database.main_outputs.world_state,
                     cycle_time: &own_database.main_outputs.cycle_time,
                     dribble_path: own_database.main_outputs.dribble_path.as_ref(),
+                    ball_search_position: own_database.main_outputs.ball_search_position.as_ref(),
+                    kick_off_pose: own_database.main_outputs.kick_off_pose.as_ref(),
+                    motion_test_command: own_database.main_outputs.motion_test_command.as_ref(),
+                    sensor_data: &own_database.main_outputs.sensor_data,
                     parameters: &parameters.behavior,
                     in_walk_kicks: &parameters.in_walk_kicks,
                     field_dimensions: &parameters.field_dimensions,
@@ -338,7 +437,8 @@ impl BehaviorCycler {
                     intercept_ball_parameters: &parameters.behavior.intercept_ball,
                     has_ground_contact: &true,
                     maximum_step_size: &parameters.step_planner.max_step_size,
-                    striker_set_position: &parameters.behavior.role_positions.striker_set_position,
+                    intention: &mut persistent_state.intention,
+                    motion_safe_exits: &mut persistent_state.motion_safe_exits,
                 })
                 .wrap_err("failed to execute cycle of node `Behavior`")?;
             own_database.main_outputs.motion_command = main_outputs.motion_command.value;
@@ -361,11 +461,12 @@ impl BehaviorCycler {
             own_database.main_outputs.look_around = main_outputs.look_around.value;
         }
         {
-            let _main_outputs = self
+            let main_outputs = self
                 .time_to_reach_kick_position
                 .cycle(control::time_to_reach_kick_position::CycleContext {
                     time_to_reach_kick_position: &mut persistent_state.time_to_reach_kick_position,
                     dribble_path: own_database.main_outputs.dribble_path.as_ref(),
+                    kick_decisions: own_database.main_outputs.kick_decisions.as_ref(),
                     stand_up_back_estimated_remaining_duration: own_database
                         .main_outputs
                         .stand_up_back_estimated_remaining_duration
@@ -375,14 +476,10 @@ impl BehaviorCycler {
                         .stand_up_front_estimated_remaining_duration
                         .as_ref(),
                     configuration: &parameters.behavior,
-                    time_to_reach_kick_position_output: framework::AdditionalOutput::new(
-                        true,
-                        &mut own_database
-                            .additional_outputs
-                            .time_to_reach_kick_position_output,
-                    ),
                 })
-                .wrap_err("failed to execute cycle of `TimeToReachKickPosition`");
+                .wrap_err("failed to execute cycle of `TimeToReachKickPosition`")?;
+            own_database.main_outputs.time_to_reach_kick_position =
+                main_outputs.time_to_reach_kick_position.value;
         }
         self.own_changed.notify_one();
         Ok(())
@@ -0,0 +1,251 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+use spl_network_messages::{
+    GamePhase, GameState, Penalty, PlayerNumber, SubState, Team, TeamColor,
+};
+use types::{FilteredGameState, GameControllerFreshness, GameControllerState, Players, TeamColors};
+
+use crate::opponent::opposing_team;
+
+const READY_DURATION: Duration = Duration::from_secs(45);
+/// Time the simulated referee waits in Set before whistling the kick-off
+const SET_DURATION: Duration = Duration::from_secs(5);
+const KICK_OFF_DURATION: Duration = Duration::from_secs(10);
+const SET_PLAY_DURATION: Duration = Duration::from_secs(30);
+const PENALTY_DURATION: Duration = Duration::from_secs(45);
+pub const HULKS_TEAM_COLORS: TeamColors = TeamColors {
+    field_player: TeamColor::Blue,
+    goal_keeper: TeamColor::Yellow,
+};
+pub const OPPONENT_TEAM_COLORS: TeamColors = TeamColors {
+    field_player: TeamColor::Red,
+    goal_keeper: TeamColor::Black,
+};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct Score {
+    pub hulks: u8,
+    pub opponent: u8,
+}
+
+/// Simulated GameController running the referee flow of the SPL rules
+///
+/// After the game started, the states advance by their timers: Ready ends after 45 seconds and
+/// the referee whistles the kick-off 5 seconds after Set. The GameController sends Playing after
+/// the delay of its operator following the whistle. Goals return to Ready with the kick-off
+/// for the conceding team. Set plays end after 30 seconds, penalties after 45 seconds except for
+/// manual penalties which last until the player is unpenalized.
+pub struct GameController {
+    game_state: GameState,
+    kicking_team: Team,
+    sub_state: Option<SubState>,
+    penalties: Players<Option<Penalty>>,
+    score: Score,
+    time_entered_game_state: Duration,
+    time_entered_sub_state: Duration,
+    whistled_at: Option<Duration>,
+}
+
+impl GameController {
+    pub fn start(kicking_team: Team, now: Duration) -> Self {
+        Self {
+            game_state: GameState::Ready,
+            kicking_team,
+            sub_state: None,
+            penalties: Default::default(),
+            score: Default::default(),
+            time_entered_game_state: now,
+            time_entered_sub_state: now,
+            whistled_at: None,
+        }
+    }
+
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    pub fn is_penalized(&self, player_number: PlayerNumber) -> bool {
+        self.penalties[player_number].is_some()
+    }
+
+    /// Advances the timers, returns whether the referee whistled the kick-off in this cycle
+    pub fn cycle(
+        &mut self,
+        now: Duration,
+        time_step: Duration,
+        game_controller_delay: Duration,
+    ) -> bool {
+        let time_in_game_state = now - self.time_entered_game_state;
+        let mut has_whistled = false;
+        match (self.game_state, self.whistled_at) {
+            (GameState::Ready, _) if time_in_game_state >= READY_DURATION => {
+                self.enter_game_state(GameState::Set, now)
+            }
+            (GameState::Set, None) if time_in_game_state >= SET_DURATION => {
+                has_whistled = self.whistle(now);
+            }
+            (GameState::Set, Some(whistled_at)) if now - whistled_at >= game_controller_delay => {
+                self.enter_game_state(GameState::Playing, now)
+            }
+            _ => {}
+        }
+
+        if self.sub_state.is_some() && now - self.time_entered_sub_state >= SET_PLAY_DURATION {
+            self.sub_state = None;
+        }
+
+        for player_number in all_player_numbers() {
+            self.penalties[player_number] =
+                self.penalties[player_number].and_then(|penalty| count_down(penalty, time_step));
+        }
+        has_whistled
+    }
+
+    pub fn goal(&mut self, scoring_team: Team, now: Duration) {
+        if self.game_state != GameState::Playing {
+            return;
+        }
+        match scoring_team {
+            Team::Hulks => self.score.hulks += 1,
+            Team::Opponent => self.score.opponent += 1,
+            Team::Uncertain => {}
+        }
+        self.kicking_team = match scoring_team {
+            Team::Hulks => Team::Opponent,
+            Team::Opponent | Team::Uncertain => Team::Hulks,
+        };
+        self.sub_state = None;
+        self.enter_game_state(GameState::Ready, now);
+    }
+
+    /// Ends Set early like the whistle of the referee, returns whether it had an effect
+    pub fn whistle(&mut self, now: Duration) -> bool {
+        if self.game_state != GameState::Set || self.whistled_at.is_some() {
+            return false;
+        }
+        self.whistled_at = Some(now);
+        true
+    }
+
+    /// Starts a set play, only possible while playing
+    pub fn free_kick(&mut self, sub_state: SubState, kicking_team: Team, now: Duration) -> bool {
+        if self.game_state != GameState::Playing {
+            return false;
+        }
+        self.sub_state = Some(sub_state);
+        self.kicking_team = kicking_team;
+        self.time_entered_sub_state = now;
+        true
+    }
+
+    pub fn penalize(&mut self, player_number: PlayerNumber, penalty: Penalty) {
+        self.penalties[player_number] = Some(penalty);
+    }
+
+    pub fn unpenalize(&mut self, player_number: PlayerNumber) {
+        self.penalties[player_number] = None;
+    }
+
+    pub fn game_controller_state(&self, remaining_amount_of_messages: u16) -> GameControllerState {
+        GameControllerState {
+            game_state: self.game_state,
+            game_phase: GamePhase::Normal,
+            kicking_team: self.kicking_team,
+            last_game_state_change: UNIX_EPOCH + self.time_entered_game_state,
+            penalties: self.penalties,
+            remaining_amount_of_messages,
+            sub_state: self.sub_state,
+            hulks_team_is_home_after_coin_toss: true,
+            freshness: GameControllerFreshness::Fresh,
+            hulks_team_colors: HULKS_TEAM_COLORS,
+            opponent_team_colors: OPPONENT_TEAM_COLORS,
+        }
+    }
+
+    /// Game state as the game state filter of a robot of `team` would conclude it from the
+    /// GameController, robots of both teams refer to their own team as `Team::Hulks`
+    pub fn filtered_game_state(&self, team: Team, now: Duration) -> FilteredGameState {
+        let kicking_team = match team {
+            Team::Opponent => opposing_team(self.kicking_team),
+            Team::Hulks | Team::Uncertain => self.kicking_team,
+        };
+        let opponent_is_kicking_team = kicking_team != Team::Hulks;
+        match self.game_state {
+            GameState::Initial => FilteredGameState::Initial,
+            GameState::Ready => FilteredGameState::Ready { kicking_team },
+            GameState::Set => FilteredGameState::Set,
+            GameState::Playing => {
+                let is_opponent_kick_off = now - self.time_entered_game_state < KICK_OFF_DURATION;
+                let is_opponent_set_play = self.sub_state.is_some();
+                FilteredGameState::Playing {
+                    ball_is_free: !(opponent_is_kicking_team
+                        && (is_opponent_kick_off || is_opponent_set_play)),
+                }
+            }
+            GameState::Finished => FilteredGameState::Finished,
+        }
+    }
+
+    fn enter_game_state(&mut self, game_state: GameState, now: Duration) {
+        self.game_state = game_state;
+        self.time_entered_game_state = now;
+        self.whistled_at = None;
+    }
+}
+
+/// Creates a penalty with the timer of the SPL rules from its name, e.g. `"PlayerPushing"`
+pub fn penalty_from_name(name: &str) -> Result<Penalty, String> {
+    let remaining = PENALTY_DURATION;
+    let penalty = match name {
+        "IllegalBallContact" => Penalty::IllegalBallContact { remaining },
+        "PlayerPushing" => Penalty::PlayerPushing { remaining },
+        "IllegalMotionInSet" => Penalty::IllegalMotionInSet { remaining },
+        "InactivePlayer" => Penalty::InactivePlayer { remaining },
+        "IllegalPosition" => Penalty::IllegalPosition { remaining },
+        "LeavingTheField" => Penalty::LeavingTheField { remaining },
+        "RequestForPickup" => Penalty::RequestForPickup { remaining },
+        "LocalGameStuck" => Penalty::LocalGameStuck { remaining },
+        "IllegalPositionInSet" => Penalty::IllegalPositionInSet { remaining },
+        "PlayerStance" => Penalty::PlayerStance { remaining },
+        "Substitute" => Penalty::Substitute { remaining },
+        "Manual" => Penalty::Manual {
+            remaining: Duration::ZERO,
+        },
+        name => return Err(format!("unknown penalty: {name}")),
+    };
+    Ok(penalty)
+}
+
+fn count_down(mut penalty: Penalty, time_step: Duration) -> Option<Penalty> {
+    match &mut penalty {
+        Penalty::Manual { .. } => {}
+        Penalty::IllegalBallContact { remaining }
+        | Penalty::PlayerPushing { remaining }
+        | Penalty::IllegalMotionInSet { remaining }
+        | Penalty::InactivePlayer { remaining }
+        | Penalty::IllegalPosition { remaining }
+        | Penalty::LeavingTheField { remaining }
+        | Penalty::RequestForPickup { remaining }
+        | Penalty::LocalGameStuck { remaining }
+        | Penalty::IllegalPositionInSet { remaining }
+        | Penalty::PlayerStance { remaining }
+        | Penalty::Substitute { remaining } => {
+            *remaining = remaining.checked_sub(time_step)?;
+        }
+    }
+    Some(penalty)
+}
+
+fn all_player_numbers() -> [PlayerNumber; 7] {
+    [
+        PlayerNumber::One,
+        PlayerNumber::Two,
+        PlayerNumber::Three,
+        PlayerNumber::Four,
+        PlayerNumber::Five,
+        PlayerNumber::Six,
+        PlayerNumber::Seven,
+    ]
+}
@@ -1,22 +1,41 @@
-use std::{io::stdout, path::PathBuf};
+use std::{
+    fs::File,
+    io::stdout,
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 use chrono::Local;
 use clap::Parser;
-use color_eyre::{install, Result};
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    install, Result,
+};
 use fern::{Dispatch, InitError};
 use log::LevelFilter;
 use tokio_util::sync::CancellationToken;
 
+mod assertions;
+mod batch;
 mod cycler;
+mod game_controller;
 mod interfake;
+mod metrics;
+mod network;
+mod opponent;
+mod perception;
 mod robot;
 mod server;
 mod simulator;
 mod state;
+mod whistle;
 
-use hardware::{NetworkInterface, TimeInterface};
+use assertions::Report;
+use batch::{run_batch, write_summary, Sweep};
+use hardware::{NetworkInterface, PathsInterface, TimeInterface};
+use simulator::{Recording, Simulator};
 
-pub trait HardwareInterface: TimeInterface + NetworkInterface {}
+pub trait HardwareInterface: TimeInterface + NetworkInterface + PathsInterface {}
 
 include!(concat!(env!("OUT_DIR"), "/generated_code.rs"));
 
@@ -24,7 +43,35 @@ include!(concat!(env!("OUT_DIR"), "/generated_code.rs"));
 struct Arguments {
     #[arg(short, long, default_value = "[::]:1337")]
     listen_address: String,
-    scenario_file: PathBuf,
+    /// Exit after the simulation instead of serving the frames to Twix
+    #[arg(long)]
+    headless: bool,
+    /// Write the assertion report as JSON to this file
+    #[arg(long)]
+    report: Option<PathBuf>,
+    /// Simulate the scenario for every configuration of this parameter sweep (JSON) instead
+    #[arg(long)]
+    sweep: Option<PathBuf>,
+    /// Summary of the parameter sweep, written as CSV if the file ends with `.csv`
+    #[arg(long, default_value = "sweep_summary.json")]
+    summary: PathBuf,
+    /// Run the simulation in real time while streaming it to the debug tools
+    #[arg(long)]
+    live: bool,
+    /// Speed of the live simulation relative to real time
+    #[arg(long, default_value_t = 1.0)]
+    real_time_factor: f32,
+    /// Seed of the simulated perception and Lua's `math.random`
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Write all frames of the simulation run to this file
+    #[arg(long)]
+    record: Option<PathBuf>,
+    /// Serve the frames of a recording instead of simulating a scenario
+    #[arg(long, conflicts_with_all = ["sweep", "live", "record"])]
+    replay: Option<PathBuf>,
+    #[arg(required_unless_present = "replay")]
+    scenario_file: Option<PathBuf>,
 }
 
 fn setup_logger(is_verbose: bool) -> Result<(), InitError> {
@@ -62,9 +109,106 @@ fn main() -> Result<()> {
 
     let arguments = Arguments::parse();
 
-    server::run(
-        Some(arguments.listen_address),
-        keep_running,
-        arguments.scenario_file,
-    )
+    if let Some(replay) = &arguments.replay {
+        let recording = Recording::load(replay)?;
+        println!(
+            "Replaying {} frames of {} with seed {}",
+            recording.frames.len(),
+            recording.scenario_file.display(),
+            recording.seed
+        );
+        return server::run(
+            Some(arguments.listen_address),
+            keep_running,
+            recording.frames,
+        );
+    }
+    let scenario_file = arguments
+        .scenario_file
+        .clone()
+        .ok_or_else(|| eyre!("scenario file is required without --replay"))?;
+
+    if let Some(sweep) = &arguments.sweep {
+        let sweep = Sweep::load(sweep)?;
+        let summaries = run_batch(&scenario_file, &sweep, arguments.seed, &keep_running)?;
+        write_summary(&arguments.summary, &summaries)?;
+        println!(
+            "Wrote summary of {} runs to {}",
+            summaries.len(),
+            arguments.summary.display()
+        );
+        return Ok(());
+    }
+
+    let mut simulator = Simulator::try_new()?;
+    simulator.seed(arguments.seed)?;
+    simulator.execute_script(&scenario_file)?;
+
+    if arguments.live {
+        server::run_live(
+            Some(arguments.listen_address.clone()),
+            keep_running,
+            &mut simulator,
+            arguments.real_time_factor,
+        )?;
+        let report = evaluate(&simulator, &scenario_file, arguments.report.as_deref())?;
+        if !report.passed {
+            bail!("scenario failed");
+        }
+        return Ok(());
+    }
+
+    let start = Instant::now();
+    let frames = simulator.run().wrap_err("failed to run simulation")?;
+    let duration = Instant::now() - start;
+    println!("Took {:.2} seconds", duration.as_secs_f32());
+
+    let report = evaluate(&simulator, &scenario_file, arguments.report.as_deref())?;
+    let recording = Recording {
+        scenario_file,
+        seed: arguments.seed,
+        frames,
+    };
+    if let Some(path) = &arguments.record {
+        recording.store(path)?;
+        println!("Recorded to {}", path.display());
+    }
+    if !arguments.headless {
+        server::run(
+            Some(arguments.listen_address),
+            keep_running,
+            recording.frames,
+        )?;
+    }
+
+    if !report.passed {
+        bail!("scenario failed");
+    }
+    Ok(())
+}
+
+/// Prints failed assertions and writes the report if requested
+fn evaluate(
+    simulator: &Simulator,
+    scenario_file: &Path,
+    report_path: Option<&Path>,
+) -> Result<Report> {
+    let report = simulator.report(scenario_file.to_path_buf());
+    for assertion in report
+        .assertions
+        .iter()
+        .filter(|assertion| !assertion.passed)
+    {
+        println!(
+            "Assertion failed at {:.2} s: {} ({})",
+            assertion.time,
+            assertion.name,
+            assertion.message.as_deref().unwrap_or("no message")
+        );
+    }
+    if let Some(path) = report_path {
+        let file = File::create(path).wrap_err("failed to create report file")?;
+        serde_json::to_writer_pretty(file, &report).wrap_err("failed to write report")?;
+    }
+    Ok(report)
 }
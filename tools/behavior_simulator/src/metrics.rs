@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use spl_network_messages::Team;
+
+use crate::state::Event;
+
+/// Aggregated outcome of a simulation run used to compare parameter sets
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Metrics {
+    pub goals_scored: usize,
+    pub goals_conceded: usize,
+    /// Simulated time in seconds until any robot kicked the ball
+    pub time_to_first_kick: Option<f32>,
+    pub falls: usize,
+    pub simulated_time: f32,
+}
+
+impl Metrics {
+    pub fn update(&mut self, events: &[Event], now: Duration) {
+        for event in events {
+            match event {
+                Event::Goal {
+                    scoring_team: Team::Hulks,
+                } => self.goals_scored += 1,
+                Event::Goal { .. } => self.goals_conceded += 1,
+                Event::Kick { .. } => {
+                    self.time_to_first_kick.get_or_insert(now.as_secs_f32());
+                }
+                Event::Fall { .. } => self.falls += 1,
+                Event::Cycle => {}
+            }
+        }
+        self.simulated_time = now.as_secs_f32();
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use rand::{rngs::StdRng, Rng};
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+use spl_network_messages::{HulkMessage, PlayerNumber};
+
+/// Delivery characteristics of the wireless connection from one robot to another
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LinkModel {
+    /// Seconds between sending and receiving a message
+    pub latency_mean: f32,
+    pub latency_standard_deviation: f32,
+    pub drop_probability: f32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Link {
+    pub sender: PlayerNumber,
+    pub receiver: PlayerNumber,
+    #[serde(flatten)]
+    pub model: LinkModel,
+}
+
+/// Delays and drops SPL messages between the simulated robots
+///
+/// Every link uses the default model unless it is listed in `links`. The defaults deliver all
+/// messages in the cycle after they were sent.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct NetworkModel {
+    pub default: LinkModel,
+    pub links: Vec<Link>,
+}
+
+pub struct InFlightMessage {
+    pub receiver: PlayerNumber,
+    pub message: HulkMessage,
+    pub arrival: Duration,
+}
+
+impl NetworkModel {
+    /// Returns the arrival time of a message sent at `now` or `None` if it is dropped
+    pub fn transmit(
+        &self,
+        sender: PlayerNumber,
+        receiver: PlayerNumber,
+        now: Duration,
+        random_number_generator: &mut StdRng,
+    ) -> Option<Duration> {
+        let model = self
+            .links
+            .iter()
+            .find(|link| link.sender == sender && link.receiver == receiver)
+            .map_or(&self.default, |link| &link.model);
+
+        if random_number_generator.gen_bool(model.drop_probability.clamp(0.0, 1.0) as f64) {
+            return None;
+        }
+        let latency = if model.latency_standard_deviation > 0.0 {
+            Normal::new(model.latency_mean, model.latency_standard_deviation)
+                .expect("standard deviation should be positive and finite")
+                .sample(random_number_generator)
+        } else {
+            model.latency_mean
+        };
+        Some(now + Duration::from_secs_f32(latency.max(0.0)))
+    }
+}
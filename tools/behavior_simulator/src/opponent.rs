@@ -0,0 +1,107 @@
+use std::{f32::consts::PI, time::Duration};
+
+use nalgebra::{point, Isometry2, Point2, UnitComplex};
+use serde::{Deserialize, Serialize};
+use spl_network_messages::{PlayerNumber, Team};
+
+use crate::state::Ball;
+
+const WALK_SPEED: f32 = 0.3;
+const TURN_SPEED: f32 = PI / 4.0;
+const KICK_DISTANCE: f32 = 0.2;
+const KICK_SPEED: f32 = 2.0;
+const KICK_COOLDOWN: f32 = 1.0;
+/// The opponents defend the goal in positive x direction
+const OWN_GOAL_X: f32 = 4.5;
+const DEFENSIVE_LINE_X: f32 = OWN_GOAL_X - 1.0;
+const DEFENSIVE_LINE_HALF_WIDTH: f32 = 1.1;
+
+/// Orientation mapping poses of the opponent team to field coordinates
+///
+/// Both teams use field coordinates with their own goal in negative x direction. The opponent
+/// team plays on the other half, i.e. rotated by half a turn.
+pub fn team_to_field(team: Team) -> Isometry2<f32> {
+    match team {
+        Team::Opponent => Isometry2::rotation(PI),
+        Team::Hulks | Team::Uncertain => Isometry2::identity(),
+    }
+}
+
+pub fn opposing_team(team: Team) -> Team {
+    match team {
+        Team::Hulks => Team::Opponent,
+        Team::Opponent => Team::Hulks,
+        Team::Uncertain => Team::Uncertain,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum OpponentPolicy {
+    Stand,
+    /// Walks to the ball and kicks it towards the goal of the Hulks
+    ChaseBall,
+    /// Stays between the ball and the own goal
+    Defend,
+}
+
+/// Opponent robot following a simple policy instead of our behavior
+///
+/// Positions are given in field coordinates of the Hulks.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ScriptedOpponent {
+    pub player_number: PlayerNumber,
+    pub position: Point2<f32>,
+    pub orientation: f32,
+    pub policy: OpponentPolicy,
+    /// Seconds until the opponent is able to kick again
+    #[serde(default)]
+    pub kick_cooldown: f32,
+}
+
+impl ScriptedOpponent {
+    pub fn cycle(&mut self, ball: Option<&mut Ball>, is_playing: bool, time_step: Duration) {
+        self.kick_cooldown = (self.kick_cooldown - time_step.as_secs_f32()).max(0.0);
+        let Some(ball) = ball else {
+            return;
+        };
+        if !is_playing {
+            return;
+        }
+
+        match self.policy {
+            OpponentPolicy::Stand => {}
+            OpponentPolicy::ChaseBall => {
+                if (ball.position - self.position).norm() < KICK_DISTANCE {
+                    if self.kick_cooldown <= 0.0 {
+                        let direction = (point![-OWN_GOAL_X, 0.0] - ball.position).normalize();
+                        ball.velocity = direction * KICK_SPEED;
+                        self.kick_cooldown = KICK_COOLDOWN;
+                    }
+                } else {
+                    self.walk_towards(ball.position, time_step);
+                }
+            }
+            OpponentPolicy::Defend => {
+                let target = point![
+                    DEFENSIVE_LINE_X,
+                    ball.position
+                        .y
+                        .clamp(-DEFENSIVE_LINE_HALF_WIDTH, DEFENSIVE_LINE_HALF_WIDTH)
+                ];
+                self.walk_towards(target, time_step);
+            }
+        }
+    }
+
+    fn walk_towards(&mut self, target: Point2<f32>, time_step: Duration) {
+        let step = (target - self.position).cap_magnitude(WALK_SPEED * time_step.as_secs_f32());
+        self.position += step;
+
+        if step.norm_squared() > f32::EPSILON {
+            let target_orientation = step.y.atan2(step.x);
+            let rotation = UnitComplex::new(target_orientation - self.orientation).angle();
+            let maximum_rotation = TURN_SPEED * time_step.as_secs_f32();
+            self.orientation += rotation.clamp(-maximum_rotation, maximum_rotation);
+        }
+    }
+}
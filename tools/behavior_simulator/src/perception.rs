@@ -0,0 +1,72 @@
+use nalgebra::{vector, Point2, UnitComplex, Vector2};
+use rand::{rngs::StdRng, Rng};
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+
+/// Radii of perceived robots, same as the defaults of the obstacle filter
+pub const ROBOT_OBSTACLE_RADIUS_AT_FOOT_HEIGHT: f32 = 0.2;
+pub const ROBOT_OBSTACLE_RADIUS_AT_HIP_HEIGHT: f32 = 0.2;
+
+/// Degrades the ground truth before it is handed to the simulated robots
+///
+/// Objects are only perceived inside the field of view cone of the top camera up to the maximum
+/// distance. Perceived positions are disturbed by gaussian noise growing with the distance and
+/// perceivable objects are missed with the false negative probability. The defaults perceive
+/// everything inside the cone perfectly.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PerceptionModel {
+    /// Scales the field of view of the top camera
+    pub field_of_view_scale: f32,
+    pub maximum_distance: f32,
+    /// Standard deviation of the position noise per meter of distance to the robot
+    pub noise_per_meter: f32,
+    pub false_negative_probability: f32,
+}
+
+impl Default for PerceptionModel {
+    fn default() -> Self {
+        Self {
+            field_of_view_scale: 1.0,
+            maximum_distance: 3.0,
+            noise_per_meter: 0.0,
+            false_negative_probability: 0.0,
+        }
+    }
+}
+
+impl PerceptionModel {
+    /// Returns the perceived position of the object at `position` relative to the robot
+    pub fn perceive(
+        &self,
+        position: Point2<f32>,
+        head_yaw: f32,
+        field_of_view: f32,
+        random_number_generator: &mut StdRng,
+    ) -> Option<Point2<f32>> {
+        let position_in_head = UnitComplex::from_angle(head_yaw).inverse() * position.coords;
+        let distance = position_in_head.norm();
+        let angle = position_in_head.angle(&Vector2::x_axis());
+        let is_visible = angle.abs() < field_of_view * self.field_of_view_scale / 2.0
+            && distance < self.maximum_distance;
+        if !is_visible
+            || random_number_generator
+                .gen_bool(self.false_negative_probability.clamp(0.0, 1.0) as f64)
+        {
+            return None;
+        }
+
+        let standard_deviation = self.noise_per_meter * distance;
+        if standard_deviation <= 0.0 {
+            return Some(position);
+        }
+        let noise = Normal::new(0.0, standard_deviation)
+            .expect("standard deviation should be positive and finite");
+        Some(
+            position
+                + vector![
+                    noise.sample(random_number_generator),
+                    noise.sample(random_number_generator)
+                ],
+        )
+    }
+}
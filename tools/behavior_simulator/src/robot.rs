@@ -10,7 +10,7 @@ use control::localization::generate_initial_pose;
 use nalgebra::vector;
 use parameters::directory::deserialize;
 use spl_network_messages::PlayerNumber;
-use types::{messages::IncomingMessage, CameraMatrix};
+use types::{coordinate_systems::Transform, messages::IncomingMessage, CameraMatrix};
 
 use crate::{
     cycler::{BehaviorCycler, Database},
@@ -26,6 +26,9 @@ pub struct Robot {
     pub parameters: Parameters,
     pub is_penalized: bool,
     pub last_kick_time: Duration,
+    pub was_fallen: bool,
+    pub was_penalized: bool,
+    pub whistle_heard_at: Option<Duration>,
 }
 
 impl Robot {
@@ -51,10 +54,10 @@ impl Robot {
 
         let mut database = Database::default();
 
-        database.main_outputs.robot_to_field = Some(generate_initial_pose(
+        database.main_outputs.robot_to_field = Some(Transform::wrap(generate_initial_pose(
             &parameter.localization.initial_poses[player_number],
             &parameter.field_dimensions,
-        ));
+        )));
 
         let persistent_state = Default::default();
 
@@ -66,6 +69,9 @@ impl Robot {
             parameters: parameter,
             is_penalized: false,
             last_kick_time: Duration::default(),
+            was_fallen: false,
+            was_penalized: false,
+            whistle_heard_at: None,
         })
     }
 
@@ -1,25 +1,30 @@
 use std::{
-    path::Path,
     sync::Arc,
+    thread::sleep,
     time::{Duration, Instant},
 };
 
 use crate::{
     cycler::Database,
+    game_controller::{GameController, Score},
     robot::to_player_number,
-    simulator::{Frame, Simulator},
-    state::Ball,
+    simulator::{Frame, Simulator, TIME_STEP},
+    state::{Ball, State},
+    structs::control,
 };
 use color_eyre::{
     eyre::{bail, WrapErr},
     Result,
 };
+use communication::{messages::Metadata, server::Runtime};
 use framework::{multiple_buffer_with_slots, Reader, Writer};
+use nalgebra::Isometry2;
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
+use spl_network_messages::PlayerNumber;
 use tokio::{net::ToSocketAddrs, select, sync::Notify, time::interval};
 use tokio_util::sync::CancellationToken;
-use types::{FieldDimensions, Players};
+use types::{FieldDimensions, FilteredGameState, GameControllerState, Players};
 
 #[derive(Clone, Serialize, Deserialize, SerializeHierarchy)]
 struct Parameters {
@@ -31,8 +36,12 @@ struct Parameters {
 #[derive(Clone, Default, Serialize, Deserialize, SerializeHierarchy)]
 struct MainOutputs {
     frame_count: usize,
+    time_elapsed: f32,
     ball: Option<Ball>,
+    game_controller_state: Option<GameControllerState>,
+    filtered_game_state: Option<FilteredGameState>,
     databases: Players<Option<Database>>,
+    opponents: Players<Option<Isometry2<f32>>>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, SerializeHierarchy)]
@@ -40,6 +49,46 @@ struct BehaviorSimulatorDatabase {
     main_outputs: MainOutputs,
 }
 
+#[derive(Clone, Default, Serialize, Deserialize, SerializeHierarchy)]
+struct GroundTruthMainOutputs {
+    time_elapsed: f32,
+    cycle_count: usize,
+    ball: Option<Ball>,
+    robot_to_field: Players<Option<Isometry2<f32>>>,
+    opponent_to_field: Players<Option<Isometry2<f32>>>,
+    game_controller_state: Option<GameControllerState>,
+    filtered_game_state: Option<FilteredGameState>,
+    score: Option<Score>,
+}
+
+impl From<&State> for GroundTruthMainOutputs {
+    fn from(state: &State) -> Self {
+        let mut robot_to_field = Players::<Option<Isometry2<f32>>>::default();
+        for (player_number, robot) in &state.robots {
+            robot_to_field[*player_number] = robot
+                .database
+                .main_outputs
+                .robot_to_field
+                .map(|robot_to_field| robot_to_field.inner);
+        }
+        Self {
+            time_elapsed: state.time_elapsed.as_secs_f32(),
+            cycle_count: state.cycle_count,
+            ball: state.ball.clone(),
+            robot_to_field,
+            opponent_to_field: state.opponent_poses(),
+            game_controller_state: Some(state.game_controller_state),
+            filtered_game_state: Some(state.filtered_game_state),
+            score: state.game_controller.as_ref().map(GameController::score),
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, SerializeHierarchy)]
+struct GroundTruthDatabase {
+    main_outputs: GroundTruthMainOutputs,
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn timeline_server(
     keep_running: CancellationToken,
@@ -66,13 +115,18 @@ async fn timeline_server(
         }
 
         let parameters = parameters_reader.next();
+        let selected_frame = parameters.selected_frame.min(frames.len() - 1);
 
         {
             let mut outputs = outputs_writer.next();
             outputs.main_outputs.frame_count = frames.len();
-            let frame = &frames[parameters.selected_frame];
+            let frame = &frames[selected_frame];
+            outputs.main_outputs.time_elapsed = frame.time_elapsed.as_secs_f32();
             outputs.main_outputs.ball = frame.ball.clone();
+            outputs.main_outputs.game_controller_state = Some(frame.game_controller_state);
+            outputs.main_outputs.filtered_game_state = Some(frame.filtered_game_state);
             outputs.main_outputs.databases = frame.robots.clone();
+            outputs.main_outputs.opponents = frame.opponents;
         }
         outputs_changed.notify_waiters();
 
@@ -80,9 +134,7 @@ async fn timeline_server(
             let mut control = control_writer.next();
             *control = to_player_number(parameters.selected_robot)
                 .ok()
-                .and_then(|player_number| {
-                    frames[parameters.selected_frame].robots[player_number].clone()
-                })
+                .and_then(|player_number| frames[selected_frame].robots[player_number].clone())
                 .unwrap_or_default();
         }
         control_changed.notify_waiters();
@@ -92,53 +144,21 @@ async fn timeline_server(
 pub fn run(
     addresses: Option<impl ToSocketAddrs + Send + Sync + 'static>,
     keep_running: CancellationToken,
-    scenario_file: impl AsRef<Path>,
+    frames: Vec<Frame>,
 ) -> Result<()> {
-    let parameter_slots = 3; // 2 for communication writer + 1 reader for timeline_server
-    let communication_server = communication::server::Runtime::<Parameters>::start(
-        addresses,
-        "tools/behavior_simulator",
-        "behavior_simulator".to_string(),
-        "behavior_simulator".to_string(),
-        parameter_slots,
-        keep_running.clone(),
-    )?;
-
-    let (outputs_writer, outputs_reader) =
-        multiple_buffer_with_slots([Default::default(), Default::default(), Default::default()]);
+    let communication_server = start_communication_server(addresses, keep_running.clone())?;
 
-    let outputs_changed = Arc::new(Notify::new());
-    let (subscribed_outputs_writer, _subscribed_outputs_reader) =
-        multiple_buffer_with_slots([Default::default(), Default::default(), Default::default()]);
-
-    communication_server.register_cycler_instance(
+    let (outputs_writer, outputs_changed) = register_output(
+        &communication_server,
         "BehaviorSimulator",
-        outputs_changed.clone(),
-        outputs_reader,
-        subscribed_outputs_writer,
+        Default::default(),
     );
-
-    let (control_writer, control_reader) =
-        multiple_buffer_with_slots([Default::default(), Default::default(), Default::default()]);
-
-    let control_changed = Arc::new(Notify::new());
-    let (subscribed_control_writer, _subscribed_control_reader) =
-        multiple_buffer_with_slots([Default::default(), Default::default(), Default::default()]);
-    communication_server.register_cycler_instance(
+    let (control_writer, control_changed) = register_output(
+        &communication_server,
         "Control",
-        control_changed.clone(),
-        control_reader,
-        subscribed_control_writer,
+        control::outputs_metadata(),
     );
 
-    let mut simulator = Simulator::try_new()?;
-    simulator.execute_script(scenario_file)?;
-
-    let start = Instant::now();
-    let frames = simulator.run().wrap_err("failed to run simulation")?;
-    let duration = Instant::now() - start;
-    println!("Took {:.2} seconds", duration.as_secs_f32());
-
     let runtime = tokio::runtime::Runtime::new()?;
     {
         let parameters_changed = communication_server.get_parameters_changed();
@@ -158,6 +178,135 @@ pub fn run(
         });
     }
 
+    join(communication_server)
+}
+
+/// Runs the simulation in real time while streaming it to the debug tools
+///
+/// The `GroundTruth` cycler instance provides the simulated world, `Control1` to `Control7` the
+/// database of each robot and `Control` the database of the selected robot. After the scenario
+/// finished, the last state is served until the simulator is cancelled.
+pub fn run_live(
+    addresses: Option<impl ToSocketAddrs + Send + Sync + 'static>,
+    keep_running: CancellationToken,
+    simulator: &mut Simulator,
+    real_time_factor: f32,
+) -> Result<()> {
+    let communication_server = start_communication_server(addresses, keep_running.clone())?;
+
+    let (ground_truth_writer, ground_truth_changed) = register_output::<GroundTruthDatabase>(
+        &communication_server,
+        "GroundTruth",
+        Default::default(),
+    );
+    let (control_writer, control_changed) = register_output::<Database>(
+        &communication_server,
+        "Control",
+        control::outputs_metadata(),
+    );
+    let robot_outputs = ROBOT_CYCLER_INSTANCES.map(|(player_number, cycler_instance)| {
+        (
+            player_number,
+            register_output::<Database>(
+                &communication_server,
+                cycler_instance,
+                control::outputs_metadata(),
+            ),
+        )
+    });
+    let parameters_reader = communication_server.get_parameters_reader();
+
+    let cycle_duration = TIME_STEP.div_f32(real_time_factor);
+    let mut next_cycle = Instant::now();
+    while !keep_running.is_cancelled() {
+        if !simulator.state.lock().finished {
+            simulator.cycle().wrap_err("failed to run simulation")?;
+        }
+
+        let state = simulator.state.lock();
+        {
+            let mut ground_truth = ground_truth_writer.next();
+            ground_truth.main_outputs = GroundTruthMainOutputs::from(&*state);
+        }
+        ground_truth_changed.notify_waiters();
+
+        for (player_number, (writer, changed)) in &robot_outputs {
+            if let Some(robot) = state.robots.get(player_number) {
+                *writer.next() = robot.database.clone();
+                changed.notify_waiters();
+            }
+        }
+
+        let selected_robot = to_player_number(parameters_reader.next().selected_robot)
+            .ok()
+            .and_then(|player_number| state.robots.get(&player_number));
+        *control_writer.next() = selected_robot
+            .map(|robot| robot.database.clone())
+            .unwrap_or_default();
+        control_changed.notify_waiters();
+        drop(state);
+
+        next_cycle += cycle_duration;
+        sleep(next_cycle.saturating_duration_since(Instant::now()));
+    }
+
+    join(communication_server)
+}
+
+const ROBOT_CYCLER_INSTANCES: [(PlayerNumber, &str); 7] = [
+    (PlayerNumber::One, "Control1"),
+    (PlayerNumber::Two, "Control2"),
+    (PlayerNumber::Three, "Control3"),
+    (PlayerNumber::Four, "Control4"),
+    (PlayerNumber::Five, "Control5"),
+    (PlayerNumber::Six, "Control6"),
+    (PlayerNumber::Seven, "Control7"),
+];
+
+fn start_communication_server(
+    addresses: Option<impl ToSocketAddrs + Send + Sync + 'static>,
+    keep_running: CancellationToken,
+) -> Result<Runtime<Parameters>> {
+    let parameter_slots = 3; // 2 for communication writer + 1 reader for the timeline or live loop
+    Ok(Runtime::<Parameters>::start(
+        addresses,
+        None,
+        Default::default(),
+        "tools/behavior_simulator",
+        Default::default(),
+        "behavior_simulator".to_string(),
+        "behavior_simulator".to_string(),
+        parameter_slots,
+        keep_running,
+    )?)
+}
+
+fn register_output<Outputs>(
+    communication_server: &Runtime<Parameters>,
+    cycler_instance: &'static str,
+    metadata: Metadata,
+) -> (Writer<Outputs>, Arc<Notify>)
+where
+    Outputs: Default + SerializeHierarchy + Send + Sync + 'static,
+{
+    let (outputs_writer, outputs_reader) =
+        multiple_buffer_with_slots([Default::default(), Default::default(), Default::default()]);
+
+    let outputs_changed = Arc::new(Notify::new());
+    let (subscribed_outputs_writer, _subscribed_outputs_reader) =
+        multiple_buffer_with_slots([Default::default(), Default::default(), Default::default()]);
+
+    communication_server.register_cycler_instance(
+        cycler_instance,
+        metadata,
+        outputs_changed.clone(),
+        outputs_reader,
+        subscribed_outputs_writer,
+    );
+    (outputs_writer, outputs_changed)
+}
+
+fn join(communication_server: Runtime<Parameters>) -> Result<()> {
     let mut encountered_error = false;
     match communication_server.join() {
         Ok(Err(error)) => {
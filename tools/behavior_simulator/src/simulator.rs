@@ -1,25 +1,70 @@
-use std::{fs::read_to_string, path::Path, sync::Arc, time::Duration};
+use std::{
+    fs::{read_to_string, File},
+    io::{BufReader, BufWriter},
+    mem::take,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
-use crate::{cycler::Database, robot::to_player_number, state::Ball};
+use crate::{
+    cycler::Database,
+    robot::{from_player_number, to_player_number},
+    state::Ball,
+};
 use color_eyre::{
     eyre::{eyre, WrapErr},
     Result,
 };
-use mlua::{Error as LuaError, Function, Lua, LuaSerdeExt, SerializeOptions, Value};
-use nalgebra::{Isometry2, Vector2};
+use mlua::{
+    Error as LuaError, Function, Lua, LuaSerdeExt, SerializeOptions, Table, ToLuaMulti, Value,
+};
+use nalgebra::{Isometry2, Point2, Vector2};
 use parking_lot::Mutex;
-use types::Players;
+use serde::{Deserialize, Serialize};
+use spl_network_messages::{GameState, SubState, Team};
+use types::{coordinate_systems::Transform, FilteredGameState, GameControllerState, Players};
 
 use crate::{
+    assertions::{Region, Report},
+    game_controller::{penalty_from_name, GameController},
     robot::Robot,
     state::{Event, LuaRobot, State},
 };
 
+pub const TIME_STEP: Duration = Duration::from_millis(12);
+
 const SERIALIZE_OPTIONS: SerializeOptions = SerializeOptions::new().serialize_none_to_null(false);
 
+#[derive(Deserialize, Serialize)]
 pub struct Frame {
+    pub time_elapsed: Duration,
     pub ball: Option<Ball>,
     pub robots: Players<Option<Database>>,
+    /// Poses in field coordinates of the Hulks
+    pub opponents: Players<Option<Isometry2<f32>>>,
+    pub game_controller_state: GameControllerState,
+    pub filtered_game_state: FilteredGameState,
+}
+
+/// Frames of a simulation run written by `--record` and served again by `--replay`
+#[derive(Deserialize, Serialize)]
+pub struct Recording {
+    pub scenario_file: PathBuf,
+    pub seed: u64,
+    pub frames: Vec<Frame>,
+}
+
+impl Recording {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let file = BufReader::new(File::open(path).wrap_err("failed to open recording")?);
+        bincode::deserialize_from(file).wrap_err("failed to deserialize recording")
+    }
+
+    pub fn store(&self, path: impl AsRef<Path>) -> Result<()> {
+        let file = BufWriter::new(File::create(path).wrap_err("failed to create recording")?);
+        bincode::serialize_into(file, self).wrap_err("failed to serialize recording")
+    }
 }
 
 pub struct Simulator {
@@ -42,10 +87,30 @@ impl Simulator {
         lua.globals()
             .set("create_robot", create_robot)
             .wrap_err("failed to insert create_robot")?;
+        register_assertions(&lua, &state).wrap_err("failed to insert assertions")?;
 
         Ok(Self { state, lua })
     }
 
+    /// Seeds the perception model and Lua's `math.random` to reproduce runs
+    pub fn seed(&mut self, seed: u64) -> Result<()> {
+        self.state.lock().seed(seed);
+        let random_seed: Function = self
+            .lua
+            .load("math.randomseed")
+            .eval()
+            .wrap_err("failed to get math.randomseed")?;
+        random_seed
+            .call::<_, ()>(seed)
+            .wrap_err("failed to seed lua")
+    }
+
+    pub fn report(&self, scenario: PathBuf) -> Report {
+        let mut state = self.state.lock();
+        let now = state.time_elapsed;
+        take(&mut state.assertions).into_report(scenario, now)
+    }
+
     pub fn execute_script(&mut self, file_name: impl AsRef<Path>) -> Result<()> {
         self.serialze_state()?;
 
@@ -76,8 +141,12 @@ impl Simulator {
                 robots[*player_number] = Some(robot.database.clone())
             }
             frames.push(Frame {
-                robots,
+                time_elapsed: state.time_elapsed,
                 ball: state.ball.clone(),
+                robots,
+                opponents: state.opponent_poses(),
+                game_controller_state: state.game_controller_state,
+                filtered_game_state: state.filtered_game_state,
             });
 
             if state.finished {
@@ -88,10 +157,20 @@ impl Simulator {
         Ok(frames)
     }
 
+    /// Runs until the scenario finishes without recording frames
+    pub fn run_to_end(&mut self) -> Result<()> {
+        loop {
+            self.cycle()?;
+            if self.state.lock().finished {
+                return Ok(());
+            }
+        }
+    }
+
     pub fn cycle(&mut self) -> Result<()> {
         let events = {
             let mut state = self.state.lock();
-            state.cycle(Duration::from_millis(12))?
+            state.cycle(TIME_STEP)?
         };
 
         self.serialze_state()?;
@@ -99,15 +178,24 @@ impl Simulator {
         self.lua.scope(|scope| {
             self.lua.globals().set(
                 "penalize",
-                scope.create_function(|_, player_number: usize| {
+                scope.create_function(|_, (player_number, penalty): (usize, Option<String>)| {
                     let player_number =
                         to_player_number(player_number).map_err(LuaError::external)?;
-                    self.state
-                        .lock()
-                        .robots
-                        .get_mut(&player_number)
-                        .unwrap()
-                        .is_penalized = true;
+                    let mut state = self.state.lock();
+                    match state.game_controller.as_mut() {
+                        Some(game_controller) => {
+                            let penalty = penalty_from_name(penalty.as_deref().unwrap_or("Manual"))
+                                .map_err(LuaError::external)?;
+                            game_controller.penalize(player_number, penalty);
+                        }
+                        None => {
+                            state
+                                .robots
+                                .get_mut(&player_number)
+                                .ok_or_else(|| LuaError::external("there is no such robot"))?
+                                .is_penalized = true
+                        }
+                    }
 
                     Ok(())
                 })?,
@@ -117,16 +205,46 @@ impl Simulator {
                 scope.create_function(|_, player_number: usize| {
                     let player_number =
                         to_player_number(player_number).map_err(LuaError::external)?;
-                    self.state
-                        .lock()
-                        .robots
-                        .get_mut(&player_number)
-                        .unwrap()
-                        .is_penalized = false;
+                    let mut state = self.state.lock();
+                    match state.game_controller.as_mut() {
+                        Some(game_controller) => game_controller.unpenalize(player_number),
+                        None => {
+                            state
+                                .robots
+                                .get_mut(&player_number)
+                                .ok_or_else(|| LuaError::external("there is no such robot"))?
+                                .is_penalized = false
+                        }
+                    }
 
                     Ok(())
                 })?,
             )?;
+            self.lua.globals().set(
+                "start_game",
+                scope.create_function(|lua, kicking_team: Value| {
+                    let kicking_team: Team = lua.from_value(kicking_team)?;
+                    let mut state = self.state.lock();
+                    let now = state.time_elapsed;
+                    state.game_controller = Some(GameController::start(kicking_team, now));
+
+                    Ok(())
+                })?,
+            )?;
+            self.lua.globals().set(
+                "free_kick",
+                scope.create_function(|lua, (sub_state, kicking_team): (Value, Value)| {
+                    let sub_state: SubState = lua.from_value(sub_state)?;
+                    let kicking_team: Team = lua.from_value(kicking_team)?;
+                    let mut state = self.state.lock();
+                    let now = state.time_elapsed;
+                    let game_controller = state.game_controller.as_mut().ok_or_else(|| {
+                        LuaError::external("game has not been started with start_game")
+                    })?;
+
+                    Ok(game_controller.free_kick(sub_state, kicking_team, now))
+                })?,
+            )?;
 
             self.lua.globals().set(
                 "set_robot_pose",
@@ -143,16 +261,136 @@ impl Simulator {
                             .unwrap()
                             .database
                             .main_outputs
-                            .robot_to_field = Some(Isometry2::new(position, angle));
+                            .robot_to_field =
+                            Some(Transform::wrap(Isometry2::new(position, angle)));
 
                         Ok(())
                     },
                 )?,
             )?;
+            self.lua.globals().set(
+                "add_robot",
+                scope.create_function(|_, player_number: usize| {
+                    let player_number =
+                        to_player_number(player_number).map_err(LuaError::external)?;
+                    let robot = Robot::try_new(player_number).map_err(LuaError::external)?;
+                    self.state.lock().robots.insert(player_number, robot);
+
+                    Ok(())
+                })?,
+            )?;
+            self.lua.globals().set(
+                "remove_robot",
+                scope.create_function(|_, player_number: usize| {
+                    let player_number =
+                        to_player_number(player_number).map_err(LuaError::external)?;
+
+                    Ok(self.state.lock().robots.remove(&player_number).is_some())
+                })?,
+            )?;
+            self.lua.globals().set(
+                "add_opponent",
+                scope.create_function(|_, player_number: usize| {
+                    let player_number =
+                        to_player_number(player_number).map_err(LuaError::external)?;
+                    let robot = Robot::try_new(player_number).map_err(LuaError::external)?;
+                    self.state.lock().opponents.insert(player_number, robot);
+
+                    Ok(())
+                })?,
+            )?;
+            self.lua.globals().set(
+                "remove_opponent",
+                scope.create_function(|_, player_number: usize| {
+                    let player_number =
+                        to_player_number(player_number).map_err(LuaError::external)?;
+
+                    Ok(self.state.lock().opponents.remove(&player_number).is_some())
+                })?,
+            )?;
+            // the ball and the game states are read back from the lua state after the callbacks,
+            // hence these functions modify the lua state instead of the simulation state
+            self.lua.globals().set(
+                "place_ball",
+                scope.create_function(|lua, position: Value| {
+                    let position: Point2<f32> = lua.from_value(position)?;
+                    let ball = Ball {
+                        position,
+                        velocity: Vector2::zeros(),
+                    };
+                    set_in_lua_state(lua, "ball", &ball)
+                })?,
+            )?;
+            self.lua.globals().set(
+                "set_ball_velocity",
+                scope.create_function(|lua, velocity: Value| {
+                    let velocity: Vector2<f32> = lua.from_value(velocity)?;
+                    let lua_state: Table = lua.globals().get("state")?;
+                    let ball: Option<Ball> = lua.from_value(lua_state.get("ball")?)?;
+                    let mut ball = ball.ok_or_else(|| LuaError::external("there is no ball"))?;
+                    ball.velocity = velocity;
+                    set_in_lua_state(lua, "ball", &ball)
+                })?,
+            )?;
+            self.lua.globals().set(
+                "remove_ball",
+                scope.create_function(|lua, ()| {
+                    let lua_state: Table = lua.globals().get("state")?;
+                    lua_state.set("ball", Value::Nil)
+                })?,
+            )?;
+            self.lua.globals().set(
+                "whistle",
+                scope.create_function(|lua, ()| {
+                    let mut state = self.state.lock();
+                    let now = state.time_elapsed;
+                    if let Some(game_controller) = state.game_controller.as_mut() {
+                        let has_whistled = game_controller.whistle(now);
+                        state.referee_whistled |= has_whistled;
+                        return Ok(has_whistled);
+                    }
+                    if !matches!(state.filtered_game_state, FilteredGameState::Set) {
+                        return Ok(false);
+                    }
+                    state.referee_whistled = true;
+                    let mut game_controller_state = state.game_controller_state;
+                    game_controller_state.game_state = GameState::Playing;
+                    set_in_lua_state(lua, "game_controller_state", &game_controller_state)?;
+                    set_in_lua_state(
+                        lua,
+                        "filtered_game_state",
+                        &FilteredGameState::Playing { ball_is_free: true },
+                    )?;
+
+                    Ok(true)
+                })?,
+            )?;
+            self.lua.globals().set(
+                "hear_whistle",
+                scope.create_function(|_, player_number: usize| {
+                    let player_number =
+                        to_player_number(player_number).map_err(LuaError::external)?;
+                    let mut state = self.state.lock();
+                    let now = state.time_elapsed;
+                    let robot = state
+                        .robots
+                        .get_mut(&player_number)
+                        .ok_or_else(|| LuaError::external("there is no such robot"))?;
+                    robot.whistle_heard_at = Some(now);
+
+                    Ok(())
+                })?,
+            )?;
             for event in events {
                 match event {
-                    Event::Cycle => self.execute_event_callback("on_cycle")?,
-                    Event::Goal => self.execute_event_callback("on_goal")?,
+                    Event::Cycle => self.execute_event_callback("on_cycle", ())?,
+                    Event::Goal { .. } => self.execute_event_callback("on_goal", ())?,
+                    Event::Kick { player_number } => {
+                        self.execute_event_callback("on_kick", from_player_number(player_number))?
+                    }
+                    Event::Fall { player_number } => {
+                        self.execute_event_callback("on_fall", from_player_number(player_number))?
+                    }
                 }
             }
 
@@ -162,9 +400,13 @@ impl Simulator {
         self.deserialize_state()
     }
 
-    fn execute_event_callback(&self, name: &str) -> Result<(), LuaError> {
-        if let Ok(on_goal) = self.lua.globals().get::<_, Function>(name) {
-            on_goal.call(())?;
+    fn execute_event_callback<'lua>(
+        &'lua self,
+        name: &str,
+        arguments: impl ToLuaMulti<'lua>,
+    ) -> Result<(), LuaError> {
+        if let Ok(callback) = self.lua.globals().get::<_, Function>(name) {
+            callback.call(arguments)?;
         }
 
         Ok(())
@@ -198,3 +440,72 @@ impl Simulator {
             .wrap_err("failed to load lua state")
     }
 }
+
+fn set_in_lua_state(lua: &Lua, key: &str, value: &impl Serialize) -> Result<(), LuaError> {
+    let lua_state: Table = lua.globals().get("state")?;
+    lua_state.set(key, lua.to_value_with(value, SERIALIZE_OPTIONS)?)
+}
+
+fn register_assertions(lua: &Lua, state: &Arc<Mutex<State>>) -> Result<(), LuaError> {
+    let assert_that = lua.create_function({
+        let state = state.clone();
+        move |_, (condition, name): (bool, String)| {
+            let mut state = state.lock();
+            let now = state.time_elapsed;
+            state.assertions.assert(name, condition, None, now);
+            Ok(())
+        }
+    })?;
+    lua.globals().set("assert_that", assert_that)?;
+
+    let assert_goal_scored_by = lua.create_function({
+        let state = state.clone();
+        move |lua, (deadline, scoring_team): (f32, Option<Value>)| {
+            let scoring_team: Option<Team> = scoring_team
+                .map(|scoring_team| lua.from_value(scoring_team))
+                .transpose()?;
+            let deadline = Duration::try_from_secs_f32(deadline).map_err(LuaError::external)?;
+            state.lock().assertions.expect_goal(scoring_team, deadline);
+            Ok(())
+        }
+    })?;
+    lua.globals()
+        .set("assert_goal_scored_by", assert_goal_scored_by)?;
+
+    let assert_robot_inside = lua.create_function({
+        let state = state.clone();
+        move |lua, (player_number, region, name): (usize, Value, Option<String>)| {
+            let player_number = to_player_number(player_number).map_err(LuaError::external)?;
+            let region: Region = lua.from_value(region)?;
+            let mut state = state.lock();
+            let now = state.time_elapsed;
+            let position = state
+                .robots
+                .get(&player_number)
+                .and_then(|robot| robot.database.main_outputs.robot_to_field)
+                .map(|robot_to_field| Point2::from(robot_to_field.inner.translation.vector));
+            let passed = position.map_or(false, |position| region.contains(position));
+            let name = name.unwrap_or_else(|| format!("robot {player_number:?} inside {region:?}"));
+            state.assertions.assert(
+                name,
+                passed,
+                Some(format!("robot was at {position:?}")),
+                now,
+            );
+            Ok(passed)
+        }
+    })?;
+    lua.globals()
+        .set("assert_robot_inside", assert_robot_inside)?;
+
+    let checkpoint = lua.create_function({
+        let state = state.clone();
+        move |_, name: String| {
+            let mut state = state.lock();
+            let now = state.time_elapsed;
+            state.assertions.checkpoint(name, now);
+            Ok(())
+        }
+    })?;
+    lua.globals().set("checkpoint", checkpoint)
+}
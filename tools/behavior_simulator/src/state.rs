@@ -1,29 +1,44 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::BTreeMap,
+    f32::consts::FRAC_PI_2,
     mem::take,
-    time::{Duration, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use color_eyre::Result;
+use control::motion::head_motion::scan_positions;
 use nalgebra::{vector, Isometry2, Point2, UnitComplex, Vector2};
+use rand::{rngs::StdRng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 use spl_network_messages::{GamePhase, GameState, HulkMessage, PlayerNumber, Team};
 use types::{
     messages::{IncomingMessage, OutgoingMessage},
-    BallPosition, FilteredGameState, GameControllerState, HeadMotion, KickVariant, LineSegment,
-    MotionCommand, OrientationMode, PathSegment, Players, PrimaryState, Side,
+    BallPosition, FallState, FilteredGameState, GameControllerFreshness, GameControllerState,
+    HeadMotion, KickVariant, LineSegment, MotionCommand, Obstacle, ObstacleKind, OrientationMode,
+    PathSegment, Players, PrimaryState, Side,
 };
 
 use crate::{
+    assertions::Assertions,
     cycler::Database,
+    game_controller::{GameController, Score, HULKS_TEAM_COLORS, OPPONENT_TEAM_COLORS},
+    metrics::Metrics,
+    network::{InFlightMessage, NetworkModel},
+    opponent::{opposing_team, team_to_field, ScriptedOpponent},
+    perception::{
+        PerceptionModel, ROBOT_OBSTACLE_RADIUS_AT_FOOT_HEIGHT, ROBOT_OBSTACLE_RADIUS_AT_HIP_HEIGHT,
+    },
     robot::Robot,
     structs::{control::AdditionalOutputs, Parameters},
+    whistle::WhistleModel,
 };
 
 pub enum Event {
     Cycle,
-    Goal,
+    Goal { scoring_team: Team },
+    Kick { player_number: PlayerNumber },
+    Fall { player_number: PlayerNumber },
 }
 
 #[derive(Default, Clone, Deserialize, Serialize, SerializeHierarchy)]
@@ -35,17 +50,34 @@ pub struct Ball {
 pub struct State {
     pub time_elapsed: Duration,
     pub cycle_count: usize,
-    pub robots: HashMap<PlayerNumber, Robot>,
+    /// Ordered to consume random numbers deterministically
+    pub robots: BTreeMap<PlayerNumber, Robot>,
+    /// Opponents driven by a second instance of our behavior, their poses are given in field
+    /// coordinates of the opponent team
+    pub opponents: BTreeMap<PlayerNumber, Robot>,
+    pub scripted_opponents: Vec<ScriptedOpponent>,
     pub ball: Option<Ball>,
     pub messages: Vec<(PlayerNumber, HulkMessage)>,
     pub finished: bool,
     pub game_controller_state: GameControllerState,
     pub filtered_game_state: FilteredGameState,
+    pub perception: PerceptionModel,
+    pub network: NetworkModel,
+    pub whistle: WhistleModel,
+    /// Whether the referee whistled since the robots last listened
+    pub referee_whistled: bool,
+    /// Drives the game states once started, otherwise scenarios set them directly
+    pub game_controller: Option<GameController>,
+    pub assertions: Assertions,
+    pub metrics: Metrics,
+    messages_in_flight: Vec<InFlightMessage>,
+    opponent_messages: Vec<(PlayerNumber, HulkMessage)>,
+    random_number_generator: StdRng,
 }
 
 impl State {
     pub fn new() -> Self {
-        let robots = HashMap::new();
+        let robots = BTreeMap::new();
 
         let game_controller_state = GameControllerState {
             game_state: GameState::Initial,
@@ -64,17 +96,32 @@ impl State {
             remaining_amount_of_messages: 1200,
             sub_state: None,
             hulks_team_is_home_after_coin_toss: false,
+            freshness: GameControllerFreshness::Fresh,
+            hulks_team_colors: HULKS_TEAM_COLORS,
+            opponent_team_colors: OPPONENT_TEAM_COLORS,
         };
 
         Self {
             time_elapsed: Duration::ZERO,
             cycle_count: 0,
             robots,
+            opponents: BTreeMap::new(),
+            scripted_opponents: Vec::new(),
             ball: None,
             messages: Vec::new(),
             finished: false,
             game_controller_state,
             filtered_game_state: FilteredGameState::Initial,
+            perception: PerceptionModel::default(),
+            network: NetworkModel::default(),
+            whistle: WhistleModel::default(),
+            referee_whistled: false,
+            game_controller: None,
+            assertions: Assertions::default(),
+            metrics: Metrics::default(),
+            messages_in_flight: Vec::new(),
+            opponent_messages: Vec::new(),
+            random_number_generator: StdRng::seed_from_u64(0),
         }
     }
 
@@ -83,185 +130,329 @@ impl State {
 
         let mut events = vec![Event::Cycle];
 
-        self.move_robots(time_step);
-        self.cycle_robots(now)?;
+        self.cycle_game_controller(time_step);
+        self.place_penalized_robots();
+        events.extend(self.move_robots(time_step));
+        events.extend(self.cycle_robots(now, time_step)?);
         events.extend(self.move_ball(time_step));
 
+        if let Some(game_controller) = self.game_controller.as_mut() {
+            for event in &events {
+                if let Event::Goal { scoring_team } = event {
+                    game_controller.goal(*scoring_team, self.time_elapsed);
+                }
+            }
+        }
+        self.assertions.update(&events, self.time_elapsed);
+        self.metrics.update(&events, self.time_elapsed);
+
         self.time_elapsed += time_step;
         self.cycle_count += 1;
 
         Ok(events)
     }
 
-    fn move_robots(&mut self, time_step: Duration) {
+    fn cycle_game_controller(&mut self, time_step: Duration) {
+        let Some(game_controller) = self.game_controller.as_mut() else {
+            return;
+        };
+        if game_controller.cycle(
+            self.time_elapsed,
+            time_step,
+            self.whistle.game_controller_delay(),
+        ) {
+            self.referee_whistled = true;
+        }
+        self.game_controller_state = game_controller
+            .game_controller_state(self.game_controller_state.remaining_amount_of_messages);
+        self.filtered_game_state =
+            game_controller.filtered_game_state(Team::Hulks, self.time_elapsed);
+        for (player_number, robot) in self.robots.iter_mut() {
+            robot.is_penalized = game_controller.is_penalized(*player_number);
+        }
+    }
+
+    /// Moves newly penalized robots to the re-entry point on the sideline farther from the ball
+    ///
+    /// Penalized robots wait there facing the field until their penalty is over and then
+    /// re-enter from this pose, like robots placed by the assistant referees.
+    fn place_penalized_robots(&mut self) {
+        let ball_y = self.ball.as_ref().map(|ball| ball.position.y);
         for robot in self.robots.values_mut() {
-            let robot_to_field = robot
+            let is_newly_penalized = robot.is_penalized && !robot.was_penalized;
+            robot.was_penalized = robot.is_penalized;
+            if !is_newly_penalized {
+                continue;
+            }
+            let Some(robot_to_field) = robot
                 .database
                 .main_outputs
                 .robot_to_field
                 .as_mut()
-                .expect("simulated robots should always have a known pose");
-
-            robot.database.additional_outputs = AdditionalOutputs::default();
-            let head_motion = match &robot.database.main_outputs.motion_command {
-                MotionCommand::Walk {
-                    head,
-                    path,
-                    orientation_mode,
-                    ..
-                } => {
-                    let step = match path[0] {
-                        PathSegment::LineSegment(LineSegment(_start, end)) => end.coords,
-                        PathSegment::Arc(arc, orientation) => {
-                            orientation.rotate_vector_90_degrees(arc.start - arc.circle.center)
-                        }
-                    }
-                    .cap_magnitude(0.3 * time_step.as_secs_f32());
-
-                    let orientation = match orientation_mode {
-                        OrientationMode::AlignWithPath => {
-                            if step.norm_squared() < f32::EPSILON {
-                                UnitComplex::identity()
-                            } else {
-                                UnitComplex::from_cos_sin_unchecked(step.x, step.y)
-                            }
-                        }
-                        OrientationMode::Override(orientation) => *orientation,
-                    };
-
-                    *robot_to_field = Isometry2::new(
-                        robot_to_field.translation.vector + robot_to_field.rotation * step,
-                        robot_to_field.rotation.angle()
-                            + orientation.angle().clamp(
-                                -std::f32::consts::FRAC_PI_4 * time_step.as_secs_f32(),
-                                std::f32::consts::FRAC_PI_4 * time_step.as_secs_f32(),
-                            ),
-                    );
-
-                    head
-                }
-                MotionCommand::InWalkKick {
-                    head,
-                    kick,
-                    kicking_side,
-                    strength,
-                } => {
-                    if let Some(ball) = self.ball.as_mut() {
-                        let side = match kicking_side {
-                            Side::Left => 1.0,
-                            Side::Right => -1.0,
-                        };
-
-                        // TODO: Check if ball is even in range
-                        // let kick_location = robot_to_field * ();
-                        if (self.time_elapsed - robot.last_kick_time).as_secs_f32() > 1.0 {
-                            let direction = match kick {
-                                KickVariant::Forward => vector![1.0, 0.0],
-                                KickVariant::Turn => vector![0.707, 0.707 * side],
-                                KickVariant::Side => vector![0.0, 1.0 * -side],
-                            };
-                            ball.velocity += *robot_to_field * direction * *strength * 2.5;
-                            robot.last_kick_time = self.time_elapsed;
-                        };
-                    }
-                    head
-                }
-                MotionCommand::SitDown { head } => head,
-                MotionCommand::Stand {
-                    head,
-                    is_energy_saving: _,
-                } => head,
-                _ => &HeadMotion::Center,
+                .map(|robot_to_field| &mut robot_to_field.inner)
+            else {
+                continue;
             };
-
-            let f = self.time_elapsed.as_secs_f32().sin();
-            let desired_head_yaw = match head_motion {
-                HeadMotion::ZeroAngles => 0.0,
-                HeadMotion::Center => 0.0,
-                HeadMotion::LookAround | HeadMotion::SearchForLostBall => {
-                    robot.database.main_outputs.look_around.yaw
-                }
-                HeadMotion::LookAt { target, .. } => target.coords.angle(&Vector2::x_axis()),
-                HeadMotion::LookLeftAndRightOf { target } => {
-                    target.coords.angle(&Vector2::x_axis()) + f
-                }
-                HeadMotion::Unstiff => 0.0,
+            let field_dimensions = &robot.parameters.field_dimensions;
+            let side = match ball_y {
+                Some(ball_y) if ball_y >= 0.0 => -1.0,
+                Some(_) => 1.0,
+                None => robot_to_field.translation.y.signum(),
             };
+            *robot_to_field = Isometry2::new(
+                vector![
+                    -field_dimensions.length.0 / 2.0 + field_dimensions.penalty_marker_distance.0,
+                    side * field_dimensions.width.0 / 2.0
+                ],
+                -side * FRAC_PI_2,
+            );
+        }
+    }
 
-            let max_head_rotation_per_cycle =
-                robot.parameters.head_motion.maximum_velocity.yaw * time_step.as_secs_f32();
-            let diff =
-                desired_head_yaw - robot.database.main_outputs.sensor_data.positions.head.yaw;
-            let movement = diff.clamp(-max_head_rotation_per_cycle, max_head_rotation_per_cycle);
+    /// Reseeds the random number generator driving the perception model
+    pub fn seed(&mut self, seed: u64) {
+        self.random_number_generator = StdRng::seed_from_u64(seed);
+    }
 
-            robot.database.main_outputs.sensor_data.positions.head.yaw += movement;
+    fn move_robots(&mut self, time_step: Duration) -> Vec<Event> {
+        let mut events = Vec::new();
+        for (player_number, robot) in self.robots.iter_mut() {
+            if move_robot(
+                robot,
+                Team::Hulks,
+                self.ball.as_mut(),
+                self.time_elapsed,
+                time_step,
+            ) {
+                events.push(Event::Kick {
+                    player_number: *player_number,
+                });
+            }
         }
+        for robot in self.opponents.values_mut() {
+            move_robot(
+                robot,
+                Team::Opponent,
+                self.ball.as_mut(),
+                self.time_elapsed,
+                time_step,
+            );
+        }
+        let is_playing = matches!(self.filtered_game_state, FilteredGameState::Playing { .. });
+        for opponent in &mut self.scripted_opponents {
+            opponent.cycle(self.ball.as_mut(), is_playing, time_step);
+        }
+        events
     }
 
-    fn cycle_robots(&mut self, now: std::time::SystemTime) -> Result<()> {
-        let incoming_messages = take(&mut self.messages);
+    fn cycle_robots(&mut self, now: SystemTime, time_step: Duration) -> Result<Vec<Event>> {
+        let mut events = Vec::new();
+        let referee_whistled = take(&mut self.referee_whistled);
+        let arrived_messages = self.transmit_messages();
+        let opponent_messages = take(&mut self.opponent_messages);
+        let robot_positions = self.robot_positions();
+        let opponent_game_controller_state = self.opponent_game_controller_state();
+        let opponent_filtered_game_state = self.opponent_filtered_game_state();
 
         for (player_number, robot) in self.robots.iter_mut() {
-            let robot_to_field = robot
-                .database
-                .main_outputs
-                .robot_to_field
-                .as_mut()
-                .expect("simulated robots should always have a known pose");
+            let incoming_messages: Vec<_> = arrived_messages
+                .iter()
+                .filter(|in_flight_message| in_flight_message.receiver == *player_number)
+                .map(|in_flight_message| IncomingMessage::Spl(in_flight_message.message))
+                .collect();
+            perceive(
+                robot,
+                (Team::Hulks, *player_number),
+                self.ball.as_ref(),
+                &robot_positions,
+                &self.perception,
+                &mut self.random_number_generator,
+                now,
+            );
+            if self.whistle.hears_whistle(
+                referee_whistled,
+                time_step,
+                &mut self.random_number_generator,
+            ) {
+                robot.whistle_heard_at = Some(self.time_elapsed);
+            }
+            let filtered_game_state = self.whistle.filtered_game_state(
+                self.filtered_game_state,
+                self.game_controller_state.kicking_team,
+                robot.whistle_heard_at,
+                self.time_elapsed,
+            );
+            set_game_states(robot, self.game_controller_state, filtered_game_state);
+
+            robot.cycle(BTreeMap::from_iter([(
+                now,
+                incoming_messages.iter().collect(),
+            )]))?;
+
+            let is_fallen = !matches!(robot.database.main_outputs.fall_state, FallState::Upright);
+            if is_fallen && !robot.was_fallen {
+                events.push(Event::Fall {
+                    player_number: *player_number,
+                });
+            }
+            robot.was_fallen = is_fallen;
+
+            for message in robot.interface.take_outgoing_messages() {
+                if let OutgoingMessage::Spl(message) = message {
+                    // the message budget of the GameController is exhausted
+                    if self.game_controller_state.remaining_amount_of_messages == 0 {
+                        continue;
+                    }
+                    self.messages.push((*player_number, message.message));
+                    self.game_controller_state.remaining_amount_of_messages -= 1
+                }
+            }
+        }
 
-            let incoming_messages: Vec<_> = incoming_messages
+        for (player_number, robot) in self.opponents.iter_mut() {
+            let incoming_messages: Vec<_> = opponent_messages
                 .iter()
                 .filter_map(|(sender, message)| {
                     (sender != player_number).then_some(IncomingMessage::Spl(*message))
                 })
                 .collect();
-            let messages_with_time =
-                BTreeMap::from_iter([(now, incoming_messages.iter().collect())]);
-
-            robot.database.main_outputs.cycle_time.start_time = now;
-
-            robot.database.main_outputs.ball_position = self
-                .ball
-                .as_ref()
-                .map(|ball| BallPosition {
-                    position: robot_to_field.inverse() * ball.position,
-                    velocity: robot_to_field.inverse() * ball.velocity,
-                    last_seen: now,
-                })
-                .filter(|ball| {
-                    let head_rotation = UnitComplex::from_angle(
-                        robot.database.main_outputs.sensor_data.positions.head.yaw,
-                    );
-                    let ball_in_head = head_rotation.inverse() * ball.position.coords;
-                    let field_of_view = robot.field_of_view();
-                    let angle_to_ball = ball_in_head.angle(&Vector2::x_axis());
-
-                    angle_to_ball.abs() < field_of_view / 2.0 && ball_in_head.norm() < 3.0
-                });
-
-            robot.database.main_outputs.primary_state =
-                match (robot.is_penalized, self.filtered_game_state) {
-                    (true, _) => PrimaryState::Penalized,
-                    (false, FilteredGameState::Initial) => PrimaryState::Initial,
-                    (false, FilteredGameState::Ready { .. }) => PrimaryState::Ready,
-                    (false, FilteredGameState::Set) => PrimaryState::Set,
-                    (false, FilteredGameState::Playing { .. }) => PrimaryState::Playing,
-                    (false, FilteredGameState::Finished) => PrimaryState::Finished,
-                };
-            robot.database.main_outputs.filtered_game_state = Some(self.filtered_game_state);
-            robot.database.main_outputs.game_controller_state = Some(self.game_controller_state);
-
-            robot.cycle(messages_with_time)?;
+            perceive(
+                robot,
+                (Team::Opponent, *player_number),
+                self.ball.as_ref(),
+                &robot_positions,
+                &self.perception,
+                &mut self.random_number_generator,
+                now,
+            );
+            if self.whistle.hears_whistle(
+                referee_whistled,
+                time_step,
+                &mut self.random_number_generator,
+            ) {
+                robot.whistle_heard_at = Some(self.time_elapsed);
+            }
+            let filtered_game_state = self.whistle.filtered_game_state(
+                opponent_filtered_game_state,
+                opponent_game_controller_state.kicking_team,
+                robot.whistle_heard_at,
+                self.time_elapsed,
+            );
+            set_game_states(robot, opponent_game_controller_state, filtered_game_state);
+
+            robot.cycle(BTreeMap::from_iter([(
+                now,
+                incoming_messages.iter().collect(),
+            )]))?;
 
             for message in robot.interface.take_outgoing_messages() {
                 if let OutgoingMessage::Spl(message) = message {
-                    self.messages.push((*player_number, message));
-                    self.game_controller_state.remaining_amount_of_messages -= 1
+                    self.opponent_messages
+                        .push((*player_number, message.message));
                 }
             }
         }
 
-        Ok(())
+        Ok(events)
+    }
+
+    /// Positions of all robots on the field in field coordinates of the Hulks
+    fn robot_positions(&self) -> Vec<(Team, PlayerNumber, Point2<f32>)> {
+        let robots = [
+            (Team::Hulks, &self.robots),
+            (Team::Opponent, &self.opponents),
+        ]
+        .into_iter()
+        .flat_map(|(team, robots)| {
+            robots
+                .iter()
+                // penalized robots are removed from the field
+                .filter(|(_player_number, robot)| !robot.is_penalized)
+                .filter_map(move |(player_number, robot)| {
+                    let robot_to_field =
+                        team_to_field(team) * robot.database.main_outputs.robot_to_field?.inner;
+                    Some((
+                        team,
+                        *player_number,
+                        Point2::from(robot_to_field.translation.vector),
+                    ))
+                })
+        });
+        let scripted_opponents = self
+            .scripted_opponents
+            .iter()
+            .map(|opponent| (Team::Opponent, opponent.player_number, opponent.position));
+        robots.chain(scripted_opponents).collect()
+    }
+
+    /// Poses of all opponents in field coordinates of the Hulks
+    pub fn opponent_poses(&self) -> Players<Option<Isometry2<f32>>> {
+        let mut poses = Players::<Option<Isometry2<f32>>>::default();
+        for (player_number, robot) in &self.opponents {
+            poses[*player_number] = robot
+                .database
+                .main_outputs
+                .robot_to_field
+                .map(|robot_to_field| team_to_field(Team::Opponent) * robot_to_field.inner);
+        }
+        for opponent in &self.scripted_opponents {
+            poses[opponent.player_number] = Some(Isometry2::new(
+                opponent.position.coords,
+                opponent.orientation,
+            ));
+        }
+        poses
+    }
+
+    /// GameController state as received by the opponents, who refer to themselves as Hulks
+    fn opponent_game_controller_state(&self) -> GameControllerState {
+        GameControllerState {
+            kicking_team: opposing_team(self.game_controller_state.kicking_team),
+            penalties: Default::default(),
+            hulks_team_is_home_after_coin_toss: !self
+                .game_controller_state
+                .hulks_team_is_home_after_coin_toss,
+            hulks_team_colors: self.game_controller_state.opponent_team_colors,
+            opponent_team_colors: self.game_controller_state.hulks_team_colors,
+            ..self.game_controller_state
+        }
+    }
+
+    fn opponent_filtered_game_state(&self) -> FilteredGameState {
+        match (&self.game_controller, self.filtered_game_state) {
+            (Some(game_controller), _) => {
+                game_controller.filtered_game_state(Team::Opponent, self.time_elapsed)
+            }
+            (None, FilteredGameState::Ready { kicking_team }) => FilteredGameState::Ready {
+                kicking_team: opposing_team(kicking_team),
+            },
+            (None, filtered_game_state) => filtered_game_state,
+        }
+    }
+
+    /// Sends the messages of the last cycle over the network and returns the ones arriving now
+    fn transmit_messages(&mut self) -> Vec<InFlightMessage> {
+        for (sender, message) in take(&mut self.messages) {
+            for receiver in self.robots.keys().filter(|receiver| **receiver != sender) {
+                if let Some(arrival) = self.network.transmit(
+                    sender,
+                    *receiver,
+                    self.time_elapsed,
+                    &mut self.random_number_generator,
+                ) {
+                    self.messages_in_flight.push(InFlightMessage {
+                        receiver: *receiver,
+                        message,
+                        arrival,
+                    });
+                }
+            }
+        }
+        let (arrived, in_flight): (Vec<_>, Vec<_>) = take(&mut self.messages_in_flight)
+            .into_iter()
+            .partition(|in_flight_message| in_flight_message.arrival <= self.time_elapsed);
+        self.messages_in_flight = in_flight;
+        arrived
     }
 
     fn move_ball(&mut self, time_step: Duration) -> Vec<Event> {
@@ -271,7 +462,13 @@ impl State {
             ball.velocity *= 0.98;
 
             if ball.position.x.abs() > 4.5 && ball.position.y < 0.75 {
-                events.push(Event::Goal);
+                // the Hulks attack the goal in positive x direction
+                let scoring_team = if ball.position.x > 0.0 {
+                    Team::Hulks
+                } else {
+                    Team::Opponent
+                };
+                events.push(Event::Goal { scoring_team });
             }
         }
         events
@@ -284,6 +481,8 @@ impl State {
             // TODO: Expose robot data to lua again
             // robots: self.robots.iter().map(LuaRobot::new).collect(),
             robots: Default::default(),
+            opponents: Default::default(),
+            scripted_opponents: self.scripted_opponents.clone(),
             ball: self.ball.clone(),
             messages: self.messages.clone(),
 
@@ -291,6 +490,10 @@ impl State {
 
             game_controller_state: self.game_controller_state,
             filtered_game_state: self.filtered_game_state,
+            perception: self.perception.clone(),
+            network: self.network.clone(),
+            whistle: self.whistle.clone(),
+            score: self.game_controller.as_ref().map(GameController::score),
         }
     }
 
@@ -304,26 +507,250 @@ impl State {
             robot.parameters = lua_robot.parameters;
             self.robots.insert(robot.parameters.player_number, robot);
         }
+        for lua_robot in lua_state.opponents {
+            let mut robot = Robot::try_new(lua_robot.parameters.player_number)
+                .expect("Creating dummy robot should never fail");
+            robot.database = lua_robot.database;
+            robot.parameters = lua_robot.parameters;
+            self.opponents.insert(robot.parameters.player_number, robot);
+        }
+        self.scripted_opponents = lua_state.scripted_opponents;
 
         self.finished = lua_state.finished;
 
         self.game_controller_state = lua_state.game_controller_state;
         self.filtered_game_state = lua_state.filtered_game_state;
+        self.perception = lua_state.perception;
+        self.network = lua_state.network;
+        self.whistle = lua_state.whistle;
 
         Ok(())
     }
 }
 
+/// Moves the robot according to its motion command and returns whether it kicked the ball
+fn move_robot(
+    robot: &mut Robot,
+    team: Team,
+    ball: Option<&mut Ball>,
+    time_elapsed: Duration,
+    time_step: Duration,
+) -> bool {
+    let mut has_kicked = false;
+    let robot_to_field = robot
+        .database
+        .main_outputs
+        .robot_to_field
+        .as_mut()
+        .map(|robot_to_field| &mut robot_to_field.inner)
+        .expect("simulated robots should always have a known pose");
+
+    robot.database.additional_outputs = AdditionalOutputs::default();
+    let head_motion = match &robot.database.main_outputs.motion_command {
+        MotionCommand::Walk {
+            head,
+            path,
+            orientation_mode,
+            ..
+        } => {
+            let step = match path[0] {
+                PathSegment::LineSegment(LineSegment(_start, end)) => end.coords,
+                PathSegment::Arc(arc, orientation) => {
+                    orientation.rotate_vector_90_degrees(arc.start - arc.circle.center)
+                }
+            }
+            .cap_magnitude(0.3 * time_step.as_secs_f32());
+
+            let orientation = match orientation_mode {
+                OrientationMode::AlignWithPath => {
+                    if step.norm_squared() < f32::EPSILON {
+                        UnitComplex::identity()
+                    } else {
+                        UnitComplex::from_cos_sin_unchecked(step.x, step.y)
+                    }
+                }
+                OrientationMode::Override(orientation) => *orientation,
+                OrientationMode::FaceTowards(target) => {
+                    UnitComplex::rotation_between(&Vector2::x(), &target.coords)
+                }
+            };
+
+            *robot_to_field = Isometry2::new(
+                robot_to_field.translation.vector + robot_to_field.rotation * step,
+                robot_to_field.rotation.angle()
+                    + orientation.angle().clamp(
+                        -std::f32::consts::FRAC_PI_4 * time_step.as_secs_f32(),
+                        std::f32::consts::FRAC_PI_4 * time_step.as_secs_f32(),
+                    ),
+            );
+
+            head
+        }
+        MotionCommand::InWalkKick {
+            head,
+            kick,
+            kicking_side,
+            strength,
+        } => {
+            if let Some(ball) = ball {
+                let side = match kicking_side {
+                    Side::Left => 1.0,
+                    Side::Right => -1.0,
+                };
+
+                // TODO: Check if ball is even in range
+                // let kick_location = robot_to_field * ();
+                if (time_elapsed - robot.last_kick_time).as_secs_f32() > 1.0 {
+                    let direction = match kick {
+                        KickVariant::Forward => vector![1.0, 0.0],
+                        KickVariant::Turn => vector![0.707, 0.707 * side],
+                        KickVariant::Side => vector![0.0, 1.0 * -side],
+                    };
+                    ball.velocity +=
+                        team_to_field(team) * (*robot_to_field * direction) * *strength * 2.5;
+                    robot.last_kick_time = time_elapsed;
+                    has_kicked = true;
+                };
+            }
+            head
+        }
+        MotionCommand::SitDown { head } => head,
+        MotionCommand::Stand {
+            head,
+            is_energy_saving: _,
+        } => head,
+        _ => &HeadMotion::Center,
+    };
+
+    let f = time_elapsed.as_secs_f32().sin();
+    let desired_head_yaw = match head_motion {
+        HeadMotion::ZeroAngles => 0.0,
+        HeadMotion::Center => 0.0,
+        HeadMotion::LookAround { profile } => {
+            scan_positions(
+                &robot.parameters.head_motion.scan_profiles[*profile],
+                time_elapsed,
+            )
+            .yaw
+        }
+        HeadMotion::SearchForLostBall => robot.database.main_outputs.look_around.yaw,
+        HeadMotion::LookAt { target, .. } => target.coords.angle(&Vector2::x_axis()),
+        HeadMotion::LookLeftAndRightOf { target } => target.coords.angle(&Vector2::x_axis()) + f,
+        HeadMotion::Angles { yaw, .. } => *yaw,
+        HeadMotion::Unstiff => 0.0,
+    };
+
+    let max_head_rotation_per_cycle =
+        robot.parameters.head_motion.maximum_velocity.yaw * time_step.as_secs_f32();
+    let diff = desired_head_yaw - robot.database.main_outputs.sensor_data.positions.head.yaw;
+    let movement = diff.clamp(-max_head_rotation_per_cycle, max_head_rotation_per_cycle);
+
+    robot.database.main_outputs.sensor_data.positions.head.yaw += movement;
+    has_kicked
+}
+
+#[allow(clippy::too_many_arguments)]
+fn perceive(
+    robot: &mut Robot,
+    (team, player_number): (Team, PlayerNumber),
+    ball: Option<&Ball>,
+    robot_positions: &[(Team, PlayerNumber, Point2<f32>)],
+    perception: &PerceptionModel,
+    random_number_generator: &mut StdRng,
+    now: SystemTime,
+) {
+    let robot_to_field = team_to_field(team)
+        * robot
+            .database
+            .main_outputs
+            .robot_to_field
+            .expect("simulated robots should always have a known pose")
+            .inner;
+    let head_yaw = robot.database.main_outputs.sensor_data.positions.head.yaw;
+    let field_of_view = robot.field_of_view();
+
+    robot.database.main_outputs.cycle_time.start_time = now;
+
+    robot.database.main_outputs.ball_position = ball.and_then(|ball| {
+        perception
+            .perceive(
+                robot_to_field.inverse() * ball.position,
+                head_yaw,
+                field_of_view,
+                random_number_generator,
+            )
+            .map(|position| BallPosition {
+                position,
+                velocity: robot_to_field.inverse() * ball.velocity,
+                last_seen: now,
+                confidence: 1.0,
+            })
+    });
+    robot.database.main_outputs.last_obstacle_measurement = Some(now);
+    robot.database.main_outputs.obstacles = robot_positions
+        .iter()
+        .filter(|(other_team, other_player_number, _position)| {
+            (*other_team, *other_player_number) != (team, player_number)
+        })
+        .filter_map(|(other_team, _other_player_number, position)| {
+            let position = perception.perceive(
+                robot_to_field.inverse() * *position,
+                head_yaw,
+                field_of_view,
+                random_number_generator,
+            )?;
+            let kind = if *other_team == team {
+                ObstacleKind::Teammate
+            } else {
+                ObstacleKind::Opponent
+            };
+            Some(Obstacle {
+                kind,
+                ..Obstacle::robot(
+                    position,
+                    ROBOT_OBSTACLE_RADIUS_AT_FOOT_HEIGHT,
+                    ROBOT_OBSTACLE_RADIUS_AT_HIP_HEIGHT,
+                )
+            })
+        })
+        .collect();
+}
+
+fn set_game_states(
+    robot: &mut Robot,
+    game_controller_state: GameControllerState,
+    filtered_game_state: FilteredGameState,
+) {
+    robot.database.main_outputs.primary_state = match (robot.is_penalized, filtered_game_state) {
+        (true, _) => PrimaryState::Penalized,
+        (false, FilteredGameState::Initial) => PrimaryState::Initial,
+        (false, FilteredGameState::Ready { .. }) => PrimaryState::Ready,
+        (false, FilteredGameState::Set) => PrimaryState::Set,
+        (false, FilteredGameState::Playing { .. }) => PrimaryState::Playing,
+        (false, FilteredGameState::Finished) => PrimaryState::Finished,
+    };
+    robot.database.main_outputs.filtered_game_state = Some(filtered_game_state);
+    robot.database.main_outputs.game_controller_state = Some(game_controller_state);
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct LuaState {
     pub time_elapsed: f32,
     pub cycle_count: usize,
     pub robots: Vec<LuaRobot>,
+    /// Opponents driven by our behavior, created with `create_robot` like the Hulks
+    pub opponents: Vec<LuaRobot>,
+    pub scripted_opponents: Vec<ScriptedOpponent>,
     pub ball: Option<Ball>,
     pub messages: Vec<(PlayerNumber, HulkMessage)>,
     pub finished: bool,
     pub game_controller_state: GameControllerState,
     pub filtered_game_state: FilteredGameState,
+    pub perception: PerceptionModel,
+    pub network: NetworkModel,
+    pub whistle: WhistleModel,
+    /// Only available after the simulated GameController started the game
+    pub score: Option<Score>,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -0,0 +1,227 @@
+use std::time::Duration;
+
+use rand::{rngs::StdRng, Rng};
+use serde::{Deserialize, Serialize};
+use spl_network_messages::Team;
+use types::FilteredGameState;
+
+/// Hearing of whistles by the simulated robots
+///
+/// A robot hearing a whistle in Set starts playing before the GameController sends Playing,
+/// which happens `game_controller_delay` seconds after the whistle of the referee. Like the game
+/// state filter, the robot returns to Set if the GameController does not confirm the whistle
+/// within `confirmation_timeout` seconds, e.g. after a false positive. The defaults hear every
+/// whistle of the referee and the GameController reacts instantly.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WhistleModel {
+    /// Probability of a robot to hear a whistle of the referee
+    pub detection_probability: f32,
+    /// Expected number of whistles per second a robot hears although nobody whistled
+    pub false_positive_rate: f32,
+    pub game_controller_delay: f32,
+    pub confirmation_timeout: f32,
+}
+
+impl Default for WhistleModel {
+    fn default() -> Self {
+        Self {
+            detection_probability: 1.0,
+            false_positive_rate: 0.0,
+            game_controller_delay: 0.0,
+            confirmation_timeout: 0.0,
+        }
+    }
+}
+
+impl WhistleModel {
+    /// Returns whether a robot hears a whistle in the cycle of length `time_step`
+    pub fn hears_whistle(
+        &self,
+        referee_whistled: bool,
+        time_step: Duration,
+        random_number_generator: &mut StdRng,
+    ) -> bool {
+        let detects_referee = referee_whistled
+            && random_number_generator.gen_bool(self.detection_probability.clamp(0.0, 1.0) as f64);
+        // no random numbers are drawn without false positives to keep existing scenarios stable
+        let false_positive_probability = self.false_positive_rate * time_step.as_secs_f32();
+        let detects_false_positive = false_positive_probability > 0.0
+            && random_number_generator.gen_bool(false_positive_probability.clamp(0.0, 1.0) as f64);
+        detects_referee || detects_false_positive
+    }
+
+    pub fn game_controller_delay(&self) -> Duration {
+        Duration::from_secs_f32(self.game_controller_delay.max(0.0))
+    }
+
+    /// Game state a robot concludes from the GameController and the last whistle it heard
+    pub fn filtered_game_state(
+        &self,
+        filtered_game_state: FilteredGameState,
+        kicking_team: Team,
+        whistle_heard_at: Option<Duration>,
+        now: Duration,
+    ) -> FilteredGameState {
+        let is_whistle_unconfirmed = whistle_heard_at.map_or(false, |whistle_heard_at| {
+            (now - whistle_heard_at).as_secs_f32() < self.confirmation_timeout
+        });
+        match filtered_game_state {
+            FilteredGameState::Set if is_whistle_unconfirmed => FilteredGameState::Playing {
+                ball_is_free: kicking_team == Team::Hulks,
+            },
+            filtered_game_state => filtered_game_state,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{RngCore, SeedableRng};
+
+    use super::*;
+
+    const TIME_STEP: Duration = Duration::from_millis(10);
+
+    fn model_with_timeout() -> WhistleModel {
+        WhistleModel {
+            confirmation_timeout: 2.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn missed_whistle_keeps_robot_in_set() {
+        let filtered_game_state = model_with_timeout().filtered_game_state(
+            FilteredGameState::Set,
+            Team::Hulks,
+            None,
+            Duration::from_secs(10),
+        );
+
+        assert!(matches!(filtered_game_state, FilteredGameState::Set));
+    }
+
+    #[test]
+    fn heard_whistle_starts_playing_before_game_controller() {
+        let model = model_with_timeout();
+        let whistle_heard_at = Some(Duration::from_secs(10));
+
+        assert!(matches!(
+            model.filtered_game_state(
+                FilteredGameState::Set,
+                Team::Hulks,
+                whistle_heard_at,
+                Duration::from_secs_f32(10.5),
+            ),
+            FilteredGameState::Playing { ball_is_free: true }
+        ));
+        assert!(matches!(
+            model.filtered_game_state(
+                FilteredGameState::Set,
+                Team::Opponent,
+                whistle_heard_at,
+                Duration::from_secs_f32(10.5),
+            ),
+            FilteredGameState::Playing {
+                ball_is_free: false
+            }
+        ));
+    }
+
+    #[test]
+    fn unconfirmed_false_positive_returns_to_set_after_confirmation_timeout() {
+        let model = model_with_timeout();
+        let whistle_heard_at = Some(Duration::from_secs(10));
+
+        assert!(matches!(
+            model.filtered_game_state(
+                FilteredGameState::Set,
+                Team::Hulks,
+                whistle_heard_at,
+                Duration::from_secs_f32(11.9),
+            ),
+            FilteredGameState::Playing { .. }
+        ));
+        assert!(matches!(
+            model.filtered_game_state(
+                FilteredGameState::Set,
+                Team::Hulks,
+                whistle_heard_at,
+                Duration::from_secs(12),
+            ),
+            FilteredGameState::Set
+        ));
+    }
+
+    #[test]
+    fn game_controller_state_is_kept_outside_of_set() {
+        let filtered_game_state = model_with_timeout().filtered_game_state(
+            FilteredGameState::Ready {
+                kicking_team: Team::Hulks,
+            },
+            Team::Hulks,
+            Some(Duration::from_secs(10)),
+            Duration::from_secs_f32(10.5),
+        );
+
+        assert!(matches!(
+            filtered_game_state,
+            FilteredGameState::Ready {
+                kicking_team: Team::Hulks
+            }
+        ));
+    }
+
+    #[test]
+    fn default_model_hears_exactly_the_referee_without_drawing_random_numbers() {
+        let model = WhistleModel::default();
+        let mut random_number_generator = StdRng::seed_from_u64(42);
+
+        assert!(!model.hears_whistle(false, TIME_STEP, &mut random_number_generator));
+        assert_eq!(
+            random_number_generator.next_u64(),
+            StdRng::seed_from_u64(42).next_u64()
+        );
+        assert!((0..1000).all(|_| model.hears_whistle(
+            true,
+            TIME_STEP,
+            &mut random_number_generator
+        )));
+    }
+
+    #[test]
+    fn whistles_are_heard_with_configured_probability_and_rate() {
+        let mut random_number_generator = StdRng::seed_from_u64(42);
+        let unreliable_model = WhistleModel {
+            detection_probability: 0.7,
+            ..Default::default()
+        };
+        let noisy_model = WhistleModel {
+            detection_probability: 0.0,
+            false_positive_rate: 0.5,
+            ..Default::default()
+        };
+
+        let number_of_detections = (0..10000)
+            .filter(|_| {
+                unreliable_model.hears_whistle(true, TIME_STEP, &mut random_number_generator)
+            })
+            .count();
+        // 0.5 false positives per second over 10000 cycles of 10 ms
+        let number_of_false_positives = (0..10000)
+            .filter(|_| noisy_model.hears_whistle(false, TIME_STEP, &mut random_number_generator))
+            .count();
+
+        assert!((6800..7200).contains(&number_of_detections));
+        assert!((30..75).contains(&number_of_false_positives));
+        let deaf_model = WhistleModel {
+            detection_probability: 0.0,
+            ..Default::default()
+        };
+        assert!(!(0..1000).any(|_| deaf_model.hears_whistle(
+            true,
+            TIME_STEP,
+            &mut random_number_generator
+        )));
+    }
+}
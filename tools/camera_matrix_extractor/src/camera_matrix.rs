@@ -1,5 +1,6 @@
 use nalgebra::{point, vector, Isometry3, Point2, UnitQuaternion, Vector2, Vector3};
 use serde::{Deserialize, Serialize};
+use types::units::{Degrees, Radians};
 
 use crate::{
     ground_calculation::Ground, robot_dimensions::RobotDimensions,
@@ -43,7 +44,7 @@ impl CameraMatrix {
         camera_position: CameraPosition,
         ground: Ground,
         robot_kinematics: RobotKinematics,
-        extrinsic_rotation: Vector3<f32>,
+        extrinsic_rotation: Vector3<Degrees>,
         focal_length: Vector2<f32>,
         optical_center: Vector2<f32>,
     ) -> Self {
@@ -60,9 +61,9 @@ impl CameraMatrix {
 
     fn camera_to_head(
         camera_position: CameraPosition,
-        extrinsic_rotation: Vector3<f32>,
+        extrinsic_rotation: Vector3<Degrees>,
     ) -> Isometry3<f32> {
-        let extrinsic_angles_in_radians = extrinsic_rotation.map(|a: f32| a.to_radians());
+        let extrinsic_angles_in_radians = extrinsic_rotation.map(|angle| Radians::from(angle).0);
         let extrinsic_rotation = UnitQuaternion::from_euler_angles(
             extrinsic_angles_in_radians.x,
             extrinsic_angles_in_radians.y,
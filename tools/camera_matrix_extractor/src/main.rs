@@ -14,6 +14,7 @@ use color_eyre::{eyre::WrapErr, Result};
 use nalgebra::vector;
 use serde_json::to_value;
 use structopt::StructOpt;
+use types::units::Degrees;
 
 use crate::{
     camera_matrix::{CameraMatrix, CameraPosition},
@@ -60,9 +61,9 @@ fn main() -> Result<()> {
         CameraPosition::Bottom
     };
     let extrinsic_rotation = if arguments.image_prefix.starts_with("top") {
-        vector![0.5, -5.5, 1.0]
+        vector![Degrees(0.5), Degrees(-5.5), Degrees(1.0)]
     } else {
-        vector![0.8, -4.0, 1.0]
+        vector![Degrees(0.8), Degrees(-4.0), Degrees(1.0)]
     };
     let focal_length = vector![0.95, 1.27];
     let optical_center = vector![0.5, 0.5];
@@ -133,7 +133,7 @@ fn handle_lola_event(
 ) -> Result<()> {
     let since_start = proxy_start.elapsed();
     let mut robot_state = read_lola_message(lola).wrap_err("failed to read lola message")?;
-    robot_state.received_at = since_start.as_secs_f32();
+    robot_state.received_at = since_start.as_secs_f64();
     {
         let mut shared_state = shared_state.lock().unwrap();
         shared_state.battery = Some(robot_state.battery);
@@ -5,7 +5,8 @@ use zbus::zvariant::Type;
 #[repr(C)]
 pub struct RobotState {
     #[serde(skip)]
-    pub received_at: f32, // TODO convert to Duration?
+    /// Seconds since proxy start
+    pub received_at: f64,
     #[serde(rename = "RobotConfig")]
     pub robot_configuration: RobotConfiguration,
     #[serde(rename = "Battery")]
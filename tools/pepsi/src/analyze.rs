@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use std::{
+    fs::{copy, read_to_string},
+    path::PathBuf,
+};
 
 use bat::{PagingMode, PrettyPrinter};
 use clap::Subcommand;
@@ -18,6 +21,18 @@ pub enum Arguments {
         /// File name to dump (may contain wildcard characters usable by glob())
         file_name: String,
     },
+    /// Dump the node graph of the latest build, render the DOT output e.g. with `dot -Tsvg`
+    DumpGraph {
+        /// Crate whose build generated the graph, e.g. `behavior_simulator`
+        #[arg(long, default_value = "hulk")]
+        crate_name: String,
+        /// Dump the graph as JSON instead of Graphviz DOT
+        #[arg(long)]
+        json: bool,
+        /// Write the graph to this file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
 }
 
 pub async fn analyze(arguments: Arguments, repository: &Repository) -> Result<()> {
@@ -46,6 +61,33 @@ pub async fn analyze(arguments: Arguments, repository: &Repository) -> Result<()
                 .print()
                 .wrap_err("failed to print file")?;
         }
+        Arguments::DumpGraph {
+            crate_name,
+            json,
+            output,
+        } => {
+            let extension = if json { "json" } else { "dot" };
+            let glob = format!("target/**/build/{crate_name}-*/out/module_graph.{extension}");
+            let file_path = repository
+                .find_latest_file(&glob)
+                .wrap_err("failed to find latest module graph, build the crate first")?;
+            match output {
+                Some(output) => {
+                    copy(&file_path, &output).wrap_err_with(|| {
+                        format!(
+                            "failed to copy {} to {}",
+                            file_path.display(),
+                            output.display()
+                        )
+                    })?;
+                }
+                None => {
+                    let graph = read_to_string(&file_path)
+                        .wrap_err_with(|| format!("failed to read {}", file_path.display()))?;
+                    print!("{graph}");
+                }
+            }
+        }
     }
 
     Ok(())
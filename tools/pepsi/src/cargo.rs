@@ -11,6 +11,7 @@ pub struct Arguments {
     pub workspace: bool,
     #[arg(long, default_value = "incremental")]
     pub profile: String,
+    /// Hardware interface the robot binary is built with (nao, webots, replayer, headless)
     #[arg(long, default_value = "webots")]
     pub target: String,
     #[arg(long)]
@@ -2,7 +2,7 @@ use std::{collections::BTreeSet, sync::Mutex};
 
 use communication::{
     client::{Communication, ConnectionStatus, CyclerOutput},
-    messages::{Fields, Path},
+    messages::{Encoding, Fields, Path},
 };
 
 use serde_json::Value;
@@ -20,11 +20,12 @@ impl Nao {
     pub fn new(address: Option<String>, connect: bool) -> Self {
         let runtime = Builder::new_multi_thread().enable_all().build().unwrap();
         let _guard = runtime.enter();
-        let communication = Communication::new(
+        let communication = Communication::new_with_encoding(
             address
                 .as_ref()
                 .map(|ip_address| ip_address_to_communication_url(ip_address)),
             connect,
+            Encoding::MessagePack,
         );
         Self {
             communication,
@@ -106,11 +106,21 @@ impl Widget for &mut BehaviorSimulatorPanel {
             let time_elapsed = now - self.playing_start;
             new_frame = Some((time_elapsed * 83.0) as usize);
         }
-        if ui.button(">>").clicked() {
-            new_frame = Some(new_frame.unwrap_or(self.selected_frame) + 10);
-        }
+        let frame_count = self
+            .frame_count
+            .require_latest::<usize>()
+            .unwrap_or(1)
+            .max(1);
+        ui.horizontal(|ui| {
+            for (label, step) in [("<<", -10), ("<", -1), (">", 1), (">>", 10)] {
+                if ui.button(label).clicked() {
+                    let frame = new_frame.unwrap_or(self.selected_frame) as isize + step;
+                    new_frame = Some(frame.rem_euclid(frame_count as isize) as usize);
+                }
+            }
+        });
         if let Some(new_frame) = new_frame {
-            self.selected_frame = new_frame % self.frame_count.require_latest().unwrap_or(1);
+            self.selected_frame = new_frame % frame_count;
             self.nao
                 .update_parameter_value("selected_frame", self.selected_frame.into());
         }
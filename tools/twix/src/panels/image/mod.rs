@@ -176,7 +176,7 @@ impl ImagePanel {
         let displayed_image_size = image_size * scale;
         let image_rect = Rect::from_min_size(image_response.rect.left_top(), displayed_image_size);
         let painter = TwixPainter::paint_at(ui, image_rect).with_camera(
-            vector![640.0, 480.0],
+            vector![image_size.x, image_size.y],
             Similarity2::identity(),
             CoordinateSystem::LeftHand,
         );
@@ -7,7 +7,7 @@ use eframe::{
 };
 use nalgebra::{point, vector, Similarity2};
 use serde_json::Value;
-use types::{CameraPosition, ImageSegments, Rgb, RgbChannel};
+use types::{CameraPosition, ImageSegments, Rgb, RgbChannel, Segment};
 
 use crate::{nao::Nao, panel::Panel, twix_painter::CoordinateSystem, value_buffer::ValueBuffer};
 
@@ -164,40 +164,12 @@ impl Widget for &mut ImageSegmentsPanel {
             }
         }
 
-        for scanline in image_segments.scan_grid.vertical_scan_lines {
+        for scanline in &image_segments.scan_grid.vertical_scan_lines {
             let x = scanline.position as f32;
-            for segment in scanline.segments {
-                let ycbcr_color = segment.color;
-                let rgb_color = Rgb::from(ycbcr_color);
+            for segment in &scanline.segments {
                 let start = point![x, segment.start as f32];
                 let end = point![x, segment.end as f32];
-                let original_color = Color32::from_rgb(rgb_color.r, rgb_color.g, rgb_color.b);
-                let medium_color = Color32::LIGHT_YELLOW;
-                let high_color = Color32::YELLOW;
-                let visualized_color = match self.color_mode {
-                    ColorMode::Original => original_color,
-                    ColorMode::FieldColor => match segment.field_color {
-                        types::Intensity::Low => original_color,
-                        types::Intensity::Medium => medium_color,
-                        types::Intensity::High => high_color,
-                    },
-                    ColorMode::Y => Color32::from_gray(ycbcr_color.y),
-                    ColorMode::Cb => Color32::from_gray(ycbcr_color.cb),
-                    ColorMode::Cr => Color32::from_gray(ycbcr_color.cr),
-                    ColorMode::Red => Color32::from_gray(rgb_color.r),
-                    ColorMode::Green => Color32::from_gray(rgb_color.g),
-                    ColorMode::Blue => Color32::from_gray(rgb_color.b),
-                    ColorMode::RedChromaticity => Color32::from_gray(
-                        (rgb_color.get_chromaticity(RgbChannel::Red) * 255.0) as u8,
-                    ),
-                    ColorMode::GreenChromaticity => Color32::from_gray(
-                        (rgb_color.get_chromaticity(RgbChannel::Green) * 255.0) as u8,
-                    ),
-                    ColorMode::BlueChromaticity => Color32::from_gray(
-                        (rgb_color.get_chromaticity(RgbChannel::Blue) * 255.0) as u8,
-                    ),
-                };
-                painter.line_segment(start, end, Stroke::new(4.0, visualized_color));
+                painter.line_segment(start, end, Stroke::new(4.0, self.color_of(segment)));
                 painter.line_segment(
                     start - vector![1.0, 0.0],
                     start + vector![1.0, 0.0],
@@ -205,6 +177,52 @@ impl Widget for &mut ImageSegmentsPanel {
                 );
             }
         }
+        for scanline in &image_segments.scan_grid.horizontal_scan_lines {
+            let y = scanline.position as f32;
+            for segment in &scanline.segments {
+                let start = point![segment.start as f32, y];
+                let end = point![segment.end as f32, y];
+                painter.line_segment(start, end, Stroke::new(2.0, self.color_of(segment)));
+                painter.line_segment(
+                    start - vector![0.0, 1.0],
+                    start + vector![0.0, 1.0],
+                    Stroke::new(1.0, Color32::from_rgb(0, 0, 255)),
+                );
+            }
+        }
         response
     }
 }
+
+impl ImageSegmentsPanel {
+    fn color_of(&self, segment: &Segment) -> Color32 {
+        let ycbcr_color = segment.color;
+        let rgb_color = Rgb::from(ycbcr_color);
+        let original_color = Color32::from_rgb(rgb_color.r, rgb_color.g, rgb_color.b);
+        let medium_color = Color32::LIGHT_YELLOW;
+        let high_color = Color32::YELLOW;
+        match self.color_mode {
+            ColorMode::Original => original_color,
+            ColorMode::FieldColor => match segment.field_color {
+                types::Intensity::Low => original_color,
+                types::Intensity::Medium => medium_color,
+                types::Intensity::High => high_color,
+            },
+            ColorMode::Y => Color32::from_gray(ycbcr_color.y),
+            ColorMode::Cb => Color32::from_gray(ycbcr_color.cb),
+            ColorMode::Cr => Color32::from_gray(ycbcr_color.cr),
+            ColorMode::Red => Color32::from_gray(rgb_color.r),
+            ColorMode::Green => Color32::from_gray(rgb_color.g),
+            ColorMode::Blue => Color32::from_gray(rgb_color.b),
+            ColorMode::RedChromaticity => {
+                Color32::from_gray((rgb_color.get_chromaticity(RgbChannel::Red) * 255.0) as u8)
+            }
+            ColorMode::GreenChromaticity => {
+                Color32::from_gray((rgb_color.get_chromaticity(RgbChannel::Green) * 255.0) as u8)
+            }
+            ColorMode::BlueChromaticity => {
+                Color32::from_gray((rgb_color.get_chromaticity(RgbChannel::Blue) * 255.0) as u8)
+            }
+        }
+    }
+}
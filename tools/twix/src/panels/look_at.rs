@@ -171,14 +171,16 @@ impl Widget for &mut LookAtPanel {
                     current_field_dimensions
                         .as_ref()
                         .map_or(DEFAULT_TARGET, |dimensions| {
-                            let half_field_length = dimensions.length / 2.0;
+                            let half_field_length = dimensions.length.0 / 2.0;
                             point![half_field_length, 0.0]
                         })
                 }
                 LookAtType::Manual => {
                     let max_dimension = current_field_dimensions
                         .as_ref()
-                        .map_or(FALLBACK_MAX_FIELD_DIMENSION, |dimensions| dimensions.length);
+                        .map_or(FALLBACK_MAX_FIELD_DIMENSION, |dimensions| {
+                            dimensions.length.0
+                        });
 
                     ui.add(
                         Slider::new(
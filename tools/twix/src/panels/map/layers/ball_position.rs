@@ -44,7 +44,7 @@ impl Layer for BallPosition {
             if let Some(ball) = ball {
                 painter.circle_filled(
                     robot_to_field.unwrap_or_default() * ball.position,
-                    field_dimensions.ball_radius,
+                    field_dimensions.ball_radius.0,
                     Color32::from_white_alpha(10),
                 );
             }
@@ -56,7 +56,7 @@ impl Layer for BallPosition {
         ) {
             painter.ball(
                 robot_to_field.unwrap_or_default() * ball.position,
-                field_dimensions.ball_radius,
+                field_dimensions.ball_radius.0,
             );
         }
         Ok(())
@@ -3,7 +3,10 @@ use std::sync::Arc;
 use eframe::egui::Widget;
 use gilrs::{Axis, Button, Gamepad, GamepadId, Gilrs};
 use serde_json::{json, Value};
-use types::Step;
+use types::{
+    units::{Meters, Radians},
+    Step,
+};
 
 use crate::{nao::Nao, panel::Panel};
 
@@ -73,9 +76,9 @@ impl Widget for &mut RemotePanel {
             let turn = -turn_right;
 
             let step = Step {
-                forward,
-                left,
-                turn,
+                forward: Meters(forward),
+                left: Meters(left),
+                turn: Radians(turn),
             };
 
             if self.enabled {
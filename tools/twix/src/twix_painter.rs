@@ -82,8 +82,8 @@ impl TwixPainter {
     }
 
     pub fn with_map_transforms(self, field_dimensions: &FieldDimensions) -> Self {
-        let length = field_dimensions.length + field_dimensions.border_strip_width * 2.0;
-        let width = field_dimensions.width + field_dimensions.border_strip_width * 2.0;
+        let length = field_dimensions.length.0 + field_dimensions.border_strip_width.0 * 2.0;
+        let width = field_dimensions.width.0 + field_dimensions.border_strip_width.0 * 2.0;
         let dimensions = vector![length, width];
         let world_to_camera = Similarity2::new(vector![length / 2.0, width / 2.0], 0.0, 1.0);
         self.with_camera(dimensions, world_to_camera, CoordinateSystem::RightHand)
@@ -94,19 +94,19 @@ impl TwixPainter {
     }
 
     pub fn field(&self, field_dimensions: &FieldDimensions) {
-        let line_stroke = Stroke::new(field_dimensions.line_width, Color32::WHITE);
+        let line_stroke = Stroke::new(field_dimensions.line_width.0, Color32::WHITE);
         let goal_post_stroke =
-            Stroke::new(field_dimensions.goal_post_diameter / 8.0, Color32::BLACK);
+            Stroke::new(field_dimensions.goal_post_diameter.0 / 8.0, Color32::BLACK);
 
         // Background
         self.rect_filled(
             point![
-                -field_dimensions.length / 2.0 - field_dimensions.border_strip_width,
-                -field_dimensions.width / 2.0 - field_dimensions.border_strip_width
+                -field_dimensions.length.0 / 2.0 - field_dimensions.border_strip_width.0,
+                -field_dimensions.width.0 / 2.0 - field_dimensions.border_strip_width.0
             ],
             point![
-                field_dimensions.length / 2.0 + field_dimensions.border_strip_width,
-                field_dimensions.width / 2.0 + field_dimensions.border_strip_width
+                field_dimensions.length.0 / 2.0 + field_dimensions.border_strip_width.0,
+                field_dimensions.width.0 / 2.0 + field_dimensions.border_strip_width.0
             ],
             Color32::DARK_GREEN,
         );
@@ -114,47 +114,50 @@ impl TwixPainter {
         // Outer lines
         self.rect_stroke(
             point![
-                -field_dimensions.length / 2.0,
-                -field_dimensions.width / 2.0
+                -field_dimensions.length.0 / 2.0,
+                -field_dimensions.width.0 / 2.0
+            ],
+            point![
+                field_dimensions.length.0 / 2.0,
+                field_dimensions.width.0 / 2.0
             ],
-            point![field_dimensions.length / 2.0, field_dimensions.width / 2.0],
             line_stroke,
         );
 
         // Center line
         self.line_segment(
-            point![0.0, -field_dimensions.width / 2.0],
-            point![0.0, field_dimensions.width / 2.0],
+            point![0.0, -field_dimensions.width.0 / 2.0],
+            point![0.0, field_dimensions.width.0 / 2.0],
             line_stroke,
         );
 
         // Center center
         self.circle_stroke(
             point![0.0, 0.0],
-            field_dimensions.center_circle_diameter / 2.0,
+            field_dimensions.center_circle_diameter.0 / 2.0,
             line_stroke,
         );
 
         // Penalty areas
         self.rect_stroke(
             point![
-                -field_dimensions.length / 2.0,
-                -field_dimensions.penalty_area_width / 2.0
+                -field_dimensions.length.0 / 2.0,
+                -field_dimensions.penalty_area_width.0 / 2.0
             ],
             point![
-                -field_dimensions.length / 2.0 + field_dimensions.penalty_area_length,
-                field_dimensions.penalty_area_width / 2.0
+                -field_dimensions.length.0 / 2.0 + field_dimensions.penalty_area_length.0,
+                field_dimensions.penalty_area_width.0 / 2.0
             ],
             line_stroke,
         );
         self.rect_stroke(
             point![
-                field_dimensions.length / 2.0 - field_dimensions.penalty_area_length,
-                -field_dimensions.penalty_area_width / 2.0
+                field_dimensions.length.0 / 2.0 - field_dimensions.penalty_area_length.0,
+                -field_dimensions.penalty_area_width.0 / 2.0
             ],
             point![
-                field_dimensions.length / 2.0,
-                field_dimensions.penalty_area_width / 2.0
+                field_dimensions.length.0 / 2.0,
+                field_dimensions.penalty_area_width.0 / 2.0
             ],
             line_stroke,
         );
@@ -162,23 +165,23 @@ impl TwixPainter {
         // Goal areas
         self.rect_stroke(
             point![
-                -field_dimensions.length / 2.0,
-                -field_dimensions.goal_box_area_width / 2.0
+                -field_dimensions.length.0 / 2.0,
+                -field_dimensions.goal_box_area_width.0 / 2.0
             ],
             point![
-                -field_dimensions.length / 2.0 + field_dimensions.goal_box_area_length,
-                field_dimensions.goal_box_area_width / 2.0
+                -field_dimensions.length.0 / 2.0 + field_dimensions.goal_box_area_length.0,
+                field_dimensions.goal_box_area_width.0 / 2.0
             ],
             line_stroke,
         );
         self.rect_stroke(
             point![
-                field_dimensions.length / 2.0 - field_dimensions.goal_box_area_length,
-                -field_dimensions.goal_box_area_width / 2.0
+                field_dimensions.length.0 / 2.0 - field_dimensions.goal_box_area_length.0,
+                -field_dimensions.goal_box_area_width.0 / 2.0
             ],
             point![
-                field_dimensions.length / 2.0,
-                field_dimensions.goal_box_area_width / 2.0
+                field_dimensions.length.0 / 2.0,
+                field_dimensions.goal_box_area_width.0 / 2.0
             ],
             line_stroke,
         );
@@ -186,51 +189,51 @@ impl TwixPainter {
         // Penalty spots
         self.line_segment(
             point![
-                -field_dimensions.length / 2.0 + field_dimensions.penalty_marker_distance
-                    - field_dimensions.penalty_marker_size / 2.0,
+                -field_dimensions.length.0 / 2.0 + field_dimensions.penalty_marker_distance.0
+                    - field_dimensions.penalty_marker_size.0 / 2.0,
                 0.0
             ],
             point![
-                -field_dimensions.length / 2.0
-                    + field_dimensions.penalty_marker_distance
-                    + field_dimensions.penalty_marker_size / 2.0,
+                -field_dimensions.length.0 / 2.0
+                    + field_dimensions.penalty_marker_distance.0
+                    + field_dimensions.penalty_marker_size.0 / 2.0,
                 0.0
             ],
             line_stroke,
         );
         self.line_segment(
             point![
-                -field_dimensions.length / 2.0 + field_dimensions.penalty_marker_distance,
-                -field_dimensions.penalty_marker_size / 2.0
+                -field_dimensions.length.0 / 2.0 + field_dimensions.penalty_marker_distance.0,
+                -field_dimensions.penalty_marker_size.0 / 2.0
             ],
             point![
-                -field_dimensions.length / 2.0 + field_dimensions.penalty_marker_distance,
-                field_dimensions.penalty_marker_size / 2.0
+                -field_dimensions.length.0 / 2.0 + field_dimensions.penalty_marker_distance.0,
+                field_dimensions.penalty_marker_size.0 / 2.0
             ],
             line_stroke,
         );
         self.line_segment(
             point![
-                field_dimensions.length / 2.0 - field_dimensions.penalty_marker_distance
-                    + field_dimensions.penalty_marker_size / 2.0,
+                field_dimensions.length.0 / 2.0 - field_dimensions.penalty_marker_distance.0
+                    + field_dimensions.penalty_marker_size.0 / 2.0,
                 0.0
             ],
             point![
-                field_dimensions.length / 2.0
-                    - field_dimensions.penalty_marker_distance
-                    - field_dimensions.penalty_marker_size / 2.0,
+                field_dimensions.length.0 / 2.0
+                    - field_dimensions.penalty_marker_distance.0
+                    - field_dimensions.penalty_marker_size.0 / 2.0,
                 0.0
             ],
             line_stroke,
         );
         self.line_segment(
             point![
-                field_dimensions.length / 2.0 - field_dimensions.penalty_marker_distance,
-                -field_dimensions.penalty_marker_size / 2.0
+                field_dimensions.length.0 / 2.0 - field_dimensions.penalty_marker_distance.0,
+                -field_dimensions.penalty_marker_size.0 / 2.0
             ],
             point![
-                field_dimensions.length / 2.0 - field_dimensions.penalty_marker_distance,
-                field_dimensions.penalty_marker_size / 2.0
+                field_dimensions.length.0 / 2.0 - field_dimensions.penalty_marker_distance.0,
+                field_dimensions.penalty_marker_size.0 / 2.0
             ],
             line_stroke,
         );
@@ -238,39 +241,41 @@ impl TwixPainter {
         // Goal posts
         self.circle(
             point![
-                -field_dimensions.length / 2.0 - field_dimensions.line_width / 2.0,
-                -field_dimensions.goal_inner_width / 2.0
-                    - field_dimensions.goal_post_diameter / 2.0
+                -field_dimensions.length.0 / 2.0 - field_dimensions.line_width.0 / 2.0,
+                -field_dimensions.goal_inner_width.0 / 2.0
+                    - field_dimensions.goal_post_diameter.0 / 2.0
             ],
-            field_dimensions.goal_post_diameter / 2.0,
+            field_dimensions.goal_post_diameter.0 / 2.0,
             Color32::WHITE,
             goal_post_stroke,
         );
         self.circle(
             point![
-                -field_dimensions.length / 2.0 - field_dimensions.line_width / 2.0,
-                field_dimensions.goal_inner_width / 2.0 + field_dimensions.goal_post_diameter / 2.0
+                -field_dimensions.length.0 / 2.0 - field_dimensions.line_width.0 / 2.0,
+                field_dimensions.goal_inner_width.0 / 2.0
+                    + field_dimensions.goal_post_diameter.0 / 2.0
             ],
-            field_dimensions.goal_post_diameter / 2.0,
+            field_dimensions.goal_post_diameter.0 / 2.0,
             Color32::WHITE,
             goal_post_stroke,
         );
         self.circle(
             point![
-                field_dimensions.length / 2.0 + field_dimensions.line_width / 2.0,
-                -field_dimensions.goal_inner_width / 2.0
-                    - field_dimensions.goal_post_diameter / 2.0
+                field_dimensions.length.0 / 2.0 + field_dimensions.line_width.0 / 2.0,
+                -field_dimensions.goal_inner_width.0 / 2.0
+                    - field_dimensions.goal_post_diameter.0 / 2.0
             ],
-            field_dimensions.goal_post_diameter / 2.0,
+            field_dimensions.goal_post_diameter.0 / 2.0,
             Color32::WHITE,
             goal_post_stroke,
         );
         self.circle(
             point![
-                field_dimensions.length / 2.0 + field_dimensions.line_width / 2.0,
-                field_dimensions.goal_inner_width / 2.0 + field_dimensions.goal_post_diameter / 2.0
+                field_dimensions.length.0 / 2.0 + field_dimensions.line_width.0 / 2.0,
+                field_dimensions.goal_inner_width.0 / 2.0
+                    + field_dimensions.goal_post_diameter.0 / 2.0
             ],
-            field_dimensions.goal_post_diameter / 2.0,
+            field_dimensions.goal_post_diameter.0 / 2.0,
             Color32::WHITE,
             goal_post_stroke,
         );